@@ -0,0 +1,406 @@
+//! Generates the low-level endpoint functions of `client::generated`
+//!
+//! Every operation of the vendored OpenAPI spec (`openapi/keyrunes.json`)
+//! becomes a method of `GeneratedClient`, named after its `operationId`.
+//! Schemas listed in the overlay (`openapi/rust-types.json`) map to the
+//! SDK's own types (`wire_type` names the type the response is read as
+//! before being converted); the other object schemas are generated as plain
+//! structs. Keeping the mappings out of the spec lets it be replaced by a
+//! newer copy as is.
+
+use serde_json::{Map, Value};
+use std::collections::BTreeSet;
+use std::fmt::Write;
+use std::path::Path;
+
+const SPEC: &str = "openapi/keyrunes.json";
+const TYPES: &str = "openapi/rust-types.json";
+const METHODS: [&str; 5] = ["get", "put", "post", "patch", "delete"];
+
+fn main() {
+    println!("cargo:rerun-if-changed={}", SPEC);
+    println!("cargo:rerun-if-changed={}", TYPES);
+    println!("cargo:rerun-if-changed=build.rs");
+
+    let spec = read_json(SPEC);
+    let types = read_json(TYPES);
+    let generator = Generator::new(&spec, &types);
+
+    let mut code = String::new();
+    generator.write_operations(&mut code);
+    generator.write_schemas(&mut code);
+
+    let out_dir = std::env::var("OUT_DIR").expect("OUT_DIR is set by cargo");
+    std::fs::write(Path::new(&out_dir).join("generated.rs"), code)
+        .expect("Failed to write the generated endpoints");
+}
+
+fn read_json(path: &str) -> Value {
+    serde_json::from_str(
+        &std::fs::read_to_string(path).unwrap_or_else(|err| panic!("{}: {}", path, err)),
+    )
+    .unwrap_or_else(|err| panic!("{}: {}", path, err))
+}
+
+struct Generator<'a> {
+    spec: &'a Value,
+    schemas: &'a Map<String, Value>,
+    /// SDK types of the schemas, from the overlay
+    types: &'a Map<String, Value>,
+}
+
+/// How an operation's response is read
+enum Response {
+    Empty,
+    Bytes,
+    Json { rust: String, wire: Option<String> },
+}
+
+impl<'a> Generator<'a> {
+    fn new(spec: &'a Value, types: &'a Value) -> Self {
+        let schemas = spec["components"]["schemas"]
+            .as_object()
+            .expect("components.schemas is an object");
+        let types = types["schemas"]
+            .as_object()
+            .unwrap_or_else(|| panic!("{}: schemas is an object", TYPES));
+        // Catches mappings left behind when the spec is updated
+        for name in types.keys() {
+            assert!(
+                schemas.contains_key(name),
+                "{}: {} is not a schema of {}",
+                TYPES,
+                name,
+                SPEC
+            );
+        }
+        Self {
+            spec,
+            schemas,
+            types,
+        }
+    }
+
+    fn write_operations(&self, code: &mut String) {
+        let paths = self.spec["paths"].as_object().expect("paths is an object");
+        let mut names = BTreeSet::new();
+
+        code.push_str("impl<'a> GeneratedClient<'a> {\n");
+        for (path, item) in paths {
+            for method in METHODS {
+                if let Some(operation) = item.get(method) {
+                    let name = operation["operationId"]
+                        .as_str()
+                        .unwrap_or_else(|| panic!("{} {} has no operationId", method, path));
+                    assert!(names.insert(name), "duplicate operationId {}", name);
+                    self.write_operation(code, path, method, name, operation);
+                }
+            }
+        }
+        code.push_str("}\n");
+    }
+
+    fn write_operation(
+        &self,
+        code: &mut String,
+        path: &str,
+        method: &str,
+        name: &str,
+        operation: &Value,
+    ) {
+        let summary = operation["summary"].as_str().unwrap_or(name);
+        let _ = writeln!(code, "    /// {}.", summary);
+        let _ = writeln!(code, "    ///");
+        let _ = writeln!(code, "    /// `{} {}`", method.to_uppercase(), path);
+
+        let mut args = Vec::new();
+        let mut path_params = Vec::new();
+        let mut query_params = Vec::new();
+        for parameter in operation["parameters"].as_array().into_iter().flatten() {
+            let param = parameter["name"].as_str().expect("parameter has a name");
+            match parameter["in"].as_str() {
+                Some("path") => {
+                    args.push(format!("{}: &str", param));
+                    path_params.push(param);
+                }
+                Some("query") => {
+                    let (rust, to_str) = query_type(&parameter["schema"]);
+                    args.push(format!("{}: Option<{}>", param, rust));
+                    query_params.push((param, to_str));
+                }
+                other => panic!("{}: unsupported parameter location {:?}", name, other),
+            }
+        }
+
+        let body = match operation["requestBody"]["content"].as_object() {
+            None => "RequestBody::Empty".to_string(),
+            Some(content) if content.contains_key("application/json") => {
+                let schema = &content["application/json"]["schema"];
+                args.push(format!("body: &{}", self.rust_type(schema)));
+                "RequestBody::Json(serde_json::to_value(body)?)".to_string()
+            }
+            Some(content) if content.contains_key("application/octet-stream") => {
+                args.push("data: Vec<u8>".to_string());
+                args.push("content_type: &str".to_string());
+                "RequestBody::Bytes { data, content_type: content_type.to_string() }".to_string()
+            }
+            Some(content) if content.contains_key("multipart/form-data") => {
+                let properties = content["multipart/form-data"]["schema"]["properties"]
+                    .as_object()
+                    .expect("multipart body has properties");
+                assert_eq!(properties.len(), 1, "{}: one file part is supported", name);
+                let field = properties.keys().next().expect("one property");
+                args.push("file_name: &str".to_string());
+                args.push("data: Vec<u8>".to_string());
+                args.push("content_type: &str".to_string());
+                format!(
+                    "RequestBody::File {{ field: {:?}.to_string(), file_name: file_name.to_string(), data, content_type: content_type.to_string() }}",
+                    field
+                )
+            }
+            Some(content) => panic!("{}: unsupported request body {:?}", name, content.keys()),
+        };
+
+        let response = self.response(name, &operation["responses"]);
+        let returns = match &response {
+            Response::Empty => "()".to_string(),
+            Response::Bytes => "Vec<u8>".to_string(),
+            Response::Json { rust, .. } => rust.clone(),
+        };
+
+        let auth = match operation.get("security").and_then(Value::as_array) {
+            None => "Auth::Required",
+            Some(requirements) if requirements.is_empty() => "Auth::None",
+            Some(requirements) if requirements.iter().any(is_empty_object) => "Auth::Optional",
+            Some(_) => "Auth::Required",
+        };
+
+        let mut signature = String::from("&self");
+        for arg in &args {
+            signature.push_str(", ");
+            signature.push_str(arg);
+        }
+        let _ = writeln!(
+            code,
+            "    pub async fn {}({}) -> Result<{}> {{",
+            name, signature, returns
+        );
+
+        let template = path_template(path);
+        let path_expr = if path_params.is_empty() {
+            format!("String::from({:?})", path)
+        } else {
+            let values: Vec<String> = path_params
+                .iter()
+                .map(|param| format!("encode_path_segment({})", param))
+                .collect();
+            format!("format!({:?}, {})", template, values.join(", "))
+        };
+        if query_params.is_empty() {
+            let _ = writeln!(code, "        let path = {};", path_expr);
+        } else {
+            let _ = writeln!(
+                code,
+                "        let mut query = url::form_urlencoded::Serializer::new(String::new());"
+            );
+            for (param, to_str) in &query_params {
+                let _ = writeln!(
+                    code,
+                    "        if let Some(value) = {} {{ query.append_pair({:?}, {}); }}",
+                    param, param, to_str
+                );
+            }
+            let _ = writeln!(
+                code,
+                "        let path = with_query({}, query.finish());",
+                path_expr
+            );
+        }
+
+        let _ = writeln!(
+            code,
+            "        let response = self.client.send_request(reqwest::Method::{}, &path, {}, {}, None).await?;",
+            method.to_uppercase(),
+            body,
+            auth
+        );
+        match response {
+            Response::Empty => {
+                let _ = writeln!(
+                    code,
+                    "        self.client.handle_empty_response(response).await"
+                );
+            }
+            Response::Bytes => {
+                let _ = writeln!(code, "        self.bytes(response).await");
+            }
+            Response::Json { rust, wire: None } => {
+                let _ = writeln!(
+                    code,
+                    "        self.client.handle_response::<{}>(response).await",
+                    rust
+                );
+            }
+            Response::Json {
+                wire: Some(wire), ..
+            } => {
+                let _ = writeln!(
+                    code,
+                    "        self.client.handle_response::<{}>(response).await.map(Into::into)",
+                    wire
+                );
+            }
+        }
+        code.push_str("    }\n\n");
+    }
+
+    fn response(&self, name: &str, responses: &Value) -> Response {
+        let (status, response) = responses
+            .as_object()
+            .and_then(|responses| responses.iter().find(|(status, _)| status.starts_with('2')))
+            .unwrap_or_else(|| panic!("{} has no success response", name));
+
+        let content = match response["content"].as_object() {
+            None => return Response::Empty,
+            Some(content) => content,
+        };
+        assert_ne!(status, "204", "{}: 204 responses have no content", name);
+        if content.contains_key("application/octet-stream") {
+            return Response::Bytes;
+        }
+
+        let schema = &content
+            .get("application/json")
+            .unwrap_or_else(|| panic!("{}: unsupported response {:?}", name, content.keys()))
+            ["schema"];
+        let wire = schema
+            .get("$ref")
+            .and_then(|reference| self.mapping(reference, "wire_type"))
+            .map(str::to_string);
+        Response::Json {
+            rust: self.rust_type(schema),
+            wire,
+        }
+    }
+
+    fn schema_name<'r>(&self, reference: &'r Value) -> &'r str {
+        let name = reference
+            .as_str()
+            .and_then(|reference| reference.strip_prefix("#/components/schemas/"))
+            .unwrap_or_else(|| panic!("unsupported reference {}", reference));
+        assert!(self.schemas.contains_key(name), "unknown schema {}", name);
+        name
+    }
+
+    /// Field of the overlay entry of a referenced schema (`type` or `wire_type`)
+    fn mapping(&self, reference: &Value, field: &str) -> Option<&'a str> {
+        self.types.get(self.schema_name(reference))?[field].as_str()
+    }
+
+    /// Rust type of a schema used in a request, a response or a field
+    fn rust_type(&self, schema: &Value) -> String {
+        if let Some(reference) = schema.get("$ref") {
+            return match self.mapping(reference, "type") {
+                Some(rust) => rust.to_string(),
+                None => self.schema_name(reference).to_string(),
+            };
+        }
+
+        match (schema["type"].as_str(), schema["format"].as_str()) {
+            (Some("string"), Some("date-time")) => "chrono::DateTime<chrono::Utc>".to_string(),
+            (Some("string"), _) => "String".to_string(),
+            (Some("integer"), _) => "i64".to_string(),
+            (Some("number"), _) => "f64".to_string(),
+            (Some("boolean"), _) => "bool".to_string(),
+            (Some("array"), _) => format!("Vec<{}>", self.rust_type(&schema["items"])),
+            (Some("object"), _) if schema.get("properties").is_none() => {
+                "serde_json::Value".to_string()
+            }
+            _ => panic!("unsupported inline schema {}", schema),
+        }
+    }
+
+    fn write_schemas(&self, code: &mut String) {
+        for (name, schema) in self.schemas {
+            if self.types.contains_key(name) {
+                continue;
+            }
+            let properties = schema["properties"]
+                .as_object()
+                .unwrap_or_else(|| panic!("schema {} has no properties", name));
+            let required: BTreeSet<&str> = schema["required"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(Value::as_str)
+                .collect();
+
+            if let Some(description) = schema["description"].as_str() {
+                let _ = writeln!(code, "/// {}", description);
+            }
+            let _ = writeln!(
+                code,
+                "#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]"
+            );
+            let _ = writeln!(code, "pub struct {} {{", name);
+            for (field, property) in properties {
+                if let Some(description) = property["description"].as_str() {
+                    let _ = writeln!(code, "    /// {}", description);
+                }
+                let rust = self.rust_type(property);
+                let ident = if field == "type" {
+                    "r#type"
+                } else {
+                    field.as_str()
+                };
+                if required.contains(field.as_str()) {
+                    let _ = writeln!(code, "    pub {}: {},", ident, rust);
+                } else {
+                    let _ = writeln!(
+                        code,
+                        "    #[serde(default, skip_serializing_if = \"Option::is_none\")]"
+                    );
+                    let _ = writeln!(code, "    pub {}: Option<{}>,", ident, rust);
+                }
+            }
+            code.push_str("}\n\n");
+        }
+    }
+}
+
+/// Rust type of a query parameter, and the expression turning `value`
+/// into its query string form
+fn query_type(schema: &Value) -> (&'static str, &'static str) {
+    match (schema["type"].as_str(), schema["format"].as_str()) {
+        (Some("string"), Some("date-time")) => (
+            "chrono::DateTime<chrono::Utc>",
+            "&value.to_rfc3339_opts(chrono::SecondsFormat::Secs, true)",
+        ),
+        (Some("string"), _) => ("&str", "value"),
+        (Some("integer"), _) => ("i64", "&value.to_string()"),
+        (Some("boolean"), _) => ("bool", "&value.to_string()"),
+        _ => panic!("unsupported query parameter {}", schema),
+    }
+}
+
+/// `format!` template of a path, with its `{param}` segments emptied
+fn path_template(path: &str) -> String {
+    let mut template = String::new();
+    let mut in_param = false;
+    for c in path.chars() {
+        match c {
+            '{' => {
+                template.push_str("{}");
+                in_param = true;
+            }
+            '}' => in_param = false,
+            _ if in_param => {}
+            _ => template.push(c),
+        }
+    }
+    template
+}
+
+fn is_empty_object(value: &Value) -> bool {
+    value.as_object().is_some_and(Map::is_empty)
+}
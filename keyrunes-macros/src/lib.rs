@@ -0,0 +1,244 @@
+//! Procedural macros for the Keyrunes Rust SDK
+//!
+//! These macros are re-exported by `keyrunes-rust-sdk` with the `macros`
+//! feature; depend on the SDK rather than on this crate directly.
+
+use proc_macro::TokenStream;
+use quote::{format_ident, quote};
+use syn::parse::Parser;
+use syn::punctuated::Punctuated;
+use syn::{
+    Data, DeriveInput, Expr, ExprLit, Fields, ItemFn, Lit, LitStr, Meta, MetaNameValue, Token,
+};
+
+/// Requires groups and permissions to call a route handler.
+///
+/// Adds a `keyrunes_rust_sdk::requirements::Require` extractor to the
+/// handler, which rejects the request unless the authenticated user
+/// belongs to every `group` and holds every `permission`. Both arguments
+/// may be repeated.
+///
+/// ```ignore
+/// #[keyrunes_rust_sdk::require(group = "editors", permission = "posts:write")]
+/// async fn create_post(Json(post): Json<NewPost>) -> impl IntoResponse {
+///     // ...
+/// }
+/// ```
+///
+/// Only free functions are supported.
+#[proc_macro_attribute]
+pub fn require(attr: TokenStream, item: TokenStream) -> TokenStream {
+    match expand_require(attr.into(), item.into()) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+/// Implements `keyrunes_rust_sdk::redact::Redact` for a struct.
+///
+/// Restrict fields with `#[keyrunes(...)]`:
+///
+/// - `group = "..."` / `permission = "..."` - who may see the field (any
+///   listed group or permission grants access; both may be repeated)
+/// - `mask` / `mask = "..."` - replace the hidden value instead of
+///   omitting the field
+///
+/// ```ignore
+/// #[derive(Serialize, KeyrunesRedact)]
+/// struct Employee {
+///     name: String,
+///     #[keyrunes(group = "hr", mask)]
+///     salary: u64,
+/// }
+/// ```
+///
+/// Field renames with `#[serde(rename = "...")]` are honored; containers
+/// using `#[serde(rename_all)]` must rename restricted fields explicitly.
+#[proc_macro_derive(KeyrunesRedact, attributes(keyrunes))]
+pub fn derive_keyrunes_redact(input: TokenStream) -> TokenStream {
+    match expand_redact(syn::parse_macro_input!(input as DeriveInput)) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+fn expand_require(
+    attr: proc_macro2::TokenStream,
+    item: proc_macro2::TokenStream,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let args = Punctuated::<MetaNameValue, Token![,]>::parse_terminated.parse2(attr)?;
+    let mut function: ItemFn = syn::parse2(item)?;
+
+    let mut groups = Vec::new();
+    let mut permissions = Vec::new();
+    for arg in &args {
+        let value = match &arg.value {
+            Expr::Lit(ExprLit {
+                lit: Lit::Str(value),
+                ..
+            }) => value.clone(),
+            other => return Err(syn::Error::new_spanned(other, "expected a string literal")),
+        };
+        if arg.path.is_ident("group") {
+            groups.push(value);
+        } else if arg.path.is_ident("permission") {
+            permissions.push(value);
+        } else {
+            return Err(syn::Error::new_spanned(
+                &arg.path,
+                "unknown argument, expected `group` or `permission`",
+            ));
+        }
+    }
+    if groups.is_empty() && permissions.is_empty() {
+        return Err(syn::Error::new(
+            proc_macro2::Span::call_site(),
+            "expected at least one `group` or `permission`",
+        ));
+    }
+    if function.sig.asyncness.is_none() {
+        return Err(syn::Error::new_spanned(
+            function.sig.fn_token,
+            "#[require] can only be applied to async handlers",
+        ));
+    }
+
+    let marker = format_ident!("__KeyrunesRequire_{}", function.sig.ident);
+    let vis = &function.vis;
+    function.sig.inputs.insert(
+        0,
+        syn::parse_quote! {
+            _: ::keyrunes_rust_sdk::requirements::Require<#marker>
+        },
+    );
+
+    Ok(quote! {
+        #[doc(hidden)]
+        #[allow(non_camel_case_types)]
+        #vis enum #marker {}
+
+        impl ::keyrunes_rust_sdk::requirements::RouteRequirements for #marker {
+            const GROUPS: &'static [&'static str] = &[#(#groups),*];
+            const PERMISSIONS: &'static [&'static str] = &[#(#permissions),*];
+        }
+
+        #function
+    })
+}
+
+fn expand_redact(input: DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    &input.ident,
+                    "KeyrunesRedact requires named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &input.ident,
+                "KeyrunesRedact can only be derived for structs",
+            ))
+        }
+    };
+    let renames_all = serde_values(&input.attrs, "rename_all")?.next().is_some();
+
+    let mut rules = Vec::new();
+    for field in fields {
+        let mut groups = Vec::new();
+        let mut permissions = Vec::new();
+        let mut mask = None;
+        let mut restricted = false;
+
+        for attr in field.attrs.iter().filter(|a| a.path().is_ident("keyrunes")) {
+            restricted = true;
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("group") {
+                    groups.push(meta.value()?.parse::<LitStr>()?);
+                } else if meta.path.is_ident("permission") {
+                    permissions.push(meta.value()?.parse::<LitStr>()?);
+                } else if meta.path.is_ident("mask") {
+                    mask = Some(if meta.input.peek(Token![=]) {
+                        let mask = meta.value()?.parse::<LitStr>()?;
+                        quote!(#mask)
+                    } else {
+                        quote!(::keyrunes_rust_sdk::redact::DEFAULT_MASK)
+                    });
+                } else {
+                    return Err(meta.error("expected `group`, `permission` or `mask`"));
+                }
+                Ok(())
+            })?;
+        }
+        if !restricted {
+            continue;
+        }
+
+        let ident = field.ident.as_ref().expect("named field");
+        if groups.is_empty() && permissions.is_empty() {
+            return Err(syn::Error::new_spanned(
+                ident,
+                "expected at least one `group` or `permission`",
+            ));
+        }
+        let key = match serde_values(&field.attrs, "rename")?.next() {
+            Some(rename) => rename.value(),
+            None if renames_all => {
+                return Err(syn::Error::new_spanned(
+                    ident,
+                    "restricted fields of a #[serde(rename_all)] struct need #[serde(rename)]",
+                ))
+            }
+            None => ident.to_string().trim_start_matches("r#").to_string(),
+        };
+        let mask = match mask {
+            Some(mask) => quote!(::core::option::Option::Some(#mask)),
+            None => quote!(::core::option::Option::None),
+        };
+
+        rules.push(quote! {
+            ::keyrunes_rust_sdk::redact::FieldRule {
+                key: #key,
+                groups: &[#(#groups),*],
+                permissions: &[#(#permissions),*],
+                mask: #mask,
+            }
+        });
+    }
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::keyrunes_rust_sdk::redact::Redact for #name #ty_generics #where_clause {
+            const FIELD_RULES: &'static [::keyrunes_rust_sdk::redact::FieldRule] = &[#(#rules),*];
+        }
+    })
+}
+
+/// String values of `#[serde(<name> = "...")]` attributes
+fn serde_values(attrs: &[syn::Attribute], name: &str) -> syn::Result<impl Iterator<Item = LitStr>> {
+    let mut values = Vec::new();
+    for attr in attrs.iter().filter(|a| a.path().is_ident("serde")) {
+        let metas = attr.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)?;
+        for meta in metas {
+            if let Meta::NameValue(MetaNameValue {
+                path,
+                value:
+                    Expr::Lit(ExprLit {
+                        lit: Lit::Str(value),
+                        ..
+                    }),
+                ..
+            }) = meta
+            {
+                if path.is_ident(name) {
+                    values.push(value);
+                }
+            }
+        }
+    }
+    Ok(values.into_iter())
+}
@@ -0,0 +1,135 @@
+//! Authorization-aware data access
+//!
+//! Filtering rows one by one with authorization checks does not scale. An
+//! [`AccessFilter`] holds the IDs of the resources a user can access
+//! (from [`KeyrunesClient::list_accessible`]) and turns them into a
+//! condition for the database query: a parameterized SQL `IN` clause, or a
+//! `sea_query::Condition` with the `sea-query` feature (for Loco and
+//! SeaORM apps).
+//!
+//! ## Quick Start
+//!
+//! ```
+//! use keyrunes_rust_sdk::access_filter::{AccessFilter, Placeholder};
+//! use keyrunes_rust_sdk::KeyrunesClient;
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = KeyrunesClient::new("https://keyrunes.example.com")?;
+//! let filter = AccessFilter::load(&client, "123", "document", "read").await?;
+//! let clause = filter.sql_in_clause("documents.id", Placeholder::Dollar(1));
+//! let sql = format!("SELECT * FROM documents WHERE {}", clause.sql);
+//! // Bind `clause.params` in order
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::client::KeyrunesClient;
+use crate::error::Result;
+
+/// Bind parameter style of the target database
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Placeholder {
+    /// `?` (MySQL, SQLite)
+    Question,
+    /// `$n`, numbered from the given index (PostgreSQL)
+    Dollar(usize),
+}
+
+/// Parameterized SQL fragment
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SqlFragment {
+    /// SQL text with placeholders
+    pub sql: String,
+    /// Values to bind, in placeholder order
+    pub params: Vec<String>,
+}
+
+/// IDs of the resources a user can access
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AccessFilter {
+    ids: Vec<String>,
+}
+
+impl AccessFilter {
+    /// Creates a filter from already known IDs.
+    pub fn new(ids: Vec<String>) -> Self {
+        Self { ids }
+    }
+
+    /// Loads the IDs of the resources of `resource_type` that `user_id` can access with `permission`.
+    pub async fn load(
+        client: &KeyrunesClient,
+        user_id: &str,
+        resource_type: &str,
+        permission: &str,
+    ) -> Result<Self> {
+        client
+            .list_accessible(user_id, resource_type, permission)
+            .await
+            .map(Self::new)
+    }
+
+    /// Returns the accessible IDs.
+    pub fn ids(&self) -> &[String] {
+        &self.ids
+    }
+
+    /// Consumes the filter, returning the accessible IDs.
+    pub fn into_ids(self) -> Vec<String> {
+        self.ids
+    }
+
+    /// Checks whether the user cannot access any resource.
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+
+    /// Checks whether `id` is accessible.
+    pub fn contains(&self, id: &str) -> bool {
+        self.ids.iter().any(|accessible| accessible == id)
+    }
+
+    /// Builds a `column IN (...)` condition.
+    ///
+    /// `column` is inserted verbatim and must not come from user input.
+    /// Without accessible IDs, the condition is `1 = 0`, which matches no row.
+    pub fn sql_in_clause(&self, column: &str, placeholder: Placeholder) -> SqlFragment {
+        if self.ids.is_empty() {
+            return SqlFragment {
+                sql: "1 = 0".to_string(),
+                params: Vec::new(),
+            };
+        }
+
+        let placeholders: Vec<String> = (0..self.ids.len())
+            .map(|index| match placeholder {
+                Placeholder::Question => "?".to_string(),
+                Placeholder::Dollar(start) => format!("${}", start + index),
+            })
+            .collect();
+
+        SqlFragment {
+            sql: format!("{} IN ({})", column, placeholders.join(", ")),
+            params: self.ids.clone(),
+        }
+    }
+
+    /// Builds a `sea_query` condition restricting `column` to the accessible IDs.
+    ///
+    /// Without accessible IDs, the condition matches no row.
+    #[cfg(feature = "sea-query")]
+    pub fn condition<C: sea_query::IntoColumnRef>(&self, column: C) -> sea_query::Condition {
+        use sea_query::{Condition, Expr};
+
+        if self.ids.is_empty() {
+            return Condition::all().add(Expr::cust("1 = 0"));
+        }
+        Condition::all().add(Expr::col(column).is_in(self.ids.iter().cloned()))
+    }
+}
+
+impl From<Vec<String>> for AccessFilter {
+    fn from(ids: Vec<String>) -> Self {
+        Self::new(ids)
+    }
+}
@@ -0,0 +1,1242 @@
+//! Administration endpoints
+//!
+//! This module contains the [`AdminClient`], a handle to the Keyrunes
+//! administration endpoints obtained with [`KeyrunesClient::admin`]. It
+//! shares the token of the client it was created from, which must belong
+//! to an administrator.
+//!
+//! ## Quick Start
+//!
+//! ```
+//! use keyrunes_rust_sdk::KeyrunesClient;
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = KeyrunesClient::new("https://keyrunes.example.com")?;
+//! client.login("admin@example.com", "password123", None).await?;
+//! let stats = client.admin().stats().await?;
+//! println!("MFA adoption: {:.0}%", stats.mfa_adoption * 100.0);
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::client::transport::{Auth, RequestBody};
+use crate::client::KeyrunesClient;
+use crate::endpoints;
+use crate::error::{KeyrunesError, Result};
+use crate::job::Job;
+use crate::models::{
+    Branding, BulkMembershipReport, CustomDomain, EmailTemplate, EmailTemplateContent,
+    EmailTemplateKind, EventFilter, EventReplay, Group, GroupMembership, HashAlgorithm, IpRule,
+    IpRuleAction, Page, Permission, PermissionSyncReport, RenderedEmail, RoleBinding,
+    ServiceAccount, SigningKey, TenantSettings, TenantStats, User, UserImport, WebhookDelivery,
+};
+use crate::permissions::PermissionDef;
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::ops::Range;
+
+/// Maximum number of users sent in one bulk membership request
+const MEMBERSHIP_BATCH_SIZE: usize = 500;
+
+/// Handle to the administration endpoints
+#[derive(Clone, Copy)]
+pub struct AdminClient<'a> {
+    client: &'a KeyrunesClient,
+}
+
+impl<'a> AdminClient<'a> {
+    pub(crate) fn new(client: &'a KeyrunesClient) -> Self {
+        Self { client }
+    }
+
+    /// Gets usage statistics of the tenant.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<TenantStats, KeyrunesError>`:
+    /// - `Ok(stats)` with the current statistics
+    /// - `Err(KeyrunesError::AuthorizationError)` if the token is not an administrator's
+    /// - `Err(KeyrunesError::InvalidToken)` if no token is set
+    pub async fn stats(&self) -> Result<TenantStats> {
+        self.client.generated().get_admin_stats().await
+    }
+
+    /// Gets the settings of the tenant.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<TenantSettings, KeyrunesError>`:
+    /// - `Ok(settings)` with the current settings
+    /// - `Err(KeyrunesError::AuthorizationError)` if the token is not an administrator's
+    pub async fn get_settings(&self) -> Result<TenantSettings> {
+        self.client.generated().get_admin_settings().await
+    }
+
+    /// Replaces the settings of the tenant.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<TenantSettings, KeyrunesError>`:
+    /// - `Ok(settings)` with the settings as applied
+    /// - `Err(KeyrunesError::AuthorizationError)` if the token is not an administrator's
+    /// - `Err(KeyrunesError::Api)` if Keyrunes rejects a setting
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyrunes_rust_sdk::{AuthMethod, KeyrunesClient, MfaPolicy};
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = KeyrunesClient::new("https://keyrunes.example.com")?;
+    /// client.login("admin@example.com", "password123", None).await?;
+    /// let admin = client.admin();
+    ///
+    /// let mut settings = admin.get_settings().await?;
+    /// settings.mfa = MfaPolicy::Required;
+    /// settings.password_policy.min_length = 12;
+    /// settings.auth_methods = vec![AuthMethod::Password, AuthMethod::Passkey];
+    /// admin.update_settings(&settings).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn update_settings(&self, settings: &TenantSettings) -> Result<TenantSettings> {
+        self.client
+            .generated()
+            .update_admin_settings(settings)
+            .await
+    }
+
+    /// Gets the branding of the tenant's hosted pages.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<Branding, KeyrunesError>`:
+    /// - `Ok(branding)` with the current branding
+    /// - `Err(KeyrunesError::AuthorizationError)` if the token is not an administrator's
+    pub async fn get_branding(&self) -> Result<Branding> {
+        self.client.generated().get_admin_branding().await
+    }
+
+    /// Replaces the branding of the tenant's hosted pages.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<Branding, KeyrunesError>`:
+    /// - `Ok(branding)` with the branding as applied
+    /// - `Err(KeyrunesError::AuthorizationError)` if the token is not an administrator's
+    /// - `Err(KeyrunesError::Api)` if a URL or color is invalid
+    pub async fn update_branding(&self, branding: &Branding) -> Result<Branding> {
+        self.client
+            .generated()
+            .update_admin_branding(branding)
+            .await
+    }
+
+    /// Gets the custom domain of the tenant's hosted pages.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<CustomDomain, KeyrunesError>`:
+    /// - `Ok(domain)` with the domain and its verification state
+    /// - `Err(KeyrunesError::Other)` if no custom domain is set
+    /// - `Err(KeyrunesError::AuthorizationError)` if the token is not an administrator's
+    pub async fn get_custom_domain(&self) -> Result<CustomDomain> {
+        self.client.generated().get_admin_custom_domain().await
+    }
+
+    /// Sets the custom domain of the tenant's hosted pages.
+    ///
+    /// The domain starts out pending: publish its `dns_records`, then call
+    /// [`Self::verify_custom_domain`].
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<CustomDomain, KeyrunesError>`:
+    /// - `Ok(domain)` with the records to publish
+    /// - `Err(KeyrunesError::AuthorizationError)` if the token is not an administrator's
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyrunes_rust_sdk::KeyrunesClient;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = KeyrunesClient::new("https://keyrunes.example.com")?;
+    /// client.login("admin@example.com", "password123", None).await?;
+    /// let domain = client.admin().set_custom_domain("login.example.com").await?;
+    /// for record in &domain.dns_records {
+    ///     println!("{} {} {}", record.name, record.record_type, record.value);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn set_custom_domain(&self, domain: &str) -> Result<CustomDomain> {
+        let response = self
+            .client
+            .send_request(
+                reqwest::Method::PUT,
+                endpoints::ADMIN_CUSTOM_DOMAIN,
+                RequestBody::Json(serde_json::json!({ "domain": domain })),
+                Auth::Required,
+                None,
+            )
+            .await?;
+
+        self.client.handle_response(response).await
+    }
+
+    /// Checks the DNS records of the custom domain.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<CustomDomain, KeyrunesError>`:
+    /// - `Ok(domain)` with the updated verification state
+    /// - `Err(KeyrunesError::AuthorizationError)` if the token is not an administrator's
+    pub async fn verify_custom_domain(&self) -> Result<CustomDomain> {
+        self.client.generated().verify_admin_custom_domain().await
+    }
+
+    /// Removes the custom domain; hosted pages are served on the Keyrunes domain again.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<(), KeyrunesError>`:
+    /// - `Ok(())` if the domain was removed
+    /// - `Err(KeyrunesError::AuthorizationError)` if the token is not an administrator's
+    pub async fn remove_custom_domain(&self) -> Result<()> {
+        self.client.generated().delete_admin_custom_domain().await
+    }
+
+    /// Lists the tenant's transactional email templates.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<Vec<EmailTemplate>, KeyrunesError>`:
+    /// - `Ok(templates)` with one template per kind of email
+    /// - `Err(KeyrunesError::AuthorizationError)` if the token is not an administrator's
+    pub async fn list_email_templates(&self) -> Result<Vec<EmailTemplate>> {
+        self.client.generated().list_admin_email_templates().await
+    }
+
+    /// Replaces the content of an email template.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<EmailTemplate, KeyrunesError>`:
+    /// - `Ok(template)` with the updated template
+    /// - `Err(KeyrunesError::AuthorizationError)` if the token is not an administrator's
+    /// - `Err(KeyrunesError::Api)` if the template does not compile
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyrunes_rust_sdk::{EmailTemplateContent, EmailTemplateKind, KeyrunesClient};
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = KeyrunesClient::new("https://keyrunes.example.com")?;
+    /// client.login("admin@example.com", "password123", None).await?;
+    /// let content = EmailTemplateContent {
+    ///     subject: "Reset your Acme password".to_string(),
+    ///     html_body: "<p>Hi {{user.name}}, <a href=\"{{link}}\">reset it here</a>.</p>".to_string(),
+    ///     text_body: None,
+    /// };
+    ///
+    /// let preview = client
+    ///     .admin()
+    ///     .render_email_template(EmailTemplateKind::PasswordReset, Some(&content), None)
+    ///     .await?;
+    /// println!("{}", preview.html);
+    /// client
+    ///     .admin()
+    ///     .update_email_template(EmailTemplateKind::PasswordReset, &content)
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn update_email_template(
+        &self,
+        kind: EmailTemplateKind,
+        content: &EmailTemplateContent,
+    ) -> Result<EmailTemplate> {
+        self.client
+            .generated()
+            .update_admin_email_template(kind.as_str(), content)
+            .await
+    }
+
+    /// Renders an email template without sending it.
+    ///
+    /// # Arguments
+    ///
+    /// * `kind` - Email to render
+    /// * `draft` - Content to render instead of the saved template (optional)
+    /// * `variables` - Values of the template variables (sample values when `None`)
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<RenderedEmail, KeyrunesError>`:
+    /// - `Ok(email)` with the rendered subject and bodies
+    /// - `Err(KeyrunesError::AuthorizationError)` if the token is not an administrator's
+    /// - `Err(KeyrunesError::Api)` if the template does not compile
+    pub async fn render_email_template(
+        &self,
+        kind: EmailTemplateKind,
+        draft: Option<&EmailTemplateContent>,
+        variables: Option<&serde_json::Value>,
+    ) -> Result<RenderedEmail> {
+        let mut body = serde_json::Map::new();
+        if let Some(draft) = draft {
+            body.insert("template".to_string(), serde_json::to_value(draft)?);
+        }
+        if let Some(variables) = variables {
+            body.insert("variables".to_string(), variables.clone());
+        }
+        let response = self
+            .client
+            .send_request(
+                reqwest::Method::POST,
+                &endpoints::admin_email_template_render(kind.as_str()),
+                RequestBody::Json(body.into()),
+                Auth::Required,
+                None,
+            )
+            .await?;
+
+        self.client.handle_response(response).await
+    }
+
+    /// Redelivers the events that occurred in `range` to the webhooks.
+    ///
+    /// Runs as a background job; use it to recover the events a consumer
+    /// missed during an outage. Consumers receive the events again with
+    /// their original IDs, so they can skip the ones already processed.
+    ///
+    /// # Arguments
+    ///
+    /// * `range` - Period the events occurred in (end excluded)
+    /// * `filter` - Events and webhooks to replay
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<Job<EventReplay>, KeyrunesError>`:
+    /// - `Ok(job)` with a handle to the replay job
+    /// - `Err(KeyrunesError::AuthorizationError)` if the token is not an administrator's
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyrunes_rust_sdk::{EventFilter, KeyrunesClient};
+    /// # use std::time::Duration;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = KeyrunesClient::new("https://keyrunes.example.com")?;
+    /// client.login("admin@example.com", "password123", None).await?;
+    /// let outage_end = chrono::Utc::now();
+    /// let outage_start = outage_end - chrono::Duration::hours(2);
+    ///
+    /// let job = client
+    ///     .admin()
+    ///     .replay_events(outage_start..outage_end, &EventFilter::new().webhook("wh_123"))
+    ///     .await?;
+    /// let replay = job
+    ///     .await_completion(Duration::from_secs(2), Duration::from_secs(600))
+    ///     .await?;
+    /// println!("{} of {} events delivered", replay.delivered, replay.matched);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn replay_events(
+        &self,
+        range: Range<DateTime<Utc>>,
+        filter: &EventFilter,
+    ) -> Result<Job<EventReplay>> {
+        let mut body = serde_json::to_value(filter)?;
+        body["from"] = serde_json::to_value(range.start)?;
+        body["to"] = serde_json::to_value(range.end)?;
+        let response = self
+            .client
+            .send_request(
+                reqwest::Method::POST,
+                endpoints::ADMIN_EVENTS_REPLAY,
+                RequestBody::Json(body),
+                Auth::Required,
+                None,
+            )
+            .await?;
+
+        self.client.job_from_response(response).await
+    }
+
+    /// Lists the deliveries to a webhook that failed after all their
+    /// retries (its dead letters), most recent first.
+    ///
+    /// # Arguments
+    ///
+    /// * `webhook_id` - Webhook ID
+    /// * `cursor` - Cursor returned by the previous page (optional)
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<Page<WebhookDelivery>, KeyrunesError>`:
+    /// - `Ok(page)` with the failed deliveries
+    /// - `Err(KeyrunesError::AuthorizationError)` if the token is not an administrator's
+    pub async fn list_failed_deliveries(
+        &self,
+        webhook_id: &str,
+        cursor: Option<&str>,
+    ) -> Result<Page<WebhookDelivery>> {
+        let mut path = format!(
+            "{}?status=failed",
+            endpoints::admin_webhook_deliveries(encode_path_segment(webhook_id))
+        );
+        if let Some(cursor) = cursor {
+            path.push_str("&cursor=");
+            path.extend(url::form_urlencoded::byte_serialize(cursor.as_bytes()));
+        }
+        let response = self
+            .client
+            .send_request(
+                reqwest::Method::GET,
+                &path,
+                RequestBody::Empty,
+                Auth::Required,
+                None,
+            )
+            .await?;
+
+        self.client.handle_response(response).await
+    }
+
+    /// Lists the IP restrictions of the tenant.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<Vec<IpRule>, KeyrunesError>`:
+    /// - `Ok(rules)` with the configured rules
+    /// - `Err(KeyrunesError::AuthorizationError)` if the token is not an administrator's
+    pub async fn list_ip_rules(&self) -> Result<Vec<IpRule>> {
+        self.client.generated().list_admin_ip_rules().await
+    }
+
+    /// Adds an IP restriction to the tenant.
+    ///
+    /// The range is validated before being sent; a bare address is treated
+    /// as a single-host range.
+    ///
+    /// # Arguments
+    ///
+    /// * `cidr` - IP range in CIDR notation (e.g., "203.0.113.0/24")
+    /// * `action` - Whether to allow or deny the range
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<IpRule, KeyrunesError>`:
+    /// - `Ok(rule)` with the created rule
+    /// - `Err(KeyrunesError::InvalidCidr)` if the range is malformed
+    /// - `Err(KeyrunesError::AuthorizationError)` if the token is not an administrator's
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyrunes_rust_sdk::{IpRuleAction, KeyrunesClient};
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = KeyrunesClient::new("https://keyrunes.example.com")?;
+    /// client.login("admin@example.com", "password123", None).await?;
+    /// let rule = client
+    ///     .admin()
+    ///     .add_ip_rule("203.0.113.0/24", IpRuleAction::Deny)
+    ///     .await?;
+    /// println!("Created rule {}", rule.id);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn add_ip_rule(&self, cidr: &str, action: IpRuleAction) -> Result<IpRule> {
+        let cidr = normalize_cidr(cidr)?;
+        let response = self
+            .client
+            .send_request(
+                reqwest::Method::POST,
+                endpoints::ADMIN_IP_RULES,
+                RequestBody::Json(serde_json::json!({ "cidr": cidr, "action": action })),
+                Auth::Required,
+                None,
+            )
+            .await?;
+
+        self.client.handle_response(response).await
+    }
+
+    /// Deletes an IP restriction.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<(), KeyrunesError>`:
+    /// - `Ok(())` if the rule was deleted
+    /// - `Err(KeyrunesError::AuthorizationError)` if the token is not an administrator's
+    pub async fn delete_ip_rule(&self, rule_id: &str) -> Result<()> {
+        self.client.generated().delete_admin_ip_rule(rule_id).await
+    }
+
+    /// Adds a permission to the tenant's catalog.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - Permission key (e.g., "documents:read")
+    /// * `description` - Human-readable description
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<Permission, KeyrunesError>`:
+    /// - `Ok(permission)` with the created permission
+    /// - `Err(KeyrunesError::AuthorizationError)` if the token is not an administrator's
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyrunes_rust_sdk::KeyrunesClient;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = KeyrunesClient::new("https://keyrunes.example.com")?;
+    /// client.login("admin@example.com", "password123", None).await?;
+    /// client
+    ///     .admin()
+    ///     .create_permission("documents:read", "Read documents")
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn create_permission<K: Into<String>, D: Into<String>>(
+        &self,
+        key: K,
+        description: D,
+    ) -> Result<Permission> {
+        let body = serde_json::json!({ "key": key.into(), "description": description.into() });
+        let response = self
+            .client
+            .send_request(
+                reqwest::Method::POST,
+                endpoints::ADMIN_PERMISSIONS,
+                RequestBody::Json(body),
+                Auth::Required,
+                None,
+            )
+            .await?;
+
+        self.client.handle_response(response).await
+    }
+
+    /// Lists the tenant's permission catalog.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<Vec<Permission>, KeyrunesError>`:
+    /// - `Ok(permissions)` with the declared permissions
+    /// - `Err(KeyrunesError::AuthorizationError)` if the token is not an administrator's
+    pub async fn list_permissions(&self) -> Result<Vec<Permission>> {
+        self.client.generated().list_admin_permissions().await
+    }
+
+    /// Updates a permission of the tenant's catalog.
+    ///
+    /// Fields set to `None` are left unchanged.
+    pub async fn update_permission(
+        &self,
+        key: &str,
+        description: Option<&str>,
+        deprecated: Option<bool>,
+    ) -> Result<Permission> {
+        let mut body = serde_json::Map::new();
+        if let Some(description) = description {
+            body.insert("description".to_string(), description.into());
+        }
+        if let Some(deprecated) = deprecated {
+            body.insert("deprecated".to_string(), deprecated.into());
+        }
+        let path = endpoints::admin_permission(encode_path_segment(key));
+        let response = self
+            .client
+            .send_request(
+                reqwest::Method::PATCH,
+                &path,
+                RequestBody::Json(body.into()),
+                Auth::Required,
+                None,
+            )
+            .await?;
+
+        self.client.handle_response(response).await
+    }
+
+    /// Syncs the tenant's permission catalog with a catalog declared in code.
+    ///
+    /// Missing permissions are created, changed descriptions are updated,
+    /// and permissions no longer declared are flagged as deprecated rather
+    /// than deleted, so existing grants keep working until cleaned up.
+    /// Running the sync twice is a no-op.
+    ///
+    /// # Arguments
+    ///
+    /// * `catalog` - Permissions declared by the application
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<PermissionSyncReport, KeyrunesError>`:
+    /// - `Ok(report)` with the applied changes
+    /// - `Err(KeyrunesError::AuthorizationError)` if the token is not an administrator's
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyrunes_rust_sdk::KeyrunesClient;
+    /// use keyrunes_rust_sdk::permissions::PermissionDef;
+    ///
+    /// const CATALOG: &[PermissionDef] = &[
+    ///     PermissionDef::new("documents:read", "Read documents"),
+    ///     PermissionDef::new("documents:write", "Create and edit documents"),
+    /// ];
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = KeyrunesClient::new("https://keyrunes.example.com")?;
+    /// client.login("admin@example.com", "password123", None).await?;
+    /// let report = client.admin().sync_permissions(CATALOG).await?;
+    /// println!("{} created, {} deprecated", report.created.len(), report.deprecated.len());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn sync_permissions(
+        &self,
+        catalog: &[PermissionDef],
+    ) -> Result<PermissionSyncReport> {
+        let existing: HashMap<String, Permission> = self
+            .list_permissions()
+            .await?
+            .into_iter()
+            .map(|permission| (permission.key.clone(), permission))
+            .collect();
+        let mut report = PermissionSyncReport::default();
+
+        for def in catalog {
+            match existing.get(def.key) {
+                None => {
+                    self.create_permission(def.key, def.description).await?;
+                    report.created.push(def.key.to_string());
+                }
+                Some(current)
+                    if current.deprecated
+                        || current.description.as_deref() != Some(def.description) =>
+                {
+                    self.update_permission(def.key, Some(def.description), Some(false))
+                        .await?;
+                    report.updated.push(def.key.to_string());
+                }
+                Some(_) => report.unchanged += 1,
+            }
+        }
+
+        let mut stale: Vec<&Permission> = existing
+            .values()
+            .filter(|permission| !permission.deprecated)
+            .filter(|permission| !catalog.iter().any(|def| def.key == permission.key))
+            .collect();
+        stale.sort_by(|a, b| a.key.cmp(&b.key));
+        for permission in stale {
+            self.update_permission(&permission.key, None, Some(true))
+                .await?;
+            report.deprecated.push(permission.key.clone());
+        }
+
+        Ok(report)
+    }
+
+    /// Removes a permission from the tenant's catalog.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<(), KeyrunesError>`:
+    /// - `Ok(())` if the permission was deleted
+    /// - `Err(KeyrunesError::AuthorizationError)` if the token is not an administrator's
+    pub async fn delete_permission(&self, key: &str) -> Result<()> {
+        self.client.generated().delete_admin_permission(key).await
+    }
+
+    /// Lists the keys Keyrunes signs tokens with, retired ones included.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<Vec<SigningKey>, KeyrunesError>`:
+    /// - `Ok(keys)` with the tenant's signing keys
+    /// - `Err(KeyrunesError::AuthorizationError)` if the token is not an administrator's
+    pub async fn signing_keys(&self) -> Result<Vec<SigningKey>> {
+        self.client.generated().list_admin_signing_keys().await
+    }
+
+    /// Rotates the signing keys.
+    ///
+    /// The pending key (or a new one) starts signing tokens, and the
+    /// active key becomes inactive: it stays published, so tokens it signed
+    /// remain valid until they expire.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<SigningKey, KeyrunesError>`:
+    /// - `Ok(key)` with the newly active key
+    /// - `Err(KeyrunesError::AuthorizationError)` if the token is not an administrator's
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyrunes_rust_sdk::{KeyrunesClient, SigningKeyStatus};
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = KeyrunesClient::new("https://keyrunes.example.com")?;
+    /// client.login("admin@example.com", "password123", None).await?;
+    /// let admin = client.admin();
+    ///
+    /// admin.rotate_signing_key().await?;
+    /// // Once the tokens signed by the previous keys have expired
+    /// for key in admin.signing_keys().await? {
+    ///     if key.status == SigningKeyStatus::Inactive {
+    ///         admin.retire_signing_key(&key.kid).await?;
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn rotate_signing_key(&self) -> Result<SigningKey> {
+        self.client.generated().rotate_admin_signing_key().await
+    }
+
+    /// Retires an inactive signing key, withdrawing it from the key set.
+    ///
+    /// Tokens it signed are rejected from then on, including by this
+    /// client, whose cached key set is dropped.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<SigningKey, KeyrunesError>`:
+    /// - `Ok(key)` with the retired key
+    /// - `Err(KeyrunesError::AuthorizationError)` if the token is not an administrator's
+    /// - `Err(KeyrunesError::HttpError)` if the key is still active or pending
+    pub async fn retire_signing_key(&self, kid: &str) -> Result<SigningKey> {
+        let key = self
+            .client
+            .generated()
+            .retire_admin_signing_key(kid)
+            .await?;
+        self.client.keys.clear().await;
+        Ok(key)
+    }
+
+    /// Lists the groups of the tenant.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<Vec<Group>, KeyrunesError>`:
+    /// - `Ok(groups)` with every group
+    /// - `Err(KeyrunesError::AuthorizationError)` if the token is not an administrator's
+    pub async fn list_groups(&self) -> Result<Vec<Group>> {
+        self.client.generated().list_admin_groups().await
+    }
+
+    /// Creates a group.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Unique group name (e.g., "engineering")
+    /// * `description` - Human-readable description, if any
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<Group, KeyrunesError>`:
+    /// - `Ok(group)` with the created group
+    /// - `Err(KeyrunesError::AuthorizationError)` if the token is not an administrator's
+    pub async fn create_group(&self, name: &str, description: Option<&str>) -> Result<Group> {
+        let body = serde_json::json!({ "name": name, "description": description });
+        let response = self
+            .client
+            .send_request(
+                reqwest::Method::POST,
+                endpoints::ADMIN_GROUPS,
+                RequestBody::Json(body),
+                Auth::Required,
+                None,
+            )
+            .await?;
+
+        self.client.handle_response(response).await
+    }
+
+    /// Updates the description of a group.
+    pub async fn update_group(&self, group_id: &str, description: &str) -> Result<Group> {
+        let body = serde_json::json!({ "description": description });
+        let response = self
+            .client
+            .send_request(
+                reqwest::Method::PATCH,
+                &endpoints::admin_group(encode_path_segment(group_id)),
+                RequestBody::Json(body),
+                Auth::Required,
+                None,
+            )
+            .await?;
+
+        self.client.handle_response(response).await
+    }
+
+    /// Deletes a group.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<(), KeyrunesError>`:
+    /// - `Ok(())` if the group was deleted
+    /// - `Err(KeyrunesError::GroupNotFoundError)` if the group doesn't exist
+    /// - `Err(KeyrunesError::AuthorizationError)` if the token is not an administrator's
+    pub async fn delete_group(&self, group_id: &str) -> Result<()> {
+        self.client.generated().delete_admin_group(group_id).await
+    }
+
+    /// Lists the permissions granted to groups.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<Vec<RoleBinding>, KeyrunesError>`:
+    /// - `Ok(bindings)` with every binding
+    /// - `Err(KeyrunesError::AuthorizationError)` if the token is not an administrator's
+    pub async fn list_role_bindings(&self) -> Result<Vec<RoleBinding>> {
+        self.client.generated().list_admin_role_bindings().await
+    }
+
+    /// Grants a permission of the catalog to the members of a group.
+    ///
+    /// # Arguments
+    ///
+    /// * `group` - Group name
+    /// * `permission` - Permission key (e.g., "documents:read")
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<RoleBinding, KeyrunesError>`:
+    /// - `Ok(binding)` with the created binding
+    /// - `Err(KeyrunesError::GroupNotFoundError)` if the group doesn't exist
+    /// - `Err(KeyrunesError::AuthorizationError)` if the token is not an administrator's
+    pub async fn create_role_binding(&self, group: &str, permission: &str) -> Result<RoleBinding> {
+        let body = serde_json::json!({ "group": group, "permission": permission });
+        let response = self
+            .client
+            .send_request(
+                reqwest::Method::POST,
+                endpoints::ADMIN_ROLE_BINDINGS,
+                RequestBody::Json(body),
+                Auth::Required,
+                None,
+            )
+            .await?;
+
+        self.client.handle_response(response).await
+    }
+
+    /// Revokes a permission granted to a group.
+    pub async fn delete_role_binding(&self, binding_id: &str) -> Result<()> {
+        self.client
+            .generated()
+            .delete_admin_role_binding(binding_id)
+            .await
+    }
+
+    /// Lists the service accounts of the tenant.
+    ///
+    /// Client secrets are not included.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<Vec<ServiceAccount>, KeyrunesError>`:
+    /// - `Ok(accounts)` with every service account
+    /// - `Err(KeyrunesError::AuthorizationError)` if the token is not an administrator's
+    pub async fn list_service_accounts(&self) -> Result<Vec<ServiceAccount>> {
+        self.client.generated().list_admin_service_accounts().await
+    }
+
+    /// Creates a service account.
+    ///
+    /// The returned account carries its client secret, which cannot be
+    /// retrieved later.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Unique account name (e.g., "billing-worker")
+    /// * `description` - Human-readable description, if any
+    /// * `groups` - Names of the groups the account belongs to
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<ServiceAccount, KeyrunesError>`:
+    /// - `Ok(account)` with the created account and its client secret
+    /// - `Err(KeyrunesError::AuthorizationError)` if the token is not an administrator's
+    pub async fn create_service_account(
+        &self,
+        name: &str,
+        description: Option<&str>,
+        groups: &[String],
+    ) -> Result<ServiceAccount> {
+        let body =
+            serde_json::json!({ "name": name, "description": description, "groups": groups });
+        let response = self
+            .client
+            .send_request(
+                reqwest::Method::POST,
+                endpoints::ADMIN_SERVICE_ACCOUNTS,
+                RequestBody::Json(body),
+                Auth::Required,
+                None,
+            )
+            .await?;
+
+        self.client.handle_response(response).await
+    }
+
+    /// Updates a service account.
+    ///
+    /// Fields set to `None` are left unchanged; `groups` replaces the
+    /// account's groups.
+    pub async fn update_service_account(
+        &self,
+        account_id: &str,
+        description: Option<&str>,
+        groups: Option<&[String]>,
+    ) -> Result<ServiceAccount> {
+        let mut body = serde_json::Map::new();
+        if let Some(description) = description {
+            body.insert("description".to_string(), description.into());
+        }
+        if let Some(groups) = groups {
+            body.insert("groups".to_string(), groups.into());
+        }
+        let response = self
+            .client
+            .send_request(
+                reqwest::Method::PATCH,
+                &endpoints::admin_service_account(encode_path_segment(account_id)),
+                RequestBody::Json(body.into()),
+                Auth::Required,
+                None,
+            )
+            .await?;
+
+        self.client.handle_response(response).await
+    }
+
+    /// Deletes a service account, revoking its credentials.
+    pub async fn delete_service_account(&self, account_id: &str) -> Result<()> {
+        self.client
+            .generated()
+            .delete_admin_service_account(account_id)
+            .await
+    }
+
+    /// Adds users to a group in bulk.
+    ///
+    /// Users are sent in batches of 500, so provisioning a large group
+    /// takes a few requests instead of one per user. A user that cannot be
+    /// added (e.g., unknown ID) does not fail the others: check the
+    /// per-user results of the report.
+    ///
+    /// # Arguments
+    ///
+    /// * `group_id` - Group ID
+    /// * `user_ids` - IDs of the users to add
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<BulkMembershipReport, KeyrunesError>`:
+    /// - `Ok(report)` with one result per user
+    /// - `Err(KeyrunesError::GroupNotFoundError)` if the group doesn't exist
+    /// - `Err(KeyrunesError::AuthorizationError)` if the token is not an administrator's
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyrunes_rust_sdk::KeyrunesClient;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = KeyrunesClient::new("https://keyrunes.example.com")?;
+    /// client.login("admin@example.com", "password123", None).await?;
+    /// let report = client
+    ///     .admin()
+    ///     .add_users_to_group("engineering", &["123", "456"])
+    ///     .await?;
+    /// for change in report.failed() {
+    ///     println!("{}: {:?}", change.user_id, change.message);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn add_users_to_group<S: AsRef<str>>(
+        &self,
+        group_id: &str,
+        user_ids: &[S],
+    ) -> Result<BulkMembershipReport> {
+        self.change_members(reqwest::Method::POST, group_id, user_ids)
+            .await
+    }
+
+    /// Removes users from a group in bulk.
+    ///
+    /// Works like [`Self::add_users_to_group`]; users not in the group are
+    /// reported as unchanged.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<BulkMembershipReport, KeyrunesError>`:
+    /// - `Ok(report)` with one result per user
+    /// - `Err(KeyrunesError::GroupNotFoundError)` if the group doesn't exist
+    /// - `Err(KeyrunesError::AuthorizationError)` if the token is not an administrator's
+    pub async fn remove_users_from_group<S: AsRef<str>>(
+        &self,
+        group_id: &str,
+        user_ids: &[S],
+    ) -> Result<BulkMembershipReport> {
+        self.change_members(reqwest::Method::DELETE, group_id, user_ids)
+            .await
+    }
+
+    /// Adds a user to a group until `expires_at`.
+    ///
+    /// For just-in-time elevated access (e.g., temporary admin while on
+    /// call): Keyrunes removes the user from the group once the membership
+    /// expires. Adding a user who is already a member replaces the expiry
+    /// of the membership.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - User ID
+    /// * `group_id` - Group ID
+    /// * `expires_at` - When the membership ends
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<GroupMembership, KeyrunesError>`:
+    /// - `Ok(membership)` with the granted membership
+    /// - `Err(KeyrunesError::GroupNotFoundError)` if the group doesn't exist
+    /// - `Err(KeyrunesError::UserNotFoundError)` if the user doesn't exist
+    /// - `Err(KeyrunesError::AuthorizationError)` if the token is not an administrator's
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyrunes_rust_sdk::KeyrunesClient;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = KeyrunesClient::new("https://keyrunes.example.com")?;
+    /// client.login("admin@example.com", "password123", None).await?;
+    /// let end_of_shift = chrono::Utc::now() + chrono::Duration::hours(8);
+    /// client
+    ///     .admin()
+    ///     .add_user_to_group_with_expiry("123", "admins", end_of_shift)
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn add_user_to_group_with_expiry(
+        &self,
+        user_id: &str,
+        group_id: &str,
+        expires_at: DateTime<Utc>,
+    ) -> Result<GroupMembership> {
+        let response = self
+            .client
+            .send_request(
+                reqwest::Method::PUT,
+                &endpoints::admin_group_member(
+                    encode_path_segment(group_id),
+                    encode_path_segment(user_id),
+                ),
+                RequestBody::Json(serde_json::json!({ "expires_at": expires_at })),
+                Auth::Required,
+                None,
+            )
+            .await?;
+
+        self.client.handle_response(response).await
+    }
+
+    /// Lists the members of a group, with the expiry of temporary grants.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<Vec<GroupMembership>, KeyrunesError>`:
+    /// - `Ok(memberships)` with the group's memberships
+    /// - `Err(KeyrunesError::GroupNotFoundError)` if the group doesn't exist
+    /// - `Err(KeyrunesError::AuthorizationError)` if the token is not an administrator's
+    pub async fn list_group_members(&self, group_id: &str) -> Result<Vec<GroupMembership>> {
+        self.client
+            .generated()
+            .list_admin_group_members(group_id)
+            .await
+    }
+
+    /// Sends a bulk membership change in batches, merging the results
+    async fn change_members<S: AsRef<str>>(
+        &self,
+        method: reqwest::Method,
+        group_id: &str,
+        user_ids: &[S],
+    ) -> Result<BulkMembershipReport> {
+        let path = endpoints::admin_group_members(encode_path_segment(group_id));
+        let mut report = BulkMembershipReport::default();
+
+        for batch in user_ids.chunks(MEMBERSHIP_BATCH_SIZE) {
+            let user_ids: Vec<&str> = batch.iter().map(AsRef::as_ref).collect();
+            let response = self
+                .client
+                .send_request(
+                    method.clone(),
+                    &path,
+                    RequestBody::Json(serde_json::json!({ "user_ids": user_ids })),
+                    Auth::Required,
+                    None,
+                )
+                .await?;
+            let batch_report: BulkMembershipReport = self.client.handle_response(response).await?;
+            report.results.extend(batch_report.results);
+        }
+
+        Ok(report)
+    }
+
+    /// Creates a user with a password hash from another credential store.
+    ///
+    /// Users migrated this way sign in with their existing password, without
+    /// a reset; Keyrunes rehashes it with its own algorithm at the first
+    /// login. The hash is checked against the format of `algorithm` before
+    /// being sent.
+    ///
+    /// # Arguments
+    ///
+    /// * `user` - User to create
+    /// * `algorithm` - Algorithm the password was hashed with
+    /// * `hash` - Hash, as stored by the previous system (see [`HashAlgorithm`])
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<User, KeyrunesError>`:
+    /// - `Ok(user)` with the created user
+    /// - `Err(KeyrunesError::InvalidPasswordHash)` if the hash is not in the format of `algorithm`
+    /// - `Err(KeyrunesError::UsernameTaken)` if the username is already in use
+    /// - `Err(KeyrunesError::AuthorizationError)` if the token is not an administrator's
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyrunes_rust_sdk::{HashAlgorithm, KeyrunesClient, UserImport};
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = KeyrunesClient::new("https://keyrunes.example.com")?;
+    /// client.login("admin@example.com", "password123", None).await?;
+    /// let user = client
+    ///     .admin()
+    ///     .import_user_with_hash(
+    ///         &UserImport::new("john", "john@example.com"),
+    ///         HashAlgorithm::Bcrypt,
+    ///         "$2b$12$R9h/cIPz0gi.URNNX3kh2OPST9/PgBkqquzi.Ss7KIUgO2t0jWMUW",
+    ///     )
+    ///     .await?;
+    /// println!("Imported {}", user.id);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn import_user_with_hash(
+        &self,
+        user: &UserImport,
+        algorithm: HashAlgorithm,
+        hash: &str,
+    ) -> Result<User> {
+        validate_password_hash(algorithm, hash)?;
+        let mut body = serde_json::to_value(user)?;
+        body["password_hash"] = serde_json::json!({ "algorithm": algorithm, "hash": hash });
+        let response = self
+            .client
+            .send_request(
+                reqwest::Method::POST,
+                endpoints::ADMIN_USERS_IMPORT,
+                RequestBody::Json(body),
+                Auth::Required,
+                None,
+            )
+            .await?;
+
+        self.client.handle_response(response).await
+    }
+}
+
+/// Percent-encodes a value used as a URL path segment
+pub(crate) fn encode_path_segment(value: &str) -> String {
+    url::form_urlencoded::byte_serialize(value.as_bytes())
+        .collect::<String>()
+        .replace('+', "%20")
+}
+
+/// Validates a CIDR block, returning it in canonical form
+///
+/// Bare addresses get a host prefix (`/32` or `/128`).
+fn normalize_cidr(cidr: &str) -> Result<String> {
+    let invalid = || KeyrunesError::InvalidCidr(cidr.to_string());
+    let (address, prefix) = match cidr.trim().split_once('/') {
+        Some((address, prefix)) => (address, Some(prefix)),
+        None => (cidr.trim(), None),
+    };
+
+    let address: IpAddr = address.parse().map_err(|_| invalid())?;
+    let max_prefix = if address.is_ipv4() { 32 } else { 128 };
+    let prefix = match prefix {
+        Some(prefix) => prefix
+            .parse::<u8>()
+            .ok()
+            .filter(|prefix| *prefix <= max_prefix)
+            .ok_or_else(invalid)?,
+        None => max_prefix,
+    };
+
+    Ok(format!("{}/{}", address, prefix))
+}
+
+/// Checks that a password hash is in the format of its algorithm
+///
+/// bcrypt hashes must be in modular crypt format; the others must be PHC
+/// strings naming the algorithm, with a salt and a hash.
+fn validate_password_hash(algorithm: HashAlgorithm, hash: &str) -> Result<()> {
+    let invalid = |reason: &str| {
+        Err(KeyrunesError::InvalidPasswordHash(format!(
+            "{} hash {}",
+            algorithm.as_str(),
+            reason
+        )))
+    };
+    let is_b64 = |part: &str| {
+        !part.is_empty()
+            && part
+                .bytes()
+                .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'.' | b'/' | b'+' | b'='))
+    };
+
+    if algorithm == HashAlgorithm::Bcrypt {
+        let valid = hash.len() == 60
+            && hash.is_ascii()
+            && ["$2a$", "$2b$", "$2y$"].iter().any(|p| hash.starts_with(p))
+            && hash[4..6]
+                .parse::<u8>()
+                .is_ok_and(|cost| (4..=31).contains(&cost))
+            && &hash[6..7] == "$"
+            && is_b64(&hash[7..]);
+        return if valid {
+            Ok(())
+        } else {
+            invalid("must be in modular crypt format ($2b$<cost>$<salt and hash>)")
+        };
+    }
+
+    let parts: Vec<&str> = hash.split('$').collect();
+    if parts.len() < 5 || !parts[0].is_empty() || parts[1] != algorithm.as_str() {
+        return invalid(&format!(
+            "must be a PHC string starting with ${}$",
+            algorithm.as_str()
+        ));
+    }
+    if parts[2..].iter().any(|part| part.is_empty())
+        || !parts[parts.len() - 2..].iter().all(|part| is_b64(part))
+    {
+        return invalid("must end with a base64 salt and hash");
+    }
+    Ok(())
+}
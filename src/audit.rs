@@ -0,0 +1,298 @@
+//! Audit trail of access decisions
+//!
+//! Compliance-heavy deployments must be able to tell who was allowed or
+//! denied what, and when. When an [`AuthAuditSink`] is set on the
+//! [`AuthService`](crate::AuthService), the middlewares report every
+//! allow/deny decision to it as an [`AuthDecision`]: user, route,
+//! requirement, outcome and latency. Requests matching the bypass rules
+//! are not reported.
+//!
+//! Two sinks are provided: `TracingAuditSink` (feature `tracing`), which
+//! emits one event per decision, and [`HttpAuditSink`], which posts
+//! decisions in batches to the Keyrunes audit intake.
+//!
+//! ## Quick Start
+//!
+//! ```
+//! use keyrunes_rust_sdk::audit::HttpAuditSink;
+//! use keyrunes_rust_sdk::{AuthService, KeyrunesClient};
+//! use std::time::Duration;
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = KeyrunesClient::new("https://keyrunes.example.com")?;
+//! let sink = HttpAuditSink::new(client.clone()).with_max_pending(200);
+//! let _flusher = sink.spawn_flusher(Duration::from_secs(5));
+//!
+//! let auth = AuthService::new(client).with_audit_sink(sink);
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::client::shutdown::Flush;
+use crate::client::KeyrunesClient;
+use crate::error::Result;
+use crate::middleware::messages::{default_message, Locale, RejectionKind};
+use crate::User;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex, Weak};
+use std::time::{Duration, Instant};
+
+/// Outcome of an access decision
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Decision {
+    Allow,
+    Deny,
+}
+
+/// An access decision taken by a middleware
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuthDecision {
+    /// User the decision applies to (`None` if the request was not authenticated)
+    pub user_id: Option<String>,
+    /// HTTP method of the request
+    pub method: String,
+    /// Route of the request (the route pattern when the framework knows it)
+    pub route: String,
+    /// Requirement that was checked (`authenticated`, `group:admins`, ...)
+    pub requirement: String,
+    /// Whether the request was allowed
+    pub decision: Decision,
+    /// Why the request was denied
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub reason: Option<String>,
+    /// Time taken to reach the decision, in milliseconds
+    pub latency_ms: u64,
+    /// When the decision was taken
+    pub timestamp: DateTime<Utc>,
+}
+
+/// Receives the access decisions taken by the middlewares
+///
+/// Called on the request path, so implementations should hand decisions
+/// off (to a buffer, a channel, a logger) rather than block.
+pub trait AuthAuditSink: Send + Sync {
+    fn record(&self, decision: AuthDecision);
+}
+
+/// Times an access decision and reports it to the audit sink, if any
+///
+/// Created by [`AuthService::audit`](crate::AuthService::audit) when a
+/// middleware starts checking a request.
+pub struct AuditTimer {
+    sink: Option<Arc<dyn AuthAuditSink>>,
+    started: Instant,
+}
+
+impl AuditTimer {
+    pub(crate) fn new(sink: Option<Arc<dyn AuthAuditSink>>) -> Self {
+        Self {
+            sink,
+            started: Instant::now(),
+        }
+    }
+
+    /// Reports the outcome of checking `requirement` for a request.
+    ///
+    /// With the `tracing` feature, the outcome is also recorded on the
+    /// current span (see [`crate::telemetry`]).
+    pub fn finish<T>(
+        self,
+        method: &str,
+        route: &str,
+        requirement: &str,
+        user: Option<&User>,
+        result: &std::result::Result<T, RejectionKind>,
+    ) {
+        #[cfg(feature = "tracing")]
+        crate::telemetry::record_decision(
+            user,
+            route,
+            match result {
+                Ok(_) => Decision::Allow,
+                Err(_) => Decision::Deny,
+            },
+        );
+
+        let Some(sink) = self.sink else {
+            return;
+        };
+
+        let (decision, reason) = match result {
+            Ok(_) => (Decision::Allow, None),
+            Err(kind) => (
+                Decision::Deny,
+                Some(default_message(
+                    kind.clone(),
+                    &Locale::from_accept_language(None),
+                )),
+            ),
+        };
+        sink.record(AuthDecision {
+            user_id: user.map(|user| user.id.clone()),
+            method: method.to_string(),
+            route: route.to_string(),
+            requirement: requirement.to_string(),
+            decision,
+            reason,
+            latency_ms: self.started.elapsed().as_millis() as u64,
+            timestamp: Utc::now(),
+        });
+    }
+}
+
+/// Audit sink emitting one `tracing` event per decision (feature `tracing`)
+///
+/// Events use the `keyrunes::audit` target: allowed requests are logged at
+/// `INFO` and denied ones at `WARN`.
+#[cfg(feature = "tracing")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TracingAuditSink;
+
+#[cfg(feature = "tracing")]
+impl AuthAuditSink for TracingAuditSink {
+    fn record(&self, decision: AuthDecision) {
+        match decision.decision {
+            Decision::Allow => tracing::info!(
+                target: "keyrunes::audit",
+                user_id = decision.user_id.as_deref(),
+                method = %decision.method,
+                route = %decision.route,
+                requirement = %decision.requirement,
+                latency_ms = decision.latency_ms,
+                "access allowed"
+            ),
+            Decision::Deny => tracing::warn!(
+                target: "keyrunes::audit",
+                user_id = decision.user_id.as_deref(),
+                method = %decision.method,
+                route = %decision.route,
+                requirement = %decision.requirement,
+                reason = decision.reason.as_deref(),
+                latency_ms = decision.latency_ms,
+                "access denied"
+            ),
+        }
+    }
+}
+
+/// Audit sink posting decisions in batches to the Keyrunes audit intake
+///
+/// Decisions are buffered and sent once `max_pending` are waiting, or by
+/// the task started with [`HttpAuditSink::spawn_flusher`]. Batches that
+/// fail to send are kept and retried with the next one. Cloning a sink is
+/// cheap and shares the buffer.
+#[derive(Clone)]
+pub struct HttpAuditSink {
+    client: KeyrunesClient,
+    pending: Arc<Mutex<Vec<AuthDecision>>>,
+    max_pending: usize,
+}
+
+impl HttpAuditSink {
+    /// Creates a sink posting to Keyrunes through `client`.
+    ///
+    /// By default, decisions are sent once 100 are pending. Pending
+    /// decisions are also sent by [`KeyrunesClient::shutdown`].
+    pub fn new(client: KeyrunesClient) -> Self {
+        let pending: Arc<Mutex<Vec<AuthDecision>>> = Arc::new(Mutex::new(Vec::new()));
+        client
+            .background
+            .track_batch(Arc::downgrade(&pending) as Weak<dyn Flush>);
+        Self {
+            client,
+            pending,
+            max_pending: 100,
+        }
+    }
+
+    /// Sets the number of pending decisions that triggers a send.
+    pub fn with_max_pending(mut self, max_pending: usize) -> Self {
+        self.max_pending = max_pending.max(1);
+        self
+    }
+
+    /// Sends all pending decisions to Keyrunes.
+    ///
+    /// On failure, the decisions are kept for the next flush.
+    pub async fn flush(&self) -> Result<()> {
+        self.pending.flush(&self.client).await
+    }
+
+    /// Spawns a task flushing pending decisions every `interval`.
+    ///
+    /// Abort the returned handle, or shut the client down, to stop flushing.
+    pub fn spawn_flusher(&self, interval: Duration) -> tokio::task::JoinHandle<()> {
+        let sink = self.clone();
+        let handle = tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.tick().await;
+            loop {
+                ticker.tick().await;
+                // Failed batches stay pending and are retried on the next tick.
+                let _ = sink.flush().await;
+            }
+        });
+        self.client.background.track_task(handle.abort_handle());
+        handle
+    }
+}
+
+#[async_trait::async_trait]
+impl Flush for Mutex<Vec<AuthDecision>> {
+    async fn flush(&self, client: &KeyrunesClient) -> Result<()> {
+        let batch = std::mem::take(&mut *lock(self));
+        send(client, self, batch).await
+    }
+}
+
+/// Sends `batch`, putting it back in `pending` on failure
+async fn send(
+    client: &KeyrunesClient,
+    pending: &Mutex<Vec<AuthDecision>>,
+    batch: Vec<AuthDecision>,
+) -> Result<()> {
+    if batch.is_empty() {
+        return Ok(());
+    }
+    if let Err(err) = client.send_audit_events(&batch).await {
+        let mut pending = lock(pending);
+        let newer = std::mem::replace(&mut *pending, batch);
+        pending.extend(newer);
+        return Err(err);
+    }
+    Ok(())
+}
+
+fn lock(pending: &Mutex<Vec<AuthDecision>>) -> std::sync::MutexGuard<'_, Vec<AuthDecision>> {
+    pending
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+impl AuthAuditSink for HttpAuditSink {
+    fn record(&self, decision: AuthDecision) {
+        let batch = {
+            let mut pending = lock(&self.pending);
+            pending.push(decision);
+            if pending.len() < self.max_pending {
+                return;
+            }
+            std::mem::take(&mut *pending)
+        };
+
+        let sink = self.clone();
+        tokio::spawn(async move {
+            let _ = send(&sink.client, &sink.pending, batch).await;
+        });
+    }
+}
+
+impl std::fmt::Debug for HttpAuditSink {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HttpAuditSink")
+            .field("max_pending", &self.max_pending)
+            .finish()
+    }
+}
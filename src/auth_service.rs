@@ -0,0 +1,385 @@
+//! Framework-agnostic authentication and authorization
+//!
+//! [`AuthService`] holds the logic shared by the framework integrations:
+//! bearer token parsing, user resolution, group, admin, permission and
+//! entitlement checks, and the mapping of failures to a [`RejectionKind`].
+//! The Axum, Actix, Rocket and Loco integrations delegate to it; on other
+//! frameworks (tide, ntex, ...) it takes a few lines to wire up:
+//!
+//! ```ignore
+//! let auth = AuthService::new(client);
+//!
+//! let user = match auth.authenticate_header(req.header("authorization")).await {
+//!     Ok(user) => user,
+//!     Err(kind) => return reply(kind.status(), auth.message(kind, req.header("accept-language"))),
+//! };
+//! ```
+//!
+//! Group checks follow the [`GroupCheckStrategy`], admin checks the
+//! [`AdminPolicy`], and entitlements use the client's cache.
+//!
+//! The shared client is never signed in as the caller: each request is
+//! resolved and checked on a view of it carrying the request's token
+//! ([`KeyrunesClient::as_user`]), so concurrent requests cannot observe
+//! each other's token, and caller tokens are never refreshed.
+
+use crate::audit::{AuditTimer, AuthAuditSink};
+use crate::claims::{AuthLevel, Claims};
+use crate::middleware::admin_policy::{AdminPolicy, GroupPolicy};
+use crate::middleware::bypass::BypassRules;
+use crate::middleware::group_check::GroupCheckStrategy;
+use crate::middleware::messages::{default_message, Locale, MessageFormatter, RejectionKind};
+#[cfg(feature = "mtls")]
+use crate::mtls::CertificateBinding;
+use crate::rate_limit::{RateLimitDecision, RateLimiter};
+use crate::requirements::{Require, RouteRequirements};
+use crate::validation::TokenValidation;
+use crate::{KeyrunesClient, User};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Authenticates requests and enforces access requirements
+///
+/// Cheap to clone; clones share the client and the admin policy. Failures
+/// are returned as a [`RejectionKind`], whose `status()` is the HTTP status
+/// to respond with and whose body is rendered by [`AuthService::message`].
+#[derive(Clone)]
+pub struct AuthService {
+    client: Arc<KeyrunesClient>,
+    messages: MessageFormatter,
+    admin_policy: Arc<dyn AdminPolicy>,
+    group_check: GroupCheckStrategy,
+    bypass: BypassRules,
+    audit: Option<Arc<dyn AuthAuditSink>>,
+    validation: Option<Arc<TokenValidation>>,
+    verified_email: bool,
+    #[cfg(feature = "mtls")]
+    certificate_binding: Option<Arc<CertificateBinding>>,
+}
+
+impl AuthService {
+    pub fn new<C: Into<Arc<KeyrunesClient>>>(client: C) -> Self {
+        Self {
+            client: client.into(),
+            messages: default_message,
+            admin_policy: Arc::new(GroupPolicy::default()),
+            group_check: GroupCheckStrategy::default(),
+            bypass: BypassRules::default(),
+            audit: None,
+            validation: None,
+            verified_email: false,
+            #[cfg(feature = "mtls")]
+            certificate_binding: None,
+        }
+    }
+
+    /// Sets how group requirements are checked (always verified with Keyrunes by default).
+    ///
+    /// Admin checks follow the admin policy; use
+    /// `GroupPolicy::default().with_strategy(strategy)` to apply the strategy to them too.
+    pub fn with_group_check(mut self, strategy: GroupCheckStrategy) -> Self {
+        self.group_check = strategy;
+        self
+    }
+
+    /// Decides who is an administrator (members of `admins` by default).
+    pub fn with_admin_policy<P: AdminPolicy + 'static>(mut self, policy: P) -> Self {
+        self.admin_policy = Arc::new(policy);
+        self
+    }
+
+    /// Renders rejection bodies with `formatter` (see [`Self::message`]).
+    pub fn with_message_formatter(mut self, formatter: MessageFormatter) -> Self {
+        self.messages = formatter;
+        self
+    }
+
+    /// Sets the requests the middlewares let through without a token
+    /// (`OPTIONS` and health endpoints by default).
+    pub fn with_bypass_rules(mut self, rules: BypassRules) -> Self {
+        self.bypass = rules;
+        self
+    }
+
+    /// Reports the middlewares' access decisions to `sink` (see [`crate::audit`]).
+    pub fn with_audit_sink<A: AuthAuditSink + 'static>(mut self, sink: A) -> Self {
+        self.audit = Some(Arc::new(sink));
+        self
+    }
+
+    /// Validates tokens locally with `validation` instead of asking Keyrunes
+    /// (see [`crate::validation`]).
+    ///
+    /// The user is then built from the token's claims, read through the
+    /// client's claims mapping.
+    pub fn with_token_validation(mut self, validation: TokenValidation) -> Self {
+        self.validation = Some(Arc::new(validation));
+        self
+    }
+
+    /// Rejects users whose email is not verified (off by default).
+    ///
+    /// Users are rejected with 403 unless their `email_verified` is
+    /// `Some(true)`, including when the server does not report it.
+    pub fn with_verified_email_required(mut self, required: bool) -> Self {
+        self.verified_email = required;
+        self
+    }
+
+    /// Verifies that certificate-bound tokens are presented with their
+    /// client certificate (feature `mtls`, see [`crate::mtls`]).
+    #[cfg(feature = "mtls")]
+    pub fn with_certificate_binding(mut self, binding: CertificateBinding) -> Self {
+        self.certificate_binding = Some(Arc::new(binding));
+        self
+    }
+
+    /// Header the middlewares read the client certificate from, if any
+    pub fn certificate_header(&self) -> Option<&str> {
+        #[cfg(feature = "mtls")]
+        if let Some(binding) = &self.certificate_binding {
+            return Some(binding.header());
+        }
+        None
+    }
+
+    /// Client the per-request views are derived from
+    pub fn client(&self) -> &Arc<KeyrunesClient> {
+        &self.client
+    }
+
+    /// View of the client acting with the caller's `token`
+    fn caller(&self, token: &str) -> KeyrunesClient {
+        self.client.as_user(token)
+    }
+
+    /// How group requirements are checked
+    pub fn group_check(&self) -> GroupCheckStrategy {
+        self.group_check
+    }
+
+    /// Whether the middlewares let a request through without a token.
+    pub fn bypasses(&self, method: &str, path: &str) -> bool {
+        self.bypass.matches(method, path)
+    }
+
+    /// Starts timing an access decision, to be reported to the audit sink.
+    pub fn audit(&self) -> AuditTimer {
+        AuditTimer::new(self.audit.clone())
+    }
+
+    /// Extracts the token from an `Authorization` header value.
+    pub fn bearer_token(authorization: Option<&str>) -> Result<&str, RejectionKind> {
+        authorization
+            .ok_or(RejectionKind::MissingToken)?
+            .strip_prefix("Bearer ")
+            .ok_or(RejectionKind::InvalidToken)
+    }
+
+    /// Resolves the user a bearer token belongs to.
+    ///
+    /// With a token validation set, the token is validated locally;
+    /// otherwise Keyrunes resolves it. Users whose email is not verified are
+    /// rejected if required (see [`Self::with_verified_email_required`]).
+    pub async fn authenticate(&self, token: &str) -> Result<User, RejectionKind> {
+        let user = if let Some(validation) = &self.validation {
+            let payload = self
+                .client
+                .validate_payload(token, validation)
+                .await
+                .map_err(|e| RejectionKind::from(&e))?;
+            User::from_claims(&payload, self.client.claims_mapping())
+                .map_err(|e| RejectionKind::from(&e))?
+        } else {
+            self.caller(token)
+                .get_current_user()
+                .await
+                .map_err(|e| RejectionKind::Unauthenticated(e.to_string()))?
+        };
+
+        if self.verified_email && user.email_verified != Some(true) {
+            return Err(RejectionKind::Forbidden(
+                "Email address not verified".to_string(),
+            ));
+        }
+        Ok(user)
+    }
+
+    /// Resolves the user of a request from its `Authorization` header value.
+    pub async fn authenticate_header(
+        &self,
+        authorization: Option<&str>,
+    ) -> Result<User, RejectionKind> {
+        self.authenticate_request(authorization, None).await
+    }
+
+    /// Resolves the user of a request from its `Authorization` header value
+    /// and the value of its client certificate header
+    /// ([`Self::certificate_header`]).
+    ///
+    /// With a certificate binding set, tokens bound to a certificate are
+    /// rejected unless `certificate` matches it.
+    pub async fn authenticate_request(
+        &self,
+        authorization: Option<&str>,
+        certificate: Option<&str>,
+    ) -> Result<User, RejectionKind> {
+        let token = Self::bearer_token(authorization)?;
+        #[cfg(feature = "mtls")]
+        if let Some(binding) = &self.certificate_binding {
+            binding
+                .verify(token, certificate)
+                .map_err(|e| RejectionKind::from(&e))?;
+        }
+        #[cfg(not(feature = "mtls"))]
+        let _ = certificate;
+        self.authenticate(token).await
+    }
+
+    /// Verifies that `user`, authenticated with `token`, belongs to `group`.
+    pub async fn require_group(
+        &self,
+        user: &User,
+        token: &str,
+        group: &str,
+    ) -> Result<(), RejectionKind> {
+        let has_group = self
+            .group_check
+            .check(&self.caller(token), user, group)
+            .await
+            .map_err(|e| RejectionKind::from(&e))?;
+
+        if !has_group {
+            return Err(RejectionKind::Forbidden(format!(
+                "User does not belong to group: {}",
+                group
+            )));
+        }
+        Ok(())
+    }
+
+    /// Verifies that `user`, authenticated with `token`, is an administrator.
+    pub async fn require_admin(&self, user: &User, token: &str) -> Result<(), RejectionKind> {
+        let is_admin = self
+            .admin_policy
+            .is_admin(&self.caller(token), user, token)
+            .await
+            .map_err(|e| RejectionKind::from(&e))?;
+
+        if !is_admin {
+            return Err(RejectionKind::Forbidden(
+                "Access denied: administrator privileges required".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Verifies that `user`, authenticated with `token`, holds `permission`
+    /// (checked against their compiled policy).
+    pub async fn require_permission(
+        &self,
+        user: &User,
+        token: &str,
+        permission: &str,
+    ) -> Result<(), RejectionKind> {
+        let policy = self
+            .caller(token)
+            .compile_policy(user.id.as_str())
+            .await
+            .map_err(|e| RejectionKind::from(&e))?;
+
+        if !policy.implies(permission) {
+            return Err(RejectionKind::Forbidden(format!(
+                "Permission required: {}",
+                permission
+            )));
+        }
+        Ok(())
+    }
+
+    /// Verifies that `user`, authenticated with `token`, holds the entitlement `key`.
+    pub async fn require_entitlement(
+        &self,
+        user: &User,
+        token: &str,
+        key: &str,
+    ) -> Result<(), RejectionKind> {
+        let entitled = self
+            .caller(token)
+            .has_entitlement(user.id.as_str(), key)
+            .await
+            .map_err(|e| RejectionKind::from(&e))?;
+
+        if !entitled {
+            return Err(RejectionKind::Forbidden(format!(
+                "Entitlement required: {}",
+                key
+            )));
+        }
+        Ok(())
+    }
+
+    /// Verifies that `user`, authenticated with `token`, meets the route requirements `R`.
+    pub async fn require<R: RouteRequirements>(
+        &self,
+        user: User,
+        token: &str,
+    ) -> Result<Require<R>, RejectionKind> {
+        Require::check(&self.caller(token), user)
+            .await
+            .map_err(|e| RejectionKind::from(&e))
+    }
+
+    /// Verifies that `token` reached `level`, returning its actual level.
+    pub fn require_auth_level(
+        &self,
+        token: &str,
+        level: AuthLevel,
+    ) -> Result<AuthLevel, RejectionKind> {
+        let current = self.claims(token)?.auth_level();
+        if current < level {
+            return Err(RejectionKind::StepUpRequired(level));
+        }
+        Ok(current)
+    }
+
+    /// Verifies that `token` was issued for a login within `max_age`.
+    pub fn require_fresh_auth(&self, token: &str, max_age: Duration) -> Result<(), RejectionKind> {
+        if !self.claims(token)?.is_fresh(max_age) {
+            return Err(RejectionKind::ReauthenticationRequired(max_age.as_secs()));
+        }
+        Ok(())
+    }
+
+    /// Consumes one request from the quota of `user`.
+    pub async fn check_rate_limit(
+        limiter: &RateLimiter,
+        user: &User,
+    ) -> Result<RateLimitDecision, RejectionKind> {
+        let decision = limiter
+            .check(&user.id)
+            .await
+            .map_err(|e| RejectionKind::from(&e))?;
+
+        if !decision.allowed {
+            let retry_after = decision
+                .retry_after
+                .map(|d| d.as_secs().max(1))
+                .unwrap_or(1);
+            return Err(RejectionKind::TooManyRequests(retry_after));
+        }
+        Ok(decision)
+    }
+
+    /// Renders the body of a rejection in the language of an `Accept-Language` header value.
+    pub fn message(&self, kind: RejectionKind, accept_language: Option<&str>) -> String {
+        (self.messages)(kind, &Locale::from_accept_language(accept_language))
+    }
+
+    /// Decodes `token` with the client's [`ClaimsMapping`](crate::claims::ClaimsMapping).
+    fn claims(&self, token: &str) -> Result<Claims, RejectionKind> {
+        self.client
+            .decode_claims(token)
+            .map_err(|_| RejectionKind::InvalidToken)
+    }
+}
@@ -0,0 +1,292 @@
+//! Emergency break-glass access
+//!
+//! When single sign-on is down during an incident, responders may need to
+//! reach Keyrunes with an emergency account whose credentials are sealed
+//! away (in a vault, a secret manager, or a safe). [`BreakGlass`] opens
+//! such a session on a dedicated view of the client, so the credentials
+//! never replace the client's own token, and makes its use conspicuous:
+//!
+//! - every request carries the incident and the reason as audit headers
+//!   (`X-Keyrunes-Break-Glass`, `X-Keyrunes-Audit-Reason`), which Keyrunes
+//!   records in its audit log;
+//! - with the `tracing` feature, opening and closing the session are logged
+//!   at `ERROR` and every request at `WARN`, under the
+//!   `keyrunes::break_glass` target, so alerting can page on them.
+//!
+//! ## Quick Start
+//!
+//! ```
+//! use keyrunes_rust_sdk::break_glass::{BreakGlass, EnvCredentials};
+//! use keyrunes_rust_sdk::KeyrunesClient;
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = KeyrunesClient::new("https://keyrunes.example.com")?;
+//! let session = BreakGlass::open(
+//!     &client,
+//!     &EnvCredentials::default(),
+//!     "INC-4821",
+//!     "SSO provider outage, restoring on-call access",
+//! )
+//! .await?;
+//! session
+//!     .client()
+//!     .admin()
+//!     .add_user_to_group_with_expiry("123", "admins", chrono::Utc::now() + chrono::Duration::hours(2))
+//!     .await?;
+//! session.close().await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::client::KeyrunesClient;
+use crate::error::{KeyrunesError, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use reqwest::header::HeaderValue;
+use std::sync::Arc;
+
+/// Header carrying the incident of a break-glass session
+const HEADER_BREAK_GLASS: &str = "X-Keyrunes-Break-Glass";
+/// Header carrying the reason of a break-glass session
+const HEADER_AUDIT_REASON: &str = "X-Keyrunes-Audit-Reason";
+
+/// Environment variable holding the emergency identity
+const ENV_IDENTITY: &str = "KEYRUNES_BREAK_GLASS_IDENTITY";
+/// Environment variable holding the emergency password
+const ENV_PASSWORD: &str = "KEYRUNES_BREAK_GLASS_PASSWORD";
+/// Environment variable holding the namespace of the emergency account
+const ENV_NAMESPACE: &str = "KEYRUNES_BREAK_GLASS_NAMESPACE";
+
+/// Credentials of an emergency account
+#[derive(Clone)]
+pub struct EmergencyCredentials {
+    /// Username or email of the emergency account
+    pub identity: String,
+    /// Password of the emergency account
+    pub password: String,
+    /// Namespace of the emergency account (default: "public")
+    pub namespace: Option<String>,
+}
+
+impl std::fmt::Debug for EmergencyCredentials {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EmergencyCredentials")
+            .field("identity", &self.identity)
+            .field("password", &"[REDACTED]")
+            .field("namespace", &self.namespace)
+            .finish()
+    }
+}
+
+/// Secure store the emergency credentials are unsealed from
+///
+/// Implement it over the vault or secret manager holding the credentials;
+/// they are read only when a break-glass session is opened.
+#[async_trait]
+pub trait SealedCredentials: Send + Sync {
+    /// Unseals the emergency credentials.
+    async fn unseal(&self) -> Result<EmergencyCredentials>;
+}
+
+#[async_trait]
+impl SealedCredentials for EmergencyCredentials {
+    async fn unseal(&self) -> Result<EmergencyCredentials> {
+        Ok(self.clone())
+    }
+}
+
+/// Emergency credentials injected in the environment (e.g., by a secret
+/// manager at incident time)
+///
+/// Reads `KEYRUNES_BREAK_GLASS_IDENTITY`, `KEYRUNES_BREAK_GLASS_PASSWORD`
+/// and, optionally, `KEYRUNES_BREAK_GLASS_NAMESPACE`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EnvCredentials;
+
+#[async_trait]
+impl SealedCredentials for EnvCredentials {
+    async fn unseal(&self) -> Result<EmergencyCredentials> {
+        let var = |name| {
+            std::env::var(name).map_err(|_| KeyrunesError::Other(format!("{} is not set", name)))
+        };
+        Ok(EmergencyCredentials {
+            identity: var(ENV_IDENTITY)?,
+            password: var(ENV_PASSWORD)?,
+            namespace: std::env::var(ENV_NAMESPACE).ok(),
+        })
+    }
+}
+
+/// Audit annotations sent with every request of a break-glass session
+#[derive(Debug)]
+pub(crate) struct Annotation {
+    incident: HeaderValue,
+    reason: HeaderValue,
+}
+
+impl Annotation {
+    fn new(incident: &str, reason: &str) -> Result<Self> {
+        let value = |name, value: &str| {
+            if value.trim().is_empty() {
+                return Err(KeyrunesError::InvalidAuditAnnotation(format!(
+                    "{} is required",
+                    name
+                )));
+            }
+            HeaderValue::from_str(value).map_err(|_| {
+                KeyrunesError::InvalidAuditAnnotation(format!(
+                    "{} must be a single line of visible ASCII",
+                    name
+                ))
+            })
+        };
+        Ok(Self {
+            incident: value("incident", incident)?,
+            reason: value("reason", reason)?,
+        })
+    }
+
+    fn incident(&self) -> &str {
+        self.incident.to_str().unwrap_or_default()
+    }
+
+    /// Adds the audit headers to `request`, and logs it.
+    pub(crate) fn annotate(&self, request: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        #[cfg(feature = "tracing")]
+        {
+            // Streaming bodies cannot be cloned; the request is logged without its target.
+            let target = request.try_clone().and_then(|clone| clone.build().ok());
+            tracing::warn!(
+                target: "keyrunes::break_glass",
+                incident = self.incident(),
+                method = target.as_ref().map(|r| r.method().to_string()),
+                url = target.as_ref().map(|r| r.url().to_string()),
+                "break-glass request"
+            );
+        }
+        request
+            .header(HEADER_BREAK_GLASS, self.incident.clone())
+            .header(HEADER_AUDIT_REASON, self.reason.clone())
+    }
+}
+
+/// Break-glass session, authenticated with the emergency credentials
+///
+/// The session runs on its own view of the client: the client it was
+/// opened from keeps its token and is not annotated.
+pub struct BreakGlass {
+    client: KeyrunesClient,
+    opened_at: DateTime<Utc>,
+}
+
+impl BreakGlass {
+    /// Unseals the emergency credentials and logs in with them.
+    ///
+    /// # Arguments
+    ///
+    /// * `client` - Client to open the session from
+    /// * `credentials` - Store the emergency credentials are unsealed from
+    /// * `incident` - Incident the access is for (e.g., "INC-4821")
+    /// * `reason` - Why the emergency access is needed
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<BreakGlass, KeyrunesError>`:
+    /// - `Ok(session)` if the emergency account is logged in
+    /// - `Err(KeyrunesError::InvalidAuditAnnotation)` if the incident or the reason is empty or not a valid header value
+    /// - `Err(KeyrunesError::AuthenticationError)` if the emergency credentials are rejected
+    pub async fn open(
+        client: &KeyrunesClient,
+        credentials: &dyn SealedCredentials,
+        incident: &str,
+        reason: &str,
+    ) -> Result<Self> {
+        let annotation = Annotation::new(incident, reason)?;
+        let credentials = credentials.unseal().await?;
+
+        #[cfg(feature = "tracing")]
+        tracing::error!(
+            target: "keyrunes::break_glass",
+            incident,
+            reason,
+            identity = %credentials.identity,
+            "break-glass access opened"
+        );
+
+        let mut view = client.detached(None);
+        view.break_glass = Some(Arc::new(annotation));
+        let login = view
+            .login(
+                credentials.identity,
+                credentials.password,
+                credentials.namespace,
+            )
+            .await;
+        #[cfg(feature = "tracing")]
+        if let Err(err) = &login {
+            tracing::error!(
+                target: "keyrunes::break_glass",
+                incident,
+                error = %err,
+                "break-glass login failed"
+            );
+        }
+        login?;
+
+        Ok(Self {
+            client: view,
+            opened_at: Utc::now(),
+        })
+    }
+
+    /// Client authenticated with the emergency account; every request it
+    /// sends is annotated.
+    pub fn client(&self) -> &KeyrunesClient {
+        &self.client
+    }
+
+    /// Incident the session was opened for.
+    pub fn incident(&self) -> &str {
+        self.annotation().incident()
+    }
+
+    /// When the session was opened.
+    pub fn opened_at(&self) -> DateTime<Utc> {
+        self.opened_at
+    }
+
+    /// Ends the session, revoking the emergency token (see
+    /// [`KeyrunesClient::logout`]).
+    ///
+    /// Returns whether the server acknowledged the revocation.
+    pub async fn close(self) -> Result<bool> {
+        let result = self.client.logout().await;
+
+        #[cfg(feature = "tracing")]
+        tracing::error!(
+            target: "keyrunes::break_glass",
+            incident = self.incident(),
+            duration_secs = (Utc::now() - self.opened_at).num_seconds(),
+            revoked = matches!(result, Ok(true)),
+            "break-glass access closed"
+        );
+
+        result
+    }
+
+    fn annotation(&self) -> &Annotation {
+        self.client
+            .break_glass
+            .as_deref()
+            .expect("break-glass clients are annotated")
+    }
+}
+
+impl std::fmt::Debug for BreakGlass {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BreakGlass")
+            .field("incident", &self.incident())
+            .field("opened_at", &self.opened_at)
+            .finish()
+    }
+}
@@ -0,0 +1,359 @@
+//! JWT claims carried by Keyrunes tokens
+//!
+//! This module contains the [`Claims`] type, a view over the payload of a
+//! Keyrunes access token, and the [`AuthLevel`] derived from it. Claims are
+//! decoded without verifying the signature: the token is always validated
+//! by the Keyrunes server on use, and the claims are only used to decide
+//! whether a request is worth forwarding at all (e.g., step-up checks).
+//!
+//! Tenants name the claims carrying the user ID, email, groups and roles
+//! differently; a [`ClaimsMapping`] tells the SDK where to find them.
+//!
+//! ## Quick Start
+//!
+//! ```
+//! use keyrunes_rust_sdk::claims::{AuthLevel, Claims};
+//!
+//! # fn example(token: &str) -> Result<(), Box<dyn std::error::Error>> {
+//! let claims = Claims::from_token(token)?;
+//! if claims.auth_level() < AuthLevel::Mfa {
+//!     println!("Step-up authentication required");
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::error::{KeyrunesError, Result};
+use serde::{Deserialize, Deserializer, Serialize};
+
+/// Authentication methods (`amr` values) that count as a second factor
+const MFA_METHODS: &[&str] = &[
+    "mfa", "otp", "totp", "sms", "hwk", "swk", "webauthn", "push",
+];
+
+/// Environment variables overriding the default [`ClaimsMapping`]
+const ENV_USER_ID_CLAIM: &str = "KEYRUNES_USER_ID_CLAIM";
+const ENV_EMAIL_CLAIM: &str = "KEYRUNES_EMAIL_CLAIM";
+const ENV_GROUPS_CLAIM: &str = "KEYRUNES_GROUPS_CLAIM";
+const ENV_ROLES_CLAIM: &str = "KEYRUNES_ROLES_CLAIM";
+
+/// Names of the claims carrying user attributes
+///
+/// Nested claims are addressed with dots (e.g., `realm_access.roles`).
+///
+/// ```
+/// use keyrunes_rust_sdk::claims::ClaimsMapping;
+///
+/// let mapping = ClaimsMapping::default()
+///     .groups_claim("https://example.com/groups")
+///     .roles_claim("realm_access.roles");
+/// assert_eq!(mapping.user_id_claim, "sub");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ClaimsMapping {
+    /// Claim holding the user ID (default `sub`)
+    pub user_id_claim: String,
+    /// Claim holding the email (default `email`)
+    pub email_claim: String,
+    /// Claim holding the groups (default `groups`)
+    pub groups_claim: String,
+    /// Claim holding the roles (default `roles`)
+    pub roles_claim: String,
+}
+
+impl Default for ClaimsMapping {
+    fn default() -> Self {
+        Self {
+            user_id_claim: "sub".to_string(),
+            email_claim: "email".to_string(),
+            groups_claim: "groups".to_string(),
+            roles_claim: "roles".to_string(),
+        }
+    }
+}
+
+impl ClaimsMapping {
+    /// Creates the default mapping, overridden by the `KEYRUNES_USER_ID_CLAIM`,
+    /// `KEYRUNES_EMAIL_CLAIM`, `KEYRUNES_GROUPS_CLAIM` and
+    /// `KEYRUNES_ROLES_CLAIM` environment variables when set.
+    pub fn from_env() -> Self {
+        let mut mapping = Self::default();
+        for (var, claim) in [
+            (ENV_USER_ID_CLAIM, &mut mapping.user_id_claim),
+            (ENV_EMAIL_CLAIM, &mut mapping.email_claim),
+            (ENV_GROUPS_CLAIM, &mut mapping.groups_claim),
+            (ENV_ROLES_CLAIM, &mut mapping.roles_claim),
+        ] {
+            if let Ok(value) = std::env::var(var) {
+                *claim = value;
+            }
+        }
+        mapping
+    }
+
+    /// Sets the claim holding the user ID.
+    pub fn user_id_claim<S: Into<String>>(mut self, claim: S) -> Self {
+        self.user_id_claim = claim.into();
+        self
+    }
+
+    /// Sets the claim holding the email.
+    pub fn email_claim<S: Into<String>>(mut self, claim: S) -> Self {
+        self.email_claim = claim.into();
+        self
+    }
+
+    /// Sets the claim holding the groups.
+    pub fn groups_claim<S: Into<String>>(mut self, claim: S) -> Self {
+        self.groups_claim = claim.into();
+        self
+    }
+
+    /// Sets the claim holding the roles.
+    pub fn roles_claim<S: Into<String>>(mut self, claim: S) -> Self {
+        self.roles_claim = claim.into();
+        self
+    }
+}
+
+/// Looks up a (possibly dotted) claim in a token payload
+pub(crate) fn lookup<'a>(
+    payload: &'a serde_json::Value,
+    claim: &str,
+) -> Option<&'a serde_json::Value> {
+    // Claims whose name contains dots (e.g., URLs) take precedence over nesting
+    if let Some(value) = payload.get(claim) {
+        return Some(value);
+    }
+    claim
+        .split('.')
+        .try_fold(payload, |value, segment| value.get(segment))
+}
+
+/// Reads a claim holding a string
+fn string_claim(payload: &serde_json::Value, claim: &str) -> Option<String> {
+    match lookup(payload, claim)? {
+        serde_json::Value::String(value) => Some(value.clone()),
+        serde_json::Value::Number(value) => Some(value.to_string()),
+        _ => None,
+    }
+}
+
+/// Reads a claim holding a list of strings (a single string is a list of one)
+fn list_claim(payload: &serde_json::Value, claim: &str) -> Vec<String> {
+    match lookup(payload, claim) {
+        Some(serde_json::Value::Array(values)) => values
+            .iter()
+            .filter_map(|v| v.as_str().map(str::to_string))
+            .collect(),
+        Some(serde_json::Value::String(value)) => vec![value.clone()],
+        _ => Vec::new(),
+    }
+}
+
+/// Deserializes a claim holding a string or a number (e.g., a numeric `sub`)
+fn string_or_number<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> std::result::Result<Option<String>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum StringOrNumber {
+        String(String),
+        Number(serde_json::Number),
+    }
+
+    Ok(
+        Option::<StringOrNumber>::deserialize(deserializer)?.map(|value| match value {
+            StringOrNumber::String(value) => value,
+            StringOrNumber::Number(value) => value.to_string(),
+        }),
+    )
+}
+
+/// Decodes the payload of a JWT without verifying its signature
+pub(crate) fn decode_payload(token: &str) -> Result<serde_json::Value> {
+    let mut validation = jsonwebtoken::Validation::default();
+    validation.insecure_disable_signature_validation();
+    validation.validate_exp = false;
+    validation.validate_aud = false;
+    validation.required_spec_claims.clear();
+
+    jsonwebtoken::decode::<serde_json::Value>(
+        token,
+        &jsonwebtoken::DecodingKey::from_secret(&[]),
+        &validation,
+    )
+    .map(|data| data.claims)
+    .map_err(|_| KeyrunesError::InvalidToken)
+}
+
+/// Claims of a Keyrunes access token
+///
+/// Only the claims used by the SDK are modeled; unknown claims are ignored.
+/// The user attributes (`user_id`, `email`, `groups`, `roles`) are read
+/// from the claims named by the [`ClaimsMapping`], never deserialized
+/// directly.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Claims {
+    /// Subject (user ID); numeric subjects are converted to strings
+    #[serde(
+        default,
+        deserialize_with = "string_or_number",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub sub: Option<String>,
+    /// Issued-at time (seconds since the Unix epoch)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub iat: Option<i64>,
+    /// Expiration time (seconds since the Unix epoch)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exp: Option<i64>,
+    /// Time of the authentication event (seconds since the Unix epoch)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auth_time: Option<i64>,
+    /// Authentication context class reference
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub acr: Option<String>,
+    /// Authentication methods references
+    #[serde(
+        default,
+        deserialize_with = "crate::id_token::one_or_many",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub amr: Vec<String>,
+    /// User ID
+    #[serde(skip_deserializing, skip_serializing_if = "Option::is_none")]
+    pub user_id: Option<String>,
+    /// User email
+    #[serde(skip_deserializing, skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+    /// Groups the user belongs to
+    #[serde(skip_deserializing, skip_serializing_if = "Vec::is_empty")]
+    pub groups: Vec<String>,
+    /// Roles granted to the user
+    #[serde(skip_deserializing, skip_serializing_if = "Vec::is_empty")]
+    pub roles: Vec<String>,
+}
+
+impl Claims {
+    /// Decodes the claims of a JWT without verifying its signature.
+    ///
+    /// User attributes are read with the default [`ClaimsMapping`].
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<Claims, KeyrunesError>`:
+    /// - `Ok(claims)` if the token is a well-formed JWT
+    /// - `Err(KeyrunesError::InvalidToken)` if the token cannot be decoded
+    pub fn from_token(token: &str) -> Result<Self> {
+        Self::from_token_with(token, &ClaimsMapping::default())
+    }
+
+    /// Decodes the claims of a JWT without verifying its signature, reading
+    /// user attributes from the claims named by `mapping`.
+    pub fn from_token_with(token: &str, mapping: &ClaimsMapping) -> Result<Self> {
+        Self::from_payload(&decode_payload(token)?, mapping)
+    }
+
+    /// Builds the claims from a decoded token payload.
+    pub fn from_payload(payload: &serde_json::Value, mapping: &ClaimsMapping) -> Result<Self> {
+        let mut claims = Claims::deserialize(payload).map_err(|_| KeyrunesError::InvalidToken)?;
+        claims.user_id = string_claim(payload, &mapping.user_id_claim);
+        claims.email = string_claim(payload, &mapping.email_claim);
+        claims.groups = list_claim(payload, &mapping.groups_claim);
+        claims.roles = list_claim(payload, &mapping.roles_claim);
+        Ok(claims)
+    }
+
+    /// Returns when the user last authenticated, as a Unix timestamp.
+    ///
+    /// Uses the `auth_time` claim, falling back to `iat` for tokens that
+    /// do not carry it.
+    pub fn authenticated_at(&self) -> Option<i64> {
+        self.auth_time.or(self.iat)
+    }
+
+    /// Checks whether the user authenticated within `max_age`.
+    ///
+    /// Tokens without `auth_time` or `iat` are never considered fresh.
+    pub fn is_fresh(&self, max_age: std::time::Duration) -> bool {
+        let max_age = i64::try_from(max_age.as_secs()).unwrap_or(i64::MAX);
+        self.authenticated_at()
+            .map(|at| chrono::Utc::now().timestamp().saturating_sub(at) <= max_age)
+            .unwrap_or(false)
+    }
+
+    /// Returns the authentication level proven by the token.
+    ///
+    /// The level is [`AuthLevel::Mfa`] when the `acr` claim mentions MFA or
+    /// when any of the `amr` methods is a second factor, and
+    /// [`AuthLevel::Password`] otherwise.
+    pub fn auth_level(&self) -> AuthLevel {
+        let acr_mfa = self
+            .acr
+            .as_deref()
+            .map(|acr| acr.to_ascii_lowercase().contains("mfa"))
+            .unwrap_or(false);
+        let amr_mfa = self
+            .amr
+            .iter()
+            .any(|method| MFA_METHODS.contains(&method.to_ascii_lowercase().as_str()));
+
+        if acr_mfa || amr_mfa {
+            AuthLevel::Mfa
+        } else {
+            AuthLevel::Password
+        }
+    }
+}
+
+/// Authentication level of a session
+///
+/// Levels are ordered, so a route requiring [`AuthLevel::Password`] also
+/// accepts [`AuthLevel::Mfa`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AuthLevel {
+    /// Authenticated with a password only
+    Password,
+    /// Authenticated with a second factor
+    Mfa,
+}
+
+impl std::fmt::Display for AuthLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AuthLevel::Password => write!(f, "password"),
+            AuthLevel::Mfa => write!(f, "mfa"),
+        }
+    }
+}
+
+/// Type-level authentication level used by the `RequireAuthLevel` extractors
+///
+/// ```
+/// use keyrunes_rust_sdk::claims::{AuthLevel, Mfa, RequiredLevel};
+///
+/// assert_eq!(Mfa::LEVEL, AuthLevel::Mfa);
+/// ```
+pub trait RequiredLevel: Send + Sync + 'static {
+    /// The minimum level required
+    const LEVEL: AuthLevel;
+}
+
+/// Marker requiring at least [`AuthLevel::Password`]
+#[derive(Debug, Clone, Copy)]
+pub struct Password;
+
+impl RequiredLevel for Password {
+    const LEVEL: AuthLevel = AuthLevel::Password;
+}
+
+/// Marker requiring [`AuthLevel::Mfa`]
+#[derive(Debug, Clone, Copy)]
+pub struct Mfa;
+
+impl RequiredLevel for Mfa {
+    const LEVEL: AuthLevel = AuthLevel::Mfa;
+}
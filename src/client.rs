@@ -16,20 +16,63 @@
 //! # }
 //! ```
 
+use crate::admin::AdminClient;
+use crate::claims::{Claims, ClaimsMapping};
+use crate::endpoints::{self, EndpointResolver};
+use crate::entitlements::EntitlementCache;
 use crate::error::{KeyrunesError, Result};
 use crate::models::*;
 use reqwest::Client;
+use shutdown::Background;
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use token_provider::Refreshes;
+use tokio::sync::{watch, RwLock};
+use transport::Auth;
+
+mod accounts;
+mod activity;
+mod audit;
+mod builder;
+mod delegations;
+mod devices;
+mod entitlements;
+mod exports;
+pub mod generated;
+mod imports;
+mod jobs;
+mod jwks;
+mod memberships;
+mod mfa;
+mod network;
+mod passwordless;
+mod permissions;
+mod quotas;
+mod raw;
+mod relations;
+pub(crate) mod shutdown;
+pub(crate) mod strict;
+mod token_provider;
+mod token_store;
+pub mod transport;
+
+pub use builder::KeyrunesClientBuilder;
+#[cfg(feature = "hickory-dns")]
+pub use network::HickoryResolver;
+pub use network::IpPreference;
+pub use raw::{RawClient, RawRequest, RawResponse};
+pub use token_provider::TokenProvider;
+pub use token_store::{MemoryTokenStore, StoredTokens, TokenStore};
 
 // Constants
-const USER_AGENT: &str = "keyrunes-rust-sdk/0.1.0";
 const HEADER_ORG_KEY: &str = "X-Organization-Key";
 const ENV_ORG_KEY: &str = "KEYRUNES_ORG_KEY";
+/// Header scoping requests to an organization (see [`KeyrunesClient::for_org`])
+const HEADER_ORG: &str = "X-Keyrunes-Org";
+/// Header pinning the API version, echoed by the server
+const HEADER_API_VERSION: &str = "X-Keyrunes-Api-Version";
 
-const ENDPOINT_LOGIN: &str = "/api/login";
-const ENDPOINT_REGISTER: &str = "/api/register";
-const ENDPOINT_ME: &str = "/api/me";
+/// Error code of a refresh rejected because the refresh token was already used
+const CODE_REFRESH_TOKEN_REUSED: &str = "refresh_token_reused";
 
 /// Client for interacting with the Keyrunes API
 ///
@@ -62,8 +105,64 @@ const ENDPOINT_ME: &str = "/api/me";
 #[derive(Clone)]
 pub struct KeyrunesClient {
     pub(crate) base_url: String,
+    endpoints: Arc<EndpointResolver>,
+    api_version: Option<String>,
+    org_id: Option<String>,
     client: Client,
     pub(crate) token: Arc<RwLock<Option<String>>>,
+    token_updates: Arc<watch::Sender<Option<TokenInfo>>>,
+    session: Arc<RwLock<SessionState>>,
+    entitlements: Arc<EntitlementCache>,
+    claims_mapping: Arc<ClaimsMapping>,
+    pub(crate) background: Arc<Background>,
+    refreshes: Arc<Refreshes>,
+    pub(crate) keys: Arc<jwks::KeyCache>,
+    token_store: Arc<dyn TokenStore>,
+    last_response: Arc<std::sync::Mutex<Option<ResponseMeta>>>,
+    auto_refresh: bool,
+    strict: bool,
+    /// Last known memberships, when the fallback is enabled
+    memberships: Option<Arc<memberships::MembershipCache>>,
+    /// Annotations of break-glass access, sent with every request
+    pub(crate) break_glass: Option<Arc<crate::break_glass::Annotation>>,
+    #[cfg(feature = "dpop")]
+    dpop: Option<Arc<crate::dpop::Dpop>>,
+}
+
+/// Session data kept alongside the token, for [`KeyrunesClient::export_session`]
+#[derive(Debug, Default)]
+pub(crate) struct SessionState {
+    refresh_token: Option<String>,
+    namespace: Option<String>,
+    user: Option<User>,
+    /// Validators of the response `user` was read from
+    user_validators: Option<Validators>,
+    /// Token the current one was refreshed from
+    refreshed_from: Option<String>,
+}
+
+/// Cache validators of a response (`ETag`, `Last-Modified`)
+#[derive(Debug, Clone, Default)]
+struct Validators {
+    etag: Option<String>,
+    last_modified: Option<String>,
+}
+
+impl Validators {
+    fn from_response(response: &reqwest::Response) -> Option<Self> {
+        let header = |name| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string)
+        };
+        let validators = Self {
+            etag: header(reqwest::header::ETAG),
+            last_modified: header(reqwest::header::LAST_MODIFIED),
+        };
+        (validators.etag.is_some() || validators.last_modified.is_some()).then_some(validators)
+    }
 }
 
 impl KeyrunesClient {
@@ -98,24 +197,23 @@ impl KeyrunesClient {
     ///     .expect("Invalid URL");
     /// ```
     pub fn new<S: Into<String>>(base_url: S) -> Result<Self> {
-        let base_url = base_url.into();
-        url::Url::parse(&base_url)?;
-
-        let mut headers = reqwest::header::HeaderMap::new();
-        if let Ok(org_key) = std::env::var(ENV_ORG_KEY) {
-            if let Ok(value) = reqwest::header::HeaderValue::from_str(&org_key) {
-                headers.insert(HEADER_ORG_KEY, value);
-            }
-        }
+        KeyrunesClientBuilder::new(base_url).build()
+    }
 
-        Ok(Self {
-            base_url: base_url.trim_end_matches('/').to_string(),
-            client: Client::builder()
-                .user_agent(USER_AGENT)
-                .default_headers(headers)
-                .build()?,
-            token: Arc::new(RwLock::new(None)),
-        })
+    /// Creates a builder to configure the client before creating it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use keyrunes_rust_sdk::KeyrunesClient;
+    ///
+    /// let client = KeyrunesClient::builder("https://keyrunes.example.com")
+    ///     .app_info("billing-service", "2.3.1")
+    ///     .build()
+    ///     .expect("Invalid URL");
+    /// ```
+    pub fn builder<S: Into<String>>(base_url: S) -> KeyrunesClientBuilder {
+        KeyrunesClientBuilder::new(base_url)
     }
 
     /// Performs login and returns the authentication token.
@@ -131,6 +229,7 @@ impl KeyrunesClient {
     /// Returns `Result<Token, KeyrunesError>`:
     /// - `Ok(token)` if login was successful
     /// - `Err(KeyrunesError::AuthenticationError)` if credentials are invalid
+    /// - `Err(KeyrunesError::ChallengeRequired)` if the server demands extra verification
     /// - `Err(KeyrunesError::NetworkError)` if there was a network error
     ///
     /// # Examples
@@ -150,7 +249,6 @@ impl KeyrunesClient {
         password: S,
         namespace: Option<S>,
     ) -> Result<Token> {
-        let url = format!("{}{}", self.base_url, ENDPOINT_LOGIN);
         let credentials = LoginCredentials {
             identity: username.into(),
             password: password.into(),
@@ -159,14 +257,120 @@ impl KeyrunesClient {
                 .unwrap_or_else(|| DEFAULT_NAMESPACE.to_string()),
         };
 
-        let response = self.client.post(&url).json(&credentials).send().await?;
+        match self.send_login(&credentials, None).await? {
+            LoginOutcome::Authenticated(token) => Ok(token),
+            LoginOutcome::ChallengeRequired(challenge) => {
+                Err(KeyrunesError::ChallengeRequired(challenge.challenge_id))
+            }
+        }
+    }
+
+    /// Performs login forwarding client context for risk scoring.
+    ///
+    /// The context (IP address, user agent, device fingerprint) of the end
+    /// user is forwarded to Keyrunes, which may demand extra verification
+    /// for risky logins instead of issuing a token right away.
+    ///
+    /// # Arguments
+    ///
+    /// * `username` - Username or email
+    /// * `password` - User password
+    /// * `namespace` - Optional namespace (defaults to "public")
+    /// * `context` - Context of the end user's client
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<LoginOutcome, KeyrunesError>`:
+    /// - `Ok(LoginOutcome::Authenticated(token))` if login was successful
+    /// - `Ok(LoginOutcome::ChallengeRequired(challenge))` if extra verification is needed
+    /// - `Err(KeyrunesError::AuthenticationError)` if credentials are invalid
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyrunes_rust_sdk::{ClientContext, KeyrunesClient, LoginOutcome, StepUpChallenge};
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = KeyrunesClient::new("https://keyrunes.example.com")?;
+    /// let context = ClientContext::new()
+    ///     .with_ip("203.0.113.7")
+    ///     .with_user_agent("Mozilla/5.0");
+    /// match client.login_with_context("user@example.com", "password", None, &context).await? {
+    ///     LoginOutcome::Authenticated(token) => println!("Token: {}", token.token),
+    ///     LoginOutcome::ChallengeRequired(challenge) => {
+    ///         let code = "123456"; // asked to the user
+    ///         let answer = StepUpChallenge::new("totp", code).with_challenge_id(challenge.challenge_id);
+    ///         client.complete_login_challenge(answer).await?;
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn login_with_context<S: Into<String>>(
+        &self,
+        username: S,
+        password: S,
+        namespace: Option<S>,
+        context: &ClientContext,
+    ) -> Result<LoginOutcome> {
+        let credentials = LoginCredentials {
+            identity: username.into(),
+            password: password.into(),
+            namespace: namespace
+                .map(|n| n.into())
+                .unwrap_or_else(|| DEFAULT_NAMESPACE.to_string()),
+        };
+
+        self.send_login(&credentials, Some(context)).await
+    }
+
+    /// Completes a login challenge returned by [`login_with_context`](Self::login_with_context).
+    ///
+    /// # Arguments
+    ///
+    /// * `challenge` - The challenge response, including the challenge ID
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<Token, KeyrunesError>`:
+    /// - `Ok(token)` if the challenge was accepted
+    /// - `Err(KeyrunesError::AuthenticationError)` if the code is invalid or the challenge expired
+    pub async fn complete_login_challenge(&self, challenge: StepUpChallenge) -> Result<Token> {
+        let url = self.url(endpoints::LOGIN_CHALLENGE);
+        let response = self
+            .send(self.client.post(&url).json(&challenge), Auth::None)
+            .await?;
 
         let token = self.handle_response::<Token>(response).await?;
-        let token_value = token.token.clone();
-        *self.token.write().await = Some(token_value);
+        self.store_issued_token(&token).await?;
         Ok(token)
     }
 
+    async fn send_login(
+        &self,
+        credentials: &LoginCredentials,
+        context: Option<&ClientContext>,
+    ) -> Result<LoginOutcome> {
+        let url = self.url(endpoints::LOGIN);
+        let request = LoginRequest {
+            credentials,
+            context,
+        };
+
+        let response = self
+            .send(self.client.post(&url).json(&request), Auth::None)
+            .await?;
+
+        let outcome = match self.handle_response::<LoginResponse>(response).await? {
+            LoginResponse::Token(token) => LoginOutcome::Authenticated(token),
+            LoginResponse::Challenge(challenge) => LoginOutcome::ChallengeRequired(challenge),
+        };
+        if let LoginOutcome::Authenticated(token) = &outcome {
+            self.store_issued_token(token).await?;
+            self.session.write().await.namespace = Some(credentials.namespace.clone());
+        }
+        Ok(outcome)
+    }
+
     /// Registers a new user.
     ///
     /// # Arguments
@@ -201,7 +405,57 @@ impl KeyrunesClient {
         password: S,
         namespace: Option<S>,
     ) -> Result<User> {
-        let url = format!("{}{}", self.base_url, ENDPOINT_REGISTER);
+        let registration = self
+            .register_with_outcome(username, email, password, namespace)
+            .await?;
+        Ok(registration.user)
+    }
+
+    /// Registers a new user, returning the token and forced-rotation flag
+    /// sent with it.
+    ///
+    /// The client is not signed in as the new user. When the server demands
+    /// a password change at the first use of the account, change it on a
+    /// view carrying the registration token (see [`Registration`]).
+    ///
+    /// # Arguments
+    ///
+    /// * `username` - Username
+    /// * `email` - User email
+    /// * `password` - User password (minimum 8 characters)
+    /// * `namespace` - Optional namespace (defaults to "public")
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<Registration, KeyrunesError>`:
+    /// - `Ok(registration)` if registration was successful
+    /// - `Err(KeyrunesError::AuthenticationError)` if email is already in use
+    /// - `Err(KeyrunesError::HttpError)` if there was an error in the request
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyrunes_rust_sdk::KeyrunesClient;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = KeyrunesClient::new("https://keyrunes.example.com")?;
+    /// let registration = client
+    ///     .register_with_outcome("john_doe", "john@example.com", "password123", None)
+    ///     .await?;
+    ///
+    /// if let (true, Some(token)) = (registration.requires_password_change, &registration.token) {
+    ///     client.as_user(token).change_password("password123", "correct-horse-battery").await?;
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn register_with_outcome<S: Into<String>>(
+        &self,
+        username: S,
+        email: S,
+        password: S,
+        namespace: Option<S>,
+    ) -> Result<Registration> {
+        let url = self.url(endpoints::REGISTER);
         let registration = UserRegistration {
             username: username.into(),
             email: email.into(),
@@ -211,15 +465,21 @@ impl KeyrunesClient {
                 .unwrap_or_else(|| DEFAULT_NAMESPACE.to_string()),
         };
 
-        let response = self.client.post(&url).json(&registration).send().await?;
+        let response = self
+            .send(self.client.post(&url).json(&registration), Auth::None)
+            .await?;
 
         let register_response: crate::models::RegisterResponse =
             self.handle_response(response).await?;
-        Ok(crate::models::User::from(register_response.user))
+        Ok(Registration::from(register_response))
     }
 
     /// Sets the authentication token manually.
     ///
+    /// The token is used as is: it replaces any refresh token the client
+    /// held, so it is neither refreshed before it expires nor retried on
+    /// rejection.
+    ///
     /// # Arguments
     ///
     /// * `token` - JWT authentication token
@@ -235,7 +495,72 @@ impl KeyrunesClient {
     /// # }
     /// ```
     pub async fn set_token<S: Into<String>>(&self, token: S) {
-        *self.token.write().await = Some(token.into());
+        self.store_token(Some(token.into())).await;
+    }
+
+    /// Returns the token held by the client, with its expiry and subject.
+    ///
+    /// Lets applications display when the session expires or decide when
+    /// to ask the user to log in again. The claims are decoded with the
+    /// client's [`ClaimsMapping`], without verifying the signature.
+    ///
+    /// # Returns
+    ///
+    /// - `Some(info)` if a token is set
+    /// - `None` if no token is set
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyrunes_rust_sdk::KeyrunesClient;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = KeyrunesClient::new("https://keyrunes.example.com")?;
+    /// client.login("user@example.com", "password123", None).await?;
+    /// if let Some(info) = client.current_token().await {
+    ///     println!("Signed in as {:?} until {:?}", info.subject, info.expires_at);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn current_token(&self) -> Option<TokenInfo> {
+        let token = self.token.read().await.clone()?;
+        Some(self.token_info(token))
+    }
+
+    /// Returns the time left until the client's token expires.
+    ///
+    /// Zero once the token has expired; `None` if no token is set or its
+    /// expiry is unknown (see [`KeyrunesClient::current_token`]).
+    pub async fn token_expires_in(&self) -> Option<std::time::Duration> {
+        self.current_token().await?.expires_in()
+    }
+
+    /// Subscribes to changes of the client's token.
+    ///
+    /// The receiver is notified whenever the token changes: on login,
+    /// refresh, [`KeyrunesClient::set_token`] and
+    /// [`KeyrunesClient::clear_token`] (`None`). Useful for components that
+    /// hold their own connection, such as websocket connectors, to
+    /// reconnect with the new credentials.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyrunes_rust_sdk::KeyrunesClient;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = KeyrunesClient::new("https://keyrunes.example.com")?;
+    /// let mut updates = client.token_updates();
+    /// tokio::spawn(async move {
+    ///     while updates.changed().await.is_ok() {
+    ///         let signed_in = updates.borrow_and_update().is_some();
+    ///         println!("Signed in: {}", signed_in);
+    ///     }
+    /// });
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn token_updates(&self) -> watch::Receiver<Option<TokenInfo>> {
+        self.token_updates.subscribe()
     }
 
     /// Gets the current authenticated user.
@@ -260,21 +585,113 @@ impl KeyrunesClient {
     /// # }
     /// ```
     pub async fn get_current_user(&self) -> Result<User> {
-        let token = self.token.read().await;
-        let token_value = token.as_ref().ok_or(KeyrunesError::InvalidToken)?;
+        let url = self.url(endpoints::ME);
+        let response = self.send(self.client.get(&url), Auth::Required).await?;
 
-        let url = format!("{}{}", self.base_url, ENDPOINT_ME);
-        let response = self
-            .client
-            .get(&url)
-            .header("Authorization", format!("Bearer {}", token_value))
-            .send()
-            .await?;
+        self.read_current_user(response).await
+    }
+
+    /// Gets the current user if it changed since it was last fetched.
+    ///
+    /// Sends the `ETag` and `Last-Modified` validators of the previous
+    /// response (`If-None-Match`, `If-Modified-Since`), so the server
+    /// answers 304 without a body when the profile is unchanged. Meant for
+    /// pollers syncing profile data periodically. The first call, and the
+    /// first after the token changes, always returns the user.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<Option<User>, KeyrunesError>`:
+    /// - `Ok(Some(user))` if the user changed (or was not fetched yet)
+    /// - `Ok(None)` if the user is unchanged
+    /// - `Err(KeyrunesError::AuthenticationError)` if not authenticated or token is invalid
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyrunes_rust_sdk::KeyrunesClient;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = KeyrunesClient::new("https://keyrunes.example.com")?;
+    /// client.login("user@example.com", "password123", None).await?;
+    /// loop {
+    ///     if let Some(user) = client.get_current_user_if_modified().await? {
+    ///         println!("Profile changed: {}", user.email);
+    ///     }
+    ///     tokio::time::sleep(std::time::Duration::from_secs(60)).await;
+    /// }
+    /// # }
+    /// ```
+    pub async fn get_current_user_if_modified(&self) -> Result<Option<User>> {
+        let url = self.url(endpoints::ME);
+        let mut request = self.client.get(&url);
+        let session = self.session.read().await;
+        if let (Some(_), Some(validators)) = (&session.user, &session.user_validators) {
+            if let Some(etag) = &validators.etag {
+                request = request.header(reqwest::header::IF_NONE_MATCH, etag);
+            }
+            if let Some(last_modified) = &validators.last_modified {
+                request = request.header(reqwest::header::IF_MODIFIED_SINCE, last_modified);
+            }
+        }
+        drop(session);
+        let response = self.send(request, Auth::Required).await?;
+
+        if response.status() == reqwest::StatusCode::NOT_MODIFIED {
+            return Ok(None);
+        }
+        self.read_current_user(response).await.map(Some)
+    }
 
+    /// Reads the current user from a response of the `me` endpoint, keeping
+    /// it (with its validators) in the session.
+    async fn read_current_user(&self, response: reqwest::Response) -> Result<User> {
+        let validators = Validators::from_response(&response);
         let user_response = self
             .handle_response::<crate::models::UserResponse>(response)
             .await?;
-        Ok(crate::models::User::from(user_response))
+        let user = crate::models::User::from(user_response);
+        let mut session = self.session.write().await;
+        session.user = Some(user.clone());
+        session.user_validators = validators;
+        Ok(user)
+    }
+
+    /// Gets the current user from the OIDC userinfo endpoint.
+    ///
+    /// An alternative to [`KeyrunesClient::get_current_user`] returning the
+    /// standard OIDC claims, for applications that also work with other
+    /// OIDC providers. The claims are merged into a [`User`]: the user ID,
+    /// email and groups are read from the claims named by the client's
+    /// [`ClaimsMapping`], and the username from `preferred_username`
+    /// (falling back to the email, then the subject).
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<UserInfo, KeyrunesError>`:
+    /// - `Ok(info)` with the user and the claims
+    /// - `Err(KeyrunesError::InvalidToken)` if not authenticated
+    /// - `Err(KeyrunesError::NetworkError)` if there was a network error
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyrunes_rust_sdk::KeyrunesClient;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = KeyrunesClient::new("https://keyrunes.example.com")?;
+    /// client.login("user@example.com", "password123", None).await?;
+    /// let info = client.userinfo().await?;
+    /// println!("{} ({:?})", info.user.username, info.claims.name);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn userinfo(&self) -> Result<UserInfo> {
+        let url = self.url(endpoints::USERINFO);
+        let response = self.send(self.client.get(&url), Auth::Required).await?;
+
+        let payload: serde_json::Value = self.handle_response(response).await?;
+        let info = UserInfo::from_payload(payload, &self.claims_mapping)?;
+        self.session.write().await.user = Some(info.user.clone());
+        Ok(info)
     }
 
     /// Registers a new administrator user.
@@ -313,7 +730,7 @@ impl KeyrunesClient {
         admin_key: S,
         namespace: Option<S>,
     ) -> Result<User> {
-        let url = format!("{}{}", self.base_url, ENDPOINT_REGISTER);
+        let url = self.url(endpoints::REGISTER);
         let registration = AdminRegistration {
             username: username.into(),
             email: email.into(),
@@ -324,7 +741,9 @@ impl KeyrunesClient {
                 .unwrap_or_else(|| DEFAULT_NAMESPACE.to_string()),
         };
 
-        let response = self.client.post(&url).json(&registration).send().await?;
+        let response = self
+            .send(self.client.post(&url).json(&registration), Auth::None)
+            .await?;
 
         let register_response: crate::models::RegisterResponse =
             self.handle_response(response).await?;
@@ -357,22 +776,7 @@ impl KeyrunesClient {
     /// # }
     /// ```
     pub async fn get_user<S: Into<String>>(&self, user_id: S) -> Result<User> {
-        let token = self.token.read().await;
-        let token_value = token.as_ref().ok_or(KeyrunesError::InvalidToken)?;
-
-        let user_id = user_id.into();
-        let url = format!("{}/api/users/{}", self.base_url, user_id);
-        let response = self
-            .client
-            .get(&url)
-            .header("Authorization", format!("Bearer {}", token_value))
-            .send()
-            .await?;
-
-        let user_response = self
-            .handle_response::<crate::models::UserResponse>(response)
-            .await?;
-        Ok(crate::models::User::from(user_response))
+        self.generated().get_user(&user_id.into()).await
     }
 
     /// Verifies if a user belongs to a specific group.
@@ -390,6 +794,10 @@ impl KeyrunesClient {
     /// - `Err(KeyrunesError::GroupNotFoundError)` if group doesn't exist
     /// - `Err(KeyrunesError::AuthenticationError)` if not authenticated
     ///
+    /// With [`KeyrunesClientBuilder::membership_fallback`], network failures
+    /// and server errors are answered with the last known result of the
+    /// check, if recent enough.
+    ///
     /// # Examples
     ///
     /// ```
@@ -409,24 +817,34 @@ impl KeyrunesClient {
         user_id: U,
         group_id: G,
     ) -> Result<bool> {
-        let token = self.token.read().await;
-        let token_value = token.as_ref().ok_or(KeyrunesError::InvalidToken)?;
-
         let user_id = user_id.into();
         let group_id = group_id.into();
-        let url = format!(
-            "{}/api/users/{}/groups/{}",
-            self.base_url, user_id, group_id
-        );
-        let response = self
-            .client
-            .get(&url)
-            .header("Authorization", format!("Bearer {}", token_value))
-            .send()
-            .await?;
+        let url = self.url(&endpoints::user_group(&user_id, &group_id));
+        let Some(cache) = &self.memberships else {
+            let response = self.send(self.client.get(&url), Auth::Required).await?;
+            return Ok(self
+                .handle_response::<GroupCheck>(response)
+                .await?
+                .has_group);
+        };
 
-        let group_check = self.handle_response::<GroupCheck>(response).await?;
-        Ok(group_check.has_group)
+        // Only outages fall back; answers of Keyrunes (4xx included) stand.
+        let err = match self.send(self.client.get(&url), Auth::Required).await {
+            Ok(response) => {
+                let outage = response.status().is_server_error();
+                match self.handle_response::<GroupCheck>(response).await {
+                    Ok(group_check) => {
+                        cache.put(&user_id, &group_id, group_check.has_group);
+                        return Ok(group_check.has_group);
+                    }
+                    Err(err) if outage => err,
+                    Err(err) => return Err(err),
+                }
+            }
+            Err(err @ KeyrunesError::NetworkError(_)) => err,
+            Err(err) => return Err(err),
+        };
+        cache.fallback(&user_id, &group_id, &err).ok_or(err)
     }
 
     /// Gets the list of groups for a user.
@@ -466,6 +884,47 @@ impl KeyrunesClient {
         Ok(user.groups)
     }
 
+    /// Completes a step-up challenge and upgrades the current session.
+    ///
+    /// Step-up authentication raises the [`AuthLevel`](crate::claims::AuthLevel)
+    /// of an existing session (e.g., from password to MFA-verified) so that
+    /// sensitive routes can be accessed. The upgraded token replaces the
+    /// current one.
+    ///
+    /// # Arguments
+    ///
+    /// * `challenge` - The step-up challenge response (method and code)
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<Token, KeyrunesError>`:
+    /// - `Ok(token)` if the challenge was accepted
+    /// - `Err(KeyrunesError::InvalidToken)` if not authenticated
+    /// - `Err(KeyrunesError::AuthenticationError)` if the code is invalid
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyrunes_rust_sdk::{KeyrunesClient, StepUpChallenge};
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = KeyrunesClient::new("https://keyrunes.example.com")?;
+    /// client.login("user@example.com", "password123", None).await?;
+    /// let token = client.step_up(StepUpChallenge::new("totp", "123456")).await?;
+    /// println!("Upgraded token: {}", token.token);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn step_up(&self, challenge: StepUpChallenge) -> Result<Token> {
+        let url = self.url(endpoints::STEP_UP);
+        let response = self
+            .send(self.client.post(&url).json(&challenge), Auth::Required)
+            .await?;
+
+        let token = self.handle_response::<Token>(response).await?;
+        self.store_issued_token(&token).await?;
+        Ok(token)
+    }
+
     /// Clears the authentication token.
     ///
     /// # Examples
@@ -480,10 +939,502 @@ impl KeyrunesClient {
     /// # }
     /// ```
     pub async fn clear_token(&self) {
-        *self.token.write().await = None;
+        self.store_token(None).await;
+        // Best effort: a leftover pair only matters if it is loaded again.
+        let _ = self.token_store.clear().await;
+    }
+
+    /// Logs out: revokes the current token on the server, then clears it.
+    ///
+    /// The client's tokens (and the token store) are cleared whatever the
+    /// outcome of the revocation, so a failed request never leaves the user
+    /// signed in locally. The browser session of a single sign-on login is
+    /// not ended; see [`KeyrunesClient::logout_url`].
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<bool, KeyrunesError>`:
+    /// - `Ok(true)` if the server acknowledged the revocation
+    /// - `Ok(false)` if there was no token, or the server rejected it as already invalid
+    /// - `Err(KeyrunesError)` if the revocation could not be confirmed (the tokens are cleared anyway)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyrunes_rust_sdk::KeyrunesClient;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = KeyrunesClient::new("https://keyrunes.example.com")?;
+    /// client.login("user@example.com", "password123", None).await?;
+    /// if !client.logout().await? {
+    ///     println!("Session had already ended");
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn logout(&self) -> Result<bool> {
+        let token = self.token.read().await.clone();
+        let result = match token {
+            Some(token) => self.revoke_token(&token).await,
+            None => {
+                self.clear_token().await;
+                return Ok(false);
+            }
+        };
+        self.clear_token().await;
+
+        match result {
+            Ok(()) => Ok(true),
+            // The token already expired or was revoked
+            Err(KeyrunesError::AuthenticationError { .. }) => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Builds the Keyrunes end-session URL, to log the user out in the browser.
+    ///
+    /// Redirecting the browser there ends the user's Keyrunes session (the
+    /// single sign-on cookie), which the API-based logout cannot reach.
+    /// Keyrunes then sends the browser to `post_logout_redirect_uri`, which
+    /// must be registered for the application. The client's token is left
+    /// untouched; call [`KeyrunesClient::clear_token`] as well.
+    ///
+    /// # Arguments
+    ///
+    /// * `post_logout_redirect_uri` - Where Keyrunes sends the browser after logout
+    /// * `id_token_hint` - ID token of the session, identifying the user to log out
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyrunes_rust_sdk::KeyrunesClient;
+    /// # fn example(id_token: &str) -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = KeyrunesClient::new("https://keyrunes.example.com")?;
+    /// let url = client.logout_url(Some("https://app.example.com/"), Some(id_token));
+    /// // Respond with a redirect to `url`
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn logout_url(
+        &self,
+        post_logout_redirect_uri: Option<&str>,
+        id_token_hint: Option<&str>,
+    ) -> String {
+        let mut query = url::form_urlencoded::Serializer::new(String::new());
+        if let Some(id_token_hint) = id_token_hint {
+            query.append_pair("id_token_hint", id_token_hint);
+        }
+        if let Some(uri) = post_logout_redirect_uri {
+            query.append_pair("post_logout_redirect_uri", uri);
+        }
+        let query = query.finish();
+
+        let url = self.url(endpoints::END_SESSION);
+        if query.is_empty() {
+            url
+        } else {
+            format!("{}?{}", url, query)
+        }
+    }
+
+    /// Exports the client's session: token, refresh token, namespace and
+    /// the user last fetched with [`KeyrunesClient::get_current_user`].
+    ///
+    /// CLIs and desktop apps can persist the result (it is serializable)
+    /// and resume with [`KeyrunesClient::restore_session`] instead of
+    /// asking the user to log in again. The export holds credentials:
+    /// store it as securely as a password.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyrunes_rust_sdk::KeyrunesClient;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = KeyrunesClient::new("https://keyrunes.example.com")?;
+    /// client.login("user@example.com", "password123", None).await?;
+    /// let saved = serde_json::to_string(&client.export_session().await)?;
+    ///
+    /// let resumed = KeyrunesClient::new("https://keyrunes.example.com")?;
+    /// resumed.restore_session(serde_json::from_str(&saved)?).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn export_session(&self) -> ClientSession {
+        let token = self.token.read().await.clone();
+        let session = self.session.read().await;
+        ClientSession {
+            version: ClientSession::VERSION,
+            token,
+            refresh_token: session.refresh_token.clone(),
+            namespace: session.namespace.clone(),
+            user: session.user.clone(),
+        }
+    }
+
+    /// Restores a session exported with [`KeyrunesClient::export_session`].
+    ///
+    /// Replaces the client's token (notifying
+    /// [`KeyrunesClient::token_updates`] subscribers) and session data.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<(), KeyrunesError>`:
+    /// - `Ok(())` if the session was restored
+    /// - `Err(KeyrunesError::Other)` if the session was exported by a newer SDK version
+    pub async fn restore_session(&self, session: ClientSession) -> Result<()> {
+        if session.version > ClientSession::VERSION {
+            return Err(KeyrunesError::Other(format!(
+                "Unsupported session version: {}",
+                session.version
+            )));
+        }
+
+        self.store_token(session.token).await;
+        *self.session.write().await = SessionState {
+            refresh_token: session.refresh_token,
+            namespace: session.namespace,
+            user: session.user,
+            user_validators: None,
+            refreshed_from: None,
+        };
+        Ok(())
+    }
+
+    /// Exchanges a refresh token for a new token, leaving the client's token untouched.
+    ///
+    /// Fails with `RefreshTokenReused` if the server detected reuse of a
+    /// rotated refresh token.
+    pub(crate) async fn exchange_refresh_token(&self, refresh_token: &str) -> Result<Token> {
+        let url = self.url(endpoints::REFRESH);
+        // Sent without `send`, which refreshes tokens itself.
+        let response = self
+            .send_once(
+                self.client
+                    .post(&url)
+                    .json(&serde_json::json!({ "refresh_token": refresh_token })),
+                None,
+            )
+            .await?;
+
+        let status = response.status();
+        if status.is_success() {
+            return self.handle_response(response).await;
+        }
+
+        // Reuse is reported with a 401, so the code is checked before it
+        // becomes an `AuthenticationError`
+        let url = response.url().clone();
+        let body = response.text().await?;
+        let code = serde_json::from_str(&body)
+            .ok()
+            .as_ref()
+            .and_then(error_code);
+        if code.as_deref() == Some(CODE_REFRESH_TOKEN_REUSED) {
+            Err(KeyrunesError::RefreshTokenReused)
+        } else {
+            Err(self.handle_error(status, &body, &url))
+        }
+    }
+
+    /// Revokes `token` on the server, leaving the client's token untouched.
+    pub(crate) async fn revoke_token(&self, token: &str) -> Result<()> {
+        let url = self.url(endpoints::LOGOUT);
+        // Sent without `send`, which would refresh the token being revoked;
+        // `send_once` still presents it as a DPoP token when enabled.
+        let response = self.send_once(self.client.post(&url), Some(token)).await?;
+
+        self.handle_empty_response(response).await
+    }
+
+    /// Returns a client scoped to an organization.
+    ///
+    /// Every request of the returned client, administration endpoints
+    /// included, carries the organization as `X-Keyrunes-Org`, and the
+    /// server restricts it to that organization. The scoped client shares
+    /// the token, session and connection pool of this one, so creating one
+    /// per request is cheap; cached entitlements are kept per organization.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyrunes_rust_sdk::KeyrunesClient;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = KeyrunesClient::new("https://keyrunes.example.com")?;
+    /// client.login("admin@example.com", "password123", None).await?;
+    /// let acme = client.for_org("acme");
+    /// let stats = acme.admin().stats().await?;
+    /// println!("{} users in acme", stats.total_users);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn for_org<S: Into<String>>(&self, org_id: S) -> KeyrunesClient {
+        let org_id = org_id.into();
+        let same_org = self.org_id.as_ref() == Some(&org_id);
+        let entitlements = if same_org {
+            self.entitlements.clone()
+        } else {
+            Arc::new(EntitlementCache::default())
+        };
+        let memberships = match &self.memberships {
+            Some(cache) if !same_org => Some(Arc::new(cache.empty_like())),
+            memberships => memberships.clone(),
+        };
+        KeyrunesClient {
+            org_id: Some(org_id),
+            entitlements,
+            memberships,
+            last_response: Arc::default(),
+            ..self.clone()
+        }
+    }
+
+    /// Organization the client is scoped to, if any (see [`Self::for_org`])
+    pub fn org_id(&self) -> Option<&str> {
+        self.org_id.as_deref()
+    }
+
+    /// Returns a view of the client acting as another user.
+    ///
+    /// The view shares the connection pool, configuration and key caches of
+    /// this client but carries its own token, cached profile and namespace:
+    /// logging in, refreshing or clearing the token on one never affects
+    /// the other. Use it to serve concurrent requests of different users,
+    /// or in jobs acting on behalf of many users.
+    ///
+    /// Tokens of the view are not saved in the client's token store.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyrunes_rust_sdk::KeyrunesClient;
+    /// # async fn example(tokens: Vec<String>) -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = KeyrunesClient::new("https://keyrunes.example.com")?;
+    /// for token in tokens {
+    ///     let user = client.as_user(token).get_current_user().await?;
+    ///     println!("Processing {}", user.username);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn as_user<S: Into<String>>(&self, token: S) -> KeyrunesClient {
+        self.detached(Some(token.into()))
+    }
+
+    /// Clone with its own token, session and token store, sharing the
+    /// connection pool and configuration.
+    pub(crate) fn detached(&self, token: Option<String>) -> KeyrunesClient {
+        let info = token.clone().map(|token| self.token_info(token));
+        KeyrunesClient {
+            token: Arc::new(RwLock::new(token)),
+            token_updates: Arc::new(watch::channel(info).0),
+            session: Arc::new(RwLock::new(SessionState::default())),
+            refreshes: Arc::new(Refreshes::default()),
+            token_store: Arc::new(MemoryTokenStore::new()),
+            last_response: Arc::default(),
+            ..self.clone()
+        }
+    }
+
+    /// Headers of interest of the last response received by the client
+    /// (request ID, rate limit, server version, deprecation notices).
+    ///
+    /// With concurrent calls on the same client, this is the response
+    /// received last; use a dedicated view ([`Self::as_user`],
+    /// [`Self::for_org`]) or client to attribute headers to a call.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyrunes_rust_sdk::KeyrunesClient;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = KeyrunesClient::new("https://keyrunes.example.com")?;
+    /// client.login("user@example.com", "password123", None).await?;
+    /// let user = client.get_current_user().await?;
+    /// if let Some(meta) = client.last_response_meta() {
+    ///     println!("{:?} requests left", meta.rate_limit_remaining);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn last_response_meta(&self) -> Option<ResponseMeta> {
+        self.last_response
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone()
+    }
+
+    /// Returns a handle to the administration endpoints.
+    ///
+    /// Admin endpoints require a token with administrator privileges.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyrunes_rust_sdk::KeyrunesClient;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = KeyrunesClient::new("https://keyrunes.example.com")?;
+    /// client.login("admin@example.com", "password123", None).await?;
+    /// let stats = client.admin().stats().await?;
+    /// println!("{} users", stats.total_users);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn admin(&self) -> AdminClient<'_> {
+        AdminClient::new(self)
+    }
+
+    /// Returns a handle to the SCIM provisioning endpoint (see [`crate::scim`]).
+    ///
+    /// The token must be allowed to provision the tenant.
+    pub fn scim(&self) -> crate::scim::ScimClient<'_> {
+        crate::scim::ScimClient::new(self)
+    }
+
+    /// Returns a handle to the low-level endpoint functions generated from
+    /// the OpenAPI spec (see [`generated`]).
+    ///
+    /// They cover every endpoint of the API, including those the
+    /// higher-level methods do not wrap yet.
+    pub fn generated(&self) -> generated::GeneratedClient<'_> {
+        generated::GeneratedClient::new(self)
+    }
+
+    /// Reports a security event to the Keyrunes risk engine.
+    ///
+    /// Use this to push anomalies detected by the application (impossible
+    /// travel, credential stuffing suspicion, etc.). The current token is
+    /// sent when available, so events can be reported both with and
+    /// without a session.
+    ///
+    /// # Arguments
+    ///
+    /// * `event` - The security event to report
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<(), KeyrunesError>`:
+    /// - `Ok(())` if the event was accepted
+    /// - `Err(KeyrunesError::HttpError)` if there was an error in the request
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyrunes_rust_sdk::{KeyrunesClient, SecurityEvent, SecurityEventKind};
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = KeyrunesClient::new("https://keyrunes.example.com")?;
+    /// let event = SecurityEvent::new(SecurityEventKind::ImpossibleTravel)
+    ///     .with_user_id("123")
+    ///     .with_ip("203.0.113.7")
+    ///     .with_metadata("previous_country", "BR");
+    /// client.report_security_event(&event).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn report_security_event(&self, event: &SecurityEvent) -> Result<()> {
+        let url = self.url(endpoints::SECURITY_EVENTS);
+        let request = self.client.post(&url).json(event);
+        let response = self.send(request, Auth::Optional).await?;
+        self.handle_empty_response(response).await
+    }
+
+    /// Returns the claims mapping configured for this client.
+    pub fn claims_mapping(&self) -> &ClaimsMapping {
+        &self.claims_mapping
+    }
+
+    /// Decodes the claims of `token` (without verifying its signature)
+    /// using the client's [`ClaimsMapping`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyrunes_rust_sdk::KeyrunesClient;
+    /// # fn example(token: &str) -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = KeyrunesClient::new("https://keyrunes.example.com")?;
+    /// let claims = client.decode_claims(token)?;
+    /// println!("Groups: {:?}", claims.groups);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn decode_claims(&self, token: &str) -> Result<Claims> {
+        Claims::from_token_with(token, &self.claims_mapping)
+    }
+
+    /// Replaces the client's token, notifying [`KeyrunesClient::token_updates`]
+    /// subscribers if it changed.
+    pub(crate) async fn store_token(&self, token: Option<String>) {
+        let mut current = self.token.write().await;
+        let mut session = self.session.write().await;
+        self.swap_token(&mut current, &mut session, token);
+    }
+
+    /// Replaces the client's token and refresh token in a single update.
+    async fn replace_tokens(&self, token: Option<String>, refresh_token: Option<String>) {
+        let mut current = self.token.write().await;
+        let mut session = self.session.write().await;
+        self.swap_token(&mut current, &mut session, token);
+        session.refresh_token = refresh_token;
+    }
+
+    fn swap_token(
+        &self,
+        current: &mut Option<String>,
+        session: &mut SessionState,
+        token: Option<String>,
+    ) {
+        if *current == token {
+            return;
+        }
+        *current = token.clone();
+        match token {
+            Some(_) => {
+                // The refresh token belongs to the replaced token only.
+                session.refresh_token = None;
+                session.user = None;
+                session.user_validators = None;
+                session.refreshed_from = None;
+            }
+            None => *session = SessionState::default(),
+        }
+        self.token_updates
+            .send_replace(token.map(|token| self.token_info(token)));
+    }
+
+    /// Stores a token issued by Keyrunes, with its refresh token, and saves
+    /// the pair in the token store.
+    pub(crate) async fn store_issued_token(&self, token: &Token) -> Result<()> {
+        self.replace_tokens(Some(token.token.clone()), token.refresh_token.clone())
+            .await;
+        self.token_store.save(&StoredTokens::from(token)).await
     }
 
-    async fn handle_response<T: for<'de> serde::Deserialize<'de>>(
+    /// Stores a token obtained by refreshing the current one, remembering
+    /// the token it replaces (see `refreshed_token`).
+    pub(crate) async fn store_refreshed_token(&self, token: &Token) -> Result<()> {
+        {
+            let mut current = self.token.write().await;
+            let mut session = self.session.write().await;
+            let previous = current.clone();
+            self.swap_token(&mut current, &mut session, Some(token.token.clone()));
+            session.refresh_token = token.refresh_token.clone();
+            session.refreshed_from = previous;
+        }
+        self.token_store.save(&StoredTokens::from(token)).await
+    }
+
+    pub(crate) fn token_info(&self, token: String) -> TokenInfo {
+        let claims = self.decode_claims(&token).ok();
+
+        TokenInfo {
+            expires_at: claims
+                .as_ref()
+                .and_then(|claims| claims.exp)
+                .and_then(|exp| chrono::DateTime::from_timestamp(exp, 0)),
+            subject: claims.and_then(|claims| claims.user_id.or(claims.sub)),
+            token,
+        }
+    }
+
+    pub(crate) async fn handle_response<T: for<'de> serde::Deserialize<'de>>(
         &self,
         response: reqwest::Response,
     ) -> Result<T> {
@@ -491,53 +1442,118 @@ impl KeyrunesClient {
         let url = response.url().clone();
         let body = response.text().await?;
 
-        if status.is_success() {
+        if status.is_success() && self.strict {
+            strict::from_str(&body)
+        } else if status.is_success() {
             serde_json::from_str(&body).map_err(Into::into)
         } else {
             Err(self.handle_error(status, &body, &url))
         }
     }
 
+    pub(crate) async fn handle_empty_response(&self, response: reqwest::Response) -> Result<()> {
+        let status = response.status();
+        let url = response.url().clone();
+
+        if status.is_success() {
+            Ok(())
+        } else {
+            let body = response.text().await?;
+            Err(self.handle_error(status, &body, &url))
+        }
+    }
+
     fn handle_error(
         &self,
         status: reqwest::StatusCode,
         body: &str,
         url: &reqwest::Url,
     ) -> KeyrunesError {
-        let error_message = if body.trim_start().starts_with('<') {
-            format!("HTTP {} - Received HTML response (endpoint may not exist or path is incorrect). Tried: {}", status.as_u16(), url)
+        let (error_message, coded) = if body.trim_start().starts_with('<') {
+            (format!("HTTP {} - Received HTML response (endpoint may not exist or path is incorrect). Tried: {}", status.as_u16(), url), None)
         } else {
-            let api_message = serde_json::from_str::<serde_json::Value>(body)
-                .map(|v| {
-                    v.get("message")
-                        .or_else(|| v.get("error"))
-                        .and_then(|m| m.as_str())
-                        .unwrap_or(body)
-                        .to_string()
-                })
-                .unwrap_or_else(|_| {
-                    if body.len() > 200 {
-                        format!("{}...", &body[..200])
-                    } else {
-                        body.to_string()
+            let json = serde_json::from_str::<serde_json::Value>(body).ok();
+            let api_message = match &json {
+                Some(v) => v
+                    .get("message")
+                    .or_else(|| v.get("error"))
+                    .or_else(|| v.get("detail"))
+                    .and_then(|m| m.as_str())
+                    .unwrap_or(body)
+                    .to_string(),
+                None => match body.char_indices().nth(200) {
+                    Some((end, _)) => format!("{}...", &body[..end]),
+                    None => body.to_string(),
+                },
+            };
+
+            let code = json.as_ref().and_then(error_code);
+
+            (
+                format!("{} (URL: {})", api_message, url),
+                code.map(|code| (code, api_message)),
+            )
+        };
+
+        let code = coded.as_ref().map(|(code, _)| code.clone());
+        let error = match status {
+            reqwest::StatusCode::UNAUTHORIZED => KeyrunesError::AuthenticationError {
+                message: error_message,
+                code,
+            },
+            reqwest::StatusCode::FORBIDDEN => KeyrunesError::AuthorizationError {
+                message: error_message,
+                code,
+            },
+            // Messages may be localized: only uncoded responses are
+            // classified by their (English) message
+            reqwest::StatusCode::NOT_FOUND => match code.as_deref() {
+                Some("user_not_found") => KeyrunesError::UserNotFoundError {
+                    message: error_message,
+                    code,
+                },
+                Some("group_not_found") => KeyrunesError::GroupNotFoundError {
+                    message: error_message,
+                    code,
+                },
+                Some(_) => KeyrunesError::Other(format!("Resource not found: {}", error_message)),
+                None if error_message.contains("user") || error_message.contains("User") => {
+                    KeyrunesError::UserNotFoundError {
+                        message: error_message,
+                        code,
+                    }
+                }
+                None if error_message.contains("group") || error_message.contains("Group") => {
+                    KeyrunesError::GroupNotFoundError {
+                        message: error_message,
+                        code,
                     }
-                });
-            format!("{} (URL: {})", api_message, url)
+                }
+                None => KeyrunesError::Other(format!("Resource not found: {}", error_message)),
+            },
+            _ => KeyrunesError::HttpError(format!("HTTP {}: {}", status.as_u16(), error_message)),
         };
 
-        match status {
-            reqwest::StatusCode::UNAUTHORIZED => KeyrunesError::AuthenticationError(error_message),
-            reqwest::StatusCode::FORBIDDEN => KeyrunesError::AuthorizationError(error_message),
-            reqwest::StatusCode::NOT_FOUND => {
-                if error_message.contains("user") || error_message.contains("User") {
-                    KeyrunesError::UserNotFoundError(error_message)
-                } else if error_message.contains("group") || error_message.contains("Group") {
-                    KeyrunesError::GroupNotFoundError(error_message)
-                } else {
-                    KeyrunesError::Other(format!("Resource not found: {}", error_message))
+        // A code only replaces the generic variants: callers matching on the
+        // status-based ones are unaffected by the server adding a code
+        match (error, coded) {
+            (KeyrunesError::HttpError(_) | KeyrunesError::Other(_), Some((code, message))) => {
+                KeyrunesError::Api {
+                    status: status.as_u16(),
+                    code,
+                    message,
                 }
             }
-            _ => KeyrunesError::HttpError(format!("HTTP {}: {}", status.as_u16(), error_message)),
+            (error, _) => error,
         }
     }
 }
+
+/// Machine-readable code of an error body; SCIM errors carry it in `scimType`
+fn error_code(body: &serde_json::Value) -> Option<String> {
+    match body.get("code").or(body.get("scimType"))? {
+        serde_json::Value::String(code) => Some(code.clone()),
+        serde_json::Value::Number(code) => Some(code.to_string()),
+        _ => None,
+    }
+}
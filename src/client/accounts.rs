@@ -0,0 +1,554 @@
+//! Account management endpoints
+
+use super::transport::{Auth, ProgressCallback, RequestBody};
+use super::KeyrunesClient;
+use crate::endpoints;
+use crate::error::{KeyrunesError, Result};
+use crate::models::{
+    Avatar, ChangePasswordRequest, LinkedAccount, PasswordResetConfirmation, PasswordResetRequest,
+    User, UserResponse, UsernameChange, DEFAULT_NAMESPACE,
+};
+
+impl KeyrunesClient {
+    /// Links a secondary account into a primary account.
+    ///
+    /// Used to merge duplicate identities (e.g., an email/password account
+    /// and an SSO account of the same person). After linking, logins to the
+    /// secondary account resolve to the primary user.
+    ///
+    /// # Arguments
+    ///
+    /// * `primary_user_id` - ID of the account that is kept
+    /// * `secondary_user_id` - ID of the account merged into it
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<LinkedAccount, KeyrunesError>`:
+    /// - `Ok(link)` if the accounts were linked
+    /// - `Err(KeyrunesError::UserNotFoundError)` if either user doesn't exist
+    /// - `Err(KeyrunesError::AuthorizationError)` if not allowed to link the accounts
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyrunes_rust_sdk::KeyrunesClient;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = KeyrunesClient::new("https://keyrunes.example.com")?;
+    /// client.login("admin@example.com", "password123", None).await?;
+    /// let link = client.link_accounts("123", "456").await?;
+    /// println!("Linked {} into {}", link.user_id, link.primary_user_id);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn link_accounts<P: Into<String>, S: Into<String>>(
+        &self,
+        primary_user_id: P,
+        secondary_user_id: S,
+    ) -> Result<LinkedAccount> {
+        let url = self.url(&endpoints::user_linked_accounts(primary_user_id.into()));
+        let response = self
+            .send(
+                self.client
+                    .post(&url)
+                    .json(&serde_json::json!({ "user_id": secondary_user_id.into() })),
+                Auth::Required,
+            )
+            .await?;
+
+        self.handle_response(response).await
+    }
+
+    /// Lists the accounts linked into a user.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - ID of the primary account
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<Vec<LinkedAccount>, KeyrunesError>`:
+    /// - `Ok(links)` if the links were successfully retrieved
+    /// - `Err(KeyrunesError::UserNotFoundError)` if user doesn't exist
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyrunes_rust_sdk::KeyrunesClient;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = KeyrunesClient::new("https://keyrunes.example.com")?;
+    /// client.login("user@example.com", "password123", None).await?;
+    /// for link in client.list_linked_accounts("123").await? {
+    ///     println!("{} ({:?})", link.user_id, link.provider);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn list_linked_accounts<S: Into<String>>(
+        &self,
+        user_id: S,
+    ) -> Result<Vec<LinkedAccount>> {
+        let url = self.url(&endpoints::user_linked_accounts(user_id.into()));
+        let response = self.send(self.client.get(&url), Auth::Required).await?;
+
+        self.handle_response(response).await
+    }
+
+    /// Changes the username of the current user.
+    ///
+    /// # Arguments
+    ///
+    /// * `new_username` - The new username
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<User, KeyrunesError>`:
+    /// - `Ok(user)` with the updated user
+    /// - `Err(KeyrunesError::UsernameTaken)` if the username is already in use
+    /// - `Err(KeyrunesError::InvalidToken)` if not authenticated
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyrunes_rust_sdk::{KeyrunesClient, KeyrunesError};
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = KeyrunesClient::new("https://keyrunes.example.com")?;
+    /// client.login("john@example.com", "password123", None).await?;
+    /// match client.change_username("johnny").await {
+    ///     Ok(user) => println!("Now known as {}", user.username),
+    ///     Err(KeyrunesError::UsernameTaken(name)) => println!("{} is taken", name),
+    ///     Err(e) => return Err(e.into()),
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn change_username<S: Into<String>>(&self, new_username: S) -> Result<User> {
+        let new_username = new_username.into();
+        let url = self.url(endpoints::ME_USERNAME);
+        let response = self
+            .send(
+                self.client
+                    .patch(&url)
+                    .json(&serde_json::json!({ "username": new_username })),
+                Auth::Required,
+            )
+            .await?;
+
+        if response.status() == reqwest::StatusCode::CONFLICT {
+            return Err(KeyrunesError::UsernameTaken(new_username));
+        }
+
+        let user_response = self.handle_response::<UserResponse>(response).await?;
+        Ok(User::from(user_response))
+    }
+
+    /// Changes the password of the current user.
+    ///
+    /// Also completes forced password rotations: after a registration that
+    /// requires a password change (see [`KeyrunesClient::register_with_outcome`]),
+    /// call it on `client.as_user(registration_token)`.
+    ///
+    /// # Arguments
+    ///
+    /// * `current_password` - Current password
+    /// * `new_password` - New password (minimum 8 characters)
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<(), KeyrunesError>`:
+    /// - `Ok(())` if the password was changed
+    /// - `Err(KeyrunesError::AuthenticationError)` if the current password is wrong
+    /// - `Err(KeyrunesError::InvalidToken)` if not authenticated
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyrunes_rust_sdk::KeyrunesClient;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = KeyrunesClient::new("https://keyrunes.example.com")?;
+    /// client.login("john@example.com", "password123", None).await?;
+    /// client.change_password("password123", "correct-horse-battery").await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn change_password<C: Into<String>, N: Into<String>>(
+        &self,
+        current_password: C,
+        new_password: N,
+    ) -> Result<()> {
+        let url = self.url(endpoints::ME_PASSWORD);
+        let request = ChangePasswordRequest::new(current_password, new_password);
+        let response = self
+            .send(self.client.post(&url).json(&request), Auth::Required)
+            .await?;
+
+        self.handle_empty_response(response).await
+    }
+
+    /// Sends a verification email to the current user.
+    ///
+    /// The email holds a code to pass to [`KeyrunesClient::verify_email`].
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<(), KeyrunesError>`:
+    /// - `Ok(())` if the email was sent
+    /// - `Err(KeyrunesError::RateLimitExceeded)` if an email was sent too recently
+    /// - `Err(KeyrunesError::InvalidToken)` if not authenticated
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyrunes_rust_sdk::KeyrunesClient;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = KeyrunesClient::new("https://keyrunes.example.com")?;
+    /// client.login("john@example.com", "password123", None).await?;
+    /// if client.get_current_user().await?.email_verified != Some(true) {
+    ///     client.send_verification_email().await?;
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn send_verification_email(&self) -> Result<()> {
+        let response = self
+            .send_request(
+                reqwest::Method::POST,
+                endpoints::ME_EMAIL_VERIFICATION,
+                RequestBody::Empty,
+                Auth::Required,
+                None,
+            )
+            .await?;
+        self.handle_empty_response(response).await
+    }
+
+    /// Verifies the current user's email with the code of a verification email.
+    ///
+    /// # Arguments
+    ///
+    /// * `code` - Code received in the verification email
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<User, KeyrunesError>`:
+    /// - `Ok(user)` with the user, its email verified
+    /// - `Err(KeyrunesError::Api)` with code `verification_code_invalid` or
+    ///   `verification_code_expired` if the code is wrong or expired
+    /// - `Err(KeyrunesError::InvalidToken)` if not authenticated
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyrunes_rust_sdk::KeyrunesClient;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = KeyrunesClient::new("https://keyrunes.example.com")?;
+    /// client.login("john@example.com", "password123", None).await?;
+    /// let user = client.verify_email("482913").await?;
+    /// assert_eq!(user.email_verified, Some(true));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn verify_email<S: Into<String>>(&self, code: S) -> Result<User> {
+        let response = self
+            .send_request(
+                reqwest::Method::POST,
+                endpoints::ME_EMAIL_VERIFY,
+                RequestBody::Json(serde_json::json!({ "code": code.into() })),
+                Auth::Required,
+                None,
+            )
+            .await?;
+
+        let user_response = self.handle_response::<UserResponse>(response).await?;
+        let user = User::from(user_response);
+        self.session.write().await.user = Some(user.clone());
+        Ok(user)
+    }
+
+    /// Sends a password reset link to the user's email (forgot password).
+    ///
+    /// # Arguments
+    ///
+    /// * `email` - Email of the account to recover
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<(), KeyrunesError>`:
+    /// - `Ok(())` if the request was accepted (also when the email is unknown,
+    ///   so the endpoint cannot be used to enumerate accounts)
+    /// - `Err(KeyrunesError::RateLimitExceeded)` if too many resets were requested
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyrunes_rust_sdk::KeyrunesClient;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = KeyrunesClient::new("https://keyrunes.example.com")?;
+    /// client.request_password_reset("john@example.com").await?;
+    /// println!("If the account exists, a reset link was sent");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn request_password_reset<S: Into<String>>(&self, email: S) -> Result<()> {
+        let request = PasswordResetRequest {
+            email: email.into(),
+            namespace: DEFAULT_NAMESPACE.to_string(),
+        };
+
+        let response = self
+            .send_request(
+                reqwest::Method::POST,
+                endpoints::PASSWORD_RESET,
+                RequestBody::Json(serde_json::to_value(&request)?),
+                Auth::None,
+                None,
+            )
+            .await?;
+        self.handle_empty_response(response).await
+    }
+
+    /// Sets a new password with the token of a password reset link.
+    ///
+    /// The user is not logged in; sessions of the account may be revoked by
+    /// the server, depending on its configuration.
+    ///
+    /// # Arguments
+    ///
+    /// * `reset_token` - Token received in the reset link
+    /// * `new_password` - New password (minimum 8 characters)
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<(), KeyrunesError>`:
+    /// - `Ok(())` if the password was reset
+    /// - `Err(KeyrunesError::ResetTokenExpired)` if the link expired
+    /// - `Err(KeyrunesError::InvalidResetToken)` if the link is unknown or was already used
+    /// - `Err(KeyrunesError::PasswordRejected)` if the password does not satisfy the password policy
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyrunes_rust_sdk::{KeyrunesClient, KeyrunesError};
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = KeyrunesClient::new("https://keyrunes.example.com")?;
+    /// match client
+    ///     .confirm_password_reset("reset-token", "correct-horse-battery")
+    ///     .await
+    /// {
+    ///     Ok(()) => println!("Password changed, please log in"),
+    ///     Err(KeyrunesError::ResetTokenExpired) => println!("Link expired, request a new one"),
+    ///     Err(e) => return Err(e.into()),
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn confirm_password_reset<T: Into<String>, P: Into<String>>(
+        &self,
+        reset_token: T,
+        new_password: P,
+    ) -> Result<()> {
+        let confirmation = PasswordResetConfirmation {
+            reset_token: reset_token.into(),
+            new_password: new_password.into(),
+        };
+
+        let response = self
+            .send_request(
+                reqwest::Method::POST,
+                endpoints::PASSWORD_RESET_CONFIRM,
+                RequestBody::Json(serde_json::to_value(&confirmation)?),
+                Auth::None,
+                None,
+            )
+            .await?;
+
+        let status = response.status();
+        if status.is_client_error() {
+            let url = response.url().clone();
+            let body = response.text().await?;
+            let details: serde_json::Value = serde_json::from_str(&body).unwrap_or_default();
+            let code = details
+                .get("code")
+                .or_else(|| details.get("error"))
+                .and_then(|c| c.as_str());
+            return Err(match code {
+                Some("reset_token_expired") => KeyrunesError::ResetTokenExpired,
+                Some("reset_token_invalid") => KeyrunesError::InvalidResetToken,
+                Some("weak_password") => KeyrunesError::PasswordRejected(
+                    details
+                        .get("message")
+                        .and_then(|m| m.as_str())
+                        .unwrap_or("password does not satisfy the password policy")
+                        .to_string(),
+                ),
+                _ => self.handle_error(status, &body, &url),
+            });
+        }
+
+        self.handle_empty_response(response).await
+    }
+
+    /// Gets the username change history of a user.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - User ID
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<Vec<UsernameChange>, KeyrunesError>`:
+    /// - `Ok(changes)` ordered from oldest to newest
+    /// - `Err(KeyrunesError::UserNotFoundError)` if user doesn't exist
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyrunes_rust_sdk::KeyrunesClient;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = KeyrunesClient::new("https://keyrunes.example.com")?;
+    /// client.login("john@example.com", "password123", None).await?;
+    /// for change in client.username_history("123").await? {
+    ///     println!("{} -> {}", change.old_username, change.new_username);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn username_history<S: Into<String>>(
+        &self,
+        user_id: S,
+    ) -> Result<Vec<UsernameChange>> {
+        let url = self.url(&endpoints::user_username_history(user_id.into()));
+        let response = self.send(self.client.get(&url), Auth::Required).await?;
+
+        self.handle_response(response).await
+    }
+
+    /// Uploads a profile picture for a user.
+    ///
+    /// The image is sent as a `multipart/form-data` body with a single
+    /// `file` part.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - User ID
+    /// * `bytes` - Image content
+    /// * `content_type` - Image content type (must be an `image/*` type)
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<Avatar, KeyrunesError>`:
+    /// - `Ok(avatar)` with the public URL of the uploaded image
+    /// - `Err(KeyrunesError::Other)` if the content type is not an image type
+    /// - `Err(KeyrunesError::HttpError)` if the image was rejected (e.g., too large)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyrunes_rust_sdk::KeyrunesClient;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = KeyrunesClient::new("https://keyrunes.example.com")?;
+    /// client.login("john@example.com", "password123", None).await?;
+    /// let bytes = std::fs::read("avatar.png")?;
+    /// let avatar = client.upload_avatar("123", bytes, "image/png").await?;
+    /// println!("Avatar URL: {}", avatar.url);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn upload_avatar<U: Into<String>, B: Into<Vec<u8>>>(
+        &self,
+        user_id: U,
+        bytes: B,
+        content_type: &str,
+    ) -> Result<Avatar> {
+        self.upload_avatar_with_progress(user_id, bytes, content_type, None)
+            .await
+    }
+
+    /// Uploads a profile picture, reporting upload progress.
+    ///
+    /// Same as [`upload_avatar`](Self::upload_avatar), invoking `progress`
+    /// as the image is sent.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyrunes_rust_sdk::KeyrunesClient;
+    /// # use std::sync::Arc;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = KeyrunesClient::new("https://keyrunes.example.com")?;
+    /// client.login("john@example.com", "password123", None).await?;
+    /// let bytes = std::fs::read("avatar.png")?;
+    /// let progress = Arc::new(|p: keyrunes_rust_sdk::client::transport::Progress| {
+    ///     println!("{}/{:?} bytes", p.transferred, p.total);
+    /// });
+    /// client
+    ///     .upload_avatar_with_progress("123", bytes, "image/png", Some(progress))
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn upload_avatar_with_progress<U: Into<String>, B: Into<Vec<u8>>>(
+        &self,
+        user_id: U,
+        bytes: B,
+        content_type: &str,
+        progress: Option<ProgressCallback>,
+    ) -> Result<Avatar> {
+        if !content_type.starts_with("image/") {
+            return Err(KeyrunesError::Other(format!(
+                "Unsupported avatar content type: {}",
+                content_type
+            )));
+        }
+
+        let body = RequestBody::File {
+            field: "file".to_string(),
+            file_name: "avatar".to_string(),
+            data: bytes.into(),
+            content_type: content_type.to_string(),
+        };
+        let path = endpoints::user_avatar(user_id.into());
+        let response = self
+            .send_request(reqwest::Method::PUT, &path, body, Auth::Required, progress)
+            .await?;
+
+        self.handle_response(response).await
+    }
+
+    /// Gets the public URL of a user's profile picture.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - User ID
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<Option<String>, KeyrunesError>`:
+    /// - `Ok(Some(url))` if the user has a profile picture
+    /// - `Ok(None)` if the user has no profile picture
+    /// - `Err(KeyrunesError::InvalidToken)` if not authenticated
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyrunes_rust_sdk::KeyrunesClient;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = KeyrunesClient::new("https://keyrunes.example.com")?;
+    /// client.login("john@example.com", "password123", None).await?;
+    /// if let Some(url) = client.get_avatar_url("123").await? {
+    ///     println!("Avatar URL: {}", url);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_avatar_url<S: Into<String>>(&self, user_id: S) -> Result<Option<String>> {
+        let url = self.url(&endpoints::user_avatar(user_id.into()));
+        let response = self.send(self.client.get(&url), Auth::Required).await?;
+
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+
+        let avatar = self.handle_response::<Avatar>(response).await?;
+        Ok(Some(avatar.url))
+    }
+}
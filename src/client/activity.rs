@@ -0,0 +1,56 @@
+//! Account activity endpoints
+
+use super::transport::Auth;
+use super::KeyrunesClient;
+use crate::endpoints;
+use crate::error::Result;
+use crate::models::{LoginEvent, LoginHistoryFilter, Page};
+
+impl KeyrunesClient {
+    /// Gets the sign-in history of a user, most recent first.
+    ///
+    /// Useful to build "recent sign-in activity" pages. Follow
+    /// [`Page::next_cursor`] with [`LoginHistoryFilter::cursor`] to fetch
+    /// older events.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - User ID
+    /// * `filter` - Date range, outcome and pagination options
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<Page<LoginEvent>, KeyrunesError>`:
+    /// - `Ok(page)` with the matching events
+    /// - `Err(KeyrunesError::UserNotFoundError)` if the user doesn't exist
+    /// - `Err(KeyrunesError::InvalidToken)` if no token is set
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyrunes_rust_sdk::{KeyrunesClient, LoginHistoryFilter};
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = KeyrunesClient::new("https://keyrunes.example.com")?;
+    /// client.login("john@example.com", "password123", None).await?;
+    /// let page = client
+    ///     .get_login_history("123", &LoginHistoryFilter::new().limit(20))
+    ///     .await?;
+    /// for event in page.items {
+    ///     println!("{} from {:?}: {}", event.timestamp, event.ip, event.success);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_login_history<S: Into<String>>(
+        &self,
+        user_id: S,
+        filter: &LoginHistoryFilter,
+    ) -> Result<Page<LoginEvent>> {
+        let url = self.url(&endpoints::user_login_history(user_id.into()));
+        let response = self
+            .send(self.client.get(&url).query(filter), Auth::Required)
+            .await?;
+
+        self.handle_response(response).await
+    }
+}
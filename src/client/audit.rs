@@ -0,0 +1,33 @@
+//! Audit intake endpoint
+
+use super::transport::Auth;
+use super::KeyrunesClient;
+use crate::audit::AuthDecision;
+use crate::endpoints;
+use crate::error::Result;
+
+impl KeyrunesClient {
+    /// Sends access decisions to the Keyrunes audit intake.
+    ///
+    /// Usually called through [`HttpAuditSink`](crate::audit::HttpAuditSink),
+    /// which batches decisions. The current token is sent when available.
+    ///
+    /// # Arguments
+    ///
+    /// * `decisions` - The decisions to record
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<(), KeyrunesError>`:
+    /// - `Ok(())` if the decisions were accepted
+    /// - `Err(KeyrunesError::HttpError)` if there was an error in the request
+    pub async fn send_audit_events(&self, decisions: &[AuthDecision]) -> Result<()> {
+        let url = self.url(endpoints::AUDIT_EVENTS);
+        let request = self
+            .client
+            .post(&url)
+            .json(&serde_json::json!({ "events": decisions }));
+        let response = self.send(request, Auth::Optional).await?;
+        self.handle_empty_response(response).await
+    }
+}
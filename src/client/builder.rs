@@ -0,0 +1,340 @@
+//! Builder for configuring a [`KeyrunesClient`]
+
+use super::jwks::KeyCache;
+use super::memberships::MembershipCache;
+use super::network::{IpPreference, PreferenceResolver};
+use super::shutdown::Background;
+use super::token_provider::Refreshes;
+use super::token_store::{MemoryTokenStore, TokenStore};
+use super::{KeyrunesClient, SessionState, ENV_ORG_KEY, HEADER_API_VERSION, HEADER_ORG_KEY};
+use crate::claims::ClaimsMapping;
+#[cfg(feature = "dpop")]
+use crate::dpop::{Dpop, DpopKey};
+use crate::endpoints::EndpointResolver;
+use crate::entitlements::EntitlementCache;
+use crate::error::{KeyrunesError, Result};
+use reqwest::dns::Resolve;
+use reqwest::header::{HeaderMap, HeaderValue};
+use reqwest::Client;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{watch, RwLock};
+
+/// Name of the SDK, as reported to the server
+const SDK_NAME: &str = "keyrunes-rust-sdk";
+
+/// Version of the SDK, as reported to the server
+const SDK_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Header describing the SDK and its environment
+const HEADER_SDK: &str = "X-Keyrunes-SDK";
+
+/// Builder for [`KeyrunesClient`]
+///
+/// ```
+/// use keyrunes_rust_sdk::KeyrunesClient;
+///
+/// let client = KeyrunesClient::builder("https://keyrunes.example.com")
+///     .app_info("billing-service", "2.3.1")
+///     .build()
+///     .expect("Invalid configuration");
+/// ```
+#[derive(Clone)]
+pub struct KeyrunesClientBuilder {
+    base_url: String,
+    app: Option<(String, String)>,
+    locale: Option<String>,
+    api_version: Option<String>,
+    claims_mapping: Option<ClaimsMapping>,
+    token_store: Option<Arc<dyn TokenStore>>,
+    endpoints: EndpointResolver,
+    auto_refresh: bool,
+    membership_fallback: Option<Duration>,
+    strict: bool,
+    ip_preference: IpPreference,
+    dns_resolver: Option<Arc<dyn Resolve>>,
+    connect_timeout: Option<Duration>,
+    #[cfg(feature = "dpop")]
+    dpop: Option<Arc<Dpop>>,
+}
+
+impl KeyrunesClientBuilder {
+    /// Creates a builder for a client of the API at `base_url`.
+    pub fn new<S: Into<String>>(base_url: S) -> Self {
+        Self {
+            base_url: base_url.into(),
+            app: None,
+            locale: None,
+            api_version: None,
+            claims_mapping: None,
+            token_store: None,
+            endpoints: EndpointResolver::default(),
+            auto_refresh: true,
+            membership_fallback: None,
+            strict: false,
+            ip_preference: IpPreference::System,
+            dns_resolver: None,
+            connect_timeout: None,
+            #[cfg(feature = "dpop")]
+            dpop: None,
+        }
+    }
+
+    /// Identifies the application using the SDK.
+    ///
+    /// The name and version are prepended to the `User-Agent` and included
+    /// in the `X-Keyrunes-SDK` header.
+    pub fn app_info<N: Into<String>, V: Into<String>>(mut self, name: N, version: V) -> Self {
+        self.app = Some((name.into(), version.into()));
+        self
+    }
+
+    /// Sets the preferred locale of server messages (e.g., "pt-BR").
+    ///
+    /// Sent as `Accept-Language`. Error messages are localized when the
+    /// server supports the locale; use [`KeyrunesError::code`] for
+    /// programmatic handling.
+    pub fn locale<S: Into<String>>(mut self, locale: S) -> Self {
+        self.locale = Some(locale.into());
+        self
+    }
+
+    /// Pins the version of the Keyrunes API (e.g., "2").
+    ///
+    /// Sent as `X-Keyrunes-Api-Version` on every request. Responses must
+    /// echo a compatible version in the same header (the same major version,
+    /// with a minor version at least the pinned one: "2.3" accepts "2.3" and
+    /// "2.5" but not "2.1" or "3.0"); others fail with
+    /// [`KeyrunesError::IncompatibleApiVersion`], so a server
+    /// upgrade cannot silently change the behavior the application relies on.
+    pub fn api_version<S: Into<String>>(mut self, version: S) -> Self {
+        self.api_version = Some(version.into());
+        self
+    }
+
+    /// Sets the address family used to reach the server when its hostname
+    /// resolves to both IPv4 and IPv6 addresses.
+    ///
+    /// In dual-stack networks where the IPv6 route is unreliable,
+    /// `IpPreference::PreferIpv4` tries IPv4 first (starting IPv6 in
+    /// parallel if IPv4 has not connected within 300 ms) and
+    /// `IpPreference::Ipv4Only` never tries IPv6.
+    pub fn ip_preference(mut self, preference: IpPreference) -> Self {
+        self.ip_preference = preference;
+        self
+    }
+
+    /// Resolves hostnames with a custom resolver instead of the system one.
+    ///
+    /// Useful in locked-down environments where the system resolver cannot
+    /// resolve the Keyrunes hostname: use `HickoryResolver` (feature
+    /// `hickory-dns`) to query custom nameservers, with optional DNSSEC
+    /// validation, or implement [`Resolve`]. The [`Self::ip_preference`]
+    /// applies to the addresses it returns.
+    pub fn dns_resolver<R: Resolve + 'static>(mut self, resolver: R) -> Self {
+        self.dns_resolver = Some(Arc::new(resolver));
+        self
+    }
+
+    /// Sets the time allowed to establish a connection.
+    ///
+    /// The timeout is split between the resolved addresses of each family,
+    /// so an unreachable address is abandoned quickly and the next one is
+    /// tried, instead of waiting for the operating system's TCP timeout.
+    /// The other family does not wait for it: it is tried 300 ms after the
+    /// first attempt (see [`Self::ip_preference`]).
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Sets the claims carrying user attributes in this tenant's tokens.
+    ///
+    /// Defaults to [`ClaimsMapping::from_env`].
+    pub fn claims_mapping(mut self, mapping: ClaimsMapping) -> Self {
+        self.claims_mapping = Some(mapping);
+        self
+    }
+
+    /// Sets where the client saves the tokens it is issued.
+    ///
+    /// Defaults to a [`MemoryTokenStore`].
+    pub fn token_store<T: TokenStore + 'static>(mut self, store: T) -> Self {
+        self.token_store = Some(Arc::new(store));
+        self
+    }
+
+    /// Sets how endpoint paths map to the paths served by the deployment
+    /// (API prefix and version).
+    ///
+    /// Defaults to the paths listed in [`crate::endpoints`].
+    pub fn endpoints(mut self, resolver: EndpointResolver) -> Self {
+        self.endpoints = resolver;
+        self
+    }
+
+    /// Enables or disables automatic token refresh (enabled by default).
+    ///
+    /// When the client holds a refresh token, a token expiring within 30
+    /// seconds is refreshed before a request is sent, and a request
+    /// rejected with 401 is retried once with a refreshed token. The new
+    /// tokens are stored like those of
+    /// [`TokenProvider::refresh`](super::TokenProvider::refresh).
+    pub fn auto_refresh(mut self, enabled: bool) -> Self {
+        self.auto_refresh = enabled;
+        self
+    }
+
+    /// Answers group checks from the last known memberships while Keyrunes
+    /// is unreachable (disabled by default).
+    ///
+    /// When [`KeyrunesClient::has_group`] fails with a network error or a
+    /// server error, the last result Keyrunes gave for the same user and
+    /// group is returned instead, if it is at most `max_staleness` old.
+    /// Such degraded decisions are counted by
+    /// [`KeyrunesClient::degraded_decisions`] and, with the `tracing`
+    /// feature, logged under the `keyrunes::degraded` target and marked on
+    /// the request span (`keyrunes.degraded`). Keep the window short: a
+    /// membership revoked during an outage is honored until it expires.
+    pub fn membership_fallback(mut self, max_staleness: Duration) -> Self {
+        self.membership_fallback = Some(max_staleness);
+        self
+    }
+
+    /// Rejects responses that do not match the SDK's models exactly.
+    ///
+    /// By default, unknown fields are ignored and values from older or
+    /// newer servers are coerced (numbers sent as strings, legacy
+    /// `access_token` field). In strict mode these responses fail with
+    /// `SerializationError` instead, which helps detecting contract drift
+    /// in staging; production clients should stay lenient.
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Sends DPoP proofs signed with `key` instead of plain bearer tokens
+    /// (feature `dpop`).
+    #[cfg(feature = "dpop")]
+    pub fn dpop(mut self, key: DpopKey) -> Self {
+        self.dpop = Some(Arc::new(Dpop::new(key)));
+        self
+    }
+
+    /// Builds the client.
+    ///
+    /// Fails with `InvalidUrl` if the base URL is invalid, or `Other` if the
+    /// application info, locale or API version cannot be sent as a header.
+    pub fn build(self) -> Result<KeyrunesClient> {
+        url::Url::parse(&self.base_url)?;
+
+        let mut headers = HeaderMap::new();
+        if let Ok(org_key) = std::env::var(ENV_ORG_KEY) {
+            if let Ok(value) = HeaderValue::from_str(&org_key) {
+                headers.insert(HEADER_ORG_KEY, value);
+            }
+        }
+        headers.insert(HEADER_SDK, header_value(&self.sdk_header())?);
+        if let Some(locale) = &self.locale {
+            headers.insert(reqwest::header::ACCEPT_LANGUAGE, header_value(locale)?);
+        }
+        if let Some(version) = &self.api_version {
+            headers.insert(HEADER_API_VERSION, header_value(version)?);
+        }
+
+        Ok(KeyrunesClient {
+            base_url: self.base_url.trim_end_matches('/').to_string(),
+            endpoints: Arc::new(self.endpoints.clone()),
+            api_version: self.api_version.clone(),
+            org_id: None,
+            client: self.http_client(headers)?,
+            token: Arc::new(RwLock::new(None)),
+            token_updates: Arc::new(watch::channel(None).0),
+            session: Arc::new(RwLock::new(SessionState::default())),
+            background: Arc::new(Background::default()),
+            refreshes: Arc::new(Refreshes::default()),
+            keys: Arc::new(KeyCache::default()),
+            entitlements: Arc::new(EntitlementCache::default()),
+            claims_mapping: Arc::new(self.claims_mapping.unwrap_or_else(ClaimsMapping::from_env)),
+            token_store: self
+                .token_store
+                .unwrap_or_else(|| Arc::new(MemoryTokenStore::new())),
+            last_response: Arc::default(),
+            auto_refresh: self.auto_refresh,
+            strict: self.strict,
+            memberships: self
+                .membership_fallback
+                .map(|max_staleness| Arc::new(MembershipCache::new(max_staleness))),
+            break_glass: None,
+            #[cfg(feature = "dpop")]
+            dpop: self.dpop,
+        })
+    }
+
+    /// HTTP client with the SDK headers and the connection strategy
+    fn http_client(&self, headers: HeaderMap) -> Result<Client> {
+        let mut builder = Client::builder()
+            .user_agent(header_value(&self.user_agent())?)
+            .default_headers(headers);
+        if self.ip_preference != IpPreference::System || self.dns_resolver.is_some() {
+            builder = builder.dns_resolver(Arc::new(PreferenceResolver {
+                preference: self.ip_preference,
+                inner: self.dns_resolver.clone(),
+            }));
+        }
+        if let Some(timeout) = self.connect_timeout {
+            builder = builder.connect_timeout(timeout);
+        }
+        Ok(builder.build()?)
+    }
+
+    /// `User-Agent` value, e.g. `billing-service/2.3.1 keyrunes-rust-sdk/0.1.0`
+    fn user_agent(&self) -> String {
+        match &self.app {
+            Some((name, version)) => {
+                format!("{}/{} {}/{}", name, version, SDK_NAME, SDK_VERSION)
+            }
+            None => format!("{}/{}", SDK_NAME, SDK_VERSION),
+        }
+    }
+
+    /// `X-Keyrunes-SDK` value, e.g. `name=keyrunes-rust-sdk; version=0.1.0; os=linux; arch=x86_64`
+    fn sdk_header(&self) -> String {
+        let mut value = format!(
+            "name={}; version={}; os={}; arch={}",
+            SDK_NAME,
+            SDK_VERSION,
+            std::env::consts::OS,
+            std::env::consts::ARCH
+        );
+        if let Some((name, version)) = &self.app {
+            value.push_str(&format!("; app={}/{}", name, version));
+        }
+        value
+    }
+}
+
+impl std::fmt::Debug for KeyrunesClientBuilder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("KeyrunesClientBuilder")
+            .field("base_url", &self.base_url)
+            .field("app", &self.app)
+            .field("locale", &self.locale)
+            .field("api_version", &self.api_version)
+            .field("claims_mapping", &self.claims_mapping)
+            .field("token_store", &self.token_store.is_some())
+            .field("endpoints", &self.endpoints)
+            .field("auto_refresh", &self.auto_refresh)
+            .field("membership_fallback", &self.membership_fallback)
+            .field("strict", &self.strict)
+            .field("ip_preference", &self.ip_preference)
+            .field("dns_resolver", &self.dns_resolver.is_some())
+            .field("connect_timeout", &self.connect_timeout)
+            .finish_non_exhaustive()
+    }
+}
+
+fn header_value(value: &str) -> Result<HeaderValue> {
+    HeaderValue::from_str(value)
+        .map_err(|_| KeyrunesError::Other(format!("Invalid header value: {}", value)))
+}
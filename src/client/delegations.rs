@@ -0,0 +1,113 @@
+//! Delegated authorization endpoints
+
+use super::transport::Auth;
+use super::KeyrunesClient;
+use crate::endpoints;
+use crate::error::Result;
+use crate::models::{Delegation, ResourceRef};
+use chrono::{DateTime, Utc};
+
+impl KeyrunesClient {
+    /// Shares access to a resource with another user.
+    ///
+    /// The sharing user must hold the granted permissions themselves.
+    ///
+    /// # Arguments
+    ///
+    /// * `resource` - Shared resource
+    /// * `from_user_id` - User sharing the resource
+    /// * `to_user_id` - User receiving access
+    /// * `permissions` - Permissions granted (e.g., "read", "comment")
+    /// * `expires_at` - When the access ends (`None` for permanent access)
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<Delegation, KeyrunesError>`:
+    /// - `Ok(delegation)` with the created delegation
+    /// - `Err(KeyrunesError::AuthorizationError)` if the sharing user lacks a permission
+    /// - `Err(KeyrunesError::UserNotFoundError)` if either user doesn't exist
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyrunes_rust_sdk::{KeyrunesClient, ResourceRef};
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = KeyrunesClient::new("https://keyrunes.example.com")?;
+    /// client.login("john@example.com", "password123", None).await?;
+    /// let document = ResourceRef::new("document", "42");
+    /// let expires_at = chrono::Utc::now() + chrono::Duration::days(7);
+    /// let delegation = client
+    ///     .grant_access(&document, "123", "456", ["read", "comment"], Some(expires_at))
+    ///     .await?;
+    /// println!("Shared ({})", delegation.id);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn grant_access<F, T, I, P>(
+        &self,
+        resource: &ResourceRef,
+        from_user_id: F,
+        to_user_id: T,
+        permissions: I,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> Result<Delegation>
+    where
+        F: Into<String>,
+        T: Into<String>,
+        I: IntoIterator<Item = P>,
+        P: Into<String>,
+    {
+        let url = self.url(endpoints::DELEGATIONS);
+        let permissions: Vec<String> = permissions.into_iter().map(Into::into).collect();
+        let response = self
+            .send(
+                self.client.post(&url).json(&serde_json::json!({
+                    "resource": resource,
+                    "from_user_id": from_user_id.into(),
+                    "to_user_id": to_user_id.into(),
+                    "permissions": permissions,
+                    "expires_at": expires_at,
+                })),
+                Auth::Required,
+            )
+            .await?;
+
+        self.handle_response(response).await
+    }
+
+    /// Revokes access previously shared with [`grant_access`](Self::grant_access).
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<(), KeyrunesError>`:
+    /// - `Ok(())` if the delegation was revoked
+    /// - `Err(KeyrunesError::AuthorizationError)` if not allowed to revoke it
+    pub async fn revoke_access(&self, delegation_id: &str) -> Result<()> {
+        let url = self.url(&endpoints::delegation(delegation_id));
+        let response = self.send(self.client.delete(&url), Auth::Required).await?;
+
+        self.handle_empty_response(response).await
+    }
+
+    /// Lists the delegations of a resource (who it is shared with).
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<Vec<Delegation>, KeyrunesError>`:
+    /// - `Ok(delegations)` with the active delegations
+    /// - `Err(KeyrunesError::InvalidToken)` if no token is set
+    pub async fn list_delegations(&self, resource: &ResourceRef) -> Result<Vec<Delegation>> {
+        let url = self.url(endpoints::DELEGATIONS);
+        let response = self
+            .send(
+                self.client.get(&url).query(&[
+                    ("resource_type", resource.resource_type.as_str()),
+                    ("resource_id", resource.id.as_str()),
+                ]),
+                Auth::Required,
+            )
+            .await?;
+
+        self.handle_response(response).await
+    }
+}
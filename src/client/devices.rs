@@ -0,0 +1,94 @@
+//! Device management endpoints
+
+use super::KeyrunesClient;
+use crate::error::Result;
+use crate::models::Device;
+
+impl KeyrunesClient {
+    /// Lists the devices a user has signed in from.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - User ID
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<Vec<Device>, KeyrunesError>`:
+    /// - `Ok(devices)` if the devices were successfully retrieved
+    /// - `Err(KeyrunesError::UserNotFoundError)` if user doesn't exist
+    /// - `Err(KeyrunesError::InvalidToken)` if not authenticated
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyrunes_rust_sdk::KeyrunesClient;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = KeyrunesClient::new("https://keyrunes.example.com")?;
+    /// client.login("user@example.com", "password123", None).await?;
+    /// for device in client.list_devices("123").await? {
+    ///     println!("{} (trusted: {})", device.name, device.trusted);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn list_devices<S: Into<String>>(&self, user_id: S) -> Result<Vec<Device>> {
+        self.generated().list_devices(&user_id.into()).await
+    }
+
+    /// Marks a device as trusted ("remember this device").
+    ///
+    /// Trusted devices may skip MFA challenges according to the tenant policy.
+    ///
+    /// # Arguments
+    ///
+    /// * `device_id` - Device ID
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<Device, KeyrunesError>`:
+    /// - `Ok(device)` with the updated device
+    /// - `Err(KeyrunesError::InvalidToken)` if not authenticated
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyrunes_rust_sdk::KeyrunesClient;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = KeyrunesClient::new("https://keyrunes.example.com")?;
+    /// client.login("user@example.com", "password123", None).await?;
+    /// let device = client.trust_device("device-1").await?;
+    /// assert!(device.trusted);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn trust_device<S: Into<String>>(&self, device_id: S) -> Result<Device> {
+        self.generated().trust_device(&device_id.into()).await
+    }
+
+    /// Revokes a device, signing it out and removing its trusted status.
+    ///
+    /// # Arguments
+    ///
+    /// * `device_id` - Device ID
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<(), KeyrunesError>`:
+    /// - `Ok(())` if the device was revoked
+    /// - `Err(KeyrunesError::InvalidToken)` if not authenticated
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyrunes_rust_sdk::KeyrunesClient;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = KeyrunesClient::new("https://keyrunes.example.com")?;
+    /// client.login("user@example.com", "password123", None).await?;
+    /// client.revoke_device("device-1").await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn revoke_device<S: Into<String>>(&self, device_id: S) -> Result<()> {
+        self.generated().revoke_device(&device_id.into()).await
+    }
+}
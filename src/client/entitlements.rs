@@ -0,0 +1,77 @@
+//! Entitlement endpoints
+
+use super::transport::Auth;
+use super::KeyrunesClient;
+use crate::endpoints;
+use crate::error::Result;
+use crate::models::Entitlement;
+
+impl KeyrunesClient {
+    /// Gets the entitlements of a user.
+    ///
+    /// Results are cached per user for
+    /// [`ENTITLEMENT_CACHE_TTL`](crate::entitlements::ENTITLEMENT_CACHE_TTL);
+    /// call [`invalidate_entitlements`](Self::invalidate_entitlements) after
+    /// a plan change to see it immediately.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - User ID
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<Vec<Entitlement>, KeyrunesError>`:
+    /// - `Ok(entitlements)` with the user's entitlements
+    /// - `Err(KeyrunesError::UserNotFoundError)` if the user doesn't exist
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyrunes_rust_sdk::KeyrunesClient;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = KeyrunesClient::new("https://keyrunes.example.com")?;
+    /// for entitlement in client.get_entitlements("123").await? {
+    ///     println!("{}: {:?}", entitlement.key, entitlement.value);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_entitlements<S: Into<String>>(&self, user_id: S) -> Result<Vec<Entitlement>> {
+        let user_id = user_id.into();
+        if let Some(entitlements) = self.entitlements.get(&user_id).await {
+            return Ok(entitlements);
+        }
+
+        let url = self.url(&endpoints::user_entitlements(&user_id));
+        let request = self.client.get(&url);
+        let response = self.send(request, Auth::Optional).await?;
+        let entitlements: Vec<Entitlement> = self.handle_response(response).await?;
+
+        self.entitlements.put(&user_id, entitlements.clone()).await;
+        Ok(entitlements)
+    }
+
+    /// Checks whether a user holds an active entitlement.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - User ID
+    /// * `key` - Entitlement key (e.g., "advanced_reports")
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<bool, KeyrunesError>`:
+    /// - `Ok(true)` if the entitlement is enabled and not expired
+    /// - `Ok(false)` otherwise
+    pub async fn has_entitlement<S: Into<String>>(&self, user_id: S, key: &str) -> Result<bool> {
+        let entitlements = self.get_entitlements(user_id).await?;
+        Ok(entitlements
+            .iter()
+            .any(|entitlement| entitlement.key == key && entitlement.is_active()))
+    }
+
+    /// Drops the cached entitlements of a user.
+    pub async fn invalidate_entitlements(&self, user_id: &str) {
+        self.entitlements.invalidate(user_id).await;
+    }
+}
@@ -0,0 +1,204 @@
+//! Export download endpoints
+
+use super::transport::Auth;
+use super::KeyrunesClient;
+use crate::endpoints;
+use crate::error::{KeyrunesError, Result};
+use crate::job::Job;
+use crate::models::{ExportArtifact, ExportDownload};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use tokio::io::AsyncReadExt;
+
+/// Response header carrying the SHA-256 checksum (hex) of an export artifact
+const CHECKSUM_HEADER: &str = "X-Checksum-Sha256";
+
+impl KeyrunesClient {
+    /// Starts an export job.
+    ///
+    /// Once the job succeeds, download the artifact with
+    /// [`download_export`](Self::download_export).
+    ///
+    /// # Arguments
+    ///
+    /// * `resource` - Resource to export (e.g., "users", "groups")
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<Job<ExportArtifact>, KeyrunesError>`:
+    /// - `Ok(job)` with a handle to the export job
+    /// - `Err(KeyrunesError::AuthorizationError)` if not allowed to export the resource
+    /// - `Err(KeyrunesError::InvalidToken)` if no token is set
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyrunes_rust_sdk::KeyrunesClient;
+    /// # use std::time::Duration;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = KeyrunesClient::new("https://keyrunes.example.com")?;
+    /// client.login("admin@example.com", "password123", None).await?;
+    /// let job = client.start_export("users").await?;
+    /// job.await_completion(Duration::from_secs(2), Duration::from_secs(300))
+    ///     .await?;
+    /// client.download_export(job.id(), "users.csv").await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn start_export<S: Into<String>>(&self, resource: S) -> Result<Job<ExportArtifact>> {
+        let url = self.url(endpoints::EXPORTS);
+        let response = self
+            .send(
+                self.client
+                    .post(&url)
+                    .json(&serde_json::json!({ "resource": resource.into() })),
+                Auth::Required,
+            )
+            .await?;
+
+        self.job_from_response(response).await
+    }
+
+    /// Downloads the artifact of an export job to a file.
+    ///
+    /// The artifact is streamed to `<path>.part` and moved to `path` once
+    /// complete. If a previous download was interrupted, the partial file
+    /// is resumed with a `Range` request. When the server announces a
+    /// checksum (`X-Checksum-Sha256` header), the file is validated before
+    /// being moved; a corrupt partial file is deleted.
+    ///
+    /// # Arguments
+    ///
+    /// * `job_id` - ID of the export job
+    /// * `path` - Destination file
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<ExportDownload, KeyrunesError>`:
+    /// - `Ok(download)` with the size and checksum of the file
+    /// - `Err(KeyrunesError::ChecksumMismatch)` if the file is corrupt
+    /// - `Err(KeyrunesError::HttpError)` if the server fails; the partial file is kept for the next attempt
+    /// - `Err(KeyrunesError::InvalidToken)` if no token is set
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyrunes_rust_sdk::KeyrunesClient;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = KeyrunesClient::new("https://keyrunes.example.com")?;
+    /// client.login("admin@example.com", "password123", None).await?;
+    /// let download = client.download_export("job-123", "users.csv").await?;
+    /// println!("Downloaded {} bytes ({})", download.bytes, download.sha256);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn download_export<S: Into<String>, P: AsRef<Path>>(
+        &self,
+        job_id: S,
+        path: P,
+    ) -> Result<ExportDownload> {
+        let url = self.url(&endpoints::export_download(job_id.into()));
+        let path = path.as_ref();
+        let partial = partial_path(path);
+
+        let mut offset = match tokio::fs::metadata(&partial).await {
+            Ok(metadata) => metadata.len(),
+            Err(_) => 0,
+        };
+
+        let response = loop {
+            let mut request = self.client.get(&url);
+            if offset > 0 {
+                request = request.header(reqwest::header::RANGE, format!("bytes={}-", offset));
+            }
+            let response = self.send(request, Auth::Required).await?;
+
+            // The partial file is no longer valid for the artifact: start over.
+            if offset > 0 && response.status() == reqwest::StatusCode::RANGE_NOT_SATISFIABLE {
+                tokio::fs::remove_file(&partial).await?;
+                offset = 0;
+                continue;
+            }
+            break response;
+        };
+
+        // The partial file is only touched once the server sent the artifact.
+        let status = response.status();
+        let resumed = match status {
+            reqwest::StatusCode::OK => false,
+            reqwest::StatusCode::PARTIAL_CONTENT if offset > 0 => true,
+            _ if !status.is_success() => {
+                let url = response.url().clone();
+                let body = response.text().await?;
+                return Err(self.handle_error(status, &body, &url));
+            }
+            _ => {
+                return Err(KeyrunesError::HttpError(format!(
+                    "HTTP {}: unexpected response to an export download",
+                    status.as_u16()
+                )))
+            }
+        };
+        let expected = response
+            .headers()
+            .get(CHECKSUM_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .map(|value| value.trim().to_ascii_lowercase());
+
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .write(true)
+            .append(resumed)
+            .truncate(!resumed)
+            .open(&partial)
+            .await?;
+        self.write_response(response, &mut file, None).await?;
+        drop(file);
+
+        let (bytes, sha256) = file_sha256(&partial).await?;
+        if let Some(expected) = expected.filter(|expected| *expected != sha256) {
+            tokio::fs::remove_file(&partial).await?;
+            return Err(KeyrunesError::ChecksumMismatch {
+                expected,
+                actual: sha256,
+            });
+        }
+
+        tokio::fs::rename(&partial, path).await?;
+        Ok(ExportDownload {
+            bytes,
+            sha256,
+            resumed,
+        })
+    }
+}
+
+/// Path of the partial file used while downloading to `path`
+fn partial_path(path: &Path) -> PathBuf {
+    let mut partial = path.as_os_str().to_owned();
+    partial.push(".part");
+    PathBuf::from(partial)
+}
+
+/// Computes the size and SHA-256 checksum (lowercase hex) of a file
+async fn file_sha256(path: &Path) -> Result<(u64, String)> {
+    let mut file = tokio::fs::File::open(path).await?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; 64 * 1024];
+    let mut bytes = 0u64;
+    loop {
+        let read = file.read(&mut buffer).await?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+        bytes += read as u64;
+    }
+
+    let sha256 = hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect();
+    Ok((bytes, sha256))
+}
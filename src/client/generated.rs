@@ -0,0 +1,71 @@
+//! Low-level endpoint functions generated from the OpenAPI spec
+//!
+//! [`GeneratedClient`] exposes every operation of the vendored OpenAPI spec
+//! (`openapi/keyrunes.json`; see `openapi/README.md` for where it comes
+//! from) as a typed method named after its `operationId`, generated by the
+//! build script. Methods take path parameters, optional query parameters
+//! and the request body as arguments. They send a single request and
+//! return the decoded response; the higher-level API of [`KeyrunesClient`]
+//! adds pagination, jobs, retries and validation on top of them.
+//!
+//! Request and response bodies use the SDK's models where the overlay
+//! (`openapi/rust-types.json`) maps them; the other bodies are generated in
+//! this module.
+//!
+//! ## Quick Start
+//!
+//! ```
+//! use keyrunes_rust_sdk::KeyrunesClient;
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = KeyrunesClient::new("https://keyrunes.example.com")?;
+//! client.login("admin@example.com", "password123", None).await?;
+//! let relations = client
+//!     .generated()
+//!     .list_relations(Some("document"), Some("doc-1"))
+//!     .await?;
+//! println!("{} relations", relations.len());
+//! # Ok(())
+//! # }
+//! ```
+
+use super::transport::{Auth, RequestBody};
+use super::KeyrunesClient;
+use crate::admin::encode_path_segment;
+use crate::error::Result;
+
+/// Handle to the generated endpoint functions
+#[derive(Clone, Copy)]
+pub struct GeneratedClient<'a> {
+    client: &'a KeyrunesClient,
+}
+
+impl<'a> GeneratedClient<'a> {
+    pub(crate) fn new(client: &'a KeyrunesClient) -> Self {
+        Self { client }
+    }
+
+    /// Reads a binary response body.
+    async fn bytes(&self, response: reqwest::Response) -> Result<Vec<u8>> {
+        let status = response.status();
+        let url = response.url().clone();
+
+        if status.is_success() {
+            Ok(response.bytes().await?.to_vec())
+        } else {
+            let body = response.text().await?;
+            Err(self.client.handle_error(status, &body, &url))
+        }
+    }
+}
+
+/// Appends a query string to a path, unless it is empty
+fn with_query(path: String, query: String) -> String {
+    if query.is_empty() {
+        path
+    } else {
+        format!("{}?{}", path, query)
+    }
+}
+
+include!(concat!(env!("OUT_DIR"), "/generated.rs"));
@@ -0,0 +1,60 @@
+//! Bulk import endpoints
+
+use super::transport::{Auth, RequestBody};
+use super::KeyrunesClient;
+use crate::endpoints;
+use crate::error::Result;
+use crate::job::Job;
+use crate::models::ImportSummary;
+
+impl KeyrunesClient {
+    /// Starts a bulk import of users from a CSV file.
+    ///
+    /// # Arguments
+    ///
+    /// * `csv` - Contents of the CSV file
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<Job<ImportSummary>, KeyrunesError>`:
+    /// - `Ok(job)` with a handle to the import job
+    /// - `Err(KeyrunesError::AuthorizationError)` if not allowed to import users
+    /// - `Err(KeyrunesError::InvalidToken)` if no token is set
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyrunes_rust_sdk::KeyrunesClient;
+    /// # use std::time::Duration;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = KeyrunesClient::new("https://keyrunes.example.com")?;
+    /// client.login("admin@example.com", "password123", None).await?;
+    /// let csv = std::fs::read("users.csv")?;
+    /// let job = client.import_users_csv(csv).await?;
+    /// let summary = job
+    ///     .await_completion(Duration::from_secs(2), Duration::from_secs(600))
+    ///     .await?;
+    /// println!("{} created, {} failed", summary.created, summary.failed);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn import_users_csv<B: Into<Vec<u8>>>(&self, csv: B) -> Result<Job<ImportSummary>> {
+        let body = RequestBody::File {
+            field: "file".to_string(),
+            file_name: "users.csv".to_string(),
+            data: csv.into(),
+            content_type: "text/csv".to_string(),
+        };
+        let response = self
+            .send_request(
+                reqwest::Method::POST,
+                endpoints::IMPORTS_USERS,
+                body,
+                Auth::Required,
+                None,
+            )
+            .await?;
+
+        self.job_from_response(response).await
+    }
+}
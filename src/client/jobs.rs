@@ -0,0 +1,57 @@
+//! Background job endpoints
+
+use super::transport::Auth;
+use super::KeyrunesClient;
+use crate::endpoints;
+use crate::error::Result;
+use crate::job::{Job, JobCreated, JobStatus};
+use serde::de::DeserializeOwned;
+
+impl KeyrunesClient {
+    /// Gets a handle to an existing background job.
+    ///
+    /// Useful to resume tracking a job started by another process. The
+    /// result type `T` must match the kind of job.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyrunes_rust_sdk::{ExportArtifact, KeyrunesClient};
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = KeyrunesClient::new("https://keyrunes.example.com")?;
+    /// client.login("admin@example.com", "password123", None).await?;
+    /// let job = client.job::<ExportArtifact>("job-123");
+    /// println!("State: {:?}", job.status().await?.state);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn job<T: DeserializeOwned>(&self, job_id: impl Into<String>) -> Job<T> {
+        Job::new(self.clone(), job_id.into())
+    }
+
+    /// Wraps the response of an endpoint starting a job into a [`Job`] handle.
+    pub(crate) async fn job_from_response<T: DeserializeOwned>(
+        &self,
+        response: reqwest::Response,
+    ) -> Result<Job<T>> {
+        let created: JobCreated = self.handle_response(response).await?;
+        Ok(self.job(created.id))
+    }
+
+    pub(crate) async fn job_status<T: DeserializeOwned>(
+        &self,
+        job_id: &str,
+    ) -> Result<JobStatus<T>> {
+        let url = self.url(&endpoints::job(job_id));
+        let response = self.send(self.client.get(&url), Auth::Required).await?;
+
+        self.handle_response(response).await
+    }
+
+    pub(crate) async fn cancel_job(&self, job_id: &str) -> Result<()> {
+        let url = self.url(&endpoints::job_cancel(job_id));
+        let response = self.send(self.client.post(&url), Auth::Required).await?;
+
+        self.handle_empty_response(response).await
+    }
+}
@@ -0,0 +1,224 @@
+//! Signing keys of Keyrunes and verification of the tokens it signs
+
+use super::transport::Auth;
+use super::KeyrunesClient;
+use crate::claims::Claims;
+use crate::endpoints;
+use crate::error::{KeyrunesError, Result};
+use crate::id_token::{IdToken, IdTokenClaims};
+use crate::validation::TokenValidation;
+use jsonwebtoken::jwk::{Jwk, JwkSet};
+use jsonwebtoken::{Algorithm, DecodingKey, Validation};
+use serde::de::DeserializeOwned;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+/// How long the fetched key set is reused before being fetched again
+const JWKS_CACHE_TTL: Duration = Duration::from_secs(60 * 60);
+
+/// Minimum time between two fetches triggered by an unknown key ID, so
+/// tokens naming made-up keys cannot make the client hammer Keyrunes
+const JWKS_MIN_REFETCH: Duration = Duration::from_secs(10);
+
+/// Key set of a client, shared by its clones
+#[derive(Debug, Default)]
+pub(crate) struct KeyCache {
+    keys: Mutex<Option<(Instant, Arc<JwkSet>)>>,
+}
+
+impl KeyCache {
+    /// Drops the cached key set, so the next verification fetches it again.
+    pub(crate) async fn clear(&self) {
+        *self.keys.lock().await = None;
+    }
+}
+
+impl KeyrunesClient {
+    /// Fetches the public keys Keyrunes signs its tokens with (JWKS).
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<JwkSet, KeyrunesError>`:
+    /// - `Ok(keys)` with the published key set
+    /// - `Err(KeyrunesError::NetworkError)` if there was a network error
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyrunes_rust_sdk::KeyrunesClient;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = KeyrunesClient::new("https://keyrunes.example.com")?;
+    /// for key in client.get_jwks().await?.keys {
+    ///     println!("{:?}", key.common.key_id);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_jwks(&self) -> Result<JwkSet> {
+        let url = self.url(endpoints::JWKS);
+        let response = self.send(self.client.get(&url), Auth::None).await?;
+
+        self.handle_response(response).await
+    }
+
+    /// Verifies an OIDC ID token issued to the application `client_id`.
+    ///
+    /// The token must be signed by Keyrunes, issued by it (the client's
+    /// base URL) to `client_id`, unexpired, and carry `nonce` when one was
+    /// sent in the authorization request. With several audiences, its `azp`
+    /// must be `client_id`.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<IdToken, KeyrunesError>`:
+    /// - `Ok(id_token)` with the verified claims
+    /// - `Err(KeyrunesError::InvalidToken)` if the token fails any check
+    /// - `Err(KeyrunesError::NetworkError)` if the signing keys could not be fetched
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyrunes_rust_sdk::KeyrunesClient;
+    /// # async fn example(raw: &str) -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = KeyrunesClient::new("https://keyrunes.example.com")?;
+    /// let id_token = client.verify_id_token(raw, "billing-app", Some("n-0S6_WzA2Mj")).await?;
+    /// println!("{}", id_token.claims().sub);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn verify_id_token(
+        &self,
+        id_token: &str,
+        client_id: &str,
+        nonce: Option<&str>,
+    ) -> Result<IdToken> {
+        let mut validation = Validation::new(Algorithm::RS256);
+        validation.set_issuer(&[&self.base_url]);
+        validation.set_audience(&[client_id]);
+        validation.set_required_spec_claims(&["exp", "iss", "aud", "sub"]);
+
+        let claims: IdTokenClaims = self.verify_signed(id_token, validation).await?;
+        let azp_matches = match &claims.azp {
+            Some(azp) => azp == client_id,
+            None => claims.aud.len() == 1,
+        };
+        let nonce_matches = nonce.is_none_or(|nonce| claims.nonce.as_deref() == Some(nonce));
+        if !azp_matches || !nonce_matches {
+            return Err(KeyrunesError::InvalidToken);
+        }
+        Ok(IdToken::new(id_token.to_string(), claims))
+    }
+
+    /// Validates an access token locally, without calling Keyrunes.
+    ///
+    /// Checks the signature against the Keyrunes signing keys, the expiry,
+    /// the issuer and the audience (see [`TokenValidation`]), and returns
+    /// the claims, with the user attributes read through the client's
+    /// [`ClaimsMapping`](crate::claims::ClaimsMapping).
+    ///
+    /// Revoked tokens stay valid until they expire; validate with Keyrunes
+    /// (e.g., [`KeyrunesClient::get_current_user`]) where that matters.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<Claims, KeyrunesError>`:
+    /// - `Ok(claims)` if the token is valid
+    /// - `Err(KeyrunesError::InvalidAudience)` if the token is not addressed to an accepted audience
+    /// - `Err(KeyrunesError::InvalidToken)` if the signature, expiry or issuer is invalid
+    /// - `Err(KeyrunesError::NetworkError)` if the signing keys could not be fetched
+    pub async fn validate_token(
+        &self,
+        token: &str,
+        validation: &TokenValidation,
+    ) -> Result<Claims> {
+        let payload = self.validate_payload(token, validation).await?;
+        Claims::from_payload(&payload, &self.claims_mapping)
+    }
+
+    /// Validates an access token locally, returning its raw payload.
+    pub(crate) async fn validate_payload(
+        &self,
+        token: &str,
+        validation: &TokenValidation,
+    ) -> Result<serde_json::Value> {
+        let mut jwt = Validation::new(Algorithm::RS256);
+        jwt.leeway = validation.leeway().as_secs();
+        jwt.validate_aud = false;
+        jwt.set_required_spec_claims(&["exp", "iss"]);
+        if validation.issuers().is_empty() {
+            jwt.set_issuer(&[&self.base_url]);
+        } else {
+            jwt.set_issuer(validation.issuers());
+        }
+
+        let payload: serde_json::Value = self.verify_signed(token, jwt).await?;
+        validation.check_audience(&payload)?;
+        Ok(payload)
+    }
+
+    /// Verifies the signature of `token` with the Keyrunes key it names,
+    /// then its claims with `validation`, and returns the claims.
+    ///
+    /// The key set is cached; a token naming an unknown key ID (after a key
+    /// rotation) triggers a new fetch. Only asymmetric algorithms are
+    /// accepted, and the algorithm of the token must match its key. Fails
+    /// with `InvalidToken` if the token cannot be verified.
+    pub(crate) async fn verify_signed<T: DeserializeOwned>(
+        &self,
+        token: &str,
+        mut validation: Validation,
+    ) -> Result<T> {
+        let header = jsonwebtoken::decode_header(token).map_err(|_| KeyrunesError::InvalidToken)?;
+        if matches!(
+            header.alg,
+            Algorithm::HS256 | Algorithm::HS384 | Algorithm::HS512
+        ) {
+            return Err(KeyrunesError::InvalidToken);
+        }
+
+        let jwk = self.signing_key(header.kid.as_deref()).await?;
+        if let Some(algorithm) = jwk.common.key_algorithm {
+            if algorithm.to_string().parse::<Algorithm>().ok() != Some(header.alg) {
+                return Err(KeyrunesError::InvalidToken);
+            }
+        }
+
+        let key = DecodingKey::from_jwk(&jwk).map_err(|_| KeyrunesError::InvalidToken)?;
+        validation.algorithms = vec![header.alg];
+        jsonwebtoken::decode::<T>(token, &key, &validation)
+            .map(|data| data.claims)
+            .map_err(|_| KeyrunesError::InvalidToken)
+    }
+
+    /// Finds the key `kid` (or the only key, for tokens naming none),
+    /// fetching the key set when it is stale or lacks the key.
+    async fn signing_key(&self, kid: Option<&str>) -> Result<Jwk> {
+        let mut cached = self.keys.keys.lock().await;
+
+        if let Some((fetched_at, keys)) = cached.as_ref() {
+            let fresh = fetched_at.elapsed() < JWKS_CACHE_TTL;
+            match find_key(keys, kid) {
+                Some(jwk) if fresh => return Ok(jwk.clone()),
+                None if fresh && fetched_at.elapsed() < JWKS_MIN_REFETCH => {
+                    return Err(KeyrunesError::InvalidToken)
+                }
+                _ => {}
+            }
+        }
+
+        let keys = Arc::new(self.get_jwks().await?);
+        *cached = Some((Instant::now(), keys.clone()));
+        find_key(&keys, kid)
+            .cloned()
+            .ok_or(KeyrunesError::InvalidToken)
+    }
+}
+
+fn find_key<'k>(keys: &'k JwkSet, kid: Option<&str>) -> Option<&'k Jwk> {
+    match kid {
+        Some(kid) => keys.find(kid),
+        None if keys.keys.len() == 1 => keys.keys.first(),
+        None => None,
+    }
+}
@@ -0,0 +1,83 @@
+//! Last known group memberships, served when Keyrunes is unreachable
+
+use super::KeyrunesClient;
+use crate::error::KeyrunesError;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Number of cached memberships above which stale entries are evicted
+const PRUNE_THRESHOLD: usize = 10_000;
+
+/// Membership checks answered by Keyrunes, by user ID and group ID
+#[derive(Debug)]
+pub(crate) struct MembershipCache {
+    max_staleness: Duration,
+    entries: Mutex<HashMap<(String, String), (Instant, bool)>>,
+    degraded: AtomicU64,
+}
+
+impl MembershipCache {
+    pub(crate) fn new(max_staleness: Duration) -> Self {
+        Self {
+            max_staleness,
+            entries: Mutex::new(HashMap::new()),
+            degraded: AtomicU64::new(0),
+        }
+    }
+
+    /// Cache of the same staleness window, without entries (for another organization)
+    pub(crate) fn empty_like(&self) -> Self {
+        Self::new(self.max_staleness)
+    }
+
+    /// Records the answer of Keyrunes.
+    pub(crate) fn put(&self, user_id: &str, group_id: &str, has_group: bool) {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        if entries.len() >= PRUNE_THRESHOLD {
+            entries.retain(|_, (checked_at, _)| checked_at.elapsed() <= self.max_staleness);
+        }
+        entries.insert(
+            (user_id.to_string(), group_id.to_string()),
+            (Instant::now(), has_group),
+        );
+    }
+
+    /// Returns the last known answer, if within the staleness window, and
+    /// reports the decision as degraded.
+    pub(crate) fn fallback(
+        &self,
+        user_id: &str,
+        group_id: &str,
+        error: &KeyrunesError,
+    ) -> Option<bool> {
+        let entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        let (checked_at, has_group) = entries
+            .get(&(user_id.to_string(), group_id.to_string()))
+            .copied()
+            .filter(|(checked_at, _)| checked_at.elapsed() <= self.max_staleness)?;
+        drop(entries);
+
+        self.degraded.fetch_add(1, Ordering::Relaxed);
+        #[cfg(feature = "tracing")]
+        crate::telemetry::record_degraded(user_id, group_id, checked_at.elapsed(), error);
+        #[cfg(not(feature = "tracing"))]
+        let _ = (checked_at, error);
+        Some(has_group)
+    }
+}
+
+impl KeyrunesClient {
+    /// Number of group checks answered from the last known memberships
+    /// because Keyrunes was unreachable (see
+    /// [`KeyrunesClientBuilder::membership_fallback`](super::KeyrunesClientBuilder::membership_fallback)).
+    ///
+    /// Always 0 when the fallback is disabled. Export it as a metric to
+    /// alert on degraded authorization.
+    pub fn degraded_decisions(&self) -> u64 {
+        self.memberships
+            .as_ref()
+            .map_or(0, |cache| cache.degraded.load(Ordering::Relaxed))
+    }
+}
@@ -0,0 +1,242 @@
+//! Multi-factor authentication endpoints
+
+use super::transport::{Auth, RequestBody};
+use super::KeyrunesClient;
+use crate::endpoints;
+use crate::error::Result;
+use crate::models::{
+    BackupCodeLogin, BackupCodes, BackupCodesStatus, LoginCredentials, PushChallenge,
+    PushChallengeState, PushChallengeStatus, Token, DEFAULT_NAMESPACE,
+};
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// Longest wait requested from the server in a single long-poll
+const PUSH_POLL_MAX_WAIT: Duration = Duration::from_secs(25);
+/// Minimum delay between polls when the server answers immediately
+const PUSH_POLL_MIN_INTERVAL: Duration = Duration::from_secs(1);
+
+impl KeyrunesClient {
+    /// Sends a push-notification approval request to the user's phone.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - User ID
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<PushChallenge, KeyrunesError>`:
+    /// - `Ok(challenge)` if the notification was sent
+    /// - `Err(KeyrunesError::UserNotFoundError)` if user doesn't exist
+    /// - `Err(KeyrunesError::HttpError)` if the user has no push-capable device
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyrunes_rust_sdk::KeyrunesClient;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = KeyrunesClient::new("https://keyrunes.example.com")?;
+    /// let challenge = client.start_push_challenge("123").await?;
+    /// println!("Approve the request on your phone ({})", challenge.challenge_id);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn start_push_challenge<S: Into<String>>(&self, user_id: S) -> Result<PushChallenge> {
+        let response = self
+            .send_request(
+                reqwest::Method::POST,
+                endpoints::MFA_PUSH,
+                RequestBody::Json(serde_json::json!({ "user_id": user_id.into() })),
+                Auth::Optional,
+                None,
+            )
+            .await?;
+
+        self.handle_response(response).await
+    }
+
+    /// Waits for the user to answer a push-notification challenge.
+    ///
+    /// Long-polls the challenge status until it is no longer pending or
+    /// `timeout` elapses. When the challenge is approved and the server
+    /// issues a token, the token becomes the client's current token.
+    ///
+    /// # Arguments
+    ///
+    /// * `challenge_id` - Challenge ID returned by [`start_push_challenge`](Self::start_push_challenge)
+    /// * `timeout` - Maximum time to wait for an answer
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<PushChallengeState, KeyrunesError>`:
+    /// - `Ok(state)` with the final status, or [`PushChallengeStatus::Pending`] on timeout
+    /// - `Err(KeyrunesError::NetworkError)` if there was a network error
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyrunes_rust_sdk::{KeyrunesClient, PushChallengeStatus};
+    /// # use std::time::Duration;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = KeyrunesClient::new("https://keyrunes.example.com")?;
+    /// let challenge = client.start_push_challenge("123").await?;
+    /// let state = client
+    ///     .await_push_approval(&challenge.challenge_id, Duration::from_secs(60))
+    ///     .await?;
+    /// if state.status == PushChallengeStatus::Approved {
+    ///     println!("Approved!");
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn await_push_approval(
+        &self,
+        challenge_id: &str,
+        timeout: Duration,
+    ) -> Result<PushChallengeState> {
+        let url = self.url(&endpoints::mfa_push_challenge(challenge_id));
+        let deadline = Instant::now() + timeout;
+
+        loop {
+            let started = Instant::now();
+            let wait = deadline
+                .saturating_duration_since(started)
+                .min(PUSH_POLL_MAX_WAIT);
+            let request = self
+                .client
+                .get(&url)
+                .query(&[("wait", wait.as_secs())])
+                .timeout(wait + Duration::from_secs(10));
+            let response = self.send(request, Auth::Optional).await?;
+            let state: PushChallengeState = self.handle_response(response).await?;
+
+            if state.status != PushChallengeStatus::Pending {
+                if let Some(token) = &state.token {
+                    self.store_issued_token(token).await?;
+                }
+                return Ok(state);
+            }
+            if Instant::now() >= deadline {
+                return Ok(state);
+            }
+
+            let next_poll = (started + PUSH_POLL_MIN_INTERVAL).min(deadline);
+            tokio::time::sleep_until(next_poll).await;
+        }
+    }
+
+    /// Generates a new set of MFA backup codes for the current user.
+    ///
+    /// Previously generated codes are invalidated. The codes are only
+    /// returned once, so they must be shown to the user immediately.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<BackupCodes, KeyrunesError>`:
+    /// - `Ok(codes)` with the new codes
+    /// - `Err(KeyrunesError::InvalidToken)` if not authenticated
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyrunes_rust_sdk::KeyrunesClient;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = KeyrunesClient::new("https://keyrunes.example.com")?;
+    /// client.login("user@example.com", "password123", None).await?;
+    /// let backup = client.generate_backup_codes().await?;
+    /// for code in &backup.codes {
+    ///     println!("{}", code);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn generate_backup_codes(&self) -> Result<BackupCodes> {
+        let url = self.url(endpoints::MFA_BACKUP_CODES);
+        let response = self.send(self.client.post(&url), Auth::Required).await?;
+
+        self.handle_response(response).await
+    }
+
+    /// Gets how many backup codes of the current user are still unused.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<BackupCodesStatus, KeyrunesError>`:
+    /// - `Ok(status)` if the status was successfully retrieved
+    /// - `Err(KeyrunesError::InvalidToken)` if not authenticated
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyrunes_rust_sdk::KeyrunesClient;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = KeyrunesClient::new("https://keyrunes.example.com")?;
+    /// client.login("user@example.com", "password123", None).await?;
+    /// let status = client.list_backup_codes_status().await?;
+    /// if status.remaining < 3 {
+    ///     println!("Running out of backup codes");
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn list_backup_codes_status(&self) -> Result<BackupCodesStatus> {
+        let url = self.url(endpoints::MFA_BACKUP_CODES);
+        let response = self.send(self.client.get(&url), Auth::Required).await?;
+
+        self.handle_response(response).await
+    }
+
+    /// Performs login using a backup code as the second factor.
+    ///
+    /// Used for account recovery when the user lost their authenticator.
+    /// The code is consumed by the server.
+    ///
+    /// # Arguments
+    ///
+    /// * `identity` - Username or email
+    /// * `password` - User password
+    /// * `code` - One of the user's unused backup codes
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<Token, KeyrunesError>`:
+    /// - `Ok(token)` if login was successful
+    /// - `Err(KeyrunesError::AuthenticationError)` if credentials or code are invalid
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyrunes_rust_sdk::KeyrunesClient;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = KeyrunesClient::new("https://keyrunes.example.com")?;
+    /// let token = client
+    ///     .login_with_backup_code("user@example.com", "password123", "ABCD-1234")
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn login_with_backup_code<S: Into<String>>(
+        &self,
+        identity: S,
+        password: S,
+        code: S,
+    ) -> Result<Token> {
+        let url = self.url(endpoints::LOGIN_BACKUP_CODE);
+        let request = BackupCodeLogin {
+            credentials: LoginCredentials {
+                identity: identity.into(),
+                password: password.into(),
+                namespace: DEFAULT_NAMESPACE.to_string(),
+            },
+            backup_code: code.into(),
+        };
+
+        let response = self
+            .send(self.client.post(&url).json(&request), Auth::None)
+            .await?;
+
+        let token = self.handle_response::<Token>(response).await?;
+        self.store_issued_token(&token).await?;
+        Ok(token)
+    }
+}
@@ -0,0 +1,187 @@
+//! Connection strategy for dual-stack networks
+//!
+//! When a hostname resolves to both IPv6 and IPv4 addresses, the family of
+//! the first resolved address is tried first, and the other family is
+//! tried in parallel when no connection is established within 300 ms or
+//! as soon as the first family fails ("happy eyeballs", as implemented by
+//! the HTTP connector). A broken route to the first family therefore
+//! delays connections by at most 300 ms, not by the connect timeout. In
+//! networks where one family is advertised but unreachable,
+//! [`IpPreference`] reorders the resolved addresses so the reachable family
+//! is tried first, or filters out the unreachable one.
+//!
+//! Hostnames are resolved by the system resolver, unless a custom
+//! [`reqwest::dns::Resolve`] implementation is set with
+//! [`KeyrunesClientBuilder::dns_resolver`](super::KeyrunesClientBuilder::dns_resolver).
+//! With feature `hickory-dns`, [`HickoryResolver`] queries custom
+//! nameservers directly, optionally validating DNSSEC signatures.
+
+use reqwest::dns::{Addrs, Resolve, Resolving};
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+/// Address family to connect with, for hosts with IPv4 and IPv6 addresses
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum IpPreference {
+    /// Order chosen by the system resolver
+    #[default]
+    System,
+    /// IPv4 addresses first, IPv6 tried in parallel after 300 ms or once IPv4 fails
+    PreferIpv4,
+    /// IPv6 addresses first, IPv4 tried in parallel after 300 ms or once IPv6 fails
+    PreferIpv6,
+    /// IPv4 addresses only
+    Ipv4Only,
+    /// IPv6 addresses only
+    Ipv6Only,
+}
+
+impl IpPreference {
+    /// Orders and filters resolved addresses according to the preference.
+    ///
+    /// Addresses of the same family keep their relative order.
+    ///
+    /// ```
+    /// use keyrunes_rust_sdk::client::IpPreference;
+    ///
+    /// let addrs = ["[::1]:443".parse().unwrap(), "127.0.0.1:443".parse().unwrap()];
+    /// let ordered = IpPreference::PreferIpv4.order(addrs);
+    /// assert!(ordered[0].is_ipv4());
+    /// ```
+    pub fn order(self, addrs: impl IntoIterator<Item = SocketAddr>) -> Vec<SocketAddr> {
+        let mut addrs: Vec<SocketAddr> = addrs.into_iter().collect();
+        match self {
+            IpPreference::System => {}
+            IpPreference::PreferIpv4 => addrs.sort_by_key(|addr| addr.is_ipv6()),
+            IpPreference::PreferIpv6 => addrs.sort_by_key(|addr| addr.is_ipv4()),
+            IpPreference::Ipv4Only => addrs.retain(SocketAddr::is_ipv4),
+            IpPreference::Ipv6Only => addrs.retain(SocketAddr::is_ipv6),
+        }
+        addrs
+    }
+}
+
+/// Resolver applying an [`IpPreference`] to the addresses resolved by the
+/// custom resolver, if any, or the system resolver
+pub(crate) struct PreferenceResolver {
+    pub(crate) preference: IpPreference,
+    pub(crate) inner: Option<Arc<dyn Resolve>>,
+}
+
+impl Resolve for PreferenceResolver {
+    fn resolve(&self, name: hyper::client::connect::dns::Name) -> Resolving {
+        let preference = self.preference;
+        let inner = self.inner.clone();
+        Box::pin(async move {
+            let host = name.as_str().to_string();
+            let resolved: Vec<SocketAddr> = match inner {
+                Some(inner) => inner.resolve(name).await?.collect(),
+                None => tokio::net::lookup_host((host.as_str(), 0)).await?.collect(),
+            };
+            let addrs = preference.order(resolved);
+            if addrs.is_empty() {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::AddrNotAvailable,
+                    format!("no address of the preferred family for {}", host),
+                )
+                .into());
+            }
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+/// Resolver querying custom nameservers, bypassing the system resolver
+/// (feature `hickory-dns`)
+///
+/// For locked-down environments where system DNS cannot resolve the
+/// Keyrunes hostname. Set it with
+/// [`KeyrunesClientBuilder::dns_resolver`](super::KeyrunesClientBuilder::dns_resolver);
+/// the [`IpPreference`] applies to the addresses it returns.
+///
+/// ```no_run
+/// use keyrunes_rust_sdk::client::HickoryResolver;
+/// use keyrunes_rust_sdk::KeyrunesClient;
+///
+/// # fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// let resolver = HickoryResolver::new(["10.0.0.53:53".parse()?]).with_dnssec(true);
+/// let client = KeyrunesClient::builder("https://keyrunes.internal")
+///     .dns_resolver(resolver)
+///     .build()?;
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "hickory-dns")]
+#[derive(Clone)]
+pub struct HickoryResolver {
+    nameservers: Vec<SocketAddr>,
+    dnssec: bool,
+    resolver: Arc<hickory_resolver::TokioAsyncResolver>,
+}
+
+#[cfg(feature = "hickory-dns")]
+impl HickoryResolver {
+    /// Resolves hostnames with `nameservers`, over UDP with TCP fallback.
+    pub fn new(nameservers: impl IntoIterator<Item = SocketAddr>) -> Self {
+        let nameservers: Vec<SocketAddr> = nameservers.into_iter().collect();
+        Self {
+            resolver: Self::resolver(&nameservers, false),
+            nameservers,
+            dnssec: false,
+        }
+    }
+
+    /// Validates DNSSEC signatures, rejecting unsigned or forged answers.
+    pub fn with_dnssec(mut self, validate: bool) -> Self {
+        self.dnssec = validate;
+        self.resolver = Self::resolver(&self.nameservers, validate);
+        self
+    }
+
+    fn resolver(
+        nameservers: &[SocketAddr],
+        dnssec: bool,
+    ) -> Arc<hickory_resolver::TokioAsyncResolver> {
+        use hickory_resolver::config::{
+            LookupIpStrategy, NameServerConfig, NameServerConfigGroup, Protocol, ResolverConfig,
+            ResolverOpts,
+        };
+
+        let mut group = NameServerConfigGroup::with_capacity(nameservers.len() * 2);
+        for addr in nameservers {
+            group.push(NameServerConfig::new(*addr, Protocol::Udp));
+            group.push(NameServerConfig::new(*addr, Protocol::Tcp));
+        }
+        let mut options = ResolverOpts::default();
+        options.validate = dnssec;
+        // Both families are resolved; `IpPreference` orders them
+        options.ip_strategy = LookupIpStrategy::Ipv4AndIpv6;
+
+        Arc::new(hickory_resolver::TokioAsyncResolver::tokio(
+            ResolverConfig::from_parts(None, Vec::new(), group),
+            options,
+        ))
+    }
+}
+
+#[cfg(feature = "hickory-dns")]
+impl Resolve for HickoryResolver {
+    fn resolve(&self, name: hyper::client::connect::dns::Name) -> Resolving {
+        let resolver = self.resolver.clone();
+        Box::pin(async move {
+            let lookup = resolver.lookup_ip(name.as_str()).await?;
+            let addrs: Vec<SocketAddr> = lookup.iter().map(|ip| SocketAddr::new(ip, 0)).collect();
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+#[cfg(feature = "hickory-dns")]
+impl std::fmt::Debug for HickoryResolver {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HickoryResolver")
+            .field("nameservers", &self.nameservers)
+            .field("dnssec", &self.dnssec)
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,250 @@
+//! Passwordless login endpoints
+
+use super::transport::{Auth, RequestBody};
+use super::KeyrunesClient;
+use crate::endpoints;
+use crate::error::{KeyrunesError, Result};
+use crate::models::{MagicLinkRequest, OtpChannel, OtpDelivery, Token, DEFAULT_NAMESPACE};
+
+impl KeyrunesClient {
+    /// Sends a magic login link to the user's email.
+    ///
+    /// The request targets the namespace of the session (the one of the last
+    /// login), or `"public"` before any login.
+    ///
+    /// # Arguments
+    ///
+    /// * `email` - User email
+    /// * `redirect` - URL the link points to; Keyrunes appends the login token to it
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<(), KeyrunesError>`:
+    /// - `Ok(())` if the request was accepted (also when the email is unknown,
+    ///   so the endpoint cannot be used to enumerate accounts)
+    /// - `Err(KeyrunesError::HttpError)` if the redirect URL is not allowed
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyrunes_rust_sdk::KeyrunesClient;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = KeyrunesClient::new("https://keyrunes.example.com")?;
+    /// client
+    ///     .request_magic_link("john@example.com", "https://app.example.com/auth/callback")
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn request_magic_link<E: Into<String>, R: Into<String>>(
+        &self,
+        email: E,
+        redirect: R,
+    ) -> Result<()> {
+        let request = MagicLinkRequest {
+            email: email.into(),
+            redirect_uri: redirect.into(),
+            namespace: self.namespace().await,
+        };
+
+        let response = self
+            .send_request(
+                reqwest::Method::POST,
+                endpoints::MAGIC_LINK,
+                RequestBody::Json(serde_json::to_value(&request)?),
+                Auth::None,
+                None,
+            )
+            .await?;
+        self.handle_empty_response(response).await
+    }
+
+    /// Completes a magic link login and returns the authentication token.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - The one-time token received on the redirect URL
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<Token, KeyrunesError>`:
+    /// - `Ok(token)` if login was successful
+    /// - `Err(KeyrunesError::AuthenticationError)` if the link is invalid, used or expired
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyrunes_rust_sdk::KeyrunesClient;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = KeyrunesClient::new("https://keyrunes.example.com")?;
+    /// let token = client.complete_magic_link("one-time-token").await?;
+    /// println!("Token: {}", token.token);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn complete_magic_link<S: Into<String>>(&self, token: S) -> Result<Token> {
+        let response = self
+            .send_request(
+                reqwest::Method::POST,
+                endpoints::MAGIC_LINK_VERIFY,
+                RequestBody::Json(serde_json::json!({ "token": token.into() })),
+                Auth::None,
+                None,
+            )
+            .await?;
+
+        let token = self.handle_response::<Token>(response).await?;
+        self.store_issued_token(&token).await?;
+        Ok(token)
+    }
+
+    /// Sends a one-time login code to the user by SMS or email.
+    ///
+    /// The request targets the namespace of the session (the one of the last
+    /// login), or `"public"` before any login.
+    ///
+    /// # Arguments
+    ///
+    /// * `identity` - Username, email or phone number
+    /// * `channel` - Delivery channel
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<OtpDelivery, KeyrunesError>`:
+    /// - `Ok(delivery)` with the code validity and resend delay
+    /// - `Err(KeyrunesError::OtpThrottled)` if a code was requested too recently
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyrunes_rust_sdk::{KeyrunesClient, OtpChannel};
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = KeyrunesClient::new("https://keyrunes.example.com")?;
+    /// let delivery = client.request_otp("john@example.com", OtpChannel::Email).await?;
+    /// println!("Code valid for {:?} seconds", delivery.expires_in);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn request_otp<S: Into<String>>(
+        &self,
+        identity: S,
+        channel: OtpChannel,
+    ) -> Result<OtpDelivery> {
+        let body = serde_json::json!({
+            "identity": identity.into(),
+            "channel": channel,
+            "namespace": self.namespace().await,
+        });
+        let response = self
+            .send_request(
+                reqwest::Method::POST,
+                endpoints::OTP,
+                RequestBody::Json(body),
+                Auth::None,
+                None,
+            )
+            .await?;
+
+        if response.status() == reqwest::StatusCode::TOO_MANY_REQUESTS {
+            let retry_after = response
+                .headers()
+                .get(reqwest::header::RETRY_AFTER)
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.parse().ok());
+            let body: serde_json::Value = response.json().await.unwrap_or_default();
+            let retry_after = retry_after
+                .or_else(|| body.get("retry_after").and_then(|v| v.as_u64()))
+                .unwrap_or(0);
+            return Err(KeyrunesError::OtpThrottled(retry_after));
+        }
+
+        self.handle_response(response).await
+    }
+
+    /// Verifies a one-time login code and returns the authentication token.
+    ///
+    /// The request targets the namespace of the session (the one of the last
+    /// login), or `"public"` before any login.
+    ///
+    /// # Arguments
+    ///
+    /// * `identity` - Identity the code was sent to
+    /// * `code` - The one-time code
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<Token, KeyrunesError>`:
+    /// - `Ok(token)` if login was successful
+    /// - `Err(KeyrunesError::OtpExpired)` if the code expired
+    /// - `Err(KeyrunesError::InvalidOtp)` if the code is wrong
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyrunes_rust_sdk::{KeyrunesClient, KeyrunesError};
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = KeyrunesClient::new("https://keyrunes.example.com")?;
+    /// match client.verify_otp("john@example.com", "123456").await {
+    ///     Ok(token) => println!("Token: {}", token.token),
+    ///     Err(KeyrunesError::OtpExpired) => println!("Code expired, request a new one"),
+    ///     Err(e) => return Err(e.into()),
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn verify_otp<I: Into<String>, C: Into<String>>(
+        &self,
+        identity: I,
+        code: C,
+    ) -> Result<Token> {
+        let body = serde_json::json!({
+            "identity": identity.into(),
+            "code": code.into(),
+            "namespace": self.namespace().await,
+        });
+        let response = self
+            .send_request(
+                reqwest::Method::POST,
+                endpoints::OTP_VERIFY,
+                RequestBody::Json(body),
+                Auth::None,
+                None,
+            )
+            .await?;
+
+        let status = response.status();
+        if status.is_client_error() && status != reqwest::StatusCode::NOT_FOUND {
+            let url = response.url().clone();
+            let body = response.text().await?;
+            let details: serde_json::Value = serde_json::from_str(&body).unwrap_or_default();
+            let code = details
+                .get("code")
+                .or_else(|| details.get("error"))
+                .and_then(|c| c.as_str());
+            return Err(match code {
+                Some("otp_expired") => KeyrunesError::OtpExpired,
+                Some("otp_invalid") => KeyrunesError::InvalidOtp {
+                    attempts_remaining: details
+                        .get("attempts_remaining")
+                        .and_then(|v| v.as_u64())
+                        .map(|v| v as u32),
+                },
+                _ => self.handle_error(status, &body, &url),
+            });
+        }
+
+        let token = self.handle_response::<Token>(response).await?;
+        self.store_issued_token(&token).await?;
+        Ok(token)
+    }
+
+    /// Namespace of the session, or the default namespace before the first login
+    async fn namespace(&self) -> String {
+        self.session
+            .read()
+            .await
+            .namespace
+            .clone()
+            .unwrap_or_else(|| DEFAULT_NAMESPACE.to_string())
+    }
+}
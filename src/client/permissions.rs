@@ -0,0 +1,60 @@
+//! Permission endpoints
+
+use super::transport::Auth;
+use super::KeyrunesClient;
+use crate::endpoints;
+use crate::error::Result;
+use crate::permissions::PermissionSet;
+use serde::Deserialize;
+
+/// Effective permissions returned by the API
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum EffectivePermissions {
+    Wrapped { permissions: PermissionSet },
+    List(PermissionSet),
+}
+
+impl KeyrunesClient {
+    /// Downloads the effective permissions of a user for local checks.
+    ///
+    /// The returned set includes permissions inherited from groups and
+    /// roles. It is a snapshot: fetch it again (e.g., per request) to pick
+    /// up changes.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - User ID
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<PermissionSet, KeyrunesError>`:
+    /// - `Ok(permissions)` with the user's effective permissions
+    /// - `Err(KeyrunesError::UserNotFoundError)` if the user doesn't exist
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyrunes_rust_sdk::KeyrunesClient;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = KeyrunesClient::new("https://keyrunes.example.com")?;
+    /// let permissions = client.compile_policy("123").await?;
+    /// for entry in ["documents:read", "billing:read", "admin:users"] {
+    ///     if permissions.implies(entry) {
+    ///         println!("Show {}", entry);
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn compile_policy<S: Into<String>>(&self, user_id: S) -> Result<PermissionSet> {
+        let url = self.url(&endpoints::user_permissions(user_id.into()));
+        let request = self.client.get(&url);
+        let response = self.send(request, Auth::Optional).await?;
+
+        match self.handle_response(response).await? {
+            EffectivePermissions::Wrapped { permissions } => Ok(permissions),
+            EffectivePermissions::List(permissions) => Ok(permissions),
+        }
+    }
+}
@@ -0,0 +1,89 @@
+//! Metered entitlement (quota) endpoints
+
+use super::transport::Auth;
+use super::KeyrunesClient;
+use crate::endpoints;
+use crate::error::{KeyrunesError, Result};
+use crate::models::Quota;
+
+impl KeyrunesClient {
+    /// Gets the usage of a metered entitlement.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - User ID
+    /// * `meter` - Meter name (e.g., "api_calls")
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<Quota, KeyrunesError>`:
+    /// - `Ok(quota)` with the current usage
+    /// - `Err(KeyrunesError::UserNotFoundError)` if the user doesn't exist
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyrunes_rust_sdk::KeyrunesClient;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = KeyrunesClient::new("https://keyrunes.example.com")?;
+    /// let quota = client.get_quota("123", "api_calls").await?;
+    /// println!("{} used, {:?} left", quota.used, quota.remaining());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_quota<S: Into<String>>(&self, user_id: S, meter: &str) -> Result<Quota> {
+        let url = self.url(&endpoints::user_quota(user_id.into(), meter));
+        let request = self.client.get(&url);
+        let response = self.send(request, Auth::Optional).await?;
+
+        self.handle_response(response).await
+    }
+
+    /// Consumes units of a metered entitlement.
+    ///
+    /// To avoid one round trip per unit, see
+    /// [`QuotaBatcher`](crate::quota::QuotaBatcher).
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - User ID
+    /// * `meter` - Meter name (e.g., "api_calls")
+    /// * `amount` - Units to consume
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<Quota, KeyrunesError>`:
+    /// - `Ok(quota)` with the usage after consumption
+    /// - `Err(KeyrunesError::QuotaExceeded)` if not enough units are left
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyrunes_rust_sdk::KeyrunesClient;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = KeyrunesClient::new("https://keyrunes.example.com")?;
+    /// client.consume_quota("123", "api_calls", 1).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn consume_quota<S: Into<String>>(
+        &self,
+        user_id: S,
+        meter: &str,
+        amount: u64,
+    ) -> Result<Quota> {
+        let url = self.url(&endpoints::user_quota_consume(user_id.into(), meter));
+        let request = self
+            .client
+            .post(&url)
+            .json(&serde_json::json!({ "amount": amount }));
+        let response = self.send(request, Auth::Optional).await?;
+
+        match response.status() {
+            reqwest::StatusCode::PAYMENT_REQUIRED | reqwest::StatusCode::TOO_MANY_REQUESTS => {
+                Err(KeyrunesError::QuotaExceeded(meter.to_string()))
+            }
+            _ => self.handle_response(response).await,
+        }
+    }
+}
@@ -0,0 +1,359 @@
+//! Requests to endpoints the SDK does not model yet
+//!
+//! [`KeyrunesClient::raw`] sends requests built by hand, relative to the
+//! base URL and carrying the client's token (and DPoP proofs), with error
+//! responses mapped to [`KeyrunesError`] like those of the modeled
+//! endpoints.
+
+use super::transport::Auth;
+use super::KeyrunesClient;
+use crate::error::{KeyrunesError, Result};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::time::Duration;
+
+/// Header carrying the namespace of the session, sent by [`KeyrunesClient::call`]
+const HEADER_NAMESPACE: &str = "X-Keyrunes-Namespace";
+
+/// Retries of [`KeyrunesClient::call`] on transient failures
+const CALL_RETRIES: u32 = 2;
+
+/// Delay before the first retry, doubled for each following one
+const RETRY_BACKOFF: Duration = Duration::from_millis(200);
+
+/// Handle to send requests to arbitrary Keyrunes endpoints
+#[derive(Clone, Copy)]
+pub struct RawClient<'a> {
+    client: &'a KeyrunesClient,
+}
+
+impl<'a> RawClient<'a> {
+    /// Starts a `GET` request to `path` (e.g., "/api/beta/insights").
+    pub fn get(&self, path: &str) -> RawRequest<'a> {
+        self.request(reqwest::Method::GET, path)
+    }
+
+    /// Starts a `POST` request to `path`.
+    pub fn post(&self, path: &str) -> RawRequest<'a> {
+        self.request(reqwest::Method::POST, path)
+    }
+
+    /// Starts a `PUT` request to `path`.
+    pub fn put(&self, path: &str) -> RawRequest<'a> {
+        self.request(reqwest::Method::PUT, path)
+    }
+
+    /// Starts a `PATCH` request to `path`.
+    pub fn patch(&self, path: &str) -> RawRequest<'a> {
+        self.request(reqwest::Method::PATCH, path)
+    }
+
+    /// Starts a `DELETE` request to `path`.
+    pub fn delete(&self, path: &str) -> RawRequest<'a> {
+        self.request(reqwest::Method::DELETE, path)
+    }
+
+    /// Starts a request to `path` with any method.
+    pub fn request(&self, method: reqwest::Method, path: &str) -> RawRequest<'a> {
+        RawRequest {
+            client: self.client,
+            method,
+            path: path.to_string(),
+            query: Vec::new(),
+            headers: Vec::new(),
+            body: None,
+            auth: Auth::Optional,
+            retries: 0,
+            error: None,
+        }
+    }
+}
+
+/// Request to an arbitrary Keyrunes endpoint, built by [`RawClient`]
+#[derive(Clone)]
+pub struct RawRequest<'a> {
+    client: &'a KeyrunesClient,
+    method: reqwest::Method,
+    path: String,
+    query: Vec<(String, String)>,
+    headers: Vec<(String, String)>,
+    body: Option<(Vec<u8>, String)>,
+    auth: Auth,
+    retries: u32,
+    /// Error building the request, reported when it is sent
+    error: Option<KeyrunesError>,
+}
+
+impl<'a> RawRequest<'a> {
+    /// Adds a query parameter.
+    pub fn query(mut self, key: &str, value: impl ToString) -> Self {
+        self.query.push((key.to_string(), value.to_string()));
+        self
+    }
+
+    /// Adds a header.
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    /// Sends `body` as JSON.
+    pub fn json<B: Serialize + ?Sized>(mut self, body: &B) -> Self {
+        match serde_json::to_vec(body) {
+            Ok(data) => self.body = Some((data, "application/json".to_string())),
+            Err(e) => self.error = Some(e.into()),
+        }
+        self
+    }
+
+    /// Sends `data` as the body, with the given content type.
+    pub fn body(mut self, data: impl Into<Vec<u8>>, content_type: &str) -> Self {
+        self.body = Some((data.into(), content_type.to_string()));
+        self
+    }
+
+    /// Sends the request without the token.
+    ///
+    /// By default the token is sent when the client has one.
+    pub fn anonymous(mut self) -> Self {
+        self.auth = Auth::None;
+        self
+    }
+
+    /// Retries the request up to `retries` times when the server is
+    /// unreachable or answers 502, 503 or 504, waiting longer each time.
+    ///
+    /// Only idempotent requests (`GET`, `HEAD`, `OPTIONS`, `PUT`, `DELETE`)
+    /// are retried.
+    pub fn retries(mut self, retries: u32) -> Self {
+        self.retries = retries;
+        self
+    }
+
+    /// Sends the request.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<RawResponse, KeyrunesError>`:
+    /// - `Ok(response)` if the server answered with a success status
+    /// - `Err(KeyrunesError::AuthenticationError)` if the server answered 401
+    /// - `Err(KeyrunesError::AuthorizationError)` if the server answered 403
+    /// - `Err(KeyrunesError::Api)` for other error statuses whose body has a code
+    /// - `Err(KeyrunesError::HttpError)` for other error statuses
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyrunes_rust_sdk::KeyrunesClient;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = KeyrunesClient::new("https://keyrunes.example.com")?;
+    /// client.login("admin@example.com", "password123", None).await?;
+    /// let response = client
+    ///     .raw()
+    ///     .get("/api/beta/insights")
+    ///     .query("period", "7d")
+    ///     .send()
+    ///     .await?;
+    /// let insights: serde_json::Value = response.json()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn send(mut self) -> Result<RawResponse> {
+        if let Some(error) = self.error.take() {
+            return Err(error);
+        }
+
+        let retries = if self.method.is_idempotent() {
+            self.retries
+        } else {
+            0
+        };
+        let mut backoff = RETRY_BACKOFF;
+        for _ in 0..retries {
+            match self.clone().send_once().await {
+                Err(KeyrunesError::NetworkError(_)) => {}
+                Ok(Err(response)) if response.is_transient() => {}
+                result => return result?.map_err(|response| response.error),
+            }
+            tokio::time::sleep(backoff).await;
+            backoff *= 2;
+        }
+        self.send_once().await?.map_err(|response| response.error)
+    }
+
+    /// Sends the request once, keeping the status of error responses
+    async fn send_once(self) -> Result<std::result::Result<RawResponse, ErrorResponse>> {
+        let mut url = url::Url::parse(&self.client.url(&self.path))?;
+        if !self.query.is_empty() {
+            url.query_pairs_mut().extend_pairs(&self.query);
+        }
+        let mut request = self.client.client.request(self.method, url);
+        for (name, value) in &self.headers {
+            request = request.header(name.as_str(), value.as_str());
+        }
+        if let Some((data, content_type)) = self.body {
+            request = request
+                .header(reqwest::header::CONTENT_TYPE, content_type)
+                .body(data);
+        }
+
+        let response = self.client.send(request, self.auth).await?;
+        let status = response.status();
+        let url = response.url().clone();
+        let headers = response.headers().clone();
+        let body = response.bytes().await?.to_vec();
+        if !status.is_success() {
+            return Ok(Err(ErrorResponse {
+                status: status.as_u16(),
+                error: self
+                    .client
+                    .handle_error(status, &String::from_utf8_lossy(&body), &url),
+            }));
+        }
+
+        Ok(Ok(RawResponse {
+            status: status.as_u16(),
+            headers,
+            body,
+        }))
+    }
+}
+
+/// Error response to a [`RawRequest`], before retries are decided
+struct ErrorResponse {
+    status: u16,
+    error: KeyrunesError,
+}
+
+impl ErrorResponse {
+    fn is_transient(&self) -> bool {
+        matches!(self.status, 502..=504)
+    }
+}
+
+/// Successful response to a [`RawRequest`]
+#[derive(Debug, Clone)]
+pub struct RawResponse {
+    status: u16,
+    headers: reqwest::header::HeaderMap,
+    body: Vec<u8>,
+}
+
+impl RawResponse {
+    /// HTTP status code
+    pub fn status(&self) -> u16 {
+        self.status
+    }
+
+    /// Value of the header `name`, if present and valid UTF-8
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(name).and_then(|value| value.to_str().ok())
+    }
+
+    /// Body bytes
+    pub fn bytes(&self) -> &[u8] {
+        &self.body
+    }
+
+    /// Body as text (invalid UTF-8 sequences replaced)
+    pub fn text(&self) -> String {
+        String::from_utf8_lossy(&self.body).into_owned()
+    }
+
+    /// Deserializes the JSON body.
+    ///
+    /// Fails with `SerializationError` if the body does not match `T`.
+    pub fn json<T: DeserializeOwned>(&self) -> Result<T> {
+        Ok(serde_json::from_slice(&self.body)?)
+    }
+}
+
+impl KeyrunesClient {
+    /// Returns a handle to send requests to endpoints the SDK does not
+    /// model yet (see [`RawClient`]).
+    ///
+    /// Requests are relative to the base URL, carry the token when the
+    /// client has one, and fail with the same errors as the modeled
+    /// endpoints.
+    pub fn raw(&self) -> RawClient<'_> {
+        RawClient { client: self }
+    }
+
+    /// Calls an endpoint with your own request and response types.
+    ///
+    /// Built on [`Self::raw`]: the request carries the token when the
+    /// client has one and the namespace of the session (as
+    /// `X-Keyrunes-Namespace`), idempotent requests are retried twice on
+    /// transient failures, and error responses are mapped to
+    /// [`KeyrunesError`]. The response is read like those of the modeled
+    /// endpoints (including strict mode); an empty body reads as `null`.
+    ///
+    /// # Arguments
+    ///
+    /// * `method` - HTTP method
+    /// * `path` - Path relative to the base URL (e.g., "/api/beta/insights")
+    /// * `body` - JSON body, if any
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<Res, KeyrunesError>`:
+    /// - `Ok(response)` with the deserialized response
+    /// - `Err(KeyrunesError::SerializationError)` if the response does not match `Res`
+    /// - `Err(KeyrunesError::NetworkError)` if the server is still unreachable after the retries
+    /// - the errors of [`RawRequest::send`] for error responses
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyrunes_rust_sdk::KeyrunesClient;
+    /// #[derive(serde::Serialize)]
+    /// struct InsightsQuery {
+    ///     metric: String,
+    /// }
+    ///
+    /// #[derive(serde::Deserialize)]
+    /// struct Insights {
+    ///     logins: u64,
+    /// }
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = KeyrunesClient::new("https://keyrunes.example.com")?;
+    /// client.login("admin@example.com", "password123", None).await?;
+    /// let query = InsightsQuery { metric: "logins".to_string() };
+    /// let insights: Insights = client
+    ///     .call(reqwest::Method::POST, "/api/beta/insights", Some(&query))
+    ///     .await?;
+    /// println!("{} logins", insights.logins);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn call<Req, Res>(
+        &self,
+        method: reqwest::Method,
+        path: &str,
+        body: Option<&Req>,
+    ) -> Result<Res>
+    where
+        Req: Serialize + ?Sized,
+        Res: DeserializeOwned,
+    {
+        let mut request = self.raw().request(method, path).retries(CALL_RETRIES);
+        if let Some(namespace) = self.session.read().await.namespace.as_deref() {
+            request = request.header(HEADER_NAMESPACE, namespace);
+        }
+        if let Some(body) = body {
+            request = request.json(body);
+        }
+
+        let response = request.send().await?;
+        let body = match response.text() {
+            text if text.trim().is_empty() => "null".to_string(),
+            text => text,
+        };
+        if self.strict {
+            super::strict::from_str(&body)
+        } else {
+            Ok(serde_json::from_str(&body)?)
+        }
+    }
+}
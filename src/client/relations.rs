@@ -0,0 +1,304 @@
+//! Relationship-based authorization (ReBAC) endpoints
+
+use super::transport::Auth;
+use super::KeyrunesClient;
+use crate::endpoints;
+use crate::error::{KeyrunesError, Result};
+use crate::models::{HierarchyDecision, Page, Relation, Resource, ResourcePath, ResourceRef};
+use futures_util::stream::{self, Stream, TryStreamExt};
+
+impl KeyrunesClient {
+    /// Registers a resource for relationship-based checks.
+    ///
+    /// Registering a resource with an owner creates the `owner` relation,
+    /// and with a parent the `parent` relation, so permissions can be
+    /// inherited (e.g., from a folder to its documents).
+    ///
+    /// # Arguments
+    ///
+    /// * `resource_type` - Resource type (e.g., "document")
+    /// * `resource_id` - Resource ID
+    /// * `owner_id` - ID of the owning user (optional)
+    /// * `parent` - Parent resource (optional)
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<Resource, KeyrunesError>`:
+    /// - `Ok(resource)` with the registered resource
+    /// - `Err(KeyrunesError::AuthorizationError)` if not allowed to register resources
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyrunes_rust_sdk::{KeyrunesClient, ResourceRef};
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = KeyrunesClient::new("https://keyrunes.example.com")?;
+    /// client.login("admin@example.com", "password123", None).await?;
+    /// let folder = ResourceRef::new("folder", "7");
+    /// client
+    ///     .register_resource("document", "42", Some("123"), Some(&folder))
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn register_resource<T: Into<String>, I: Into<String>>(
+        &self,
+        resource_type: T,
+        resource_id: I,
+        owner_id: Option<&str>,
+        parent: Option<&ResourceRef>,
+    ) -> Result<Resource> {
+        let url = self.url(endpoints::RESOURCES);
+        let response = self
+            .send(
+                self.client.post(&url).json(&serde_json::json!({
+                    "type": resource_type.into(),
+                    "id": resource_id.into(),
+                    "owner_id": owner_id,
+                    "parent": parent,
+                })),
+                Auth::Required,
+            )
+            .await?;
+
+        self.handle_response(response).await
+    }
+
+    /// Deletes a registered resource and all its relations.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<(), KeyrunesError>`:
+    /// - `Ok(())` if the resource was deleted
+    /// - `Err(KeyrunesError::AuthorizationError)` if not allowed to delete it
+    pub async fn delete_resource(&self, resource: &ResourceRef) -> Result<()> {
+        let url = self.url(&endpoints::resource(&resource.resource_type, &resource.id));
+        let response = self.send(self.client.delete(&url), Auth::Required).await?;
+
+        self.handle_empty_response(response).await
+    }
+
+    /// Adds a relation tuple.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyrunes_rust_sdk::{KeyrunesClient, Relation, ResourceRef};
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = KeyrunesClient::new("https://keyrunes.example.com")?;
+    /// client.login("admin@example.com", "password123", None).await?;
+    /// client
+    ///     .add_relation(&Relation::new(
+    ///         ResourceRef::new("user", "456"),
+    ///         "editor",
+    ///         ResourceRef::new("document", "42"),
+    ///     ))
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn add_relation(&self, relation: &Relation) -> Result<()> {
+        let url = self.url(endpoints::RELATIONS);
+        let response = self
+            .send(self.client.post(&url).json(relation), Auth::Required)
+            .await?;
+
+        self.handle_empty_response(response).await
+    }
+
+    /// Removes a relation tuple.
+    pub async fn remove_relation(&self, relation: &Relation) -> Result<()> {
+        let url = self.url(endpoints::RELATIONS);
+        let response = self
+            .send(self.client.delete(&url).json(relation), Auth::Required)
+            .await?;
+
+        self.handle_empty_response(response).await
+    }
+
+    /// Lists the relations whose object is `object`.
+    pub async fn list_relations(&self, object: &ResourceRef) -> Result<Vec<Relation>> {
+        let url = self.url(endpoints::RELATIONS);
+        let response = self
+            .send(
+                self.client.get(&url).query(&[
+                    ("object_type", object.resource_type.as_str()),
+                    ("object_id", object.id.as_str()),
+                ]),
+                Auth::Required,
+            )
+            .await?;
+
+        self.handle_response(response).await
+    }
+
+    /// Checks whether a relation holds, directly or through inheritance.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<bool, KeyrunesError>`:
+    /// - `Ok(true)` if `subject` has `relation` on `object`
+    /// - `Ok(false)` otherwise
+    pub async fn check_relation(&self, relation: &Relation) -> Result<bool> {
+        let url = self.url(endpoints::RELATIONS_CHECK);
+        let request = self.client.post(&url).json(relation);
+        let response = self.send(request, Auth::Optional).await?;
+
+        let result: serde_json::Value = self.handle_response(response).await?;
+        Ok(result
+            .get("allowed")
+            .and_then(|allowed| allowed.as_bool())
+            .unwrap_or(false))
+    }
+
+    /// Checks a permission on a nested resource, including inherited grants.
+    ///
+    /// The path is expanded client-side into the resource and its
+    /// ancestors, which are checked in a single call: a permission granted
+    /// on `org/123` applies to `org/123/project/456/doc/789`.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - User ID
+    /// * `path` - Path of the resource (e.g., "org/123/project/456/doc/789")
+    /// * `permission` - Permission to check (e.g., "read")
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<HierarchyDecision, KeyrunesError>`:
+    /// - `Ok(decision)` with the outcome and the granting resource
+    /// - `Err(KeyrunesError::UserNotFoundError)` if the user doesn't exist
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyrunes_rust_sdk::{KeyrunesClient, ResourcePath};
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = KeyrunesClient::new("https://keyrunes.example.com")?;
+    /// let path: ResourcePath = "org/123/project/456/doc/789".parse()?;
+    /// let decision = client.check_path_permission("42", &path, "read").await?;
+    /// if let Some(resource) = decision.granted_by {
+    ///     println!("Granted through {}", resource);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn check_path_permission(
+        &self,
+        user_id: &str,
+        path: &ResourcePath,
+        permission: &str,
+    ) -> Result<HierarchyDecision> {
+        let url = self.url(endpoints::AUTHORIZE_HIERARCHY);
+        let mut resources: Vec<&ResourceRef> = vec![path.resource()];
+        resources.extend(path.ancestors());
+        let request = self.client.post(&url).json(&serde_json::json!({
+            "user_id": user_id,
+            "permission": permission,
+            "resources": resources,
+        }));
+        let response = self.send(request, Auth::Optional).await?;
+
+        self.handle_response(response).await
+    }
+
+    /// Lists the IDs of the resources of a type a user can access.
+    ///
+    /// Fetches every page; use [`list_accessible_page`](Self::list_accessible_page)
+    /// or [`list_accessible_stream`](Self::list_accessible_stream) for large
+    /// result sets.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - User ID
+    /// * `resource_type` - Resource type (e.g., "document")
+    /// * `permission` - Permission or relation required (e.g., "read")
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<Vec<String>, KeyrunesError>`:
+    /// - `Ok(ids)` with the accessible resource IDs
+    /// - `Err(KeyrunesError::UserNotFoundError)` if the user doesn't exist
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyrunes_rust_sdk::KeyrunesClient;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = KeyrunesClient::new("https://keyrunes.example.com")?;
+    /// let ids = client.list_accessible("123", "document", "read").await?;
+    /// println!("{} readable documents", ids.len());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn list_accessible(
+        &self,
+        user_id: &str,
+        resource_type: &str,
+        permission: &str,
+    ) -> Result<Vec<String>> {
+        self.list_accessible_stream(user_id, resource_type, permission)
+            .try_collect()
+            .await
+    }
+
+    /// Lists one page of the resources a user can access.
+    ///
+    /// Pass the [`Page::next_cursor`] of a page to get the following one.
+    pub async fn list_accessible_page(
+        &self,
+        user_id: &str,
+        resource_type: &str,
+        permission: &str,
+        cursor: Option<&str>,
+    ) -> Result<Page<String>> {
+        let url = self.url(&endpoints::user_accessible(user_id));
+        let mut query = vec![("resource_type", resource_type), ("permission", permission)];
+        if let Some(cursor) = cursor {
+            query.push(("cursor", cursor));
+        }
+        let request = self.client.get(&url).query(&query);
+        let response = self.send(request, Auth::Optional).await?;
+
+        self.handle_response(response).await
+    }
+
+    /// Streams the IDs of the resources a user can access, page by page.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyrunes_rust_sdk::KeyrunesClient;
+    /// use futures_util::TryStreamExt;
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = KeyrunesClient::new("https://keyrunes.example.com")?;
+    /// let ids = client.list_accessible_stream("123", "document", "read");
+    /// futures_util::pin_mut!(ids);
+    /// while let Some(id) = ids.try_next().await? {
+    ///     println!("{}", id);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn list_accessible_stream<'a>(
+        &'a self,
+        user_id: &'a str,
+        resource_type: &'a str,
+        permission: &'a str,
+    ) -> impl Stream<Item = Result<String>> + 'a {
+        // State: (cursor of the next page, whether a page remains)
+        stream::try_unfold((None::<String>, true), move |(cursor, more)| async move {
+            if !more {
+                return Ok::<_, KeyrunesError>(None);
+            }
+            let page = self
+                .list_accessible_page(user_id, resource_type, permission, cursor.as_deref())
+                .await?;
+            let more = page.has_more();
+            let items = stream::iter(page.items.into_iter().map(Ok::<_, KeyrunesError>));
+            Ok(Some((items, (page.next_cursor, more))))
+        })
+        .try_flatten()
+    }
+}
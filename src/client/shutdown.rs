@@ -0,0 +1,91 @@
+//! Graceful shutdown of the client's background work
+
+use super::KeyrunesClient;
+use crate::error::Result;
+use async_trait::async_trait;
+use std::sync::{Mutex, Weak};
+use tokio::task::AbortHandle;
+
+/// Pending work to send before shutting down (audit decisions, quota usage)
+#[async_trait]
+pub(crate) trait Flush: Send + Sync {
+    async fn flush(&self, client: &KeyrunesClient) -> Result<()>;
+}
+
+/// Background tasks and pending batches attached to a client
+///
+/// Batches are held weakly: a dropped sink or batcher has nothing left to
+/// flush, and must not be kept alive by the client it uses.
+#[derive(Default)]
+pub(crate) struct Background {
+    tasks: Mutex<Vec<AbortHandle>>,
+    batches: Mutex<Vec<Weak<dyn Flush>>>,
+}
+
+impl Background {
+    /// Cancels `task` on shutdown.
+    pub(crate) fn track_task(&self, task: AbortHandle) {
+        let mut tasks = self.tasks.lock().unwrap_or_else(|e| e.into_inner());
+        tasks.retain(|task| !task.is_finished());
+        tasks.push(task);
+    }
+
+    /// Flushes `batch` on shutdown.
+    pub(crate) fn track_batch(&self, batch: Weak<dyn Flush>) {
+        let mut batches = self.batches.lock().unwrap_or_else(|e| e.into_inner());
+        batches.retain(|batch| batch.strong_count() > 0);
+        batches.push(batch);
+    }
+}
+
+impl KeyrunesClient {
+    /// Stops the client's background work and sends what is pending.
+    ///
+    /// Cancels the background tasks started from this client (such as the
+    /// flushers of [`HttpAuditSink`](crate::audit::HttpAuditSink) and
+    /// [`QuotaBatcher`](crate::quota::QuotaBatcher)), then flushes their
+    /// pending audit decisions and quota usage. Call it from the service's
+    /// shutdown sequence, after the server stopped accepting requests.
+    ///
+    /// Every batch is flushed even if some fail; the first error is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyrunes_rust_sdk::KeyrunesClient;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = KeyrunesClient::new("https://keyrunes.example.com")?;
+    /// // ... serve requests until a shutdown signal is received
+    /// client.shutdown().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn shutdown(&self) -> Result<()> {
+        let tasks = std::mem::take(
+            &mut *self
+                .background
+                .tasks
+                .lock()
+                .unwrap_or_else(|e| e.into_inner()),
+        );
+        for task in tasks {
+            task.abort();
+        }
+
+        let batches = std::mem::take(
+            &mut *self
+                .background
+                .batches
+                .lock()
+                .unwrap_or_else(|e| e.into_inner()),
+        );
+        let mut first_error = None;
+        for batch in batches.iter().filter_map(Weak::upgrade) {
+            if let Err(err) = batch.flush(self).await {
+                first_error.get_or_insert(err);
+            }
+        }
+
+        first_error.map_or(Ok(()), Err)
+    }
+}
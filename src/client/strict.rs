@@ -0,0 +1,186 @@
+//! Strict deserialization of API responses
+//!
+//! With [`KeyrunesClientBuilder::strict`](super::KeyrunesClientBuilder::strict),
+//! responses are read as if every model had `#[serde(deny_unknown_fields)]`,
+//! and the models skip the fallbacks that tolerate older or newer servers
+//! (numbers sent as strings, the legacy `access_token` field, ...).
+//!
+//! The response is parsed into a [`Value`], then deserialized through
+//! [`StrictDeserializer`], which checks the keys of every object read as a
+//! struct against the fields of that struct. Structs read as maps (those
+//! with a `#[serde(flatten)]` field) keep their unknown fields, as they
+//! store them.
+
+use crate::error::Result;
+use serde::de::value::BorrowedStrDeserializer;
+use serde::de::{DeserializeOwned, DeserializeSeed, Error as _, MapAccess, SeqAccess, Visitor};
+use serde::forward_to_deserialize_any;
+use serde_json::{Map, Value};
+use std::cell::Cell;
+
+thread_local! {
+    static STRICT: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Whether a strict deserialization is running on this thread
+///
+/// Checked by the deserializers of the models before falling back on a
+/// lenient reading.
+pub(crate) fn is_strict() -> bool {
+    STRICT.with(Cell::get)
+}
+
+/// Deserializes a response body, rejecting unknown fields and fallbacks
+pub(crate) fn from_str<T: DeserializeOwned>(body: &str) -> Result<T> {
+    struct Reset(bool);
+
+    impl Drop for Reset {
+        fn drop(&mut self) {
+            STRICT.with(|strict| strict.set(self.0));
+        }
+    }
+
+    let value: Value = serde_json::from_str(body)?;
+    let _reset = Reset(STRICT.with(|strict| strict.replace(true)));
+    Ok(T::deserialize(StrictDeserializer(&value))?)
+}
+
+/// Deserializer over a [`Value`] rejecting unknown struct fields
+struct StrictDeserializer<'a>(&'a Value);
+
+impl<'de> serde::Deserializer<'de> for StrictDeserializer<'de> {
+    type Error = serde_json::Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> serde_json::Result<V::Value> {
+        match self.0 {
+            Value::Null => visitor.visit_unit(),
+            Value::Bool(value) => visitor.visit_bool(*value),
+            Value::Number(number) => {
+                if let Some(value) = number.as_u64() {
+                    visitor.visit_u64(value)
+                } else if let Some(value) = number.as_i64() {
+                    visitor.visit_i64(value)
+                } else {
+                    visitor.visit_f64(number.as_f64().unwrap_or(f64::NAN))
+                }
+            }
+            Value::String(value) => visitor.visit_borrowed_str(value),
+            Value::Array(values) => visitor.visit_seq(Seq(values.iter())),
+            Value::Object(map) => visitor.visit_map(Fields::new(map)),
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> serde_json::Result<V::Value> {
+        match self.0 {
+            Value::Null => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> serde_json::Result<V::Value> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> serde_json::Result<V::Value> {
+        if let Value::Object(map) = self.0 {
+            if let Some(key) = map.keys().find(|key| !fields.contains(&key.as_str())) {
+                return Err(serde_json::Error::unknown_field(key, fields));
+            }
+        }
+        self.deserialize_any(visitor)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> serde_json::Result<V::Value> {
+        match self.0 {
+            Value::String(variant) => visitor.visit_enum(BorrowedStrDeserializer::new(variant)),
+            // Enums with data are rare in responses: read them leniently
+            value => serde::Deserializer::deserialize_enum(value, name, variants, visitor),
+        }
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple tuple_struct map identifier ignored_any
+    }
+}
+
+struct Seq<'a>(std::slice::Iter<'a, Value>);
+
+impl<'de> SeqAccess<'de> for Seq<'de> {
+    type Error = serde_json::Error;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> serde_json::Result<Option<T::Value>> {
+        self.0
+            .next()
+            .map(|value| seed.deserialize(StrictDeserializer(value)))
+            .transpose()
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.0.len())
+    }
+}
+
+struct Fields<'a> {
+    entries: serde_json::map::Iter<'a>,
+    value: Option<&'a Value>,
+}
+
+impl<'a> Fields<'a> {
+    fn new(map: &'a Map<String, Value>) -> Self {
+        Self {
+            entries: map.iter(),
+            value: None,
+        }
+    }
+}
+
+impl<'de> MapAccess<'de> for Fields<'de> {
+    type Error = serde_json::Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> serde_json::Result<Option<K::Value>> {
+        match self.entries.next() {
+            Some((key, value)) => {
+                self.value = Some(value);
+                seed.deserialize(BorrowedStrDeserializer::new(key))
+                    .map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(
+        &mut self,
+        seed: V,
+    ) -> serde_json::Result<V::Value> {
+        let value = self
+            .value
+            .take()
+            .ok_or_else(|| serde_json::Error::custom("value requested before key"))?;
+        seed.deserialize(StrictDeserializer(value))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.entries.len())
+    }
+}
@@ -0,0 +1,281 @@
+//! Access tokens kept valid by refreshing them
+
+use super::KeyrunesClient;
+use crate::error::{KeyrunesError, Result};
+use crate::models::Token;
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::sync::watch;
+
+/// Time before expiry at which a token is refreshed, by default
+const DEFAULT_REFRESH_MARGIN: Duration = Duration::from_secs(30);
+
+/// Outcome of a refresh, shared with every caller waiting for it
+type Flight = watch::Receiver<Option<Result<Token>>>;
+
+/// Refresh in progress for a client, shared by its clones
+#[derive(Default)]
+pub(crate) struct Refreshes {
+    inflight: Mutex<Option<Flight>>,
+}
+
+impl Refreshes {
+    /// Joins the refresh in progress, or registers a new one.
+    ///
+    /// Returns the sender when the caller must perform the refresh.
+    fn join(&self) -> (Flight, Option<watch::Sender<Option<Result<Token>>>>) {
+        let mut inflight = self.inflight.lock().unwrap_or_else(|e| e.into_inner());
+        // A closed channel is a refresh that never completed (its task panicked).
+        if let Some(flight) = inflight.as_ref().filter(|f| f.has_changed().is_ok()) {
+            return (flight.clone(), None);
+        }
+        let (sender, flight) = watch::channel(None);
+        *inflight = Some(flight.clone());
+        (flight, Some(sender))
+    }
+
+    fn finish(&self) {
+        self.inflight
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .take();
+    }
+}
+
+/// Hands out the client's access token, refreshing it before it expires
+///
+/// Refreshes are single-flight: when many tasks find the token expired at
+/// the same time, one request is sent to the refresh endpoint and every
+/// caller receives its result. This keeps bursts from hammering Keyrunes,
+/// and keeps concurrent requests from spending the same refresh token
+/// (which servers rotating refresh tokens treat as reuse).
+///
+/// Obtained with [`KeyrunesClient::token_provider`]; clones of the client
+/// share the refresh in progress.
+///
+/// # Examples
+///
+/// ```
+/// # use keyrunes_rust_sdk::KeyrunesClient;
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// let client = KeyrunesClient::new("https://keyrunes.example.com")?;
+/// client.login("user@example.com", "password123", None).await?;
+///
+/// let provider = client.token_provider();
+/// let token = provider.token().await?;
+/// println!("Bearer {}", token);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct TokenProvider {
+    client: KeyrunesClient,
+    margin: Duration,
+}
+
+impl TokenProvider {
+    /// Sets how long before expiry the token is refreshed (default: 30 seconds).
+    pub fn with_refresh_margin(mut self, margin: Duration) -> Self {
+        self.margin = margin;
+        self
+    }
+
+    /// Returns the client's token, refreshing it first if it expires within
+    /// the refresh margin.
+    ///
+    /// Tokens without a known expiry are returned as is. Fails with
+    /// `InvalidToken` if the client has no token.
+    pub async fn token(&self) -> Result<String> {
+        let info = self
+            .client
+            .current_token()
+            .await
+            .ok_or(KeyrunesError::InvalidToken)?;
+
+        match info.expires_in() {
+            Some(left) if left <= self.margin => Ok(self.refresh().await?.token),
+            _ => Ok(info.token),
+        }
+    }
+
+    /// Exchanges the stored refresh token for a new token and stores it.
+    ///
+    /// The new token and the rotated refresh token are saved together in
+    /// the client's [`TokenStore`](crate::client::TokenStore). If a refresh
+    /// is already in progress, waits for it and returns its result instead
+    /// of sending another request.
+    ///
+    /// Fails with `InvalidToken` if there is no refresh token, and with
+    /// `RefreshTokenReused` if the server detected reuse of the refresh
+    /// token; the client's tokens are then cleared and the user must log
+    /// in again.
+    pub async fn refresh(&self) -> Result<Token> {
+        let (mut flight, sender) = self.client.refreshes.join();
+
+        if let Some(sender) = sender {
+            // The refresh runs in its own task, so that it completes for the
+            // waiters even if the caller that started it is cancelled.
+            let client = self.client.clone();
+            tokio::spawn(async move {
+                let result = refresh_stored(&client).await;
+                client.refreshes.finish();
+                sender.send_replace(Some(result));
+            });
+        }
+
+        let outcome = flight
+            .wait_for(Option::is_some)
+            .await
+            .map_err(|_| KeyrunesError::Other("Token refresh did not complete".to_string()))?;
+        outcome.clone().expect("waited for a refresh outcome")
+    }
+}
+
+impl KeyrunesClient {
+    /// Returns a [`TokenProvider`] for the client's token.
+    pub fn token_provider(&self) -> TokenProvider {
+        TokenProvider {
+            client: self.clone(),
+            margin: DEFAULT_REFRESH_MARGIN,
+        }
+    }
+
+    /// Exchanges the stored refresh token for a new token.
+    ///
+    /// The new token replaces the client's token, and the rotated refresh
+    /// token (if the server rotates them) replaces the stored one. A
+    /// refresh already in progress is joined instead of sending another
+    /// request, as with [`TokenProvider::refresh`].
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<Token, KeyrunesError>`:
+    /// - `Ok(Token)` if the token was refreshed
+    /// - `Err(KeyrunesError::InvalidToken)` if the client has no refresh token
+    /// - `Err(KeyrunesError::RefreshTokenReused)` if the server detected reuse of the refresh token (the client's tokens are cleared)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyrunes_rust_sdk::KeyrunesClient;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = KeyrunesClient::new("https://keyrunes.example.com")?;
+    /// client.login("user@example.com", "password123", None).await?;
+    /// let token = client.refresh_token().await?;
+    /// println!("New token expires in {:?}s", token.expires_in);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn refresh_token(&self) -> Result<Token> {
+        self.token_provider().refresh().await
+    }
+
+    /// Exchanges `refresh_token` for a new token, and stores both.
+    ///
+    /// For refresh tokens kept outside the client (e.g., in a cookie).
+    /// The new token and refresh token replace the client's ones.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<Token, KeyrunesError>`:
+    /// - `Ok(Token)` if the token was refreshed
+    /// - `Err(KeyrunesError::AuthenticationError)` if the refresh token is invalid or expired
+    /// - `Err(KeyrunesError::RefreshTokenReused)` if the server detected reuse of the refresh token
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyrunes_rust_sdk::KeyrunesClient;
+    /// # async fn example(stored_refresh_token: &str) -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = KeyrunesClient::new("https://keyrunes.example.com")?;
+    /// client.refresh_with(stored_refresh_token).await?;
+    /// let user = client.get_current_user().await?;
+    /// println!("Welcome back, {}", user.username);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn refresh_with(&self, refresh_token: &str) -> Result<Token> {
+        let mut token = self.exchange_refresh_token(refresh_token).await?;
+        // Servers that do not rotate refresh tokens keep the current one valid.
+        token
+            .refresh_token
+            .get_or_insert_with(|| refresh_token.to_string());
+        self.store_refreshed_token(&token).await?;
+        Ok(token)
+    }
+}
+
+impl KeyrunesClient {
+    /// Token to send with a request, refreshed first if it is about to
+    /// expire and automatic refresh is enabled.
+    ///
+    /// A failed refresh leaves the current token in use, unless the server
+    /// detected reuse of the refresh token (the session is then gone).
+    pub(crate) async fn request_token(&self) -> Result<Option<String>> {
+        let Some(token) = self.token.read().await.clone() else {
+            return Ok(None);
+        };
+        if !self.auto_refresh || !self.has_refresh_token().await {
+            return Ok(Some(token));
+        }
+
+        match self.token_info(token.clone()).expires_in() {
+            Some(left) if left <= DEFAULT_REFRESH_MARGIN => {
+                match self.token_provider().refresh().await {
+                    Ok(refreshed) => Ok(Some(refreshed.token)),
+                    Err(KeyrunesError::RefreshTokenReused) => {
+                        Err(KeyrunesError::RefreshTokenReused)
+                    }
+                    Err(_) => Ok(Some(token)),
+                }
+            }
+            _ => Ok(Some(token)),
+        }
+    }
+
+    /// Token to retry a request rejected with `sent`: the token another task
+    /// already refreshed from `sent`, or a newly refreshed one.
+    ///
+    /// Returns `None` when no other token can be obtained, in particular
+    /// when `sent` is not the client's own session token: a token is only
+    /// ever replaced by its own refresh, never by another caller's token.
+    pub(crate) async fn refreshed_token(&self, sent: &str) -> Option<String> {
+        let current = self.token.read().await.clone()?;
+        if current != sent {
+            let refreshed_from = self.session.read().await.refreshed_from.clone();
+            return (refreshed_from.as_deref() == Some(sent)).then_some(current);
+        }
+        if !self.has_refresh_token().await {
+            return None;
+        }
+        self.token_provider()
+            .refresh()
+            .await
+            .ok()
+            .map(|token| token.token)
+    }
+
+    async fn has_refresh_token(&self) -> bool {
+        self.session.read().await.refresh_token.is_some()
+    }
+}
+
+/// Refreshes the client's token with its stored refresh token.
+async fn refresh_stored(client: &KeyrunesClient) -> Result<Token> {
+    let refresh_token = client
+        .session
+        .read()
+        .await
+        .refresh_token
+        .clone()
+        .ok_or(KeyrunesError::InvalidToken)?;
+
+    match client.refresh_with(&refresh_token).await {
+        Err(KeyrunesError::RefreshTokenReused) => {
+            // The server revoked the whole session: only a new login restores it.
+            client.clear_token().await;
+            Err(KeyrunesError::RefreshTokenReused)
+        }
+        result => result,
+    }
+}
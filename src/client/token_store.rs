@@ -0,0 +1,123 @@
+//! Persistence of the client's tokens
+
+use super::KeyrunesClient;
+use crate::error::Result;
+use crate::models::Token;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+/// Token pair saved by a [`TokenStore`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StoredTokens {
+    /// Access token
+    pub token: String,
+    /// Refresh token, if the server issued one
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub refresh_token: Option<String>,
+}
+
+impl From<&Token> for StoredTokens {
+    fn from(token: &Token) -> Self {
+        Self {
+            token: token.token.clone(),
+            refresh_token: token.refresh_token.clone(),
+        }
+    }
+}
+
+/// Where the client saves the tokens Keyrunes issues
+///
+/// Keyrunes rotates refresh tokens: each refresh returns a new one and
+/// spends the old one. The client saves every token pair it is issued
+/// (on login and on refresh) as a single [`StoredTokens`], so a store
+/// backed by a file, a keyring or a database never holds an access token
+/// with the refresh token of another pair, and a restarted process resumes
+/// with the latest refresh token (see [`KeyrunesClient::load_tokens`]).
+///
+/// The default store, [`MemoryTokenStore`], keeps the pair in memory only.
+#[async_trait]
+pub trait TokenStore: Send + Sync {
+    /// Returns the saved tokens, if any.
+    async fn load(&self) -> Result<Option<StoredTokens>>;
+
+    /// Replaces the saved tokens.
+    async fn save(&self, tokens: &StoredTokens) -> Result<()>;
+
+    /// Removes the saved tokens.
+    async fn clear(&self) -> Result<()>;
+}
+
+/// Token store keeping the tokens in memory
+#[derive(Debug, Default)]
+pub struct MemoryTokenStore {
+    tokens: RwLock<Option<StoredTokens>>,
+}
+
+impl MemoryTokenStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl TokenStore for MemoryTokenStore {
+    async fn load(&self) -> Result<Option<StoredTokens>> {
+        Ok(self.tokens.read().await.clone())
+    }
+
+    async fn save(&self, tokens: &StoredTokens) -> Result<()> {
+        *self.tokens.write().await = Some(tokens.clone());
+        Ok(())
+    }
+
+    async fn clear(&self) -> Result<()> {
+        *self.tokens.write().await = None;
+        Ok(())
+    }
+}
+
+/// Lets the application keep a handle on the store it gives the client
+#[async_trait]
+impl<T: TokenStore + ?Sized> TokenStore for Arc<T> {
+    async fn load(&self) -> Result<Option<StoredTokens>> {
+        (**self).load().await
+    }
+
+    async fn save(&self, tokens: &StoredTokens) -> Result<()> {
+        (**self).save(tokens).await
+    }
+
+    async fn clear(&self) -> Result<()> {
+        (**self).clear().await
+    }
+}
+
+impl KeyrunesClient {
+    /// Restores the tokens saved in the client's [`TokenStore`].
+    ///
+    /// Call it at startup to resume the session of a previous process.
+    /// Returns `false` if the store holds no tokens.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyrunes_rust_sdk::KeyrunesClient;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = KeyrunesClient::new("https://keyrunes.example.com")?;
+    /// if !client.load_tokens().await? {
+    ///     client.login("user@example.com", "password123", None).await?;
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn load_tokens(&self) -> Result<bool> {
+        let Some(tokens) = self.token_store.load().await? else {
+            return Ok(false);
+        };
+        self.replace_tokens(Some(tokens.token), tokens.refresh_token)
+            .await;
+        Ok(true)
+    }
+}
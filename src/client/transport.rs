@@ -0,0 +1,290 @@
+//! Request helpers shared by the endpoint modules
+//!
+//! Most endpoints send and receive JSON, but some upload files (avatars,
+//! CSV imports) or download large artifacts (exports). [`RequestBody`]
+//! covers all body kinds, and [`ProgressCallback`] reports the progress
+//! of byte transfers.
+
+use super::KeyrunesClient;
+use crate::error::{KeyrunesError, Result};
+use crate::models::ResponseMeta;
+use std::sync::Arc;
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+
+/// Size of the chunks used to report upload progress
+const UPLOAD_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Progress of a byte transfer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Progress {
+    /// Bytes transferred so far
+    pub transferred: u64,
+    /// Total bytes to transfer, when known
+    pub total: Option<u64>,
+}
+
+/// Callback invoked as bytes are uploaded or downloaded
+pub type ProgressCallback = Arc<dyn Fn(Progress) + Send + Sync>;
+
+/// Body of a request sent through the client
+#[derive(Debug, Clone)]
+pub enum RequestBody {
+    /// No body
+    Empty,
+    /// JSON body
+    Json(serde_json::Value),
+    /// Raw bytes with the given content type
+    Bytes { data: Vec<u8>, content_type: String },
+    /// `multipart/form-data` body with a single file part
+    File {
+        field: String,
+        file_name: String,
+        data: Vec<u8>,
+        content_type: String,
+    },
+}
+
+/// Whether a request must carry the current token
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Auth {
+    /// Fail with `InvalidToken` when there is no token
+    Required,
+    /// Send the token when there is one
+    Optional,
+    /// Never send the token
+    None,
+}
+
+impl KeyrunesClient {
+    /// URL of an endpoint path (see [`crate::endpoints`]), resolved for the
+    /// deployment.
+    pub(crate) fn url(&self, path: &str) -> String {
+        format!("{}{}", self.base_url, self.endpoints.resolve(path))
+    }
+
+    /// Sends a request to `path` (relative to the base URL).
+    ///
+    /// The response status is not checked; pass the response to
+    /// `handle_response` or `handle_empty_response`.
+    pub(crate) async fn send_request(
+        &self,
+        method: reqwest::Method,
+        path: &str,
+        body: RequestBody,
+        auth: Auth,
+        progress: Option<ProgressCallback>,
+    ) -> Result<reqwest::Response> {
+        let url = self.url(path);
+        let mut request = self.client.request(method, &url);
+
+        request = match body {
+            RequestBody::Empty => request,
+            RequestBody::Json(value) => request.json(&value),
+            RequestBody::Bytes { data, content_type } => request
+                .header(reqwest::header::CONTENT_TYPE, content_type)
+                .header(reqwest::header::CONTENT_LENGTH, data.len())
+                .body(upload_body(data, progress)),
+            RequestBody::File {
+                field,
+                file_name,
+                data,
+                content_type,
+            } => {
+                let length = data.len() as u64;
+                let part = reqwest::multipart::Part::stream_with_length(
+                    upload_body(data, progress),
+                    length,
+                )
+                .file_name(file_name)
+                .mime_str(&content_type)?;
+                request.multipart(reqwest::multipart::Form::new().part(field, part))
+            }
+        };
+
+        self.send(request, auth).await
+    }
+
+    /// Sends `request`, attaching the current token according to `auth`
+    /// and the organization of a scoped client.
+    ///
+    /// With automatic refresh (the default, see
+    /// [`KeyrunesClientBuilder::auto_refresh`](super::KeyrunesClientBuilder::auto_refresh)),
+    /// a token about to expire is refreshed before the request is sent, and
+    /// a request rejected with 401 is sent once more with a refreshed token.
+    ///
+    /// With DPoP enabled (feature `dpop`), the request also carries a proof
+    /// and the token is sent as a DPoP token (see [`crate::dpop`]).
+    pub(crate) async fn send(
+        &self,
+        request: reqwest::RequestBuilder,
+        auth: Auth,
+    ) -> Result<reqwest::Response> {
+        let token = match auth {
+            Auth::Required => Some(
+                self.request_token()
+                    .await?
+                    .ok_or(KeyrunesError::InvalidToken)?,
+            ),
+            Auth::Optional => self.request_token().await?,
+            Auth::None => None,
+        };
+        // Streaming bodies cannot be sent twice; such requests are not retried.
+        let retry = match &token {
+            Some(_) if self.auto_refresh => request.try_clone(),
+            _ => None,
+        };
+
+        let response = self.send_once(request, token.as_deref()).await?;
+        if response.status() != reqwest::StatusCode::UNAUTHORIZED {
+            return Ok(response);
+        }
+        let (Some(retry), Some(sent)) = (retry, token) else {
+            return Ok(response);
+        };
+        match self.refreshed_token(&sent).await {
+            Some(token) => self.send_once(retry, Some(&token)).await,
+            None => Ok(response),
+        }
+    }
+
+    /// Sends `request` once with `token`.
+    pub(crate) async fn send_once(
+        &self,
+        request: reqwest::RequestBuilder,
+        token: Option<&str>,
+    ) -> Result<reqwest::Response> {
+        let request = match &self.org_id {
+            Some(org_id) => request.header(super::HEADER_ORG, org_id),
+            None => request,
+        };
+        let request = match &self.break_glass {
+            Some(annotation) => annotation.annotate(request),
+            None => request,
+        };
+
+        #[cfg(feature = "dpop")]
+        if let Some(dpop) = &self.dpop {
+            let response = dpop.send(&self.client, request, token).await?;
+            return self.received(response);
+        }
+
+        let request = match token {
+            Some(token) => request.header("Authorization", format!("Bearer {}", token)),
+            None => request,
+        };
+        self.received(request.send().await?)
+    }
+
+    /// Records the headers of a response (see
+    /// [`KeyrunesClient::last_response_meta`]) and checks its API version.
+    fn received(&self, response: reqwest::Response) -> Result<reqwest::Response> {
+        *self
+            .last_response
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) =
+            Some(ResponseMeta::from_response(&response));
+        self.check_api_version(response)
+    }
+
+    /// Checks that the response echoes a version compatible with the pinned
+    /// one, if any (see [`KeyrunesClientBuilder::api_version`](super::KeyrunesClientBuilder::api_version)).
+    ///
+    /// Error responses without the header (e.g., from a proxy) are let
+    /// through so their status is reported.
+    fn check_api_version(&self, response: reqwest::Response) -> Result<reqwest::Response> {
+        let Some(requested) = &self.api_version else {
+            return Ok(response);
+        };
+        let served = response
+            .headers()
+            .get(super::HEADER_API_VERSION)
+            .and_then(|value| value.to_str().ok())
+            .map(str::trim);
+        match served {
+            Some(served) if is_compatible_version(requested, served) => Ok(response),
+            None if !response.status().is_success() => Ok(response),
+            served => Err(KeyrunesError::IncompatibleApiVersion {
+                requested: requested.clone(),
+                served: served.map(str::to_string),
+            }),
+        }
+    }
+
+    /// Streams a successful response body into `writer`, returning the bytes written.
+    pub(crate) async fn write_response<W: AsyncWrite + Unpin>(
+        &self,
+        mut response: reqwest::Response,
+        writer: &mut W,
+        progress: Option<ProgressCallback>,
+    ) -> Result<u64> {
+        let status = response.status();
+        if !status.is_success() {
+            let url = response.url().clone();
+            let body = response.text().await?;
+            return Err(self.handle_error(status, &body, &url));
+        }
+
+        let total = response.content_length();
+        let mut transferred = 0u64;
+        while let Some(chunk) = response.chunk().await? {
+            writer.write_all(&chunk).await?;
+            transferred += chunk.len() as u64;
+            if let Some(progress) = &progress {
+                progress(Progress { transferred, total });
+            }
+        }
+        writer.flush().await?;
+
+        Ok(transferred)
+    }
+}
+
+/// Whether the server version `served` is compatible with `requested`:
+/// the same major version, with a minor version at least the requested one
+/// (a missing minor version counts as 0)
+fn is_compatible_version(requested: &str, served: &str) -> bool {
+    if requested == served {
+        return true;
+    }
+    match (parse_version(requested), parse_version(served)) {
+        (Some((major, minor)), Some((served_major, served_minor))) => {
+            major == served_major && served_minor >= minor
+        }
+        _ => false,
+    }
+}
+
+/// Major and minor parts of a version such as "2" or "2.3"
+fn parse_version(version: &str) -> Option<(u64, u64)> {
+    let mut parts = version.split('.');
+    let major = parts.next()?.parse().ok()?;
+    let minor = match parts.next() {
+        Some(minor) => minor.parse().ok()?,
+        None => 0,
+    };
+    Some((major, minor))
+}
+
+/// Builds an upload body, reporting progress chunk by chunk when requested
+fn upload_body(data: Vec<u8>, progress: Option<ProgressCallback>) -> reqwest::Body {
+    let Some(progress) = progress else {
+        return reqwest::Body::from(data);
+    };
+
+    let total = data.len() as u64;
+    let chunks: Vec<Vec<u8>> = data
+        .chunks(UPLOAD_CHUNK_SIZE)
+        .map(|chunk| chunk.to_vec())
+        .collect();
+    let mut transferred = 0u64;
+    let stream = futures_util::stream::iter(chunks.into_iter().map(move |chunk| {
+        transferred += chunk.len() as u64;
+        progress(Progress {
+            transferred,
+            total: Some(total),
+        });
+        Ok::<_, std::io::Error>(chunk)
+    }));
+
+    reqwest::Body::wrap_stream(stream)
+}
@@ -0,0 +1,10 @@
+//! Comparison of secrets
+
+/// Compares two secrets without leaking the position of the first difference
+pub(crate) fn constant_time_eq(a: &str, b: &str) -> bool {
+    a.len() == b.len()
+        && a.bytes()
+            .zip(b.bytes())
+            .fold(0u8, |diff, (x, y)| diff | (x ^ y))
+            == 0
+}
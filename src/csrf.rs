@@ -0,0 +1,150 @@
+//! CSRF protection for cookie-based authentication
+//!
+//! Browsers attach cookies to cross-site requests, so cookie or session
+//! authentication must be paired with CSRF protection. [`CsrfProtection`]
+//! implements the double-submit pattern: a random token is sent in a cookie
+//! readable by the page's scripts, and every state-changing request must
+//! echo it in the `X-CSRF-Token` header. A cross-site attacker can make the
+//! browser send the cookie but cannot read it to set the header.
+//!
+//! With server-side sessions (see [`crate::session`]), each session carries
+//! its own CSRF token, and the header must also match the session's token,
+//! so a cookie planted by a sibling subdomain is rejected too.
+//!
+//! ```
+//! use keyrunes_rust_sdk::csrf::CsrfProtection;
+//!
+//! let csrf = CsrfProtection::new();
+//! let token = CsrfProtection::generate_token();
+//! let set_cookie = csrf.cookie(&token);
+//!
+//! let cookie_header = format!("keyrunes_csrf={}", token);
+//! assert!(csrf.verify("POST", Some(&cookie_header), Some(&token), None).is_ok());
+//! assert!(csrf.verify("POST", Some(&cookie_header), None, None).is_err());
+//! ```
+
+use crate::compare::constant_time_eq;
+use crate::middleware::messages::RejectionKind;
+use crate::session::{random_token, SessionData};
+
+/// Name of the CSRF cookie, by default
+const DEFAULT_COOKIE_NAME: &str = "keyrunes_csrf";
+
+/// Header carrying the CSRF token, by default
+const DEFAULT_HEADER_NAME: &str = "x-csrf-token";
+
+/// Methods that must not change state, and are therefore not checked
+const SAFE_METHODS: [&str; 4] = ["GET", "HEAD", "OPTIONS", "TRACE"];
+
+/// Double-submit CSRF token verification
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CsrfProtection {
+    cookie_name: String,
+    header_name: String,
+    secure: bool,
+}
+
+impl CsrfProtection {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the name of the CSRF cookie (default: `keyrunes_csrf`).
+    pub fn with_cookie_name<S: Into<String>>(mut self, name: S) -> Self {
+        self.cookie_name = name.into();
+        self
+    }
+
+    /// Sets the header carrying the CSRF token (default: `X-CSRF-Token`).
+    pub fn with_header_name<S: Into<String>>(mut self, name: S) -> Self {
+        self.header_name = name.into().to_ascii_lowercase();
+        self
+    }
+
+    /// Sets whether the CSRF cookie is only sent over HTTPS (the default).
+    pub fn with_secure(mut self, secure: bool) -> Self {
+        self.secure = secure;
+        self
+    }
+
+    /// Name of the header carrying the CSRF token (lowercase)
+    pub fn header_name(&self) -> &str {
+        &self.header_name
+    }
+
+    /// Generates a new random CSRF token.
+    pub fn generate_token() -> String {
+        random_token()
+    }
+
+    /// `Set-Cookie` value sending `token` to the browser.
+    ///
+    /// The cookie is not `HttpOnly`: the page's scripts read it to set the header.
+    pub fn cookie(&self, token: &str) -> String {
+        let mut cookie = format!("{}={}; Path=/; SameSite=Lax", self.cookie_name, token);
+        if self.secure {
+            cookie.push_str("; Secure");
+        }
+        cookie
+    }
+
+    /// `Set-Cookie` value sending the CSRF token of `session` to the browser.
+    pub fn session_cookie(&self, session: &SessionData) -> String {
+        self.cookie(&session.csrf_token)
+    }
+
+    /// Verifies a request.
+    ///
+    /// Safe methods (`GET`, `HEAD`, `OPTIONS`, `TRACE`) always pass. Other
+    /// methods must send the CSRF header matching the CSRF cookie and, when
+    /// the request belongs to a session, the session's token. Fails with
+    /// `Forbidden` otherwise.
+    pub fn verify(
+        &self,
+        method: &str,
+        cookie_header: Option<&str>,
+        header_value: Option<&str>,
+        session: Option<&SessionData>,
+    ) -> Result<(), RejectionKind> {
+        if SAFE_METHODS
+            .iter()
+            .any(|safe| method.eq_ignore_ascii_case(safe))
+        {
+            return Ok(());
+        }
+
+        let cookie = cookie_header.and_then(|header| self.cookie_value(header));
+        let valid = match (cookie, header_value) {
+            (Some(cookie), Some(header)) if !header.is_empty() => {
+                constant_time_eq(cookie, header)
+                    && session.is_none_or(|session| constant_time_eq(&session.csrf_token, header))
+            }
+            _ => false,
+        };
+
+        if !valid {
+            return Err(RejectionKind::Forbidden(
+                "CSRF token missing or invalid".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    fn cookie_value<'h>(&self, cookie_header: &'h str) -> Option<&'h str> {
+        cookie_header
+            .split(';')
+            .filter_map(|pair| pair.trim().split_once('='))
+            .find(|(name, _)| *name == self.cookie_name)
+            .map(|(_, value)| value)
+    }
+}
+
+impl Default for CsrfProtection {
+    fn default() -> Self {
+        Self {
+            cookie_name: DEFAULT_COOKIE_NAME.to_string(),
+            header_name: DEFAULT_HEADER_NAME.to_string(),
+            secure: true,
+        }
+    }
+}
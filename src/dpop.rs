@@ -0,0 +1,264 @@
+//! DPoP proof-of-possession tokens (feature `dpop`)
+//!
+//! Bearer tokens can be replayed by anyone who obtains them. With DPoP
+//! (RFC 9449), the client holds a private key, Keyrunes binds the tokens it
+//! issues to that key, and every request carries a proof signed with it:
+//! a short-lived JWT naming the HTTP method and URL, and the hash of the
+//! access token. A stolen token is useless without the key.
+//!
+//! Enable it on the client with a key; the client then attaches a proof to
+//! every request, sends its token as `Authorization: DPoP <token>`, and
+//! answers nonce challenges (`DPoP-Nonce`) by retrying the request once
+//! with the server's nonce.
+//!
+//! ```
+//! use keyrunes_rust_sdk::dpop::DpopKey;
+//! use keyrunes_rust_sdk::KeyrunesClient;
+//!
+//! # fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = KeyrunesClient::builder("https://keyrunes.example.com")
+//!     .dpop(DpopKey::generate()?)
+//!     .build()?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::error::{KeyrunesError, Result};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use jsonwebtoken::jwk::{
+    AlgorithmParameters, CommonParameters, EllipticCurve, EllipticCurveKeyParameters,
+    EllipticCurveKeyType, Jwk,
+};
+use jsonwebtoken::{Algorithm, EncodingKey, Header};
+use reqwest::header::HeaderValue;
+use reqwest::{RequestBuilder, Response, StatusCode};
+use ring::rand::{SecureRandom, SystemRandom};
+use ring::signature::{EcdsaKeyPair, KeyPair, ECDSA_P256_SHA256_FIXED_SIGNING};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::sync::Mutex;
+
+/// Header carrying the proof
+const HEADER_DPOP: &str = "DPoP";
+
+/// Header carrying the nonce the server expects in the next proofs
+const HEADER_DPOP_NONCE: &str = "DPoP-Nonce";
+
+/// Key pair signing DPoP proofs (ECDSA P-256, `ES256`)
+pub struct DpopKey {
+    pkcs8: Vec<u8>,
+    encoding_key: EncodingKey,
+    jwk: Jwk,
+    thumbprint: String,
+}
+
+impl DpopKey {
+    /// Generates a new ephemeral key pair.
+    pub fn generate() -> Result<Self> {
+        let pkcs8 =
+            EcdsaKeyPair::generate_pkcs8(&ECDSA_P256_SHA256_FIXED_SIGNING, &SystemRandom::new())
+                .map_err(|_| KeyrunesError::Other("Failed to generate DPoP key".to_string()))?;
+        Self::from_pkcs8(pkcs8.as_ref())
+    }
+
+    /// Loads a key pair from its PKCS#8 encoding (see [`DpopKey::pkcs8`]).
+    ///
+    /// Tokens are bound to the key: a process resuming saved tokens (see
+    /// [`TokenStore`](crate::client::TokenStore)) must reuse the key they
+    /// were issued for.
+    pub fn from_pkcs8(pkcs8: &[u8]) -> Result<Self> {
+        let key_pair = EcdsaKeyPair::from_pkcs8(
+            &ECDSA_P256_SHA256_FIXED_SIGNING,
+            pkcs8,
+            &SystemRandom::new(),
+        )
+        .map_err(|err| KeyrunesError::Other(format!("Invalid DPoP key: {}", err)))?;
+        // Uncompressed point: 0x04, then the x and y coordinates.
+        let point = key_pair.public_key().as_ref();
+        let x = URL_SAFE_NO_PAD.encode(&point[1..33]);
+        let y = URL_SAFE_NO_PAD.encode(&point[33..65]);
+
+        // RFC 7638: hash of the required members, in lexicographic order.
+        let canonical = format!(r#"{{"crv":"P-256","kty":"EC","x":"{}","y":"{}"}}"#, x, y);
+        let thumbprint = URL_SAFE_NO_PAD.encode(Sha256::digest(canonical.as_bytes()));
+
+        Ok(Self {
+            pkcs8: pkcs8.to_vec(),
+            encoding_key: EncodingKey::from_ec_der(pkcs8),
+            jwk: Jwk {
+                common: CommonParameters::default(),
+                algorithm: AlgorithmParameters::EllipticCurve(EllipticCurveKeyParameters {
+                    key_type: EllipticCurveKeyType::EC,
+                    curve: EllipticCurve::P256,
+                    x,
+                    y,
+                }),
+            },
+            thumbprint,
+        })
+    }
+
+    /// PKCS#8 encoding of the key pair, to save it
+    pub fn pkcs8(&self) -> &[u8] {
+        &self.pkcs8
+    }
+
+    /// Public key, as sent in the proofs
+    pub fn jwk(&self) -> &Jwk {
+        &self.jwk
+    }
+
+    /// JWK thumbprint of the public key (the `jkt` tokens are bound to)
+    pub fn thumbprint(&self) -> &str {
+        &self.thumbprint
+    }
+
+    /// Creates a proof for a request.
+    ///
+    /// `url` is stripped of its query and fragment. `access_token` is the
+    /// token sent with the request, if any, and `nonce` the last nonce
+    /// received from the server.
+    pub fn proof(
+        &self,
+        method: &str,
+        url: &str,
+        access_token: Option<&str>,
+        nonce: Option<&str>,
+    ) -> Result<String> {
+        let mut jti = [0u8; 16];
+        SystemRandom::new()
+            .fill(&mut jti)
+            .map_err(|_| KeyrunesError::Other("Failed to generate DPoP proof ID".to_string()))?;
+
+        let mut header = Header::new(Algorithm::ES256);
+        header.typ = Some("dpop+jwt".to_string());
+        header.jwk = Some(self.jwk.clone());
+
+        let claims = ProofClaims {
+            jti: URL_SAFE_NO_PAD.encode(jti),
+            htm: method,
+            htu: url.split(['?', '#']).next().unwrap_or(url),
+            iat: chrono::Utc::now().timestamp(),
+            ath: access_token.map(|token| URL_SAFE_NO_PAD.encode(Sha256::digest(token))),
+            nonce,
+        };
+
+        jsonwebtoken::encode(&header, &claims, &self.encoding_key)
+            .map_err(|err| KeyrunesError::Other(format!("Failed to sign DPoP proof: {}", err)))
+    }
+}
+
+impl std::fmt::Debug for DpopKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DpopKey")
+            .field("thumbprint", &self.thumbprint)
+            .finish()
+    }
+}
+
+/// Claims of a DPoP proof
+#[derive(Serialize)]
+struct ProofClaims<'a> {
+    jti: String,
+    htm: &'a str,
+    htu: &'a str,
+    iat: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ath: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    nonce: Option<&'a str>,
+}
+
+/// DPoP state of a client: its key and the server's last nonce
+#[derive(Debug)]
+pub(crate) struct Dpop {
+    key: DpopKey,
+    nonce: Mutex<Option<String>>,
+}
+
+impl Dpop {
+    pub(crate) fn new(key: DpopKey) -> Self {
+        Self {
+            key,
+            nonce: Mutex::new(None),
+        }
+    }
+
+    /// Sends `request` with a proof, and `token` as a DPoP token.
+    ///
+    /// A request rejected with a new nonce is retried once with it, when its
+    /// body can be replayed.
+    pub(crate) async fn send(
+        &self,
+        client: &reqwest::Client,
+        request: RequestBuilder,
+        token: Option<&str>,
+    ) -> Result<Response> {
+        let mut request = request.build()?;
+        if let Some(token) = token {
+            request.headers_mut().insert(
+                reqwest::header::AUTHORIZATION,
+                header_value(&format!("DPoP {}", token))?,
+            );
+        }
+
+        let retry = request.try_clone();
+        let sent_nonce = self.sign(&mut request, token)?;
+        let response = client.execute(request).await?;
+        let nonce = self.remember_nonce(&response);
+
+        match retry {
+            Some(mut retry) if is_nonce_challenge(&response) && nonce != sent_nonce => {
+                self.sign(&mut retry, token)?;
+                let response = client.execute(retry).await?;
+                self.remember_nonce(&response);
+                Ok(response)
+            }
+            _ => Ok(response),
+        }
+    }
+
+    /// Attaches a proof to `request`, returning the nonce it carries.
+    fn sign(&self, request: &mut reqwest::Request, token: Option<&str>) -> Result<Option<String>> {
+        let nonce = self.nonce.lock().unwrap_or_else(|e| e.into_inner()).clone();
+        let proof = self.key.proof(
+            request.method().as_str(),
+            request.url().as_str(),
+            token,
+            nonce.as_deref(),
+        )?;
+        request
+            .headers_mut()
+            .insert(HEADER_DPOP, header_value(&proof)?);
+        Ok(nonce)
+    }
+
+    /// Keeps the nonce sent by the server, returning the current nonce.
+    fn remember_nonce(&self, response: &Response) -> Option<String> {
+        let mut nonce = self.nonce.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(value) = response
+            .headers()
+            .get(HEADER_DPOP_NONCE)
+            .and_then(|value| value.to_str().ok())
+        {
+            *nonce = Some(value.to_string());
+        }
+        nonce.clone()
+    }
+}
+
+/// Whether the server rejected a request for want of a (fresh) nonce
+///
+/// Token endpoints answer `400`, resources `401`; both send the nonce to use.
+fn is_nonce_challenge(response: &Response) -> bool {
+    matches!(
+        response.status(),
+        StatusCode::BAD_REQUEST | StatusCode::UNAUTHORIZED
+    ) && response.headers().contains_key(HEADER_DPOP_NONCE)
+}
+
+fn header_value(value: &str) -> Result<HeaderValue> {
+    HeaderValue::from_str(value)
+        .map_err(|_| KeyrunesError::Other("Invalid DPoP header value".to_string()))
+}
@@ -0,0 +1,336 @@
+//! Paths of the Keyrunes API endpoints
+//!
+//! Every endpoint the SDK calls is listed here, relative to the base URL:
+//! constants for fixed paths, functions for paths with parameters. Values
+//! inserted in paths are used as given; callers encode them when needed.
+//!
+//! Paths under [`API_PREFIX`] are rewritten by the client's
+//! [`EndpointResolver`], so a server exposing its API under another prefix
+//! or a versioned one (e.g., `/api/v2`) only needs a different resolver.
+//! OAuth, SCIM and `.well-known` paths are never rewritten.
+//!
+//! ## Quick Start
+//!
+//! ```
+//! use keyrunes_rust_sdk::endpoints::{self, EndpointResolver};
+//! use keyrunes_rust_sdk::KeyrunesClient;
+//!
+//! let resolver = EndpointResolver::new().version("v2");
+//! assert_eq!(resolver.resolve(endpoints::LOGIN), "/api/v2/login");
+//! assert_eq!(resolver.resolve(endpoints::JWKS), "/.well-known/jwks.json");
+//!
+//! let client = KeyrunesClient::builder("https://keyrunes.example.com")
+//!     .endpoints(resolver)
+//!     .build()
+//!     .expect("Invalid configuration");
+//! ```
+
+use std::borrow::Cow;
+use std::fmt::Display;
+
+/// Prefix of the paths rewritten by [`EndpointResolver`]
+pub const API_PREFIX: &str = "/api";
+
+/// Password login
+pub const LOGIN: &str = "/api/login";
+/// Answer to a login challenge
+pub const LOGIN_CHALLENGE: &str = "/api/login/challenge";
+/// Login with an MFA backup code
+pub const LOGIN_BACKUP_CODE: &str = "/api/login/backup-code";
+/// Registration of users and administrators
+pub const REGISTER: &str = "/api/register";
+/// Current user
+pub const ME: &str = "/api/me";
+/// Username of the current user
+pub const ME_USERNAME: &str = "/api/me/username";
+/// Password of the current user
+pub const ME_PASSWORD: &str = "/api/me/password";
+/// Verification email of the current user
+pub const ME_EMAIL_VERIFICATION: &str = "/api/me/email/verification";
+/// Email verification of the current user, with the emailed code
+pub const ME_EMAIL_VERIFY: &str = "/api/me/email/verify";
+/// Password reset request (forgot password)
+pub const PASSWORD_RESET: &str = "/api/password/reset";
+/// Password reset confirmation
+pub const PASSWORD_RESET_CONFIRM: &str = "/api/password/reset/confirm";
+/// Step-up authentication
+pub const STEP_UP: &str = "/api/step-up";
+/// Token refresh
+pub const REFRESH: &str = "/api/refresh";
+/// Server-side logout
+pub const LOGOUT: &str = "/api/logout";
+/// Security events reported to the risk engine
+pub const SECURITY_EVENTS: &str = "/api/security/events";
+/// Audit events
+pub const AUDIT_EVENTS: &str = "/api/audit/events";
+/// MFA push challenges
+pub const MFA_PUSH: &str = "/api/mfa/push";
+/// MFA backup codes
+pub const MFA_BACKUP_CODES: &str = "/api/mfa/backup-codes";
+/// Magic link requests
+pub const MAGIC_LINK: &str = "/api/passwordless/magic-link";
+/// Magic link verification
+pub const MAGIC_LINK_VERIFY: &str = "/api/passwordless/magic-link/verify";
+/// One-time code requests
+pub const OTP: &str = "/api/passwordless/otp";
+/// One-time code verification
+pub const OTP_VERIFY: &str = "/api/passwordless/otp/verify";
+/// CSV user imports
+pub const IMPORTS_USERS: &str = "/api/imports/users";
+/// Bulk imports of users and groups (migrations)
+pub const IMPORTS_BULK: &str = "/api/imports/bulk";
+/// Exports
+pub const EXPORTS: &str = "/api/exports";
+/// Resources of the relationship graph
+pub const RESOURCES: &str = "/api/resources";
+/// Relations between users and resources
+pub const RELATIONS: &str = "/api/relations";
+/// Relation checks
+pub const RELATIONS_CHECK: &str = "/api/relations/check";
+/// Authorization checks on hierarchical resources
+pub const AUTHORIZE_HIERARCHY: &str = "/api/authorize/hierarchy";
+/// Delegations
+pub const DELEGATIONS: &str = "/api/delegations";
+/// Tenant statistics
+pub const ADMIN_STATS: &str = "/api/admin/stats";
+/// Tenant settings
+pub const ADMIN_SETTINGS: &str = "/api/admin/settings";
+/// Hosted-page branding
+pub const ADMIN_BRANDING: &str = "/api/admin/branding";
+/// Custom domain
+pub const ADMIN_CUSTOM_DOMAIN: &str = "/api/admin/custom-domain";
+/// Verification of the custom domain
+pub const ADMIN_CUSTOM_DOMAIN_VERIFY: &str = "/api/admin/custom-domain/verify";
+/// Email templates
+pub const ADMIN_EMAIL_TEMPLATES: &str = "/api/admin/email-templates";
+/// Event replays
+pub const ADMIN_EVENTS_REPLAY: &str = "/api/admin/events/replay";
+/// Groups
+pub const ADMIN_GROUPS: &str = "/api/admin/groups";
+/// IP restrictions
+pub const ADMIN_IP_RULES: &str = "/api/admin/ip-rules";
+/// Permission catalog
+pub const ADMIN_PERMISSIONS: &str = "/api/admin/permissions";
+/// Permissions granted to groups
+pub const ADMIN_ROLE_BINDINGS: &str = "/api/admin/role-bindings";
+/// Service accounts
+pub const ADMIN_SERVICE_ACCOUNTS: &str = "/api/admin/service-accounts";
+/// Token signing keys
+pub const ADMIN_SIGNING_KEYS: &str = "/api/admin/signing-keys";
+/// Rotation of the signing key
+pub const ADMIN_SIGNING_KEYS_ROTATE: &str = "/api/admin/signing-keys/rotate";
+/// Imports of users with a password hash
+pub const ADMIN_USERS_IMPORT: &str = "/api/admin/users/import";
+/// OIDC end-session endpoint
+pub const END_SESSION: &str = "/oauth/logout";
+/// OIDC userinfo endpoint
+pub const USERINFO: &str = "/oauth/userinfo";
+/// Public keys tokens are signed with
+pub const JWKS: &str = "/.well-known/jwks.json";
+/// SCIM 2.0 service provider
+pub const SCIM: &str = "/scim/v2";
+
+/// User
+pub fn user(user_id: impl Display) -> String {
+    format!("/api/users/{}", user_id)
+}
+
+/// Membership of a user in a group
+pub fn user_group(user_id: impl Display, group_id: impl Display) -> String {
+    format!("/api/users/{}/groups/{}", user_id, group_id)
+}
+
+/// Resources a user can access
+pub fn user_accessible(user_id: impl Display) -> String {
+    format!("/api/users/{}/accessible", user_id)
+}
+
+/// Entitlements of a user
+pub fn user_entitlements(user_id: impl Display) -> String {
+    format!("/api/users/{}/entitlements", user_id)
+}
+
+/// Permissions of a user
+pub fn user_permissions(user_id: impl Display) -> String {
+    format!("/api/users/{}/permissions", user_id)
+}
+
+/// Devices of a user
+pub fn user_devices(user_id: impl Display) -> String {
+    format!("/api/users/{}/devices", user_id)
+}
+
+/// Login history of a user
+pub fn user_login_history(user_id: impl Display) -> String {
+    format!("/api/users/{}/login-history", user_id)
+}
+
+/// Accounts linked to a user
+pub fn user_linked_accounts(user_id: impl Display) -> String {
+    format!("/api/users/{}/linked-accounts", user_id)
+}
+
+/// Username history of a user
+pub fn user_username_history(user_id: impl Display) -> String {
+    format!("/api/users/{}/username-history", user_id)
+}
+
+/// Avatar of a user
+pub fn user_avatar(user_id: impl Display) -> String {
+    format!("/api/users/{}/avatar", user_id)
+}
+
+/// Quota of a user for a meter
+pub fn user_quota(user_id: impl Display, meter: impl Display) -> String {
+    format!("/api/users/{}/quotas/{}", user_id, meter)
+}
+
+/// Consumption of a user's quota
+pub fn user_quota_consume(user_id: impl Display, meter: impl Display) -> String {
+    format!("/api/users/{}/quotas/{}/consume", user_id, meter)
+}
+
+/// Device
+pub fn device(device_id: impl Display) -> String {
+    format!("/api/devices/{}", device_id)
+}
+
+/// Trust of a device
+pub fn device_trust(device_id: impl Display) -> String {
+    format!("/api/devices/{}/trust", device_id)
+}
+
+/// Resource of the relationship graph
+pub fn resource(resource_type: impl Display, resource_id: impl Display) -> String {
+    format!("/api/resources/{}/{}", resource_type, resource_id)
+}
+
+/// MFA push challenge
+pub fn mfa_push_challenge(challenge_id: impl Display) -> String {
+    format!("/api/mfa/push/{}", challenge_id)
+}
+
+/// Delegation
+pub fn delegation(delegation_id: impl Display) -> String {
+    format!("/api/delegations/{}", delegation_id)
+}
+
+/// Background job
+pub fn job(job_id: impl Display) -> String {
+    format!("/api/jobs/{}", job_id)
+}
+
+/// Cancellation of a background job
+pub fn job_cancel(job_id: impl Display) -> String {
+    format!("/api/jobs/{}/cancel", job_id)
+}
+
+/// Download of an export
+pub fn export_download(job_id: impl Display) -> String {
+    format!("/api/exports/{}/download", job_id)
+}
+
+/// Email template
+pub fn admin_email_template(kind: impl Display) -> String {
+    format!("/api/admin/email-templates/{}", kind)
+}
+
+/// Rendering of an email template
+pub fn admin_email_template_render(kind: impl Display) -> String {
+    format!("/api/admin/email-templates/{}/render", kind)
+}
+
+/// Deliveries of a webhook
+pub fn admin_webhook_deliveries(webhook_id: impl Display) -> String {
+    format!("/api/admin/webhooks/{}/deliveries", webhook_id)
+}
+
+/// Group
+pub fn admin_group(group_id: impl Display) -> String {
+    format!("/api/admin/groups/{}", group_id)
+}
+
+/// Members of a group
+pub fn admin_group_members(group_id: impl Display) -> String {
+    format!("/api/admin/groups/{}/members", group_id)
+}
+
+/// Membership of a user in a group
+pub fn admin_group_member(group_id: impl Display, user_id: impl Display) -> String {
+    format!("/api/admin/groups/{}/members/{}", group_id, user_id)
+}
+
+/// IP restriction
+pub fn admin_ip_rule(rule_id: impl Display) -> String {
+    format!("/api/admin/ip-rules/{}", rule_id)
+}
+
+/// Permission of the catalog
+pub fn admin_permission(key: impl Display) -> String {
+    format!("/api/admin/permissions/{}", key)
+}
+
+/// Permission granted to a group
+pub fn admin_role_binding(binding_id: impl Display) -> String {
+    format!("/api/admin/role-bindings/{}", binding_id)
+}
+
+/// Service account
+pub fn admin_service_account(account_id: impl Display) -> String {
+    format!("/api/admin/service-accounts/{}", account_id)
+}
+
+/// Retirement of a signing key
+pub fn admin_signing_key_retire(kid: impl Display) -> String {
+    format!("/api/admin/signing-keys/{}/retire", kid)
+}
+
+/// Maps endpoint paths to the paths served by the Keyrunes deployment
+///
+/// By default paths are used as listed in this module. A different prefix
+/// or an API version rewrites the [`API_PREFIX`] of every path.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EndpointResolver {
+    prefix: String,
+}
+
+impl EndpointResolver {
+    /// Creates a resolver keeping paths as they are.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Serves the API under `prefix` instead of `/api` (e.g., "/auth/api").
+    pub fn prefix(mut self, prefix: &str) -> Self {
+        self.prefix = format!("/{}", prefix.trim_matches('/'));
+        self
+    }
+
+    /// Serves the API under a version segment after the prefix (e.g.,
+    /// "v2" maps `/api/login` to `/api/v2/login`).
+    pub fn version(mut self, version: &str) -> Self {
+        self.prefix = format!("{}/{}", self.prefix, version.trim_matches('/'));
+        self
+    }
+
+    /// Path served by the deployment for an endpoint path
+    pub fn resolve<'a>(&self, path: &'a str) -> Cow<'a, str> {
+        match path.strip_prefix(API_PREFIX) {
+            Some(rest)
+                if self.prefix != API_PREFIX
+                    && (rest.is_empty() || rest.starts_with(['/', '?'])) =>
+            {
+                Cow::Owned(format!("{}{}", self.prefix, rest))
+            }
+            _ => Cow::Borrowed(path),
+        }
+    }
+}
+
+impl Default for EndpointResolver {
+    fn default() -> Self {
+        Self {
+            prefix: API_PREFIX.to_string(),
+        }
+    }
+}
@@ -0,0 +1,72 @@
+//! Per-user entitlements (plans, feature flags)
+//!
+//! Keyrunes stores entitlements alongside identities, so plan gating can
+//! use the same infrastructure as authentication checks. Entitlements are
+//! fetched with [`KeyrunesClient::get_entitlements`](crate::KeyrunesClient::get_entitlements)
+//! and cached per user for [`ENTITLEMENT_CACHE_TTL`].
+//!
+//! The framework integrations gate routes with a `RequireEntitlement`
+//! extractor, parameterized by an [`EntitlementKey`] marker.
+//!
+//! ## Quick Start
+//!
+//! ```
+//! use keyrunes_rust_sdk::entitlements::EntitlementKey;
+//! use keyrunes_rust_sdk::KeyrunesClient;
+//!
+//! struct AdvancedReports;
+//!
+//! impl EntitlementKey for AdvancedReports {
+//!     const KEY: &'static str = "advanced_reports";
+//! }
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = KeyrunesClient::new("https://keyrunes.example.com")?;
+//! if client.has_entitlement("123", AdvancedReports::KEY).await? {
+//!     println!("Reports unlocked");
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::models::Entitlement;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+/// How long fetched entitlements are reused before being fetched again
+pub const ENTITLEMENT_CACHE_TTL: Duration = Duration::from_secs(60);
+
+/// Type-level entitlement key used by the `RequireEntitlement` extractors
+pub trait EntitlementKey: Send + Sync + 'static {
+    /// Key of the required entitlement
+    const KEY: &'static str;
+}
+
+/// Entitlements cached per user ID
+#[derive(Debug, Default)]
+pub(crate) struct EntitlementCache {
+    entries: Mutex<HashMap<String, (Instant, Vec<Entitlement>)>>,
+}
+
+impl EntitlementCache {
+    /// Gets the cached entitlements of a user, if still fresh.
+    pub(crate) async fn get(&self, user_id: &str) -> Option<Vec<Entitlement>> {
+        let entries = self.entries.lock().await;
+        entries
+            .get(user_id)
+            .filter(|(fetched_at, _)| fetched_at.elapsed() < ENTITLEMENT_CACHE_TTL)
+            .map(|(_, entitlements)| entitlements.clone())
+    }
+
+    pub(crate) async fn put(&self, user_id: &str, entitlements: Vec<Entitlement>) {
+        self.entries
+            .lock()
+            .await
+            .insert(user_id.to_string(), (Instant::now(), entitlements));
+    }
+
+    pub(crate) async fn invalidate(&self, user_id: &str) {
+        self.entries.lock().await.remove(user_id);
+    }
+}
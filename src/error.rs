@@ -10,7 +10,11 @@
 //! ```
 //! use keyrunes_rust_sdk::KeyrunesError;
 //!
-//! let error = KeyrunesError::AuthenticationError("Invalid credentials".to_string());
+//! let error = KeyrunesError::AuthenticationError {
+//!     message: "Invalid credentials".to_string(),
+//!     code: Some("invalid_credentials".to_string()),
+//! };
+//! assert_eq!(error.code(), Some("invalid_credentials"));
 //! println!("Error: {}", error);
 //! ```
 
@@ -20,23 +24,43 @@ pub type Result<T> = std::result::Result<T, KeyrunesError>;
 ///
 /// This enum represents all types of errors that can occur
 /// during interaction with the Keyrunes API.
-#[derive(Debug, thiserror::Error)]
+#[derive(Debug, Clone, thiserror::Error)]
 pub enum KeyrunesError {
     /// Authentication error (invalid credentials, expired token, etc.)
-    #[error("Authentication error: {0}")]
-    AuthenticationError(String),
+    #[error("Authentication error: {message}")]
+    AuthenticationError {
+        /// Error message
+        message: String,
+        /// Machine-readable error code sent by the server, if any
+        code: Option<String>,
+    },
 
     /// Authorization error (access denied, insufficient permissions, etc.)
-    #[error("Authorization error: {0}")]
-    AuthorizationError(String),
+    #[error("Authorization error: {message}")]
+    AuthorizationError {
+        /// Error message
+        message: String,
+        /// Machine-readable error code sent by the server, if any
+        code: Option<String>,
+    },
 
     /// Group not found
-    #[error("Group not found: {0}")]
-    GroupNotFoundError(String),
+    #[error("Group not found: {message}")]
+    GroupNotFoundError {
+        /// Error message
+        message: String,
+        /// Machine-readable error code sent by the server, if any
+        code: Option<String>,
+    },
 
     /// User not found
-    #[error("User not found: {0}")]
-    UserNotFoundError(String),
+    #[error("User not found: {message}")]
+    UserNotFoundError {
+        /// Error message
+        message: String,
+        /// Machine-readable error code sent by the server, if any
+        code: Option<String>,
+    },
 
     /// Network error (timeout, connection lost, etc.)
     #[error("Network error: {0}")]
@@ -58,11 +82,178 @@ pub enum KeyrunesError {
     #[error("Invalid or missing token")]
     InvalidToken,
 
+    /// The token is not addressed to an accepted audience
+    #[error("Invalid audience: expected one of {expected:?}, got {found:?}")]
+    InvalidAudience {
+        /// Accepted audiences
+        expected: Vec<String>,
+        /// Audiences of the token
+        found: Vec<String>,
+    },
+
+    /// The session must be upgraded to a higher authentication level
+    #[error("Step-up authentication required: {0} level needed")]
+    StepUpRequired(crate::claims::AuthLevel),
+
+    /// The user authenticated too long ago and must log in again
+    #[error("Re-authentication required: last login older than {0} seconds")]
+    ReauthenticationRequired(u64),
+
+    /// A rotated refresh token was used again: the server revoked the session
+    /// and the user must log in again
+    #[error("Refresh token reuse detected: login required")]
+    RefreshTokenReused,
+
+    /// Too many requests for the current identity
+    #[error("Rate limit exceeded: retry after {0} seconds")]
+    RateLimitExceeded(u64),
+
+    /// Too many failed login attempts for the identity or IP address
+    #[error("Too many failed login attempts: locked for {0} seconds")]
+    LoginLocked(u64),
+
+    /// The server demands extra verification to complete the login
+    #[error("Login challenge required: {0}")]
+    ChallengeRequired(String),
+
+    /// The one-time code expired
+    #[error("One-time code expired")]
+    OtpExpired,
+
+    /// The one-time code is invalid
+    #[error("Invalid one-time code")]
+    InvalidOtp {
+        /// Verification attempts left before the code is invalidated (if known)
+        attempts_remaining: Option<u32>,
+    },
+
+    /// A new one-time code cannot be sent yet
+    #[error("One-time code resend throttled: retry after {0} seconds")]
+    OtpThrottled(u64),
+
+    /// The password reset token expired
+    #[error("Password reset token expired")]
+    ResetTokenExpired,
+
+    /// The password reset token is unknown or was already used
+    #[error("Invalid password reset token")]
+    InvalidResetToken,
+
+    /// The new password does not satisfy the password policy
+    #[error("Password rejected: {0}")]
+    PasswordRejected(String),
+
+    /// The requested username is already in use
+    #[error("Username already taken: {0}")]
+    UsernameTaken(String),
+
+    /// A downloaded file does not match the checksum announced by the server
+    #[error("Checksum mismatch: expected {expected}, got {actual}")]
+    ChecksumMismatch {
+        /// Checksum announced by the server
+        expected: String,
+        /// Checksum of the downloaded file
+        actual: String,
+    },
+
+    /// A background job failed
+    #[error("Job {job_id} failed: {reason}")]
+    JobFailed {
+        /// Job ID
+        job_id: String,
+        /// Failure reason reported by the server
+        reason: String,
+    },
+
+    /// A background job was cancelled
+    #[error("Job cancelled: {0}")]
+    JobCancelled(String),
+
+    /// A background job did not finish in time
+    #[error("Timed out waiting for job: {0}")]
+    JobTimedOut(String),
+
+    /// A metered quota is exhausted
+    #[error("Quota exceeded for meter: {0}")]
+    QuotaExceeded(String),
+
+    /// Malformed hierarchical resource path
+    #[error("Invalid resource path: {0}")]
+    InvalidResourcePath(String),
+
+    /// Malformed CIDR block
+    #[error("Invalid CIDR: {0}")]
+    InvalidCidr(String),
+
+    /// Password hash not in the format of its algorithm
+    #[error("Invalid password hash: {0}")]
+    InvalidPasswordHash(String),
+
+    /// Error response carrying a machine-readable code
+    ///
+    /// Returned instead of `HttpError` or `Other` when the server includes a
+    /// `code` in the error body; 401, 403 and user or group 404 responses
+    /// keep their dedicated variants, which carry the code as well. The
+    /// message may be localized
+    /// (see [`KeyrunesClientBuilder::locale`](crate::KeyrunesClientBuilder::locale)),
+    /// so match on [`code`](KeyrunesError::code) rather than on the message.
+    #[error("API error {status} ({code}): {message}")]
+    Api {
+        /// HTTP status code
+        status: u16,
+        /// Machine-readable error code (e.g., "username_taken")
+        code: String,
+        /// Human-readable message, in the requested locale when supported
+        message: String,
+    },
+
+    /// A provisioning spec is inconsistent (duplicates, unknown references)
+    #[error("Invalid provisioning spec: {0}")]
+    InvalidProvisioningSpec(String),
+
+    /// A change of a provisioning plan failed
+    #[error("Provisioning failed at {change}: {source}")]
+    ProvisioningFailed {
+        /// Change that failed (e.g., "+ group engineering")
+        change: String,
+        /// Error returned for the change
+        source: Box<KeyrunesError>,
+        /// Whether the changes applied before were all undone
+        rolled_back: bool,
+    },
+
+    /// The server answered with an API version incompatible with the pinned one
+    #[error("Incompatible API version: requested {requested}, server answered {served:?}")]
+    IncompatibleApiVersion {
+        /// Version pinned with `KeyrunesClientBuilder::api_version`
+        requested: String,
+        /// Version echoed by the server, if any
+        served: Option<String>,
+    },
+
+    /// A break-glass incident or reason is empty or cannot be sent as a header
+    #[error("Invalid audit annotation: {0}")]
+    InvalidAuditAnnotation(String),
+
     /// Other uncategorized errors
     #[error("Error: {0}")]
     Other(String),
 }
 
+impl KeyrunesError {
+    /// Machine-readable error code sent by the server, if any
+    pub fn code(&self) -> Option<&str> {
+        match self {
+            KeyrunesError::Api { code, .. } => Some(code),
+            KeyrunesError::AuthenticationError { code, .. }
+            | KeyrunesError::AuthorizationError { code, .. }
+            | KeyrunesError::GroupNotFoundError { code, .. }
+            | KeyrunesError::UserNotFoundError { code, .. } => code.as_deref(),
+            _ => None,
+        }
+    }
+}
+
 impl From<reqwest::Error> for KeyrunesError {
     fn from(err: reqwest::Error) -> Self {
         if err.is_timeout() || err.is_connect() {
@@ -84,3 +275,16 @@ impl From<url::ParseError> for KeyrunesError {
         KeyrunesError::InvalidUrl(err.to_string())
     }
 }
+
+impl From<std::io::Error> for KeyrunesError {
+    fn from(err: std::io::Error) -> Self {
+        KeyrunesError::Other(format!("I/O error: {}", err))
+    }
+}
+
+#[cfg(feature = "redis")]
+impl From<redis::RedisError> for KeyrunesError {
+    fn from(err: redis::RedisError) -> Self {
+        KeyrunesError::Other(format!("Redis error: {}", err))
+    }
+}
@@ -0,0 +1,346 @@
+//! Typed registry of the groups and permissions an application relies on
+//!
+//! Group names spelled out as strings across handlers, middlewares and
+//! provisioning specs drift silently from the server: a typo or a renamed
+//! group only shows up as a denied request. [`keyrunes_groups!`] declares
+//! the groups once, as an enum implementing [`KnownGroups`]; its variants
+//! convert to the group names wherever the SDK takes one
+//! ([`KeyrunesClient::has_group`], `KeyrunesRequireGroup::new`,
+//! [`RouteRequirements`](crate::requirements::RouteRequirements) through
+//! `as_str`), `RequireGroup<G>` (Axum and Rocket) only accepts the groups of
+//! the set, [`ProvisioningSpec::with_known_groups`] provisions them, and
+//! [`missing_groups`] reports those the server does not know.
+//!
+//! [`keyrunes_permissions!`] does the same for permission keys, with
+//! [`KnownPermissions`], [`ProvisioningSpec::with_known_permissions`] and
+//! [`missing_permissions`].
+//!
+//! ## Quick Start
+//!
+//! ```
+//! use keyrunes_rust_sdk::{groups, keyrunes_groups, KeyrunesClient};
+//!
+//! keyrunes_groups! {
+//!     /// Groups of the application
+//!     pub enum AppGroup {
+//!         Admins => "admins",
+//!         Editors => "editors",
+//!     }
+//! }
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = KeyrunesClient::new("https://keyrunes.example.com")?;
+//! client.login("admin@example.com", "password123", None).await?;
+//! let missing = groups::missing_groups::<AppGroup>(&client).await?;
+//! assert!(missing.is_empty(), "groups missing on the server: {:?}", missing);
+//!
+//! if client.has_group("123", AppGroup::Editors).await? {
+//!     println!("User 123 can edit");
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`keyrunes_groups!`]: crate::keyrunes_groups
+//! [`keyrunes_permissions!`]: crate::keyrunes_permissions
+//! [`ProvisioningSpec::with_known_groups`]: crate::provisioning::ProvisioningSpec::with_known_groups
+//! [`ProvisioningSpec::with_known_permissions`]: crate::provisioning::ProvisioningSpec::with_known_permissions
+
+use crate::client::KeyrunesClient;
+use crate::error::Result;
+
+/// Closed set of groups, generated by [`keyrunes_groups!`](crate::keyrunes_groups)
+pub trait KnownGroups: Copy + Send + Sync + 'static {
+    /// Every group of the set, in declaration order
+    const ALL: &'static [Self];
+
+    /// Name of the group on the server
+    fn name(self) -> &'static str;
+
+    /// Returns the group named `name`, if it belongs to the set.
+    fn parse(name: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|group| group.name() == name)
+    }
+}
+
+/// Closed set of permissions, generated by [`keyrunes_permissions!`](crate::keyrunes_permissions)
+pub trait KnownPermissions: Copy + Send + Sync + 'static {
+    /// Every permission of the set, in declaration order
+    const ALL: &'static [Self];
+
+    /// Key of the permission on the server
+    fn key(self) -> &'static str;
+
+    /// Description of the permission, used when provisioning it
+    fn description(self) -> &'static str;
+
+    /// Returns the permission with key `key`, if it belongs to the set.
+    fn parse(key: &str) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|permission| permission.key() == key)
+    }
+}
+
+/// Group named by a request, such as the `group_id` of `RequireGroup`
+///
+/// Implemented by `String`, which accepts any name, and by the enums of
+/// [`keyrunes_groups!`](crate::keyrunes_groups), which only accept the
+/// names of their set.
+pub trait GroupName: Sized {
+    /// Returns the group named `name`, if it is accepted.
+    fn from_name(name: &str) -> Option<Self>;
+
+    /// Name of the group on the server
+    fn as_name(&self) -> &str;
+}
+
+impl GroupName for String {
+    fn from_name(name: &str) -> Option<Self> {
+        Some(name.to_string())
+    }
+
+    fn as_name(&self) -> &str {
+        self
+    }
+}
+
+impl<G: KnownGroups> GroupName for G {
+    fn from_name(name: &str) -> Option<Self> {
+        G::parse(name)
+    }
+
+    fn as_name(&self) -> &str {
+        self.name()
+    }
+}
+
+/// Returns the groups of `G` that do not exist on the server.
+///
+/// Meant to run at startup or in CI, so that drift between the code and the
+/// tenant fails loudly. Requires an administrator token.
+///
+/// # Returns
+///
+/// Returns `Result<Vec<G>, KeyrunesError>`:
+/// - `Ok(groups)` with the missing groups, in declaration order
+/// - `Err(KeyrunesError::AuthorizationError)` if the token is not an administrator's
+pub async fn missing_groups<G: KnownGroups>(client: &KeyrunesClient) -> Result<Vec<G>> {
+    let existing = client.admin().list_groups().await?;
+    Ok(G::ALL
+        .iter()
+        .copied()
+        .filter(|group| {
+            !existing
+                .iter()
+                .any(|existing| existing.name == group.name())
+        })
+        .collect())
+}
+
+/// Returns the permissions of `P` that do not exist on the server.
+///
+/// The permission counterpart of [`missing_groups`]. Requires an
+/// administrator token.
+///
+/// # Returns
+///
+/// Returns `Result<Vec<P>, KeyrunesError>`:
+/// - `Ok(permissions)` with the missing permissions, in declaration order
+/// - `Err(KeyrunesError::AuthorizationError)` if the token is not an administrator's
+pub async fn missing_permissions<P: KnownPermissions>(client: &KeyrunesClient) -> Result<Vec<P>> {
+    let existing = client.admin().list_permissions().await?;
+    Ok(P::ALL
+        .iter()
+        .copied()
+        .filter(|permission| {
+            !existing
+                .iter()
+                .any(|existing| existing.key == permission.key())
+        })
+        .collect())
+}
+
+/// Declares an enum of names with `ALL`, `as_str`, `Display`, `AsRef<str>`
+/// and `Into<String>`; shared by the registry macros.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __keyrunes_names {
+    (
+        $(#[$meta:meta])*
+        $vis:vis enum $name:ident {
+            $($(#[$variant_meta:meta])* $variant:ident => $value:literal),+ $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+        $vis enum $name {
+            $($(#[$variant_meta])* $variant,)+
+        }
+
+        impl $name {
+            /// Every variant, in declaration order
+            pub const ALL: &'static [Self] = &[$(Self::$variant),+];
+
+            /// Name on the server
+            pub const fn as_str(self) -> &'static str {
+                match self {
+                    $(Self::$variant => $value,)+
+                }
+            }
+        }
+
+        impl ::std::fmt::Display for $name {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                f.write_str(self.as_str())
+            }
+        }
+
+        impl ::std::convert::AsRef<str> for $name {
+            fn as_ref(&self) -> &str {
+                self.as_str()
+            }
+        }
+
+        impl ::std::convert::From<$name> for ::std::string::String {
+            fn from(value: $name) -> Self {
+                value.as_str().to_string()
+            }
+        }
+    };
+}
+
+/// Declares a typed enum of group names.
+///
+/// Each variant maps to the name of a group on the server. The enum gets
+/// `as_str` (a `const fn`), `ALL`, [`KnownGroups`], `Display`, `AsRef<str>`,
+/// `FromStr` (failing with `GroupNotFoundError` for unknown names) and
+/// `Into<String>`, so it can be passed wherever a group name is expected.
+///
+/// Without an enum header, the enum is named `KeyrunesGroup`:
+///
+/// ```
+/// keyrunes_rust_sdk::keyrunes_groups! { Admins => "admins", Editors => "editors" }
+///
+/// assert_eq!(KeyrunesGroup::Admins.as_str(), "admins");
+/// assert_eq!("editors".parse::<KeyrunesGroup>().unwrap(), KeyrunesGroup::Editors);
+/// ```
+#[macro_export]
+macro_rules! keyrunes_groups {
+    (
+        $(#[$meta:meta])*
+        $vis:vis enum $name:ident {
+            $($(#[$variant_meta:meta])* $variant:ident => $group:literal),+ $(,)?
+        }
+    ) => {
+        $crate::__keyrunes_names! {
+            $(#[$meta])*
+            $vis enum $name {
+                $($(#[$variant_meta])* $variant => $group),+
+            }
+        }
+
+        impl $crate::groups::KnownGroups for $name {
+            const ALL: &'static [Self] = Self::ALL;
+
+            fn name(self) -> &'static str {
+                self.as_str()
+            }
+        }
+
+        impl ::std::str::FromStr for $name {
+            type Err = $crate::KeyrunesError;
+
+            fn from_str(name: &str) -> ::std::result::Result<Self, Self::Err> {
+                <Self as $crate::groups::KnownGroups>::parse(name).ok_or_else(|| {
+                    $crate::KeyrunesError::GroupNotFoundError {
+                        message: name.to_string(),
+                        code: None,
+                    }
+                })
+            }
+        }
+    };
+    ($($(#[$variant_meta:meta])* $variant:ident => $group:literal),+ $(,)?) => {
+        $crate::keyrunes_groups! {
+            /// Groups of the application
+            pub enum KeyrunesGroup {
+                $($(#[$variant_meta])* $variant => $group),+
+            }
+        }
+    };
+}
+
+/// Declares a typed enum of permission keys.
+///
+/// Each variant maps to the key and the description of a permission on the
+/// server. The enum gets `as_str` (a `const fn`, usable in
+/// [`RouteRequirements::PERMISSIONS`](crate::requirements::RouteRequirements::PERMISSIONS)),
+/// `description`, `ALL`, [`KnownPermissions`], `Display`, `AsRef<str>`,
+/// `FromStr` (failing with `Other` for unknown keys) and `Into<String>`.
+///
+/// Without an enum header, the enum is named `KeyrunesPermission`:
+///
+/// ```
+/// keyrunes_rust_sdk::keyrunes_permissions! {
+///     PostsWrite => "posts:write": "Write and publish posts",
+/// }
+///
+/// assert_eq!(KeyrunesPermission::PostsWrite.as_str(), "posts:write");
+/// ```
+#[macro_export]
+macro_rules! keyrunes_permissions {
+    (
+        $(#[$meta:meta])*
+        $vis:vis enum $name:ident {
+            $($(#[$variant_meta:meta])* $variant:ident => $key:literal: $description:literal),+ $(,)?
+        }
+    ) => {
+        $crate::__keyrunes_names! {
+            $(#[$meta])*
+            $vis enum $name {
+                $($(#[$variant_meta])* $variant => $key),+
+            }
+        }
+
+        impl $name {
+            /// Description of the permission
+            pub const fn description(self) -> &'static str {
+                match self {
+                    $(Self::$variant => $description,)+
+                }
+            }
+        }
+
+        impl $crate::groups::KnownPermissions for $name {
+            const ALL: &'static [Self] = Self::ALL;
+
+            fn key(self) -> &'static str {
+                self.as_str()
+            }
+
+            fn description(self) -> &'static str {
+                self.description()
+            }
+        }
+
+        impl ::std::str::FromStr for $name {
+            type Err = $crate::KeyrunesError;
+
+            fn from_str(key: &str) -> ::std::result::Result<Self, Self::Err> {
+                <Self as $crate::groups::KnownPermissions>::parse(key).ok_or_else(|| {
+                    $crate::KeyrunesError::Other(format!("Unknown permission: {}", key))
+                })
+            }
+        }
+    };
+    ($($(#[$variant_meta:meta])* $variant:ident => $key:literal: $description:literal),+ $(,)?) => {
+        $crate::keyrunes_permissions! {
+            /// Permissions of the application
+            pub enum KeyrunesPermission {
+                $($(#[$variant_meta])* $variant => $key: $description),+
+            }
+        }
+    };
+}
@@ -0,0 +1,109 @@
+//! OIDC ID tokens
+//!
+//! An ID token tells the application who signed in; an access token
+//! ([`Token`](crate::Token)) lets it call APIs on the user's behalf. Both
+//! are JWTs, so they are easily confused: an ID token sent as a bearer
+//! token, or an access token trusted as proof of identity. [`IdToken`]
+//! keeps them apart. It is only obtained by verifying the raw ID token
+//! with [`KeyrunesClient::verify_id_token`](crate::KeyrunesClient::verify_id_token),
+//! and it cannot be set as the client's token.
+//!
+//! ## Quick Start
+//!
+//! ```
+//! use keyrunes_rust_sdk::KeyrunesClient;
+//!
+//! # async fn example(nonce: &str) -> Result<(), Box<dyn std::error::Error>> {
+//! let client = KeyrunesClient::new("https://keyrunes.example.com")?;
+//! let token = client.login("john@example.com", "password123", None).await?;
+//!
+//! if let Some(raw) = &token.id_token {
+//!     let id_token = client.verify_id_token(raw, "billing-app", Some(nonce)).await?;
+//!     println!("Signed in as {}", id_token.claims().sub);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use serde::{Deserialize, Deserializer, Serialize};
+
+/// Verified claims of an ID token
+///
+/// Standard claims not listed here are kept in `extra`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IdTokenClaims {
+    /// Issuer (Keyrunes)
+    pub iss: String,
+    /// User the token identifies
+    pub sub: String,
+    /// Applications the token was issued to
+    #[serde(deserialize_with = "one_or_many")]
+    pub aud: Vec<String>,
+    /// Expiration (Unix timestamp)
+    pub exp: i64,
+    /// When the token was issued (Unix timestamp)
+    pub iat: i64,
+    /// When the user authenticated (Unix timestamp)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auth_time: Option<i64>,
+    /// Nonce of the authorization request
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<String>,
+    /// Application the token was issued to, when there are several audiences
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub azp: Option<String>,
+    /// Keyrunes session of the user
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sid: Option<String>,
+    /// Authentication methods (e.g., `pwd`, `otp`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub amr: Vec<String>,
+    /// Other claims
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+
+/// A verified ID token
+///
+/// Only identifies the user to the application: unlike [`Token`](crate::Token),
+/// it does not convert into a string, so it cannot be passed to
+/// [`KeyrunesClient::set_token`](crate::KeyrunesClient::set_token) by mistake.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IdToken {
+    raw: String,
+    claims: IdTokenClaims,
+}
+
+impl IdToken {
+    pub(crate) fn new(raw: String, claims: IdTokenClaims) -> Self {
+        Self { raw, claims }
+    }
+
+    /// Verified claims of the token
+    pub fn claims(&self) -> &IdTokenClaims {
+        &self.claims
+    }
+
+    /// The token as issued, e.g. for the `id_token_hint` of
+    /// [`KeyrunesClient::logout_url`](crate::KeyrunesClient::logout_url)
+    pub fn as_str(&self) -> &str {
+        &self.raw
+    }
+}
+
+/// Deserializes a claim holding a string or an array of strings
+pub(crate) fn one_or_many<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<String>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany {
+        One(String),
+        Many(Vec<String>),
+    }
+
+    Ok(match OneOrMany::deserialize(deserializer)? {
+        OneOrMany::One(value) => vec![value],
+        OneOrMany::Many(values) => values,
+    })
+}
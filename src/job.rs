@@ -0,0 +1,177 @@
+//! Asynchronous Keyrunes operations
+//!
+//! Some operations (exports, bulk imports, deletions) run as background
+//! jobs on the Keyrunes server. Methods starting them return a [`Job`]
+//! handle, which can be polled with [`Job::status`], awaited with
+//! [`Job::await_completion`], or cancelled with [`Job::cancel`].
+//!
+//! ## Quick Start
+//!
+//! ```
+//! use keyrunes_rust_sdk::KeyrunesClient;
+//! use std::time::Duration;
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = KeyrunesClient::new("https://keyrunes.example.com")?;
+//! client.login("admin@example.com", "password123", None).await?;
+//! let job = client.start_export("users").await?;
+//! let artifact = job
+//!     .await_completion(Duration::from_secs(2), Duration::from_secs(300))
+//!     .await?;
+//! println!("Export ready: {:?} bytes", artifact.size);
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::client::KeyrunesClient;
+use crate::error::{KeyrunesError, Result};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::marker::PhantomData;
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// State of a background job
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobState {
+    /// Waiting to be processed
+    Pending,
+    /// Being processed
+    Running,
+    /// Completed successfully
+    Succeeded,
+    /// Completed with an error
+    Failed,
+    /// Cancelled before completion
+    Cancelled,
+}
+
+impl JobState {
+    /// Checks whether the job has finished (successfully or not).
+    pub fn is_terminal(&self) -> bool {
+        matches!(
+            self,
+            JobState::Succeeded | JobState::Failed | JobState::Cancelled
+        )
+    }
+}
+
+/// Status of a background job
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobStatus<T> {
+    /// Job ID
+    pub id: String,
+    /// Current state
+    pub state: JobState,
+    /// Completion ratio between 0.0 and 1.0 (if reported)
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub progress: Option<f64>,
+    /// Result of the job (only set once succeeded)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<T>,
+    /// Failure reason (only set once failed)
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub error: Option<String>,
+}
+
+/// Response of the endpoints starting a job
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct JobCreated {
+    /// ID of the created job
+    #[serde(alias = "job_id")]
+    pub id: String,
+}
+
+/// Handle to a background job producing a `T`
+///
+/// Cloning a handle is cheap; all clones refer to the same job.
+pub struct Job<T> {
+    client: KeyrunesClient,
+    id: String,
+    _result: PhantomData<fn() -> T>,
+}
+
+impl<T: DeserializeOwned> Job<T> {
+    pub(crate) fn new(client: KeyrunesClient, id: String) -> Self {
+        Self {
+            client,
+            id,
+            _result: PhantomData,
+        }
+    }
+
+    /// Returns the job ID.
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Gets the current status of the job.
+    pub async fn status(&self) -> Result<JobStatus<T>> {
+        self.client.job_status(&self.id).await
+    }
+
+    /// Waits for the job to finish, polling its status every `poll_interval`.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<T, KeyrunesError>`:
+    /// - `Ok(result)` if the job succeeded
+    /// - `Err(KeyrunesError::JobFailed)` if the job failed
+    /// - `Err(KeyrunesError::JobCancelled)` if the job was cancelled
+    /// - `Err(KeyrunesError::JobTimedOut)` if the job is still running after `timeout`
+    pub async fn await_completion(&self, poll_interval: Duration, timeout: Duration) -> Result<T> {
+        let deadline = Instant::now() + timeout;
+
+        loop {
+            let status = self.status().await?;
+            match status.state {
+                JobState::Succeeded => {
+                    return match status.result {
+                        Some(result) => Ok(result),
+                        // Jobs without output (e.g., deletions) report no result.
+                        None => Ok(serde_json::from_value(serde_json::Value::Null)?),
+                    };
+                }
+                JobState::Failed => {
+                    return Err(KeyrunesError::JobFailed {
+                        job_id: self.id.clone(),
+                        reason: status.error.unwrap_or_else(|| "unknown error".to_string()),
+                    });
+                }
+                JobState::Cancelled => return Err(KeyrunesError::JobCancelled(self.id.clone())),
+                JobState::Pending | JobState::Running => {}
+            }
+
+            let now = Instant::now();
+            if now >= deadline {
+                return Err(KeyrunesError::JobTimedOut(self.id.clone()));
+            }
+            tokio::time::sleep_until((now + poll_interval).min(deadline)).await;
+        }
+    }
+
+    /// Requests cancellation of the job.
+    ///
+    /// Cancellation is asynchronous: the job may still complete if it was
+    /// about to finish.
+    pub async fn cancel(&self) -> Result<()> {
+        self.client.cancel_job(&self.id).await
+    }
+}
+
+impl<T> Clone for Job<T> {
+    fn clone(&self) -> Self {
+        Self {
+            client: self.client.clone(),
+            id: self.id.clone(),
+            _result: PhantomData,
+        }
+    }
+}
+
+impl<T> std::fmt::Debug for Job<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Job").field("id", &self.id).finish()
+    }
+}
@@ -17,17 +17,91 @@
 //!
 //! ## Modules
 //!
+//! - [`access_filter`] - Authorization filters for database queries
+//! - [`admin`] - Administration endpoints
+//! - [`audit`] - Audit trail of access decisions
+//! - [`auth_service`] - Framework-agnostic authentication and authorization
+//! - [`break_glass`] - Emergency access with sealed credentials, for incident response
+//! - [`claims`] - JWT claims and authentication levels
 //! - [`client`] - Main client for interacting with the Keyrunes API
+//! - `csrf` - CSRF protection for cookie-based authentication (feature `sessions`)
+//! - `dpop` - DPoP proof-of-possession tokens (feature `dpop`)
+//! - [`endpoints`] - Paths of the Keyrunes API endpoints
+//! - [`entitlements`] - Per-user entitlements (plans, feature flags)
 //! - [`error`] - Error types for the library
+//! - [`groups`] - Typed registry of the groups an application relies on
+//! - [`id_token`] - OIDC ID tokens
+//! - [`job`] - Handles to asynchronous Keyrunes operations
+//! - [`login_guard`] - Brute-force protection for login endpoints
+//! - [`logout`] - OIDC back-channel logout
+//! - [`migrations`] - Migration of users and groups from Auth0 and Keycloak
 //! - [`models`] - Data models for serialization/deserialization
+//! - `mtls` - Certificate-bound access tokens (feature `mtls`)
+//! - `oauth` - State of OAuth/OIDC authorization requests (feature `oauth`)
+//! - [`permissions`] - Local permission checks
+//! - `pkce` - PKCE, state and nonce helpers for OAuth flows (feature `oauth`)
+//! - [`profile_watcher`] - Change detection for the current user's profile
+//! - [`provisioning`] - Declarative provisioning of groups, permissions and service accounts
+//! - [`quota`] - Batched consumption of metered entitlements
+//! - [`rate_limit`] - Rate limiting keyed by user identity
+//! - [`redact`] - Field-level redaction of API responses
+//! - [`requirements`] - Route-level group and permission requirements
+//! - [`scim`] - SCIM 2.0 user and group provisioning
+//! - `session` - Server-side sessions for browser apps (feature `sessions`)
+//! - `telemetry` - OpenTelemetry attributes on request spans (feature `tracing`)
+//! - [`validation`] - Local validation of incoming access tokens
 
+pub mod access_filter;
+pub mod admin;
+pub mod audit;
+pub mod auth_service;
+pub mod break_glass;
+pub mod claims;
 pub mod client;
+#[cfg(any(feature = "sessions", feature = "oauth", feature = "axum"))]
+mod compare;
+#[cfg(feature = "sessions")]
+pub mod csrf;
+#[cfg(feature = "dpop")]
+pub mod dpop;
+pub mod endpoints;
+pub mod entitlements;
 pub mod error;
+pub mod groups;
+pub mod id_token;
+pub mod job;
+pub mod login_guard;
+pub mod logout;
+pub mod migrations;
 pub mod models;
+#[cfg(feature = "mtls")]
+pub mod mtls;
+#[cfg(feature = "oauth")]
+pub mod oauth;
+pub mod permissions;
+#[cfg(feature = "oauth")]
+pub mod pkce;
+pub mod profile_watcher;
+pub mod provisioning;
+pub mod quota;
+pub mod rate_limit;
+pub mod redact;
+pub mod requirements;
+pub mod scim;
+pub mod validation;
+
+#[cfg(feature = "sessions")]
+pub mod session;
+
+#[cfg(feature = "tracing")]
+pub mod telemetry;
 
-#[cfg(any(feature = "axum", feature = "actix", feature = "rocket"))]
 pub mod middleware;
 
-pub use client::KeyrunesClient;
+#[cfg(feature = "macros")]
+pub use keyrunes_macros::{require, KeyrunesRedact};
+
+pub use auth_service::AuthService;
+pub use client::{KeyrunesClient, KeyrunesClientBuilder, TokenProvider, TokenStore};
 pub use error::{KeyrunesError, Result};
 pub use models::*;
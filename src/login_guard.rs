@@ -0,0 +1,461 @@
+//! Brute-force protection for login endpoints
+//!
+//! This module contains the [`LoginGuard`], which counts failed login
+//! attempts per identity and per IP address and locks them out with an
+//! exponentially growing delay. Counters are kept in a pluggable
+//! [`LoginAttemptStore`]: [`InMemoryLoginAttemptStore`] for single-instance
+//! deployments, and `RedisLoginAttemptStore` (feature `redis`) when several
+//! instances must share counters.
+//!
+//! When a lockout starts, the guard can report the suspicious activity to
+//! the Keyrunes security events endpoint.
+//!
+//! ## Quick Start
+//!
+//! ```
+//! use keyrunes_rust_sdk::login_guard::LoginGuard;
+//! use keyrunes_rust_sdk::KeyrunesClient;
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = KeyrunesClient::new("https://keyrunes.example.com")?;
+//! let guard = LoginGuard::in_memory().with_reporting(client.clone());
+//!
+//! guard.check("john@example.com", Some("203.0.113.7")).await?;
+//! match client.login("john@example.com", "password123", None).await {
+//!     Ok(_) => guard.record_success("john@example.com").await?,
+//!     Err(_) => {
+//!         guard.record_failure("john@example.com", Some("203.0.113.7")).await?;
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::client::KeyrunesClient;
+use crate::error::{KeyrunesError, Result};
+use crate::models::{SecurityEvent, SecurityEventKind};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+
+/// Lockout policy for the [`LoginGuard`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LoginGuardConfig {
+    /// Failed attempts allowed before the first lockout
+    pub max_attempts: u32,
+    /// Duration of the first lockout; doubled on every further failure
+    pub base_lockout: Duration,
+    /// Upper bound for the lockout duration
+    pub max_lockout: Duration,
+    /// Failures are forgotten once this long has passed since the first
+    /// failure and since the end of the last lockout, so lockouts keep
+    /// escalating while the attempts go on
+    pub window: Duration,
+}
+
+impl Default for LoginGuardConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            base_lockout: Duration::from_secs(30),
+            max_lockout: Duration::from_secs(60 * 60),
+            window: Duration::from_secs(15 * 60),
+        }
+    }
+}
+
+impl LoginGuardConfig {
+    /// Lockout duration after `failures` consecutive failures
+    fn lockout_for(&self, failures: u32) -> Option<Duration> {
+        if failures < self.max_attempts {
+            return None;
+        }
+        let exponent = (failures - self.max_attempts).min(31);
+        let lockout = self.base_lockout.saturating_mul(1u32 << exponent);
+        Some(lockout.min(self.max_lockout))
+    }
+}
+
+/// Failed attempt counter for one identity or IP address
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AttemptRecord {
+    /// Consecutive failed attempts
+    pub failures: u32,
+    /// Time of the first failure in the current window
+    pub first_failure_at: DateTime<Utc>,
+    /// End of the last lockout, if any
+    pub locked_until: Option<DateTime<Utc>>,
+}
+
+impl AttemptRecord {
+    /// Returns the record after a failure at `now`.
+    ///
+    /// The failure is counted in `previous` while it is within the window
+    /// of `config`, or in a new record otherwise, and the lockout of
+    /// `config` is applied. Stores apply this update atomically in
+    /// [`LoginAttemptStore::increment`].
+    pub fn after_failure(
+        previous: Option<Self>,
+        config: &LoginGuardConfig,
+        now: DateTime<Utc>,
+    ) -> Self {
+        let mut record = match previous {
+            Some(record) if !record.is_expired(config, now) => record,
+            _ => AttemptRecord {
+                failures: 0,
+                first_failure_at: now,
+                locked_until: None,
+            },
+        };
+        record.failures += 1;
+
+        if let Some(lockout) = config.lockout_for(record.failures) {
+            record.locked_until =
+                Some(now + chrono::Duration::from_std(lockout).unwrap_or(chrono::Duration::MAX));
+        }
+        record
+    }
+
+    /// Whether the window of `config` has passed since the last activity
+    fn is_expired(&self, config: &LoginGuardConfig, now: DateTime<Utc>) -> bool {
+        let window = chrono::Duration::from_std(config.window).unwrap_or(chrono::Duration::MAX);
+        now - self.last_activity() > window
+    }
+
+    /// Start of the window: the first failure, or the end of the last lockout
+    fn last_activity(&self) -> DateTime<Utc> {
+        self.locked_until.map_or(self.first_failure_at, |until| {
+            until.max(self.first_failure_at)
+        })
+    }
+}
+
+/// Storage backend for failed attempt counters
+///
+/// Implement this trait to share counters between instances (e.g., a
+/// database table).
+#[async_trait::async_trait]
+pub trait LoginAttemptStore: Send + Sync {
+    /// Gets the record stored under `key`.
+    async fn get(&self, key: &str) -> Result<Option<AttemptRecord>>;
+    /// Records a failure at `now` under `key`, returning the updated record.
+    ///
+    /// The record is read and updated atomically (see
+    /// [`AttemptRecord::after_failure`]), so concurrent failures are all
+    /// counted.
+    async fn increment(
+        &self,
+        key: &str,
+        config: &LoginGuardConfig,
+        now: DateTime<Utc>,
+    ) -> Result<AttemptRecord>;
+    /// Removes the record stored under `key`.
+    async fn remove(&self, key: &str) -> Result<()>;
+}
+
+/// In-memory attempt store
+///
+/// Records whose window and lockout have passed are dropped on the next
+/// failure, so attacker-chosen identities do not accumulate.
+#[derive(Debug, Default)]
+pub struct InMemoryLoginAttemptStore {
+    records: Mutex<HashMap<String, AttemptRecord>>,
+}
+
+impl InMemoryLoginAttemptStore {
+    /// Creates an empty store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait::async_trait]
+impl LoginAttemptStore for InMemoryLoginAttemptStore {
+    async fn get(&self, key: &str) -> Result<Option<AttemptRecord>> {
+        Ok(self.records.lock().await.get(key).cloned())
+    }
+
+    async fn increment(
+        &self,
+        key: &str,
+        config: &LoginGuardConfig,
+        now: DateTime<Utc>,
+    ) -> Result<AttemptRecord> {
+        let mut records = self.records.lock().await;
+        records.retain(|_, record| !record.is_expired(config, now));
+        let record = AttemptRecord::after_failure(records.remove(key), config, now);
+        records.insert(key.to_string(), record.clone());
+        Ok(record)
+    }
+
+    async fn remove(&self, key: &str) -> Result<()> {
+        self.records.lock().await.remove(key);
+        Ok(())
+    }
+}
+
+/// Redis-backed attempt store
+///
+/// Records are updated atomically with a Lua script, so counters are
+/// shared by every instance connected to the same Redis server. They
+/// expire once their window has passed.
+#[cfg(feature = "redis")]
+#[derive(Clone)]
+pub struct RedisLoginAttemptStore {
+    connection: redis::aio::MultiplexedConnection,
+    prefix: String,
+}
+
+/// Same update as [`AttemptRecord::after_failure`], with times in milliseconds
+#[cfg(feature = "redis")]
+const REDIS_RECORD_FAILURE: &str = r#"
+local now = tonumber(ARGV[1])
+local window = tonumber(ARGV[2])
+local max_attempts = tonumber(ARGV[3])
+local base_lockout = tonumber(ARGV[4])
+local max_lockout = tonumber(ARGV[5])
+local data = redis.call('HMGET', KEYS[1], 'failures', 'first', 'until')
+local failures = tonumber(data[1])
+local first = tonumber(data[2])
+local locked_until = tonumber(data[3])
+if not failures or not first or now - math.max(first, locked_until or first) > window then
+  failures = 0
+  first = now
+  locked_until = nil
+  redis.call('DEL', KEYS[1])
+end
+failures = failures + 1
+if failures >= max_attempts then
+  local exponent = math.min(failures - max_attempts, 31)
+  locked_until = now + math.min(base_lockout * 2 ^ exponent, max_lockout)
+end
+redis.call('HSET', KEYS[1], 'failures', failures, 'first', string.format('%.0f', first))
+if locked_until then
+  redis.call('HSET', KEYS[1], 'until', string.format('%.0f', locked_until))
+end
+redis.call('PEXPIRE', KEYS[1], string.format('%.0f', math.max(first, locked_until or first) - now + window))
+return {failures, string.format('%.0f', first), string.format('%.0f', locked_until or -1)}
+"#;
+
+#[cfg(feature = "redis")]
+impl RedisLoginAttemptStore {
+    /// Connects to Redis at `url` (e.g., `redis://127.0.0.1/`).
+    pub async fn connect(url: &str) -> Result<Self> {
+        let client = redis::Client::open(url)?;
+        Ok(Self {
+            connection: client.get_multiplexed_async_connection().await?,
+            prefix: "keyrunes:login:".to_string(),
+        })
+    }
+
+    /// Sets the key prefix (default: `keyrunes:login:`).
+    pub fn with_prefix<S: Into<String>>(mut self, prefix: S) -> Self {
+        self.prefix = prefix.into();
+        self
+    }
+}
+
+#[cfg(feature = "redis")]
+#[async_trait::async_trait]
+impl LoginAttemptStore for RedisLoginAttemptStore {
+    async fn get(&self, key: &str) -> Result<Option<AttemptRecord>> {
+        let mut connection = self.connection.clone();
+        let (failures, first, locked_until): (Option<u32>, Option<i64>, Option<i64>) =
+            redis::cmd("HMGET")
+                .arg(format!("{}{}", self.prefix, key))
+                .arg("failures")
+                .arg("first")
+                .arg("until")
+                .query_async(&mut connection)
+                .await?;
+
+        Ok(match (failures, first) {
+            (Some(failures), Some(first)) => {
+                Some(record_from_millis(failures, first, locked_until))
+            }
+            _ => None,
+        })
+    }
+
+    async fn increment(
+        &self,
+        key: &str,
+        config: &LoginGuardConfig,
+        now: DateTime<Utc>,
+    ) -> Result<AttemptRecord> {
+        let mut connection = self.connection.clone();
+        let (failures, first, locked_until): (u32, String, String) =
+            redis::Script::new(REDIS_RECORD_FAILURE)
+                .key(format!("{}{}", self.prefix, key))
+                .arg(now.timestamp_millis())
+                .arg(millis(config.window))
+                .arg(config.max_attempts)
+                .arg(millis(config.base_lockout))
+                .arg(millis(config.max_lockout))
+                .invoke_async(&mut connection)
+                .await?;
+
+        let first = first.parse().unwrap_or(now.timestamp_millis());
+        let locked_until = locked_until.parse().ok().filter(|until: &i64| *until >= 0);
+        Ok(record_from_millis(failures, first, locked_until))
+    }
+
+    async fn remove(&self, key: &str) -> Result<()> {
+        let mut connection = self.connection.clone();
+        redis::cmd("DEL")
+            .arg(format!("{}{}", self.prefix, key))
+            .query_async::<()>(&mut connection)
+            .await?;
+        Ok(())
+    }
+}
+
+/// Duration in milliseconds, saturating for the Lua script
+#[cfg(feature = "redis")]
+fn millis(duration: Duration) -> i64 {
+    i64::try_from(duration.as_millis()).unwrap_or(i64::MAX)
+}
+
+/// Builds a record from the timestamps (in milliseconds) kept in Redis
+#[cfg(feature = "redis")]
+fn record_from_millis(failures: u32, first: i64, locked_until: Option<i64>) -> AttemptRecord {
+    let time = |millis: i64| DateTime::from_timestamp_millis(millis).unwrap_or_default();
+    AttemptRecord {
+        failures,
+        first_failure_at: time(first),
+        locked_until: locked_until.map(time),
+    }
+}
+
+/// Status returned after recording a failed attempt
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoginAttemptStatus {
+    /// Consecutive failed attempts for the identity
+    pub failures: u32,
+    /// Lockout applied by this failure, if any
+    pub locked_for: Option<Duration>,
+}
+
+/// Brute-force protection for login endpoints
+///
+/// Call [`check`](LoginGuard::check) before [`KeyrunesClient::login`], then
+/// [`record_success`](LoginGuard::record_success) or
+/// [`record_failure`](LoginGuard::record_failure) depending on the result.
+#[derive(Clone)]
+pub struct LoginGuard {
+    store: Arc<dyn LoginAttemptStore>,
+    config: LoginGuardConfig,
+    reporter: Option<KeyrunesClient>,
+}
+
+impl LoginGuard {
+    /// Creates a guard using the given store and the default policy.
+    pub fn new(store: Arc<dyn LoginAttemptStore>) -> Self {
+        Self {
+            store,
+            config: LoginGuardConfig::default(),
+            reporter: None,
+        }
+    }
+
+    /// Creates a guard backed by an [`InMemoryLoginAttemptStore`].
+    pub fn in_memory() -> Self {
+        Self::new(Arc::new(InMemoryLoginAttemptStore::new()))
+    }
+
+    /// Sets the lockout policy.
+    pub fn with_config(mut self, config: LoginGuardConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Reports lockouts to the Keyrunes security events endpoint using `client`.
+    pub fn with_reporting(mut self, client: KeyrunesClient) -> Self {
+        self.reporter = Some(client);
+        self
+    }
+
+    /// Checks whether a login attempt is currently allowed.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<(), KeyrunesError>`:
+    /// - `Ok(())` if neither the identity nor the IP address is locked out
+    /// - `Err(KeyrunesError::LoginLocked)` with the remaining lockout in seconds
+    pub async fn check(&self, identity: &str, ip: Option<&str>) -> Result<()> {
+        let now = Utc::now();
+        for key in Self::keys(identity, ip) {
+            let locked_until = self.store.get(&key).await?.and_then(|r| r.locked_until);
+            if let Some(locked_until) = locked_until.filter(|until| *until > now) {
+                let remaining = (locked_until - now).num_seconds().max(1) as u64;
+                return Err(KeyrunesError::LoginLocked(remaining));
+            }
+        }
+        Ok(())
+    }
+
+    /// Records a failed login attempt and applies a lockout when needed.
+    pub async fn record_failure(
+        &self,
+        identity: &str,
+        ip: Option<&str>,
+    ) -> Result<LoginAttemptStatus> {
+        let now = Utc::now();
+        let mut status = LoginAttemptStatus {
+            failures: 0,
+            locked_for: None,
+        };
+
+        for (index, key) in Self::keys(identity, ip).into_iter().enumerate() {
+            let record = self.store.increment(&key, &self.config, now).await?;
+            let locked_for = self.config.lockout_for(record.failures);
+
+            if index == 0 {
+                status = LoginAttemptStatus {
+                    failures: record.failures,
+                    locked_for,
+                };
+            } else if status.locked_for.is_none() {
+                status.locked_for = locked_for;
+            }
+        }
+
+        if let (Some(reporter), Some(lockout)) = (&self.reporter, status.locked_for) {
+            let mut event = SecurityEvent::new(SecurityEventKind::BruteForce)
+                .with_metadata("identity", identity)
+                .with_metadata("failures", status.failures)
+                .with_metadata("lockout_seconds", lockout.as_secs());
+            if let Some(ip) = ip {
+                event = event.with_ip(ip);
+            }
+            // Reporting is best effort: a Keyrunes outage must not break logins.
+            let _ = reporter.report_security_event(&event).await;
+        }
+
+        Ok(status)
+    }
+
+    /// Records a successful login, clearing the counter of the identity.
+    ///
+    /// The counter of the IP address is not cleared: otherwise logging into
+    /// an account of their own between guesses would let an attacker reset
+    /// the throttling of their address. It expires with the window.
+    pub async fn record_success(&self, identity: &str) -> Result<()> {
+        self.store.remove(&Self::identity_key(identity)).await
+    }
+
+    fn identity_key(identity: &str) -> String {
+        format!("identity:{}", identity.to_lowercase())
+    }
+
+    fn keys(identity: &str, ip: Option<&str>) -> Vec<String> {
+        let mut keys = vec![Self::identity_key(identity)];
+        if let Some(ip) = ip {
+            keys.push(format!("ip:{}", ip));
+        }
+        keys
+    }
+}
@@ -0,0 +1,177 @@
+//! OIDC back-channel logout
+//!
+//! When a user signs out of Keyrunes, or an administrator ends their
+//! session, Keyrunes notifies the applications the user signed in to by
+//! posting a logout token (a signed JWT, in the `logout_token` form field)
+//! to their back-channel logout URL. [`BackChannelLogout`] verifies the
+//! token with the Keyrunes signing keys, then drops what the application
+//! holds for the user: the client's cached entitlements and, with feature
+//! `sessions`, the server-side sessions created for the user or for the
+//! Keyrunes session that ended.
+//!
+//! The framework integrations expose it as a `backchannel_logout` handler
+//! (axum and actix), answering `200` once the user is logged out and `400`
+//! when the token is rejected.
+//!
+//! ## Quick Start
+//!
+//! ```
+//! use keyrunes_rust_sdk::logout::BackChannelLogout;
+//! use keyrunes_rust_sdk::KeyrunesClient;
+//!
+//! # async fn example(body: &[u8]) -> Result<(), Box<dyn std::error::Error>> {
+//! let client = KeyrunesClient::new("https://keyrunes.example.com")?;
+//! let logout = BackChannelLogout::new(client, "billing-app");
+//!
+//! // Body of the POST request sent by Keyrunes
+//! let token = logout.handle_form(body).await?;
+//! println!("Logged out {:?}", token.sub);
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::error::{KeyrunesError, Result};
+#[cfg(feature = "sessions")]
+use crate::session::SessionManager;
+use crate::KeyrunesClient;
+use jsonwebtoken::{Algorithm, Validation};
+use serde::{Deserialize, Serialize};
+
+/// Event a logout token must carry
+pub const BACKCHANNEL_LOGOUT_EVENT: &str = "http://schemas.openid.net/event/backchannel-logout";
+
+/// Form field carrying the logout token
+const FORM_FIELD: &str = "logout_token";
+
+/// Verified claims of a logout token
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LogoutToken {
+    /// Issuer (Keyrunes)
+    pub iss: String,
+    /// When the token was issued (Unix timestamp)
+    pub iat: i64,
+    /// Unique ID of the token
+    pub jti: String,
+    /// User who logged out
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sub: Option<String>,
+    /// Keyrunes session that ended
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sid: Option<String>,
+    /// Events of the token, including [`BACKCHANNEL_LOGOUT_EVENT`]
+    pub events: serde_json::Map<String, serde_json::Value>,
+}
+
+/// Verifies logout tokens and logs their users out locally
+///
+/// Cloning is cheap and shares the client and the session store.
+#[derive(Clone)]
+pub struct BackChannelLogout {
+    client: KeyrunesClient,
+    issuer: String,
+    audience: String,
+    #[cfg(feature = "sessions")]
+    sessions: Option<SessionManager>,
+}
+
+impl BackChannelLogout {
+    /// Handles logout tokens issued by the Keyrunes server of `client` to
+    /// `audience`, the ID the application is registered with in Keyrunes.
+    ///
+    /// Tokens addressed to other applications are rejected, as the
+    /// back-channel logout specification requires.
+    pub fn new<S: Into<String>>(client: KeyrunesClient, audience: S) -> Self {
+        Self {
+            issuer: client.base_url.clone(),
+            client,
+            audience: audience.into(),
+            #[cfg(feature = "sessions")]
+            sessions: None,
+        }
+    }
+
+    /// Sets the expected issuer (default: the client's base URL).
+    pub fn with_issuer<S: Into<String>>(mut self, issuer: S) -> Self {
+        self.issuer = issuer.into();
+        self
+    }
+
+    /// Ends the matching sessions of `sessions` on logout (feature `sessions`).
+    ///
+    /// The store must support deleting sessions by owner (see
+    /// [`SessionStore::delete_where`](crate::session::SessionStore::delete_where)).
+    #[cfg(feature = "sessions")]
+    pub fn with_sessions(mut self, sessions: SessionManager) -> Self {
+        self.sessions = Some(sessions);
+        self
+    }
+
+    /// Verifies a logout token and returns its claims.
+    ///
+    /// The token must be signed by Keyrunes, issued by the expected issuer
+    /// to the expected audience, unexpired, name a user (`sub`) or a session
+    /// (`sid`), carry the back-channel logout event, and have no `nonce`
+    /// (which would make it an ID token). Fails with `InvalidToken` otherwise.
+    pub async fn verify(&self, logout_token: &str) -> Result<LogoutToken> {
+        let mut validation = Validation::new(Algorithm::RS256);
+        validation.set_issuer(&[&self.issuer]);
+        validation.set_required_spec_claims(&["exp", "iss", "aud"]);
+        validation.set_audience(&[&self.audience]);
+
+        let claims: serde_json::Value = self.client.verify_signed(logout_token, validation).await?;
+        if claims.get("nonce").is_some() {
+            return Err(KeyrunesError::InvalidToken);
+        }
+        let token: LogoutToken =
+            serde_json::from_value(claims).map_err(|_| KeyrunesError::InvalidToken)?;
+
+        let is_logout = token
+            .events
+            .get(BACKCHANNEL_LOGOUT_EVENT)
+            .is_some_and(serde_json::Value::is_object);
+        if !is_logout || (token.sub.is_none() && token.sid.is_none()) {
+            return Err(KeyrunesError::InvalidToken);
+        }
+        Ok(token)
+    }
+
+    /// Verifies a logout token and logs its user out locally.
+    ///
+    /// Drops the user's cached entitlements and ends the sessions of the
+    /// user, or of the Keyrunes session the token names.
+    pub async fn handle(&self, logout_token: &str) -> Result<LogoutToken> {
+        let token = self.verify(logout_token).await?;
+
+        if let Some(sub) = &token.sub {
+            self.client.invalidate_entitlements(sub).await;
+        }
+        #[cfg(feature = "sessions")]
+        if let Some(sessions) = &self.sessions {
+            sessions
+                .destroy_matching(token.sub.as_deref(), token.sid.as_deref())
+                .await?;
+        }
+        Ok(token)
+    }
+
+    /// Handles the body of a back-channel logout request
+    /// (`application/x-www-form-urlencoded`, with a `logout_token` field).
+    ///
+    /// Fails with `InvalidToken` if the field is missing.
+    pub async fn handle_form(&self, body: &[u8]) -> Result<LogoutToken> {
+        let logout_token = url::form_urlencoded::parse(body)
+            .find(|(name, _)| name == FORM_FIELD)
+            .map(|(_, value)| value.into_owned())
+            .ok_or(KeyrunesError::InvalidToken)?;
+        self.handle(&logout_token).await
+    }
+}
+
+impl std::fmt::Debug for BackChannelLogout {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BackChannelLogout")
+            .field("issuer", &self.issuer)
+            .field("audience", &self.audience)
+            .finish_non_exhaustive()
+    }
+}
@@ -1,5 +1,20 @@
 //! Middleware for Actix Web integration
 
+use super::admin_policy::AdminPolicy;
+use super::bypass::BypassRules;
+use super::group_check::GroupCheckStrategy;
+use super::messages::{default_message, Locale, MessageFormatter, RejectionKind};
+use crate::audit::{AuditTimer, AuthAuditSink};
+use crate::auth_service::AuthService;
+use crate::claims::AuthLevel;
+use crate::logout::BackChannelLogout;
+#[cfg(feature = "mtls")]
+use crate::mtls::CertificateBinding;
+use crate::rate_limit::RateLimiter;
+use crate::requirements::{Require, RouteRequirements};
+use crate::validation::TokenValidation;
+#[cfg(feature = "sessions")]
+use crate::{csrf::CsrfProtection, session::SessionData};
 use crate::{KeyrunesClient, User};
 use actix_web::{
     dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
@@ -16,14 +31,69 @@ use std::{
 #[derive(Clone)]
 pub struct KeyrunesState {
     pub client: Arc<KeyrunesClient>,
+    pub auth: AuthService,
 }
 
 impl KeyrunesState {
     pub fn new(client: KeyrunesClient) -> Self {
+        Self::from_service(AuthService::new(client))
+    }
+
+    /// Creates the state from a configured [`AuthService`].
+    pub fn from_service(auth: AuthService) -> Self {
         Self {
-            client: Arc::new(client),
+            client: auth.client().clone(),
+            auth,
         }
     }
+
+    /// Sets how group requirements are checked (always verified with Keyrunes by default).
+    ///
+    /// Admin checks follow the admin policy; use
+    /// `GroupPolicy::default().with_strategy(strategy)` to apply the strategy to them too.
+    pub fn with_group_check(mut self, strategy: GroupCheckStrategy) -> Self {
+        self.auth = self.auth.with_group_check(strategy);
+        self
+    }
+
+    /// Decides who is an administrator (members of `admins` by default).
+    pub fn with_admin_policy<P: AdminPolicy + 'static>(mut self, policy: P) -> Self {
+        self.auth = self.auth.with_admin_policy(policy);
+        self
+    }
+
+    /// Renders rejection bodies with `formatter`, in the request's `Accept-Language`.
+    pub fn with_message_formatter(mut self, formatter: MessageFormatter) -> Self {
+        self.auth = self.auth.with_message_formatter(formatter);
+        self
+    }
+
+    /// Sets the requests the middlewares let through without a token
+    /// (`OPTIONS` and health endpoints by default).
+    pub fn with_bypass_rules(mut self, rules: BypassRules) -> Self {
+        self.auth = self.auth.with_bypass_rules(rules);
+        self
+    }
+
+    /// Validates tokens locally instead of asking Keyrunes (see [`crate::validation`]).
+    pub fn with_token_validation(mut self, validation: TokenValidation) -> Self {
+        self.auth = self.auth.with_token_validation(validation);
+        self
+    }
+
+    /// Rejects certificate-bound tokens presented without their client
+    /// certificate (feature `mtls`, see [`crate::mtls`]).
+    #[cfg(feature = "mtls")]
+    pub fn with_certificate_binding(mut self, binding: CertificateBinding) -> Self {
+        self.auth = self.auth.with_certificate_binding(binding);
+        self
+    }
+
+    /// Reports the middlewares' access decisions to `sink` (see [`crate::audit`]).
+    pub fn with_audit_sink<A: AuthAuditSink + 'static>(mut self, sink: A) -> Self {
+        self.auth = self.auth.with_audit_sink(sink);
+        self
+    }
 }
 
 /// Authenticated user data stored in the request
@@ -41,13 +111,48 @@ impl FromRequest for AuthenticatedUser {
             return ready(Ok(user.clone()));
         }
 
-        ready(Err(actix_web::error::ErrorUnauthorized(
-            "User not authenticated",
+        ready(Err(reject(
+            req,
+            RejectionKind::Unauthenticated("User not authenticated".to_string()),
         )))
     }
 }
 
+/// Enforces the route requirements `R` for the user set by [`KeyrunesAuthMiddleware`]
+impl<R: RouteRequirements> FromRequest for Require<R> {
+    type Error = actix_web::Error;
+    type Future = Pin<Box<dyn std::future::Future<Output = Result<Self, Self::Error>>>>;
+
+    fn from_request(req: &actix_web::HttpRequest, _: &mut actix_web::dev::Payload) -> Self::Future {
+        let req = req.clone();
+
+        Box::pin(async move {
+            let user =
+                AuthenticatedUser::from_request(&req, &mut actix_web::dev::Payload::None).await?;
+            let state = req
+                .app_data::<actix_web::web::Data<KeyrunesState>>()
+                .ok_or_else(|| {
+                    reject(
+                        &req,
+                        RejectionKind::Internal("KeyrunesState not configured".to_string()),
+                    )
+                })?;
+
+            let token = AuthService::bearer_token(authorization(&req))
+                .map_err(|kind| reject(&req, kind))?;
+            state
+                .auth
+                .require(user.user, token)
+                .await
+                .map_err(|kind| reject(&req, kind))
+        })
+    }
+}
+
 /// Middleware for authentication in Actix
+///
+/// Requests matching the state's bypass rules (see
+/// [`KeyrunesState::with_bypass_rules`]) are passed on without resolving a user.
 pub struct KeyrunesAuthMiddleware;
 
 impl<S, B> Transform<S, ServiceRequest> for KeyrunesAuthMiddleware
@@ -89,16 +194,19 @@ where
         let service = self.service.clone();
 
         Box::pin(async move {
-            if let Some(auth_header) = req.headers().get("authorization") {
-                if let Ok(auth_str) = auth_header.to_str() {
-                    if let Some(token) = auth_str.strip_prefix("Bearer ") {
-                        if let Some(state) = req.app_data::<actix_web::web::Data<KeyrunesState>>() {
-                            state.client.set_token(token.to_string()).await;
-                            if let Ok(user) = state.client.get_current_user().await {
-                                req.extensions_mut().insert(AuthenticatedUser { user });
-                            }
-                        }
-                    }
+            let state = req
+                .app_data::<actix_web::web::Data<KeyrunesState>>()
+                .filter(|state| !state.auth.bypasses(req.method().as_str(), req.path()));
+            if let Some(state) = state {
+                if let Ok(user) = state
+                    .auth
+                    .authenticate_request(
+                        authorization(req.request()),
+                        certificate(req.request(), &state.auth),
+                    )
+                    .await
+                {
+                    req.extensions_mut().insert(AuthenticatedUser { user });
                 }
             }
 
@@ -107,6 +215,361 @@ where
     }
 }
 
+/// Middleware that rejects unauthenticated requests
+///
+/// Unlike [`KeyrunesAuthMiddleware`], requests without a valid token are
+/// rejected, so an app can wrap everything once and list the routes that
+/// stay open as public routes in the state's bypass rules:
+///
+/// ```ignore
+/// let state = KeyrunesState::new(client).with_bypass_rules(
+///     BypassRules::default().with_route(RoutePattern::prefix("/public/")),
+/// );
+/// App::new()
+///     .app_data(web::Data::new(state))
+///     .wrap(KeyrunesRequireAuth)
+///     .service(profile)
+/// ```
+pub struct KeyrunesRequireAuth;
+
+impl<S, B> Transform<S, ServiceRequest> for KeyrunesRequireAuth
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = KeyrunesRequireAuthService<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(KeyrunesRequireAuthService {
+            service: Rc::new(service),
+        }))
+    }
+}
+
+pub struct KeyrunesRequireAuthService<S> {
+    service: Rc<S>,
+}
+
+impl<S, B> Service<ServiceRequest> for KeyrunesRequireAuthService<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = Pin<Box<dyn std::future::Future<Output = Result<Self::Response, Self::Error>>>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let service = self.service.clone();
+
+        Box::pin(async move {
+            let Some(state) = req
+                .app_data::<actix_web::web::Data<KeyrunesState>>()
+                .cloned()
+            else {
+                return Err(reject(
+                    req.request(),
+                    RejectionKind::Internal("KeyrunesState not configured".to_string()),
+                ));
+            };
+            if state.auth.bypasses(req.method().as_str(), req.path()) {
+                return service.call(req).await;
+            }
+
+            let timer = state.auth.audit();
+            let result = state
+                .auth
+                .authenticate_request(
+                    authorization(req.request()),
+                    certificate(req.request(), &state.auth),
+                )
+                .await;
+            audit(
+                timer,
+                req.request(),
+                "authenticated",
+                result.as_ref().ok(),
+                &result,
+            );
+            let user = result.map_err(|kind| reject(req.request(), kind))?;
+            req.extensions_mut().insert(AuthenticatedUser { user });
+
+            service.call(req).await
+        })
+    }
+}
+
+/// Middleware that rate-limits requests by authenticated user ID
+///
+/// Must run after [`KeyrunesAuthMiddleware`] (i.e., be registered before it
+/// with `wrap`), since it reads the user resolved by that middleware.
+/// Unauthenticated requests are not limited.
+pub struct KeyrunesRateLimit {
+    limiter: RateLimiter,
+}
+
+impl KeyrunesRateLimit {
+    pub fn new(limiter: RateLimiter) -> Self {
+        Self { limiter }
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for KeyrunesRateLimit
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = KeyrunesRateLimitService<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(KeyrunesRateLimitService {
+            service: Rc::new(service),
+            limiter: self.limiter.clone(),
+        }))
+    }
+}
+
+pub struct KeyrunesRateLimitService<S> {
+    service: Rc<S>,
+    limiter: RateLimiter,
+}
+
+impl<S, B> Service<ServiceRequest> for KeyrunesRateLimitService<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = Pin<Box<dyn std::future::Future<Output = Result<Self::Response, Self::Error>>>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let service = self.service.clone();
+        let limiter = self.limiter.clone();
+
+        Box::pin(async move {
+            let user = req.extensions().get::<AuthenticatedUser>().cloned();
+
+            if let Some(user) = user {
+                let timer = req
+                    .app_data::<actix_web::web::Data<KeyrunesState>>()
+                    .map_or(AuditTimer::new(None), |state| state.auth.audit());
+                let result = AuthService::check_rate_limit(&limiter, &user.user).await;
+                audit(
+                    timer,
+                    req.request(),
+                    "rate_limit",
+                    Some(&user.user),
+                    &result,
+                );
+                result.map_err(|kind| reject(req.request(), kind))?;
+            }
+
+            service.call(req).await
+        })
+    }
+}
+
+/// Middleware that requires membership in a group for a whole scope
+///
+/// Must run after [`KeyrunesAuthMiddleware`] (i.e., be registered before it
+/// with `wrap`). Requests from unauthenticated users or non-members are
+/// rejected before any handler runs, except those matching the state's
+/// bypass rules:
+///
+/// ```ignore
+/// web::scope("/admin")
+///     .wrap(KeyrunesRequireGroup::new("admins"))
+///     .wrap(KeyrunesAuthMiddleware)
+///     .service(dashboard)
+/// ```
+pub struct KeyrunesRequireGroup {
+    group_id: Rc<str>,
+}
+
+impl KeyrunesRequireGroup {
+    pub fn new(group_id: impl Into<String>) -> Self {
+        Self {
+            group_id: group_id.into().into(),
+        }
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for KeyrunesRequireGroup
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = KeyrunesRequireGroupService<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(KeyrunesRequireGroupService {
+            service: Rc::new(service),
+            group_id: self.group_id.clone(),
+        }))
+    }
+}
+
+pub struct KeyrunesRequireGroupService<S> {
+    service: Rc<S>,
+    group_id: Rc<str>,
+}
+
+impl<S, B> Service<ServiceRequest> for KeyrunesRequireGroupService<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = Pin<Box<dyn std::future::Future<Output = Result<Self::Response, Self::Error>>>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let service = self.service.clone();
+        let group_id = self.group_id.clone();
+
+        Box::pin(async move {
+            let Some(state) = req
+                .app_data::<actix_web::web::Data<KeyrunesState>>()
+                .cloned()
+            else {
+                return Err(reject(
+                    req.request(),
+                    RejectionKind::Internal("KeyrunesState not configured".to_string()),
+                ));
+            };
+            if state.auth.bypasses(req.method().as_str(), req.path()) {
+                return service.call(req).await;
+            }
+            let timer = state.auth.audit();
+            let user = req.extensions().get::<AuthenticatedUser>().cloned();
+            let token = AuthService::bearer_token(authorization(req.request()));
+            let result = match (&user, token) {
+                (Some(user), Ok(token)) => {
+                    state.auth.require_group(&user.user, token, &group_id).await
+                }
+                (Some(_), Err(kind)) => Err(kind),
+                (None, _) => Err(RejectionKind::Unauthenticated(
+                    "User not authenticated".to_string(),
+                )),
+            };
+            audit(
+                timer,
+                req.request(),
+                &format!("group:{}", group_id),
+                user.as_ref().map(|user| &user.user),
+                &result,
+            );
+            result.map_err(|kind| reject(req.request(), kind))?;
+
+            service.call(req).await
+        })
+    }
+}
+
+/// Middleware that rejects state-changing requests without a valid CSRF token (feature `sessions`)
+///
+/// See [`CsrfProtection::verify`]. When a [`SessionData`] is in the request
+/// extensions, the token must also match the session's.
+#[cfg(feature = "sessions")]
+pub struct KeyrunesCsrf {
+    csrf: Rc<CsrfProtection>,
+}
+
+#[cfg(feature = "sessions")]
+impl KeyrunesCsrf {
+    pub fn new(csrf: CsrfProtection) -> Self {
+        Self {
+            csrf: Rc::new(csrf),
+        }
+    }
+}
+
+#[cfg(feature = "sessions")]
+impl<S, B> Transform<S, ServiceRequest> for KeyrunesCsrf
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = KeyrunesCsrfService<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(KeyrunesCsrfService {
+            service: Rc::new(service),
+            csrf: self.csrf.clone(),
+        }))
+    }
+}
+
+#[cfg(feature = "sessions")]
+pub struct KeyrunesCsrfService<S> {
+    service: Rc<S>,
+    csrf: Rc<CsrfProtection>,
+}
+
+#[cfg(feature = "sessions")]
+impl<S, B> Service<ServiceRequest> for KeyrunesCsrfService<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = Pin<Box<dyn std::future::Future<Output = Result<Self::Response, Self::Error>>>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let verified = self.csrf.verify(
+            req.method().as_str(),
+            req.headers()
+                .get(actix_web::http::header::COOKIE)
+                .and_then(|h| h.to_str().ok()),
+            req.headers()
+                .get(self.csrf.header_name())
+                .and_then(|h| h.to_str().ok()),
+            req.extensions().get::<SessionData>(),
+        );
+        if let Err(kind) = verified {
+            let err = reject(req.request(), kind);
+            return Box::pin(async move { Err(err) });
+        }
+
+        let service = self.service.clone();
+        Box::pin(async move { service.call(req).await })
+    }
+}
+
 /// Helper function to verify if the user belongs to a group
 pub async fn require_group(
     req: &actix_web::HttpRequest,
@@ -115,26 +578,176 @@ pub async fn require_group(
     let user = AuthenticatedUser::from_request(req, &mut actix_web::dev::Payload::None).await?;
 
     if let Some(state) = req.app_data::<actix_web::web::Data<KeyrunesState>>() {
-        let has_group = state
-            .client
-            .has_group(&user.user.id, group_id)
+        let token =
+            AuthService::bearer_token(authorization(req)).map_err(|kind| reject(req, kind))?;
+        state
+            .auth
+            .require_group(&user.user, token, group_id)
             .await
-            .map_err(|e| actix_web::error::ErrorForbidden(e.to_string()))?;
-
-        if !has_group {
-            return Err(actix_web::error::ErrorForbidden(format!(
-                "User does not belong to group: {}",
-                group_id
-            )));
-        }
+            .map_err(|kind| reject(req, kind))?;
     }
 
     Ok(user)
 }
 
 /// Helper function to verify if the user is an administrator
+///
+/// Uses the [`AdminPolicy`] of the [`KeyrunesState`].
 pub async fn require_admin(
     req: &actix_web::HttpRequest,
 ) -> Result<AuthenticatedUser, actix_web::Error> {
-    require_group(req, "admins").await
+    let user = AuthenticatedUser::from_request(req, &mut actix_web::dev::Payload::None).await?;
+    let state = req
+        .app_data::<actix_web::web::Data<KeyrunesState>>()
+        .ok_or_else(|| {
+            reject(
+                req,
+                RejectionKind::Internal("KeyrunesState not configured".to_string()),
+            )
+        })?;
+    let token = AuthService::bearer_token(authorization(req)).map_err(|kind| reject(req, kind))?;
+
+    state
+        .auth
+        .require_admin(&user.user, token)
+        .await
+        .map_err(|kind| reject(req, kind))?;
+
+    Ok(user)
+}
+
+/// Helper function to verify that the user holds an entitlement
+pub async fn require_entitlement(
+    req: &actix_web::HttpRequest,
+    key: &str,
+) -> Result<AuthenticatedUser, actix_web::Error> {
+    let user = AuthenticatedUser::from_request(req, &mut actix_web::dev::Payload::None).await?;
+
+    if let Some(state) = req.app_data::<actix_web::web::Data<KeyrunesState>>() {
+        let token =
+            AuthService::bearer_token(authorization(req)).map_err(|kind| reject(req, kind))?;
+        state
+            .auth
+            .require_entitlement(&user.user, token, key)
+            .await
+            .map_err(|kind| reject(req, kind))?;
+    }
+
+    Ok(user)
+}
+
+/// Helper function to verify that the session reached a minimum authentication level
+pub async fn require_auth_level(
+    req: &actix_web::HttpRequest,
+    level: AuthLevel,
+) -> Result<AuthenticatedUser, actix_web::Error> {
+    let user = AuthenticatedUser::from_request(req, &mut actix_web::dev::Payload::None).await?;
+    let state = req
+        .app_data::<actix_web::web::Data<KeyrunesState>>()
+        .ok_or_else(|| {
+            reject(
+                req,
+                RejectionKind::Internal("KeyrunesState not configured".to_string()),
+            )
+        })?;
+
+    AuthService::bearer_token(authorization(req))
+        .and_then(|token| state.auth.require_auth_level(token, level))
+        .map_err(|kind| reject(req, kind))?;
+
+    Ok(user)
+}
+
+/// Helper function to verify that the user authenticated within `max_age`
+pub async fn require_fresh_auth(
+    req: &actix_web::HttpRequest,
+    max_age: std::time::Duration,
+) -> Result<AuthenticatedUser, actix_web::Error> {
+    let user = AuthenticatedUser::from_request(req, &mut actix_web::dev::Payload::None).await?;
+    let state = req
+        .app_data::<actix_web::web::Data<KeyrunesState>>()
+        .ok_or_else(|| {
+            reject(
+                req,
+                RejectionKind::Internal("KeyrunesState not configured".to_string()),
+            )
+        })?;
+
+    AuthService::bearer_token(authorization(req))
+        .and_then(|token| state.auth.require_fresh_auth(token, max_age))
+        .map_err(|kind| reject(req, kind))?;
+
+    Ok(user)
+}
+
+/// Handler for OIDC back-channel logout requests from Keyrunes
+///
+/// Mount it on the back-channel logout URL registered in Keyrunes, with a
+/// [`BackChannelLogout`] in the app data. Answers `200` once the user is
+/// logged out, and `400` if the logout token is rejected:
+///
+/// ```ignore
+/// let logout = BackChannelLogout::new(client, "billing-app").with_sessions(sessions);
+/// App::new()
+///     .app_data(web::Data::new(logout))
+///     .route("/auth/backchannel-logout", web::post().to(backchannel_logout))
+/// ```
+pub async fn backchannel_logout(
+    logout: actix_web::web::Data<BackChannelLogout>,
+    body: actix_web::web::Bytes,
+) -> actix_web::HttpResponse {
+    let mut response = match logout.handle_form(&body).await {
+        Ok(_) => actix_web::HttpResponse::Ok(),
+        Err(_) => actix_web::HttpResponse::BadRequest(),
+    };
+    response
+        .insert_header((actix_web::http::header::CACHE_CONTROL, "no-store"))
+        .finish()
+}
+
+/// Reports the outcome of a middleware check to the audit sink
+fn audit<T>(
+    timer: AuditTimer,
+    req: &actix_web::HttpRequest,
+    requirement: &str,
+    user: Option<&User>,
+    result: &Result<T, RejectionKind>,
+) {
+    let route = req.match_pattern();
+    timer.finish(
+        req.method().as_str(),
+        route.as_deref().unwrap_or(req.path()),
+        requirement,
+        user,
+        result,
+    );
+}
+
+/// Returns the `Authorization` header of the request
+fn authorization(req: &actix_web::HttpRequest) -> Option<&str> {
+    req.headers()
+        .get(actix_web::http::header::AUTHORIZATION)
+        .and_then(|h| h.to_str().ok())
+}
+
+/// Value of the client certificate header, when certificate binding is set
+fn certificate<'a>(req: &'a actix_web::HttpRequest, auth: &AuthService) -> Option<&'a str> {
+    let name = auth.certificate_header()?;
+    req.headers().get(name).and_then(|h| h.to_str().ok())
+}
+
+/// Builds the error for a rejection, rendered with the state's message formatter
+fn reject(req: &actix_web::HttpRequest, kind: RejectionKind) -> actix_web::Error {
+    let accept_language = req
+        .headers()
+        .get(actix_web::http::header::ACCEPT_LANGUAGE)
+        .and_then(|h| h.to_str().ok());
+    let status = actix_web::http::StatusCode::from_u16(kind.status())
+        .unwrap_or(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR);
+    let message = match req.app_data::<actix_web::web::Data<KeyrunesState>>() {
+        Some(state) => state.auth.message(kind, accept_language),
+        None => default_message(kind, &Locale::from_accept_language(accept_language)),
+    };
+
+    actix_web::error::InternalError::new(message, status).into()
 }
@@ -0,0 +1,138 @@
+//! Strategies deciding who is an administrator
+//!
+//! The `RequireAdmin` extractors and `require_admin` helpers ask the
+//! integration's `KeyrunesState` for its [`AdminPolicy`]. By default a user
+//! is an administrator when they belong to the `admins` group; deployments
+//! with a different notion of "admin" configure it once:
+//!
+//! ```ignore
+//! let state = KeyrunesState::new(client).with_admin_policy(PermissionPolicy::new("admin:*"));
+//! ```
+
+use super::group_check::GroupCheckStrategy;
+use crate::claims;
+use crate::error::Result;
+use crate::{KeyrunesClient, User};
+use async_trait::async_trait;
+
+/// Group whose members are administrators under the default policy
+pub const DEFAULT_ADMIN_GROUP: &str = "admins";
+
+/// Decides whether an authenticated user is an administrator
+#[async_trait]
+pub trait AdminPolicy: Send + Sync {
+    /// Checks whether `user`, authenticated with `token`, is an administrator.
+    async fn is_admin(&self, client: &KeyrunesClient, user: &User, token: &str) -> Result<bool>;
+}
+
+/// Administrators are the members of a group
+///
+/// Membership is confirmed with Keyrunes unless another
+/// [`GroupCheckStrategy`] is set.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GroupPolicy {
+    group: String,
+    strategy: GroupCheckStrategy,
+}
+
+impl GroupPolicy {
+    pub fn new<S: Into<String>>(group: S) -> Self {
+        Self {
+            group: group.into(),
+            strategy: GroupCheckStrategy::default(),
+        }
+    }
+
+    /// Sets how membership is checked.
+    pub fn with_strategy(mut self, strategy: GroupCheckStrategy) -> Self {
+        self.strategy = strategy;
+        self
+    }
+}
+
+impl Default for GroupPolicy {
+    fn default() -> Self {
+        Self::new(DEFAULT_ADMIN_GROUP)
+    }
+}
+
+#[async_trait]
+impl AdminPolicy for GroupPolicy {
+    async fn is_admin(&self, client: &KeyrunesClient, user: &User, _token: &str) -> Result<bool> {
+        self.strategy.check(client, user, &self.group).await
+    }
+}
+
+/// Administrators hold a role in their token (see [`ClaimsMapping`](crate::claims::ClaimsMapping))
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RolePolicy {
+    role: String,
+}
+
+impl RolePolicy {
+    pub fn new<S: Into<String>>(role: S) -> Self {
+        Self { role: role.into() }
+    }
+}
+
+#[async_trait]
+impl AdminPolicy for RolePolicy {
+    async fn is_admin(&self, client: &KeyrunesClient, _user: &User, token: &str) -> Result<bool> {
+        Ok(client.decode_claims(token)?.roles.contains(&self.role))
+    }
+}
+
+/// Administrators hold a permission (checked against their compiled policy)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PermissionPolicy {
+    permission: String,
+}
+
+impl PermissionPolicy {
+    pub fn new<S: Into<String>>(permission: S) -> Self {
+        Self {
+            permission: permission.into(),
+        }
+    }
+}
+
+#[async_trait]
+impl AdminPolicy for PermissionPolicy {
+    async fn is_admin(&self, client: &KeyrunesClient, user: &User, _token: &str) -> Result<bool> {
+        Ok(client
+            .compile_policy(user.id.as_str())
+            .await?
+            .implies(&self.permission))
+    }
+}
+
+/// Administrators carry a claim with a given value, checked locally
+///
+/// The claim matches when it equals `value` or is a list containing it.
+/// Nested claims are addressed with dots (e.g., `app_metadata.admin`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClaimPolicy {
+    claim: String,
+    value: serde_json::Value,
+}
+
+impl ClaimPolicy {
+    pub fn new<C: Into<String>, V: Into<serde_json::Value>>(claim: C, value: V) -> Self {
+        Self {
+            claim: claim.into(),
+            value: value.into(),
+        }
+    }
+}
+
+#[async_trait]
+impl AdminPolicy for ClaimPolicy {
+    async fn is_admin(&self, _client: &KeyrunesClient, _user: &User, token: &str) -> Result<bool> {
+        let payload = claims::decode_payload(token)?;
+        Ok(match claims::lookup(&payload, &self.claim) {
+            Some(serde_json::Value::Array(values)) => values.contains(&self.value),
+            Some(value) => *value == self.value,
+            None => false,
+        })
+    }
+}
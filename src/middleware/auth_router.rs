@@ -0,0 +1,264 @@
+//! Ready-made authentication routes for Axum
+//!
+//! [`keyrunes_auth_router`] mounts a complete cookie-based auth backend that
+//! proxies to Keyrunes:
+//!
+//! | Route                | Description                                         |
+//! |----------------------|-----------------------------------------------------|
+//! | `POST /auth/login`   | Logs in with `{username, password, namespace?}`     |
+//! | `POST /auth/logout`  | Revokes the token and clears the cookies            |
+//! | `POST /auth/refresh` | Exchanges the refresh cookie for a new token        |
+//! | `GET /auth/me`       | Returns the authenticated user                      |
+//!
+//! The token and refresh token are kept in `HttpOnly` cookies, so browser
+//! code never handles them:
+//!
+//! ```ignore
+//! let app = Router::new()
+//!     .route("/api/profile", get(profile))
+//!     .merge(keyrunes_auth_router())
+//!     .with_state(KeyrunesState::new(client));
+//! ```
+
+use super::axum::{KeyrunesRejection, KeyrunesState};
+use crate::auth_service::AuthService;
+use crate::{KeyrunesError, Token, User};
+use axum::{
+    extract::{FromRef, State},
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Json, Router,
+};
+use serde::Deserialize;
+
+/// Cookie holding the token, by default
+const DEFAULT_TOKEN_COOKIE: &str = "keyrunes_token";
+
+/// Cookie holding the refresh token, by default
+const DEFAULT_REFRESH_COOKIE: &str = "keyrunes_refresh";
+
+/// Cookie settings of the auth routes
+///
+/// Cookies are `HttpOnly`, `Secure` and `SameSite=Lax` by default; disable
+/// `secure` for local development over plain HTTP.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuthCookies {
+    /// Name of the cookie holding the token
+    pub token_name: String,
+    /// Name of the cookie holding the refresh token
+    pub refresh_name: String,
+    /// Whether cookies are only sent over HTTPS
+    pub secure: bool,
+    /// Domain of the cookies (the request host when unset)
+    pub domain: Option<String>,
+}
+
+impl AuthCookies {
+    /// Sets whether cookies are only sent over HTTPS.
+    pub fn with_secure(mut self, secure: bool) -> Self {
+        self.secure = secure;
+        self
+    }
+
+    /// Sets the domain of the cookies.
+    pub fn with_domain<S: Into<String>>(mut self, domain: S) -> Self {
+        self.domain = Some(domain.into());
+        self
+    }
+
+    /// Reads the cookie `name` from the request headers.
+    fn get<'h>(&self, headers: &'h HeaderMap, name: &str) -> Option<&'h str> {
+        headers
+            .get_all(header::COOKIE)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(';'))
+            .filter_map(|pair| pair.trim().split_once('='))
+            .find(|(key, _)| *key == name)
+            .map(|(_, value)| value)
+    }
+
+    /// `Set-Cookie` value storing `value` for `max_age` seconds (a session cookie when `None`).
+    fn set(&self, name: &str, value: &str, max_age: Option<i64>) -> String {
+        let mut cookie = format!("{}={}; Path=/; HttpOnly; SameSite=Lax", name, value);
+        if let Some(max_age) = max_age {
+            cookie.push_str(&format!("; Max-Age={}", max_age.max(0)));
+        }
+        if self.secure {
+            cookie.push_str("; Secure");
+        }
+        if let Some(domain) = &self.domain {
+            cookie.push_str(&format!("; Domain={}", domain));
+        }
+        cookie
+    }
+
+    /// `Set-Cookie` values storing `token` and its refresh token
+    fn store(&self, token: &Token) -> Vec<String> {
+        let max_age = token.expires_in.or_else(|| {
+            token
+                .expires_at
+                .map(|at| (at - chrono::Utc::now()).num_seconds())
+        });
+
+        let mut cookies = vec![self.set(&self.token_name, &token.token, max_age)];
+        if let Some(refresh_token) = &token.refresh_token {
+            cookies.push(self.set(&self.refresh_name, refresh_token, None));
+        }
+        cookies
+    }
+
+    /// `Set-Cookie` values removing both cookies
+    fn clear(&self) -> Vec<String> {
+        vec![
+            self.set(&self.token_name, "", Some(0)),
+            self.set(&self.refresh_name, "", Some(0)),
+        ]
+    }
+}
+
+impl Default for AuthCookies {
+    fn default() -> Self {
+        Self {
+            token_name: DEFAULT_TOKEN_COOKIE.to_string(),
+            refresh_name: DEFAULT_REFRESH_COOKIE.to_string(),
+            secure: true,
+            domain: None,
+        }
+    }
+}
+
+/// Body of `POST /auth/login`
+#[derive(Debug, Deserialize)]
+struct LoginForm {
+    username: String,
+    password: String,
+    #[serde(default)]
+    namespace: Option<String>,
+}
+
+/// Router with the auth routes, using the default [`AuthCookies`]
+pub fn keyrunes_auth_router<S>() -> Router<S>
+where
+    KeyrunesState: FromRef<S>,
+    S: Clone + Send + Sync + 'static,
+{
+    keyrunes_auth_router_with(AuthCookies::default())
+}
+
+/// Router with the auth routes, storing tokens in `cookies`
+pub fn keyrunes_auth_router_with<S>(cookies: AuthCookies) -> Router<S>
+where
+    KeyrunesState: FromRef<S>,
+    S: Clone + Send + Sync + 'static,
+{
+    let login_cookies = cookies.clone();
+    let logout_cookies = cookies.clone();
+    let refresh_cookies = cookies.clone();
+    let me_cookies = cookies;
+
+    Router::new()
+        .route(
+            "/auth/login",
+            post(move |State(state): State<KeyrunesState>, Json(form)| {
+                login(state, login_cookies, form)
+            }),
+        )
+        .route(
+            "/auth/logout",
+            post(move |State(state): State<KeyrunesState>, headers| {
+                logout(state, logout_cookies, headers)
+            }),
+        )
+        .route(
+            "/auth/refresh",
+            post(move |State(state): State<KeyrunesState>, headers| {
+                refresh(state, refresh_cookies, headers)
+            }),
+        )
+        .route(
+            "/auth/me",
+            get(move |State(state): State<KeyrunesState>, headers| me(state, me_cookies, headers)),
+        )
+}
+
+async fn login(
+    state: KeyrunesState,
+    cookies: AuthCookies,
+    form: LoginForm,
+) -> Result<Response, KeyrunesRejection> {
+    let token = state
+        .client
+        .login(form.username, form.password, form.namespace)
+        .await
+        .map_err(|e| match e {
+            // Challenges cannot be completed through these routes
+            KeyrunesError::ChallengeRequired(_) => KeyrunesRejection::AuthError(e.to_string()),
+            e => e.into(),
+        })?;
+    let user = state.auth.authenticate(&token.token).await?;
+
+    Ok(with_cookies(Json(user), cookies.store(&token)))
+}
+
+async fn logout(
+    state: KeyrunesState,
+    cookies: AuthCookies,
+    headers: HeaderMap,
+) -> Result<Response, KeyrunesRejection> {
+    if let Some(token) = request_token(&cookies, &headers) {
+        match state.client.revoke_token(token).await {
+            // The token already expired or was revoked; clear the cookies anyway
+            Ok(()) | Err(KeyrunesError::AuthenticationError { .. }) => {}
+            Err(e) => return Err(e.into()),
+        }
+    }
+
+    Ok(with_cookies(StatusCode::NO_CONTENT, cookies.clear()))
+}
+
+async fn refresh(
+    state: KeyrunesState,
+    cookies: AuthCookies,
+    headers: HeaderMap,
+) -> Result<Response, KeyrunesRejection> {
+    let refresh_token = cookies
+        .get(&headers, &cookies.refresh_name)
+        .ok_or(KeyrunesRejection::MissingToken)?;
+    let token = state.client.exchange_refresh_token(refresh_token).await?;
+
+    Ok(with_cookies(StatusCode::NO_CONTENT, cookies.store(&token)))
+}
+
+async fn me(
+    state: KeyrunesState,
+    cookies: AuthCookies,
+    headers: HeaderMap,
+) -> Result<Json<User>, KeyrunesRejection> {
+    let token = request_token(&cookies, &headers).ok_or(KeyrunesRejection::MissingToken)?;
+
+    Ok(Json(state.auth.authenticate(token).await?))
+}
+
+/// Token of the request, from the `Authorization` header or the token cookie
+fn request_token<'h>(cookies: &AuthCookies, headers: &'h HeaderMap) -> Option<&'h str> {
+    let authorization = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|h| h.to_str().ok());
+
+    AuthService::bearer_token(authorization)
+        .ok()
+        .or_else(|| cookies.get(headers, &cookies.token_name))
+        .filter(|token| !token.is_empty())
+}
+
+fn with_cookies(response: impl IntoResponse, cookies: Vec<String>) -> Response {
+    let mut response = response.into_response();
+    for cookie in cookies {
+        if let Ok(value) = HeaderValue::from_str(&cookie) {
+            response.headers_mut().append(header::SET_COOKIE, value);
+        }
+    }
+    response
+}
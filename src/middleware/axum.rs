@@ -1,112 +1,201 @@
 //! Middleware for Axum integration
 
+use super::admin_policy::AdminPolicy;
+use super::bypass::BypassRules;
+use super::group_check::GroupCheckStrategy;
+use super::messages::{default_message, Locale, MessageFormatter, RejectionKind};
+use crate::audit::{AuditTimer, AuthAuditSink};
+use crate::auth_service::AuthService;
+use crate::claims::{AuthLevel, Mfa, RequiredLevel};
+#[cfg(feature = "sessions")]
+use crate::csrf::CsrfProtection;
+use crate::entitlements::EntitlementKey;
+use crate::groups::GroupName;
+use crate::logout::BackChannelLogout;
+#[cfg(feature = "mtls")]
+use crate::mtls::CertificateBinding;
+use crate::rate_limit::RateLimiter;
+use crate::requirements::{Require, RouteRequirements};
+#[cfg(feature = "sessions")]
+use crate::session::{SessionData, SessionManager};
+use crate::validation::TokenValidation;
 use crate::{KeyrunesClient, KeyrunesError, User};
 use axum::{
     async_trait,
-    extract::{FromRequestParts, Query},
+    extract::{FromRef, FromRequestParts, MatchedPath, Query, Request, State},
     http::request::Parts,
-    http::StatusCode,
+    http::{header, StatusCode},
+    middleware::Next,
     response::{IntoResponse, Response},
     RequestPartsExt,
 };
 use std::collections::HashMap;
+use std::marker::PhantomData;
 use std::sync::Arc;
 
 /// Keyrunes client state for use in Axum
+///
+/// The extractors accept any router state that provides a `KeyrunesState`
+/// through [`FromRef`], so it can be a field of the application's state:
+///
+/// ```ignore
+/// #[derive(Clone)]
+/// struct AppState {
+///     keyrunes: KeyrunesState,
+///     db: PgPool,
+/// }
+///
+/// impl FromRef<AppState> for KeyrunesState {
+///     fn from_ref(state: &AppState) -> Self {
+///         state.keyrunes.clone()
+///     }
+/// }
+///
+/// async fn profile(AuthenticatedUser { user }: AuthenticatedUser, State(state): State<AppState>) {}
+/// ```
 #[derive(Clone)]
 pub struct KeyrunesState {
     pub client: Arc<KeyrunesClient>,
+    pub auth: AuthService,
 }
 
 impl KeyrunesState {
     pub fn new(client: KeyrunesClient) -> Self {
+        Self::from_service(AuthService::new(client))
+    }
+
+    /// Creates the state from a configured [`AuthService`].
+    pub fn from_service(auth: AuthService) -> Self {
         Self {
-            client: Arc::new(client),
+            client: auth.client().clone(),
+            auth,
         }
     }
+
+    /// Sets how group requirements are checked (always verified with Keyrunes by default).
+    ///
+    /// Admin checks follow the admin policy; use
+    /// `GroupPolicy::default().with_strategy(strategy)` to apply the strategy to them too.
+    pub fn with_group_check(mut self, strategy: GroupCheckStrategy) -> Self {
+        self.auth = self.auth.with_group_check(strategy);
+        self
+    }
+
+    /// Decides who is an administrator (members of `admins` by default).
+    pub fn with_admin_policy<P: AdminPolicy + 'static>(mut self, policy: P) -> Self {
+        self.auth = self.auth.with_admin_policy(policy);
+        self
+    }
+
+    /// Renders rejection bodies with `formatter` (see [`localize_rejections`]).
+    pub fn with_message_formatter(mut self, formatter: MessageFormatter) -> Self {
+        self.auth = self.auth.with_message_formatter(formatter);
+        self
+    }
+
+    /// Sets the requests the middlewares let through without a token
+    /// (`OPTIONS` and health endpoints by default).
+    pub fn with_bypass_rules(mut self, rules: BypassRules) -> Self {
+        self.auth = self.auth.with_bypass_rules(rules);
+        self
+    }
+
+    /// Validates tokens locally instead of asking Keyrunes (see [`crate::validation`]).
+    pub fn with_token_validation(mut self, validation: TokenValidation) -> Self {
+        self.auth = self.auth.with_token_validation(validation);
+        self
+    }
+
+    /// Rejects certificate-bound tokens presented without their client
+    /// certificate (feature `mtls`, see [`crate::mtls`]).
+    #[cfg(feature = "mtls")]
+    pub fn with_certificate_binding(mut self, binding: CertificateBinding) -> Self {
+        self.auth = self.auth.with_certificate_binding(binding);
+        self
+    }
+
+    /// Reports the middlewares' access decisions to `sink` (see [`crate::audit`]).
+    pub fn with_audit_sink<A: AuthAuditSink + 'static>(mut self, sink: A) -> Self {
+        self.auth = self.auth.with_audit_sink(sink);
+        self
+    }
 }
 
 /// Extractor that gets the current authenticated user
+///
+/// Reuses the user resolved by [`require_auth`] when that layer ran.
 #[derive(Clone, Debug)]
 pub struct AuthenticatedUser {
     pub user: User,
 }
 
 #[async_trait]
-impl FromRequestParts<KeyrunesState> for AuthenticatedUser {
+impl<S> FromRequestParts<S> for AuthenticatedUser
+where
+    KeyrunesState: FromRef<S>,
+    S: Send + Sync,
+{
     type Rejection = KeyrunesRejection;
 
-    async fn from_request_parts(
-        parts: &mut Parts,
-        state: &KeyrunesState,
-    ) -> Result<Self, Self::Rejection> {
-        let auth_header = parts
-            .headers
-            .get("authorization")
-            .and_then(|h| h.to_str().ok())
-            .ok_or(KeyrunesRejection::MissingToken)?;
-
-        let token = auth_header
-            .strip_prefix("Bearer ")
-            .ok_or(KeyrunesRejection::InvalidToken)?;
-
-        let keyrunes_state = state;
-
-        keyrunes_state.client.set_token(token.to_string()).await;
-        let user = keyrunes_state
-            .client
-            .get_current_user()
-            .await
-            .map_err(|e| KeyrunesRejection::AuthError(e.to_string()))?;
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        if let Some(user) = parts.extensions.get::<AuthenticatedUser>() {
+            return Ok(user.clone());
+        }
+
+        let state = &KeyrunesState::from_ref(state);
+        let user = state
+            .auth
+            .authenticate_request(authorization(parts), certificate(parts, &state.auth))
+            .await?;
 
         Ok(AuthenticatedUser { user })
     }
 }
 
 /// Extractor to verify if the user belongs to a specific group
+///
+/// The group is read from the `group_id` query parameter. With a group enum
+/// declared by [`keyrunes_groups!`](crate::keyrunes_groups) as `G` (e.g.,
+/// `RequireGroup<AppGroup>`), groups outside the set are rejected.
 #[derive(Clone, Debug)]
-pub struct RequireGroup {
+pub struct RequireGroup<G: GroupName = String> {
     pub user: User,
-    pub group_id: String,
+    pub group_id: G,
 }
 
 #[async_trait]
-impl FromRequestParts<KeyrunesState> for RequireGroup {
+impl<S, G> FromRequestParts<S> for RequireGroup<G>
+where
+    KeyrunesState: FromRef<S>,
+    S: Send + Sync,
+    G: GroupName + Send,
+{
     type Rejection = KeyrunesRejection;
 
-    async fn from_request_parts(
-        parts: &mut Parts,
-        state: &KeyrunesState,
-    ) -> Result<Self, Self::Rejection> {
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
         let authenticated_user = AuthenticatedUser::from_request_parts(parts, state).await?;
+        let state = &KeyrunesState::from_ref(state);
 
         let query_params = parts
             .extract::<Query<HashMap<String, String>>>()
             .await
             .map_err(|_| KeyrunesRejection::Other("Error processing query params".to_string()))?;
 
-        let group_id = query_params
+        let name = query_params
             .get("group_id")
             .ok_or(KeyrunesRejection::MissingGroup)?;
+        let group_id = G::from_name(name)
+            .ok_or_else(|| KeyrunesRejection::Forbidden(format!("Unknown group: {}", name)))?;
 
-        let keyrunes_state = state;
-
-        let has_group = keyrunes_state
-            .client
-            .has_group(&authenticated_user.user.id, group_id)
-            .await
-            .map_err(|e| KeyrunesRejection::AuthError(e.to_string()))?;
-
-        if !has_group {
-            return Err(KeyrunesRejection::Forbidden(format!(
-                "User does not belong to group: {}",
-                group_id
-            )));
-        }
+        let token = AuthService::bearer_token(authorization(parts))?;
+        state
+            .auth
+            .require_group(&authenticated_user.user, token, group_id.as_name())
+            .await?;
 
         Ok(RequireGroup {
             user: authenticated_user.user,
-            group_id: group_id.clone(),
+            group_id,
         })
     }
 }
@@ -118,35 +207,377 @@ pub struct RequireAdmin {
 }
 
 #[async_trait]
-impl FromRequestParts<KeyrunesState> for RequireAdmin {
+impl<S> FromRequestParts<S> for RequireAdmin
+where
+    KeyrunesState: FromRef<S>,
+    S: Send + Sync,
+{
     type Rejection = KeyrunesRejection;
 
-    async fn from_request_parts(
-        parts: &mut Parts,
-        state: &KeyrunesState,
-    ) -> Result<Self, Self::Rejection> {
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
         let authenticated_user = AuthenticatedUser::from_request_parts(parts, state).await?;
+        let state = &KeyrunesState::from_ref(state);
 
-        let keyrunes_state = state;
+        let token = AuthService::bearer_token(authorization(parts))?;
+        state
+            .auth
+            .require_admin(&authenticated_user.user, token)
+            .await?;
 
-        let is_admin = keyrunes_state
-            .client
-            .has_group(&authenticated_user.user.id, "admins")
-            .await
-            .map_err(|e| KeyrunesRejection::AuthError(e.to_string()))?;
+        Ok(RequireAdmin {
+            user: authenticated_user.user,
+        })
+    }
+}
 
-        if !is_admin {
-            return Err(KeyrunesRejection::Forbidden(
-                "Access denied: administrator privileges required".to_string(),
-            ));
-        }
+/// Extractor to verify that the session reached a minimum authentication level
+///
+/// Use [`Mfa`](crate::claims::Mfa) (the default) to demand an MFA-verified
+/// session; otherwise the request is rejected so the client can perform
+/// step-up authentication.
+#[derive(Clone, Debug)]
+pub struct RequireAuthLevel<L: RequiredLevel = Mfa> {
+    pub user: User,
+    pub level: AuthLevel,
+    _level: PhantomData<L>,
+}
 
-        Ok(RequireAdmin {
+#[async_trait]
+impl<S, L: RequiredLevel> FromRequestParts<S> for RequireAuthLevel<L>
+where
+    KeyrunesState: FromRef<S>,
+    S: Send + Sync,
+{
+    type Rejection = KeyrunesRejection;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let authenticated_user = AuthenticatedUser::from_request_parts(parts, state).await?;
+
+        let state = &KeyrunesState::from_ref(state);
+
+        let token = AuthService::bearer_token(authorization(parts))?;
+        let level = state.auth.require_auth_level(token, L::LEVEL)?;
+
+        Ok(RequireAuthLevel {
+            user: authenticated_user.user,
+            level,
+            _level: PhantomData,
+        })
+    }
+}
+
+/// Extractor to verify that the user authenticated recently
+///
+/// Rejects tokens whose `auth_time` (or `iat`) is older than `MINUTES`
+/// minutes, instructing the client to log in again. Useful for payment
+/// or account settings routes.
+#[derive(Clone, Debug)]
+pub struct RequireFreshAuth<const MINUTES: u64 = 5> {
+    pub user: User,
+}
+
+#[async_trait]
+impl<S, const MINUTES: u64> FromRequestParts<S> for RequireFreshAuth<MINUTES>
+where
+    KeyrunesState: FromRef<S>,
+    S: Send + Sync,
+{
+    type Rejection = KeyrunesRejection;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let authenticated_user = AuthenticatedUser::from_request_parts(parts, state).await?;
+
+        let state = &KeyrunesState::from_ref(state);
+
+        let token = AuthService::bearer_token(authorization(parts))?;
+        state
+            .auth
+            .require_fresh_auth(token, std::time::Duration::from_secs(MINUTES * 60))?;
+
+        Ok(RequireFreshAuth {
             user: authenticated_user.user,
         })
     }
 }
 
+/// Extractor to verify that the user holds the entitlement `E`
+///
+/// ```ignore
+/// struct AdvancedReports;
+///
+/// impl EntitlementKey for AdvancedReports {
+///     const KEY: &'static str = "advanced_reports";
+/// }
+///
+/// async fn reports(RequireEntitlement { user, .. }: RequireEntitlement<AdvancedReports>) {}
+/// ```
+#[derive(Clone, Debug)]
+pub struct RequireEntitlement<E: EntitlementKey> {
+    pub user: User,
+    _entitlement: PhantomData<E>,
+}
+
+#[async_trait]
+impl<S, E: EntitlementKey> FromRequestParts<S> for RequireEntitlement<E>
+where
+    KeyrunesState: FromRef<S>,
+    S: Send + Sync,
+{
+    type Rejection = KeyrunesRejection;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let authenticated_user = AuthenticatedUser::from_request_parts(parts, state).await?;
+        let state = &KeyrunesState::from_ref(state);
+
+        let token = AuthService::bearer_token(authorization(parts))?;
+        state
+            .auth
+            .require_entitlement(&authenticated_user.user, token, E::KEY)
+            .await?;
+
+        Ok(RequireEntitlement {
+            user: authenticated_user.user,
+            _entitlement: PhantomData,
+        })
+    }
+}
+
+#[async_trait]
+impl<S, R: RouteRequirements> FromRequestParts<S> for Require<R>
+where
+    KeyrunesState: FromRef<S>,
+    S: Send + Sync,
+{
+    type Rejection = KeyrunesRejection;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let authenticated_user = AuthenticatedUser::from_request_parts(parts, state).await?;
+        let state = &KeyrunesState::from_ref(state);
+
+        let token = AuthService::bearer_token(authorization(parts))?;
+        Ok(state.auth.require(authenticated_user.user, token).await?)
+    }
+}
+
+/// State for the [`rate_limit`] middleware
+///
+/// Pairs the Keyrunes state used to resolve the user with the limiter to
+/// apply. Create one per route (or group of routes) to get per-route quotas.
+#[derive(Clone)]
+pub struct KeyrunesRateLimit {
+    pub state: KeyrunesState,
+    pub limiter: RateLimiter,
+}
+
+impl KeyrunesRateLimit {
+    pub fn new(state: KeyrunesState, limiter: RateLimiter) -> Self {
+        Self { state, limiter }
+    }
+}
+
+/// Middleware that rate-limits requests by authenticated user ID
+///
+/// Requests matching the state's bypass rules (see
+/// [`KeyrunesState::with_bypass_rules`]) are not limited. Use with `axum::middleware::from_fn_with_state`:
+///
+/// ```ignore
+/// let limit = KeyrunesRateLimit::new(state.clone(), RateLimiter::in_memory(RateLimitQuota::per_minute(60)));
+/// let app = Router::new()
+///     .route("/api/reports", get(reports))
+///     .route_layer(axum::middleware::from_fn_with_state(limit, rate_limit));
+/// ```
+pub async fn rate_limit(
+    State(layer): State<KeyrunesRateLimit>,
+    request: Request,
+    next: Next,
+) -> Result<Response, KeyrunesRejection> {
+    if layer
+        .state
+        .auth
+        .bypasses(request.method().as_str(), request.uri().path())
+    {
+        return Ok(next.run(request).await);
+    }
+
+    let timer = layer.state.auth.audit();
+    let (mut parts, body) = request.into_parts();
+    let (user, result) = match AuthenticatedUser::from_request_parts(&mut parts, &layer.state).await
+    {
+        Ok(user) => {
+            let result = AuthService::check_rate_limit(&layer.limiter, &user.user).await;
+            (Some(user.user), result.map(|_| ()))
+        }
+        Err(rejection) => (None, Err(rejection.kind())),
+    };
+    audit(timer, &parts, "rate_limit", user.as_ref(), &result);
+    result?;
+
+    Ok(next.run(Request::from_parts(parts, body)).await)
+}
+
+/// Middleware that rejects unauthenticated requests
+///
+/// Wraps a whole router; the routes that stay open are listed as public
+/// routes in the state's bypass rules. The resolved user is added to the
+/// request extensions, where [`AuthenticatedUser`] and the other extractors
+/// reuse it:
+///
+/// ```ignore
+/// let state = KeyrunesState::new(client).with_bypass_rules(
+///     BypassRules::default()
+///         .with_route(RoutePattern::exact("/login"))
+///         .with_route(RoutePattern::prefix("/static/")),
+/// );
+/// let app = Router::new()
+///     .route("/login", post(login))
+///     .route("/api/profile", get(profile))
+///     .layer(axum::middleware::from_fn_with_state(state.clone(), require_auth))
+///     .with_state(state);
+/// ```
+pub async fn require_auth(
+    State(state): State<KeyrunesState>,
+    request: Request,
+    next: Next,
+) -> Result<Response, KeyrunesRejection> {
+    if state
+        .auth
+        .bypasses(request.method().as_str(), request.uri().path())
+    {
+        return Ok(next.run(request).await);
+    }
+
+    let timer = state.auth.audit();
+    let (mut parts, body) = request.into_parts();
+    let result = state
+        .auth
+        .authenticate_request(authorization(&parts), certificate(&parts, &state.auth))
+        .await;
+    audit(
+        timer,
+        &parts,
+        "authenticated",
+        result.as_ref().ok(),
+        &result,
+    );
+    parts.extensions.insert(AuthenticatedUser { user: result? });
+
+    Ok(next.run(Request::from_parts(parts, body)).await)
+}
+
+/// Middleware that authenticates requests with a server-side session (feature `sessions`)
+///
+/// Resolves the session cookie and passes the stored token on as the
+/// `Authorization` header, so the extractors work unchanged while the
+/// browser only holds the session ID. The resolved [`SessionData`] is
+/// added to the request extensions. Cookies of timed out sessions are
+/// cleared:
+///
+/// ```ignore
+/// let sessions = SessionManager::in_memory();
+/// let app = Router::new()
+///     .route("/api/profile", get(profile))
+///     .layer(axum::middleware::from_fn_with_state(sessions, session_auth))
+///     .with_state(state);
+/// ```
+#[cfg(feature = "sessions")]
+pub async fn session_auth(
+    State(sessions): State<SessionManager>,
+    mut request: Request,
+    next: Next,
+) -> Result<Response, KeyrunesRejection> {
+    let session_id = sessions
+        .session_id(
+            request
+                .headers()
+                .get(header::COOKIE)
+                .and_then(|h| h.to_str().ok()),
+        )
+        .map(str::to_string);
+    let Some(session_id) = session_id else {
+        return Ok(next.run(request).await);
+    };
+
+    match sessions.resolve(&session_id).await? {
+        Some(session) => {
+            if !request.headers().contains_key(header::AUTHORIZATION) {
+                if let Ok(value) = format!("Bearer {}", session.token).parse() {
+                    request.headers_mut().insert(header::AUTHORIZATION, value);
+                }
+            }
+            request.extensions_mut().insert(session);
+            Ok(next.run(request).await)
+        }
+        None => {
+            let mut response = next.run(request).await;
+            if let Ok(value) = sessions.clear_cookie().parse() {
+                response.headers_mut().append(header::SET_COOKIE, value);
+            }
+            Ok(response)
+        }
+    }
+}
+
+/// Middleware that rejects state-changing requests without a valid CSRF token (feature `sessions`)
+///
+/// See [`CsrfProtection::verify`]. When [`session_auth`] runs first, the
+/// token must also match the session's; add this layer before it so it
+/// runs after:
+///
+/// ```ignore
+/// let app = Router::new()
+///     .route("/api/posts", post(create_post))
+///     .layer(axum::middleware::from_fn_with_state(CsrfProtection::new(), csrf_protect))
+///     .layer(axum::middleware::from_fn_with_state(sessions, session_auth))
+///     .with_state(state);
+/// ```
+#[cfg(feature = "sessions")]
+pub async fn csrf_protect(
+    State(csrf): State<CsrfProtection>,
+    request: Request,
+    next: Next,
+) -> Result<Response, KeyrunesRejection> {
+    let headers = request.headers();
+    csrf.verify(
+        request.method().as_str(),
+        headers.get(header::COOKIE).and_then(|h| h.to_str().ok()),
+        headers
+            .get(csrf.header_name())
+            .and_then(|h| h.to_str().ok()),
+        request.extensions().get::<SessionData>(),
+    )?;
+
+    Ok(next.run(request).await)
+}
+
+/// Reports the outcome of a middleware check to the audit sink
+fn audit<T>(
+    timer: AuditTimer,
+    parts: &Parts,
+    requirement: &str,
+    user: Option<&User>,
+    result: &Result<T, RejectionKind>,
+) {
+    let route = parts
+        .extensions
+        .get::<MatchedPath>()
+        .map_or(parts.uri.path(), MatchedPath::as_str);
+    timer.finish(parts.method.as_str(), route, requirement, user, result);
+}
+
+/// Returns the `Authorization` header of the request
+fn authorization(parts: &Parts) -> Option<&str> {
+    parts
+        .headers
+        .get(header::AUTHORIZATION)
+        .and_then(|h| h.to_str().ok())
+}
+
+/// Value of the client certificate header, when certificate binding is set
+fn certificate<'a>(parts: &'a Parts, auth: &AuthService) -> Option<&'a str> {
+    let name = auth.certificate_header()?;
+    parts.headers.get(name).and_then(|h| h.to_str().ok())
+}
+
 /// Custom rejection for Keyrunes errors in Axum
 #[derive(Debug)]
 pub enum KeyrunesRejection {
@@ -154,46 +585,133 @@ pub enum KeyrunesRejection {
     InvalidToken,
     MissingState,
     MissingGroup,
+    StepUpRequired(AuthLevel),
+    ReauthenticationRequired(u64),
+    TooManyRequests(u64),
     AuthError(String),
     Forbidden(String),
     Other(String),
 }
 
+impl KeyrunesRejection {
+    /// Reason for the rejection
+    pub fn kind(&self) -> RejectionKind {
+        match self {
+            KeyrunesRejection::MissingToken => RejectionKind::MissingToken,
+            KeyrunesRejection::InvalidToken => RejectionKind::InvalidToken,
+            KeyrunesRejection::MissingState => {
+                RejectionKind::Internal("Keyrunes state not configured".to_string())
+            }
+            KeyrunesRejection::MissingGroup => RejectionKind::MissingGroup,
+            KeyrunesRejection::StepUpRequired(level) => RejectionKind::StepUpRequired(*level),
+            KeyrunesRejection::ReauthenticationRequired(max_age) => {
+                RejectionKind::ReauthenticationRequired(*max_age)
+            }
+            KeyrunesRejection::TooManyRequests(retry_after) => {
+                RejectionKind::TooManyRequests(*retry_after)
+            }
+            KeyrunesRejection::AuthError(msg) => RejectionKind::Unauthenticated(msg.clone()),
+            KeyrunesRejection::Forbidden(msg) => RejectionKind::Forbidden(msg.clone()),
+            KeyrunesRejection::Other(msg) => RejectionKind::Internal(msg.clone()),
+        }
+    }
+}
+
 impl IntoResponse for KeyrunesRejection {
     fn into_response(self) -> Response {
-        let (status, message) = match self {
-            KeyrunesRejection::MissingToken => (
-                StatusCode::UNAUTHORIZED,
-                "Authentication token missing".to_string(),
-            ),
-            KeyrunesRejection::InvalidToken => (
-                StatusCode::UNAUTHORIZED,
-                "Invalid authentication token".to_string(),
-            ),
-            KeyrunesRejection::MissingState => (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                "Keyrunes state not configured".to_string(),
-            ),
-            KeyrunesRejection::MissingGroup => (
-                StatusCode::BAD_REQUEST,
-                "Missing group_id parameter".to_string(),
-            ),
-            KeyrunesRejection::AuthError(msg) => (StatusCode::UNAUTHORIZED, msg),
-            KeyrunesRejection::Forbidden(msg) => (StatusCode::FORBIDDEN, msg),
-            KeyrunesRejection::Other(msg) => (StatusCode::INTERNAL_SERVER_ERROR, msg),
-        };
-
-        (status, message).into_response()
+        let kind = self.kind();
+        let status =
+            StatusCode::from_u16(kind.status()).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+
+        let mut response =
+            (status, default_message(kind.clone(), &Locale::default())).into_response();
+        if let RejectionKind::TooManyRequests(retry_after) = kind {
+            response
+                .headers_mut()
+                .insert(header::RETRY_AFTER, retry_after.into());
+        }
+        response.extensions_mut().insert(kind);
+        response
     }
 }
 
+/// Middleware that renders Keyrunes rejections with the state's message formatter
+///
+/// The locale is taken from the request's `Accept-Language` header:
+///
+/// ```ignore
+/// let state = KeyrunesState::new(client).with_message_formatter(localized);
+/// let app = Router::new()
+///     .route("/api/profile", get(profile))
+///     .layer(axum::middleware::from_fn_with_state(state.clone(), localize_rejections))
+///     .with_state(state);
+/// ```
+pub async fn localize_rejections(
+    State(state): State<KeyrunesState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let accept_language = request
+        .headers()
+        .get(header::ACCEPT_LANGUAGE)
+        .and_then(|h| h.to_str().ok())
+        .map(str::to_string);
+
+    let mut response = next.run(request).await;
+    if let Some(kind) = response.extensions_mut().remove::<RejectionKind>() {
+        let message = state.auth.message(kind, accept_language.as_deref());
+        response.headers_mut().remove(header::CONTENT_LENGTH);
+        *response.body_mut() = axum::body::Body::from(message);
+    }
+    response
+}
+
+/// Handler for OIDC back-channel logout requests from Keyrunes
+///
+/// Mount it on the back-channel logout URL registered in Keyrunes, with a
+/// [`BackChannelLogout`] in the router state (directly or through
+/// [`FromRef`]). Answers `200` once the user is logged out, and `400` if the
+/// logout token is rejected:
+///
+/// ```ignore
+/// let logout = BackChannelLogout::new(client, "billing-app").with_sessions(sessions);
+/// let app = Router::new()
+///     .route("/auth/backchannel-logout", post(backchannel_logout))
+///     .with_state(logout);
+/// ```
+pub async fn backchannel_logout(
+    State(logout): State<BackChannelLogout>,
+    body: axum::body::Bytes,
+) -> Response {
+    let status = match logout.handle_form(&body).await {
+        Ok(_) => StatusCode::OK,
+        Err(_) => StatusCode::BAD_REQUEST,
+    };
+    (status, [(header::CACHE_CONTROL, "no-store")]).into_response()
+}
+
 impl From<KeyrunesError> for KeyrunesRejection {
     fn from(err: KeyrunesError) -> Self {
-        match err {
-            KeyrunesError::AuthenticationError(msg) => KeyrunesRejection::AuthError(msg),
-            KeyrunesError::AuthorizationError(msg) => KeyrunesRejection::Forbidden(msg),
-            KeyrunesError::InvalidToken => KeyrunesRejection::InvalidToken,
-            _ => KeyrunesRejection::Other(err.to_string()),
+        RejectionKind::from(&err).into()
+    }
+}
+
+impl From<RejectionKind> for KeyrunesRejection {
+    fn from(kind: RejectionKind) -> Self {
+        match kind {
+            RejectionKind::MissingToken => KeyrunesRejection::MissingToken,
+            RejectionKind::InvalidToken => KeyrunesRejection::InvalidToken,
+            RejectionKind::MissingGroup => KeyrunesRejection::MissingGroup,
+            RejectionKind::StepUpRequired(level) => KeyrunesRejection::StepUpRequired(level),
+            RejectionKind::ReauthenticationRequired(max_age) => {
+                KeyrunesRejection::ReauthenticationRequired(max_age)
+            }
+            RejectionKind::TooManyRequests(retry_after) => {
+                KeyrunesRejection::TooManyRequests(retry_after)
+            }
+            RejectionKind::Unauthenticated(msg) => KeyrunesRejection::AuthError(msg),
+            RejectionKind::Forbidden(msg) => KeyrunesRejection::Forbidden(msg),
+            RejectionKind::Internal(msg) => KeyrunesRejection::Other(msg),
         }
     }
 }
@@ -0,0 +1,138 @@
+//! Requests the middlewares let through without a token
+//!
+//! Browsers send CORS preflights (`OPTIONS`) without credentials, and load
+//! balancers probe health endpoints without them either. [`BypassRules`]
+//! lists the methods and public routes the middlewares skip; by default,
+//! `OPTIONS` requests and the usual health endpoints.
+//!
+//! Public routes are [`RoutePattern`]s, so an application can wrap its whole
+//! router in an authentication layer and list the few routes that stay open:
+//!
+//! ```
+//! use keyrunes_rust_sdk::middleware::bypass::{BypassRules, RoutePattern};
+//!
+//! let rules = BypassRules::default()
+//!     .with_route(RoutePattern::exact("/login"))
+//!     .with_route(RoutePattern::prefix("/static/"))
+//!     .with_route(RoutePattern::glob("/docs/*/index.html"));
+//!
+//! assert!(rules.matches("GET", "/static/app.js"));
+//! assert!(rules.matches("GET", "/docs/v2/index.html"));
+//! assert!(!rules.matches("GET", "/api/users"));
+//! ```
+
+/// Paths skipped by default
+const DEFAULT_PATHS: [&str; 4] = ["/health", "/healthz", "/livez", "/readyz"];
+
+/// Paths of public routes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RoutePattern {
+    /// The path itself
+    Exact(String),
+    /// Any path starting with the prefix
+    Prefix(String),
+    /// Paths matching a glob: `*` matches within a segment, and a `**`
+    /// segment matches any number of segments
+    Glob(String),
+}
+
+impl RoutePattern {
+    pub fn exact<S: Into<String>>(path: S) -> Self {
+        RoutePattern::Exact(path.into())
+    }
+
+    pub fn prefix<S: Into<String>>(prefix: S) -> Self {
+        RoutePattern::Prefix(prefix.into())
+    }
+
+    pub fn glob<S: Into<String>>(pattern: S) -> Self {
+        RoutePattern::Glob(pattern.into())
+    }
+
+    /// Whether `path` matches the pattern.
+    pub fn matches(&self, path: &str) -> bool {
+        match self {
+            RoutePattern::Exact(exact) => path == exact,
+            RoutePattern::Prefix(prefix) => path.starts_with(prefix.as_str()),
+            RoutePattern::Glob(pattern) => {
+                let pattern: Vec<&str> = pattern.split('/').collect();
+                let path: Vec<&str> = path.split('/').collect();
+                glob_segments(&pattern, &path)
+            }
+        }
+    }
+}
+
+/// Matches path segments against glob segments
+fn glob_segments(pattern: &[&str], path: &[&str]) -> bool {
+    match pattern.split_first() {
+        None => path.is_empty(),
+        Some((&"**", rest)) => (0..=path.len()).any(|skip| glob_segments(rest, &path[skip..])),
+        Some((segment, rest)) => match path.split_first() {
+            Some((name, path)) => {
+                glob_segment(segment.as_bytes(), name.as_bytes()) && glob_segments(rest, path)
+            }
+            None => false,
+        },
+    }
+}
+
+/// Matches a segment against a glob segment, where `*` matches any characters
+fn glob_segment(pattern: &[u8], name: &[u8]) -> bool {
+    match pattern.split_first() {
+        None => name.is_empty(),
+        Some((b'*', rest)) => (0..=name.len()).any(|skip| glob_segment(rest, &name[skip..])),
+        Some((c, rest)) => name.first() == Some(c) && glob_segment(rest, &name[1..]),
+    }
+}
+
+/// Methods and public routes the middlewares skip
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BypassRules {
+    methods: Vec<String>,
+    routes: Vec<RoutePattern>,
+}
+
+impl BypassRules {
+    /// Rules that skip nothing
+    pub fn none() -> Self {
+        Self {
+            methods: Vec::new(),
+            routes: Vec::new(),
+        }
+    }
+
+    /// Skips requests with `method` (case-insensitive).
+    pub fn with_method<S: Into<String>>(mut self, method: S) -> Self {
+        self.methods.push(method.into().to_ascii_uppercase());
+        self
+    }
+
+    /// Skips requests to exactly `path`.
+    pub fn with_path<S: Into<String>>(self, path: S) -> Self {
+        self.with_route(RoutePattern::exact(path))
+    }
+
+    /// Skips requests to paths matching `pattern`.
+    pub fn with_route(mut self, pattern: RoutePattern) -> Self {
+        self.routes.push(pattern);
+        self
+    }
+
+    /// Whether a request is let through without authentication.
+    pub fn matches(&self, method: &str, path: &str) -> bool {
+        self.methods.iter().any(|m| m.eq_ignore_ascii_case(method))
+            || self.routes.iter().any(|route| route.matches(path))
+    }
+}
+
+impl Default for BypassRules {
+    /// Skips `OPTIONS` requests and `/health`, `/healthz`, `/livez` and `/readyz`.
+    fn default() -> Self {
+        DEFAULT_PATHS
+            .iter()
+            .fold(Self::none().with_method("OPTIONS"), |rules, path| {
+                rules.with_path(*path)
+            })
+    }
+}
@@ -0,0 +1,35 @@
+//! Strategies for checking group membership
+//!
+//! The user resolved from the token (`/api/me`) already lists its groups.
+//! [`GroupCheckStrategy`] decides whether group checks trust that list or
+//! confirm membership with Keyrunes.
+
+use crate::error::Result;
+use crate::{KeyrunesClient, User};
+
+/// How group membership is checked
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GroupCheckStrategy {
+    /// Trust the groups on the user profile; never call Keyrunes
+    TrustProfileGroups,
+    /// Always confirm membership with Keyrunes
+    #[default]
+    AlwaysVerify,
+    /// Trust the profile when it lists the group; otherwise confirm with
+    /// Keyrunes (the profile may omit groups)
+    VerifyIfMissing,
+}
+
+impl GroupCheckStrategy {
+    /// Checks whether `user` belongs to `group`.
+    pub async fn check(self, client: &KeyrunesClient, user: &User, group: &str) -> Result<bool> {
+        let in_profile = user.groups.iter().any(|g| g == group);
+        match self {
+            GroupCheckStrategy::TrustProfileGroups => Ok(in_profile),
+            GroupCheckStrategy::VerifyIfMissing if in_profile => Ok(true),
+            GroupCheckStrategy::AlwaysVerify | GroupCheckStrategy::VerifyIfMissing => {
+                client.has_group(&user.id, group).await
+            }
+        }
+    }
+}
@@ -1,5 +1,15 @@
 //! Middleware for Loco integration (Rails-like framework for Rust)
+//!
+//! The helpers never sign the shared client in as the caller: the user is
+//! resolved, and its requirements checked, on a view of the client carrying
+//! the caller's token ([`KeyrunesClient::as_user`]).
 
+use super::admin_policy::{AdminPolicy, DEFAULT_ADMIN_GROUP};
+use super::group_check::GroupCheckStrategy;
+use super::messages::{MessageFormatter, RejectionKind};
+use crate::auth_service::AuthService;
+use crate::claims::AuthLevel;
+use crate::rate_limit::RateLimiter;
 use crate::{KeyrunesClient, KeyrunesError, User};
 use std::sync::Arc;
 
@@ -7,20 +17,82 @@ use std::sync::Arc;
 #[derive(Clone)]
 pub struct KeyrunesState {
     pub client: Arc<KeyrunesClient>,
+    pub auth: AuthService,
 }
 
 impl KeyrunesState {
     pub fn new(client: KeyrunesClient) -> Self {
+        Self::from_service(AuthService::new(client))
+    }
+
+    /// Creates the state from a configured [`AuthService`].
+    pub fn from_service(auth: AuthService) -> Self {
         Self {
-            client: Arc::new(client),
+            client: auth.client().clone(),
+            auth,
         }
     }
+
+    /// Sets how group requirements are checked (always verified with Keyrunes by default).
+    ///
+    /// Admin checks follow the admin policy; use
+    /// `GroupPolicy::default().with_strategy(strategy)` to apply the strategy to them too.
+    pub fn with_group_check(mut self, strategy: GroupCheckStrategy) -> Self {
+        self.auth = self.auth.with_group_check(strategy);
+        self
+    }
+
+    /// Decides who is an administrator (members of `admins` by default).
+    pub fn with_admin_policy<P: AdminPolicy + 'static>(mut self, policy: P) -> Self {
+        self.auth = self.auth.with_admin_policy(policy);
+        self
+    }
+
+    /// Renders rejection bodies with `formatter` (see [`Self::rejection_message`]).
+    pub fn with_message_formatter(mut self, formatter: MessageFormatter) -> Self {
+        self.auth = self.auth.with_message_formatter(formatter);
+        self
+    }
+
+    /// Renders the body for an error returned by the helpers below, in the
+    /// request's `Accept-Language`; the status is `RejectionKind::from(err).status()`.
+    pub fn rejection_message(
+        &self,
+        err: &KeyrunesError,
+        headers: &impl std::borrow::Borrow<http::HeaderMap>,
+    ) -> String {
+        self.auth.message(
+            RejectionKind::from(err),
+            headers
+                .borrow()
+                .get(http::header::ACCEPT_LANGUAGE)
+                .and_then(|h| h.to_str().ok()),
+        )
+    }
 }
 
 /// Structure representing an authenticated user in Loco
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct AuthenticatedUser {
     pub user: User,
+    /// Token the user was resolved from
+    token: String,
+}
+
+impl AuthenticatedUser {
+    /// Token the user was resolved from
+    pub fn token(&self) -> &str {
+        &self.token
+    }
+}
+
+impl std::fmt::Debug for AuthenticatedUser {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AuthenticatedUser")
+            .field("user", &self.user)
+            .field("token", &"[REDACTED]")
+            .finish()
+    }
 }
 
 /// Helper to extract token from Authorization header
@@ -41,9 +113,11 @@ pub async fn get_user_from_token(
     client: &KeyrunesClient,
     token: &str,
 ) -> Result<AuthenticatedUser, KeyrunesError> {
-    client.set_token(token.to_string()).await;
-    let user = client.get_current_user().await?;
-    Ok(AuthenticatedUser { user })
+    let user = client.as_user(token).get_current_user().await?;
+    Ok(AuthenticatedUser {
+        user,
+        token: token.to_string(),
+    })
 }
 
 /// Helper to verify if the user belongs to a group
@@ -52,20 +126,105 @@ pub async fn require_group(
     user: &AuthenticatedUser,
     group_id: &str,
 ) -> Result<(), KeyrunesError> {
-    let has_group = client.has_group(&user.user.id, group_id).await?;
+    let has_group = client
+        .as_user(user.token())
+        .has_group(&user.user.id, group_id)
+        .await?;
     if !has_group {
-        return Err(KeyrunesError::AuthorizationError(format!(
-            "User does not belong to group: {}",
-            group_id
-        )));
+        return Err(KeyrunesError::AuthorizationError {
+            message: format!("User does not belong to group: {}", group_id),
+            code: None,
+        });
     }
     Ok(())
 }
 
-/// Helper to verify if the user is an administrator
+/// Helper to verify if the user belongs to a group, checked with the state's [`GroupCheckStrategy`]
+pub async fn require_group_with_strategy(
+    state: &KeyrunesState,
+    user: &AuthenticatedUser,
+    group_id: &str,
+) -> Result<(), KeyrunesError> {
+    Ok(state
+        .auth
+        .require_group(&user.user, user.token(), group_id)
+        .await?)
+}
+
+/// Helper to verify if the user is an administrator (member of `admins`)
 pub async fn require_admin(
     client: &KeyrunesClient,
     user: &AuthenticatedUser,
 ) -> Result<(), KeyrunesError> {
-    require_group(client, user, "admins").await
+    require_group(client, user, DEFAULT_ADMIN_GROUP).await
+}
+
+/// Helper to verify if the user is an administrator under the state's [`AdminPolicy`]
+pub async fn require_admin_with_policy(
+    state: &KeyrunesState,
+    user: &AuthenticatedUser,
+    token: &str,
+) -> Result<(), KeyrunesError> {
+    Ok(state.auth.require_admin(&user.user, token).await?)
+}
+
+/// Helper to verify that the user holds an entitlement
+pub async fn require_entitlement(
+    client: &KeyrunesClient,
+    user: &AuthenticatedUser,
+    key: &str,
+) -> Result<(), KeyrunesError> {
+    if !client
+        .as_user(user.token())
+        .has_entitlement(user.user.id.as_str(), key)
+        .await?
+    {
+        return Err(KeyrunesError::AuthorizationError {
+            message: format!("Entitlement required: {}", key),
+            code: None,
+        });
+    }
+    Ok(())
+}
+
+/// Helper to verify that a token reached a minimum authentication level
+///
+/// The token is decoded with the client's [`ClaimsMapping`](crate::claims::ClaimsMapping).
+pub fn require_auth_level(
+    client: &KeyrunesClient,
+    token: &str,
+    level: AuthLevel,
+) -> Result<(), KeyrunesError> {
+    if client.decode_claims(token)?.auth_level() < level {
+        return Err(KeyrunesError::StepUpRequired(level));
+    }
+    Ok(())
+}
+
+/// Helper to verify that a token was issued for a login within `max_age`
+pub fn require_fresh_auth(
+    client: &KeyrunesClient,
+    token: &str,
+    max_age: std::time::Duration,
+) -> Result<(), KeyrunesError> {
+    if !client.decode_claims(token)?.is_fresh(max_age) {
+        return Err(KeyrunesError::ReauthenticationRequired(max_age.as_secs()));
+    }
+    Ok(())
+}
+
+/// Helper to consume one request from the user's rate limit quota
+pub async fn check_rate_limit(
+    limiter: &RateLimiter,
+    user: &AuthenticatedUser,
+) -> Result<(), KeyrunesError> {
+    let decision = limiter.check(&user.user.id).await?;
+    if !decision.allowed {
+        let retry_after = decision
+            .retry_after
+            .map(|d| d.as_secs().max(1))
+            .unwrap_or(1);
+        return Err(KeyrunesError::RateLimitExceeded(retry_after));
+    }
+    Ok(())
 }
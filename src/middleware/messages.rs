@@ -0,0 +1,209 @@
+//! Localizable rejection messages
+//!
+//! The framework integrations reject unauthenticated or unauthorized
+//! requests with English messages by default. Register a
+//! [`MessageFormatter`] on the integration's `KeyrunesState` to render
+//! them in the caller's [`Locale`] instead:
+//!
+//! ```
+//! use keyrunes_rust_sdk::middleware::messages::{default_message, Locale, RejectionKind};
+//!
+//! fn localized(kind: RejectionKind, locale: &Locale) -> String {
+//!     match (locale.language().as_str(), &kind) {
+//!         ("pt", RejectionKind::MissingToken) => "Token de autenticação ausente".to_string(),
+//!         _ => default_message(kind, locale),
+//!     }
+//! }
+//! ```
+
+use crate::claims::AuthLevel;
+use crate::KeyrunesError;
+
+/// Locale used when the request does not send `Accept-Language`
+const DEFAULT_LOCALE: &str = "en";
+
+/// Reason a request was rejected
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RejectionKind {
+    /// No bearer token in the request
+    MissingToken,
+    /// The bearer token is malformed
+    InvalidToken,
+    /// The user could not be authenticated
+    Unauthenticated(String),
+    /// The user is not allowed to access the route
+    Forbidden(String),
+    /// The `group_id` query parameter is missing
+    MissingGroup,
+    /// The session must step up to the given level
+    StepUpRequired(AuthLevel),
+    /// The user must log in again (maximum login age in seconds)
+    ReauthenticationRequired(u64),
+    /// The user is rate limited (seconds until the next allowed request)
+    TooManyRequests(u64),
+    /// The integration is misconfigured or Keyrunes failed
+    Internal(String),
+}
+
+impl RejectionKind {
+    /// HTTP status code of the rejection
+    pub fn status(&self) -> u16 {
+        match self {
+            RejectionKind::MissingToken
+            | RejectionKind::InvalidToken
+            | RejectionKind::Unauthenticated(_)
+            | RejectionKind::StepUpRequired(_)
+            | RejectionKind::ReauthenticationRequired(_) => 401,
+            RejectionKind::Forbidden(_) => 403,
+            RejectionKind::MissingGroup => 400,
+            RejectionKind::TooManyRequests(_) => 429,
+            RejectionKind::Internal(_) => 500,
+        }
+    }
+}
+
+impl From<&KeyrunesError> for RejectionKind {
+    fn from(err: &KeyrunesError) -> Self {
+        match err {
+            KeyrunesError::AuthenticationError { message, .. } => {
+                RejectionKind::Unauthenticated(message.clone())
+            }
+            KeyrunesError::AuthorizationError { message, .. } => {
+                RejectionKind::Forbidden(message.clone())
+            }
+            KeyrunesError::InvalidToken => RejectionKind::InvalidToken,
+            KeyrunesError::RefreshTokenReused | KeyrunesError::InvalidAudience { .. } => {
+                RejectionKind::Unauthenticated(err.to_string())
+            }
+            KeyrunesError::StepUpRequired(level) => RejectionKind::StepUpRequired(*level),
+            KeyrunesError::ReauthenticationRequired(max_age) => {
+                RejectionKind::ReauthenticationRequired(*max_age)
+            }
+            KeyrunesError::RateLimitExceeded(retry_after) => {
+                RejectionKind::TooManyRequests(*retry_after)
+            }
+            KeyrunesError::Api {
+                status: 401,
+                message,
+                ..
+            } => RejectionKind::Unauthenticated(message.clone()),
+            KeyrunesError::Api {
+                status: 403,
+                message,
+                ..
+            } => RejectionKind::Forbidden(message.clone()),
+            _ => RejectionKind::Internal(err.to_string()),
+        }
+    }
+}
+
+impl From<RejectionKind> for KeyrunesError {
+    fn from(kind: RejectionKind) -> Self {
+        match kind {
+            RejectionKind::MissingToken => KeyrunesError::AuthenticationError {
+                message: "Authentication token missing".to_string(),
+                code: None,
+            },
+            RejectionKind::InvalidToken => KeyrunesError::InvalidToken,
+            RejectionKind::Unauthenticated(msg) => KeyrunesError::AuthenticationError {
+                message: msg,
+                code: None,
+            },
+            RejectionKind::Forbidden(msg) => KeyrunesError::AuthorizationError {
+                message: msg,
+                code: None,
+            },
+            RejectionKind::MissingGroup => {
+                KeyrunesError::Other("Missing group_id parameter".to_string())
+            }
+            RejectionKind::StepUpRequired(level) => KeyrunesError::StepUpRequired(level),
+            RejectionKind::ReauthenticationRequired(max_age) => {
+                KeyrunesError::ReauthenticationRequired(max_age)
+            }
+            RejectionKind::TooManyRequests(retry_after) => {
+                KeyrunesError::RateLimitExceeded(retry_after)
+            }
+            RejectionKind::Internal(msg) => KeyrunesError::Other(msg),
+        }
+    }
+}
+
+/// Language preferred by the caller
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Locale(String);
+
+impl Locale {
+    /// Creates a locale from a language tag (e.g., "pt-BR").
+    pub fn new<S: Into<String>>(tag: S) -> Self {
+        Self(tag.into())
+    }
+
+    /// Picks the preferred language of an `Accept-Language` header value.
+    ///
+    /// Falls back to English when the header is missing or holds no
+    /// language (e.g., only `*`).
+    pub fn from_accept_language(header: Option<&str>) -> Self {
+        let mut preferred: Option<(&str, f32)> = None;
+        for entry in header.unwrap_or_default().split(',') {
+            let mut parts = entry.split(';').map(str::trim);
+            let tag = parts.next().unwrap_or_default();
+            if tag.is_empty() || tag == "*" {
+                continue;
+            }
+            let quality = parts
+                .find_map(|p| p.strip_prefix("q="))
+                .and_then(|q| q.parse::<f32>().ok())
+                .unwrap_or(1.0);
+            if quality > 0.0 && preferred.is_none_or(|(_, best)| quality > best) {
+                preferred = Some((tag, quality));
+            }
+        }
+
+        preferred.map(|(tag, _)| Self::new(tag)).unwrap_or_default()
+    }
+
+    /// Full language tag (e.g., "pt-BR")
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Primary language subtag, lowercased (e.g., "pt")
+    pub fn language(&self) -> String {
+        self.0
+            .split(['-', '_'])
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase()
+    }
+}
+
+impl Default for Locale {
+    fn default() -> Self {
+        Self::new(DEFAULT_LOCALE)
+    }
+}
+
+/// Renders the body of a rejection for the caller's locale
+pub type MessageFormatter = fn(RejectionKind, &Locale) -> String;
+
+/// Default (English) rejection messages
+pub fn default_message(kind: RejectionKind, _locale: &Locale) -> String {
+    match kind {
+        RejectionKind::MissingToken => "Authentication token missing".to_string(),
+        RejectionKind::InvalidToken => "Invalid authentication token".to_string(),
+        RejectionKind::MissingGroup => "Missing group_id parameter".to_string(),
+        RejectionKind::StepUpRequired(level) => {
+            format!("Step-up authentication required: {} level needed", level)
+        }
+        RejectionKind::ReauthenticationRequired(max_age) => format!(
+            "Re-authentication required: last login older than {} seconds",
+            max_age
+        ),
+        RejectionKind::TooManyRequests(retry_after) => {
+            format!("Rate limit exceeded: retry after {} seconds", retry_after)
+        }
+        RejectionKind::Unauthenticated(msg)
+        | RejectionKind::Forbidden(msg)
+        | RejectionKind::Internal(msg) => msg,
+    }
+}
@@ -3,10 +3,24 @@
 #[cfg(feature = "axum")]
 pub mod axum;
 
+#[cfg(feature = "axum")]
+pub mod auth_router;
+
+#[cfg(feature = "axum")]
+pub mod scim_router;
+
 #[cfg(feature = "actix")]
 pub mod actix;
 
 #[cfg(feature = "rocket")]
 pub mod rocket;
 
+pub mod admin_policy;
+
+pub mod bypass;
+
+pub mod group_check;
+
 pub mod loco;
+
+pub mod messages;
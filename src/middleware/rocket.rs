@@ -1,24 +1,132 @@
 //! Middleware for Rocket integration
 
+use super::admin_policy::{AdminPolicy, GroupPolicy};
+use super::group_check::GroupCheckStrategy;
+use super::messages::{default_message, Locale, MessageFormatter, RejectionKind};
+use crate::auth_service::AuthService;
+use crate::claims::{AuthLevel, Mfa, RequiredLevel};
+use crate::entitlements::EntitlementKey;
+use crate::groups::GroupName;
+use crate::rate_limit::RateLimiter;
 use crate::{KeyrunesClient, KeyrunesError, User};
 use rocket::{
+    catch, catchers,
+    fairing::{AdHoc, Fairing},
+    http::Status,
     request::{FromRequest, Outcome, Request},
-    State,
+    Catcher, Ignite, Rocket, Sentinel, State,
 };
+use serde::Deserialize;
+use std::marker::PhantomData;
 use std::sync::Arc;
 
 /// Keyrunes client state for use in Rocket
 #[derive(Clone)]
 pub struct KeyrunesState {
     pub client: Arc<KeyrunesClient>,
+    pub auth: AuthService,
 }
 
 impl KeyrunesState {
     pub fn new(client: KeyrunesClient) -> Self {
+        Self::from_service(AuthService::new(client))
+    }
+
+    /// Creates the state from a configured [`AuthService`].
+    pub fn from_service(auth: AuthService) -> Self {
         Self {
-            client: Arc::new(client),
+            client: auth.client().clone(),
+            auth,
         }
     }
+
+    /// Sets how group requirements are checked (always verified with Keyrunes by default).
+    ///
+    /// Admin checks follow the admin policy; use
+    /// `GroupPolicy::default().with_strategy(strategy)` to apply the strategy to them too.
+    pub fn with_group_check(mut self, strategy: GroupCheckStrategy) -> Self {
+        self.auth = self.auth.with_group_check(strategy);
+        self
+    }
+
+    /// Decides who is an administrator (members of `admins` by default).
+    pub fn with_admin_policy<P: AdminPolicy + 'static>(mut self, policy: P) -> Self {
+        self.auth = self.auth.with_admin_policy(policy);
+        self
+    }
+
+    /// Renders rejection bodies with `formatter` (see [`catchers`]).
+    pub fn with_message_formatter(mut self, formatter: MessageFormatter) -> Self {
+        self.auth = self.auth.with_message_formatter(formatter);
+        self
+    }
+}
+
+/// Keyrunes settings read from Rocket's configuration (the `keyrunes` table)
+///
+/// ```toml
+/// [default.keyrunes]
+/// url = "https://keyrunes.example.com"
+/// admin_group = "staff"
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct KeyrunesConfig {
+    /// Base URL of the Keyrunes API
+    pub url: String,
+    /// Group whose members are administrators (default `admins`)
+    #[serde(default)]
+    pub admin_group: Option<String>,
+    /// Preferred locale of server messages
+    #[serde(default)]
+    pub locale: Option<String>,
+}
+
+impl KeyrunesConfig {
+    /// Creates the state described by this configuration.
+    pub fn into_state(self) -> crate::Result<KeyrunesState> {
+        let mut builder = KeyrunesClient::builder(self.url);
+        if let Some(locale) = self.locale {
+            builder = builder.locale(locale);
+        }
+
+        let mut state = KeyrunesState::new(builder.build()?);
+        if let Some(group) = self.admin_group {
+            state = state.with_admin_policy(GroupPolicy::new(group));
+        }
+        Ok(state)
+    }
+}
+
+/// Fairing that manages a [`KeyrunesState`] built from [`KeyrunesConfig`]
+///
+/// Launch fails when the `keyrunes` configuration is missing or invalid:
+///
+/// ```ignore
+/// rocket::build().attach(keyrunes_rust_sdk::middleware::rocket::fairing())
+/// ```
+pub fn fairing() -> impl Fairing {
+    AdHoc::try_on_ignite("Keyrunes", |rocket| async move {
+        let state = match rocket.figment().extract_inner::<KeyrunesConfig>("keyrunes") {
+            Ok(config) => config.into_state(),
+            Err(e) => Err(KeyrunesError::Other(format!(
+                "Invalid keyrunes config: {}",
+                e
+            ))),
+        };
+
+        match state {
+            Ok(state) => Ok(rocket.manage(state)),
+            Err(e) => {
+                rocket::error!("Keyrunes setup failed: {}", e);
+                Err(rocket)
+            }
+        }
+    })
+}
+
+/// Whether the [`KeyrunesState`] used by the guards is missing
+fn missing_state(rocket: &Rocket<Ignite>) -> bool {
+    rocket.state::<KeyrunesState>().is_none()
 }
 
 /// Guard that gets the current authenticated user
@@ -32,104 +140,69 @@ impl<'r> FromRequest<'r> for AuthenticatedUser {
     type Error = KeyrunesError;
 
     async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
-        let auth_header = match request.headers().get_one("authorization") {
-            Some(header) => header,
-            None => {
-                return Outcome::Error((
-                    rocket::http::Status::Unauthorized,
-                    KeyrunesError::AuthenticationError("Token missing".to_string()),
-                ))
-            }
+        let token = match AuthService::bearer_token(request.headers().get_one("authorization")) {
+            Ok(token) => token,
+            Err(kind) => return reject(request, kind),
         };
 
-        let token = match auth_header.strip_prefix("Bearer ") {
-            Some(t) => t,
-            None => {
-                return Outcome::Error((
-                    rocket::http::Status::Unauthorized,
-                    KeyrunesError::AuthenticationError("Invalid token format".to_string()),
-                ))
-            }
+        let state = match keyrunes_state(request).await {
+            Ok(state) => state,
+            Err(kind) => return reject(request, kind),
         };
 
-        let state = match request.guard::<&State<KeyrunesState>>().await {
-            Outcome::Success(s) => s,
-            _ => {
-                return Outcome::Error((
-                    rocket::http::Status::InternalServerError,
-                    KeyrunesError::Other("Keyrunes state not configured".to_string()),
-                ))
-            }
-        };
-
-        state.client.set_token(token.to_string()).await;
-        match state.client.get_current_user().await {
+        match state.auth.authenticate(token).await {
             Ok(user) => Outcome::Success(AuthenticatedUser { user }),
-            Err(e) => Outcome::Error((rocket::http::Status::Unauthorized, e)),
+            Err(kind) => reject(request, kind),
         }
     }
 }
 
 /// Guard that verifies if the user belongs to a specific group
+///
+/// The group is read from the `group_id` query parameter. With a group enum
+/// declared by [`keyrunes_groups!`](crate::keyrunes_groups) as `G` (e.g.,
+/// `RequireGroup<AppGroup>`), groups outside the set are rejected.
 #[derive(Debug, Clone)]
-pub struct RequireGroup {
+pub struct RequireGroup<G: GroupName = String> {
     pub user: User,
-    pub group_id: String,
+    pub group_id: G,
 }
 
 #[rocket::async_trait]
-impl<'r> FromRequest<'r> for RequireGroup {
+impl<'r, G: GroupName + Send> FromRequest<'r> for RequireGroup<G> {
     type Error = KeyrunesError;
 
     async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
-        let authenticated_user = match AuthenticatedUser::from_request(request).await {
-            Outcome::Success(user) => user,
-            Outcome::Error(err) => return Outcome::Error(err),
-            Outcome::Forward(_) => {
-                return Outcome::Error((
-                    rocket::http::Status::Unauthorized,
-                    KeyrunesError::AuthenticationError("Not authenticated".to_string()),
-                ))
-            }
+        let (user, state) = match authenticated(request).await {
+            Ok(authenticated) => authenticated,
+            Err(kind) => return reject(request, kind),
         };
 
         let group_id = match request.query_value::<String>("group_id") {
-            Some(Ok(gid)) => gid,
-            _ => {
-                return Outcome::Error((
-                    rocket::http::Status::BadRequest,
-                    KeyrunesError::Other("Missing group_id parameter in query string".to_string()),
-                ))
-            }
+            Some(Ok(name)) => match G::from_name(&name) {
+                Some(group_id) => group_id,
+                None => {
+                    return reject(
+                        request,
+                        RejectionKind::Forbidden(format!("Unknown group: {}", name)),
+                    )
+                }
+            },
+            _ => return reject(request, RejectionKind::MissingGroup),
         };
 
-        let state = match request.guard::<&State<KeyrunesState>>().await {
-            rocket::request::Outcome::Success(s) => s,
-            _ => {
-                return Outcome::Error((
-                    rocket::http::Status::InternalServerError,
-                    KeyrunesError::Other("Keyrunes state not configured".to_string()),
-                ))
-            }
+        let token = match AuthService::bearer_token(request.headers().get_one("authorization")) {
+            Ok(token) => token,
+            Err(kind) => return reject(request, kind),
         };
 
         match state
-            .client
-            .has_group(&authenticated_user.user.id, &group_id)
+            .auth
+            .require_group(&user, token, group_id.as_name())
             .await
         {
-            Ok(true) => Outcome::Success(RequireGroup {
-                user: authenticated_user.user,
-                group_id,
-            }),
-            Ok(false) => Outcome::Error((
-                rocket::http::Status::Forbidden,
-                KeyrunesError::AuthorizationError(format!(
-                    "User does not belong to group: {}",
-                    group_id
-                )),
-            )),
-            Err(e) => Outcome::Error((rocket::http::Status::Unauthorized, e)),
+            Ok(()) => Outcome::Success(RequireGroup { user, group_id }),
+            Err(kind) => reject(request, kind),
         }
     }
 }
@@ -145,42 +218,282 @@ impl<'r> FromRequest<'r> for RequireAdmin {
     type Error = KeyrunesError;
 
     async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
-        let authenticated_user = match AuthenticatedUser::from_request(request).await {
-            Outcome::Success(user) => user,
-            Outcome::Error(err) => return Outcome::Error(err),
-            Outcome::Forward(_) => {
-                return Outcome::Error((
-                    rocket::http::Status::Unauthorized,
-                    KeyrunesError::AuthenticationError("Not authenticated".to_string()),
-                ))
-            }
+        let (user, state) = match authenticated(request).await {
+            Ok(authenticated) => authenticated,
+            Err(kind) => return reject(request, kind),
+        };
+
+        let token = match AuthService::bearer_token(request.headers().get_one("authorization")) {
+            Ok(token) => token,
+            Err(kind) => return reject(request, kind),
+        };
+
+        match state.auth.require_admin(&user, token).await {
+            Ok(()) => Outcome::Success(RequireAdmin { user }),
+            Err(kind) => reject(request, kind),
+        }
+    }
+}
+
+/// Guard that verifies the session reached a minimum authentication level
+#[derive(Debug, Clone)]
+pub struct RequireAuthLevel<L: RequiredLevel = Mfa> {
+    pub user: User,
+    pub level: AuthLevel,
+    _level: PhantomData<L>,
+}
+
+#[rocket::async_trait]
+impl<'r, L: RequiredLevel> FromRequest<'r> for RequireAuthLevel<L> {
+    type Error = KeyrunesError;
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let (user, state) = match authenticated(request).await {
+            Ok(authenticated) => authenticated,
+            Err(kind) => return reject(request, kind),
+        };
+
+        match AuthService::bearer_token(request.headers().get_one("authorization"))
+            .and_then(|token| state.auth.require_auth_level(token, L::LEVEL))
+        {
+            Ok(level) => Outcome::Success(RequireAuthLevel {
+                user,
+                level,
+                _level: PhantomData,
+            }),
+            Err(kind) => reject(request, kind),
+        }
+    }
+}
+
+/// Guard that verifies the user authenticated within the last `MINUTES` minutes
+#[derive(Debug, Clone)]
+pub struct RequireFreshAuth<const MINUTES: u64 = 5> {
+    pub user: User,
+}
+
+#[rocket::async_trait]
+impl<'r, const MINUTES: u64> FromRequest<'r> for RequireFreshAuth<MINUTES> {
+    type Error = KeyrunesError;
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let (user, state) = match authenticated(request).await {
+            Ok(authenticated) => authenticated,
+            Err(kind) => return reject(request, kind),
         };
 
-        let state = match request.guard::<&State<KeyrunesState>>().await {
-            rocket::request::Outcome::Success(s) => s,
+        let max_age = std::time::Duration::from_secs(MINUTES * 60);
+        match AuthService::bearer_token(request.headers().get_one("authorization"))
+            .and_then(|token| state.auth.require_fresh_auth(token, max_age))
+        {
+            Ok(()) => Outcome::Success(RequireFreshAuth { user }),
+            Err(kind) => reject(request, kind),
+        }
+    }
+}
+
+/// Guard that verifies the user holds the entitlement `E`
+#[derive(Debug, Clone)]
+pub struct RequireEntitlement<E: EntitlementKey> {
+    pub user: User,
+    _entitlement: PhantomData<E>,
+}
+
+#[rocket::async_trait]
+impl<'r, E: EntitlementKey> FromRequest<'r> for RequireEntitlement<E> {
+    type Error = KeyrunesError;
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let (user, state) = match authenticated(request).await {
+            Ok(authenticated) => authenticated,
+            Err(kind) => return reject(request, kind),
+        };
+
+        let token = match AuthService::bearer_token(request.headers().get_one("authorization")) {
+            Ok(token) => token,
+            Err(kind) => return reject(request, kind),
+        };
+
+        match state.auth.require_entitlement(&user, token, E::KEY).await {
+            Ok(()) => Outcome::Success(RequireEntitlement {
+                user,
+                _entitlement: PhantomData,
+            }),
+            Err(kind) => reject(request, kind),
+        }
+    }
+}
+
+/// Guard that rate-limits requests by authenticated user ID
+///
+/// Requires a [`RateLimiter`] to be managed by Rocket (`rocket.manage(limiter)`).
+#[derive(Debug, Clone)]
+pub struct RateLimited {
+    pub user: User,
+    pub remaining: u32,
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for RateLimited {
+    type Error = KeyrunesError;
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let (user, _) = match authenticated(request).await {
+            Ok(authenticated) => authenticated,
+            Err(kind) => return reject(request, kind),
+        };
+
+        let limiter = match request.guard::<&State<RateLimiter>>().await {
+            Outcome::Success(l) => l,
             _ => {
-                return Outcome::Error((
-                    rocket::http::Status::InternalServerError,
-                    KeyrunesError::Other("Keyrunes state not configured".to_string()),
-                ))
+                return reject(
+                    request,
+                    RejectionKind::Internal("Rate limiter not configured".to_string()),
+                )
             }
         };
 
-        match state
-            .client
-            .has_group(&authenticated_user.user.id, "admins")
-            .await
-        {
-            Ok(true) => Outcome::Success(RequireAdmin {
-                user: authenticated_user.user,
+        match AuthService::check_rate_limit(limiter, &user).await {
+            Ok(decision) => Outcome::Success(RateLimited {
+                user,
+                remaining: decision.remaining,
             }),
-            Ok(false) => Outcome::Error((
-                rocket::http::Status::Forbidden,
-                KeyrunesError::AuthorizationError(
-                    "Access denied: administrator privileges required".to_string(),
-                ),
-            )),
-            Err(e) => Outcome::Error((rocket::http::Status::Unauthorized, e)),
+            Err(kind) => reject(request, kind),
         }
     }
 }
+
+/// Returns the managed [`KeyrunesState`]
+async fn keyrunes_state<'r>(request: &'r Request<'_>) -> Result<&'r KeyrunesState, RejectionKind> {
+    match request.guard::<&State<KeyrunesState>>().await {
+        Outcome::Success(state) => Ok(state.inner()),
+        _ => Err(RejectionKind::Internal(
+            "Keyrunes state not configured".to_string(),
+        )),
+    }
+}
+
+/// Runs the [`AuthenticatedUser`] guard, returning the user and the state
+async fn authenticated<'r>(
+    request: &'r Request<'_>,
+) -> Result<(User, &'r KeyrunesState), RejectionKind> {
+    let user = match AuthenticatedUser::from_request(request).await {
+        Outcome::Success(authenticated) => authenticated.user,
+        Outcome::Error((_, err)) => return Err(RejectionKind::from(&err)),
+        Outcome::Forward(_) => {
+            return Err(RejectionKind::Unauthenticated(
+                "Not authenticated".to_string(),
+            ))
+        }
+    };
+    Ok((user, keyrunes_state(request).await?))
+}
+
+/// Rejection recorded by a failing guard, read back by [`catchers`]
+struct RecordedRejection(Option<RejectionKind>);
+
+/// Records why a guard failed; the first recorded rejection wins
+fn record_rejection(request: &Request<'_>, kind: RejectionKind) {
+    request.local_cache(|| RecordedRejection(Some(kind)));
+}
+
+/// Fails a guard, recording the rejection for [`catchers`]
+fn reject<T>(request: &Request<'_>, kind: RejectionKind) -> Outcome<T, KeyrunesError> {
+    let status = Status::from_code(kind.status()).unwrap_or(Status::InternalServerError);
+    record_rejection(request, kind.clone());
+    Outcome::Error((status, kind.into()))
+}
+
+/// Catchers rendering guard rejections with the state's message formatter
+///
+/// The locale is taken from the request's `Accept-Language` header:
+///
+/// ```ignore
+/// rocket::build()
+///     .manage(KeyrunesState::new(client).with_message_formatter(localized))
+///     .register("/", keyrunes_rust_sdk::middleware::rocket::catchers())
+/// ```
+pub fn catchers() -> Vec<Catcher> {
+    catchers![bad_request, unauthorized, forbidden, too_many_requests]
+}
+
+#[catch(400)]
+fn bad_request(request: &Request<'_>) -> String {
+    rejection_message(request, Status::BadRequest)
+}
+
+#[catch(401)]
+fn unauthorized(request: &Request<'_>) -> String {
+    rejection_message(request, Status::Unauthorized)
+}
+
+#[catch(403)]
+fn forbidden(request: &Request<'_>) -> String {
+    rejection_message(request, Status::Forbidden)
+}
+
+#[catch(429)]
+fn too_many_requests(request: &Request<'_>) -> String {
+    rejection_message(request, Status::TooManyRequests)
+}
+
+/// Renders the recorded rejection, or a generic one for `status`
+fn rejection_message(request: &Request<'_>, status: Status) -> String {
+    let kind = match &request.local_cache(|| RecordedRejection(None)).0 {
+        Some(kind) => kind.clone(),
+        None if status == Status::Forbidden => RejectionKind::Forbidden(status.to_string()),
+        None if status == Status::Unauthorized => {
+            RejectionKind::Unauthenticated(status.to_string())
+        }
+        None => RejectionKind::Internal(status.to_string()),
+    };
+    let accept_language = request.headers().get_one("accept-language");
+    match request.rocket().state::<KeyrunesState>() {
+        Some(state) => state.auth.message(kind, accept_language),
+        None => default_message(kind, &Locale::from_accept_language(accept_language)),
+    }
+}
+
+// Sentinels: abort launch when a route uses a guard without its state
+
+impl Sentinel for AuthenticatedUser {
+    fn abort(rocket: &Rocket<Ignite>) -> bool {
+        missing_state(rocket)
+    }
+}
+
+impl<G: GroupName> Sentinel for RequireGroup<G> {
+    fn abort(rocket: &Rocket<Ignite>) -> bool {
+        missing_state(rocket)
+    }
+}
+
+impl Sentinel for RequireAdmin {
+    fn abort(rocket: &Rocket<Ignite>) -> bool {
+        missing_state(rocket)
+    }
+}
+
+impl<L: RequiredLevel> Sentinel for RequireAuthLevel<L> {
+    fn abort(rocket: &Rocket<Ignite>) -> bool {
+        missing_state(rocket)
+    }
+}
+
+impl<const MINUTES: u64> Sentinel for RequireFreshAuth<MINUTES> {
+    fn abort(rocket: &Rocket<Ignite>) -> bool {
+        missing_state(rocket)
+    }
+}
+
+impl<E: EntitlementKey> Sentinel for RequireEntitlement<E> {
+    fn abort(rocket: &Rocket<Ignite>) -> bool {
+        missing_state(rocket)
+    }
+}
+
+impl Sentinel for RateLimited {
+    fn abort(rocket: &Rocket<Ignite>) -> bool {
+        missing_state(rocket) || rocket.state::<RateLimiter>().is_none()
+    }
+}
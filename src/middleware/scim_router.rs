@@ -0,0 +1,384 @@
+//! SCIM 2.0 service provider routes for Axum
+//!
+//! [`scim_router`] exposes a SCIM surface (RFC 7644) that identity
+//! providers such as Okta or Microsoft Entra ID provision users and groups
+//! into. Each request is forwarded to Keyrunes through the client's
+//! [`ScimClient`](crate::scim::ScimClient), so an application proxying to
+//! Keyrunes can be registered in the IdP as the SCIM app:
+//!
+//! | Route                          | Description                            |
+//! |--------------------------------|----------------------------------------|
+//! | `GET /ServiceProviderConfig`   | Supported SCIM features                |
+//! | `GET /Users`                   | Lists users (filtered, paginated)      |
+//! | `POST /Users`                  | Creates a user                         |
+//! | `GET /Users/:id`               | Reads a user                           |
+//! | `PUT /Users/:id`               | Replaces a user                        |
+//! | `PATCH /Users/:id`             | Patches a user                         |
+//! | `DELETE /Users/:id`            | Deletes a user                         |
+//! | `/Groups`, `/Groups/:id`       | Same, for groups                       |
+//!
+//! The IdP authenticates with a bearer token configured on both sides; the
+//! client's own token (allowed to provision the tenant) is used towards
+//! Keyrunes. Errors are answered with SCIM error bodies.
+//!
+//! ```ignore
+//! let scim = ScimServer::new(client).with_bearer_token(std::env::var("SCIM_TOKEN")?);
+//! let app = Router::new().nest("/scim/v2", scim_router(scim));
+//! ```
+
+use crate::compare::constant_time_eq;
+use crate::scim::{ScimFilter, ScimGroup, ScimPatch, ScimQuery, ScimUser};
+use crate::{KeyrunesClient, KeyrunesError};
+use axum::{
+    extract::{Path, Query, State},
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+
+/// Content type of SCIM responses
+const SCIM_CONTENT_TYPE: &str = "application/scim+json";
+
+/// Schema of SCIM error bodies
+const ERROR_SCHEMA: &str = "urn:ietf:params:scim:api:messages:2.0:Error";
+
+/// Largest page returned by the list routes
+const MAX_RESULTS: u64 = 200;
+
+/// State of the SCIM routes
+///
+/// Requests are rejected until a bearer token is set with
+/// [`ScimServer::with_bearer_token`].
+#[derive(Clone)]
+pub struct ScimServer {
+    client: KeyrunesClient,
+    bearer_tokens: Vec<String>,
+}
+
+impl ScimServer {
+    /// Forwards provisioning requests to Keyrunes with `client`.
+    pub fn new(client: KeyrunesClient) -> Self {
+        Self {
+            client,
+            bearer_tokens: Vec::new(),
+        }
+    }
+
+    /// Accepts requests authenticated with `token` (may be called several
+    /// times, e.g., while rotating the token configured in the IdP).
+    pub fn with_bearer_token<S: Into<String>>(mut self, token: S) -> Self {
+        self.bearer_tokens.push(token.into());
+        self
+    }
+
+    /// Checks the `Authorization` header of a request.
+    fn authorize(&self, headers: &HeaderMap) -> Result<(), ScimError> {
+        let token = headers
+            .get(header::AUTHORIZATION)
+            .and_then(|h| h.to_str().ok())
+            .and_then(|h| h.strip_prefix("Bearer "));
+        let authorized = token.is_some_and(|token| {
+            self.bearer_tokens
+                .iter()
+                .any(|expected| constant_time_eq(expected, token))
+        });
+        if authorized {
+            Ok(())
+        } else {
+            Err(ScimError::new(
+                StatusCode::UNAUTHORIZED,
+                None,
+                "Invalid SCIM bearer token",
+            ))
+        }
+    }
+}
+
+impl std::fmt::Debug for ScimServer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ScimServer")
+            .field("bearer_tokens", &self.bearer_tokens.len())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Router with the SCIM routes, to nest under the SCIM base path (e.g., `/scim/v2`)
+pub fn scim_router<S>(server: ScimServer) -> Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    Router::new()
+        .route("/ServiceProviderConfig", get(service_provider_config))
+        .route("/Users", get(list_users).post(create_user))
+        .route(
+            "/Users/:id",
+            get(get_user)
+                .put(replace_user)
+                .patch(patch_user)
+                .delete(delete_user),
+        )
+        .route("/Groups", get(list_groups).post(create_group))
+        .route(
+            "/Groups/:id",
+            get(get_group)
+                .put(replace_group)
+                .patch(patch_group)
+                .delete(delete_group),
+        )
+        .with_state(server)
+}
+
+/// Query parameters of the list routes
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ListParams {
+    filter: Option<String>,
+    start_index: Option<u64>,
+    count: Option<u64>,
+}
+
+impl ListParams {
+    fn query(self) -> ScimQuery {
+        let mut query = ScimQuery::new().count(self.count.unwrap_or(MAX_RESULTS).min(MAX_RESULTS));
+        if let Some(filter) = self.filter {
+            query = query.filter(ScimFilter::raw(filter));
+        }
+        if let Some(start_index) = self.start_index {
+            query = query.start_index(start_index.max(1));
+        }
+        query
+    }
+}
+
+async fn service_provider_config(
+    State(server): State<ScimServer>,
+    headers: HeaderMap,
+) -> Result<Response, ScimError> {
+    server.authorize(&headers)?;
+    let config = serde_json::json!({
+        "schemas": ["urn:ietf:params:scim:schemas:core:2.0:ServiceProviderConfig"],
+        "patch": {"supported": true},
+        "bulk": {"supported": false, "maxOperations": 0, "maxPayloadSize": 0},
+        "filter": {"supported": true, "maxResults": MAX_RESULTS},
+        "changePassword": {"supported": false},
+        "sort": {"supported": false},
+        "etag": {"supported": false},
+        "authenticationSchemes": [{
+            "type": "oauthbearertoken",
+            "name": "OAuth Bearer Token",
+            "description": "Authentication with a bearer token",
+        }],
+    });
+    Ok(scim_response(StatusCode::OK, &config))
+}
+
+async fn list_users(
+    State(server): State<ScimServer>,
+    headers: HeaderMap,
+    Query(params): Query<ListParams>,
+) -> Result<Response, ScimError> {
+    server.authorize(&headers)?;
+    let page = server.client.scim().list_users(&params.query()).await?;
+    Ok(list_response(page))
+}
+
+async fn create_user(
+    State(server): State<ScimServer>,
+    headers: HeaderMap,
+    Json(user): Json<ScimUser>,
+) -> Result<Response, ScimError> {
+    server.authorize(&headers)?;
+    let user = server.client.scim().create_user(&user).await?;
+    Ok(scim_response(StatusCode::CREATED, &user))
+}
+
+async fn get_user(
+    State(server): State<ScimServer>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+) -> Result<Response, ScimError> {
+    server.authorize(&headers)?;
+    let user = server.client.scim().get_user(&id).await?;
+    Ok(scim_response(StatusCode::OK, &user))
+}
+
+async fn replace_user(
+    State(server): State<ScimServer>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+    Json(user): Json<ScimUser>,
+) -> Result<Response, ScimError> {
+    server.authorize(&headers)?;
+    let user = server.client.scim().replace_user(&id, &user).await?;
+    Ok(scim_response(StatusCode::OK, &user))
+}
+
+async fn patch_user(
+    State(server): State<ScimServer>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+    Json(patch): Json<ScimPatch>,
+) -> Result<Response, ScimError> {
+    server.authorize(&headers)?;
+    let user = server.client.scim().patch_user(&id, &patch).await?;
+    Ok(scim_response(StatusCode::OK, &user))
+}
+
+async fn delete_user(
+    State(server): State<ScimServer>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+) -> Result<StatusCode, ScimError> {
+    server.authorize(&headers)?;
+    server.client.scim().delete_user(&id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn list_groups(
+    State(server): State<ScimServer>,
+    headers: HeaderMap,
+    Query(params): Query<ListParams>,
+) -> Result<Response, ScimError> {
+    server.authorize(&headers)?;
+    let page = server.client.scim().list_groups(&params.query()).await?;
+    Ok(list_response(page))
+}
+
+async fn create_group(
+    State(server): State<ScimServer>,
+    headers: HeaderMap,
+    Json(group): Json<ScimGroup>,
+) -> Result<Response, ScimError> {
+    server.authorize(&headers)?;
+    let group = server.client.scim().create_group(&group).await?;
+    Ok(scim_response(StatusCode::CREATED, &group))
+}
+
+async fn get_group(
+    State(server): State<ScimServer>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+) -> Result<Response, ScimError> {
+    server.authorize(&headers)?;
+    let group = server.client.scim().get_group(&id).await?;
+    Ok(scim_response(StatusCode::OK, &group))
+}
+
+async fn replace_group(
+    State(server): State<ScimServer>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+    Json(group): Json<ScimGroup>,
+) -> Result<Response, ScimError> {
+    server.authorize(&headers)?;
+    let group = server.client.scim().replace_group(&id, &group).await?;
+    Ok(scim_response(StatusCode::OK, &group))
+}
+
+async fn patch_group(
+    State(server): State<ScimServer>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+    Json(patch): Json<ScimPatch>,
+) -> Result<Response, ScimError> {
+    server.authorize(&headers)?;
+    let group = server.client.scim().patch_group(&id, &patch).await?;
+    Ok(scim_response(StatusCode::OK, &group))
+}
+
+async fn delete_group(
+    State(server): State<ScimServer>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+) -> Result<StatusCode, ScimError> {
+    server.authorize(&headers)?;
+    server.client.scim().delete_group(&id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Answers a list route, with the SCIM list envelope
+fn list_response<T: Serialize>(page: crate::scim::ScimListResponse<T>) -> Response {
+    let mut body = serde_json::to_value(&page).unwrap_or_default();
+    body["schemas"] = serde_json::json!(["urn:ietf:params:scim:api:messages:2.0:ListResponse"]);
+    scim_response(StatusCode::OK, &body)
+}
+
+fn scim_response<T: Serialize>(status: StatusCode, body: &T) -> Response {
+    let mut response = (status, Json(body)).into_response();
+    response.headers_mut().insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static(SCIM_CONTENT_TYPE),
+    );
+    response
+}
+
+/// Failure of a SCIM route, answered with a SCIM error body
+#[derive(Debug)]
+pub struct ScimError {
+    status: StatusCode,
+    scim_type: Option<String>,
+    detail: String,
+}
+
+impl ScimError {
+    fn new(status: StatusCode, scim_type: Option<String>, detail: &str) -> Self {
+        Self {
+            status,
+            scim_type,
+            detail: detail.to_string(),
+        }
+    }
+}
+
+impl From<KeyrunesError> for ScimError {
+    fn from(err: KeyrunesError) -> Self {
+        if let KeyrunesError::Api {
+            status,
+            code,
+            message,
+        } = &err
+        {
+            let status = StatusCode::from_u16(*status).unwrap_or(StatusCode::BAD_GATEWAY);
+            return Self::new(status, Some(code.clone()), message);
+        }
+
+        let status = match &err {
+            KeyrunesError::UserNotFoundError { .. } | KeyrunesError::GroupNotFoundError { .. } => {
+                StatusCode::NOT_FOUND
+            }
+            KeyrunesError::Other(msg) if msg.starts_with("Resource not found") => {
+                StatusCode::NOT_FOUND
+            }
+            KeyrunesError::RateLimitExceeded(_) => StatusCode::TOO_MANY_REQUESTS,
+            // Keyrunes rejected the client's token: a misconfiguration of the
+            // application, not of the IdP
+            KeyrunesError::AuthenticationError { .. }
+            | KeyrunesError::AuthorizationError { .. } => StatusCode::BAD_GATEWAY,
+            // Other error statuses of Keyrunes ("HTTP 409: ...") are passed on
+            KeyrunesError::HttpError(msg) => msg
+                .strip_prefix("HTTP ")
+                .and_then(|rest| rest.get(..3))
+                .and_then(|code| code.parse().ok())
+                .and_then(|code| StatusCode::from_u16(code).ok())
+                .unwrap_or(StatusCode::BAD_GATEWAY),
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        Self::new(status, None, &err.to_string())
+    }
+}
+
+impl IntoResponse for ScimError {
+    fn into_response(self) -> Response {
+        let mut body = serde_json::json!({
+            "schemas": [ERROR_SCHEMA],
+            "status": self.status.as_u16().to_string(),
+            "detail": self.detail,
+        });
+        if let Some(scim_type) = self.scim_type {
+            body["scimType"] = scim_type.into();
+        }
+        scim_response(self.status, &body)
+    }
+}
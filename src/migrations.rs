@@ -0,0 +1,505 @@
+//! Migration from other identity providers
+//!
+//! A [`Migration`] reads the users and groups of an Auth0 bulk user export
+//! or of a Keycloak realm export, and imports them into Keyrunes with one
+//! bulk import job. Password hashes found in the export (Auth0 password
+//! hash exports, Keycloak credentials) are imported along with the users,
+//! where the server supports it, so users keep their passwords.
+//!
+//! Records that cannot be read are skipped and listed in the report
+//! rather than failing the whole migration. A dry run validates the import
+//! on the server without writing anything.
+//!
+//! ## Quick Start
+//!
+//! ```
+//! use keyrunes_rust_sdk::migrations::{ImportOptions, Migration};
+//! use keyrunes_rust_sdk::KeyrunesClient;
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = KeyrunesClient::new("https://keyrunes.example.com")?;
+//! client.login("admin@example.com", "password123", None).await?;
+//! let migration = Migration::from_keycloak_realm(&std::fs::read("realm-export.json")?)?;
+//!
+//! let report = migration.import(&client, &ImportOptions::new().dry_run()).await?;
+//! for error in &report.errors {
+//!     eprintln!("{}", error);
+//! }
+//! if report.errors.is_empty() {
+//!     migration.import(&client, &ImportOptions::new()).await?;
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::client::transport::{Auth, RequestBody};
+use crate::client::KeyrunesClient;
+use crate::endpoints;
+use crate::error::{KeyrunesError, Result};
+use crate::models::ImportSummary;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeSet;
+use std::time::Duration;
+
+/// Identity provider an export comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MigrationSource {
+    /// Auth0 bulk user export (NDJSON or JSON array)
+    Auth0,
+    /// Keycloak realm export (JSON)
+    Keycloak,
+}
+
+/// Password hash carried over from the previous identity provider
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PasswordHash {
+    /// Hash algorithm (e.g., "bcrypt", "pbkdf2-sha256")
+    pub algorithm: String,
+    /// Hash, in the encoding of the source (modular crypt format for
+    /// bcrypt, base64 for PBKDF2)
+    pub hash: String,
+    /// Salt, base64 encoded, when kept apart from the hash
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub salt: Option<String>,
+    /// Iteration count, for iterated hashes
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub iterations: Option<u32>,
+}
+
+/// User read from an export
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MigrationUser {
+    /// ID of the user in the previous identity provider
+    pub external_id: String,
+    /// Username (the email address when the source has none)
+    pub username: String,
+    /// Email address
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub email: Option<String>,
+    /// Whether the email address was verified
+    #[serde(default)]
+    pub email_verified: bool,
+    /// Given name
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub given_name: Option<String>,
+    /// Family name
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub family_name: Option<String>,
+    /// Whether the user may sign in
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    /// Groups of the user
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub groups: Vec<String>,
+    /// Password hash, when the export has one
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub password_hash: Option<PasswordHash>,
+    /// Other attributes (Auth0 `app_metadata`/`user_metadata`, Keycloak attributes)
+    #[serde(skip_serializing_if = "serde_json::Map::is_empty", default)]
+    pub metadata: serde_json::Map<String, Value>,
+}
+
+/// Record of an export that could not be read or imported
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MigrationError {
+    /// Record concerned (e.g., "line 12", a user ID), when known
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub record: Option<String>,
+    /// What went wrong
+    pub message: String,
+}
+
+impl MigrationError {
+    fn new(record: Option<String>, message: impl Into<String>) -> Self {
+        Self {
+            record,
+            message: message.into(),
+        }
+    }
+}
+
+impl std::fmt::Display for MigrationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.record {
+            Some(record) => write!(f, "{}: {}", record, self.message),
+            None => f.write_str(&self.message),
+        }
+    }
+}
+
+/// Outcome of an import
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MigrationReport {
+    /// Whether the import was a dry run (nothing written)
+    pub dry_run: bool,
+    /// Users created (or that would be, in a dry run)
+    pub created: u64,
+    /// Existing users updated
+    pub updated: u64,
+    /// Users with a password hash
+    pub with_password: usize,
+    /// Records skipped while reading the export, then records rejected by Keyrunes
+    pub errors: Vec<MigrationError>,
+}
+
+/// Options of [`Migration::import`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportOptions {
+    dry_run: bool,
+    poll_interval: Duration,
+    timeout: Duration,
+}
+
+impl ImportOptions {
+    /// Imports for real, waiting up to 10 minutes for the import job.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Validates the import without writing anything.
+    pub fn dry_run(mut self) -> Self {
+        self.dry_run = true;
+        self
+    }
+
+    /// Sets how often the import job is polled (default: 2 seconds).
+    pub fn poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    /// Sets how long to wait for the import job (default: 10 minutes).
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+}
+
+impl Default for ImportOptions {
+    fn default() -> Self {
+        Self {
+            dry_run: false,
+            poll_interval: Duration::from_secs(2),
+            timeout: Duration::from_secs(600),
+        }
+    }
+}
+
+/// Users and groups read from an export, ready to import
+#[derive(Debug, Clone, PartialEq)]
+pub struct Migration {
+    source: MigrationSource,
+    users: Vec<MigrationUser>,
+    groups: Vec<String>,
+    errors: Vec<MigrationError>,
+}
+
+impl Migration {
+    /// Reads an Auth0 bulk user export.
+    ///
+    /// Accepts the NDJSON files of export jobs (one user per line) and JSON
+    /// arrays. Lines of an Auth0 password hash export (`_id.$oid`,
+    /// `passwordHash`) are read too, with their bcrypt hashes. Unreadable
+    /// lines are skipped and listed in [`Self::errors`].
+    pub fn from_auth0_export(data: &[u8]) -> Self {
+        let text = String::from_utf8_lossy(data);
+        let records: Vec<(String, std::result::Result<Value, String>)> =
+            if text.trim_start().starts_with('[') {
+                match serde_json::from_str::<Vec<Value>>(&text) {
+                    Ok(values) => values
+                        .into_iter()
+                        .enumerate()
+                        .map(|(i, value)| (format!("record {}", i + 1), Ok(value)))
+                        .collect(),
+                    Err(e) => vec![("file".to_string(), Err(e.to_string()))],
+                }
+            } else {
+                text.lines()
+                    .enumerate()
+                    .filter(|(_, line)| !line.trim().is_empty())
+                    .map(|(i, line)| {
+                        let value = serde_json::from_str(line).map_err(|e| e.to_string());
+                        (format!("line {}", i + 1), value)
+                    })
+                    .collect()
+            };
+
+        let mut migration = Self::new(MigrationSource::Auth0);
+        for (record, value) in records {
+            match value.and_then(|value| auth0_user(&value)) {
+                Ok(user) => migration.users.push(user),
+                Err(message) => migration
+                    .errors
+                    .push(MigrationError::new(Some(record), message)),
+            }
+        }
+        migration
+    }
+
+    /// Reads a Keycloak realm export (`kc.sh export` or the admin console's
+    /// partial export).
+    ///
+    /// Groups are named after their path (`/engineering/backend` becomes
+    /// `engineering/backend`). Users without a username and an email are
+    /// skipped and listed in [`Self::errors`].
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<Migration, KeyrunesError>`:
+    /// - `Ok(migration)` with the users and groups of the realm
+    /// - `Err(KeyrunesError::SerializationError)` if the file is not a realm export
+    pub fn from_keycloak_realm(data: &[u8]) -> Result<Self> {
+        let realm: Value = serde_json::from_slice(data)?;
+        if !realm.is_object() {
+            return Err(KeyrunesError::SerializationError(
+                "Keycloak realm export must be a JSON object".to_string(),
+            ));
+        }
+
+        let mut migration = Self::new(MigrationSource::Keycloak);
+        let mut groups = BTreeSet::new();
+        collect_keycloak_groups(realm.get("groups"), &mut groups);
+        for (i, user) in array(realm.get("users")).iter().enumerate() {
+            match keycloak_user(user) {
+                Ok(user) => {
+                    groups.extend(user.groups.iter().cloned());
+                    migration.users.push(user);
+                }
+                Err(message) => {
+                    let record = str_field(user, "id").unwrap_or_else(|| format!("user {}", i + 1));
+                    migration
+                        .errors
+                        .push(MigrationError::new(Some(record), message));
+                }
+            }
+        }
+        migration.groups = groups.into_iter().collect();
+        Ok(migration)
+    }
+
+    fn new(source: MigrationSource) -> Self {
+        Self {
+            source,
+            users: Vec::new(),
+            groups: Vec::new(),
+            errors: Vec::new(),
+        }
+    }
+
+    /// Identity provider the export comes from
+    pub fn source(&self) -> MigrationSource {
+        self.source
+    }
+
+    /// Users to import
+    pub fn users(&self) -> &[MigrationUser] {
+        &self.users
+    }
+
+    /// Groups to create
+    pub fn groups(&self) -> &[String] {
+        &self.groups
+    }
+
+    /// Records skipped while reading the export
+    pub fn errors(&self) -> &[MigrationError] {
+        &self.errors
+    }
+
+    /// Imports the users and groups with a bulk import job, and waits for it.
+    ///
+    /// Groups are created first, then users, with their group memberships
+    /// and password hashes. Users that already exist (same username) are
+    /// updated.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<MigrationReport, KeyrunesError>`:
+    /// - `Ok(report)` with the counts and the skipped or rejected records
+    /// - `Err(KeyrunesError::AuthorizationError)` if not allowed to import users
+    /// - `Err(KeyrunesError::JobFailed)` if the import job failed
+    /// - `Err(KeyrunesError::JobTimedOut)` if the job is still running after the timeout
+    pub async fn import(
+        &self,
+        client: &KeyrunesClient,
+        options: &ImportOptions,
+    ) -> Result<MigrationReport> {
+        let body = serde_json::json!({
+            "source": self.source,
+            "dry_run": options.dry_run,
+            "groups": self.groups,
+            "users": self.users,
+        });
+        let response = client
+            .send_request(
+                reqwest::Method::POST,
+                endpoints::IMPORTS_BULK,
+                RequestBody::Json(body),
+                Auth::Required,
+                None,
+            )
+            .await?;
+        let summary: ImportSummary = client
+            .job_from_response(response)
+            .await?
+            .await_completion(options.poll_interval, options.timeout)
+            .await?;
+
+        let mut errors = self.errors.clone();
+        errors.extend(
+            summary
+                .errors
+                .into_iter()
+                .map(|message| MigrationError::new(None, message)),
+        );
+        Ok(MigrationReport {
+            dry_run: options.dry_run,
+            created: summary.created,
+            updated: summary.updated,
+            with_password: self
+                .users
+                .iter()
+                .filter(|user| user.password_hash.is_some())
+                .count(),
+            errors,
+        })
+    }
+}
+
+/// Reads a user of an Auth0 export.
+fn auth0_user(value: &Value) -> std::result::Result<MigrationUser, String> {
+    if !value.is_object() {
+        return Err("expected a JSON object".to_string());
+    }
+    let email = str_field(value, "email");
+    let external_id = str_field(value, "user_id")
+        .or_else(|| {
+            value
+                .pointer("/_id/$oid")
+                .and_then(Value::as_str)
+                .map(str::to_string)
+        })
+        .ok_or("missing user_id")?;
+    let username = str_field(value, "username")
+        .or_else(|| email.clone())
+        .ok_or("missing username and email")?;
+
+    let mut metadata = serde_json::Map::new();
+    for key in ["app_metadata", "user_metadata"] {
+        if let Some(data) = value.get(key).filter(|data| data.is_object()) {
+            metadata.insert(key.to_string(), data.clone());
+        }
+    }
+
+    Ok(MigrationUser {
+        external_id,
+        username,
+        email,
+        email_verified: value["email_verified"].as_bool().unwrap_or(false),
+        given_name: str_field(value, "given_name"),
+        family_name: str_field(value, "family_name"),
+        enabled: !value["blocked"].as_bool().unwrap_or(false),
+        groups: Vec::new(),
+        password_hash: str_field(value, "passwordHash").map(|hash| PasswordHash {
+            algorithm: "bcrypt".to_string(),
+            hash,
+            salt: None,
+            iterations: None,
+        }),
+        metadata,
+    })
+}
+
+/// Reads a user of a Keycloak realm export.
+fn keycloak_user(value: &Value) -> std::result::Result<MigrationUser, String> {
+    let email = str_field(value, "email");
+    let username = str_field(value, "username")
+        .or_else(|| email.clone())
+        .ok_or("missing username and email")?;
+    let external_id = str_field(value, "id").unwrap_or_else(|| username.clone());
+
+    let password_hash = array(value.get("credentials"))
+        .iter()
+        .filter(|credential| credential["type"] == "password")
+        .find_map(keycloak_password);
+    let mut metadata = serde_json::Map::new();
+    if let Some(attributes) = value.get("attributes").filter(|data| data.is_object()) {
+        metadata.insert("attributes".to_string(), attributes.clone());
+    }
+
+    Ok(MigrationUser {
+        external_id,
+        username,
+        email,
+        email_verified: value["emailVerified"].as_bool().unwrap_or(false),
+        given_name: str_field(value, "firstName"),
+        family_name: str_field(value, "lastName"),
+        enabled: value["enabled"].as_bool().unwrap_or(true),
+        groups: array(value.get("groups"))
+            .iter()
+            .filter_map(Value::as_str)
+            .map(|path| path.trim_start_matches('/').to_string())
+            .collect(),
+        password_hash,
+        metadata,
+    })
+}
+
+/// Reads a Keycloak password credential, in the current format
+/// (`secretData`/`credentialData` JSON strings) or the legacy one.
+fn keycloak_password(credential: &Value) -> Option<PasswordHash> {
+    let parse = |field: &str| -> Value {
+        credential[field]
+            .as_str()
+            .and_then(|data| serde_json::from_str(data).ok())
+            .unwrap_or_default()
+    };
+    let secret = parse("secretData");
+    let data = parse("credentialData");
+
+    let hash =
+        str_field(&secret, "value").or_else(|| str_field(credential, "hashedSaltedValue"))?;
+    let algorithm = str_field(&data, "algorithm")
+        .or_else(|| str_field(credential, "algorithm"))
+        .unwrap_or_else(|| "pbkdf2-sha256".to_string());
+    let iterations = data["hashIterations"]
+        .as_u64()
+        .or_else(|| credential["hashIterations"].as_u64())
+        .and_then(|iterations| u32::try_from(iterations).ok());
+
+    Some(PasswordHash {
+        algorithm,
+        hash,
+        salt: str_field(&secret, "salt").or_else(|| str_field(credential, "salt")),
+        iterations,
+    })
+}
+
+/// Adds the paths of Keycloak groups and their subgroups to `groups`.
+fn collect_keycloak_groups(value: Option<&Value>, groups: &mut BTreeSet<String>) {
+    for group in array(value) {
+        let path = str_field(group, "path").or_else(|| str_field(group, "name"));
+        if let Some(path) = path {
+            groups.insert(path.trim_start_matches('/').to_string());
+        }
+        collect_keycloak_groups(group.get("subGroups"), groups);
+    }
+}
+
+fn array(value: Option<&Value>) -> &[Value] {
+    value.and_then(Value::as_array).map_or(&[], Vec::as_slice)
+}
+
+fn str_field(value: &Value, key: &str) -> Option<String> {
+    value
+        .get(key)
+        .and_then(Value::as_str)
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+}
+
+fn default_enabled() -> bool {
+    true
+}
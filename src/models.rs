@@ -24,15 +24,25 @@ use serde::{Deserialize, Serialize};
 /// User model
 ///
 /// Represents a user in the Keyrunes system.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 #[serde(from = "UserResponse")]
 pub struct User {
-    /// Unique user ID
+    /// Unique user ID, as a string whatever its format on the server
     pub id: String,
+    /// Numeric user ID, when the server identifies users by integer (`user_id`)
+    #[serde(rename = "user_id", skip_serializing_if = "Option::is_none")]
+    pub numeric_id: Option<u64>,
+    /// User ID, when the server identifies users by UUID (`external_id`)
+    #[cfg(feature = "uuid")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub external_id: Option<uuid::Uuid>,
     /// Username
     pub username: String,
     /// User email
     pub email: String,
+    /// Whether the email was verified (`None` if the server does not report it)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email_verified: Option<bool>,
     /// List of groups the user belongs to
     #[serde(default)]
     pub groups: Vec<String>,
@@ -49,13 +59,15 @@ pub struct User {
 pub(crate) struct UserResponse {
     #[serde(default, rename = "id")]
     id_str: Option<String>,
-    #[serde(default, rename = "user_id")]
+    #[serde(default, rename = "user_id", deserialize_with = "int_or_string")]
     user_id_num: Option<u64>,
     #[serde(default, rename = "external_id")]
     external_id_str: Option<String>,
     username: String,
     email: String,
     #[serde(default)]
+    email_verified: Option<bool>,
+    #[serde(default)]
     groups: Vec<String>,
     #[serde(default)]
     created_at: Option<DateTime<Utc>>,
@@ -65,6 +77,11 @@ pub(crate) struct UserResponse {
 
 impl From<UserResponse> for User {
     fn from(response: UserResponse) -> Self {
+        #[cfg(feature = "uuid")]
+        let external_id = response
+            .external_id_str
+            .as_deref()
+            .and_then(|s| uuid::Uuid::parse_str(s).ok());
         let id = response
             .id_str
             .or(response.external_id_str)
@@ -76,8 +93,12 @@ impl From<UserResponse> for User {
             });
         User {
             id,
+            numeric_id: response.user_id_num,
+            #[cfg(feature = "uuid")]
+            external_id,
             username: response.username,
             email: response.email,
+            email_verified: response.email_verified,
             groups: response.groups,
             created_at: response.created_at,
             updated_at: response.updated_at,
@@ -85,6 +106,181 @@ impl From<UserResponse> for User {
     }
 }
 
+/// Deserializes an optional integer sent either as a number or as a string
+/// holding one (e.g., `"user_id": "123"`), as servers differ between
+/// versions
+fn int_or_string<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: TryFrom<u64> + TryFrom<i64> + std::str::FromStr,
+{
+    use serde::de::{Error, Unexpected, Visitor};
+
+    struct IntOrString<T>(std::marker::PhantomData<T>);
+
+    impl<'de, T> Visitor<'de> for IntOrString<T>
+    where
+        T: TryFrom<u64> + TryFrom<i64> + std::str::FromStr,
+    {
+        type Value = Option<T>;
+
+        fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            f.write_str("an integer or a string holding one")
+        }
+
+        fn visit_u64<E: Error>(self, value: u64) -> Result<Self::Value, E> {
+            T::try_from(value)
+                .map(Some)
+                .map_err(|_| E::invalid_value(Unexpected::Unsigned(value), &self))
+        }
+
+        fn visit_i64<E: Error>(self, value: i64) -> Result<Self::Value, E> {
+            T::try_from(value)
+                .map(Some)
+                .map_err(|_| E::invalid_value(Unexpected::Signed(value), &self))
+        }
+
+        fn visit_str<E: Error>(self, value: &str) -> Result<Self::Value, E> {
+            if crate::client::strict::is_strict() {
+                return Err(E::invalid_type(Unexpected::Str(value), &"an integer"));
+            }
+            let trimmed = value.trim();
+            if trimmed.is_empty() {
+                return Ok(None);
+            }
+            trimmed
+                .parse()
+                .map(Some)
+                .map_err(|_| E::invalid_value(Unexpected::Str(value), &self))
+        }
+
+        fn visit_none<E: Error>(self) -> Result<Self::Value, E> {
+            Ok(None)
+        }
+
+        fn visit_unit<E: Error>(self) -> Result<Self::Value, E> {
+            Ok(None)
+        }
+
+        fn visit_some<D: serde::Deserializer<'de>>(
+            self,
+            deserializer: D,
+        ) -> Result<Self::Value, D::Error> {
+            deserializer.deserialize_any(self)
+        }
+    }
+
+    deserializer.deserialize_any(IntOrString(std::marker::PhantomData))
+}
+
+/// Standard OIDC claims returned by the userinfo endpoint
+///
+/// Claims not listed here (including custom ones, such as groups) are kept
+/// in `extra`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct UserInfoClaims {
+    /// Subject (user ID)
+    pub sub: String,
+    /// Full name
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Given name
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub given_name: Option<String>,
+    /// Family name
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub family_name: Option<String>,
+    /// Name the user prefers to be referred to by
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub preferred_username: Option<String>,
+    /// Email
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+    /// Whether the email was verified
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub email_verified: Option<bool>,
+    /// URL of the profile picture
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub picture: Option<String>,
+    /// Locale (e.g., `en-US`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub locale: Option<String>,
+    /// Time zone (e.g., `Europe/Paris`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub zoneinfo: Option<String>,
+    /// Last update of the profile (Unix timestamp)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<i64>,
+    /// Other claims
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+
+/// Response of the OIDC userinfo endpoint
+///
+/// Returned by [`KeyrunesClient::userinfo`](crate::KeyrunesClient::userinfo).
+#[derive(Debug, Clone)]
+pub struct UserInfo {
+    /// The user, built from the claims
+    pub user: User,
+    /// Standard claims, as returned
+    pub claims: UserInfoClaims,
+}
+
+impl UserInfo {
+    /// Builds the user from a userinfo response, reading the user ID, email
+    /// and groups from the claims named by `mapping`.
+    pub(crate) fn from_payload(
+        payload: serde_json::Value,
+        mapping: &crate::claims::ClaimsMapping,
+    ) -> crate::error::Result<Self> {
+        let user = User::from_claims(&payload, mapping)?;
+        let claims: UserInfoClaims = serde_json::from_value(payload)?;
+        Ok(Self { user, claims })
+    }
+}
+
+impl User {
+    /// Builds a user from OIDC claims (a token payload or a userinfo
+    /// response), reading the user ID, email and groups from the claims
+    /// named by `mapping`.
+    ///
+    /// The username is `preferred_username`, falling back to the email,
+    /// then the user ID.
+    pub(crate) fn from_claims(
+        payload: &serde_json::Value,
+        mapping: &crate::claims::ClaimsMapping,
+    ) -> crate::error::Result<Self> {
+        let claims = crate::claims::Claims::from_payload(payload, mapping)?;
+        let id = claims
+            .user_id
+            .or(claims.sub)
+            .ok_or(crate::error::KeyrunesError::InvalidToken)?;
+        let username = payload
+            .get("preferred_username")
+            .and_then(|v| v.as_str())
+            .map(str::to_string)
+            .or_else(|| claims.email.clone())
+            .unwrap_or_else(|| id.clone());
+
+        Ok(User {
+            numeric_id: id.parse().ok(),
+            #[cfg(feature = "uuid")]
+            external_id: uuid::Uuid::parse_str(&id).ok(),
+            id,
+            username,
+            email: claims.email.unwrap_or_default(),
+            email_verified: payload.get("email_verified").and_then(|v| v.as_bool()),
+            groups: claims.groups,
+            created_at: None,
+            updated_at: payload
+                .get("updated_at")
+                .and_then(|v| v.as_i64())
+                .and_then(|ts| DateTime::from_timestamp(ts, 0)),
+        })
+    }
+}
+
 /// Registration response wrapper
 #[derive(Debug, Clone, Deserialize)]
 pub struct RegisterResponse {
@@ -96,6 +292,32 @@ pub struct RegisterResponse {
     pub requires_password_change: Option<bool>,
 }
 
+/// Outcome of a registration
+///
+/// The client that sent the registration is not signed in as the new user;
+/// when the password must be changed first, do it on a view carrying the
+/// registration token:
+/// `client.as_user(token).change_password(current, new)`.
+#[derive(Debug, Clone)]
+pub struct Registration {
+    /// Registered user
+    pub user: User,
+    /// Token issued for the new account, if any
+    pub token: Option<String>,
+    /// Whether the server demands a password change before the account can be used
+    pub requires_password_change: bool,
+}
+
+impl From<RegisterResponse> for Registration {
+    fn from(response: RegisterResponse) -> Self {
+        Self {
+            user: User::from(response.user),
+            token: response.token,
+            requires_password_change: response.requires_password_change.unwrap_or(false),
+        }
+    }
+}
+
 /// Group model
 ///
 /// Represents a group in the Keyrunes system.
@@ -118,7 +340,7 @@ pub struct Group {
 /// Represents a JWT token returned after successful authentication.
 /// Accepts both legacy format (access_token) and current format (token).
 #[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(from = "TokenResponse")]
+#[serde(try_from = "TokenResponse")]
 pub struct Token {
     /// JWT token
     pub token: String,
@@ -134,65 +356,123 @@ pub struct Token {
     /// Token expiration date (optional)
     #[serde(skip_serializing_if = "Option::is_none", default)]
     pub expires_at: Option<DateTime<Utc>>,
+    /// OIDC ID token, when Keyrunes issued one (unverified; see
+    /// [`KeyrunesClient::verify_id_token`](crate::KeyrunesClient::verify_id_token))
+    ///
+    /// It identifies the user to the application and must not be sent to
+    /// APIs in place of `token`.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub id_token: Option<String>,
 }
 
 #[derive(Deserialize)]
-#[serde(untagged)]
-enum TokenResponse {
-    NewFormat {
-        token: String,
-        #[serde(default)]
-        token_type: Option<String>,
-        #[serde(default)]
-        expires_in: Option<i64>,
-        #[serde(default)]
-        refresh_token: Option<String>,
-        #[serde(default)]
-        expires_at: Option<DateTime<Utc>>,
-    },
-    LegacyFormat {
-        access_token: String,
-        #[serde(default)]
-        token_type: Option<String>,
-        #[serde(default)]
-        expires_in: Option<i64>,
-        #[serde(default)]
-        refresh_token: Option<String>,
-    },
-}
-
-impl From<TokenResponse> for Token {
-    fn from(response: TokenResponse) -> Self {
-        match response {
-            TokenResponse::NewFormat {
-                token,
-                token_type,
-                expires_in,
-                refresh_token,
-                expires_at,
-            } => Token {
-                token,
-                token_type,
-                expires_in,
-                refresh_token,
-                expires_at,
-            },
-            TokenResponse::LegacyFormat {
-                access_token,
-                token_type,
-                expires_in,
-                refresh_token,
-            } => Token {
-                token: access_token,
-                token_type,
-                expires_in,
-                refresh_token,
-                expires_at: None,
-            },
-        }
+struct TokenResponse {
+    #[serde(default)]
+    token: Option<String>,
+    /// Legacy name of `token`
+    #[serde(default)]
+    access_token: Option<String>,
+    #[serde(default)]
+    token_type: Option<String>,
+    #[serde(default, deserialize_with = "int_or_string")]
+    expires_in: Option<i64>,
+    #[serde(default)]
+    refresh_token: Option<String>,
+    #[serde(default)]
+    expires_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    id_token: Option<String>,
+}
+
+impl TryFrom<TokenResponse> for Token {
+    type Error = String;
+
+    fn try_from(response: TokenResponse) -> Result<Self, Self::Error> {
+        let (token, expires_at) = match (response.token, response.access_token) {
+            (Some(token), _) => (token, response.expires_at),
+            (None, Some(_)) if crate::client::strict::is_strict() => {
+                return Err("legacy `access_token` field instead of `token`".to_string())
+            }
+            // The legacy format has no expiration date
+            (None, Some(access_token)) => (access_token, None),
+            (None, None) => return Err("missing field `token`".to_string()),
+        };
+        Ok(Token {
+            token,
+            token_type: response.token_type,
+            expires_in: response.expires_in,
+            refresh_token: response.refresh_token,
+            expires_at,
+            id_token: response.id_token,
+        })
+    }
+}
+
+impl Token {
+    /// Decodes the claims carried by this token (signature not verified).
+    pub fn claims(&self) -> crate::error::Result<crate::claims::Claims> {
+        crate::claims::Claims::from_token(&self.token)
+    }
+}
+
+/// Introspection of the token held by a client
+///
+/// `expires_at` and `subject` are read from the claims of the token; both
+/// are `None` for opaque (non-JWT) tokens.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenInfo {
+    /// The token itself
+    pub token: String,
+    /// When the token expires (`exp` claim)
+    pub expires_at: Option<DateTime<Utc>>,
+    /// User the token was issued to (user ID claim of the claims mapping)
+    pub subject: Option<String>,
+}
+
+impl TokenInfo {
+    /// Time left until the token expires (zero once expired, `None` if unknown).
+    pub fn expires_in(&self) -> Option<std::time::Duration> {
+        self.expires_at.map(|expires_at| {
+            (expires_at - Utc::now())
+                .to_std()
+                .unwrap_or(std::time::Duration::ZERO)
+        })
+    }
+
+    /// Whether the token is known to have expired.
+    pub fn is_expired(&self) -> bool {
+        self.expires_at
+            .is_some_and(|expires_at| expires_at <= Utc::now())
     }
 }
 
+/// Session of a client, exported with `KeyrunesClient::export_session`
+///
+/// Versioned so that sessions saved by older SDK versions can still be
+/// restored after an upgrade.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClientSession {
+    /// Format version of the export
+    pub version: u32,
+    /// Access token
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub token: Option<String>,
+    /// Refresh token
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub refresh_token: Option<String>,
+    /// Namespace the user logged in to
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub namespace: Option<String>,
+    /// Last fetched profile of the user
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub user: Option<User>,
+}
+
+impl ClientSession {
+    /// Current format version
+    pub const VERSION: u32 = 1;
+}
+
 /// User registration data
 ///
 /// Used to register a new user in the system.
@@ -241,6 +521,124 @@ pub struct LoginCredentials {
     pub namespace: String,
 }
 
+/// Password change request
+///
+/// Used to change the password of the current user.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChangePasswordRequest {
+    /// Current password
+    pub current_password: String,
+    /// New password (minimum 8 characters)
+    pub new_password: String,
+}
+
+impl ChangePasswordRequest {
+    /// Creates a password change request.
+    pub fn new<C: Into<String>, N: Into<String>>(current_password: C, new_password: N) -> Self {
+        Self {
+            current_password: current_password.into(),
+            new_password: new_password.into(),
+        }
+    }
+}
+
+/// Password reset request (forgot password)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PasswordResetRequest {
+    /// Email of the account to recover
+    pub email: String,
+    /// Namespace (default: "public")
+    #[serde(default = "default_namespace")]
+    pub namespace: String,
+}
+
+/// Password reset confirmation, with the token sent by email
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PasswordResetConfirmation {
+    /// Reset token received by email
+    pub reset_token: String,
+    /// New password (minimum 8 characters)
+    pub new_password: String,
+}
+
+/// Context of the end user's client, forwarded to Keyrunes for risk scoring
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClientContext {
+    /// IP address of the end user
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub ip: Option<String>,
+    /// User agent of the end user's browser or app
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub user_agent: Option<String>,
+    /// Device fingerprint computed by the application
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub device_fingerprint: Option<String>,
+}
+
+impl ClientContext {
+    /// Creates an empty context.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the IP address.
+    pub fn with_ip<S: Into<String>>(mut self, ip: S) -> Self {
+        self.ip = Some(ip.into());
+        self
+    }
+
+    /// Sets the user agent.
+    pub fn with_user_agent<S: Into<String>>(mut self, user_agent: S) -> Self {
+        self.user_agent = Some(user_agent.into());
+        self
+    }
+
+    /// Sets the device fingerprint.
+    pub fn with_device_fingerprint<S: Into<String>>(mut self, fingerprint: S) -> Self {
+        self.device_fingerprint = Some(fingerprint.into());
+        self
+    }
+}
+
+/// Login request body sent to the API
+#[derive(Serialize)]
+pub(crate) struct LoginRequest<'a> {
+    #[serde(flatten)]
+    pub(crate) credentials: &'a LoginCredentials,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) context: Option<&'a ClientContext>,
+}
+
+/// Login response from API (token or verification challenge)
+#[derive(Deserialize)]
+#[serde(untagged)]
+pub(crate) enum LoginResponse {
+    Token(Token),
+    Challenge(LoginChallenge),
+}
+
+/// Extra verification demanded by the server during login
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoginChallenge {
+    /// Challenge ID, to be sent back with the verification code
+    pub challenge_id: String,
+    /// Verification methods accepted for this challenge (e.g., "totp")
+    #[serde(default)]
+    pub methods: Vec<String>,
+    /// Reason given by the risk engine (optional)
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub reason: Option<String>,
+}
+
+/// Result of a login with client context
+#[derive(Debug, Clone)]
+pub enum LoginOutcome {
+    /// Login succeeded and a token was issued
+    Authenticated(Token),
+    /// The server demands extra verification before issuing a token
+    ChallengeRequired(LoginChallenge),
+}
+
 /// Default namespace value ("public")
 pub const DEFAULT_NAMESPACE: &str = "public";
 
@@ -248,6 +646,10 @@ fn default_namespace() -> String {
     DEFAULT_NAMESPACE.to_string()
 }
 
+fn default_true() -> bool {
+    true
+}
+
 /// Group verification result
 ///
 /// Represents the result of a group membership verification.
@@ -270,3 +672,1318 @@ pub struct GroupVerificationResponse {
     /// Indicates whether the user belongs to the group
     pub has_group: bool,
 }
+
+/// Step-up challenge response
+///
+/// Used to upgrade the authentication level of an existing session.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StepUpChallenge {
+    /// Challenge ID issued by the server (optional)
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub challenge_id: Option<String>,
+    /// Verification method (e.g., "totp", "webauthn")
+    pub method: String,
+    /// Verification code
+    pub code: String,
+}
+
+impl StepUpChallenge {
+    /// Creates a challenge response for the given method and code.
+    pub fn new<M: Into<String>, C: Into<String>>(method: M, code: C) -> Self {
+        Self {
+            challenge_id: None,
+            method: method.into(),
+            code: code.into(),
+        }
+    }
+
+    /// Sets the server-issued challenge ID.
+    pub fn with_challenge_id<S: Into<String>>(mut self, challenge_id: S) -> Self {
+        self.challenge_id = Some(challenge_id.into());
+        self
+    }
+}
+
+/// Standard security event kinds
+///
+/// Kinds not covered by the SDK can be sent with [`SecurityEventKind::Other`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SecurityEventKind {
+    /// Logins from locations too far apart for the elapsed time
+    ImpossibleTravel,
+    /// Many identities tried from the same source
+    CredentialStuffing,
+    /// Many failed attempts against the same identity
+    BruteForce,
+    /// Signs that an account was taken over (e.g., sudden credential changes)
+    AccountTakeover,
+    /// Request from a known malicious or anonymizing IP address
+    SuspiciousIp,
+    /// A token was used from an unexpected context
+    TokenMisuse,
+    /// Custom event kind
+    #[serde(untagged)]
+    Other(String),
+}
+
+/// Security event reported to the Keyrunes risk engine
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SecurityEvent {
+    /// Event kind
+    pub kind: SecurityEventKind,
+    /// User the event relates to (optional)
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub user_id: Option<String>,
+    /// Source IP address (optional)
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub ip: Option<String>,
+    /// Additional event data
+    #[serde(skip_serializing_if = "serde_json::Map::is_empty", default)]
+    pub metadata: serde_json::Map<String, serde_json::Value>,
+    /// When the event occurred (defaults to the time of reception)
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub occurred_at: Option<DateTime<Utc>>,
+}
+
+impl SecurityEvent {
+    /// Creates an event of the given kind.
+    pub fn new(kind: SecurityEventKind) -> Self {
+        Self {
+            kind,
+            user_id: None,
+            ip: None,
+            metadata: serde_json::Map::new(),
+            occurred_at: None,
+        }
+    }
+
+    /// Sets the user the event relates to.
+    pub fn with_user_id<S: Into<String>>(mut self, user_id: S) -> Self {
+        self.user_id = Some(user_id.into());
+        self
+    }
+
+    /// Sets the source IP address.
+    pub fn with_ip<S: Into<String>>(mut self, ip: S) -> Self {
+        self.ip = Some(ip.into());
+        self
+    }
+
+    /// Adds a metadata entry.
+    pub fn with_metadata<K: Into<String>, V: Into<serde_json::Value>>(
+        mut self,
+        key: K,
+        value: V,
+    ) -> Self {
+        self.metadata.insert(key.into(), value.into());
+        self
+    }
+
+    /// Sets when the event occurred.
+    pub fn with_occurred_at(mut self, occurred_at: DateTime<Utc>) -> Self {
+        self.occurred_at = Some(occurred_at);
+        self
+    }
+}
+
+/// Device model
+///
+/// Represents a device a user has signed in from.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Device {
+    /// Unique device ID
+    pub id: String,
+    /// Display name (e.g., "Chrome on macOS")
+    #[serde(default)]
+    pub name: String,
+    /// Whether the device is trusted ("remember this device")
+    #[serde(default)]
+    pub trusted: bool,
+    /// User agent of the last sign-in
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub user_agent: Option<String>,
+    /// IP address of the last sign-in
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub ip: Option<String>,
+    /// Last activity date
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub last_seen_at: Option<DateTime<Utc>>,
+    /// First sign-in date
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+/// Push-notification MFA challenge
+///
+/// Returned when a push approval request is sent to the user's phone.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PushChallenge {
+    /// Challenge ID, used to poll the approval status
+    pub challenge_id: String,
+    /// Challenge expiration date
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+/// Status of a push-notification MFA challenge
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PushChallengeStatus {
+    /// Waiting for the user to answer
+    Pending,
+    /// Approved on the user's device
+    Approved,
+    /// Denied on the user's device
+    Denied,
+    /// Expired without an answer
+    Expired,
+}
+
+/// Current state of a push-notification MFA challenge
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PushChallengeState {
+    /// Challenge ID
+    pub challenge_id: String,
+    /// Approval status
+    pub status: PushChallengeStatus,
+    /// Token issued on approval (optional)
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub token: Option<Token>,
+}
+
+/// MFA backup codes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupCodes {
+    /// One-time backup codes
+    pub codes: Vec<String>,
+    /// Generation date
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub generated_at: Option<DateTime<Utc>>,
+}
+
+/// Usage status of the MFA backup codes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupCodesStatus {
+    /// Number of codes generated
+    pub total: u32,
+    /// Number of codes not used yet
+    pub remaining: u32,
+    /// Generation date
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub generated_at: Option<DateTime<Utc>>,
+}
+
+/// Login request using a backup code
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct BackupCodeLogin {
+    #[serde(flatten)]
+    pub(crate) credentials: LoginCredentials,
+    pub(crate) backup_code: String,
+}
+
+/// Magic link request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MagicLinkRequest {
+    /// User email
+    pub email: String,
+    /// URL the link points to
+    pub redirect_uri: String,
+    /// Namespace (default: "public")
+    #[serde(default = "default_namespace")]
+    pub namespace: String,
+}
+
+/// Delivery channel for one-time codes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OtpChannel {
+    /// Text message to the user's phone
+    Sms,
+    /// Email to the user's address
+    Email,
+}
+
+/// Delivery information for a one-time code
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OtpDelivery {
+    /// Channel the code was sent through
+    pub channel: OtpChannel,
+    /// Code validity in seconds (optional)
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub expires_in: Option<u64>,
+    /// Seconds before another code can be requested (optional)
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub resend_after: Option<u64>,
+    /// Masked destination (e.g., "+55 ** ****-1234")
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub destination: Option<String>,
+}
+
+/// Linked account model
+///
+/// Represents a secondary account merged into a primary user.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LinkedAccount {
+    /// ID of the primary account
+    pub primary_user_id: String,
+    /// ID of the linked (secondary) account
+    pub user_id: String,
+    /// Identity provider of the linked account (e.g., "google")
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub provider: Option<String>,
+    /// Email of the linked account
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub email: Option<String>,
+    /// Link creation date
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub linked_at: Option<DateTime<Utc>>,
+}
+
+/// Username change history entry
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UsernameChange {
+    /// Username before the change
+    pub old_username: String,
+    /// Username after the change
+    pub new_username: String,
+    /// Change date
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub changed_at: Option<DateTime<Utc>>,
+}
+
+/// Profile picture of a user
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Avatar {
+    /// Public URL of the image
+    pub url: String,
+    /// Image content type (e.g., "image/png")
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub content_type: Option<String>,
+    /// Last update date
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+/// Result of an export download
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExportDownload {
+    /// Size of the downloaded file in bytes
+    pub bytes: u64,
+    /// SHA-256 checksum of the file (lowercase hex)
+    pub sha256: String,
+    /// Whether a previously interrupted download was resumed
+    pub resumed: bool,
+}
+
+/// Artifact produced by an export job
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportArtifact {
+    /// Size of the artifact in bytes
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub size: Option<u64>,
+    /// SHA-256 checksum of the artifact (lowercase hex)
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub sha256: Option<String>,
+    /// Expiration date of the artifact
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+/// Summary of a bulk import job
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ImportSummary {
+    /// Rows that created a new record
+    #[serde(default)]
+    pub created: u64,
+    /// Rows that updated an existing record
+    #[serde(default)]
+    pub updated: u64,
+    /// Rows that could not be imported
+    #[serde(default)]
+    pub failed: u64,
+    /// Error messages of the failed rows
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub errors: Vec<String>,
+}
+
+/// Usage statistics of a tenant
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TenantStats {
+    /// Total number of users
+    pub total_users: u64,
+    /// Sessions currently active
+    pub active_sessions: u64,
+    /// Successful logins in the last 24 hours
+    pub logins_last_24h: u64,
+    /// Ratio of users with MFA enabled, between 0.0 and 1.0
+    pub mfa_adoption: f64,
+}
+
+/// Settings of a tenant
+///
+/// Settings this version of the SDK does not know are kept in `extra`, so
+/// reading, changing and writing back the settings leaves them untouched.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TenantSettings {
+    /// Token and session lifetimes
+    pub sessions: SessionSettings,
+    /// Who must use multi-factor authentication
+    pub mfa: MfaPolicy,
+    /// Rules passwords must follow
+    pub password_policy: PasswordPolicy,
+    /// Ways users may sign in
+    pub auth_methods: Vec<AuthMethod>,
+    /// Other settings
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+
+/// Token and session lifetimes of a tenant
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionSettings {
+    /// Access token lifetime in seconds
+    pub access_token_lifetime: u64,
+    /// Refresh token lifetime in seconds
+    pub refresh_token_lifetime: u64,
+    /// Inactivity after which a session ends, in seconds (optional)
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub idle_timeout: Option<u64>,
+}
+
+/// Multi-factor authentication requirement of a tenant
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MfaPolicy {
+    /// MFA cannot be enabled
+    Disabled,
+    /// Users may enable MFA
+    Optional,
+    /// Administrators must use MFA
+    RequiredForAdmins,
+    /// Every user must use MFA
+    Required,
+}
+
+/// Rules passwords must follow
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PasswordPolicy {
+    /// Minimum length
+    pub min_length: u32,
+    /// Whether an uppercase letter is required
+    #[serde(default)]
+    pub require_uppercase: bool,
+    /// Whether a lowercase letter is required
+    #[serde(default)]
+    pub require_lowercase: bool,
+    /// Whether a digit is required
+    #[serde(default)]
+    pub require_digit: bool,
+    /// Whether a symbol is required
+    #[serde(default)]
+    pub require_symbol: bool,
+    /// Number of previous passwords that cannot be reused
+    #[serde(default)]
+    pub history: u32,
+    /// Days after which passwords must be changed (optional)
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub max_age_days: Option<u32>,
+}
+
+/// Way of signing in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuthMethod {
+    /// Identifier and password
+    Password,
+    /// Link sent by email
+    MagicLink,
+    /// One-time code sent by SMS or email
+    Otp,
+    /// WebAuthn passkey
+    Passkey,
+    /// External identity provider (e.g., Google)
+    Social,
+}
+
+/// Transactional email sent by Keyrunes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EmailTemplateKind {
+    /// Email address verification
+    Verification,
+    /// Password reset link
+    PasswordReset,
+    /// Invitation to join the tenant
+    Invitation,
+    /// Passwordless sign-in link
+    MagicLink,
+}
+
+impl EmailTemplateKind {
+    /// Identifier of the template in the API (e.g., "password_reset")
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EmailTemplateKind::Verification => "verification",
+            EmailTemplateKind::PasswordReset => "password_reset",
+            EmailTemplateKind::Invitation => "invitation",
+            EmailTemplateKind::MagicLink => "magic_link",
+        }
+    }
+}
+
+/// Content of an email template
+///
+/// Subject and bodies may reference variables (e.g., `{{user.name}}`,
+/// `{{link}}`), filled in when the email is sent.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EmailTemplateContent {
+    /// Subject line
+    pub subject: String,
+    /// HTML body
+    pub html_body: String,
+    /// Plain-text body (optional; derived from the HTML body when absent)
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub text_body: Option<String>,
+}
+
+/// Email template of a tenant
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EmailTemplate {
+    /// Email the template is used for
+    pub kind: EmailTemplateKind,
+    /// Subject and bodies
+    #[serde(flatten)]
+    pub content: EmailTemplateContent,
+    /// Whether the template is the Keyrunes default (never customized)
+    #[serde(default)]
+    pub is_default: bool,
+    /// Last update date
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+/// Email template rendered with sample variables
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RenderedEmail {
+    /// Subject line
+    pub subject: String,
+    /// HTML body
+    pub html: String,
+    /// Plain-text body
+    pub text: String,
+}
+
+/// Branding of the pages Keyrunes hosts for a tenant (login, consent, ...)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Branding {
+    /// URL of the logo
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub logo_url: Option<String>,
+    /// URL of the favicon
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub favicon_url: Option<String>,
+    /// Primary color, as a CSS hex color (e.g., "#336699")
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub primary_color: Option<String>,
+    /// Background color, as a CSS hex color
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub background_color: Option<String>,
+    /// Name displayed instead of the tenant's
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub display_name: Option<String>,
+    /// Other branding settings
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+
+/// Verification state of a custom domain
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DomainStatus {
+    /// Waiting for the DNS records to be published
+    Pending,
+    /// Verified; the hosted pages are served on the domain
+    Verified,
+    /// Verification failed (records missing or wrong)
+    Failed,
+}
+
+/// DNS record to publish for a custom domain
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DnsRecord {
+    /// Record type (e.g., "CNAME", "TXT")
+    #[serde(rename = "type")]
+    pub record_type: String,
+    /// Record name
+    pub name: String,
+    /// Expected value
+    pub value: String,
+}
+
+/// Custom domain serving a tenant's hosted pages
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CustomDomain {
+    /// Domain name (e.g., "login.example.com")
+    pub domain: String,
+    /// Verification state
+    pub status: DomainStatus,
+    /// Records to publish for the domain to be verified
+    #[serde(default)]
+    pub dns_records: Vec<DnsRecord>,
+    /// Date the domain was verified
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub verified_at: Option<DateTime<Utc>>,
+}
+
+/// Events to replay to webhooks
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EventFilter {
+    /// Only events of these types (e.g., "user.created"); all types when empty
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub event_types: Vec<String>,
+    /// Only deliver to this webhook; all subscribed webhooks when `None`
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub webhook_id: Option<String>,
+    /// Only events whose delivery failed
+    #[serde(default)]
+    pub failed_only: bool,
+}
+
+impl EventFilter {
+    /// Creates a filter matching every event.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only replays events of `event_type` (may be called several times).
+    pub fn event_type<S: Into<String>>(mut self, event_type: S) -> Self {
+        self.event_types.push(event_type.into());
+        self
+    }
+
+    /// Only delivers to the webhook `webhook_id`.
+    pub fn webhook<S: Into<String>>(mut self, webhook_id: S) -> Self {
+        self.webhook_id = Some(webhook_id.into());
+        self
+    }
+
+    /// Only replays events whose delivery failed.
+    pub fn failed_only(mut self) -> Self {
+        self.failed_only = true;
+        self
+    }
+}
+
+/// Result of an event replay job
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EventReplay {
+    /// Events matching the filter
+    pub matched: u64,
+    /// Deliveries that succeeded
+    pub delivered: u64,
+    /// Deliveries that failed again
+    #[serde(default)]
+    pub failed: u64,
+}
+
+/// Webhook delivery that failed after all its retries (dead letter)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WebhookDelivery {
+    /// Delivery ID
+    pub id: String,
+    /// ID of the delivered event
+    pub event_id: String,
+    /// Type of the delivered event (e.g., "user.created")
+    pub event_type: String,
+    /// Number of delivery attempts
+    pub attempts: u32,
+    /// HTTP status of the last attempt (`None` if the endpoint was unreachable)
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub last_status: Option<u16>,
+    /// Error of the last attempt
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub last_error: Option<String>,
+    /// When the event occurred
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub occurred_at: Option<DateTime<Utc>>,
+    /// Date of the last attempt
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub last_attempt_at: Option<DateTime<Utc>>,
+}
+
+/// Algorithm of a password hash imported from another credential store
+///
+/// Hashes are passed in the format the algorithm is usually stored in:
+/// modular crypt format for bcrypt (`$2b$12$...`), PHC strings for the
+/// others (`$argon2id$v=19$m=65536,t=3,p=4$salt$hash`,
+/// `$pbkdf2-sha256$i=27500$salt$hash`, `$scrypt$ln=15,r=8,p=1$salt$hash`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum HashAlgorithm {
+    /// bcrypt (`$2a$`, `$2b$` or `$2y$`)
+    Bcrypt,
+    /// Argon2id
+    Argon2id,
+    /// Argon2i
+    Argon2i,
+    /// scrypt
+    Scrypt,
+    /// PBKDF2 with HMAC-SHA1
+    Pbkdf2Sha1,
+    /// PBKDF2 with HMAC-SHA256
+    Pbkdf2Sha256,
+    /// PBKDF2 with HMAC-SHA512
+    Pbkdf2Sha512,
+}
+
+impl HashAlgorithm {
+    /// Identifier of the algorithm in the API, which is also its PHC
+    /// identifier (e.g., "pbkdf2-sha256")
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HashAlgorithm::Bcrypt => "bcrypt",
+            HashAlgorithm::Argon2id => "argon2id",
+            HashAlgorithm::Argon2i => "argon2i",
+            HashAlgorithm::Scrypt => "scrypt",
+            HashAlgorithm::Pbkdf2Sha1 => "pbkdf2-sha1",
+            HashAlgorithm::Pbkdf2Sha256 => "pbkdf2-sha256",
+            HashAlgorithm::Pbkdf2Sha512 => "pbkdf2-sha512",
+        }
+    }
+}
+
+/// User imported with an existing password hash
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UserImport {
+    /// Username
+    pub username: String,
+    /// User email
+    pub email: String,
+    /// Whether the email address was already verified
+    #[serde(default)]
+    pub email_verified: bool,
+    /// ID of the user in the previous credential store
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub external_id: Option<String>,
+}
+
+impl UserImport {
+    /// Creates a user with an unverified email address.
+    pub fn new(username: impl Into<String>, email: impl Into<String>) -> Self {
+        Self {
+            username: username.into(),
+            email: email.into(),
+            email_verified: false,
+            external_id: None,
+        }
+    }
+}
+
+/// Headers of interest of a response from Keyrunes
+///
+/// See [`KeyrunesClient::last_response_meta`](crate::KeyrunesClient::last_response_meta).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ResponseMeta {
+    /// HTTP status code
+    pub status: u16,
+    /// ID of the request on the server (`X-Request-Id`), to quote to support
+    pub request_id: Option<String>,
+    /// Version of the Keyrunes server (`X-Keyrunes-Version`)
+    pub server_version: Option<String>,
+    /// Requests allowed in the current window (`X-RateLimit-Limit`)
+    pub rate_limit: Option<u64>,
+    /// Requests left in the current window (`X-RateLimit-Remaining`)
+    pub rate_limit_remaining: Option<u64>,
+    /// Seconds until the window resets (`X-RateLimit-Reset`)
+    pub rate_limit_reset: Option<u64>,
+    /// Deprecation notice of the endpoint (`Deprecation`)
+    pub deprecation: Option<String>,
+    /// When the endpoint will be removed (`Sunset`)
+    pub sunset: Option<String>,
+}
+
+impl ResponseMeta {
+    pub(crate) fn from_response(response: &reqwest::Response) -> Self {
+        let headers = response.headers();
+        let text = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(|value| value.trim().to_string())
+        };
+        let number = |name: &str| text(name).and_then(|value| value.parse().ok());
+
+        Self {
+            status: response.status().as_u16(),
+            request_id: text("x-request-id"),
+            server_version: text("x-keyrunes-version"),
+            rate_limit: number("x-ratelimit-limit"),
+            rate_limit_remaining: number("x-ratelimit-remaining"),
+            rate_limit_reset: number("x-ratelimit-reset"),
+            deprecation: text("deprecation"),
+            sunset: text("sunset"),
+        }
+    }
+
+    /// Checks whether the server flagged the endpoint as deprecated.
+    pub fn is_deprecated(&self) -> bool {
+        self.deprecation
+            .as_deref()
+            .is_some_and(|value| value != "false")
+    }
+}
+
+/// Permission granted to the members of a group
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RoleBinding {
+    /// Binding ID
+    pub id: String,
+    /// Name of the group
+    pub group: String,
+    /// Key of the granted permission (e.g., "documents:read")
+    pub permission: String,
+    /// Creation date
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+/// Non-human account used by services to call Keyrunes-protected APIs
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ServiceAccount {
+    /// Account ID
+    pub id: String,
+    /// Unique name (e.g., "billing-worker")
+    pub name: String,
+    /// Human-readable description
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub description: Option<String>,
+    /// Names of the groups the account belongs to
+    #[serde(default)]
+    pub groups: Vec<String>,
+    /// OAuth client ID of the account
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub client_id: Option<String>,
+    /// OAuth client secret, only returned when the account is created
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub client_secret: Option<String>,
+    /// Creation date
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+/// Outcome of a bulk membership change for one user
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MembershipChangeStatus {
+    /// The user was added to the group
+    Added,
+    /// The user was removed from the group
+    Removed,
+    /// The user was already in the group (or not in it, for removals)
+    Unchanged,
+    /// The change failed for this user (see `code` and `message`)
+    Failed,
+}
+
+/// Result of a bulk membership change for one user
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MembershipChange {
+    /// User ID
+    pub user_id: String,
+    /// Outcome of the change
+    pub status: MembershipChangeStatus,
+    /// Error code, for failed changes (e.g., "user_not_found")
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub code: Option<String>,
+    /// Error message, for failed changes
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub message: Option<String>,
+}
+
+/// Per-user results of a bulk membership change
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BulkMembershipReport {
+    /// One result per requested user, in request order
+    pub results: Vec<MembershipChange>,
+}
+
+impl BulkMembershipReport {
+    /// Results of the changes that failed
+    pub fn failed(&self) -> impl Iterator<Item = &MembershipChange> {
+        self.results
+            .iter()
+            .filter(|change| change.status == MembershipChangeStatus::Failed)
+    }
+
+    /// Checks whether the change succeeded for every user.
+    pub fn is_complete(&self) -> bool {
+        self.failed().next().is_none()
+    }
+}
+
+/// Membership of a user in a group
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GroupMembership {
+    /// Member user ID
+    pub user_id: String,
+    /// Group ID
+    pub group_id: String,
+    /// When the user was added to the group
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub added_at: Option<DateTime<Utc>>,
+    /// When the membership ends (`None` for permanent memberships)
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+impl GroupMembership {
+    /// Checks whether the membership is a temporary grant.
+    pub fn is_temporary(&self) -> bool {
+        self.expires_at.is_some()
+    }
+
+    /// Checks whether the membership has ended.
+    pub fn is_expired(&self) -> bool {
+        self.expires_at
+            .is_some_and(|expires_at| expires_at <= Utc::now())
+    }
+}
+
+/// Page of results from a paginated endpoint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Page<T> {
+    /// Items of the page
+    pub items: Vec<T>,
+    /// Cursor of the next page (`None` on the last page)
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub next_cursor: Option<String>,
+    /// Total number of items across all pages (if reported)
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub total: Option<u64>,
+}
+
+impl<T> Page<T> {
+    /// Checks whether there are more pages.
+    pub fn has_more(&self) -> bool {
+        self.next_cursor.is_some()
+    }
+}
+
+/// Sign-in attempt of a user
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoginEvent {
+    /// Attempt date
+    pub timestamp: DateTime<Utc>,
+    /// IP address of the client
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub ip: Option<String>,
+    /// User agent of the client
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub user_agent: Option<String>,
+    /// Whether the attempt succeeded
+    pub success: bool,
+    /// Authentication method (e.g., "password", "magic_link", "otp")
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub method: Option<String>,
+    /// Reason of the failure (only set for failed attempts)
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub failure_reason: Option<String>,
+}
+
+/// Filter for the login history
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LoginHistoryFilter {
+    /// Only attempts after this date
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub since: Option<DateTime<Utc>>,
+    /// Only attempts before this date
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub until: Option<DateTime<Utc>>,
+    /// Only successful (`true`) or failed (`false`) attempts
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub success: Option<bool>,
+    /// Maximum number of events per page
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub limit: Option<u32>,
+    /// Cursor returned by the previous page
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub cursor: Option<String>,
+}
+
+impl LoginHistoryFilter {
+    /// Creates an empty filter (most recent events first).
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only returns attempts after `since`.
+    pub fn since(mut self, since: DateTime<Utc>) -> Self {
+        self.since = Some(since);
+        self
+    }
+
+    /// Only returns attempts before `until`.
+    pub fn until(mut self, until: DateTime<Utc>) -> Self {
+        self.until = Some(until);
+        self
+    }
+
+    /// Only returns successful (`true`) or failed (`false`) attempts.
+    pub fn success(mut self, success: bool) -> Self {
+        self.success = Some(success);
+        self
+    }
+
+    /// Sets the page size.
+    pub fn limit(mut self, limit: u32) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Requests the page following `cursor`.
+    pub fn cursor<S: Into<String>>(mut self, cursor: S) -> Self {
+        self.cursor = Some(cursor.into());
+        self
+    }
+}
+
+/// Action applied to requests matching an IP rule
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IpRuleAction {
+    /// Allow requests from the range
+    Allow,
+    /// Deny requests from the range
+    Deny,
+}
+
+/// IP restriction of a tenant
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IpRule {
+    /// Rule ID
+    pub id: String,
+    /// IP range in CIDR notation (e.g., "203.0.113.0/24")
+    pub cidr: String,
+    /// Action applied to the range
+    pub action: IpRuleAction,
+    /// Rule creation date
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+/// Lifecycle state of a signing key
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SigningKeyStatus {
+    /// Published, and used for signing at the next rotation
+    Pending,
+    /// Signs new tokens
+    Active,
+    /// No longer signs, but still published to verify tokens in flight
+    Inactive,
+    /// Withdrawn from the key set; tokens it signed are rejected
+    Retired,
+}
+
+/// Key Keyrunes signs tokens with
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SigningKey {
+    /// Key ID (`kid` of the tokens it signs)
+    pub kid: String,
+    /// Signature algorithm (e.g., "RS256")
+    pub alg: String,
+    /// Lifecycle state
+    pub status: SigningKeyStatus,
+    /// Key creation date
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub created_at: Option<DateTime<Utc>>,
+    /// Date the key started signing
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub activated_at: Option<DateTime<Utc>>,
+    /// Date the key was retired
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub retired_at: Option<DateTime<Utc>>,
+}
+
+/// Entitlement granted to a user (plan feature, flag, or limit)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Entitlement {
+    /// Entitlement key (e.g., "advanced_reports")
+    pub key: String,
+    /// Whether the entitlement is enabled
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Associated value (e.g., a seat limit)
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub value: Option<serde_json::Value>,
+    /// Expiration date
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+impl Entitlement {
+    /// Checks whether the entitlement is enabled and not expired.
+    pub fn is_active(&self) -> bool {
+        self.enabled && self.expires_at.map(|at| at > Utc::now()).unwrap_or(true)
+    }
+}
+
+/// Usage of a metered entitlement
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Quota {
+    /// Meter name (e.g., "api_calls")
+    pub meter: String,
+    /// Units consumed in the current period
+    pub used: u64,
+    /// Units allowed per period (`None` for unlimited)
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub limit: Option<u64>,
+    /// End of the current period
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub resets_at: Option<DateTime<Utc>>,
+}
+
+impl Quota {
+    /// Returns the units left in the current period (`None` for unlimited).
+    pub fn remaining(&self) -> Option<u64> {
+        self.limit.map(|limit| limit.saturating_sub(self.used))
+    }
+}
+
+/// Reference to a resource protected by Keyrunes (e.g., a document)
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ResourceRef {
+    /// Resource type (e.g., "document")
+    #[serde(rename = "type")]
+    pub resource_type: String,
+    /// Resource ID
+    pub id: String,
+}
+
+impl ResourceRef {
+    /// Creates a reference to the resource `id` of type `resource_type`.
+    pub fn new<T: Into<String>, I: Into<String>>(resource_type: T, id: I) -> Self {
+        Self {
+            resource_type: resource_type.into(),
+            id: id.into(),
+        }
+    }
+}
+
+impl std::fmt::Display for ResourceRef {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.resource_type, self.id)
+    }
+}
+
+/// Path of a resource nested in parent resources
+///
+/// Written as alternating type/ID segments, from the root to the resource
+/// (e.g., `org/123/project/456/doc/789`).
+///
+/// ```
+/// use keyrunes_rust_sdk::{ResourcePath, ResourceRef};
+///
+/// let path: ResourcePath = "org/123/project/456".parse().unwrap();
+/// assert_eq!(path.resource(), &ResourceRef::new("project", "456"));
+/// assert_eq!(path.ancestors().count(), 1);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ResourcePath {
+    segments: Vec<ResourceRef>,
+}
+
+impl ResourcePath {
+    /// Creates a path from its resources, root first.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Err(KeyrunesError::InvalidResourcePath)` if `segments` is empty.
+    pub fn new(segments: Vec<ResourceRef>) -> crate::error::Result<Self> {
+        if segments.is_empty() {
+            return Err(crate::error::KeyrunesError::InvalidResourcePath(
+                "empty path".to_string(),
+            ));
+        }
+        Ok(Self { segments })
+    }
+
+    /// Returns the resources of the path, root first.
+    pub fn segments(&self) -> &[ResourceRef] {
+        &self.segments
+    }
+
+    /// Returns the resource the path points to.
+    pub fn resource(&self) -> &ResourceRef {
+        &self.segments[self.segments.len() - 1]
+    }
+
+    /// Returns the ancestors of the resource, closest first.
+    pub fn ancestors(&self) -> impl Iterator<Item = &ResourceRef> {
+        self.segments[..self.segments.len() - 1].iter().rev()
+    }
+
+    /// Returns the path of the parent resource, if any.
+    pub fn parent(&self) -> Option<ResourcePath> {
+        (self.segments.len() > 1).then(|| ResourcePath {
+            segments: self.segments[..self.segments.len() - 1].to_vec(),
+        })
+    }
+}
+
+impl std::str::FromStr for ResourcePath {
+    type Err = crate::error::KeyrunesError;
+
+    fn from_str(path: &str) -> crate::error::Result<Self> {
+        let parts: Vec<&str> = path.trim_matches('/').split('/').collect();
+        if !parts.len().is_multiple_of(2) || parts.iter().any(|part| part.is_empty()) {
+            return Err(crate::error::KeyrunesError::InvalidResourcePath(
+                path.to_string(),
+            ));
+        }
+
+        Self::new(
+            parts
+                .chunks(2)
+                .map(|pair| ResourceRef::new(pair[0], pair[1]))
+                .collect(),
+        )
+    }
+}
+
+impl std::fmt::Display for ResourcePath {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let segments: Vec<String> = self
+            .segments
+            .iter()
+            .map(|segment| format!("{}/{}", segment.resource_type, segment.id))
+            .collect();
+        write!(f, "{}", segments.join("/"))
+    }
+}
+
+/// Result of a permission check on a resource path
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HierarchyDecision {
+    /// Whether the permission is granted
+    pub allowed: bool,
+    /// Resource of the path that grants the permission (the resource itself or an ancestor)
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub granted_by: Option<ResourceRef>,
+}
+
+/// Access to a resource shared by one user with another
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Delegation {
+    /// Delegation ID
+    pub id: String,
+    /// Shared resource
+    pub resource: ResourceRef,
+    /// User sharing the resource
+    pub from_user_id: String,
+    /// User receiving access
+    pub to_user_id: String,
+    /// Permissions granted (e.g., "read", "comment")
+    #[serde(default)]
+    pub permissions: Vec<String>,
+    /// Expiration date (`None` for permanent access)
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub expires_at: Option<DateTime<Utc>>,
+    /// Creation date
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+/// Resource registered for relationship-based authorization
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Resource {
+    /// Resource type (e.g., "document")
+    #[serde(rename = "type")]
+    pub resource_type: String,
+    /// Resource ID
+    pub id: String,
+    /// ID of the owning user
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub owner_id: Option<String>,
+    /// Parent resource (e.g., the folder of a document)
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub parent: Option<ResourceRef>,
+    /// Registration date
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+impl Resource {
+    /// Returns a reference to this resource.
+    pub fn to_ref(&self) -> ResourceRef {
+        ResourceRef::new(self.resource_type.clone(), self.id.clone())
+    }
+}
+
+/// Relationship tuple: `subject` has `relation` on `object`
+///
+/// For example, `user:123` is `editor` of `document:42`, or
+/// `group:eng` is `viewer` of `folder:7`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Relation {
+    /// Subject of the relation (a user, group, or another resource)
+    pub subject: ResourceRef,
+    /// Relation name (e.g., "owner", "editor", "parent")
+    pub relation: String,
+    /// Object of the relation
+    pub object: ResourceRef,
+}
+
+impl Relation {
+    /// Creates a relation tuple.
+    pub fn new<S: Into<String>>(subject: ResourceRef, relation: S, object: ResourceRef) -> Self {
+        Self {
+            subject,
+            relation: relation.into(),
+            object,
+        }
+    }
+}
+
+impl std::fmt::Display for Relation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}#{}@{}", self.object, self.relation, self.subject)
+    }
+}
+
+/// Permission declared in the tenant's permission catalog
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Permission {
+    /// Permission key (e.g., "documents:read")
+    pub key: String,
+    /// Human-readable description
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub description: Option<String>,
+    /// Whether the permission is no longer declared by the application
+    #[serde(default)]
+    pub deprecated: bool,
+    /// Creation date
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+/// Changes applied by a permission catalog sync
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PermissionSyncReport {
+    /// Keys of the created permissions
+    pub created: Vec<String>,
+    /// Keys of the permissions whose description changed or that were re-declared
+    pub updated: Vec<String>,
+    /// Keys of the permissions flagged as deprecated (no longer declared)
+    pub deprecated: Vec<String>,
+    /// Number of permissions already up to date
+    pub unchanged: usize,
+}
+
+impl PermissionSyncReport {
+    /// Checks whether the sync changed anything.
+    pub fn is_noop(&self) -> bool {
+        self.created.is_empty() && self.updated.is_empty() && self.deprecated.is_empty()
+    }
+}
@@ -0,0 +1,150 @@
+//! Certificate-bound access tokens (feature `mtls`)
+//!
+//! With mutual TLS, Keyrunes can bind the tokens it issues to the client
+//! certificate they were requested with (RFC 8705): the token carries the
+//! SHA-256 thumbprint of the certificate in its `cnf` claim (`x5t#S256`).
+//! A stolen token is then useless without the certificate's private key.
+//!
+//! TLS usually terminates at the edge (load balancer, ingress), which
+//! forwards the client certificate, or its fingerprint, to the application
+//! in a header. [`CertificateBinding`] names that header and its format.
+//! Set it on the integration's `KeyrunesState` and the middlewares reject
+//! bound tokens presented without the matching certificate.
+//!
+//! ```
+//! use keyrunes_rust_sdk::mtls::{CertificateBinding, CertificateFormat};
+//!
+//! // nginx: proxy_set_header X-Client-Cert $ssl_client_escaped_cert;
+//! let binding = CertificateBinding::new("X-Client-Cert", CertificateFormat::Pem);
+//! ```
+//!
+//! The edge must strip the header from incoming requests, so that clients
+//! cannot present a certificate they do not hold.
+
+use crate::claims::decode_payload;
+use crate::error::{KeyrunesError, Result};
+use base64::engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD};
+use base64::Engine;
+use sha2::{Digest, Sha256};
+
+/// Confirmation method holding the certificate thumbprint, in the `cnf` claim
+pub const CNF_THUMBPRINT: &str = "x5t#S256";
+
+/// How the edge forwards the client certificate
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CertificateFormat {
+    /// URL-encoded PEM certificate (e.g., nginx `$ssl_client_escaped_cert`,
+    /// AWS ALB `X-Amzn-Mtls-Clientcert`)
+    Pem,
+    /// SHA-256 fingerprint of the certificate, hex (with or without colons)
+    /// or base64url encoded
+    Thumbprint,
+}
+
+/// Verifies that tokens are bound to the client certificate of the request
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CertificateBinding {
+    header: String,
+    format: CertificateFormat,
+    require_bound: bool,
+}
+
+impl CertificateBinding {
+    /// Reads the client certificate from `header`, in `format`.
+    ///
+    /// Tokens that are not bound to a certificate are still accepted; see
+    /// [`CertificateBinding::require_bound_tokens`].
+    pub fn new<S: Into<String>>(header: S, format: CertificateFormat) -> Self {
+        Self {
+            header: header.into(),
+            format,
+            require_bound: false,
+        }
+    }
+
+    /// Also rejects tokens that are not bound to a certificate.
+    pub fn require_bound_tokens(mut self) -> Self {
+        self.require_bound = true;
+        self
+    }
+
+    /// Header carrying the client certificate
+    pub fn header(&self) -> &str {
+        &self.header
+    }
+
+    /// Thumbprint (`x5t#S256`) of the certificate in a header value, or
+    /// `None` if the value cannot be read.
+    pub fn thumbprint(&self, value: &str) -> Option<String> {
+        match self.format {
+            CertificateFormat::Pem => {
+                let pem = percent_encoding::percent_decode_str(value)
+                    .decode_utf8()
+                    .ok()?;
+                let body: String = pem
+                    .lines()
+                    .filter(|line| !line.starts_with("-----"))
+                    .flat_map(|line| line.split_whitespace())
+                    .collect();
+                Some(certificate_thumbprint(&STANDARD.decode(body).ok()?))
+            }
+            CertificateFormat::Thumbprint => {
+                let value = value.trim().replace(':', "");
+                let digest = if value.len() == 64 {
+                    decode_hex(&value)?
+                } else {
+                    URL_SAFE_NO_PAD.decode(value.trim_end_matches('=')).ok()?
+                };
+                (digest.len() == 32).then(|| URL_SAFE_NO_PAD.encode(digest))
+            }
+        }
+    }
+
+    /// Verifies that `token` is bound to the certificate in `header_value`
+    /// (the value of the certificate header, if the request has one).
+    ///
+    /// Fails with `AuthenticationError` if the token is bound to another
+    /// certificate, or no certificate was presented, and with
+    /// `InvalidToken` if the token cannot be decoded.
+    pub fn verify(&self, token: &str, header_value: Option<&str>) -> Result<()> {
+        let payload = decode_payload(token)?;
+        let Some(bound) = payload
+            .get("cnf")
+            .and_then(|cnf| cnf.get(CNF_THUMBPRINT))
+            .and_then(|thumbprint| thumbprint.as_str())
+        else {
+            return if self.require_bound {
+                Err(KeyrunesError::AuthenticationError {
+                    message: "Token is not bound to a client certificate".to_string(),
+                    code: None,
+                })
+            } else {
+                Ok(())
+            };
+        };
+
+        match header_value.and_then(|value| self.thumbprint(value)) {
+            Some(presented) if presented == bound => Ok(()),
+            Some(_) => Err(KeyrunesError::AuthenticationError {
+                message: "Token is bound to another client certificate".to_string(),
+                code: None,
+            }),
+            None => Err(KeyrunesError::AuthenticationError {
+                message: "Client certificate required".to_string(),
+                code: None,
+            }),
+        }
+    }
+}
+
+/// Thumbprint (`x5t#S256`) of a DER-encoded certificate: its SHA-256 hash, base64url encoded
+pub fn certificate_thumbprint(der: &[u8]) -> String {
+    URL_SAFE_NO_PAD.encode(Sha256::digest(der))
+}
+
+fn decode_hex(value: &str) -> Option<Vec<u8>> {
+    (0..value.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(value.get(i..i + 2)?, 16).ok())
+        .collect()
+}
@@ -0,0 +1,308 @@
+//! State of OAuth/OIDC authorization requests (feature `oauth`)
+//!
+//! Between the redirect to the authorization endpoint and the callback, the
+//! application must remember what it sent: the `state`, the OIDC `nonce`
+//! and the PKCE code verifier (see [`crate::pkce`]). The callback is only
+//! accepted if its `state` matches a pending request, and each request can
+//! be completed once.
+//!
+//! When several instances serve the application, the callback may reach
+//! another instance than the one that started the flow, so the pending
+//! requests must be shared. [`StateStore`] abstracts where they are kept:
+//! [`InMemoryStateStore`] for a single instance, `RedisStateStore`
+//! (feature `redis`) for a shared store, and [`CookieStateStore`] keeps
+//! them in a signed cookie on the browser itself, with no server-side
+//! storage.
+//!
+//! ## Quick Start
+//!
+//! ```
+//! use keyrunes_rust_sdk::oauth::{AuthorizationState, InMemoryStateStore, StateStore};
+//! use std::time::Duration;
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let store = InMemoryStateStore::new();
+//!
+//! // Redirect: remember the request, send its values to Keyrunes
+//! let request = AuthorizationState::new("https://app.example.com/callback");
+//! store.save(&request, Duration::from_secs(600)).await?;
+//! // ...?state={request.state}&nonce={nonce}&code_challenge={request.code_challenge()}
+//!
+//! // Callback: exchange the code only if the state is known
+//! let pending = store.take(&request.state).await?.ok_or("unknown state")?;
+//! // ... send pending.code_verifier with the code
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::compare::constant_time_eq;
+use crate::error::{KeyrunesError, Result};
+use crate::pkce::{self, Pkce};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use ring::hmac;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::Duration;
+use tokio::sync::Mutex;
+
+/// Name of the state cookie, by default
+const DEFAULT_COOKIE_NAME: &str = "keyrunes_oauth_state";
+
+/// An authorization request waiting for its callback
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuthorizationState {
+    /// `state` sent in the authorization request
+    pub state: String,
+    /// OIDC `nonce` sent in the authorization request
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<String>,
+    /// PKCE code verifier, sent with the code in the token request
+    pub code_verifier: String,
+    /// Callback URL of the request
+    pub redirect_uri: String,
+    /// When the request was started
+    pub created_at: DateTime<Utc>,
+}
+
+impl AuthorizationState {
+    /// Starts a request to `redirect_uri` with a new state, nonce and code verifier.
+    pub fn new<S: Into<String>>(redirect_uri: S) -> Self {
+        Self {
+            state: pkce::generate_state(),
+            nonce: Some(pkce::generate_nonce()),
+            code_verifier: Pkce::generate().verifier,
+            redirect_uri: redirect_uri.into(),
+            created_at: Utc::now(),
+        }
+    }
+
+    /// S256 code challenge of the code verifier
+    pub fn code_challenge(&self) -> String {
+        pkce::challenge(&self.code_verifier)
+    }
+}
+
+/// Storage backend for pending authorization requests
+///
+/// Implement this trait to keep them in a custom store. Requests are keyed
+/// by their `state`.
+#[async_trait::async_trait]
+pub trait StateStore: Send + Sync {
+    /// Stores a pending request, expiring it after `ttl`.
+    async fn save(&self, request: &AuthorizationState, ttl: Duration) -> Result<()>;
+
+    /// Removes and returns the pending request with `state`, if it exists
+    /// and has not expired.
+    ///
+    /// A request can only be taken once, so a replayed callback is rejected.
+    async fn take(&self, state: &str) -> Result<Option<AuthorizationState>>;
+}
+
+/// In-memory store of pending authorization requests
+///
+/// Requests are local to the process; use a shared store when running
+/// several instances.
+#[derive(Debug, Default)]
+pub struct InMemoryStateStore {
+    requests: Mutex<HashMap<String, (AuthorizationState, tokio::time::Instant)>>,
+}
+
+impl InMemoryStateStore {
+    /// Creates an empty store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait::async_trait]
+impl StateStore for InMemoryStateStore {
+    async fn save(&self, request: &AuthorizationState, ttl: Duration) -> Result<()> {
+        let expires_at = tokio::time::Instant::now() + ttl;
+        let mut requests = self.requests.lock().await;
+        requests.retain(|_, (_, expires_at)| *expires_at > tokio::time::Instant::now());
+        requests.insert(request.state.clone(), (request.clone(), expires_at));
+        Ok(())
+    }
+
+    async fn take(&self, state: &str) -> Result<Option<AuthorizationState>> {
+        let mut requests = self.requests.lock().await;
+        Ok(requests
+            .remove(state)
+            .filter(|(_, expires_at)| *expires_at > tokio::time::Instant::now())
+            .map(|(request, _)| request))
+    }
+}
+
+/// Redis-backed store of pending authorization requests
+///
+/// Requests are stored as JSON with a Redis expiry, and taken with
+/// `GETDEL` (Redis 6.2 or later), so each callback is accepted once across
+/// all instances.
+#[cfg(feature = "redis")]
+#[derive(Clone)]
+pub struct RedisStateStore {
+    connection: redis::aio::MultiplexedConnection,
+    prefix: String,
+}
+
+#[cfg(feature = "redis")]
+impl RedisStateStore {
+    /// Connects to Redis at `url` (e.g., `redis://127.0.0.1/`).
+    pub async fn connect(url: &str) -> Result<Self> {
+        let client = redis::Client::open(url)?;
+        Ok(Self {
+            connection: client.get_multiplexed_async_connection().await?,
+            prefix: "keyrunes:oauth:".to_string(),
+        })
+    }
+
+    /// Sets the key prefix (default: `keyrunes:oauth:`).
+    pub fn with_prefix<S: Into<String>>(mut self, prefix: S) -> Self {
+        self.prefix = prefix.into();
+        self
+    }
+}
+
+#[cfg(feature = "redis")]
+#[async_trait::async_trait]
+impl StateStore for RedisStateStore {
+    async fn save(&self, request: &AuthorizationState, ttl: Duration) -> Result<()> {
+        let mut connection = self.connection.clone();
+        redis::cmd("SET")
+            .arg(format!("{}{}", self.prefix, request.state))
+            .arg(serde_json::to_string(request)?)
+            .arg("PX")
+            .arg(ttl.as_millis().max(1) as u64)
+            .query_async::<()>(&mut connection)
+            .await?;
+        Ok(())
+    }
+
+    async fn take(&self, state: &str) -> Result<Option<AuthorizationState>> {
+        let mut connection = self.connection.clone();
+        let value: Option<String> = redis::cmd("GETDEL")
+            .arg(format!("{}{}", self.prefix, state))
+            .query_async(&mut connection)
+            .await?;
+        value
+            .map(|value| serde_json::from_str(&value).map_err(Into::into))
+            .transpose()
+    }
+}
+
+/// Keeps the pending authorization request in a signed cookie
+///
+/// Nothing is stored server-side: the request travels to the browser in an
+/// `HttpOnly` cookie, signed with HMAC-SHA256 so that it cannot be forged,
+/// and comes back with the callback. Every instance sharing the secret can
+/// complete the flow. The cookie is signed, not encrypted.
+///
+/// Unlike the [`StateStore`]s, the request is carried by the HTTP exchange:
+/// set [`CookieStateStore::cookie`] on the redirect, and pass the callback's
+/// `Cookie` header to [`CookieStateStore::take`]. A browser runs one flow at
+/// a time: starting another replaces the pending one.
+#[derive(Clone)]
+pub struct CookieStateStore {
+    key: hmac::Key,
+    cookie_name: String,
+    secure: bool,
+}
+
+/// Contents of the state cookie
+#[derive(Serialize, Deserialize)]
+struct SealedState {
+    request: AuthorizationState,
+    expires_at: DateTime<Utc>,
+}
+
+impl CookieStateStore {
+    /// Creates a store signing cookies with `secret` (at least 32 bytes).
+    ///
+    /// Fails with `Other` if the secret is shorter.
+    pub fn new(secret: &[u8]) -> Result<Self> {
+        if secret.len() < 32 {
+            return Err(KeyrunesError::Other(
+                "Cookie state secret must be at least 32 bytes".to_string(),
+            ));
+        }
+        Ok(Self {
+            key: hmac::Key::new(hmac::HMAC_SHA256, secret),
+            cookie_name: DEFAULT_COOKIE_NAME.to_string(),
+            secure: true,
+        })
+    }
+
+    /// Sets the name of the state cookie (default: `keyrunes_oauth_state`).
+    pub fn with_cookie_name<S: Into<String>>(mut self, name: S) -> Self {
+        self.cookie_name = name.into();
+        self
+    }
+
+    /// Sets whether the state cookie is only sent over HTTPS (the default).
+    pub fn with_secure(mut self, secure: bool) -> Self {
+        self.secure = secure;
+        self
+    }
+
+    /// `Set-Cookie` value carrying `request`, expiring after `ttl`.
+    pub fn cookie(&self, request: &AuthorizationState, ttl: Duration) -> Result<String> {
+        let sealed = SealedState {
+            request: request.clone(),
+            expires_at: Utc::now()
+                + chrono::Duration::from_std(ttl)
+                    .map_err(|_| KeyrunesError::Other("Invalid state TTL".to_string()))?,
+        };
+        let payload = URL_SAFE_NO_PAD.encode(serde_json::to_vec(&sealed)?);
+        let signature = URL_SAFE_NO_PAD.encode(hmac::sign(&self.key, payload.as_bytes()));
+        Ok(self.set_cookie(&format!("{}.{}", payload, signature), ttl.as_secs()))
+    }
+
+    /// Returns the request carried by the `Cookie` header if its signature
+    /// is valid, it has not expired, and its state is `state`.
+    ///
+    /// Send [`CookieStateStore::clear_cookie`] with the response, so the
+    /// request cannot be completed twice.
+    pub fn take(&self, cookie_header: Option<&str>, state: &str) -> Option<AuthorizationState> {
+        let value = cookie_header?
+            .split(';')
+            .filter_map(|pair| pair.trim().split_once('='))
+            .find(|(name, _)| *name == self.cookie_name)
+            .map(|(_, value)| value)?;
+        let (payload, signature) = value.split_once('.')?;
+        let signature = URL_SAFE_NO_PAD.decode(signature).ok()?;
+        hmac::verify(&self.key, payload.as_bytes(), &signature).ok()?;
+
+        let sealed: SealedState =
+            serde_json::from_slice(&URL_SAFE_NO_PAD.decode(payload).ok()?).ok()?;
+        (sealed.expires_at > Utc::now() && constant_time_eq(&sealed.request.state, state))
+            .then_some(sealed.request)
+    }
+
+    /// `Set-Cookie` value removing the state cookie.
+    pub fn clear_cookie(&self) -> String {
+        self.set_cookie("", 0)
+    }
+
+    fn set_cookie(&self, value: &str, max_age: u64) -> String {
+        // `Lax` so the cookie comes back with the top-level callback redirect.
+        let mut cookie = format!(
+            "{}={}; Path=/; HttpOnly; SameSite=Lax; Max-Age={}",
+            self.cookie_name, value, max_age
+        );
+        if self.secure {
+            cookie.push_str("; Secure");
+        }
+        cookie
+    }
+}
+
+impl std::fmt::Debug for CookieStateStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CookieStateStore")
+            .field("cookie_name", &self.cookie_name)
+            .field("secure", &self.secure)
+            .finish()
+    }
+}
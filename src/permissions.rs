@@ -0,0 +1,152 @@
+//! Local permission checks
+//!
+//! Rendering a page may require dozens of authorization checks (e.g.,
+//! which menu entries to show). Instead of one HTTP call per check,
+//! [`KeyrunesClient::compile_policy`](crate::KeyrunesClient::compile_policy)
+//! downloads the effective permissions of a user once and returns a
+//! [`PermissionSet`] answering checks locally.
+//!
+//! Permissions are colon-separated keys (e.g., `documents:read`). A `*`
+//! segment matches any segment, and a trailing `*` matches any suffix, so
+//! `documents:*` implies `documents:read` and `documents:read:own`.
+//!
+//! The application's permission catalog can also be declared in code as
+//! [`PermissionDef`] constants and synced to Keyrunes with
+//! [`AdminClient::sync_permissions`](crate::admin::AdminClient::sync_permissions).
+//!
+//! ## Quick Start
+//!
+//! ```
+//! use keyrunes_rust_sdk::permissions::PermissionSet;
+//!
+//! let permissions = PermissionSet::new(["documents:*", "billing:read"]);
+//! assert!(permissions.implies("documents:delete"));
+//! assert!(!permissions.implies("billing:write"));
+//! ```
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+/// Separator between the segments of a permission key
+const SEGMENT_SEPARATOR: char = ':';
+
+/// Wildcard segment
+const WILDCARD: &str = "*";
+
+/// Permission declared by the application
+///
+/// ```
+/// use keyrunes_rust_sdk::permissions::PermissionDef;
+///
+/// pub const CATALOG: &[PermissionDef] = &[
+///     PermissionDef::new("documents:read", "Read documents"),
+///     PermissionDef::new("documents:write", "Create and edit documents"),
+/// ];
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PermissionDef {
+    /// Permission key (e.g., "documents:read")
+    pub key: &'static str,
+    /// Human-readable description
+    pub description: &'static str,
+}
+
+impl PermissionDef {
+    /// Declares a permission.
+    pub const fn new(key: &'static str, description: &'static str) -> Self {
+        Self { key, description }
+    }
+}
+
+/// Effective permissions of a user, checked locally
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "Vec<String>", into = "Vec<String>")]
+pub struct PermissionSet {
+    permissions: BTreeSet<String>,
+}
+
+impl PermissionSet {
+    /// Creates a set from permission keys.
+    pub fn new<I, S>(permissions: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            permissions: permissions.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    /// Checks whether the set holds exactly `permission` (wildcards are not expanded).
+    pub fn contains(&self, permission: &str) -> bool {
+        self.permissions.contains(permission)
+    }
+
+    /// Checks whether `permission` is granted by any permission of the set, including wildcards.
+    pub fn implies(&self, permission: &str) -> bool {
+        self.contains(permission)
+            || self
+                .permissions
+                .iter()
+                .any(|granted| pattern_implies(granted, permission))
+    }
+
+    /// Checks whether every permission is implied.
+    pub fn implies_all<'a, I: IntoIterator<Item = &'a str>>(&self, permissions: I) -> bool {
+        permissions
+            .into_iter()
+            .all(|permission| self.implies(permission))
+    }
+
+    /// Checks whether at least one permission is implied.
+    pub fn implies_any<'a, I: IntoIterator<Item = &'a str>>(&self, permissions: I) -> bool {
+        permissions
+            .into_iter()
+            .any(|permission| self.implies(permission))
+    }
+
+    /// Returns the permission keys, sorted.
+    pub fn iter(&self) -> impl Iterator<Item = &str> {
+        self.permissions.iter().map(String::as_str)
+    }
+
+    /// Returns the number of permission keys.
+    pub fn len(&self) -> usize {
+        self.permissions.len()
+    }
+
+    /// Checks whether the set is empty.
+    pub fn is_empty(&self) -> bool {
+        self.permissions.is_empty()
+    }
+}
+
+impl From<Vec<String>> for PermissionSet {
+    fn from(permissions: Vec<String>) -> Self {
+        Self::new(permissions)
+    }
+}
+
+impl From<PermissionSet> for Vec<String> {
+    fn from(set: PermissionSet) -> Self {
+        set.permissions.into_iter().collect()
+    }
+}
+
+/// Checks whether the granted pattern covers `permission`
+fn pattern_implies(granted: &str, permission: &str) -> bool {
+    let mut granted = granted.split(SEGMENT_SEPARATOR).peekable();
+    let mut requested = permission.split(SEGMENT_SEPARATOR);
+
+    while let Some(segment) = granted.next() {
+        let last = granted.peek().is_none();
+        match requested.next() {
+            // A trailing wildcard covers any remaining segments.
+            Some(_) if segment == WILDCARD && last => return true,
+            Some(requested) if segment == WILDCARD || segment == requested => {}
+            _ => return false,
+        }
+    }
+
+    requested.next().is_none()
+}
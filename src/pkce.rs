@@ -0,0 +1,131 @@
+//! PKCE, state and nonce helpers for OAuth flows (feature `oauth`)
+//!
+//! Authorization code flows bind the authorization request to the callback
+//! with three random values:
+//!
+//! - the PKCE code verifier (RFC 7636): its S256 challenge goes in the
+//!   authorization request, the verifier itself in the token request;
+//! - the `state`, echoed back on the callback to reject forged callbacks;
+//! - the OIDC `nonce`, echoed back in the ID token to reject replayed tokens.
+//!
+//! Comparisons of received values against stored ones run in constant time.
+//!
+//! ```
+//! use keyrunes_rust_sdk::pkce::{self, Pkce};
+//!
+//! let pkce = Pkce::generate();
+//! let state = pkce::generate_state();
+//! // Redirect to ...?code_challenge={pkce.challenge}&code_challenge_method=S256&state={state}
+//!
+//! // On the callback, check the state before exchanging the code
+//! assert!(pkce::verify_state(&state, &state));
+//! assert!(pkce.verify(&pkce.challenge));
+//! ```
+
+use crate::compare::constant_time_eq;
+use crate::error::{KeyrunesError, Result};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+
+/// Code challenge method of the challenges computed here
+pub const CHALLENGE_METHOD: &str = "S256";
+
+/// Random bytes in verifiers, states and nonces (43 characters once encoded)
+const RANDOM_BYTES: usize = 32;
+
+/// Length bounds of a code verifier (RFC 7636, section 4.1)
+const VERIFIER_LENGTH: std::ops::RangeInclusive<usize> = 43..=128;
+
+/// PKCE code verifier and its S256 challenge
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pkce {
+    /// Code verifier, sent in the token request (keep it secret until then)
+    pub verifier: String,
+    /// Code challenge, sent in the authorization request
+    pub challenge: String,
+}
+
+impl Pkce {
+    /// Generates a random code verifier and its challenge.
+    pub fn generate() -> Self {
+        let verifier = random_string();
+        Self {
+            challenge: challenge(&verifier),
+            verifier,
+        }
+    }
+
+    /// Computes the challenge of an existing code verifier.
+    ///
+    /// Fails with `Other` if the verifier is not 43 to 128 characters from
+    /// the unreserved set (`A-Z a-z 0-9 - . _ ~`).
+    pub fn from_verifier<S: Into<String>>(verifier: S) -> Result<Self> {
+        let verifier = verifier.into();
+        if !is_valid_verifier(&verifier) {
+            return Err(KeyrunesError::Other(
+                "Invalid PKCE code verifier".to_string(),
+            ));
+        }
+        Ok(Self {
+            challenge: challenge(&verifier),
+            verifier,
+        })
+    }
+
+    /// Code challenge method (`S256`)
+    pub fn method(&self) -> &'static str {
+        CHALLENGE_METHOD
+    }
+
+    /// Whether `challenge` is the challenge of this verifier.
+    pub fn verify(&self, challenge: &str) -> bool {
+        constant_time_eq(&self.challenge, challenge)
+    }
+}
+
+/// Computes the S256 challenge of a code verifier.
+pub fn challenge(verifier: &str) -> String {
+    URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes()))
+}
+
+/// Whether `verifier` matches `challenge` (S256), as an authorization
+/// server would check it.
+pub fn verify_challenge(verifier: &str, challenge: &str) -> bool {
+    is_valid_verifier(verifier) && constant_time_eq(&self::challenge(verifier), challenge)
+}
+
+/// Generates a random `state` for an authorization request.
+pub fn generate_state() -> String {
+    random_string()
+}
+
+/// Generates a random OIDC `nonce` for an authorization request.
+pub fn generate_nonce() -> String {
+    random_string()
+}
+
+/// Whether the `state` received on the callback is the one sent.
+pub fn verify_state(expected: &str, received: &str) -> bool {
+    !expected.is_empty() && constant_time_eq(expected, received)
+}
+
+/// Whether the `nonce` of an ID token is the one sent.
+pub fn verify_nonce(expected: &str, received: &str) -> bool {
+    !expected.is_empty() && constant_time_eq(expected, received)
+}
+
+fn is_valid_verifier(verifier: &str) -> bool {
+    VERIFIER_LENGTH.contains(&verifier.len())
+        && verifier
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'-' | b'.' | b'_' | b'~'))
+}
+
+/// Base64url encoding of random bytes
+fn random_string() -> String {
+    let mut bytes = [0u8; RANDOM_BYTES];
+    rand::rngs::OsRng.fill_bytes(&mut bytes);
+    URL_SAFE_NO_PAD.encode(bytes)
+}
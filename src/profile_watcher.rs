@@ -0,0 +1,202 @@
+//! Change detection for the current user's profile
+//!
+//! Group memberships can change while a user is signed in. The
+//! [`ProfileWatcher`] polls the current user with conditional requests
+//! ([`KeyrunesClient::get_current_user_if_modified`]), so unchanged
+//! profiles cost a 304 without a body, and invokes callbacks when the
+//! profile or the groups change (e.g., to push live UI updates over a
+//! websocket).
+//!
+//! Profiles received by other means, such as a webhook relayed by the
+//! backend, can be fed to the same change detection with
+//! [`ProfileWatcher::observe`].
+//!
+//! ## Quick Start
+//!
+//! ```
+//! use keyrunes_rust_sdk::profile_watcher::ProfileWatcher;
+//! use keyrunes_rust_sdk::KeyrunesClient;
+//! use std::time::Duration;
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = KeyrunesClient::new("https://keyrunes.example.com")?;
+//! client.login("user@example.com", "password123", None).await?;
+//!
+//! let watcher = ProfileWatcher::new(client).on_groups_change(|change| {
+//!     println!("Joined {:?}, left {:?}", change.groups_added, change.groups_removed);
+//! });
+//! let _poller = watcher.spawn(Duration::from_secs(30));
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::client::KeyrunesClient;
+use crate::error::Result;
+use crate::models::User;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Callback invoked when the watched profile changes
+pub type ProfileCallback = Arc<dyn Fn(&ProfileChange) + Send + Sync>;
+
+/// Change of the current user's profile
+#[derive(Debug, Clone)]
+pub struct ProfileChange {
+    /// Profile before the change
+    pub previous: User,
+    /// Profile after the change
+    pub current: User,
+    /// Groups the user joined
+    pub groups_added: Vec<String>,
+    /// Groups the user left
+    pub groups_removed: Vec<String>,
+}
+
+impl ProfileChange {
+    fn new(previous: User, current: User) -> Self {
+        let groups_added = current
+            .groups
+            .iter()
+            .filter(|group| !previous.groups.contains(group))
+            .cloned()
+            .collect();
+        let groups_removed = previous
+            .groups
+            .iter()
+            .filter(|group| !current.groups.contains(group))
+            .cloned()
+            .collect();
+        Self {
+            previous,
+            current,
+            groups_added,
+            groups_removed,
+        }
+    }
+
+    /// Whether the user joined or left groups.
+    pub fn groups_changed(&self) -> bool {
+        !self.groups_added.is_empty() || !self.groups_removed.is_empty()
+    }
+}
+
+/// Watcher of the current user's profile
+///
+/// The first profile seen is the baseline and is not reported; callbacks
+/// are invoked for every later profile that differs from the last one.
+/// Cloning a watcher is cheap and shares the last seen profile.
+#[derive(Clone)]
+pub struct ProfileWatcher {
+    client: KeyrunesClient,
+    last: Arc<Mutex<Option<User>>>,
+    on_change: Vec<ProfileCallback>,
+    on_groups_change: Vec<ProfileCallback>,
+}
+
+impl ProfileWatcher {
+    /// Creates a watcher of the profile of the user signed in on `client`.
+    pub fn new(client: KeyrunesClient) -> Self {
+        Self {
+            client,
+            last: Arc::new(Mutex::new(None)),
+            on_change: Vec::new(),
+            on_groups_change: Vec::new(),
+        }
+    }
+
+    /// Invokes `callback` when any field of the profile changes.
+    pub fn on_change<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(&ProfileChange) + Send + Sync + 'static,
+    {
+        self.on_change.push(Arc::new(callback));
+        self
+    }
+
+    /// Invokes `callback` when the user joins or leaves groups.
+    pub fn on_groups_change<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(&ProfileChange) + Send + Sync + 'static,
+    {
+        self.on_groups_change.push(Arc::new(callback));
+        self
+    }
+
+    /// Returns the last profile seen.
+    pub fn current(&self) -> Option<User> {
+        self.last.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Fetches the profile if it changed, and reports the change.
+    ///
+    /// The first poll fetches the profile unconditionally to set the
+    /// baseline: the client may already hold validators for it, and a 304
+    /// would not tell what the profile is.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<Option<ProfileChange>, KeyrunesError>`:
+    /// - `Ok(Some(change))` if the profile changed (callbacks were invoked)
+    /// - `Ok(None)` if the profile is unchanged, or this is the baseline
+    /// - `Err(KeyrunesError::AuthenticationError)` if not authenticated or token is invalid
+    pub async fn poll(&self) -> Result<Option<ProfileChange>> {
+        let user = if self.current().is_none() {
+            Some(self.client.get_current_user().await?)
+        } else {
+            self.client.get_current_user_if_modified().await?
+        };
+        Ok(user.and_then(|user| self.observe(user)))
+    }
+
+    /// Compares a profile obtained by other means (e.g., from a webhook)
+    /// with the last one seen, and reports the change.
+    ///
+    /// Returns the change, if any; callbacks are invoked before returning.
+    pub fn observe(&self, user: User) -> Option<ProfileChange> {
+        let previous = {
+            let mut last = self.last.lock().unwrap_or_else(|e| e.into_inner());
+            match last.as_ref() {
+                Some(previous) if previous == &user => return None,
+                _ => last.replace(user.clone())?,
+            }
+        };
+
+        let change = ProfileChange::new(previous, user);
+        for callback in &self.on_change {
+            callback(&change);
+        }
+        if change.groups_changed() {
+            for callback in &self.on_groups_change {
+                callback(&change);
+            }
+        }
+        Some(change)
+    }
+
+    /// Spawns a task polling the profile every `interval`.
+    ///
+    /// Abort the returned handle, or shut the client down, to stop polling.
+    pub fn spawn(&self, interval: Duration) -> tokio::task::JoinHandle<()> {
+        let watcher = self.clone();
+        let handle = tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                // Failed polls are retried on the next tick.
+                let _ = watcher.poll().await;
+            }
+        });
+        self.client.background.track_task(handle.abort_handle());
+        handle
+    }
+}
+
+impl std::fmt::Debug for ProfileWatcher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ProfileWatcher")
+            .field("current", &self.current())
+            .field("on_change", &self.on_change.len())
+            .field("on_groups_change", &self.on_groups_change.len())
+            .finish()
+    }
+}
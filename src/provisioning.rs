@@ -0,0 +1,570 @@
+//! Declarative provisioning of groups, permissions and service accounts
+//!
+//! A [`ProvisioningSpec`] declares the access model an environment needs:
+//! groups, the permission catalog, the permissions granted to each group
+//! (role bindings) and service accounts. [`ProvisioningSpec::plan`]
+//! compares it with the tenant and returns the [`Plan`] of changes to make,
+//! which [`Plan::apply`] applies through the administration endpoints.
+//!
+//! Resources are created or updated, never deleted: applying the same spec
+//! twice is a no-op, and resources managed by hand are left alone. If a
+//! change fails, the changes already applied are undone, in reverse order,
+//! before the error is returned.
+//!
+//! ## Quick Start
+//!
+//! ```
+//! use keyrunes_rust_sdk::provisioning::ProvisioningSpec;
+//! use keyrunes_rust_sdk::KeyrunesClient;
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = KeyrunesClient::new("https://keyrunes.example.com")?;
+//! client.login("admin@example.com", "password123", None).await?;
+//! let spec = ProvisioningSpec::from_json(br#"{
+//!     "groups": [{"name": "engineering", "description": "Engineering team"}],
+//!     "permissions": [{"key": "deploys:create", "description": "Start deploys"}],
+//!     "role_bindings": [{"group": "engineering", "permission": "deploys:create"}],
+//!     "service_accounts": [{"name": "ci", "groups": ["engineering"]}]
+//! }"#)?;
+//!
+//! let plan = spec.plan(&client).await?;
+//! println!("{}", plan);
+//! let report = plan.apply(&client).await?;
+//! for account in &report.service_accounts {
+//!     println!("{}: {:?}", account.name, account.client_secret);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::admin::AdminClient;
+use crate::client::KeyrunesClient;
+use crate::error::{KeyrunesError, Result};
+use crate::groups::{KnownGroups, KnownPermissions};
+use crate::models::{Permission, ServiceAccount};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashSet};
+use std::fmt;
+
+/// Access model of an environment
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProvisioningSpec {
+    /// Groups
+    #[serde(default)]
+    pub groups: Vec<GroupSpec>,
+    /// Permissions of the catalog
+    #[serde(default)]
+    pub permissions: Vec<PermissionSpec>,
+    /// Permissions granted to groups
+    #[serde(default)]
+    pub role_bindings: Vec<RoleBindingSpec>,
+    /// Service accounts
+    #[serde(default)]
+    pub service_accounts: Vec<ServiceAccountSpec>,
+}
+
+/// Group declared in a [`ProvisioningSpec`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GroupSpec {
+    /// Group name
+    pub name: String,
+    /// Description, left unchanged when `None`
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub description: Option<String>,
+}
+
+/// Permission declared in a [`ProvisioningSpec`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PermissionSpec {
+    /// Permission key (e.g., "documents:read")
+    pub key: String,
+    /// Human-readable description
+    pub description: String,
+}
+
+/// Permission granted to a group, declared in a [`ProvisioningSpec`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RoleBindingSpec {
+    /// Group name
+    pub group: String,
+    /// Permission key
+    pub permission: String,
+}
+
+/// Service account declared in a [`ProvisioningSpec`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ServiceAccountSpec {
+    /// Account name
+    pub name: String,
+    /// Description, left unchanged when `None`
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub description: Option<String>,
+    /// Names of the groups of the account (replacing its current groups)
+    #[serde(default)]
+    pub groups: Vec<String>,
+}
+
+impl ProvisioningSpec {
+    /// Reads a spec from JSON.
+    ///
+    /// Fails with `SerializationError` if the JSON does not match the spec
+    /// (unknown fields included), or `InvalidProvisioningSpec` if the spec
+    /// is inconsistent (see [`Self::validate`]).
+    pub fn from_json(data: &[u8]) -> Result<Self> {
+        let spec: Self = serde_json::from_slice(data)?;
+        spec.validate()?;
+        Ok(spec)
+    }
+
+    /// Declares the groups of `G` (see [`keyrunes_groups!`](crate::keyrunes_groups)),
+    /// in addition to the groups already declared.
+    pub fn with_known_groups<G: KnownGroups>(mut self) -> Self {
+        for group in G::ALL {
+            if !self.groups.iter().any(|spec| spec.name == group.name()) {
+                self.groups.push(GroupSpec {
+                    name: group.name().to_string(),
+                    description: None,
+                });
+            }
+        }
+        self
+    }
+
+    /// Declares the permissions of `P` (see
+    /// [`keyrunes_permissions!`](crate::keyrunes_permissions)), in addition
+    /// to the permissions already declared, with the descriptions of `P`.
+    pub fn with_known_permissions<P: KnownPermissions>(mut self) -> Self {
+        for permission in P::ALL {
+            if !self
+                .permissions
+                .iter()
+                .any(|spec| spec.key == permission.key())
+            {
+                self.permissions.push(PermissionSpec {
+                    key: permission.key().to_string(),
+                    description: permission.description().to_string(),
+                });
+            }
+        }
+        self
+    }
+
+    /// Checks that names are unique and that role bindings and service
+    /// accounts reference declared groups and permissions.
+    ///
+    /// References to groups and permissions that only exist on the tenant
+    /// are checked by [`Self::plan`].
+    pub fn validate(&self) -> Result<()> {
+        unique("group", self.groups.iter().map(|group| group.name.as_str()))?;
+        unique(
+            "permission",
+            self.permissions
+                .iter()
+                .map(|permission| permission.key.as_str()),
+        )?;
+        unique(
+            "service account",
+            self.service_accounts
+                .iter()
+                .map(|account| account.name.as_str()),
+        )?;
+        let mut bindings = HashSet::new();
+        for binding in &self.role_bindings {
+            if !bindings.insert((&binding.group, &binding.permission)) {
+                return Err(KeyrunesError::InvalidProvisioningSpec(format!(
+                    "duplicate role binding {} -> {}",
+                    binding.group, binding.permission
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Compares the spec with the tenant and returns the changes to apply.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<Plan, KeyrunesError>`:
+    /// - `Ok(plan)` with the changes, empty if the tenant matches the spec
+    /// - `Err(KeyrunesError::InvalidProvisioningSpec)` if the spec is inconsistent or
+    ///   references a group or permission that does not exist
+    /// - `Err(KeyrunesError::AuthorizationError)` if the token is not an administrator's
+    pub async fn plan(&self, client: &KeyrunesClient) -> Result<Plan> {
+        self.validate()?;
+        let admin = client.admin();
+        let groups = admin.list_groups().await?;
+        let permissions = admin.list_permissions().await?;
+        let bindings = admin.list_role_bindings().await?;
+        let accounts = admin.list_service_accounts().await?;
+        let mut changes = Vec::new();
+
+        for spec in &self.groups {
+            match groups.iter().find(|group| group.name == spec.name) {
+                None => changes.push(Change::CreateGroup(spec.clone())),
+                Some(group)
+                    if spec.description.is_some() && spec.description != group.description =>
+                {
+                    changes.push(Change::UpdateGroup {
+                        id: group.id.clone(),
+                        previous: group.description.clone(),
+                        spec: spec.clone(),
+                    })
+                }
+                Some(_) => {}
+            }
+        }
+
+        for spec in &self.permissions {
+            match permissions
+                .iter()
+                .find(|permission| permission.key == spec.key)
+            {
+                None => changes.push(Change::CreatePermission(spec.clone())),
+                Some(permission)
+                    if permission.deprecated
+                        || permission.description.as_deref() != Some(&spec.description) =>
+                {
+                    changes.push(Change::UpdatePermission {
+                        previous: permission.clone(),
+                        spec: spec.clone(),
+                    })
+                }
+                Some(_) => {}
+            }
+        }
+
+        let group_exists = |name: &str| {
+            self.groups.iter().any(|group| group.name == name)
+                || groups.iter().any(|group| group.name == name)
+        };
+        for spec in &self.role_bindings {
+            if !group_exists(&spec.group) {
+                return Err(unknown_reference("group", &spec.group));
+            }
+            if !self.permissions.iter().any(|p| p.key == spec.permission)
+                && !permissions.iter().any(|p| p.key == spec.permission)
+            {
+                return Err(unknown_reference("permission", &spec.permission));
+            }
+            if !bindings
+                .iter()
+                .any(|b| b.group == spec.group && b.permission == spec.permission)
+            {
+                changes.push(Change::CreateRoleBinding(spec.clone()));
+            }
+        }
+
+        for spec in &self.service_accounts {
+            if let Some(group) = spec.groups.iter().find(|group| !group_exists(group)) {
+                return Err(unknown_reference("group", group));
+            }
+            match accounts.iter().find(|account| account.name == spec.name) {
+                None => changes.push(Change::CreateServiceAccount(spec.clone())),
+                Some(account)
+                    if (spec.description.is_some() && spec.description != account.description)
+                        || spec.groups.iter().collect::<BTreeSet<_>>()
+                            != account.groups.iter().collect::<BTreeSet<_>>() =>
+                {
+                    changes.push(Change::UpdateServiceAccount {
+                        previous: account.clone(),
+                        spec: spec.clone(),
+                    })
+                }
+                Some(_) => {}
+            }
+        }
+
+        Ok(Plan { changes })
+    }
+
+    /// Plans and applies the spec (see [`Self::plan`] and [`Plan::apply`]).
+    pub async fn apply(&self, client: &KeyrunesClient) -> Result<ApplyReport> {
+        self.plan(client).await?.apply(client).await
+    }
+}
+
+/// Change of a [`Plan`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Change {
+    /// Creates a group
+    CreateGroup(GroupSpec),
+    /// Updates the description of a group
+    UpdateGroup {
+        /// Group ID
+        id: String,
+        /// Current description
+        previous: Option<String>,
+        /// Declared group
+        spec: GroupSpec,
+    },
+    /// Adds a permission to the catalog
+    CreatePermission(PermissionSpec),
+    /// Updates the description of a permission, or re-declares a deprecated one
+    UpdatePermission {
+        /// Current permission
+        previous: Permission,
+        /// Declared permission
+        spec: PermissionSpec,
+    },
+    /// Grants a permission to a group
+    CreateRoleBinding(RoleBindingSpec),
+    /// Creates a service account
+    CreateServiceAccount(ServiceAccountSpec),
+    /// Updates the description or the groups of a service account
+    UpdateServiceAccount {
+        /// Current account
+        previous: ServiceAccount,
+        /// Declared account
+        spec: ServiceAccountSpec,
+    },
+}
+
+impl Change {
+    /// Checks whether the change creates a resource.
+    pub fn is_create(&self) -> bool {
+        matches!(
+            self,
+            Change::CreateGroup(_)
+                | Change::CreatePermission(_)
+                | Change::CreateRoleBinding(_)
+                | Change::CreateServiceAccount(_)
+        )
+    }
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Change::CreateGroup(spec) => write!(f, "+ group {}", spec.name),
+            Change::UpdateGroup { spec, .. } => write!(f, "~ group {}", spec.name),
+            Change::CreatePermission(spec) => write!(f, "+ permission {}", spec.key),
+            Change::UpdatePermission { spec, .. } => write!(f, "~ permission {}", spec.key),
+            Change::CreateRoleBinding(spec) => {
+                write!(f, "+ role binding {} -> {}", spec.group, spec.permission)
+            }
+            Change::CreateServiceAccount(spec) => write!(f, "+ service account {}", spec.name),
+            Change::UpdateServiceAccount { spec, .. } => {
+                write!(f, "~ service account {}", spec.name)
+            }
+        }
+    }
+}
+
+/// Changes needed for the tenant to match a [`ProvisioningSpec`]
+///
+/// Displayed like a `terraform plan`: one line per change (`+` for
+/// creations, `~` for updates) and a summary.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Plan {
+    /// Changes, in the order they are applied
+    pub changes: Vec<Change>,
+}
+
+impl Plan {
+    /// Checks whether the tenant already matches the spec.
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Applies the changes, in order.
+    ///
+    /// If a change fails, the changes already applied are undone in
+    /// reverse order (created resources are deleted, updated ones
+    /// restored) before the error is returned.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<ApplyReport, KeyrunesError>`:
+    /// - `Ok(report)` if every change was applied
+    /// - `Err(KeyrunesError::ProvisioningFailed)` with the failed change and whether the
+    ///   rollback succeeded
+    pub async fn apply(&self, client: &KeyrunesClient) -> Result<ApplyReport> {
+        let admin = client.admin();
+        let mut report = ApplyReport::default();
+        let mut undo = Vec::new();
+
+        for change in &self.changes {
+            match apply_change(&admin, change, &mut report).await {
+                Ok(step) => undo.push(step),
+                Err(source) => {
+                    let rolled_back = rollback(&admin, undo).await;
+                    return Err(KeyrunesError::ProvisioningFailed {
+                        change: change.to_string(),
+                        source: Box::new(source),
+                        rolled_back,
+                    });
+                }
+            }
+        }
+
+        Ok(report)
+    }
+}
+
+impl fmt::Display for Plan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return write!(f, "No changes.");
+        }
+        for change in &self.changes {
+            writeln!(f, "{}", change)?;
+        }
+        let created = self.changes.iter().filter(|c| c.is_create()).count();
+        write!(
+            f,
+            "Plan: {} to create, {} to update.",
+            created,
+            self.changes.len() - created
+        )
+    }
+}
+
+/// Result of [`Plan::apply`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ApplyReport {
+    /// Number of applied changes
+    pub applied: usize,
+    /// Created service accounts, with their client secrets (only available now)
+    pub service_accounts: Vec<ServiceAccount>,
+}
+
+/// Reverts an applied change
+enum Undo {
+    DeleteGroup(String),
+    RestoreGroup { id: String, description: String },
+    DeletePermission(String),
+    RestorePermission(Permission),
+    DeleteRoleBinding(String),
+    DeleteServiceAccount(String),
+    RestoreServiceAccount(ServiceAccount),
+}
+
+/// Applies one change, returning how to revert it
+async fn apply_change(
+    admin: &AdminClient<'_>,
+    change: &Change,
+    report: &mut ApplyReport,
+) -> Result<Undo> {
+    let undo = match change {
+        Change::CreateGroup(spec) => {
+            let group = admin
+                .create_group(&spec.name, spec.description.as_deref())
+                .await?;
+            Undo::DeleteGroup(group.id)
+        }
+        Change::UpdateGroup { id, previous, spec } => {
+            admin
+                .update_group(id, spec.description.as_deref().unwrap_or_default())
+                .await?;
+            Undo::RestoreGroup {
+                id: id.clone(),
+                description: previous.clone().unwrap_or_default(),
+            }
+        }
+        Change::CreatePermission(spec) => {
+            admin
+                .create_permission(&spec.key, &spec.description)
+                .await?;
+            Undo::DeletePermission(spec.key.clone())
+        }
+        Change::UpdatePermission { previous, spec } => {
+            admin
+                .update_permission(&spec.key, Some(&spec.description), Some(false))
+                .await?;
+            Undo::RestorePermission(previous.clone())
+        }
+        Change::CreateRoleBinding(spec) => {
+            let binding = admin
+                .create_role_binding(&spec.group, &spec.permission)
+                .await?;
+            Undo::DeleteRoleBinding(binding.id)
+        }
+        Change::CreateServiceAccount(spec) => {
+            let account = admin
+                .create_service_account(&spec.name, spec.description.as_deref(), &spec.groups)
+                .await?;
+            let undo = Undo::DeleteServiceAccount(account.id.clone());
+            report.service_accounts.push(account);
+            undo
+        }
+        Change::UpdateServiceAccount { previous, spec } => {
+            admin
+                .update_service_account(
+                    &previous.id,
+                    spec.description.as_deref(),
+                    Some(&spec.groups),
+                )
+                .await?;
+            Undo::RestoreServiceAccount(previous.clone())
+        }
+    };
+    report.applied += 1;
+    Ok(undo)
+}
+
+/// Reverts applied changes in reverse order, returning whether all succeeded
+async fn rollback(admin: &AdminClient<'_>, undo: Vec<Undo>) -> bool {
+    let mut complete = true;
+    for step in undo.into_iter().rev() {
+        let result = match step {
+            Undo::DeleteGroup(id) => admin.delete_group(&id).await,
+            Undo::RestoreGroup { id, description } => {
+                admin.update_group(&id, &description).await.map(drop)
+            }
+            Undo::DeletePermission(key) => admin.delete_permission(&key).await,
+            Undo::RestorePermission(permission) => admin
+                .update_permission(
+                    &permission.key,
+                    permission.description.as_deref(),
+                    Some(permission.deprecated),
+                )
+                .await
+                .map(drop),
+            Undo::DeleteRoleBinding(id) => admin.delete_role_binding(&id).await,
+            Undo::DeleteServiceAccount(id) => admin.delete_service_account(&id).await,
+            Undo::RestoreServiceAccount(account) => admin
+                .update_service_account(
+                    &account.id,
+                    account.description.as_deref(),
+                    Some(&account.groups),
+                )
+                .await
+                .map(drop),
+        };
+        complete &= result.is_ok();
+    }
+    complete
+}
+
+/// Fails if a name is declared twice
+fn unique<'a>(kind: &str, names: impl Iterator<Item = &'a str>) -> Result<()> {
+    let mut seen = HashSet::new();
+    for name in names {
+        if name.trim().is_empty() {
+            return Err(KeyrunesError::InvalidProvisioningSpec(format!(
+                "{} with an empty name",
+                kind
+            )));
+        }
+        if !seen.insert(name) {
+            return Err(KeyrunesError::InvalidProvisioningSpec(format!(
+                "duplicate {} {}",
+                kind, name
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Error for a reference to a group or permission declared nowhere
+fn unknown_reference(kind: &str, name: &str) -> KeyrunesError {
+    KeyrunesError::InvalidProvisioningSpec(format!(
+        "unknown {} {} (neither declared nor on the tenant)",
+        kind, name
+    ))
+}
@@ -0,0 +1,214 @@
+//! Batched consumption of metered entitlements
+//!
+//! Reporting every unit of a metered entitlement to Keyrunes costs one
+//! round trip per request. The [`QuotaBatcher`] keeps an optimistic local
+//! counter per user and meter instead: consumption is checked against the
+//! last known usage and reported in batches, either when enough units are
+//! pending or periodically.
+//!
+//! Because checks are local, several instances may together overshoot a
+//! quota by up to their pending units; the server remains authoritative
+//! and its answer replaces the local counter on every flush.
+//!
+//! ## Quick Start
+//!
+//! ```
+//! use keyrunes_rust_sdk::quota::QuotaBatcher;
+//! use keyrunes_rust_sdk::KeyrunesClient;
+//! use std::time::Duration;
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = KeyrunesClient::new("https://keyrunes.example.com")?;
+//! let batcher = QuotaBatcher::new(client).with_max_pending(100);
+//! let _flusher = batcher.spawn_flusher(Duration::from_secs(10));
+//!
+//! if !batcher.consume("123", "api_calls", 1).await? {
+//!     println!("Quota exhausted");
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::client::shutdown::Flush;
+use crate::client::KeyrunesClient;
+use crate::error::{KeyrunesError, Result};
+use crate::models::Quota;
+use std::collections::HashMap;
+use std::sync::{Arc, Weak};
+use std::time::Duration;
+use tokio::sync::Mutex;
+
+/// Local counters, by user ID and meter
+type Counters = Mutex<HashMap<(String, String), Counter>>;
+
+/// Local state of one user/meter pair
+#[derive(Debug)]
+struct Counter {
+    /// Last usage reported by the server
+    quota: Quota,
+    /// Units consumed locally and not yet reported
+    pending: u64,
+}
+
+impl Counter {
+    fn remaining(&self) -> Option<u64> {
+        self.quota
+            .remaining()
+            .map(|remaining| remaining.saturating_sub(self.pending))
+    }
+}
+
+/// Optimistic, batching quota consumer
+///
+/// Cloning a batcher is cheap and shares the local counters.
+#[derive(Clone)]
+pub struct QuotaBatcher {
+    client: KeyrunesClient,
+    counters: Arc<Counters>,
+    max_pending: u64,
+}
+
+impl QuotaBatcher {
+    /// Creates a batcher reporting to Keyrunes through `client`.
+    ///
+    /// By default, consumption is reported once 50 units are pending.
+    /// Pending units are also reported by [`KeyrunesClient::shutdown`].
+    pub fn new(client: KeyrunesClient) -> Self {
+        let counters: Arc<Counters> = Arc::new(Mutex::new(HashMap::new()));
+        client
+            .background
+            .track_batch(Arc::downgrade(&counters) as Weak<dyn Flush>);
+        Self {
+            client,
+            counters,
+            max_pending: 50,
+        }
+    }
+
+    /// Sets the number of pending units that triggers a report.
+    pub fn with_max_pending(mut self, max_pending: u64) -> Self {
+        self.max_pending = max_pending.max(1);
+        self
+    }
+
+    /// Consumes units locally, reporting them when the batch is full.
+    ///
+    /// The usage is fetched from Keyrunes the first time a user/meter pair
+    /// is seen.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<bool, KeyrunesError>`:
+    /// - `Ok(true)` if the units were consumed
+    /// - `Ok(false)` if the quota is exhausted (nothing is consumed)
+    pub async fn consume(&self, user_id: &str, meter: &str, amount: u64) -> Result<bool> {
+        let key = (user_id.to_string(), meter.to_string());
+        let mut counters = self.counters.lock().await;
+
+        if !counters.contains_key(&key) {
+            let quota = self.client.get_quota(user_id, meter).await?;
+            counters.insert(key.clone(), Counter { quota, pending: 0 });
+        }
+        let Some(counter) = counters.get_mut(&key) else {
+            return Ok(false);
+        };
+
+        if counter
+            .remaining()
+            .is_some_and(|remaining| remaining < amount)
+        {
+            return Ok(false);
+        }
+        counter.pending += amount;
+
+        if counter.pending >= self.max_pending {
+            report(&self.client, counter, user_id, meter).await?;
+        }
+        Ok(true)
+    }
+
+    /// Returns the locally known remaining units (`None` if unknown or unlimited).
+    pub async fn remaining(&self, user_id: &str, meter: &str) -> Option<u64> {
+        let key = (user_id.to_string(), meter.to_string());
+        self.counters
+            .lock()
+            .await
+            .get(&key)
+            .and_then(Counter::remaining)
+    }
+
+    /// Reports all pending units to Keyrunes.
+    ///
+    /// Every counter is flushed even if some reports fail; the first error
+    /// is returned.
+    pub async fn flush(&self) -> Result<()> {
+        self.counters.flush(&self.client).await
+    }
+
+    /// Spawns a task flushing pending units every `interval`.
+    ///
+    /// Abort the returned handle, or shut the client down, to stop flushing.
+    pub fn spawn_flusher(&self, interval: Duration) -> tokio::task::JoinHandle<()> {
+        let batcher = self.clone();
+        let handle = tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.tick().await;
+            loop {
+                ticker.tick().await;
+                // Failed reports stay pending and are retried on the next tick.
+                let _ = batcher.flush().await;
+            }
+        });
+        self.client.background.track_task(handle.abort_handle());
+        handle
+    }
+}
+
+#[async_trait::async_trait]
+impl Flush for Counters {
+    async fn flush(&self, client: &KeyrunesClient) -> Result<()> {
+        let mut counters = self.lock().await;
+        let mut first_error = None;
+
+        for ((user_id, meter), counter) in counters.iter_mut() {
+            if counter.pending == 0 {
+                continue;
+            }
+            if let Err(err) = report(client, counter, user_id, meter).await {
+                first_error.get_or_insert(err);
+            }
+        }
+
+        first_error.map_or(Ok(()), Err)
+    }
+}
+
+async fn report(
+    client: &KeyrunesClient,
+    counter: &mut Counter,
+    user_id: &str,
+    meter: &str,
+) -> Result<()> {
+    match client.consume_quota(user_id, meter, counter.pending).await {
+        Ok(quota) => {
+            counter.quota = quota;
+            counter.pending = 0;
+            Ok(())
+        }
+        Err(KeyrunesError::QuotaExceeded(meter)) => {
+            // The server rejected the batch: resynchronize with its view.
+            counter.quota = client.get_quota(user_id, &meter).await?;
+            counter.pending = 0;
+            Err(KeyrunesError::QuotaExceeded(meter))
+        }
+        Err(err) => Err(err),
+    }
+}
+
+impl std::fmt::Debug for QuotaBatcher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("QuotaBatcher")
+            .field("max_pending", &self.max_pending)
+            .finish()
+    }
+}
@@ -0,0 +1,312 @@
+//! Rate limiting keyed by Keyrunes identity
+//!
+//! This module contains a token-bucket [`RateLimiter`] that limits requests
+//! per authenticated user instead of per IP address, which breaks behind
+//! load balancers. Buckets are kept in a pluggable [`RateLimitStore`]:
+//! [`InMemoryRateLimitStore`] for single-instance deployments, and
+//! `RedisRateLimitStore` (feature `redis`) when several instances must
+//! share quotas.
+//!
+//! The framework integrations in [`middleware`](crate::middleware) apply
+//! a limiter to the user resolved by the authentication extractors.
+//!
+//! ## Quick Start
+//!
+//! ```
+//! use keyrunes_rust_sdk::rate_limit::{RateLimitQuota, RateLimiter};
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let limiter = RateLimiter::in_memory(RateLimitQuota::per_minute(60));
+//! let decision = limiter.check("user-123").await?;
+//! if !decision.allowed {
+//!     println!("Retry after {:?}", decision.retry_after);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::error::Result;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+/// Rate limit quota for a token bucket
+///
+/// A bucket holds up to `burst` tokens and is refilled at `requests`
+/// tokens per `period`. Each request consumes one token.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RateLimitQuota {
+    /// Number of requests allowed per period
+    pub requests: u32,
+    /// Period over which `requests` are replenished
+    pub period: Duration,
+    /// Maximum bucket size (defaults to `requests`)
+    pub burst: u32,
+}
+
+impl RateLimitQuota {
+    /// Creates a quota of `requests` per `period`.
+    pub fn new(requests: u32, period: Duration) -> Self {
+        Self {
+            requests,
+            period,
+            burst: requests,
+        }
+    }
+
+    /// Creates a quota of `requests` per second.
+    pub fn per_second(requests: u32) -> Self {
+        Self::new(requests, Duration::from_secs(1))
+    }
+
+    /// Creates a quota of `requests` per minute.
+    pub fn per_minute(requests: u32) -> Self {
+        Self::new(requests, Duration::from_secs(60))
+    }
+
+    /// Sets the maximum bucket size.
+    pub fn with_burst(mut self, burst: u32) -> Self {
+        self.burst = burst;
+        self
+    }
+
+    /// Tokens refilled per second
+    fn refill_rate(&self) -> f64 {
+        self.requests as f64 / self.period.as_secs_f64().max(f64::EPSILON)
+    }
+
+    /// Time until one token is available, given the current token count
+    fn retry_after(&self, tokens: f64) -> Duration {
+        let missing = (1.0 - tokens).max(0.0);
+        Duration::from_secs_f64(missing / self.refill_rate().max(f64::EPSILON))
+    }
+}
+
+/// Result of a rate limit check
+#[derive(Debug, Clone, PartialEq)]
+pub struct RateLimitDecision {
+    /// Whether the request is allowed
+    pub allowed: bool,
+    /// Remaining requests in the bucket
+    pub remaining: u32,
+    /// Time to wait before retrying (only set when rejected)
+    pub retry_after: Option<Duration>,
+}
+
+/// Storage backend for token buckets
+///
+/// Implement this trait to keep buckets in a custom store.
+#[async_trait::async_trait]
+pub trait RateLimitStore: Send + Sync {
+    /// Consumes one token from the bucket identified by `key`.
+    async fn acquire(&self, key: &str, quota: &RateLimitQuota) -> Result<RateLimitDecision>;
+}
+
+/// In-memory token bucket store
+///
+/// Buckets are local to the process; use a shared store when running
+/// several instances. Buckets that have refilled are dropped on the next
+/// request, since a missing bucket starts full.
+#[derive(Debug, Default)]
+pub struct InMemoryRateLimitStore {
+    buckets: Mutex<HashMap<String, Bucket>>,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    updated_at: Instant,
+    /// Burst and refill rate of the quota the bucket was last used with
+    burst: f64,
+    refill_rate: f64,
+}
+
+impl Bucket {
+    /// Whether the bucket has refilled to its burst by `now`
+    fn is_full(&self, now: Instant) -> bool {
+        let elapsed = now.duration_since(self.updated_at).as_secs_f64();
+        self.tokens + elapsed * self.refill_rate >= self.burst
+    }
+}
+
+impl InMemoryRateLimitStore {
+    /// Creates an empty store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of buckets currently tracked
+    pub async fn len(&self) -> usize {
+        self.buckets.lock().await.len()
+    }
+
+    /// Whether no bucket is tracked
+    pub async fn is_empty(&self) -> bool {
+        self.buckets.lock().await.is_empty()
+    }
+}
+
+#[async_trait::async_trait]
+impl RateLimitStore for InMemoryRateLimitStore {
+    async fn acquire(&self, key: &str, quota: &RateLimitQuota) -> Result<RateLimitDecision> {
+        let now = Instant::now();
+        let mut buckets = self.buckets.lock().await;
+        buckets.retain(|_, bucket| !bucket.is_full(now));
+        let bucket = buckets.entry(key.to_string()).or_insert(Bucket {
+            tokens: quota.burst as f64,
+            updated_at: now,
+            burst: quota.burst as f64,
+            refill_rate: quota.refill_rate(),
+        });
+
+        let elapsed = now.duration_since(bucket.updated_at).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * quota.refill_rate()).min(quota.burst as f64);
+        bucket.updated_at = now;
+        bucket.burst = quota.burst as f64;
+        bucket.refill_rate = quota.refill_rate();
+
+        Ok(consume(&mut bucket.tokens, quota))
+    }
+}
+
+/// Redis-backed token bucket store
+///
+/// Buckets are updated atomically with a Lua script, so quotas are shared
+/// by every instance connected to the same Redis server.
+#[cfg(feature = "redis")]
+#[derive(Clone)]
+pub struct RedisRateLimitStore {
+    connection: redis::aio::MultiplexedConnection,
+    prefix: String,
+}
+
+#[cfg(feature = "redis")]
+const REDIS_TOKEN_BUCKET: &str = r#"
+local capacity = tonumber(ARGV[1])
+local rate = tonumber(ARGV[2])
+local now = tonumber(ARGV[3])
+local data = redis.call('HMGET', KEYS[1], 'tokens', 'ts')
+local tokens = tonumber(data[1]) or capacity
+local ts = tonumber(data[2]) or now
+tokens = math.min(capacity, tokens + math.max(0, now - ts) / 1000 * rate)
+local allowed = 0
+if tokens >= 1 then
+  tokens = tokens - 1
+  allowed = 1
+end
+redis.call('HSET', KEYS[1], 'tokens', tostring(tokens), 'ts', now)
+redis.call('PEXPIRE', KEYS[1], math.ceil(capacity / rate * 1000))
+return {allowed, tostring(tokens)}
+"#;
+
+#[cfg(feature = "redis")]
+impl RedisRateLimitStore {
+    /// Connects to Redis at `url` (e.g., `redis://127.0.0.1/`).
+    pub async fn connect(url: &str) -> Result<Self> {
+        let client = redis::Client::open(url)?;
+        Ok(Self {
+            connection: client.get_multiplexed_async_connection().await?,
+            prefix: "keyrunes:ratelimit:".to_string(),
+        })
+    }
+
+    /// Sets the key prefix (default: `keyrunes:ratelimit:`).
+    pub fn with_prefix<S: Into<String>>(mut self, prefix: S) -> Self {
+        self.prefix = prefix.into();
+        self
+    }
+}
+
+#[cfg(feature = "redis")]
+#[async_trait::async_trait]
+impl RateLimitStore for RedisRateLimitStore {
+    async fn acquire(&self, key: &str, quota: &RateLimitQuota) -> Result<RateLimitDecision> {
+        let now = chrono::Utc::now().timestamp_millis();
+        let mut connection = self.connection.clone();
+        let (allowed, tokens): (i64, String) = redis::Script::new(REDIS_TOKEN_BUCKET)
+            .key(format!("{}{}", self.prefix, key))
+            .arg(quota.burst)
+            .arg(quota.refill_rate())
+            .arg(now)
+            .invoke_async(&mut connection)
+            .await?;
+
+        let tokens: f64 = tokens.parse().unwrap_or(0.0);
+        Ok(RateLimitDecision {
+            allowed: allowed == 1,
+            remaining: tokens.floor() as u32,
+            retry_after: (allowed != 1).then(|| quota.retry_after(tokens)),
+        })
+    }
+}
+
+/// Consumes one token from a bucket, building the decision
+fn consume(tokens: &mut f64, quota: &RateLimitQuota) -> RateLimitDecision {
+    if *tokens >= 1.0 {
+        *tokens -= 1.0;
+        RateLimitDecision {
+            allowed: true,
+            remaining: tokens.floor() as u32,
+            retry_after: None,
+        }
+    } else {
+        RateLimitDecision {
+            allowed: false,
+            remaining: 0,
+            retry_after: Some(quota.retry_after(*tokens)),
+        }
+    }
+}
+
+/// Rate limiter keyed by user identity
+///
+/// Cloning a limiter is cheap and shares the underlying store.
+#[derive(Clone)]
+pub struct RateLimiter {
+    store: Arc<dyn RateLimitStore>,
+    quota: RateLimitQuota,
+    scope: String,
+}
+
+impl RateLimiter {
+    /// Creates a limiter using the given store.
+    pub fn new(store: Arc<dyn RateLimitStore>, quota: RateLimitQuota) -> Self {
+        Self {
+            store,
+            quota,
+            scope: "default".to_string(),
+        }
+    }
+
+    /// Creates a limiter backed by an [`InMemoryRateLimitStore`].
+    pub fn in_memory(quota: RateLimitQuota) -> Self {
+        Self::new(Arc::new(InMemoryRateLimitStore::new()), quota)
+    }
+
+    /// Sets the scope used to separate quotas of different routes sharing a store.
+    pub fn with_scope<S: Into<String>>(mut self, scope: S) -> Self {
+        self.scope = scope.into();
+        self
+    }
+
+    /// Returns the configured quota.
+    pub fn quota(&self) -> &RateLimitQuota {
+        &self.quota
+    }
+
+    /// Consumes one request from the quota of `user_id`.
+    pub async fn check(&self, user_id: &str) -> Result<RateLimitDecision> {
+        let key = format!("{}:{}", self.scope, user_id);
+        self.store.acquire(&key, &self.quota).await
+    }
+}
+
+impl std::fmt::Debug for RateLimiter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RateLimiter")
+            .field("quota", &self.quota)
+            .field("scope", &self.scope)
+            .finish()
+    }
+}
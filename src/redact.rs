@@ -0,0 +1,143 @@
+//! Field-level redaction of API responses
+//!
+//! Some response fields should only be visible to privileged callers
+//! (e.g., a user's internal notes for admins). Types implementing
+//! [`Redact`] declare which fields are restricted, and
+//! [`Redact::redacted`] serializes them for a given [`AuthContext`],
+//! omitting or masking the fields the caller may not see.
+//!
+//! With the `macros` feature, `#[derive(KeyrunesRedact)]` implements
+//! [`Redact`] from `#[keyrunes(...)]` field attributes:
+//!
+//! ```ignore
+//! #[derive(Serialize, KeyrunesRedact)]
+//! struct Employee {
+//!     name: String,
+//!     #[keyrunes(group = "hr", mask)]
+//!     salary: u64,
+//!     #[keyrunes(group = "admins", permission = "employees:audit")]
+//!     notes: String,
+//! }
+//!
+//! let body = employee.redacted(&AuthContext::load(&client, &user).await?)?;
+//! ```
+//!
+//! A restricted field is visible when the caller belongs to any of its
+//! groups or holds any of its permissions. Hidden fields are omitted, or
+//! replaced with [`DEFAULT_MASK`] (or a custom string with
+//! `mask = "..."`).
+
+use crate::error::Result;
+use crate::permissions::PermissionSet;
+use crate::{KeyrunesClient, User};
+use serde::Serialize;
+use std::collections::BTreeSet;
+
+/// Value replacing masked fields
+pub const DEFAULT_MASK: &str = "***";
+
+/// Groups and permissions of the caller a response is rendered for
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AuthContext {
+    groups: BTreeSet<String>,
+    permissions: PermissionSet,
+}
+
+impl AuthContext {
+    /// Creates a context from the caller's groups and permissions.
+    pub fn new<I, S>(groups: I, permissions: PermissionSet) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            groups: groups.into_iter().map(Into::into).collect(),
+            permissions,
+        }
+    }
+
+    /// Creates a context with the groups of `user` and no permissions.
+    pub fn from_user(user: &User) -> Self {
+        Self::new(user.groups.iter().cloned(), PermissionSet::default())
+    }
+
+    /// Creates a context with the groups of `user` and their compiled policy.
+    pub async fn load(client: &KeyrunesClient, user: &User) -> Result<Self> {
+        let permissions = client.compile_policy(user.id.as_str()).await?;
+        Ok(Self::from_user(user).with_permissions(permissions))
+    }
+
+    /// Replaces the caller's permissions.
+    pub fn with_permissions(mut self, permissions: PermissionSet) -> Self {
+        self.permissions = permissions;
+        self
+    }
+
+    /// Checks whether the caller belongs to `group`.
+    pub fn has_group(&self, group: &str) -> bool {
+        self.groups.contains(group)
+    }
+
+    /// Checks whether the caller holds `permission` (wildcards apply).
+    pub fn has_permission(&self, permission: &str) -> bool {
+        self.permissions.implies(permission)
+    }
+}
+
+impl From<&User> for AuthContext {
+    fn from(user: &User) -> Self {
+        Self::from_user(user)
+    }
+}
+
+/// Restriction on a serialized field
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FieldRule {
+    /// Serialized name of the field
+    pub key: &'static str,
+    /// Groups allowed to see the field
+    pub groups: &'static [&'static str],
+    /// Permissions allowed to see the field
+    pub permissions: &'static [&'static str],
+    /// Replacement for hidden values; `None` omits the field
+    pub mask: Option<&'static str>,
+}
+
+impl FieldRule {
+    /// Checks whether `ctx` may see the field.
+    pub fn allows(&self, ctx: &AuthContext) -> bool {
+        self.groups.iter().any(|group| ctx.has_group(group))
+            || self
+                .permissions
+                .iter()
+                .any(|permission| ctx.has_permission(permission))
+    }
+}
+
+/// Types serialized with restricted fields
+///
+/// Usually implemented with `#[derive(KeyrunesRedact)]`.
+pub trait Redact: Serialize {
+    /// Restricted fields; fields not listed are always visible
+    const FIELD_RULES: &'static [FieldRule];
+
+    /// Serializes `self`, omitting or masking the fields `ctx` may not see.
+    fn redacted(&self, ctx: &AuthContext) -> Result<serde_json::Value> {
+        let mut value = serde_json::to_value(self)?;
+        if let Some(object) = value.as_object_mut() {
+            for rule in Self::FIELD_RULES.iter().filter(|rule| !rule.allows(ctx)) {
+                match rule.mask {
+                    Some(mask) => {
+                        if let Some(field) = object.get_mut(rule.key) {
+                            *field = serde_json::Value::String(mask.to_string());
+                        }
+                    }
+                    None => {
+                        object.remove(rule.key);
+                    }
+                }
+            }
+        }
+        Ok(value)
+    }
+}
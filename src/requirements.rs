@@ -0,0 +1,95 @@
+//! Route-level group and permission requirements
+//!
+//! [`RouteRequirements`] declares the groups and permissions a route needs,
+//! and the [`Require`] extractor enforces them in the Axum and Actix
+//! integrations. The `#[require]` attribute (feature `macros`) generates
+//! both for a handler:
+//!
+//! ```ignore
+//! #[keyrunes_rust_sdk::require(group = "editors", permission = "posts:write")]
+//! async fn create_post(Json(post): Json<NewPost>) -> StatusCode {
+//!     StatusCode::CREATED
+//! }
+//! ```
+//!
+//! Without the macro, implement [`RouteRequirements`] on a marker type and
+//! take `Require<Marker>` as a handler argument.
+
+use crate::error::{KeyrunesError, Result};
+use crate::{KeyrunesClient, User};
+use std::marker::PhantomData;
+
+/// Groups and permissions required to access a route
+pub trait RouteRequirements: Send + Sync + 'static {
+    /// Groups the user must belong to (all of them)
+    const GROUPS: &'static [&'static str];
+    /// Permissions the user must hold (all of them)
+    const PERMISSIONS: &'static [&'static str];
+}
+
+/// Checks that `user` meets the requirements `R`
+///
+/// Groups are checked first; permissions are checked against the user's
+/// compiled policy, so wildcard grants apply. Fails with
+/// `AuthorizationError` naming the first unmet requirement.
+pub async fn check<R: RouteRequirements>(client: &KeyrunesClient, user: &User) -> Result<()> {
+    for group in R::GROUPS {
+        if !client.has_group(&user.id, *group).await? {
+            return Err(KeyrunesError::AuthorizationError {
+                message: format!("User does not belong to group: {}", group),
+                code: None,
+            });
+        }
+    }
+
+    if !R::PERMISSIONS.is_empty() {
+        let policy = client.compile_policy(user.id.as_str()).await?;
+        if let Some(missing) = R::PERMISSIONS
+            .iter()
+            .find(|permission| !policy.implies(permission))
+        {
+            return Err(KeyrunesError::AuthorizationError {
+                message: format!("Permission required: {}", missing),
+                code: None,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// Extractor that authenticates the user and enforces the requirements `R`
+pub struct Require<R: RouteRequirements> {
+    pub user: User,
+    _requirements: PhantomData<R>,
+}
+
+impl<R: RouteRequirements> Require<R> {
+    /// Checks `user` against `R`, returning the extractor on success
+    pub async fn check(client: &KeyrunesClient, user: User) -> Result<Self> {
+        check::<R>(client, &user).await?;
+        Ok(Self {
+            user,
+            _requirements: PhantomData,
+        })
+    }
+}
+
+impl<R: RouteRequirements> Clone for Require<R> {
+    fn clone(&self) -> Self {
+        Self {
+            user: self.user.clone(),
+            _requirements: PhantomData,
+        }
+    }
+}
+
+impl<R: RouteRequirements> std::fmt::Debug for Require<R> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Require")
+            .field("user", &self.user)
+            .field("groups", &R::GROUPS)
+            .field("permissions", &R::PERMISSIONS)
+            .finish()
+    }
+}
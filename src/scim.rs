@@ -0,0 +1,653 @@
+//! SCIM 2.0 provisioning
+//!
+//! This module contains the [`ScimClient`], a handle to the Keyrunes SCIM
+//! endpoint (`/scim/v2`, RFC 7644) obtained with [`KeyrunesClient::scim`].
+//! It provisions users and groups from a directory of record (an HR
+//! system, an internal directory): create, replace, patch and delete,
+//! with filtered, paginated listing. The client's token must be allowed to
+//! provision the tenant.
+//!
+//! ## Quick Start
+//!
+//! ```
+//! use keyrunes_rust_sdk::scim::{ScimEmail, ScimFilter, ScimPatch, ScimQuery, ScimUser};
+//! use keyrunes_rust_sdk::KeyrunesClient;
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = KeyrunesClient::new("https://keyrunes.example.com")?;
+//! client.set_token("scim-token").await;
+//! let scim = client.scim();
+//!
+//! let query = ScimQuery::new().filter(ScimFilter::eq("externalId", "E-1042"));
+//! let existing = scim.list_users(&query).await?;
+//! match existing.resources.first() {
+//!     Some(user) => {
+//!         let id = user.id.as_deref().unwrap_or_default();
+//!         scim.patch_user(id, &ScimPatch::new().replace("active", false)).await?;
+//!     }
+//!     None => {
+//!         let mut user = ScimUser::new("john");
+//!         user.external_id = Some("E-1042".to_string());
+//!         user.emails.push(ScimEmail::primary("john@example.com"));
+//!         scim.create_user(&user).await?;
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::client::transport::{Auth, RequestBody};
+use crate::client::KeyrunesClient;
+use crate::endpoints;
+use crate::error::Result;
+use chrono::{DateTime, Utc};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+/// Schema of SCIM users
+pub const USER_SCHEMA: &str = "urn:ietf:params:scim:schemas:core:2.0:User";
+
+/// Schema of SCIM groups
+pub const GROUP_SCHEMA: &str = "urn:ietf:params:scim:schemas:core:2.0:Group";
+
+/// Schema of SCIM PATCH requests
+pub const PATCH_SCHEMA: &str = "urn:ietf:params:scim:api:messages:2.0:PatchOp";
+
+/// Resource metadata, set by Keyrunes
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScimMeta {
+    /// Resource type ("User" or "Group")
+    pub resource_type: String,
+    /// Creation date
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub created: Option<DateTime<Utc>>,
+    /// Last modification date
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub last_modified: Option<DateTime<Utc>>,
+    /// Version of the resource (ETag)
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub version: Option<String>,
+    /// URL of the resource
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub location: Option<String>,
+}
+
+/// Name of a SCIM user
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScimName {
+    /// Full name, formatted for display
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub formatted: Option<String>,
+    /// Family name
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub family_name: Option<String>,
+    /// Given name
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub given_name: Option<String>,
+}
+
+/// Email address of a SCIM user
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScimEmail {
+    /// Email address
+    pub value: String,
+    /// Kind of address (e.g., "work")
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none", default)]
+    pub kind: Option<String>,
+    /// Whether it is the user's primary address
+    #[serde(default)]
+    pub primary: bool,
+}
+
+impl ScimEmail {
+    /// Primary work address
+    pub fn primary<S: Into<String>>(value: S) -> Self {
+        Self {
+            value: value.into(),
+            kind: Some("work".to_string()),
+            primary: true,
+        }
+    }
+}
+
+/// Reference to a group member, or to a group a user belongs to
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScimMember {
+    /// ID of the user or group
+    pub value: String,
+    /// Display name
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub display: Option<String>,
+    /// URL of the user or group
+    #[serde(rename = "$ref", skip_serializing_if = "Option::is_none", default)]
+    pub reference: Option<String>,
+}
+
+impl ScimMember {
+    /// Member with ID `value`
+    pub fn new<S: Into<String>>(value: S) -> Self {
+        Self {
+            value: value.into(),
+            display: None,
+            reference: None,
+        }
+    }
+}
+
+/// SCIM user
+///
+/// Attributes not listed here (e.g., schema extensions) are kept in `extra`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScimUser {
+    /// Schemas of the resource
+    pub schemas: Vec<String>,
+    /// Keyrunes user ID (set by Keyrunes)
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub id: Option<String>,
+    /// ID of the user in the directory of record
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub external_id: Option<String>,
+    /// Unique username
+    pub user_name: String,
+    /// Name of the user
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub name: Option<ScimName>,
+    /// Name displayed to other users
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub display_name: Option<String>,
+    /// Email addresses
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub emails: Vec<ScimEmail>,
+    /// Whether the user may sign in
+    #[serde(default = "default_active")]
+    pub active: bool,
+    /// Groups the user belongs to (read-only; change group members instead)
+    #[serde(skip_serializing, default)]
+    pub groups: Vec<ScimMember>,
+    /// Resource metadata (set by Keyrunes)
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub meta: Option<ScimMeta>,
+    /// Other attributes
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+
+impl ScimUser {
+    /// Active user named `user_name`
+    pub fn new<S: Into<String>>(user_name: S) -> Self {
+        Self {
+            schemas: vec![USER_SCHEMA.to_string()],
+            id: None,
+            external_id: None,
+            user_name: user_name.into(),
+            name: None,
+            display_name: None,
+            emails: Vec::new(),
+            active: true,
+            groups: Vec::new(),
+            meta: None,
+            extra: serde_json::Map::new(),
+        }
+    }
+}
+
+/// SCIM group
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScimGroup {
+    /// Schemas of the resource
+    pub schemas: Vec<String>,
+    /// Keyrunes group ID (set by Keyrunes)
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub id: Option<String>,
+    /// ID of the group in the directory of record
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub external_id: Option<String>,
+    /// Group name
+    pub display_name: String,
+    /// Members of the group
+    #[serde(default)]
+    pub members: Vec<ScimMember>,
+    /// Resource metadata (set by Keyrunes)
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub meta: Option<ScimMeta>,
+    /// Other attributes
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+
+impl ScimGroup {
+    /// Empty group named `display_name`
+    pub fn new<S: Into<String>>(display_name: S) -> Self {
+        Self {
+            schemas: vec![GROUP_SCHEMA.to_string()],
+            id: None,
+            external_id: None,
+            display_name: display_name.into(),
+            members: Vec::new(),
+            meta: None,
+            extra: serde_json::Map::new(),
+        }
+    }
+}
+
+/// Page of a SCIM listing
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScimListResponse<T> {
+    /// Number of resources matching the query, across all pages
+    pub total_results: u64,
+    /// 1-based index of the first resource of the page
+    #[serde(default = "default_start_index")]
+    pub start_index: u64,
+    /// Number of resources in the page
+    #[serde(default)]
+    pub items_per_page: u64,
+    /// Resources of the page
+    #[serde(rename = "Resources", default = "Vec::new")]
+    pub resources: Vec<T>,
+}
+
+impl<T> ScimListResponse<T> {
+    /// Start index of the next page, or `None` on the last page.
+    pub fn next_start_index(&self) -> Option<u64> {
+        let next = self.start_index + self.resources.len() as u64;
+        (!self.resources.is_empty() && next <= self.total_results).then_some(next)
+    }
+}
+
+/// SCIM filter expression (e.g., `userName eq "john"`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScimFilter(String);
+
+impl ScimFilter {
+    /// `attribute` equals `value`
+    pub fn eq(attribute: &str, value: &str) -> Self {
+        Self::compare(attribute, "eq", value)
+    }
+
+    /// `attribute` contains `value`
+    pub fn contains(attribute: &str, value: &str) -> Self {
+        Self::compare(attribute, "co", value)
+    }
+
+    /// `attribute` starts with `value`
+    pub fn starts_with(attribute: &str, value: &str) -> Self {
+        Self::compare(attribute, "sw", value)
+    }
+
+    /// `attribute` has a value
+    pub fn present(attribute: &str) -> Self {
+        Self(format!("{} pr", attribute))
+    }
+
+    /// Filter written in the SCIM filter syntax, used as is
+    pub fn raw<S: Into<String>>(expression: S) -> Self {
+        Self(expression.into())
+    }
+
+    /// Both this filter and `other` match
+    pub fn and(self, other: ScimFilter) -> Self {
+        Self(format!("({}) and ({})", self.0, other.0))
+    }
+
+    /// This filter or `other` matches
+    pub fn or(self, other: ScimFilter) -> Self {
+        Self(format!("({}) or ({})", self.0, other.0))
+    }
+
+    /// The filter expression
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    fn compare(attribute: &str, operator: &str, value: &str) -> Self {
+        let value = value.replace('\\', "\\\\").replace('"', "\\\"");
+        Self(format!("{} {} \"{}\"", attribute, operator, value))
+    }
+}
+
+impl std::fmt::Display for ScimFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Filter and page of a SCIM listing
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ScimQuery {
+    filter: Option<ScimFilter>,
+    start_index: Option<u64>,
+    count: Option<u64>,
+}
+
+impl ScimQuery {
+    /// Query for the first page of all resources.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only lists resources matching `filter`.
+    pub fn filter(mut self, filter: ScimFilter) -> Self {
+        self.filter = Some(filter);
+        self
+    }
+
+    /// Starts the page at the 1-based index `start_index`.
+    pub fn start_index(mut self, start_index: u64) -> Self {
+        self.start_index = Some(start_index);
+        self
+    }
+
+    /// Sets the page size.
+    pub fn count(mut self, count: u64) -> Self {
+        self.count = Some(count);
+        self
+    }
+
+    fn query_string(&self) -> String {
+        let mut query = url::form_urlencoded::Serializer::new(String::new());
+        if let Some(filter) = &self.filter {
+            query.append_pair("filter", filter.as_str());
+        }
+        if let Some(start_index) = self.start_index {
+            query.append_pair("startIndex", &start_index.to_string());
+        }
+        if let Some(count) = self.count {
+            query.append_pair("count", &count.to_string());
+        }
+        query.finish()
+    }
+}
+
+/// Operation of a SCIM PATCH request
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScimPatchOperation {
+    /// `add`, `replace` or `remove`
+    pub op: String,
+    /// Attribute path (e.g., `active`, `members[value eq "123"]`)
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub path: Option<String>,
+    /// New value
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub value: Option<serde_json::Value>,
+}
+
+/// SCIM PATCH request, applying its operations in order
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScimPatch {
+    schemas: Vec<String>,
+    #[serde(rename = "Operations")]
+    operations: Vec<ScimPatchOperation>,
+}
+
+impl ScimPatch {
+    /// Creates an empty patch.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `value` to the attribute at `path` (appended for multi-valued attributes).
+    pub fn add<V: Into<serde_json::Value>>(self, path: &str, value: V) -> Self {
+        self.operation("add", Some(path), Some(value.into()))
+    }
+
+    /// Replaces the attribute at `path` with `value`.
+    pub fn replace<V: Into<serde_json::Value>>(self, path: &str, value: V) -> Self {
+        self.operation("replace", Some(path), Some(value.into()))
+    }
+
+    /// Removes the attribute at `path`.
+    pub fn remove(self, path: &str) -> Self {
+        self.operation("remove", Some(path), None)
+    }
+
+    /// Adds users or groups to the members of a group.
+    pub fn add_members<I, S>(self, ids: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let members: Vec<serde_json::Value> = ids
+            .into_iter()
+            .map(|id| serde_json::json!({ "value": id.into() }))
+            .collect();
+        self.add("members", members)
+    }
+
+    /// Removes a user or group from the members of a group.
+    pub fn remove_member(self, id: &str) -> Self {
+        let id = id.replace('\\', "\\\\").replace('"', "\\\"");
+        self.remove(&format!("members[value eq \"{}\"]", id))
+    }
+
+    /// Operations of the patch
+    pub fn operations(&self) -> &[ScimPatchOperation] {
+        &self.operations
+    }
+
+    fn operation(mut self, op: &str, path: Option<&str>, value: Option<serde_json::Value>) -> Self {
+        self.operations.push(ScimPatchOperation {
+            op: op.to_string(),
+            path: path.map(str::to_string),
+            value,
+        });
+        self
+    }
+}
+
+impl Default for ScimPatch {
+    fn default() -> Self {
+        Self {
+            schemas: vec![PATCH_SCHEMA.to_string()],
+            operations: Vec::new(),
+        }
+    }
+}
+
+/// Handle to the SCIM endpoint
+#[derive(Clone, Copy)]
+pub struct ScimClient<'a> {
+    client: &'a KeyrunesClient,
+}
+
+impl<'a> ScimClient<'a> {
+    pub(crate) fn new(client: &'a KeyrunesClient) -> Self {
+        Self { client }
+    }
+
+    /// Provisions a user.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<ScimUser, KeyrunesError>`:
+    /// - `Ok(user)` with the created user, including its `id`
+    /// - `Err(KeyrunesError::Api)` with code `uniqueness` if the username is taken
+    /// - `Err(KeyrunesError::AuthorizationError)` if the token may not provision users
+    pub async fn create_user(&self, user: &ScimUser) -> Result<ScimUser> {
+        self.request(
+            reqwest::Method::POST,
+            "/Users",
+            Some(serde_json::to_value(user)?),
+        )
+        .await
+    }
+
+    /// Gets a user by ID.
+    pub async fn get_user(&self, id: &str) -> Result<ScimUser> {
+        self.request(reqwest::Method::GET, &resource_path("Users", id), None)
+            .await
+    }
+
+    /// Replaces all the attributes of a user.
+    ///
+    /// Attributes missing from `user` are cleared; use
+    /// [`Self::patch_user`] to change some attributes only.
+    pub async fn replace_user(&self, id: &str, user: &ScimUser) -> Result<ScimUser> {
+        self.request(
+            reqwest::Method::PUT,
+            &resource_path("Users", id),
+            Some(serde_json::to_value(user)?),
+        )
+        .await
+    }
+
+    /// Changes some attributes of a user.
+    pub async fn patch_user(&self, id: &str, patch: &ScimPatch) -> Result<ScimUser> {
+        self.request(
+            reqwest::Method::PATCH,
+            &resource_path("Users", id),
+            Some(serde_json::to_value(patch)?),
+        )
+        .await
+    }
+
+    /// Deprovisions a user.
+    ///
+    /// To suspend a user instead, patch `active` to `false`.
+    pub async fn delete_user(&self, id: &str) -> Result<()> {
+        self.delete(&resource_path("Users", id)).await
+    }
+
+    /// Lists the users matching `query`, a page at a time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyrunes_rust_sdk::KeyrunesClient;
+    /// use keyrunes_rust_sdk::scim::{ScimFilter, ScimQuery};
+    ///
+    /// # async fn example(client: KeyrunesClient) -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut query = ScimQuery::new()
+    ///     .filter(ScimFilter::raw("active eq true"))
+    ///     .count(100);
+    /// loop {
+    ///     let page = client.scim().list_users(&query).await?;
+    ///     for user in &page.resources {
+    ///         println!("{}", user.user_name);
+    ///     }
+    ///     match page.next_start_index() {
+    ///         Some(next) => query = query.start_index(next),
+    ///         None => break,
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn list_users(&self, query: &ScimQuery) -> Result<ScimListResponse<ScimUser>> {
+        self.list("/Users", query).await
+    }
+
+    /// Provisions a group.
+    pub async fn create_group(&self, group: &ScimGroup) -> Result<ScimGroup> {
+        self.request(
+            reqwest::Method::POST,
+            "/Groups",
+            Some(serde_json::to_value(group)?),
+        )
+        .await
+    }
+
+    /// Gets a group by ID.
+    pub async fn get_group(&self, id: &str) -> Result<ScimGroup> {
+        self.request(reqwest::Method::GET, &resource_path("Groups", id), None)
+            .await
+    }
+
+    /// Replaces all the attributes of a group, members included.
+    pub async fn replace_group(&self, id: &str, group: &ScimGroup) -> Result<ScimGroup> {
+        self.request(
+            reqwest::Method::PUT,
+            &resource_path("Groups", id),
+            Some(serde_json::to_value(group)?),
+        )
+        .await
+    }
+
+    /// Changes some attributes of a group (e.g., adds or removes members).
+    pub async fn patch_group(&self, id: &str, patch: &ScimPatch) -> Result<ScimGroup> {
+        self.request(
+            reqwest::Method::PATCH,
+            &resource_path("Groups", id),
+            Some(serde_json::to_value(patch)?),
+        )
+        .await
+    }
+
+    /// Deprovisions a group. Its members are not deleted.
+    pub async fn delete_group(&self, id: &str) -> Result<()> {
+        self.delete(&resource_path("Groups", id)).await
+    }
+
+    /// Lists the groups matching `query`, a page at a time.
+    pub async fn list_groups(&self, query: &ScimQuery) -> Result<ScimListResponse<ScimGroup>> {
+        self.list("/Groups", query).await
+    }
+
+    async fn list<T: DeserializeOwned>(
+        &self,
+        resource: &str,
+        query: &ScimQuery,
+    ) -> Result<ScimListResponse<T>> {
+        let query = query.query_string();
+        let path = if query.is_empty() {
+            resource.to_string()
+        } else {
+            format!("{}?{}", resource, query)
+        };
+        self.request(reqwest::Method::GET, &path, None).await
+    }
+
+    async fn request<T: DeserializeOwned>(
+        &self,
+        method: reqwest::Method,
+        path: &str,
+        body: Option<serde_json::Value>,
+    ) -> Result<T> {
+        let body = body.map_or(RequestBody::Empty, RequestBody::Json);
+        let response = self
+            .client
+            .send_request(
+                method,
+                &format!("{}{}", endpoints::SCIM, path),
+                body,
+                Auth::Required,
+                None,
+            )
+            .await?;
+
+        self.client.handle_response(response).await
+    }
+
+    async fn delete(&self, path: &str) -> Result<()> {
+        let response = self
+            .client
+            .send_request(
+                reqwest::Method::DELETE,
+                &format!("{}{}", endpoints::SCIM, path),
+                RequestBody::Empty,
+                Auth::Required,
+                None,
+            )
+            .await?;
+
+        self.client.handle_empty_response(response).await
+    }
+}
+
+/// Path of the resource `id` of type `resource` ("Users" or "Groups")
+fn resource_path(resource: &str, id: &str) -> String {
+    let id: String = url::form_urlencoded::byte_serialize(id.as_bytes()).collect();
+    format!("/{}/{}", resource, id.replace('+', "%20"))
+}
+
+fn default_active() -> bool {
+    true
+}
+
+fn default_start_index() -> u64 {
+    1
+}
@@ -0,0 +1,494 @@
+//! Server-side sessions for browser apps
+//!
+//! In session mode the Keyrunes token never reaches the browser: the
+//! [`SessionManager`] stores it server-side under a random session ID, and
+//! only that ID is sent to the browser in an `HttpOnly` cookie. Sessions
+//! are kept in a pluggable [`SessionStore`]: [`InMemorySessionStore`] for
+//! single-instance deployments, and `RedisSessionStore` (feature `redis`)
+//! when several instances must share sessions.
+//!
+//! Sessions end after an idle timeout (no request) or an absolute timeout
+//! (since login), whichever comes first.
+//!
+//! ## Quick Start
+//!
+//! ```
+//! use keyrunes_rust_sdk::session::SessionManager;
+//! use keyrunes_rust_sdk::KeyrunesClient;
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = KeyrunesClient::new("https://keyrunes.example.com")?;
+//! let sessions = SessionManager::in_memory();
+//!
+//! let token = client.login("john@example.com", "password123", None).await?;
+//! let session_id = sessions.create(&token).await?;
+//! let set_cookie = sessions.cookie(&session_id);
+//!
+//! // On later requests, resolve the cookie back to the token
+//! if let Some(session) = sessions.resolve(&session_id).await? {
+//!     client.set_token(session.token).await;
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::claims::decode_payload;
+use crate::error::{KeyrunesError, Result};
+use crate::models::Token;
+use chrono::{DateTime, Utc};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+
+/// Name of the session cookie, by default
+const DEFAULT_COOKIE_NAME: &str = "keyrunes_session";
+
+/// Random bytes in session IDs and CSRF tokens
+const TOKEN_BYTES: usize = 32;
+
+/// Tokens and timestamps of a session
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionData {
+    /// Keyrunes token of the session
+    pub token: String,
+    /// Refresh token, when Keyrunes issued one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub refresh_token: Option<String>,
+    /// CSRF token bound to the session (see [`crate::csrf`])
+    #[serde(default)]
+    pub csrf_token: String,
+    /// When the session was created (the login)
+    pub created_at: DateTime<Utc>,
+    /// When the session was last used
+    pub last_seen_at: DateTime<Utc>,
+    /// Subject (`sub` claim) of the token the session was created with
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subject: Option<String>,
+    /// Keyrunes session ID (`sid` claim) of the token the session was created with
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sid: Option<String>,
+}
+
+impl SessionData {
+    fn new(token: &Token, now: DateTime<Utc>) -> Self {
+        let claims = decode_payload(&token.token).unwrap_or_default();
+        let claim = |name: &str| claims.get(name).and_then(|v| v.as_str()).map(String::from);
+        Self {
+            token: token.token.clone(),
+            refresh_token: token.refresh_token.clone(),
+            csrf_token: random_token(),
+            created_at: now,
+            last_seen_at: now,
+            subject: claim("sub"),
+            sid: claim("sid"),
+        }
+    }
+
+    /// Whether the session belongs to `subject` and to the Keyrunes session
+    /// `sid`, ignoring the criteria that are `None`
+    fn belongs_to(&self, subject: Option<&str>, sid: Option<&str>) -> bool {
+        subject.is_none_or(|subject| self.subject.as_deref() == Some(subject))
+            && sid.is_none_or(|sid| self.sid.as_deref() == Some(sid))
+    }
+}
+
+/// Idle and absolute session timeouts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SessionTimeouts {
+    /// Sessions end after this long without requests
+    pub idle: Duration,
+    /// Sessions end this long after login, regardless of activity
+    pub absolute: Duration,
+}
+
+impl SessionTimeouts {
+    /// Time the session may still live at `now`, or `None` when it timed out
+    fn remaining(&self, session: &SessionData, now: DateTime<Utc>) -> Option<Duration> {
+        let idle = self.idle.checked_sub(elapsed(session.last_seen_at, now))?;
+        let absolute = self
+            .absolute
+            .checked_sub(elapsed(session.created_at, now))?;
+        Some(idle.min(absolute)).filter(|remaining| !remaining.is_zero())
+    }
+}
+
+impl Default for SessionTimeouts {
+    fn default() -> Self {
+        Self {
+            idle: Duration::from_secs(30 * 60),
+            absolute: Duration::from_secs(12 * 60 * 60),
+        }
+    }
+}
+
+/// Storage backend for sessions
+///
+/// Implement this trait to keep sessions in a custom store.
+#[async_trait::async_trait]
+pub trait SessionStore: Send + Sync {
+    /// Loads the session `id`, if it exists and has not expired.
+    async fn load(&self, id: &str) -> Result<Option<SessionData>>;
+
+    /// Stores the session `id`, expiring it after `ttl`.
+    async fn save(&self, id: &str, session: &SessionData, ttl: Duration) -> Result<()>;
+
+    /// Removes the session `id`.
+    async fn delete(&self, id: &str) -> Result<()>;
+
+    /// Removes every session for which `matches` returns `true`, returning
+    /// how many were removed.
+    ///
+    /// Used by back-channel logout (see [`crate::logout`]) to end the
+    /// sessions of a user. The default implementation fails with `Other`,
+    /// for stores that cannot enumerate their sessions.
+    async fn delete_where(
+        &self,
+        matches: &(dyn for<'s> Fn(&'s SessionData) -> bool + Sync),
+    ) -> Result<usize> {
+        let _ = matches;
+        Err(KeyrunesError::Other(
+            "Session store cannot delete sessions by owner".to_string(),
+        ))
+    }
+}
+
+/// In-memory session store
+///
+/// Sessions are local to the process; use a shared store when running
+/// several instances.
+#[derive(Debug, Default)]
+pub struct InMemorySessionStore {
+    sessions: Mutex<HashMap<String, (SessionData, tokio::time::Instant)>>,
+}
+
+impl InMemorySessionStore {
+    /// Creates an empty store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait::async_trait]
+impl SessionStore for InMemorySessionStore {
+    async fn load(&self, id: &str) -> Result<Option<SessionData>> {
+        let mut sessions = self.sessions.lock().await;
+        match sessions.get(id) {
+            Some((session, expires_at)) if *expires_at > tokio::time::Instant::now() => {
+                Ok(Some(session.clone()))
+            }
+            Some(_) => {
+                sessions.remove(id);
+                Ok(None)
+            }
+            None => Ok(None),
+        }
+    }
+
+    async fn save(&self, id: &str, session: &SessionData, ttl: Duration) -> Result<()> {
+        let expires_at = tokio::time::Instant::now() + ttl;
+        let mut sessions = self.sessions.lock().await;
+        sessions.retain(|_, (_, expires_at)| *expires_at > tokio::time::Instant::now());
+        sessions.insert(id.to_string(), (session.clone(), expires_at));
+        Ok(())
+    }
+
+    async fn delete(&self, id: &str) -> Result<()> {
+        self.sessions.lock().await.remove(id);
+        Ok(())
+    }
+
+    async fn delete_where(
+        &self,
+        matches: &(dyn for<'s> Fn(&'s SessionData) -> bool + Sync),
+    ) -> Result<usize> {
+        let mut sessions = self.sessions.lock().await;
+        let before = sessions.len();
+        sessions.retain(|_, (session, _)| !matches(session));
+        Ok(before - sessions.len())
+    }
+}
+
+/// Redis-backed session store
+///
+/// Sessions are stored as JSON with a Redis expiry, so they are shared by
+/// every instance connected to the same Redis server.
+#[cfg(feature = "redis")]
+#[derive(Clone)]
+pub struct RedisSessionStore {
+    connection: redis::aio::MultiplexedConnection,
+    prefix: String,
+}
+
+#[cfg(feature = "redis")]
+impl RedisSessionStore {
+    /// Connects to Redis at `url` (e.g., `redis://127.0.0.1/`).
+    pub async fn connect(url: &str) -> Result<Self> {
+        let client = redis::Client::open(url)?;
+        Ok(Self {
+            connection: client.get_multiplexed_async_connection().await?,
+            prefix: "keyrunes:session:".to_string(),
+        })
+    }
+
+    /// Sets the key prefix (default: `keyrunes:session:`).
+    pub fn with_prefix<S: Into<String>>(mut self, prefix: S) -> Self {
+        self.prefix = prefix.into();
+        self
+    }
+}
+
+#[cfg(feature = "redis")]
+#[async_trait::async_trait]
+impl SessionStore for RedisSessionStore {
+    async fn load(&self, id: &str) -> Result<Option<SessionData>> {
+        let mut connection = self.connection.clone();
+        let value: Option<String> = redis::cmd("GET")
+            .arg(format!("{}{}", self.prefix, id))
+            .query_async(&mut connection)
+            .await?;
+        value
+            .map(|value| serde_json::from_str(&value).map_err(Into::into))
+            .transpose()
+    }
+
+    async fn save(&self, id: &str, session: &SessionData, ttl: Duration) -> Result<()> {
+        let mut connection = self.connection.clone();
+        redis::cmd("SET")
+            .arg(format!("{}{}", self.prefix, id))
+            .arg(serde_json::to_string(session)?)
+            .arg("PX")
+            .arg(ttl.as_millis().max(1) as u64)
+            .query_async::<()>(&mut connection)
+            .await?;
+        Ok(())
+    }
+
+    async fn delete(&self, id: &str) -> Result<()> {
+        let mut connection = self.connection.clone();
+        redis::cmd("DEL")
+            .arg(format!("{}{}", self.prefix, id))
+            .query_async::<()>(&mut connection)
+            .await?;
+        Ok(())
+    }
+
+    async fn delete_where(
+        &self,
+        matches: &(dyn for<'s> Fn(&'s SessionData) -> bool + Sync),
+    ) -> Result<usize> {
+        let mut connection = self.connection.clone();
+        let mut cursor = 0u64;
+        let mut deleted = 0;
+        loop {
+            let (next, keys): (u64, Vec<String>) = redis::cmd("SCAN")
+                .arg(cursor)
+                .arg("MATCH")
+                .arg(format!("{}*", self.prefix))
+                .arg("COUNT")
+                .arg(100)
+                .query_async(&mut connection)
+                .await?;
+            for key in keys {
+                let value: Option<String> = redis::cmd("GET")
+                    .arg(&key)
+                    .query_async(&mut connection)
+                    .await?;
+                let Some(session) = value.and_then(|v| serde_json::from_str(&v).ok()) else {
+                    continue;
+                };
+                if matches(&session) {
+                    let removed: usize = redis::cmd("DEL")
+                        .arg(&key)
+                        .query_async(&mut connection)
+                        .await?;
+                    deleted += removed;
+                }
+            }
+            if next == 0 {
+                return Ok(deleted);
+            }
+            cursor = next;
+        }
+    }
+}
+
+/// Creates, resolves and ends server-side sessions
+///
+/// Cloning a manager is cheap and shares the underlying store.
+#[derive(Clone)]
+pub struct SessionManager {
+    store: Arc<dyn SessionStore>,
+    timeouts: SessionTimeouts,
+    cookie_name: String,
+    secure: bool,
+}
+
+impl SessionManager {
+    /// Creates a manager using the given store.
+    pub fn new(store: Arc<dyn SessionStore>) -> Self {
+        Self {
+            store,
+            timeouts: SessionTimeouts::default(),
+            cookie_name: DEFAULT_COOKIE_NAME.to_string(),
+            secure: true,
+        }
+    }
+
+    /// Creates a manager backed by an [`InMemorySessionStore`].
+    pub fn in_memory() -> Self {
+        Self::new(Arc::new(InMemorySessionStore::new()))
+    }
+
+    /// Sets the idle and absolute timeouts (30 minutes and 12 hours by default).
+    pub fn with_timeouts(mut self, timeouts: SessionTimeouts) -> Self {
+        self.timeouts = timeouts;
+        self
+    }
+
+    /// Sets the name of the session cookie (default: `keyrunes_session`).
+    pub fn with_cookie_name<S: Into<String>>(mut self, name: S) -> Self {
+        self.cookie_name = name.into();
+        self
+    }
+
+    /// Sets whether the session cookie is only sent over HTTPS (the default).
+    pub fn with_secure(mut self, secure: bool) -> Self {
+        self.secure = secure;
+        self
+    }
+
+    /// Returns the configured timeouts.
+    pub fn timeouts(&self) -> &SessionTimeouts {
+        &self.timeouts
+    }
+
+    /// Starts a session holding `token`, returning its ID.
+    pub async fn create(&self, token: &Token) -> Result<String> {
+        let id = random_token();
+        let session = SessionData::new(token, Utc::now());
+        let ttl = self.timeouts.idle.min(self.timeouts.absolute);
+        self.store.save(&id, &session, ttl).await?;
+        Ok(id)
+    }
+
+    /// Loads the session `id`, extending its idle timeout.
+    ///
+    /// Returns `None` when the session does not exist or timed out.
+    pub async fn resolve(&self, id: &str) -> Result<Option<SessionData>> {
+        let Some(mut session) = self.store.load(id).await? else {
+            return Ok(None);
+        };
+
+        let now = Utc::now();
+        if self.timeouts.remaining(&session, now).is_none() {
+            self.store.delete(id).await?;
+            return Ok(None);
+        }
+
+        session.last_seen_at = now;
+        if let Some(ttl) = self.timeouts.remaining(&session, now) {
+            self.store.save(id, &session, ttl).await?;
+        }
+        Ok(Some(session))
+    }
+
+    /// Replaces the tokens of the session `id` (e.g., after a refresh).
+    ///
+    /// Returns `false` when the session does not exist or timed out.
+    pub async fn update_token(&self, id: &str, token: &Token) -> Result<bool> {
+        let Some(mut session) = self.resolve(id).await? else {
+            return Ok(false);
+        };
+
+        session.token = token.token.clone();
+        if token.refresh_token.is_some() {
+            session.refresh_token = token.refresh_token.clone();
+        }
+        if let Some(ttl) = self.timeouts.remaining(&session, Utc::now()) {
+            self.store.save(id, &session, ttl).await?;
+        }
+        Ok(true)
+    }
+
+    /// Ends the session `id`.
+    pub async fn destroy(&self, id: &str) -> Result<()> {
+        self.store.delete(id).await
+    }
+
+    /// Ends the sessions of `subject`, or those created within the
+    /// Keyrunes session `sid`, returning how many were ended.
+    ///
+    /// When both are given, only sessions matching both are ended; when
+    /// neither is, nothing is. Fails with `Other` if the store cannot
+    /// delete sessions by owner.
+    pub async fn destroy_matching(
+        &self,
+        subject: Option<&str>,
+        sid: Option<&str>,
+    ) -> Result<usize> {
+        if subject.is_none() && sid.is_none() {
+            return Ok(0);
+        }
+        self.store
+            .delete_where(&|session| session.belongs_to(subject, sid))
+            .await
+    }
+
+    /// Reads the session ID from a `Cookie` header value.
+    pub fn session_id<'h>(&self, cookie_header: Option<&'h str>) -> Option<&'h str> {
+        cookie_header?
+            .split(';')
+            .filter_map(|pair| pair.trim().split_once('='))
+            .find(|(name, _)| *name == self.cookie_name)
+            .map(|(_, value)| value)
+            .filter(|value| !value.is_empty())
+    }
+
+    /// `Set-Cookie` value sending the session `id` to the browser.
+    ///
+    /// The cookie expires with the absolute timeout.
+    pub fn cookie(&self, id: &str) -> String {
+        self.set_cookie(id, self.timeouts.absolute.as_secs())
+    }
+
+    /// `Set-Cookie` value removing the session cookie.
+    pub fn clear_cookie(&self) -> String {
+        self.set_cookie("", 0)
+    }
+
+    fn set_cookie(&self, value: &str, max_age: u64) -> String {
+        let mut cookie = format!(
+            "{}={}; Path=/; HttpOnly; SameSite=Lax; Max-Age={}",
+            self.cookie_name, value, max_age
+        );
+        if self.secure {
+            cookie.push_str("; Secure");
+        }
+        cookie
+    }
+}
+
+impl std::fmt::Debug for SessionManager {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SessionManager")
+            .field("timeouts", &self.timeouts)
+            .field("cookie_name", &self.cookie_name)
+            .field("secure", &self.secure)
+            .finish()
+    }
+}
+
+/// Generates an unguessable token (hex-encoded random bytes), used for session IDs and CSRF tokens
+pub(crate) fn random_token() -> String {
+    let mut bytes = [0u8; TOKEN_BYTES];
+    rand::rngs::OsRng.fill_bytes(&mut bytes);
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Time elapsed from `since` to `now` (zero if `since` is in the future)
+fn elapsed(since: DateTime<Utc>, now: DateTime<Utc>) -> Duration {
+    (now - since).to_std().unwrap_or_default()
+}
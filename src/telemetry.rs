@@ -0,0 +1,88 @@
+//! OpenTelemetry attributes on request spans (feature `tracing`)
+//!
+//! When a middleware takes an access decision, it records the identity of
+//! the caller on the current span with the OpenTelemetry semantic
+//! conventions: `enduser.id`, `enduser.role` (the user's groups, comma
+//! separated), `http.route` and `keyrunes.decision` (`allow` or `deny`).
+//! Decisions taken from cached memberships while Keyrunes was unreachable
+//! also set `keyrunes.degraded` (see
+//! [`KeyrunesClientBuilder::membership_fallback`](crate::KeyrunesClientBuilder::membership_fallback)).
+//! Pipelines exporting spans through `tracing-opentelemetry` then show
+//! who made each request, without changes to the application.
+//!
+//! `tracing` only records values on fields declared when the span was
+//! created. Create request spans with [`request_span`], or declare the
+//! fields in [`FIELDS`] as `tracing::field::Empty` on your own spans:
+//!
+//! ```ignore
+//! let app = Router::new()
+//!     .route("/api/profile", get(profile))
+//!     .layer(axum::middleware::from_fn_with_state(state.clone(), require_auth))
+//!     .layer(TraceLayer::new_for_http().make_span_with(|request: &Request<_>| {
+//!         telemetry::request_span(request.method().as_str(), request.uri().path())
+//!     }));
+//! ```
+
+use crate::audit::Decision;
+use crate::error::KeyrunesError;
+use crate::User;
+use std::time::Duration;
+use tracing::field::Empty;
+use tracing::Span;
+
+/// Span fields recorded by the middlewares
+pub const FIELDS: [&str; 5] = [
+    "enduser.id",
+    "enduser.role",
+    "http.route",
+    "keyrunes.decision",
+    "keyrunes.degraded",
+];
+
+/// Creates a request span declaring the fields recorded by the middlewares.
+pub fn request_span(method: &str, path: &str) -> Span {
+    tracing::info_span!(
+        "request",
+        otel.name = %format!("{} {}", method, path),
+        http.request.method = %method,
+        url.path = %path,
+        enduser.id = Empty,
+        enduser.role = Empty,
+        http.route = Empty,
+        keyrunes.decision = Empty,
+        keyrunes.degraded = Empty,
+    )
+}
+
+/// Records an access decision on the current span.
+pub(crate) fn record_decision(user: Option<&User>, route: &str, decision: Decision) {
+    let span = Span::current();
+    if let Some(user) = user {
+        span.record("enduser.id", user.id.as_str());
+        if !user.groups.is_empty() {
+            span.record("enduser.role", user.groups.join(",").as_str());
+        }
+    }
+    span.record("http.route", route);
+    span.record(
+        "keyrunes.decision",
+        match decision {
+            Decision::Allow => "allow",
+            Decision::Deny => "deny",
+        },
+    );
+}
+
+/// Reports a group check answered from a cached membership, and marks the
+/// current span as degraded.
+pub(crate) fn record_degraded(user_id: &str, group_id: &str, age: Duration, error: &KeyrunesError) {
+    Span::current().record("keyrunes.degraded", true);
+    tracing::warn!(
+        target: "keyrunes::degraded",
+        user_id,
+        group_id,
+        age_secs = age.as_secs(),
+        error = %error,
+        "group membership served from cache"
+    );
+}
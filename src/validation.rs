@@ -0,0 +1,121 @@
+//! Local validation of incoming access tokens
+//!
+//! By default the framework integrations resolve the user of a bearer
+//! token by asking Keyrunes (`/api/me`), which costs a round trip per
+//! request. Resource servers and gateways can validate tokens locally
+//! instead. The signature is checked against the Keyrunes signing keys
+//! (fetched once and cached), then the claims against a
+//! [`TokenValidation`], which lists the audiences and issuers to accept.
+//!
+//! ## Quick Start
+//!
+//! ```
+//! use keyrunes_rust_sdk::validation::TokenValidation;
+//! use keyrunes_rust_sdk::{KeyrunesClient, KeyrunesError};
+//!
+//! # async fn example(token: &str) -> Result<(), Box<dyn std::error::Error>> {
+//! let client = KeyrunesClient::new("https://keyrunes.example.com")?;
+//! let validation = TokenValidation::new()
+//!     .require_audience("orders-api")
+//!     .require_audience("billing-api");
+//!
+//! match client.validate_token(token, &validation).await {
+//!     Ok(claims) => println!("Token of {:?}", claims.user_id),
+//!     Err(KeyrunesError::InvalidAudience { found, .. }) => println!("Token for {:?}", found),
+//!     Err(err) => return Err(err.into()),
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::error::{KeyrunesError, Result};
+use std::time::Duration;
+
+/// Clock skew tolerated on `exp` and `nbf`, by default
+const DEFAULT_LEEWAY: Duration = Duration::from_secs(60);
+
+/// Audiences and issuers accepted when validating tokens locally
+///
+/// With no audience, tokens for any audience are accepted; with several,
+/// a token must be addressed to at least one of them. With no issuer,
+/// only tokens issued by the client's Keyrunes server (its base URL) are
+/// accepted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenValidation {
+    audiences: Vec<String>,
+    issuers: Vec<String>,
+    leeway: Duration,
+}
+
+impl TokenValidation {
+    /// Creates a validation accepting any audience, from the client's server.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Accepts tokens addressed to `audience`.
+    pub fn require_audience<S: Into<String>>(mut self, audience: S) -> Self {
+        self.audiences.push(audience.into());
+        self
+    }
+
+    /// Accepts tokens issued by `issuer`.
+    pub fn allow_issuer<S: Into<String>>(mut self, issuer: S) -> Self {
+        self.issuers.push(issuer.into());
+        self
+    }
+
+    /// Sets the clock skew tolerated on expiry (default: 60 seconds).
+    pub fn with_leeway(mut self, leeway: Duration) -> Self {
+        self.leeway = leeway;
+        self
+    }
+
+    /// Accepted audiences
+    pub fn audiences(&self) -> &[String] {
+        &self.audiences
+    }
+
+    /// Accepted issuers (the client's base URL when empty)
+    pub fn issuers(&self) -> &[String] {
+        &self.issuers
+    }
+
+    /// Tolerated clock skew
+    pub fn leeway(&self) -> Duration {
+        self.leeway
+    }
+
+    /// Checks that the `aud` claim names an accepted audience.
+    pub(crate) fn check_audience(&self, payload: &serde_json::Value) -> Result<()> {
+        if self.audiences.is_empty() {
+            return Ok(());
+        }
+        let found: Vec<String> = match payload.get("aud") {
+            Some(serde_json::Value::String(aud)) => vec![aud.clone()],
+            Some(serde_json::Value::Array(auds)) => auds
+                .iter()
+                .filter_map(|aud| aud.as_str().map(str::to_string))
+                .collect(),
+            _ => Vec::new(),
+        };
+        if found.iter().any(|aud| self.audiences.contains(aud)) {
+            Ok(())
+        } else {
+            Err(KeyrunesError::InvalidAudience {
+                expected: self.audiences.clone(),
+                found,
+            })
+        }
+    }
+}
+
+impl Default for TokenValidation {
+    fn default() -> Self {
+        Self {
+            audiences: Vec::new(),
+            issuers: Vec::new(),
+            leeway: DEFAULT_LEEWAY,
+        }
+    }
+}
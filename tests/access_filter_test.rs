@@ -0,0 +1,84 @@
+use keyrunes_rust_sdk::access_filter::{AccessFilter, Placeholder};
+use keyrunes_rust_sdk::KeyrunesClient;
+use mockito::Server;
+
+fn filter() -> AccessFilter {
+    AccessFilter::new(vec!["1".to_string(), "2".to_string(), "3".to_string()])
+}
+
+#[test]
+fn test_sql_in_clause_placeholders() {
+    // #act
+    let question = filter().sql_in_clause("documents.id", Placeholder::Question);
+    let dollar = filter().sql_in_clause("documents.id", Placeholder::Dollar(2));
+
+    // #assert
+    assert_eq!(question.sql, "documents.id IN (?, ?, ?)");
+    assert_eq!(dollar.sql, "documents.id IN ($2, $3, $4)");
+    assert_eq!(dollar.params, vec!["1", "2", "3"]);
+}
+
+#[test]
+fn test_sql_in_clause_empty_matches_nothing() {
+    // #act
+    let clause = AccessFilter::default().sql_in_clause("id", Placeholder::Question);
+
+    // #assert
+    assert_eq!(clause.sql, "1 = 0");
+    assert!(clause.params.is_empty());
+}
+
+#[test]
+fn test_contains() {
+    assert!(filter().contains("2"));
+    assert!(!filter().contains("4"));
+}
+
+#[cfg(feature = "sea-query")]
+#[test]
+fn test_sea_query_condition() {
+    use sea_query::{Alias, PostgresQueryBuilder, Query};
+
+    // #act
+    let sql = Query::select()
+        .column(Alias::new("id"))
+        .from(Alias::new("documents"))
+        .cond_where(filter().condition(Alias::new("id")))
+        .to_string(PostgresQueryBuilder);
+    let empty = Query::select()
+        .column(Alias::new("id"))
+        .from(Alias::new("documents"))
+        .cond_where(AccessFilter::default().condition(Alias::new("id")))
+        .to_string(PostgresQueryBuilder);
+
+    // #assert
+    assert_eq!(
+        sql,
+        r#"SELECT "id" FROM "documents" WHERE "id" IN ('1', '2', '3')"#
+    );
+    assert_eq!(empty, r#"SELECT "id" FROM "documents" WHERE 1 = 0"#);
+}
+
+#[tokio::test]
+async fn test_load() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let _mock = server
+        .mock("GET", "/api/users/123/accessible")
+        .match_query(mockito::Matcher::Any)
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"items":["7","9"]}"#)
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::new(server.url()).unwrap();
+
+    // #act
+    let filter = AccessFilter::load(&client, "123", "document", "read")
+        .await
+        .unwrap();
+
+    // #assert
+    assert_eq!(filter.into_ids(), vec!["7", "9"]);
+}
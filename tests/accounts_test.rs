@@ -0,0 +1,453 @@
+use keyrunes_rust_sdk::{KeyrunesClient, KeyrunesError};
+use mockito::Server;
+
+#[tokio::test]
+async fn test_link_accounts() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("POST", "/api/users/123/linked-accounts")
+        .match_header("authorization", "Bearer test-token-789")
+        .match_body(mockito::Matcher::Json(
+            serde_json::json!({"user_id": "456"}),
+        ))
+        .with_status(201)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"primary_user_id":"123","user_id":"456","provider":"google"}"#)
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::new(server.url()).unwrap();
+    client.set_token("test-token-789").await;
+
+    // #act
+    let link = client.link_accounts("123", "456").await.unwrap();
+
+    // #assert
+    assert_eq!(link.primary_user_id, "123");
+    assert_eq!(link.user_id, "456");
+    assert_eq!(link.provider.as_deref(), Some("google"));
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_list_linked_accounts() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("GET", "/api/users/123/linked-accounts")
+        .match_header("authorization", "Bearer test-token-789")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"[{"primary_user_id":"123","user_id":"456"}]"#)
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::new(server.url()).unwrap();
+    client.set_token("test-token-789").await;
+
+    // #act
+    let links = client.list_linked_accounts("123").await.unwrap();
+
+    // #assert
+    assert_eq!(links.len(), 1);
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_list_linked_accounts_no_token() {
+    // #setup
+    let client = KeyrunesClient::new("https://example.com").unwrap();
+
+    // #act
+    let result = client.list_linked_accounts("123").await;
+
+    // #assert
+    assert!(matches!(result, Err(KeyrunesError::InvalidToken)));
+}
+
+#[tokio::test]
+async fn test_change_username() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("PATCH", "/api/me/username")
+        .match_header("authorization", "Bearer test-token-789")
+        .match_body(mockito::Matcher::Json(
+            serde_json::json!({"username": "johnny"}),
+        ))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"user_id":123,"username":"johnny","email":"john@example.com"}"#)
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::new(server.url()).unwrap();
+    client.set_token("test-token-789").await;
+
+    // #act
+    let user = client.change_username("johnny").await.unwrap();
+
+    // #assert
+    assert_eq!(user.username, "johnny");
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_change_username_conflict() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("PATCH", "/api/me/username")
+        .with_status(409)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"message":"Username already exists"}"#)
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::new(server.url()).unwrap();
+    client.set_token("test-token-789").await;
+
+    // #act
+    let result = client.change_username("admin").await;
+
+    // #assert
+    assert!(matches!(
+        result,
+        Err(KeyrunesError::UsernameTaken(name)) if name == "admin"
+    ));
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_change_password_completes_forced_rotation() {
+    // #setup
+    let mut server = Server::new_async().await;
+    server
+        .mock("POST", "/api/register")
+        .with_status(201)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"user":{"user_id":123,"username":"john","email":"john@example.com"},"token":"rotation-token","requires_password_change":true}"#)
+        .create_async()
+        .await;
+    let mock = server
+        .mock("POST", "/api/me/password")
+        .match_header("authorization", "Bearer rotation-token")
+        .match_body(mockito::Matcher::Json(serde_json::json!({
+            "current_password": "temporary1",
+            "new_password": "correct-horse-battery",
+        })))
+        .with_status(204)
+        .expect(1)
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::new(server.url()).unwrap();
+    let registration = client
+        .register_with_outcome("john", "john@example.com", "temporary1", None)
+        .await
+        .unwrap();
+
+    // #act
+    client
+        .as_user(registration.token.clone().unwrap())
+        .change_password("temporary1", "correct-horse-battery")
+        .await
+        .unwrap();
+
+    // #assert
+    assert!(registration.requires_password_change);
+    assert!(client.current_token().await.is_none());
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_change_password_wrong_current_password() {
+    // #setup
+    let mut server = Server::new_async().await;
+    server
+        .mock("POST", "/api/me/password")
+        .with_status(401)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"message":"Invalid current password"}"#)
+        .create_async()
+        .await;
+    let client = KeyrunesClient::new(server.url()).unwrap();
+    client.set_token("test-token-789").await;
+
+    // #act
+    let result = client
+        .change_password("wrong", "correct-horse-battery")
+        .await;
+
+    // #assert
+    assert!(matches!(
+        result,
+        Err(KeyrunesError::AuthenticationError { .. })
+    ));
+}
+
+#[tokio::test]
+async fn test_email_verification() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let send = server
+        .mock("POST", "/api/me/email/verification")
+        .match_header("authorization", "Bearer test-token-789")
+        .with_status(202)
+        .expect(1)
+        .create_async()
+        .await;
+    let verify = server
+        .mock("POST", "/api/me/email/verify")
+        .match_body(mockito::Matcher::Json(
+            serde_json::json!({"code": "482913"}),
+        ))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{"user_id":123,"username":"john","email":"john@example.com","email_verified":true}"#,
+        )
+        .expect(1)
+        .create_async()
+        .await;
+    let client = KeyrunesClient::new(server.url()).unwrap();
+    client.set_token("test-token-789").await;
+
+    // #act
+    client.send_verification_email().await.unwrap();
+    let user = client.verify_email("482913").await.unwrap();
+
+    // #assert
+    assert_eq!(user.email_verified, Some(true));
+    send.assert_async().await;
+    verify.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_request_password_reset() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("POST", "/api/password/reset")
+        .match_body(mockito::Matcher::Json(serde_json::json!({
+            "email": "john@example.com",
+            "namespace": "public",
+        })))
+        .with_status(202)
+        .expect(1)
+        .create_async()
+        .await;
+    let client = KeyrunesClient::new(server.url()).unwrap();
+
+    // #act
+    let result = client.request_password_reset("john@example.com").await;
+
+    // #assert
+    assert!(result.is_ok());
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_confirm_password_reset() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("POST", "/api/password/reset/confirm")
+        .match_body(mockito::Matcher::Json(serde_json::json!({
+            "reset_token": "reset-123",
+            "new_password": "correct-horse-battery",
+        })))
+        .with_status(204)
+        .expect(1)
+        .create_async()
+        .await;
+    let client = KeyrunesClient::new(server.url()).unwrap();
+
+    // #act
+    let result = client
+        .confirm_password_reset("reset-123", "correct-horse-battery")
+        .await;
+
+    // #assert
+    assert!(result.is_ok());
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_confirm_password_reset_errors() {
+    // #setup
+    let mut server = Server::new_async().await;
+    server
+        .mock("POST", "/api/password/reset/confirm")
+        .match_body(mockito::Matcher::PartialJson(
+            serde_json::json!({"reset_token": "old"}),
+        ))
+        .with_status(400)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"code":"reset_token_expired","message":"Reset link expired"}"#)
+        .create_async()
+        .await;
+    server
+        .mock("POST", "/api/password/reset/confirm")
+        .match_body(mockito::Matcher::PartialJson(
+            serde_json::json!({"reset_token": "fresh"}),
+        ))
+        .with_status(422)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"code":"weak_password","message":"Password is too common"}"#)
+        .create_async()
+        .await;
+    let client = KeyrunesClient::new(server.url()).unwrap();
+
+    // #act
+    let expired = client.confirm_password_reset("old", "correct-horse").await;
+    let weak = client.confirm_password_reset("fresh", "password").await;
+
+    // #assert
+    assert!(matches!(expired, Err(KeyrunesError::ResetTokenExpired)));
+    assert!(matches!(
+        weak,
+        Err(KeyrunesError::PasswordRejected(message)) if message == "Password is too common"
+    ));
+}
+
+#[tokio::test]
+async fn test_username_history() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("GET", "/api/users/123/username-history")
+        .match_header("authorization", "Bearer test-token-789")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"[{"old_username":"john","new_username":"johnny","changed_at":"2026-01-01T00:00:00Z"}]"#)
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::new(server.url()).unwrap();
+    client.set_token("test-token-789").await;
+
+    // #act
+    let history = client.username_history("123").await.unwrap();
+
+    // #assert
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0].old_username, "john");
+    assert!(history[0].changed_at.is_some());
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_upload_avatar() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("PUT", "/api/users/123/avatar")
+        .match_header("authorization", "Bearer test-token-789")
+        .match_header(
+            "content-type",
+            mockito::Matcher::Regex("^multipart/form-data; boundary=".to_string()),
+        )
+        .match_body(mockito::Matcher::Regex("image/png".to_string()))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"url":"https://cdn.example.com/123.png","content_type":"image/png"}"#)
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::new(server.url()).unwrap();
+    client.set_token("test-token-789").await;
+
+    // #act
+    let avatar = client
+        .upload_avatar("123", vec![0x89, 0x50, 0x4e, 0x47], "image/png")
+        .await
+        .unwrap();
+
+    // #assert
+    assert_eq!(avatar.url, "https://cdn.example.com/123.png");
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_upload_avatar_rejects_non_image() {
+    // #setup
+    let client = KeyrunesClient::new("https://example.com").unwrap();
+    client.set_token("test-token-789").await;
+
+    // #act
+    let result = client
+        .upload_avatar("123", b"hello".to_vec(), "text/plain")
+        .await;
+
+    // #assert
+    assert!(matches!(result, Err(KeyrunesError::Other(_))));
+}
+
+#[tokio::test]
+async fn test_get_avatar_url() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let found_mock = server
+        .mock("GET", "/api/users/123/avatar")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"url":"https://cdn.example.com/123.png"}"#)
+        .create_async()
+        .await;
+    let missing_mock = server
+        .mock("GET", "/api/users/456/avatar")
+        .with_status(404)
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::new(server.url()).unwrap();
+    client.set_token("test-token-789").await;
+
+    // #act
+    let found = client.get_avatar_url("123").await.unwrap();
+    let missing = client.get_avatar_url("456").await.unwrap();
+
+    // #assert
+    assert_eq!(found.as_deref(), Some("https://cdn.example.com/123.png"));
+    assert!(missing.is_none());
+    found_mock.assert_async().await;
+    missing_mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_upload_avatar_with_progress() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("PUT", "/api/users/123/avatar")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"url":"https://cdn.example.com/123.png"}"#)
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::new(server.url()).unwrap();
+    client.set_token("test-token-789").await;
+    let reports = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    let sink = reports.clone();
+    let progress: keyrunes_rust_sdk::client::transport::ProgressCallback =
+        std::sync::Arc::new(move |p| sink.lock().unwrap().push(p));
+
+    // #act
+    let result = client
+        .upload_avatar_with_progress("123", vec![0u8; 150 * 1024], "image/png", Some(progress))
+        .await;
+
+    // #assert
+    assert!(result.is_ok());
+    let last = {
+        let reports = reports.lock().unwrap();
+        assert_eq!(reports.len(), 3);
+        *reports.last().unwrap()
+    };
+    assert_eq!(last.transferred, 150 * 1024);
+    assert_eq!(last.total, Some(150 * 1024));
+    mock.assert_async().await;
+}
@@ -0,0 +1,49 @@
+use keyrunes_rust_sdk::{KeyrunesClient, LoginHistoryFilter};
+use mockito::{Matcher, Server};
+
+#[tokio::test]
+async fn test_get_login_history() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("GET", "/api/users/123/login-history")
+        .match_header("authorization", "Bearer test-token-789")
+        .match_query(Matcher::AllOf(vec![
+            Matcher::UrlEncoded("success".into(), "false".into()),
+            Matcher::UrlEncoded("limit".into(), "2".into()),
+            Matcher::UrlEncoded("cursor".into(), "abc".into()),
+        ]))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{
+                "items": [
+                    {"timestamp":"2024-05-01T10:00:00Z","ip":"203.0.113.7","user_agent":"Firefox","success":false,"method":"password","failure_reason":"invalid_password"},
+                    {"timestamp":"2024-04-30T08:00:00Z","success":false,"method":"otp"}
+                ],
+                "next_cursor": "def",
+                "total": 5
+            }"#,
+        )
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::new(server.url()).unwrap();
+    client.set_token("test-token-789").await;
+    let filter = LoginHistoryFilter::new()
+        .success(false)
+        .limit(2)
+        .cursor("abc");
+
+    // #act
+    let page = client.get_login_history("123", &filter).await.unwrap();
+
+    // #assert
+    assert_eq!(page.items.len(), 2);
+    assert_eq!(page.items[0].ip.as_deref(), Some("203.0.113.7"));
+    assert_eq!(page.items[0].method.as_deref(), Some("password"));
+    assert!(page.items[1].user_agent.is_none());
+    assert!(page.has_more());
+    assert_eq!(page.total, Some(5));
+    mock.assert_async().await;
+}
@@ -0,0 +1,168 @@
+#![cfg(feature = "actix")]
+
+use actix_web::{test, web, App, HttpResponse};
+use keyrunes_rust_sdk::middleware::actix::{
+    KeyrunesAuthMiddleware, KeyrunesRequireAuth, KeyrunesRequireGroup, KeyrunesState,
+};
+use keyrunes_rust_sdk::middleware::bypass::{BypassRules, RoutePattern};
+use keyrunes_rust_sdk::KeyrunesClient;
+use mockito::Server;
+
+async fn mock_me(server: &mut Server) {
+    server
+        .mock("GET", "/api/me")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"id":"123","username":"john","email":"john@example.com","groups":[]}"#)
+        .create_async()
+        .await;
+}
+
+async fn mock_group(server: &mut Server, has_group: bool) {
+    server
+        .mock("GET", "/api/users/123/groups/admins")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(format!(r#"{{"has_group":{}}}"#, has_group))
+        .create_async()
+        .await;
+}
+
+async fn admin_status(server: &Server, token: Option<&str>) -> u16 {
+    let state = KeyrunesState::new(KeyrunesClient::new(server.url()).unwrap());
+    let app = test::init_service(
+        App::new().app_data(web::Data::new(state)).service(
+            web::scope("/admin")
+                .wrap(KeyrunesRequireGroup::new("admins"))
+                .wrap(KeyrunesAuthMiddleware)
+                .route("/dashboard", web::get().to(HttpResponse::Ok)),
+        ),
+    )
+    .await;
+
+    let mut request = test::TestRequest::get().uri("/admin/dashboard");
+    if let Some(token) = token {
+        request = request.insert_header(("authorization", format!("Bearer {}", token)));
+    }
+    match test::try_call_service(&app, request.to_request()).await {
+        Ok(response) => response.status().as_u16(),
+        Err(err) => err.as_response_error().status_code().as_u16(),
+    }
+}
+
+#[actix_web::test]
+async fn test_require_group_allows_members() {
+    // #setup
+    let mut server = Server::new_async().await;
+    mock_me(&mut server).await;
+    mock_group(&mut server, true).await;
+
+    // #act
+    let status = admin_status(&server, Some("token")).await;
+
+    // #assert
+    assert_eq!(status, 200);
+}
+
+#[actix_web::test]
+async fn test_require_group_rejects_non_members() {
+    // #setup
+    let mut server = Server::new_async().await;
+    mock_me(&mut server).await;
+    mock_group(&mut server, false).await;
+
+    // #act
+    let status = admin_status(&server, Some("token")).await;
+
+    // #assert
+    assert_eq!(status, 403);
+}
+
+#[actix_web::test]
+async fn test_require_group_rejects_anonymous() {
+    // #setup
+    let server = Server::new_async().await;
+
+    // #act
+    let status = admin_status(&server, None).await;
+
+    // #assert
+    assert_eq!(status, 401);
+}
+
+async fn scope_status(server: &Server, request: test::TestRequest) -> u16 {
+    let state = KeyrunesState::new(KeyrunesClient::new(server.url()).unwrap());
+    let app = test::init_service(
+        App::new().app_data(web::Data::new(state)).service(
+            web::scope("")
+                .wrap(KeyrunesRequireGroup::new("admins"))
+                .wrap(KeyrunesAuthMiddleware)
+                .route("/health", web::get().to(HttpResponse::Ok))
+                .route("/dashboard", web::route().to(HttpResponse::Ok)),
+        ),
+    )
+    .await;
+
+    match test::try_call_service(&app, request.to_request()).await {
+        Ok(response) => response.status().as_u16(),
+        Err(err) => err.as_response_error().status_code().as_u16(),
+    }
+}
+
+#[actix_web::test]
+async fn test_preflight_and_health_checks_bypass_auth() {
+    // #setup
+    let server = Server::new_async().await;
+
+    // #act
+    let preflight = scope_status(
+        &server,
+        test::TestRequest::default()
+            .method(actix_web::http::Method::OPTIONS)
+            .uri("/dashboard"),
+    )
+    .await;
+    let health = scope_status(&server, test::TestRequest::get().uri("/health")).await;
+    let dashboard = scope_status(&server, test::TestRequest::get().uri("/dashboard")).await;
+
+    // #assert
+    assert_eq!(preflight, 200);
+    assert_eq!(health, 200);
+    assert_eq!(dashboard, 401);
+}
+
+#[actix_web::test]
+async fn test_require_auth_allows_public_routes() {
+    // #setup
+    let server = Server::new_async().await;
+    let state = KeyrunesState::new(KeyrunesClient::new(server.url()).unwrap())
+        .with_bypass_rules(BypassRules::none().with_route(RoutePattern::glob("/docs/*")));
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(state))
+            .wrap(KeyrunesRequireAuth)
+            .route("/docs/{page}", web::get().to(HttpResponse::Ok))
+            .route("/profile", web::get().to(HttpResponse::Ok)),
+    )
+    .await;
+
+    // #act
+    let public = test::call_service(
+        &app,
+        test::TestRequest::get().uri("/docs/intro").to_request(),
+    )
+    .await
+    .status()
+    .as_u16();
+    let private =
+        match test::try_call_service(&app, test::TestRequest::get().uri("/profile").to_request())
+            .await
+        {
+            Ok(response) => response.status().as_u16(),
+            Err(err) => err.as_response_error().status_code().as_u16(),
+        };
+
+    // #assert
+    assert_eq!(public, 200);
+    assert_eq!(private, 401);
+}
@@ -0,0 +1,113 @@
+use keyrunes_rust_sdk::middleware::admin_policy::{
+    AdminPolicy, ClaimPolicy, GroupPolicy, PermissionPolicy, RolePolicy,
+};
+use keyrunes_rust_sdk::{KeyrunesClient, User};
+use mockito::Server;
+use serde_json::json;
+
+fn user() -> User {
+    User {
+        id: "123".to_string(),
+        username: "john".to_string(),
+        email: "john@example.com".to_string(),
+        groups: vec![],
+        ..Default::default()
+    }
+}
+
+fn make_token(claims: serde_json::Value) -> String {
+    jsonwebtoken::encode(
+        &jsonwebtoken::Header::default(),
+        &claims,
+        &jsonwebtoken::EncodingKey::from_secret(b"secret"),
+    )
+    .unwrap()
+}
+
+#[tokio::test]
+async fn test_group_policy_defaults_to_admins() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("GET", "/api/users/123/groups/admins")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"has_group":true}"#)
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::new(server.url()).unwrap();
+    client.set_token("token".to_string()).await;
+
+    // #act
+    let is_admin = GroupPolicy::default()
+        .is_admin(&client, &user(), "token")
+        .await
+        .unwrap();
+
+    // #assert
+    mock.assert_async().await;
+    assert!(is_admin);
+}
+
+#[tokio::test]
+async fn test_permission_policy() {
+    // #setup
+    let mut server = Server::new_async().await;
+    server
+        .mock("GET", "/api/users/123/permissions")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"["admin:*"]"#)
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::new(server.url()).unwrap();
+
+    // #act
+    let allowed = PermissionPolicy::new("admin:users")
+        .is_admin(&client, &user(), "token")
+        .await
+        .unwrap();
+    let denied = PermissionPolicy::new("billing:write")
+        .is_admin(&client, &user(), "token")
+        .await
+        .unwrap();
+
+    // #assert
+    assert!(allowed);
+    assert!(!denied);
+}
+
+#[tokio::test]
+async fn test_local_claim_policies() {
+    // #setup
+    let client = KeyrunesClient::new("https://example.com").unwrap();
+    let token = make_token(json!({
+        "sub": "123",
+        "roles": ["superuser"],
+        "app_metadata": {"admin": true}
+    }));
+
+    // #assert
+    assert!(RolePolicy::new("superuser")
+        .is_admin(&client, &user(), &token)
+        .await
+        .unwrap());
+    assert!(!RolePolicy::new("owner")
+        .is_admin(&client, &user(), &token)
+        .await
+        .unwrap());
+    assert!(ClaimPolicy::new("app_metadata.admin", true)
+        .is_admin(&client, &user(), &token)
+        .await
+        .unwrap());
+    assert!(ClaimPolicy::new("roles", "superuser")
+        .is_admin(&client, &user(), &token)
+        .await
+        .unwrap());
+    assert!(!ClaimPolicy::new("missing", true)
+        .is_admin(&client, &user(), &token)
+        .await
+        .unwrap());
+}
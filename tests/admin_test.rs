@@ -0,0 +1,900 @@
+use keyrunes_rust_sdk::{
+    AuthMethod, Branding, DomainStatus, EmailTemplateContent, EmailTemplateKind, EventFilter,
+    HashAlgorithm, IpRuleAction, KeyrunesClient, KeyrunesError, MembershipChangeStatus, MfaPolicy,
+    SigningKeyStatus, UserImport,
+};
+use mockito::Server;
+
+#[tokio::test]
+async fn test_admin_stats() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("GET", "/api/admin/stats")
+        .match_header("authorization", "Bearer admin-token")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{"total_users":1200,"active_sessions":87,"logins_last_24h":430,"mfa_adoption":0.62}"#,
+        )
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::new(server.url()).unwrap();
+    client.set_token("admin-token").await;
+
+    // #act
+    let stats = client.admin().stats().await.unwrap();
+
+    // #assert
+    assert_eq!(stats.total_users, 1200);
+    assert_eq!(stats.active_sessions, 87);
+    assert_eq!(stats.logins_last_24h, 430);
+    assert!((stats.mfa_adoption - 0.62).abs() < f64::EPSILON);
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_admin_stats_forbidden() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let _mock = server
+        .mock("GET", "/api/admin/stats")
+        .with_status(403)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"message":"Admin access required"}"#)
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::new(server.url()).unwrap();
+    client.set_token("user-token").await;
+
+    // #act
+    let result = client.admin().stats().await;
+
+    // #assert
+    assert!(matches!(
+        result,
+        Err(KeyrunesError::AuthorizationError { .. })
+    ));
+}
+
+#[tokio::test]
+async fn test_ip_rules() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let add_mock = server
+        .mock("POST", "/api/admin/ip-rules")
+        .match_body(mockito::Matcher::Json(
+            serde_json::json!({ "cidr": "203.0.113.7/32", "action": "deny" }),
+        ))
+        .with_status(201)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"id":"r1","cidr":"203.0.113.7/32","action":"deny"}"#)
+        .create_async()
+        .await;
+    let list_mock = server
+        .mock("GET", "/api/admin/ip-rules")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"[{"id":"r1","cidr":"203.0.113.7/32","action":"deny"}]"#)
+        .create_async()
+        .await;
+    let delete_mock = server
+        .mock("DELETE", "/api/admin/ip-rules/r1")
+        .with_status(204)
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::new(server.url()).unwrap();
+    client.set_token("admin-token").await;
+    let admin = client.admin();
+
+    // #act
+    let rule = admin
+        .add_ip_rule("203.0.113.7", IpRuleAction::Deny)
+        .await
+        .unwrap();
+    let rules = admin.list_ip_rules().await.unwrap();
+    admin.delete_ip_rule(&rule.id).await.unwrap();
+
+    // #assert
+    assert_eq!(rule.action, IpRuleAction::Deny);
+    assert_eq!(rules.len(), 1);
+    add_mock.assert_async().await;
+    list_mock.assert_async().await;
+    delete_mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_add_ip_rule_rejects_invalid_cidr() {
+    // #setup
+    let client = KeyrunesClient::new("http://localhost:1").unwrap();
+    client.set_token("admin-token").await;
+
+    for cidr in ["203.0.113.0/33", "not-an-ip", "2001:db8::/129", "10.0.0.0/"] {
+        // #act
+        let result = client.admin().add_ip_rule(cidr, IpRuleAction::Allow).await;
+
+        // #assert
+        assert!(
+            matches!(result, Err(KeyrunesError::InvalidCidr(_))),
+            "{} should be rejected",
+            cidr
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_permission_catalog() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let create_mock = server
+        .mock("POST", "/api/admin/permissions")
+        .match_body(mockito::Matcher::Json(
+            serde_json::json!({ "key": "documents:read", "description": "Read documents" }),
+        ))
+        .with_status(201)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"key":"documents:read","description":"Read documents"}"#)
+        .create_async()
+        .await;
+    let list_mock = server
+        .mock("GET", "/api/admin/permissions")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"[{"key":"documents:read","description":"Read documents"}]"#)
+        .create_async()
+        .await;
+    let delete_mock = server
+        .mock("DELETE", "/api/admin/permissions/documents%3Aread")
+        .with_status(204)
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::new(server.url()).unwrap();
+    client.set_token("admin-token").await;
+    let admin = client.admin();
+
+    // #act
+    let created = admin
+        .create_permission("documents:read", "Read documents")
+        .await
+        .unwrap();
+    let permissions = admin.list_permissions().await.unwrap();
+    admin.delete_permission("documents:read").await.unwrap();
+
+    // #assert
+    assert_eq!(created.key, "documents:read");
+    assert_eq!(permissions, vec![created]);
+    create_mock.assert_async().await;
+    list_mock.assert_async().await;
+    delete_mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_sync_permissions() {
+    use keyrunes_rust_sdk::permissions::PermissionDef;
+
+    // #setup
+    const CATALOG: &[PermissionDef] = &[
+        PermissionDef::new("documents:read", "Read documents"),
+        PermissionDef::new("documents:write", "Create and edit documents"),
+        PermissionDef::new("billing:read", "Read invoices"),
+        PermissionDef::new("reports:read", "Read reports"),
+    ];
+    let mut server = Server::new_async().await;
+    let _list_mock = server
+        .mock("GET", "/api/admin/permissions")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"[
+                {"key":"documents:read","description":"Read documents"},
+                {"key":"documents:write","description":"Edit documents"},
+                {"key":"billing:read","description":"Read invoices","deprecated":true},
+                {"key":"legacy:export","description":"Export everything"},
+                {"key":"legacy:old","deprecated":true}
+            ]"#,
+        )
+        .create_async()
+        .await;
+    let create_mock = server
+        .mock("POST", "/api/admin/permissions")
+        .match_body(mockito::Matcher::Json(
+            serde_json::json!({ "key": "reports:read", "description": "Read reports" }),
+        ))
+        .with_status(201)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"key":"reports:read","description":"Read reports"}"#)
+        .expect(1)
+        .create_async()
+        .await;
+    let update_mock = server
+        .mock("PATCH", "/api/admin/permissions/documents%3Awrite")
+        .match_body(mockito::Matcher::Json(serde_json::json!({
+            "description": "Create and edit documents",
+            "deprecated": false
+        })))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"key":"documents:write","description":"Create and edit documents"}"#)
+        .expect(1)
+        .create_async()
+        .await;
+    let restore_mock = server
+        .mock("PATCH", "/api/admin/permissions/billing%3Aread")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"key":"billing:read","description":"Read invoices"}"#)
+        .expect(1)
+        .create_async()
+        .await;
+    let deprecate_mock = server
+        .mock("PATCH", "/api/admin/permissions/legacy%3Aexport")
+        .match_body(mockito::Matcher::Json(
+            serde_json::json!({ "deprecated": true }),
+        ))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"key":"legacy:export","deprecated":true}"#)
+        .expect(1)
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::new(server.url()).unwrap();
+    client.set_token("admin-token").await;
+
+    // #act
+    let report = client.admin().sync_permissions(CATALOG).await.unwrap();
+
+    // #assert
+    assert_eq!(report.created, vec!["reports:read"]);
+    assert_eq!(report.updated, vec!["documents:write", "billing:read"]);
+    assert_eq!(report.deprecated, vec!["legacy:export"]);
+    assert_eq!(report.unchanged, 1);
+    assert!(!report.is_noop());
+    create_mock.assert_async().await;
+    update_mock.assert_async().await;
+    restore_mock.assert_async().await;
+    deprecate_mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_signing_keys() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let _list_mock = server
+        .mock("GET", "/api/admin/signing-keys")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"[
+                {"kid":"k2","alg":"RS256","status":"active","activated_at":"2026-01-01T00:00:00Z"},
+                {"kid":"k1","alg":"RS256","status":"inactive"},
+                {"kid":"k0","alg":"RS256","status":"retired","retired_at":"2025-06-01T00:00:00Z"}
+            ]"#,
+        )
+        .create_async()
+        .await;
+    let rotate_mock = server
+        .mock("POST", "/api/admin/signing-keys/rotate")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"kid":"k3","alg":"RS256","status":"active"}"#)
+        .expect(1)
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::new(server.url()).unwrap();
+    client.set_token("admin-token").await;
+
+    // #act
+    let keys = client.admin().signing_keys().await.unwrap();
+    let rotated = client.admin().rotate_signing_key().await.unwrap();
+
+    // #assert
+    assert_eq!(keys.len(), 3);
+    assert_eq!(keys[0].status, SigningKeyStatus::Active);
+    assert!(keys[0].activated_at.is_some());
+    assert_eq!(keys[2].status, SigningKeyStatus::Retired);
+    assert_eq!(rotated.kid, "k3");
+    rotate_mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_retire_signing_key() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let retire_mock = server
+        .mock("POST", "/api/admin/signing-keys/k1/retire")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"kid":"k1","alg":"RS256","status":"retired"}"#)
+        .expect(1)
+        .create_async()
+        .await;
+    let _active_mock = server
+        .mock("POST", "/api/admin/signing-keys/k2/retire")
+        .with_status(409)
+        .with_body("Active keys cannot be retired")
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::new(server.url()).unwrap();
+    client.set_token("admin-token").await;
+
+    // #act
+    let retired = client.admin().retire_signing_key("k1").await.unwrap();
+    let active = client.admin().retire_signing_key("k2").await;
+
+    // #assert
+    assert_eq!(retired.status, SigningKeyStatus::Retired);
+    assert!(matches!(active, Err(KeyrunesError::HttpError(_))));
+    retire_mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_tenant_settings() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let settings = serde_json::json!({
+        "sessions": {"access_token_lifetime": 900, "refresh_token_lifetime": 2592000},
+        "mfa": "optional",
+        "password_policy": {"min_length": 8, "require_digit": true},
+        "auth_methods": ["password", "magic_link"],
+        "branding": {"color": "#336699"},
+    });
+    let _get_mock = server
+        .mock("GET", "/api/admin/settings")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(settings.to_string())
+        .create_async()
+        .await;
+    let update_mock = server
+        .mock("PUT", "/api/admin/settings")
+        .match_body(mockito::Matcher::PartialJson(serde_json::json!({
+            "mfa": "required",
+            "password_policy": {"min_length": 12, "require_digit": true},
+            "auth_methods": ["password", "passkey"],
+            "branding": {"color": "#336699"},
+        })))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(settings.to_string())
+        .expect(1)
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::new(server.url()).unwrap();
+    client.set_token("admin-token").await;
+    let admin = client.admin();
+
+    // #act
+    let mut current = admin.get_settings().await.unwrap();
+    current.mfa = MfaPolicy::Required;
+    current.password_policy.min_length = 12;
+    current.auth_methods = vec![AuthMethod::Password, AuthMethod::Passkey];
+    admin.update_settings(&current).await.unwrap();
+
+    // #assert
+    assert_eq!(current.sessions.access_token_lifetime, 900);
+    assert_eq!(current.sessions.idle_timeout, None);
+    update_mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_email_templates() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let _list_mock = server
+        .mock("GET", "/api/admin/email-templates")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"[{"kind":"verification","subject":"Verify your email","html_body":"<p>{{link}}</p>","is_default":true},
+                {"kind":"password_reset","subject":"Reset","html_body":"<p>{{link}}</p>","text_body":"{{link}}"}]"#,
+        )
+        .create_async()
+        .await;
+    let update_mock = server
+        .mock("PUT", "/api/admin/email-templates/password_reset")
+        .match_body(mockito::Matcher::Json(serde_json::json!({
+            "subject": "Reset your Acme password",
+            "html_body": "<p>{{link}}</p>",
+        })))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{"kind":"password_reset","subject":"Reset your Acme password","html_body":"<p>{{link}}</p>"}"#,
+        )
+        .expect(1)
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::new(server.url()).unwrap();
+    client.set_token("admin-token").await;
+    let content = EmailTemplateContent {
+        subject: "Reset your Acme password".to_string(),
+        html_body: "<p>{{link}}</p>".to_string(),
+        text_body: None,
+    };
+
+    // #act
+    let templates = client.admin().list_email_templates().await.unwrap();
+    let updated = client
+        .admin()
+        .update_email_template(EmailTemplateKind::PasswordReset, &content)
+        .await
+        .unwrap();
+
+    // #assert
+    assert_eq!(templates[0].kind, EmailTemplateKind::Verification);
+    assert!(templates[0].is_default);
+    assert_eq!(templates[1].content.text_body.as_deref(), Some("{{link}}"));
+    assert_eq!(updated.content, content);
+    update_mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_render_email_template() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let render_mock = server
+        .mock("POST", "/api/admin/email-templates/invitation/render")
+        .match_body(mockito::Matcher::Json(serde_json::json!({
+            "template": {"subject": "Join {{tenant}}", "html_body": "<p>Join {{tenant}}</p>"},
+            "variables": {"tenant": "Acme"},
+        })))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"subject":"Join Acme","html":"<p>Join Acme</p>","text":"Join Acme"}"#)
+        .expect(1)
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::new(server.url()).unwrap();
+    client.set_token("admin-token").await;
+    let draft = EmailTemplateContent {
+        subject: "Join {{tenant}}".to_string(),
+        html_body: "<p>Join {{tenant}}</p>".to_string(),
+        text_body: None,
+    };
+
+    // #act
+    let email = client
+        .admin()
+        .render_email_template(
+            EmailTemplateKind::Invitation,
+            Some(&draft),
+            Some(&serde_json::json!({"tenant": "Acme"})),
+        )
+        .await
+        .unwrap();
+
+    // #assert
+    assert_eq!(email.subject, "Join Acme");
+    assert_eq!(email.text, "Join Acme");
+    render_mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_update_branding() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("PUT", "/api/admin/branding")
+        .match_body(mockito::Matcher::Json(serde_json::json!({
+            "logo_url": "https://cdn.example.com/logo.svg",
+            "primary_color": "#336699",
+        })))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r##"{"logo_url":"https://cdn.example.com/logo.svg","primary_color":"#336699","layout":"centered"}"##,
+        )
+        .expect(1)
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::new(server.url()).unwrap();
+    client.set_token("admin-token").await;
+    let branding = Branding {
+        logo_url: Some("https://cdn.example.com/logo.svg".to_string()),
+        primary_color: Some("#336699".to_string()),
+        ..Branding::default()
+    };
+
+    // #act
+    let applied = client.admin().update_branding(&branding).await.unwrap();
+
+    // #assert
+    assert_eq!(applied.primary_color.as_deref(), Some("#336699"));
+    assert_eq!(applied.extra["layout"], "centered");
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_custom_domain() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let set_mock = server
+        .mock("PUT", "/api/admin/custom-domain")
+        .match_body(mockito::Matcher::Json(
+            serde_json::json!({ "domain": "login.example.com" }),
+        ))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{"domain":"login.example.com","status":"pending",
+                "dns_records":[{"type":"CNAME","name":"login.example.com","value":"acme.keyrunes.io"}]}"#,
+        )
+        .expect(1)
+        .create_async()
+        .await;
+    let verify_mock = server
+        .mock("POST", "/api/admin/custom-domain/verify")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{"domain":"login.example.com","status":"verified","verified_at":"2026-03-01T12:00:00Z"}"#,
+        )
+        .expect(1)
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::new(server.url()).unwrap();
+    client.set_token("admin-token").await;
+
+    // #act
+    let pending = client
+        .admin()
+        .set_custom_domain("login.example.com")
+        .await
+        .unwrap();
+    let verified = client.admin().verify_custom_domain().await.unwrap();
+
+    // #assert
+    assert_eq!(pending.status, DomainStatus::Pending);
+    assert_eq!(pending.dns_records[0].record_type, "CNAME");
+    assert_eq!(verified.status, DomainStatus::Verified);
+    assert!(verified.verified_at.is_some());
+    set_mock.assert_async().await;
+    verify_mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_replay_events() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let replay_mock = server
+        .mock("POST", "/api/admin/events/replay")
+        .match_body(mockito::Matcher::Json(serde_json::json!({
+            "event_types": ["user.created"],
+            "webhook_id": "wh_1",
+            "failed_only": true,
+            "from": "2026-03-01T10:00:00Z",
+            "to": "2026-03-01T12:00:00Z",
+        })))
+        .with_status(202)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"job_id":"job_9"}"#)
+        .expect(1)
+        .create_async()
+        .await;
+    let _status_mock = server
+        .mock("GET", "/api/jobs/job_9")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{"id":"job_9","state":"succeeded","result":{"matched":12,"delivered":11,"failed":1}}"#,
+        )
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::new(server.url()).unwrap();
+    client.set_token("admin-token").await;
+    let from = "2026-03-01T10:00:00Z".parse().unwrap();
+    let to = "2026-03-01T12:00:00Z".parse().unwrap();
+    let filter = EventFilter::new()
+        .event_type("user.created")
+        .webhook("wh_1")
+        .failed_only();
+
+    // #act
+    let job = client
+        .admin()
+        .replay_events(from..to, &filter)
+        .await
+        .unwrap();
+    let replay = job
+        .await_completion(
+            std::time::Duration::from_millis(10),
+            std::time::Duration::from_secs(1),
+        )
+        .await
+        .unwrap();
+
+    // #assert
+    assert_eq!(job.id(), "job_9");
+    assert_eq!(replay.delivered, 11);
+    assert_eq!(replay.failed, 1);
+    replay_mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_list_failed_deliveries() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("GET", "/api/admin/webhooks/wh_1/deliveries")
+        .match_query(mockito::Matcher::AllOf(vec![
+            mockito::Matcher::UrlEncoded("status".into(), "failed".into()),
+            mockito::Matcher::UrlEncoded("cursor".into(), "c+1".into()),
+        ]))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{"items":[{"id":"d1","event_id":"e1","event_type":"user.created","attempts":8,
+                "last_status":503,"last_attempt_at":"2026-03-01T11:00:00Z"}],"next_cursor":null}"#,
+        )
+        .expect(1)
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::new(server.url()).unwrap();
+    client.set_token("admin-token").await;
+
+    // #act
+    let page = client
+        .admin()
+        .list_failed_deliveries("wh_1", Some("c+1"))
+        .await
+        .unwrap();
+
+    // #assert
+    assert!(!page.has_more());
+    assert_eq!(page.items[0].attempts, 8);
+    assert_eq!(page.items[0].last_status, Some(503));
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_import_user_with_hash() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let hash = "$argon2id$v=19$m=65536,t=3,p=4$c2FsdHNhbHQ$aGFzaGhhc2hoYXNo";
+    let mock = server
+        .mock("POST", "/api/admin/users/import")
+        .match_header("authorization", "Bearer admin-token")
+        .match_body(mockito::Matcher::Json(serde_json::json!({
+            "username": "john",
+            "email": "john@example.com",
+            "email_verified": true,
+            "password_hash": {"algorithm": "argon2id", "hash": hash},
+        })))
+        .with_status(201)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"id":"123","username":"john","email":"john@example.com"}"#)
+        .expect(1)
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::new(server.url()).unwrap();
+    client.set_token("admin-token").await;
+    let mut user = UserImport::new("john", "john@example.com");
+    user.email_verified = true;
+
+    // #act
+    let imported = client
+        .admin()
+        .import_user_with_hash(&user, HashAlgorithm::Argon2id, hash)
+        .await
+        .unwrap();
+
+    // #assert
+    assert_eq!(imported.id, "123");
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_import_user_with_hash_rejects_malformed_hash() {
+    // #setup
+    let client = KeyrunesClient::new("http://localhost:1").unwrap();
+    client.set_token("admin-token").await;
+    let user = UserImport::new("john", "john@example.com");
+    let cases = [
+        (HashAlgorithm::Bcrypt, "$2b$12$tooshort"),
+        (
+            HashAlgorithm::Bcrypt,
+            "$2b$99$R9h/cIPz0gi.URNNX3kh2OPST9/PgBkqquzi.Ss7KIUgO2t0jWMUW",
+        ),
+        (
+            HashAlgorithm::Pbkdf2Sha256,
+            "$pbkdf2-sha512$i=27500$c2FsdA$aGFzaA",
+        ),
+        (HashAlgorithm::Scrypt, "$scrypt$ln=15,r=8,p=1$$aGFzaA"),
+        (HashAlgorithm::Argon2id, "plaintext"),
+    ];
+
+    for (algorithm, hash) in cases {
+        // #act
+        let result = client
+            .admin()
+            .import_user_with_hash(&user, algorithm, hash)
+            .await;
+
+        // #assert
+        assert!(
+            matches!(result, Err(KeyrunesError::InvalidPasswordHash(_))),
+            "{:?} {} should be rejected",
+            algorithm,
+            hash
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_add_users_to_group_reports_partial_failures() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let user_ids: Vec<String> = (0..502).map(|i| i.to_string()).collect();
+    let first_batch = server
+        .mock("POST", "/api/admin/groups/engineering/members")
+        .match_header("authorization", "Bearer admin-token")
+        .match_body(mockito::Matcher::Json(serde_json::json!({
+            "user_ids": &user_ids[..500],
+        })))
+        .with_status(207)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{"results":[
+                {"user_id":"0","status":"added"},
+                {"user_id":"1","status":"unchanged"}
+            ]}"#,
+        )
+        .expect(1)
+        .create_async()
+        .await;
+    let second_batch = server
+        .mock("POST", "/api/admin/groups/engineering/members")
+        .match_body(mockito::Matcher::Json(serde_json::json!({
+            "user_ids": ["500", "501"],
+        })))
+        .with_status(207)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{"results":[
+                {"user_id":"500","status":"added"},
+                {"user_id":"501","status":"failed","code":"user_not_found","message":"User not found"}
+            ]}"#,
+        )
+        .expect(1)
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::new(server.url()).unwrap();
+    client.set_token("admin-token").await;
+
+    // #act
+    let report = client
+        .admin()
+        .add_users_to_group("engineering", &user_ids)
+        .await
+        .unwrap();
+
+    // #assert
+    assert_eq!(report.results.len(), 4);
+    assert_eq!(report.results[1].status, MembershipChangeStatus::Unchanged);
+    assert!(!report.is_complete());
+    let failed: Vec<_> = report.failed().collect();
+    assert_eq!(failed.len(), 1);
+    assert_eq!(failed[0].user_id, "501");
+    assert_eq!(failed[0].code.as_deref(), Some("user_not_found"));
+    first_batch.assert_async().await;
+    second_batch.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_add_user_to_group_with_expiry() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let grant = server
+        .mock("PUT", "/api/admin/groups/admins/members/123")
+        .match_body(mockito::Matcher::Json(serde_json::json!({
+            "expires_at": "2030-01-01T08:00:00Z",
+        })))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"user_id":"123","group_id":"admins","expires_at":"2030-01-01T08:00:00Z"}"#)
+        .expect(1)
+        .create_async()
+        .await;
+    let listing = server
+        .mock("GET", "/api/admin/groups/admins/members")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"[
+                {"user_id":"1","group_id":"admins","added_at":"2024-01-01T00:00:00Z"},
+                {"user_id":"123","group_id":"admins","expires_at":"2020-01-01T00:00:00Z"}
+            ]"#,
+        )
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::new(server.url()).unwrap();
+    client.set_token("admin-token").await;
+    let expires_at = "2030-01-01T08:00:00Z".parse().unwrap();
+
+    // #act
+    let membership = client
+        .admin()
+        .add_user_to_group_with_expiry("123", "admins", expires_at)
+        .await
+        .unwrap();
+    let members = client.admin().list_group_members("admins").await.unwrap();
+
+    // #assert
+    assert!(membership.is_temporary());
+    assert!(!membership.is_expired());
+    assert!(!members[0].is_temporary());
+    assert!(members[1].is_expired());
+    grant.assert_async().await;
+    listing.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_remove_users_from_group() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("DELETE", "/api/admin/groups/on%20call/members")
+        .match_body(mockito::Matcher::Json(serde_json::json!({
+            "user_ids": ["123", "456"],
+        })))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{"results":[
+                {"user_id":"123","status":"removed"},
+                {"user_id":"456","status":"removed"}
+            ]}"#,
+        )
+        .expect(1)
+        .create_async()
+        .await;
+    let _missing = server
+        .mock("DELETE", "/api/admin/groups/unknown/members")
+        .with_status(404)
+        .with_body(r#"{"message":"Group not found"}"#)
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::new(server.url()).unwrap();
+    client.set_token("admin-token").await;
+
+    // #act
+    let report = client
+        .admin()
+        .remove_users_from_group("on call", &["123", "456"])
+        .await
+        .unwrap();
+    let missing = client
+        .admin()
+        .remove_users_from_group("unknown", &["123"])
+        .await;
+
+    // #assert
+    assert!(report.is_complete());
+    assert!(report
+        .results
+        .iter()
+        .all(|change| change.status == MembershipChangeStatus::Removed));
+    assert!(matches!(
+        missing,
+        Err(KeyrunesError::GroupNotFoundError { .. })
+    ));
+    mock.assert_async().await;
+}
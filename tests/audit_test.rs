@@ -0,0 +1,258 @@
+use keyrunes_rust_sdk::audit::{AuthAuditSink, AuthDecision, Decision, HttpAuditSink};
+use keyrunes_rust_sdk::middleware::messages::RejectionKind;
+use keyrunes_rust_sdk::{AuthService, KeyrunesClient, User};
+use mockito::{Matcher, Server};
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone, Default)]
+struct CollectingSink(Arc<Mutex<Vec<AuthDecision>>>);
+
+impl CollectingSink {
+    fn decisions(&self) -> Vec<AuthDecision> {
+        self.0.lock().unwrap().clone()
+    }
+}
+
+impl AuthAuditSink for CollectingSink {
+    fn record(&self, decision: AuthDecision) {
+        self.0.lock().unwrap().push(decision);
+    }
+}
+
+fn user() -> User {
+    User {
+        id: "123".to_string(),
+        username: "john".to_string(),
+        email: "john@example.com".to_string(),
+        groups: vec![],
+        ..Default::default()
+    }
+}
+
+fn decision(requirement: &str) -> AuthDecision {
+    AuthDecision {
+        user_id: Some("123".to_string()),
+        method: "GET".to_string(),
+        route: "/reports".to_string(),
+        requirement: requirement.to_string(),
+        decision: Decision::Allow,
+        reason: None,
+        latency_ms: 3,
+        timestamp: chrono::Utc::now(),
+    }
+}
+
+#[test]
+fn test_timer_reports_allow_and_deny() {
+    // #setup
+    let sink = CollectingSink::default();
+    let auth = AuthService::new(KeyrunesClient::new("http://localhost").unwrap())
+        .with_audit_sink(sink.clone());
+
+    // #act
+    auth.audit().finish(
+        "GET",
+        "/reports",
+        "group:analysts",
+        Some(&user()),
+        &Ok::<(), _>(()),
+    );
+    auth.audit().finish::<()>(
+        "DELETE",
+        "/reports/{id}",
+        "group:admins",
+        Some(&user()),
+        &Err(RejectionKind::Forbidden("nope".to_string())),
+    );
+
+    // #assert
+    let decisions = sink.decisions();
+    assert_eq!(decisions.len(), 2);
+    assert_eq!(decisions[0].decision, Decision::Allow);
+    assert_eq!(decisions[0].user_id.as_deref(), Some("123"));
+    assert_eq!(decisions[0].reason, None);
+    assert_eq!(decisions[1].decision, Decision::Deny);
+    assert_eq!(decisions[1].route, "/reports/{id}");
+    assert_eq!(decisions[1].reason.as_deref(), Some("nope"));
+}
+
+#[test]
+fn test_timer_without_sink_is_a_no_op() {
+    // #setup
+    let auth = AuthService::new(KeyrunesClient::new("http://localhost").unwrap());
+
+    // #act & #assert
+    auth.audit()
+        .finish("GET", "/", "authenticated", None, &Ok::<(), _>(()));
+}
+
+#[tokio::test]
+async fn test_http_sink_posts_batches() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("POST", "/api/audit/events")
+        .match_body(Matcher::PartialJson(serde_json::json!({
+            "events": [
+                {"requirement": "authenticated", "decision": "allow", "route": "/reports"},
+                {"requirement": "rate_limit", "decision": "allow"}
+            ]
+        })))
+        .with_status(202)
+        .create_async()
+        .await;
+    let sink = HttpAuditSink::new(KeyrunesClient::new(server.url()).unwrap());
+
+    // #act
+    sink.record(decision("authenticated"));
+    sink.record(decision("rate_limit"));
+    sink.flush().await.unwrap();
+
+    // #assert
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_http_sink_sends_when_batch_is_full() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("POST", "/api/audit/events")
+        .with_status(202)
+        .create_async()
+        .await;
+    let sink = HttpAuditSink::new(KeyrunesClient::new(server.url()).unwrap()).with_max_pending(2);
+
+    // #act
+    sink.record(decision("authenticated"));
+    sink.record(decision("authenticated"));
+    tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+
+    // #assert
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_http_sink_keeps_failed_batches() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let failure = server
+        .mock("POST", "/api/audit/events")
+        .with_status(503)
+        .expect(1)
+        .create_async()
+        .await;
+    let sink = HttpAuditSink::new(KeyrunesClient::new(server.url()).unwrap());
+    sink.record(decision("authenticated"));
+
+    // #act
+    let failed = sink.flush().await;
+    failure.remove_async().await;
+    let retry = server
+        .mock("POST", "/api/audit/events")
+        .match_body(Matcher::PartialJson(serde_json::json!({
+            "events": [{"requirement": "authenticated"}, {"requirement": "rate_limit"}]
+        })))
+        .with_status(202)
+        .create_async()
+        .await;
+    sink.record(decision("rate_limit"));
+    let retried = sink.flush().await;
+
+    // #assert
+    assert!(failed.is_err());
+    assert!(retried.is_ok());
+    retry.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_shutdown_flushes_sink_and_stops_flusher() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("POST", "/api/audit/events")
+        .match_body(Matcher::PartialJson(serde_json::json!({
+            "events": [{"requirement": "authenticated"}]
+        })))
+        .with_status(202)
+        .expect(1)
+        .create_async()
+        .await;
+    let client = KeyrunesClient::new(server.url()).unwrap();
+    let sink = HttpAuditSink::new(client.clone());
+    let flusher = sink.spawn_flusher(std::time::Duration::from_secs(3600));
+    sink.record(decision("authenticated"));
+
+    // #act
+    client.shutdown().await.unwrap();
+
+    // #assert
+    mock.assert_async().await;
+    assert!(flusher.await.unwrap_err().is_cancelled());
+}
+
+#[cfg(feature = "axum")]
+mod axum_layer {
+    use super::CollectingSink;
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use axum::routing::get;
+    use axum::Router;
+    use keyrunes_rust_sdk::audit::Decision;
+    use keyrunes_rust_sdk::middleware::axum::{require_auth, KeyrunesState};
+    use keyrunes_rust_sdk::KeyrunesClient;
+    use mockito::Server;
+    use tower::ServiceExt;
+
+    #[tokio::test]
+    async fn test_require_auth_reports_decisions() {
+        // #setup
+        let mut server = Server::new_async().await;
+        server
+            .mock("GET", "/api/me")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"id":"123","username":"john","email":"john@example.com","groups":[]}"#)
+            .create_async()
+            .await;
+        let sink = CollectingSink::default();
+        let state = KeyrunesState::new(KeyrunesClient::new(server.url()).unwrap())
+            .with_audit_sink(sink.clone());
+        let app: Router = Router::new()
+            .route("/reports/:id", get(|| async { "ok" }))
+            .route("/health", get(|| async { "ok" }))
+            .layer(axum::middleware::from_fn_with_state(state, require_auth));
+
+        // #act
+        let anonymous = app
+            .clone()
+            .oneshot(Request::get("/reports/1").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let authenticated = app
+            .clone()
+            .oneshot(
+                Request::get("/reports/2")
+                    .header("authorization", "Bearer token")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        app.oneshot(Request::get("/health").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+
+        // #assert
+        assert_eq!(anonymous.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(authenticated.status(), StatusCode::OK);
+        let decisions = sink.decisions();
+        assert_eq!(decisions.len(), 2);
+        assert_eq!(decisions[0].decision, Decision::Deny);
+        assert_eq!(decisions[0].user_id, None);
+        assert_eq!(decisions[0].route, "/reports/:id");
+        assert_eq!(decisions[0].requirement, "authenticated");
+        assert_eq!(decisions[1].decision, Decision::Allow);
+        assert_eq!(decisions[1].user_id.as_deref(), Some("123"));
+    }
+}
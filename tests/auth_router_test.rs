@@ -0,0 +1,217 @@
+#![cfg(feature = "axum")]
+
+use axum::body::Body;
+use axum::http::{header, Request, StatusCode};
+use axum::Router;
+use keyrunes_rust_sdk::middleware::auth_router::{keyrunes_auth_router_with, AuthCookies};
+use keyrunes_rust_sdk::middleware::axum::KeyrunesState;
+use keyrunes_rust_sdk::KeyrunesClient;
+use mockito::{Matcher, Server};
+use tower::ServiceExt;
+
+fn app(server: &Server) -> Router {
+    keyrunes_auth_router_with(AuthCookies::default().with_secure(false)).with_state(
+        KeyrunesState::new(KeyrunesClient::new(server.url()).unwrap()),
+    )
+}
+
+fn set_cookies(response: &axum::response::Response) -> Vec<String> {
+    response
+        .headers()
+        .get_all(header::SET_COOKIE)
+        .iter()
+        .map(|value| value.to_str().unwrap().to_string())
+        .collect()
+}
+
+#[tokio::test]
+async fn test_login_sets_cookies_and_returns_user() {
+    // #setup
+    let mut server = Server::new_async().await;
+    server
+        .mock("POST", "/api/login")
+        .match_body(Matcher::PartialJson(serde_json::json!({
+            "identity": "john",
+            "password": "secret"
+        })))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"token":"abc","expires_in":3600,"refresh_token":"r1"}"#)
+        .create_async()
+        .await;
+    server
+        .mock("GET", "/api/me")
+        .match_header("authorization", "Bearer abc")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"id":"123","username":"john","email":"john@example.com","groups":[]}"#)
+        .create_async()
+        .await;
+
+    // #act
+    let response = app(&server)
+        .oneshot(
+            Request::post("/auth/login")
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(r#"{"username":"john","password":"secret"}"#))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    // #assert
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        set_cookies(&response),
+        vec![
+            "keyrunes_token=abc; Path=/; HttpOnly; SameSite=Lax; Max-Age=3600".to_string(),
+            "keyrunes_refresh=r1; Path=/; HttpOnly; SameSite=Lax".to_string(),
+        ]
+    );
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let user: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(user["username"], "john");
+}
+
+#[tokio::test]
+async fn test_login_rejects_invalid_credentials() {
+    // #setup
+    let mut server = Server::new_async().await;
+    server
+        .mock("POST", "/api/login")
+        .with_status(401)
+        .with_body(r#"{"message":"Invalid credentials"}"#)
+        .create_async()
+        .await;
+
+    // #act
+    let response = app(&server)
+        .oneshot(
+            Request::post("/auth/login")
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(r#"{"username":"john","password":"wrong"}"#))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    // #assert
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    assert!(set_cookies(&response).is_empty());
+}
+
+#[tokio::test]
+async fn test_me_reads_token_cookie() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("GET", "/api/me")
+        .match_header("authorization", "Bearer abc")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"id":"123","username":"john","email":"john@example.com","groups":[]}"#)
+        .create_async()
+        .await;
+
+    // #act
+    let response = app(&server)
+        .oneshot(
+            Request::get("/auth/me")
+                .header(header::COOKIE, "theme=dark; keyrunes_token=abc")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    // #assert
+    mock.assert_async().await;
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_me_without_token_is_unauthorized() {
+    // #setup
+    let server = Server::new_async().await;
+
+    // #act
+    let response = app(&server)
+        .oneshot(Request::get("/auth/me").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+
+    // #assert
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_refresh_replaces_cookies() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("POST", "/api/refresh")
+        .match_body(Matcher::Json(serde_json::json!({"refresh_token": "r1"})))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"token":"def","refresh_token":"r2"}"#)
+        .create_async()
+        .await;
+
+    // #act
+    let response = app(&server)
+        .oneshot(
+            Request::post("/auth/refresh")
+                .header(header::COOKIE, "keyrunes_refresh=r1")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    // #assert
+    mock.assert_async().await;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    assert_eq!(
+        set_cookies(&response),
+        vec![
+            "keyrunes_token=def; Path=/; HttpOnly; SameSite=Lax".to_string(),
+            "keyrunes_refresh=r2; Path=/; HttpOnly; SameSite=Lax".to_string(),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_logout_revokes_token_and_clears_cookies() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("POST", "/api/logout")
+        .match_header("authorization", "Bearer abc")
+        .with_status(204)
+        .create_async()
+        .await;
+
+    // #act
+    let response = app(&server)
+        .oneshot(
+            Request::post("/auth/logout")
+                .header(header::COOKIE, "keyrunes_token=abc")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    // #assert
+    mock.assert_async().await;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    assert_eq!(
+        set_cookies(&response),
+        vec![
+            "keyrunes_token=; Path=/; HttpOnly; SameSite=Lax; Max-Age=0".to_string(),
+            "keyrunes_refresh=; Path=/; HttpOnly; SameSite=Lax; Max-Age=0".to_string(),
+        ]
+    );
+}
@@ -0,0 +1,287 @@
+use keyrunes_rust_sdk::claims::{AuthLevel, ClaimsMapping};
+use keyrunes_rust_sdk::middleware::group_check::GroupCheckStrategy;
+use keyrunes_rust_sdk::middleware::messages::{Locale, RejectionKind};
+use keyrunes_rust_sdk::{AuthService, ClientSession, KeyrunesClient, User};
+use mockito::Server;
+
+fn user() -> User {
+    User {
+        id: "123".to_string(),
+        username: "john".to_string(),
+        email: "john@example.com".to_string(),
+        groups: vec!["editors".to_string()],
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_bearer_token() {
+    assert_eq!(AuthService::bearer_token(Some("Bearer abc")), Ok("abc"));
+    assert_eq!(
+        AuthService::bearer_token(None),
+        Err(RejectionKind::MissingToken)
+    );
+    assert_eq!(
+        AuthService::bearer_token(Some("Basic abc")),
+        Err(RejectionKind::InvalidToken)
+    );
+}
+
+#[tokio::test]
+async fn test_authenticate_header_resolves_user() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("GET", "/api/me")
+        .match_header("authorization", "Bearer abc")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"id":"123","username":"john","email":"john@example.com","groups":[]}"#)
+        .create_async()
+        .await;
+    let auth = AuthService::new(KeyrunesClient::new(server.url()).unwrap());
+
+    // #act
+    let user = auth.authenticate_header(Some("Bearer abc")).await.unwrap();
+
+    // #assert
+    mock.assert_async().await;
+    assert_eq!(user.username, "john");
+}
+
+#[tokio::test]
+async fn test_authenticate_rejects_invalid_token() {
+    // #setup
+    let mut server = Server::new_async().await;
+    server
+        .mock("GET", "/api/me")
+        .with_status(401)
+        .with_body("Token expired")
+        .create_async()
+        .await;
+    let auth = AuthService::new(KeyrunesClient::new(server.url()).unwrap());
+
+    // #act
+    let result = auth.authenticate("abc").await;
+
+    // #assert
+    assert_eq!(result.unwrap_err().status(), 401);
+}
+
+#[tokio::test]
+async fn test_authenticate_never_uses_the_clients_own_session() {
+    // #setup
+    let mut server = Server::new_async().await;
+    server
+        .mock("GET", "/api/me")
+        .match_header("authorization", "Bearer forged")
+        .with_status(401)
+        .with_body("Invalid token")
+        .create_async()
+        .await;
+    let service_mock = server
+        .mock("GET", "/api/me")
+        .match_header("authorization", "Bearer service-token")
+        .expect(0)
+        .create_async()
+        .await;
+    let refresh_mock = server
+        .mock("POST", "/api/refresh")
+        .expect(0)
+        .create_async()
+        .await;
+    let client = KeyrunesClient::new(server.url()).unwrap();
+    client
+        .restore_session(ClientSession {
+            version: ClientSession::VERSION,
+            token: Some("service-token".to_string()),
+            refresh_token: Some("service-refresh".to_string()),
+            namespace: None,
+            user: None,
+        })
+        .await
+        .unwrap();
+    let auth = AuthService::new(client);
+
+    // #act
+    let result = auth.authenticate_header(Some("Bearer forged")).await;
+
+    // #assert
+    assert_eq!(result.unwrap_err().status(), 401);
+    service_mock.assert_async().await;
+    refresh_mock.assert_async().await;
+    let session = auth.client().export_session().await;
+    assert_eq!(session.token.as_deref(), Some("service-token"));
+    assert_eq!(session.refresh_token.as_deref(), Some("service-refresh"));
+}
+
+#[tokio::test]
+async fn test_authenticate_rejects_unverified_email_when_required() {
+    // #setup
+    let mut server = Server::new_async().await;
+    server
+        .mock("GET", "/api/me")
+        .match_header("authorization", "Bearer unverified")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{"id":"1","username":"john","email":"john@example.com","email_verified":false}"#,
+        )
+        .create_async()
+        .await;
+    server
+        .mock("GET", "/api/me")
+        .match_header("authorization", "Bearer verified")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{"id":"2","username":"jane","email":"jane@example.com","email_verified":true}"#,
+        )
+        .create_async()
+        .await;
+    let auth = AuthService::new(KeyrunesClient::new(server.url()).unwrap())
+        .with_verified_email_required(true);
+
+    // #act
+    let unverified = auth.authenticate("unverified").await;
+    let verified = auth.authenticate("verified").await;
+
+    // #assert
+    assert_eq!(unverified.unwrap_err().status(), 403);
+    assert_eq!(verified.unwrap().email_verified, Some(true));
+}
+
+#[tokio::test]
+async fn test_require_group_follows_strategy() {
+    // #setup
+    let server = Server::new_async().await;
+    let auth = AuthService::new(KeyrunesClient::new(server.url()).unwrap())
+        .with_group_check(GroupCheckStrategy::TrustProfileGroups);
+
+    // #act
+    let editors = auth.require_group(&user(), "abc", "editors").await;
+    let admins = auth.require_group(&user(), "abc", "admins").await;
+
+    // #assert
+    assert_eq!(editors, Ok(()));
+    assert_eq!(
+        admins,
+        Err(RejectionKind::Forbidden(
+            "User does not belong to group: admins".to_string()
+        ))
+    );
+}
+
+#[tokio::test]
+async fn test_require_permission_uses_compiled_policy() {
+    // #setup
+    let mut server = Server::new_async().await;
+    server
+        .mock("GET", "/api/users/123/permissions")
+        .match_header("authorization", "Bearer abc")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"["posts:*"]"#)
+        .create_async()
+        .await;
+    let auth = AuthService::new(KeyrunesClient::new(server.url()).unwrap());
+
+    // #act
+    let write = auth.require_permission(&user(), "abc", "posts:write").await;
+    let billing = auth
+        .require_permission(&user(), "abc", "billing:read")
+        .await;
+
+    // #assert
+    assert_eq!(write, Ok(()));
+    assert_eq!(billing.unwrap_err().status(), 403);
+}
+
+#[test]
+fn test_require_auth_level_rejects_malformed_token() {
+    let auth = AuthService::new(KeyrunesClient::new("http://localhost").unwrap());
+    assert_eq!(
+        auth.require_auth_level("not-a-jwt", AuthLevel::Mfa),
+        Err(RejectionKind::InvalidToken)
+    );
+}
+
+#[test]
+fn test_require_auth_level_uses_client_claims_mapping() {
+    // #setup
+    let client = KeyrunesClient::builder("http://localhost")
+        .claims_mapping(ClaimsMapping::default().user_id_claim("uid"))
+        .build()
+        .unwrap();
+    let auth = AuthService::new(client);
+    let token = jsonwebtoken::encode(
+        &jsonwebtoken::Header::default(),
+        &serde_json::json!({"uid": "123", "acr": "mfa", "iat": chrono::Utc::now().timestamp()}),
+        &jsonwebtoken::EncodingKey::from_secret(b"secret"),
+    )
+    .unwrap();
+
+    // #act
+    let level = auth.require_auth_level(&token, AuthLevel::Mfa);
+    let fresh = auth.require_fresh_auth(&token, std::time::Duration::from_secs(300));
+
+    // #assert
+    assert_eq!(level, Ok(AuthLevel::Mfa));
+    assert_eq!(fresh, Ok(()));
+}
+
+#[tokio::test]
+async fn test_message_uses_formatter_and_locale() {
+    // #setup
+    fn localized(kind: RejectionKind, locale: &Locale) -> String {
+        format!("{}:{}", locale.language(), kind.status())
+    }
+    let server = Server::new_async().await;
+    let auth = AuthService::new(KeyrunesClient::new(server.url()).unwrap())
+        .with_message_formatter(localized);
+
+    // #act
+    let message = auth.message(RejectionKind::MissingToken, Some("pt-BR,en;q=0.5"));
+
+    // #assert
+    assert_eq!(message, "pt:401");
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_concurrent_users_are_resolved_independently() {
+    // #setup
+    let mut server = Server::new_async().await;
+    for (token, username) in [("token-john", "john"), ("token-jane", "jane")] {
+        server
+            .mock("GET", "/api/me")
+            .match_header("authorization", format!("Bearer {}", token).as_str())
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(format!(
+                r#"{{"id":"{0}","username":"{0}","email":"{0}@example.com","groups":[]}}"#,
+                username
+            ))
+            .create_async()
+            .await;
+    }
+    let auth = AuthService::new(KeyrunesClient::new(server.url()).unwrap());
+
+    // #act
+    let tasks = (0..20).map(|i| {
+        let auth = auth.clone();
+        let (token, username) = if i % 2 == 0 {
+            ("token-john", "john")
+        } else {
+            ("token-jane", "jane")
+        };
+        tokio::spawn(async move { (username, auth.authenticate(token).await) })
+    });
+    let results = futures_util::future::join_all(tasks).await;
+
+    // #assert
+    for result in results {
+        let (username, user) = result.unwrap();
+        assert_eq!(user.unwrap().username, username);
+    }
+    assert!(auth.client().current_token().await.is_none());
+}
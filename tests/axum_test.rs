@@ -0,0 +1,251 @@
+#![cfg(feature = "axum")]
+
+use axum::body::Body;
+use axum::extract::{FromRef, State};
+use axum::http::{Request, StatusCode};
+use axum::routing::get;
+use axum::Router;
+use keyrunes_rust_sdk::middleware::axum::{
+    rate_limit, require_auth, AuthenticatedUser, KeyrunesRateLimit, KeyrunesState, RequireAdmin,
+    RequireGroup,
+};
+use keyrunes_rust_sdk::middleware::bypass::{BypassRules, RoutePattern};
+use keyrunes_rust_sdk::rate_limit::{RateLimitQuota, RateLimiter};
+use keyrunes_rust_sdk::{keyrunes_groups, KeyrunesClient};
+use mockito::Server;
+use tower::ServiceExt;
+
+keyrunes_groups! {
+    /// Groups of the test application
+    pub enum AppGroup {
+        Editors => "editors",
+    }
+}
+
+#[derive(Clone)]
+struct AppState {
+    keyrunes: KeyrunesState,
+    greeting: &'static str,
+}
+
+impl FromRef<AppState> for KeyrunesState {
+    fn from_ref(state: &AppState) -> Self {
+        state.keyrunes.clone()
+    }
+}
+
+async fn hello(
+    AuthenticatedUser { user }: AuthenticatedUser,
+    State(state): State<AppState>,
+) -> String {
+    format!("{}, {}!", state.greeting, user.username)
+}
+
+async fn admin(RequireAdmin { user }: RequireAdmin) -> String {
+    user.username
+}
+
+async fn editors(group: RequireGroup<AppGroup>) -> String {
+    format!("{}: {}", group.group_id, group.user.username)
+}
+
+fn app(server: &Server) -> Router {
+    let state = AppState {
+        keyrunes: KeyrunesState::new(KeyrunesClient::new(server.url()).unwrap()),
+        greeting: "Hello",
+    };
+    Router::new()
+        .route("/hello", get(hello))
+        .route("/admin", get(admin))
+        .route("/group", get(editors))
+        .with_state(state)
+}
+
+async fn mock_me(server: &mut Server) -> mockito::Mock {
+    server
+        .mock("GET", "/api/me")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"id":"123","username":"john","email":"john@example.com","groups":[]}"#)
+        .create_async()
+        .await
+}
+
+#[tokio::test]
+async fn test_extractors_with_nested_state() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let mock = mock_me(&mut server).await;
+
+    // #act
+    let response = app(&server)
+        .oneshot(
+            Request::get("/hello")
+                .header("authorization", "Bearer token")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    // #assert
+    mock.assert_async().await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    assert_eq!(&body[..], b"Hello, john!");
+}
+
+#[tokio::test]
+async fn test_require_admin_with_nested_state() {
+    // #setup
+    let mut server = Server::new_async().await;
+    mock_me(&mut server).await;
+    server
+        .mock("GET", "/api/users/123/groups/admins")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"has_group":false}"#)
+        .create_async()
+        .await;
+
+    // #act
+    let response = app(&server)
+        .oneshot(
+            Request::get("/admin")
+                .header("authorization", "Bearer token")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    // #assert
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn test_require_group_with_typed_groups() {
+    // #setup
+    let mut server = Server::new_async().await;
+    mock_me(&mut server).await;
+    let check = server
+        .mock("GET", "/api/users/123/groups/editors")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"has_group":true}"#)
+        .expect(1)
+        .create_async()
+        .await;
+    let request = |query: &str| {
+        Request::get(format!("/group?group_id={}", query))
+            .header("authorization", "Bearer token")
+            .body(Body::empty())
+            .unwrap()
+    };
+
+    // #act
+    let known = app(&server).oneshot(request("editors")).await.unwrap();
+    let unknown = app(&server).oneshot(request("sales")).await.unwrap();
+
+    // #assert
+    check.assert_async().await;
+    assert_eq!(known.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(known.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    assert_eq!(&body[..], b"editors: john");
+    assert_eq!(unknown.status(), StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn test_missing_token_is_rejected() {
+    // #setup
+    let server = Server::new_async().await;
+
+    // #act
+    let response = app(&server)
+        .oneshot(Request::get("/hello").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+
+    // #assert
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_rate_limit_lets_preflights_through() {
+    // #setup
+    let server = Server::new_async().await;
+    let state = KeyrunesState::new(KeyrunesClient::new(server.url()).unwrap());
+    let limit = KeyrunesRateLimit::new(
+        state,
+        RateLimiter::in_memory(RateLimitQuota::per_minute(60)),
+    );
+    let app: Router = Router::new()
+        .route("/reports", get(|| async { "ok" }).options(|| async { "" }))
+        .route_layer(axum::middleware::from_fn_with_state(limit, rate_limit));
+
+    // #act
+    let preflight = app
+        .clone()
+        .oneshot(Request::options("/reports").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    let anonymous = app
+        .oneshot(Request::get("/reports").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+
+    // #assert
+    assert_eq!(preflight.status(), StatusCode::OK);
+    assert_eq!(anonymous.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_require_auth_allows_public_routes() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let me = mock_me(&mut server).await;
+    let state = KeyrunesState::new(KeyrunesClient::new(server.url()).unwrap())
+        .with_bypass_rules(BypassRules::default().with_route(RoutePattern::prefix("/public/")));
+    let app: Router = Router::new()
+        .route("/public/about", get(|| async { "about" }))
+        .route(
+            "/profile",
+            get(|AuthenticatedUser { user }: AuthenticatedUser| async move { user.username }),
+        )
+        .layer(axum::middleware::from_fn_with_state(
+            state.clone(),
+            require_auth,
+        ))
+        .with_state(state);
+
+    // #act
+    let public = app
+        .clone()
+        .oneshot(Request::get("/public/about").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    let anonymous = app
+        .clone()
+        .oneshot(Request::get("/profile").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    let authenticated = app
+        .oneshot(
+            Request::get("/profile")
+                .header("authorization", "Bearer token")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    // #assert
+    assert_eq!(public.status(), StatusCode::OK);
+    assert_eq!(anonymous.status(), StatusCode::UNAUTHORIZED);
+    assert_eq!(authenticated.status(), StatusCode::OK);
+    me.expect(1).assert_async().await;
+}
@@ -0,0 +1,77 @@
+use keyrunes_rust_sdk::break_glass::{BreakGlass, EmergencyCredentials};
+use keyrunes_rust_sdk::{KeyrunesClient, KeyrunesError};
+use mockito::Server;
+
+fn credentials() -> EmergencyCredentials {
+    EmergencyCredentials {
+        identity: "breakglass@example.com".to_string(),
+        password: "sealed-password".to_string(),
+        namespace: None,
+    }
+}
+
+#[tokio::test]
+async fn test_break_glass_annotates_every_request() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let login = server
+        .mock("POST", "/api/login")
+        .match_header("x-keyrunes-break-glass", "INC-4821")
+        .match_header("x-keyrunes-audit-reason", "SSO outage")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"token":"emergency-token"}"#)
+        .expect(1)
+        .create_async()
+        .await;
+    let me = server
+        .mock("GET", "/api/me")
+        .match_header("authorization", "Bearer emergency-token")
+        .match_header("x-keyrunes-break-glass", "INC-4821")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"user_id":1,"username":"breakglass","email":"breakglass@example.com"}"#)
+        .expect(1)
+        .create_async()
+        .await;
+    let logout = server
+        .mock("POST", "/api/logout")
+        .match_header("x-keyrunes-break-glass", "INC-4821")
+        .with_status(204)
+        .expect(1)
+        .create_async()
+        .await;
+    let client = KeyrunesClient::new(server.url()).unwrap();
+
+    // #act
+    let session = BreakGlass::open(&client, &credentials(), "INC-4821", "SSO outage")
+        .await
+        .unwrap();
+    let user = session.client().get_current_user().await.unwrap();
+    let incident = session.incident().to_string();
+    let revoked = session.close().await.unwrap();
+
+    // #assert
+    assert_eq!(user.username, "breakglass");
+    assert_eq!(incident, "INC-4821");
+    assert!(revoked);
+    assert!(client.current_token().await.is_none());
+    login.assert_async().await;
+    me.assert_async().await;
+    logout.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_break_glass_requires_a_reason() {
+    // #setup
+    let client = KeyrunesClient::new("http://localhost").unwrap();
+
+    // #act
+    let result = BreakGlass::open(&client, &credentials(), "INC-4821", " ").await;
+
+    // #assert
+    assert!(matches!(
+        result,
+        Err(KeyrunesError::InvalidAuditAnnotation(message)) if message.contains("reason")
+    ));
+}
@@ -0,0 +1,61 @@
+use keyrunes_rust_sdk::middleware::bypass::{BypassRules, RoutePattern};
+
+#[test]
+fn test_default_rules_skip_preflights_and_health_checks() {
+    // #setup
+    let rules = BypassRules::default();
+
+    // #act & #assert
+    assert!(rules.matches("OPTIONS", "/api/users"));
+    assert!(rules.matches("options", "/api/users"));
+    assert!(rules.matches("GET", "/health"));
+    assert!(rules.matches("GET", "/readyz"));
+    assert!(!rules.matches("GET", "/api/users"));
+    assert!(!rules.matches("GET", "/health/details"));
+}
+
+#[test]
+fn test_custom_rules() {
+    // #setup
+    let rules = BypassRules::none().with_method("head").with_path("/status");
+
+    // #act & #assert
+    assert!(rules.matches("HEAD", "/api/users"));
+    assert!(rules.matches("GET", "/status"));
+    assert!(!rules.matches("OPTIONS", "/api/users"));
+    assert!(!rules.matches("GET", "/health"));
+}
+
+#[test]
+fn test_route_patterns() {
+    // #setup
+    let exact = RoutePattern::exact("/login");
+    let prefix = RoutePattern::prefix("/static/");
+    let glob = RoutePattern::glob("/docs/*/*.html");
+    let deep = RoutePattern::glob("/public/**/readme.md");
+
+    // #act & #assert
+    assert!(exact.matches("/login"));
+    assert!(!exact.matches("/login/reset"));
+    assert!(prefix.matches("/static/css/app.css"));
+    assert!(!prefix.matches("/staticfiles"));
+    assert!(glob.matches("/docs/v2/index.html"));
+    assert!(!glob.matches("/docs/v2/api/index.html"));
+    assert!(!glob.matches("/docs/v2/index.json"));
+    assert!(deep.matches("/public/readme.md"));
+    assert!(deep.matches("/public/a/b/readme.md"));
+    assert!(!deep.matches("/private/readme.md"));
+}
+
+#[test]
+fn test_public_routes_are_bypassed() {
+    // #setup
+    let rules = BypassRules::none()
+        .with_route(RoutePattern::exact("/login"))
+        .with_route(RoutePattern::prefix("/assets/"));
+
+    // #act & #assert
+    assert!(rules.matches("POST", "/login"));
+    assert!(rules.matches("GET", "/assets/logo.svg"));
+    assert!(!rules.matches("GET", "/api/profile"));
+}
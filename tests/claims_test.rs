@@ -0,0 +1,191 @@
+use keyrunes_rust_sdk::claims::{AuthLevel, Claims, ClaimsMapping};
+use keyrunes_rust_sdk::KeyrunesClient;
+use serde_json::json;
+use std::time::Duration;
+
+fn make_token(claims: serde_json::Value) -> String {
+    jsonwebtoken::encode(
+        &jsonwebtoken::Header::default(),
+        &claims,
+        &jsonwebtoken::EncodingKey::from_secret(b"secret"),
+    )
+    .unwrap()
+}
+
+#[test]
+fn test_claims_from_token() {
+    // #setup
+    let token = make_token(json!({"sub": "123", "iat": 1700000000, "exp": 1700003600}));
+
+    // #act
+    let claims = Claims::from_token(&token).unwrap();
+
+    // #assert
+    assert_eq!(claims.sub.as_deref(), Some("123"));
+    assert_eq!(claims.iat, Some(1700000000));
+    assert_eq!(claims.exp, Some(1700003600));
+}
+
+#[test]
+fn test_claims_accept_numeric_sub_and_single_amr() {
+    // #setup
+    let token = make_token(json!({"sub": 42, "amr": "otp"}));
+
+    // #act
+    let claims = Claims::from_token(&token).unwrap();
+
+    // #assert
+    assert_eq!(claims.sub.as_deref(), Some("42"));
+    assert_eq!(claims.user_id.as_deref(), Some("42"));
+    assert_eq!(claims.amr, ["otp"]);
+    assert_eq!(claims.auth_level(), AuthLevel::Mfa);
+}
+
+#[test]
+fn test_claims_from_invalid_token() {
+    // #act
+    let result = Claims::from_token("not-a-jwt");
+
+    // #assert
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_auth_level_password() {
+    // #setup
+    let token = make_token(json!({"sub": "123", "amr": ["pwd"]}));
+
+    // #act
+    let level = Claims::from_token(&token).unwrap().auth_level();
+
+    // #assert
+    assert_eq!(level, AuthLevel::Password);
+}
+
+#[test]
+fn test_auth_level_mfa_from_amr() {
+    // #setup
+    let token = make_token(json!({"sub": "123", "amr": ["pwd", "otp"]}));
+
+    // #act
+    let level = Claims::from_token(&token).unwrap().auth_level();
+
+    // #assert
+    assert_eq!(level, AuthLevel::Mfa);
+}
+
+#[test]
+fn test_auth_level_mfa_from_acr() {
+    // #setup
+    let token = make_token(json!({"sub": "123", "acr": "urn:keyrunes:acr:mfa"}));
+
+    // #act
+    let level = Claims::from_token(&token).unwrap().auth_level();
+
+    // #assert
+    assert_eq!(level, AuthLevel::Mfa);
+    assert!(AuthLevel::Password < AuthLevel::Mfa);
+}
+
+#[test]
+fn test_is_fresh_recent_auth_time() {
+    // #setup
+    let now = chrono::Utc::now().timestamp();
+    let token = make_token(json!({"sub": "123", "iat": now - 3600, "auth_time": now - 60}));
+
+    // #act
+    let claims = Claims::from_token(&token).unwrap();
+
+    // #assert
+    assert_eq!(claims.authenticated_at(), Some(now - 60));
+    assert!(claims.is_fresh(Duration::from_secs(300)));
+}
+
+#[test]
+fn test_is_fresh_stale_iat() {
+    // #setup
+    let now = chrono::Utc::now().timestamp();
+    let token = make_token(json!({"sub": "123", "iat": now - 3600}));
+
+    // #act
+    let claims = Claims::from_token(&token).unwrap();
+
+    // #assert
+    assert!(!claims.is_fresh(Duration::from_secs(300)));
+}
+
+#[test]
+fn test_is_fresh_without_timestamps() {
+    // #setup
+    let token = make_token(json!({"sub": "123"}));
+
+    // #act
+    let claims = Claims::from_token(&token).unwrap();
+
+    // #assert
+    assert!(!claims.is_fresh(Duration::from_secs(300)));
+}
+
+#[test]
+fn test_claims_default_mapping() {
+    // #setup
+    let token = make_token(json!({
+        "sub": "123",
+        "email": "john@example.com",
+        "groups": ["admins", "editors"],
+        "roles": "owner"
+    }));
+
+    // #act
+    let claims = Claims::from_token(&token).unwrap();
+
+    // #assert
+    assert_eq!(claims.user_id.as_deref(), Some("123"));
+    assert_eq!(claims.email.as_deref(), Some("john@example.com"));
+    assert_eq!(claims.groups, vec!["admins", "editors"]);
+    assert_eq!(claims.roles, vec!["owner"]);
+}
+
+#[test]
+fn test_claims_custom_mapping() {
+    // #setup
+    let token = make_token(json!({
+        "sub": "auth0|123",
+        "uid": 42,
+        "https://example.com/groups": ["admins"],
+        "realm_access": {"roles": ["billing"]},
+        "mail": "john@example.com"
+    }));
+    let mapping = ClaimsMapping::default()
+        .user_id_claim("uid")
+        .email_claim("mail")
+        .groups_claim("https://example.com/groups")
+        .roles_claim("realm_access.roles");
+
+    // #act
+    let claims = Claims::from_token_with(&token, &mapping).unwrap();
+
+    // #assert
+    assert_eq!(claims.sub.as_deref(), Some("auth0|123"));
+    assert_eq!(claims.user_id.as_deref(), Some("42"));
+    assert_eq!(claims.email.as_deref(), Some("john@example.com"));
+    assert_eq!(claims.groups, vec!["admins"]);
+    assert_eq!(claims.roles, vec!["billing"]);
+}
+
+#[test]
+fn test_client_decode_claims_uses_mapping() {
+    // #setup
+    let token = make_token(json!({"sub": "123", "team": ["ops"]}));
+    let client = KeyrunesClient::builder("https://example.com")
+        .claims_mapping(ClaimsMapping::default().groups_claim("team"))
+        .build()
+        .unwrap();
+
+    // #act
+    let claims = client.decode_claims(&token).unwrap();
+
+    // #assert
+    assert_eq!(client.claims_mapping().groups_claim, "team");
+    assert_eq!(claims.groups, vec!["ops"]);
+}
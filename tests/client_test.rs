@@ -1,5 +1,7 @@
+use keyrunes_rust_sdk::client::IpPreference;
 use keyrunes_rust_sdk::{KeyrunesClient, KeyrunesError};
-use mockito::Server;
+use mockito::{Matcher, Server};
+use std::time::Duration;
 
 #[tokio::test]
 async fn test_client_new() {
@@ -13,6 +15,416 @@ async fn test_client_new_invalid_url() {
     assert!(client.is_err());
 }
 
+#[tokio::test]
+async fn test_client_sends_sdk_headers() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("POST", "/api/login")
+        .match_header(
+            "user-agent",
+            format!("keyrunes-rust-sdk/{}", env!("CARGO_PKG_VERSION")).as_str(),
+        )
+        .match_header(
+            "x-keyrunes-sdk",
+            Matcher::Regex(format!(
+                "^name=keyrunes-rust-sdk; version={}; os=",
+                env!("CARGO_PKG_VERSION")
+            )),
+        )
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"token":"test-token-123"}"#)
+        .create_async()
+        .await;
+
+    // #act
+    let client = KeyrunesClient::new(server.url()).unwrap();
+    let result = client.login("user@example.com", "password", None).await;
+
+    // #assert
+    assert!(result.is_ok());
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_builder_app_info() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("POST", "/api/login")
+        .match_header(
+            "user-agent",
+            Matcher::Regex("^billing-service/2.3.1 keyrunes-rust-sdk/".to_string()),
+        )
+        .match_header(
+            "x-keyrunes-sdk",
+            Matcher::Regex("; app=billing-service/2.3.1$".to_string()),
+        )
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"token":"test-token-123"}"#)
+        .create_async()
+        .await;
+
+    // #act
+    let client = KeyrunesClient::builder(server.url())
+        .app_info("billing-service", "2.3.1")
+        .build()
+        .unwrap();
+    let result = client.login("user@example.com", "password", None).await;
+
+    // #assert
+    assert!(result.is_ok());
+    mock.assert_async().await;
+}
+
+#[test]
+fn test_builder_rejects_invalid_app_info() {
+    // #act
+    let result = KeyrunesClient::builder("https://example.com")
+        .app_info("bad\napp", "1.0")
+        .build();
+
+    // #assert
+    assert!(matches!(result, Err(KeyrunesError::Other(_))));
+}
+
+#[tokio::test]
+async fn test_locale_sends_accept_language_and_exposes_code() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("POST", "/api/register")
+        .match_header("accept-language", "pt-BR")
+        .with_status(409)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"code":"username_taken","message":"Nome de usuário em uso"}"#)
+        .create_async()
+        .await;
+
+    // #act
+    let client = KeyrunesClient::builder(server.url())
+        .locale("pt-BR")
+        .build()
+        .unwrap();
+    let result = client
+        .register("john", "john@example.com", "password123", None)
+        .await;
+
+    // #assert
+    mock.assert_async().await;
+    let err = result.unwrap_err();
+    assert_eq!(err.code(), Some("username_taken"));
+    match err {
+        KeyrunesError::Api {
+            status, message, ..
+        } => {
+            assert_eq!(status, 409);
+            assert_eq!(message, "Nome de usuário em uso");
+        }
+        other => panic!("Expected Api error, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_coded_errors_keep_status_variants() {
+    // #setup
+    let mut server = Server::new_async().await;
+    server
+        .mock("POST", "/api/login")
+        .with_status(401)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"code":"invalid_credentials","message":"Credenciais inválidas"}"#)
+        .create_async()
+        .await;
+    server
+        .mock("POST", "/api/logout")
+        .with_status(401)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"code":"token_revoked","message":"Token revoked"}"#)
+        .create_async()
+        .await;
+    let client = KeyrunesClient::new(server.url()).unwrap();
+
+    // #act
+    let login = client.login("user@example.com", "wrong", None).await;
+    client.set_token("abc").await;
+    let acknowledged = client.logout().await.unwrap();
+
+    // #assert
+    match login {
+        Err(KeyrunesError::AuthenticationError { message, code }) => {
+            assert!(message.contains("Credenciais inválidas"));
+            assert_eq!(code.as_deref(), Some("invalid_credentials"));
+        }
+        other => panic!("Expected AuthenticationError, got {:?}", other),
+    }
+    assert!(!acknowledged);
+}
+
+#[tokio::test]
+async fn test_coded_not_found_is_classified_by_code() {
+    // #setup
+    let mut server = Server::new_async().await;
+    server
+        .mock("GET", "/api/users/999")
+        .with_status(404)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"code":"user_not_found","message":"Usuário não encontrado"}"#)
+        .create_async()
+        .await;
+    server
+        .mock("DELETE", "/api/admin/groups/ops")
+        .with_status(404)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"code":"group_not_found","message":"Grupo não encontrado"}"#)
+        .create_async()
+        .await;
+    let client = KeyrunesClient::builder(server.url())
+        .locale("pt-BR")
+        .build()
+        .unwrap();
+    client.set_token("test-token-789").await;
+
+    // #act
+    let user = client.get_user("999").await.unwrap_err();
+    let group = client.admin().delete_group("ops").await.unwrap_err();
+
+    // #assert
+    assert!(matches!(user, KeyrunesError::UserNotFoundError { .. }));
+    assert_eq!(user.code(), Some("user_not_found"));
+    assert!(matches!(group, KeyrunesError::GroupNotFoundError { .. }));
+    assert_eq!(group.code(), Some("group_not_found"));
+}
+
+#[tokio::test]
+async fn test_error_truncates_long_multibyte_body() {
+    // #setup
+    let mut server = Server::new_async().await;
+    server
+        .mock("POST", "/api/login")
+        .with_status(500)
+        .with_body("é".repeat(300))
+        .create_async()
+        .await;
+    let client = KeyrunesClient::new(server.url()).unwrap();
+
+    // #act
+    let result = client.login("user@example.com", "password123", None).await;
+
+    // #assert
+    match result {
+        Err(KeyrunesError::HttpError(msg)) => {
+            assert!(msg.contains(&format!("{}...", "é".repeat(200))));
+            assert!(!msg.contains(&"é".repeat(201)));
+        }
+        other => panic!("Expected HttpError, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_api_version_accepts_compatible_versions() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let login_mock = server
+        .mock("POST", "/api/login")
+        .match_header("x-keyrunes-api-version", "2.1")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_header("x-keyrunes-api-version", "2.4")
+        .with_body(r#"{"token":"test-token-123"}"#)
+        .create_async()
+        .await;
+    let _me = server
+        .mock("GET", "/api/me")
+        .with_status(503)
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::builder(server.url())
+        .api_version("2.1")
+        .build()
+        .unwrap();
+
+    // #act
+    let login = client.login("user@example.com", "password", None).await;
+    let me = client.get_current_user().await;
+
+    // #assert
+    assert!(login.is_ok());
+    assert!(matches!(me, Err(KeyrunesError::HttpError(_))));
+    login_mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_api_version_rejects_incompatible_versions() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let _login = server
+        .mock("POST", "/api/login")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_header("x-keyrunes-api-version", "3.0")
+        .with_body(r#"{"token":"test-token-123"}"#)
+        .create_async()
+        .await;
+    let _user = server
+        .mock("GET", "/api/users/123")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"id":"123","username":"john","email":"john@example.com"}"#)
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::builder(server.url())
+        .api_version("2")
+        .build()
+        .unwrap();
+    client.set_token("test-token-123").await;
+
+    // #act
+    let login = client.login("user@example.com", "password", None).await;
+    let user = client.get_user("123").await;
+
+    // #assert
+    match login {
+        Err(KeyrunesError::IncompatibleApiVersion { requested, served }) => {
+            assert_eq!(requested, "2");
+            assert_eq!(served.as_deref(), Some("3.0"));
+        }
+        other => panic!("Expected IncompatibleApiVersion, got {:?}", other),
+    }
+    assert!(matches!(
+        user,
+        Err(KeyrunesError::IncompatibleApiVersion { served: None, .. })
+    ));
+}
+
+#[tokio::test]
+async fn test_api_version_rejects_older_minor_versions() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let _login = server
+        .mock("POST", "/api/login")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_header("x-keyrunes-api-version", "2.1")
+        .with_body(r#"{"token":"test-token-123"}"#)
+        .create_async()
+        .await;
+    let _me = server
+        .mock("GET", "/api/me")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_header("x-keyrunes-api-version", "2")
+        .with_body(r#"{"id":"123","username":"john","email":"john@example.com"}"#)
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::builder(server.url())
+        .api_version("2.3")
+        .build()
+        .unwrap();
+    client.set_token("test-token-123").await;
+
+    // #act
+    let login = client.login("user@example.com", "password", None).await;
+    let me = client.get_current_user().await;
+
+    // #assert
+    assert!(matches!(
+        login,
+        Err(KeyrunesError::IncompatibleApiVersion { served: Some(ref served), .. }) if served == "2.1"
+    ));
+    assert!(matches!(
+        me,
+        Err(KeyrunesError::IncompatibleApiVersion { served: Some(ref served), .. }) if served == "2"
+    ));
+}
+
+#[tokio::test]
+async fn test_strict_mode_accepts_exact_responses() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("GET", "/api/users/123")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"user_id":123,"username":"john","email":"john@example.com","groups":[]}"#)
+        .create_async()
+        .await;
+    let client = KeyrunesClient::builder(server.url())
+        .strict(true)
+        .build()
+        .unwrap();
+    client.set_token("test-token-789").await;
+
+    // #act
+    let user = client.get_user("123").await.unwrap();
+
+    // #assert
+    assert_eq!(user.numeric_id, Some(123));
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_strict_mode_rejects_drift() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let _unknown_field = server
+        .mock("GET", "/api/users/1")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"user_id":1,"username":"john","email":"john@example.com","nickname":"jo"}"#)
+        .create_async()
+        .await;
+    let _string_id = server
+        .mock("GET", "/api/users/2")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"user_id":"2","username":"john","email":"john@example.com"}"#)
+        .create_async()
+        .await;
+    let _legacy_token = server
+        .mock("POST", "/api/login")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"access_token":"test-token-123"}"#)
+        .create_async()
+        .await;
+    let strict = KeyrunesClient::builder(server.url())
+        .strict(true)
+        .build()
+        .unwrap();
+    strict.set_token("test-token-789").await;
+    let lenient = KeyrunesClient::new(server.url()).unwrap();
+    lenient.set_token("test-token-789").await;
+
+    // #act
+    let unknown_field = strict.get_user("1").await;
+    let string_id = strict.get_user("2").await;
+    let legacy_token = strict.login("user@example.com", "password", None).await;
+
+    // #assert
+    match unknown_field {
+        Err(KeyrunesError::SerializationError(message)) => {
+            assert!(message.contains("unknown field `nickname`"), "{}", message)
+        }
+        other => panic!("Expected SerializationError, got {:?}", other),
+    }
+    assert!(matches!(
+        string_id,
+        Err(KeyrunesError::SerializationError(_))
+    ));
+    assert!(matches!(
+        legacy_token,
+        Err(KeyrunesError::SerializationError(_))
+    ));
+    assert!(lenient.get_user("1").await.is_ok());
+    assert!(lenient.get_user("2").await.is_ok());
+}
+
 #[tokio::test]
 async fn test_login_success() {
     // #setup
@@ -56,7 +468,7 @@ async fn test_login_failure() {
     // #assert
     assert!(result.is_err());
     match result.unwrap_err() {
-        KeyrunesError::AuthenticationError(_) => {}
+        KeyrunesError::AuthenticationError { .. } => {}
         _ => panic!("Expected AuthenticationError"),
     }
 
@@ -76,393 +488,1285 @@ async fn test_register_success() {
         .await;
 
     // #act
-    let client = KeyrunesClient::new(server.url()).unwrap();
-    let result = client
-        .register("john", "john@example.com", "password123", None)
-        .await;
+    let client = KeyrunesClient::new(server.url()).unwrap();
+    let result = client
+        .register("john", "john@example.com", "password123", None)
+        .await;
+
+    // #assert
+    assert!(result.is_ok());
+    let user = result.unwrap();
+    assert_eq!(user.id, "123");
+    assert_eq!(user.username, "john");
+    assert_eq!(user.email, "john@example.com");
+
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_register_failure() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("POST", "/api/register")
+        .with_status(400)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"message":"Email already exists"}"#)
+        .create_async()
+        .await;
+
+    // #act
+    let client = KeyrunesClient::new(server.url()).unwrap();
+    let result = client
+        .register("john", "john@example.com", "password123", None)
+        .await;
+
+    // #assert
+    assert!(result.is_err());
+
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_register_and_login_flow() {
+    // #setup
+    let mut server = Server::new_async().await;
+
+    let register_mock = server
+        .mock("POST", "/api/register")
+        .with_status(201)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"user":{"user_id":123,"username":"john","email":"john@example.com","groups":[]},"token":"test-token","requires_password_change":false}"#)
+        .create_async()
+        .await;
+
+    let login_mock = server
+        .mock("POST", "/api/login")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"token":"test-token-456"}"#)
+        .create_async()
+        .await;
+
+    // #act
+    let client = KeyrunesClient::new(server.url()).unwrap();
+    let user = client
+        .register("john", "john@example.com", "password123", None)
+        .await
+        .unwrap();
+    let token = client
+        .login("john@example.com", "password123", None)
+        .await
+        .unwrap();
+
+    // #assert
+    assert_eq!(user.username, "john");
+    assert_eq!(token.token, "test-token-456");
+
+    register_mock.assert_async().await;
+    login_mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_set_token() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("GET", "/api/me")
+        .match_header("authorization", "Bearer test-token-123")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"user_id":999,"username":"test","email":"test@example.com","groups":[]}"#)
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::new(server.url()).unwrap();
+
+    // #act
+    client.set_token("test-token-123").await;
+    let user = client.get_current_user().await;
+
+    // #assert
+    assert!(user.is_ok());
+    assert_eq!(user.unwrap().username, "test");
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_current_token_introspection() {
+    // #setup
+    let client = KeyrunesClient::new("http://localhost").unwrap();
+    let exp = chrono::Utc::now().timestamp() + 600;
+    let token = jsonwebtoken::encode(
+        &jsonwebtoken::Header::default(),
+        &serde_json::json!({"sub": "123", "exp": exp}),
+        &jsonwebtoken::EncodingKey::from_secret(b"secret"),
+    )
+    .unwrap();
+
+    // #act
+    let before = client.current_token().await;
+    client.set_token(token.clone()).await;
+    let info = client.current_token().await.unwrap();
+    let expires_in = client.token_expires_in().await.unwrap();
+
+    // #assert
+    assert!(before.is_none());
+    assert_eq!(info.token, token);
+    assert_eq!(info.subject.as_deref(), Some("123"));
+    assert_eq!(info.expires_at.unwrap().timestamp(), exp);
+    assert!(!info.is_expired());
+    assert!(expires_in.as_secs() > 590 && expires_in.as_secs() <= 600);
+}
+
+#[tokio::test]
+async fn test_current_token_opaque() {
+    // #setup
+    let client = KeyrunesClient::new("http://localhost").unwrap();
+    client.set_token("opaque-token").await;
+
+    // #act
+    let info = client.current_token().await.unwrap();
+
+    // #assert
+    assert_eq!(info.token, "opaque-token");
+    assert_eq!(info.expires_at, None);
+    assert_eq!(info.subject, None);
+    assert_eq!(client.token_expires_in().await, None);
+}
+
+#[tokio::test]
+async fn test_token_updates_notify_changes() {
+    // #setup
+    let mut server = Server::new_async().await;
+    server
+        .mock("POST", "/api/login")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"token":"login-token"}"#)
+        .create_async()
+        .await;
+    let client = KeyrunesClient::new(server.url()).unwrap();
+    let mut updates = client.token_updates();
+
+    // #act & #assert
+    client.login("john", "secret", None).await.unwrap();
+    assert!(updates.has_changed().unwrap());
+    assert_eq!(
+        updates
+            .borrow_and_update()
+            .as_ref()
+            .map(|info| info.token.as_str()),
+        Some("login-token")
+    );
+
+    client.set_token("login-token").await;
+    assert!(!updates.has_changed().unwrap());
+
+    client.set_token("other-token").await;
+    assert_eq!(
+        updates
+            .borrow_and_update()
+            .as_ref()
+            .map(|info| info.token.as_str()),
+        Some("other-token")
+    );
+
+    client.clear_token().await;
+    assert!(updates.has_changed().unwrap());
+    assert!(updates.borrow_and_update().is_none());
+}
+
+#[tokio::test]
+async fn test_export_and_restore_session() {
+    // #setup
+    let mut server = Server::new_async().await;
+    server
+        .mock("POST", "/api/login")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"token":"abc","refresh_token":"r1"}"#)
+        .create_async()
+        .await;
+    server
+        .mock("GET", "/api/me")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{"id":"123","username":"john","email":"john@example.com","groups":["admins"]}"#,
+        )
+        .create_async()
+        .await;
+    let client = KeyrunesClient::new(server.url()).unwrap();
+    client.login("john", "secret", Some("acme")).await.unwrap();
+    client.get_current_user().await.unwrap();
+
+    // #act
+    let saved = serde_json::to_string(&client.export_session().await).unwrap();
+    let resumed = KeyrunesClient::new(server.url()).unwrap();
+    resumed
+        .restore_session(serde_json::from_str(&saved).unwrap())
+        .await
+        .unwrap();
+    let session = resumed.export_session().await;
+
+    // #assert
+    assert_eq!(session.version, 1);
+    assert_eq!(session.token.as_deref(), Some("abc"));
+    assert_eq!(session.refresh_token.as_deref(), Some("r1"));
+    assert_eq!(session.namespace.as_deref(), Some("acme"));
+    let user = session.user.unwrap();
+    assert_eq!(user.username, "john");
+    assert_eq!(user.groups, vec!["admins".to_string()]);
+    assert_eq!(resumed.current_token().await.unwrap().token, "abc");
+}
+
+#[tokio::test]
+async fn test_restore_session_rejects_newer_versions() {
+    // #setup
+    let client = KeyrunesClient::new("http://localhost").unwrap();
+    let session = serde_json::from_str(r#"{"version":2,"token":"abc"}"#).unwrap();
+
+    // #act
+    let result = client.restore_session(session).await;
+
+    // #assert
+    assert!(matches!(result, Err(KeyrunesError::Other(_))));
+    assert!(client.current_token().await.is_none());
+}
+
+#[tokio::test]
+async fn test_clear_token_clears_session() {
+    // #setup
+    let client = KeyrunesClient::new("http://localhost").unwrap();
+    let session =
+        serde_json::from_str(r#"{"version":1,"token":"abc","refresh_token":"r1"}"#).unwrap();
+    client.restore_session(session).await.unwrap();
+
+    // #act
+    client.clear_token().await;
+    let session = client.export_session().await;
+
+    // #assert
+    assert_eq!(session.token, None);
+    assert_eq!(session.refresh_token, None);
+}
+
+#[tokio::test]
+async fn test_logout_revokes_and_clears_token() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("POST", "/api/logout")
+        .match_header("authorization", "Bearer abc")
+        .with_status(204)
+        .expect(1)
+        .create_async()
+        .await;
+    let client = KeyrunesClient::new(server.url()).unwrap();
+    client.set_token("abc").await;
+
+    // #act
+    let acknowledged = client.logout().await.unwrap();
+
+    // #assert
+    assert!(acknowledged);
+    assert!(client.current_token().await.is_none());
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_logout_with_revoked_token() {
+    // #setup
+    let mut server = Server::new_async().await;
+    server
+        .mock("POST", "/api/logout")
+        .with_status(401)
+        .with_body(r#"{"message":"Token revoked"}"#)
+        .create_async()
+        .await;
+    let client = KeyrunesClient::new(server.url()).unwrap();
+    client.set_token("abc").await;
+
+    // #act
+    let acknowledged = client.logout().await.unwrap();
+    let without_token = client.logout().await.unwrap();
+
+    // #assert
+    assert!(!acknowledged);
+    assert!(!without_token);
+    assert!(client.current_token().await.is_none());
+}
+
+#[tokio::test]
+async fn test_get_current_user_success() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("GET", "/api/me")
+        .match_header("authorization", "Bearer test-token-456")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{"user_id":123,"username":"john","email":"john@example.com","groups":["users"]}"#,
+        )
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::new(server.url()).unwrap();
+    client.set_token("test-token-456").await;
+
+    // #act
+    let result = client.get_current_user().await;
+
+    // #assert
+    assert!(result.is_ok());
+    let user = result.unwrap();
+    assert_eq!(user.id, "123");
+    assert_eq!(user.username, "john");
+    assert_eq!(user.email, "john@example.com");
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_get_current_user_if_modified() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let first = server
+        .mock("GET", "/api/me")
+        .match_header("if-none-match", Matcher::Missing)
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_header("etag", "\"v1\"")
+        .with_body(
+            r#"{"user_id":123,"username":"john","email":"john@example.com","groups":["users"]}"#,
+        )
+        .expect(1)
+        .create_async()
+        .await;
+    let unchanged = server
+        .mock("GET", "/api/me")
+        .match_header("if-none-match", "\"v1\"")
+        .with_status(304)
+        .expect(1)
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::new(server.url()).unwrap();
+    client.set_token("test-token-456").await;
+
+    // #act
+    let changed = client.get_current_user_if_modified().await.unwrap();
+    let again = client.get_current_user_if_modified().await.unwrap();
+
+    // #assert
+    assert_eq!(changed.unwrap().username, "john");
+    assert!(again.is_none());
+    first.assert_async().await;
+    unchanged.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_get_current_user_no_token() {
+    // #setup
+    let client = KeyrunesClient::new("https://example.com").unwrap();
+
+    // #act
+    let result = client.get_current_user().await;
+
+    // #assert
+    assert!(result.is_err());
+    assert!(matches!(result.unwrap_err(), KeyrunesError::InvalidToken));
+}
+
+#[tokio::test]
+async fn test_get_current_user_unauthorized() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("GET", "/api/me")
+        .match_header("authorization", "Bearer invalid-token")
+        .with_status(401)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"message":"Invalid token"}"#)
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::new(server.url()).unwrap();
+    client.set_token("invalid-token").await;
+
+    // #act
+    let result = client.get_current_user().await;
+
+    // #assert
+    assert!(result.is_err());
+    assert!(matches!(
+        result.unwrap_err(),
+        KeyrunesError::AuthenticationError { .. }
+    ));
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_register_admin_success() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("POST", "/api/register")
+        .with_status(201)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{"user":{"user_id":999,"username":"admin","email":"admin@example.com","groups":["admins"]},"token":"admin-token","requires_password_change":false}"#,
+        )
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::new(server.url()).unwrap();
+
+    // #act
+    let result = client
+        .register_admin(
+            "admin",
+            "admin@example.com",
+            "password123",
+            "admin-key",
+            None,
+        )
+        .await;
+
+    // #assert
+    assert!(result.is_ok());
+    let admin = result.unwrap();
+    assert_eq!(admin.username, "admin");
+    assert_eq!(admin.email, "admin@example.com");
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_get_user_success() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("GET", "/api/users/123")
+        .match_header("authorization", "Bearer test-token-789")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{"user_id":123,"username":"john","email":"john@example.com","groups":["users"]}"#,
+        )
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::new(server.url()).unwrap();
+    client.set_token("test-token-789").await;
+
+    // #act
+    let result = client.get_user("123").await;
+
+    // #assert
+    assert!(result.is_ok());
+    let user = result.unwrap();
+    assert_eq!(user.id, "123");
+    assert_eq!(user.username, "john");
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_get_user_not_found() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("GET", "/api/users/999")
+        .match_header("authorization", "Bearer test-token-789")
+        .with_status(404)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"message":"User not found"}"#)
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::new(server.url()).unwrap();
+    client.set_token("test-token-789").await;
+
+    // #act
+    let result = client.get_user("999").await;
+
+    // #assert
+    assert!(result.is_err());
+    assert!(matches!(
+        result.unwrap_err(),
+        KeyrunesError::UserNotFoundError { .. }
+    ));
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_has_group_true() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("GET", "/api/users/123/groups/admins")
+        .match_header("authorization", "Bearer test-token-789")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"has_group":true}"#)
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::new(server.url()).unwrap();
+    client.set_token("test-token-789").await;
+
+    // #act
+    let result = client.has_group("123", "admins").await;
+
+    // #assert
+    assert!(result.is_ok());
+    assert!(result.unwrap());
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_has_group_false() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("GET", "/api/users/123/groups/admins")
+        .match_header("authorization", "Bearer test-token-789")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"has_group":false}"#)
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::new(server.url()).unwrap();
+    client.set_token("test-token-789").await;
+
+    // #act
+    let result = client.has_group("123", "admins").await;
+
+    // #assert
+    assert!(result.is_ok());
+    assert!(!result.unwrap());
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_has_group_falls_back_to_last_known_membership() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let ok_mock = server
+        .mock("GET", "/api/users/123/groups/admins")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"has_group":true}"#)
+        .expect(1)
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::builder(server.url())
+        .membership_fallback(Duration::from_secs(60))
+        .build()
+        .unwrap();
+    client.set_token("test-token-789").await;
+    assert!(client.has_group("123", "admins").await.unwrap());
+    ok_mock.assert_async().await;
+    ok_mock.remove_async().await;
+
+    server
+        .mock(
+            "GET",
+            Matcher::Regex(r"^/api/users/123/groups/".to_string()),
+        )
+        .with_status(503)
+        .with_body("maintenance")
+        .create_async()
+        .await;
+
+    // #act
+    let cached = client.has_group("123", "admins").await;
+    let unknown = client.has_group("123", "editors").await;
+
+    // #assert
+    assert!(cached.unwrap());
+    assert!(matches!(unknown, Err(KeyrunesError::HttpError(_))));
+    assert_eq!(client.degraded_decisions(), 1);
+}
+
+#[tokio::test]
+async fn test_has_group_fallback_expires() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let ok_mock = server
+        .mock("GET", "/api/users/123/groups/admins")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"has_group":true}"#)
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::builder(server.url())
+        .membership_fallback(Duration::from_millis(50))
+        .build()
+        .unwrap();
+    client.set_token("test-token-789").await;
+    assert!(client.has_group("123", "admins").await.unwrap());
+    ok_mock.remove_async().await;
+    server
+        .mock("GET", "/api/users/123/groups/admins")
+        .with_status(503)
+        .create_async()
+        .await;
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    // #act
+    let result = client.has_group("123", "admins").await;
+
+    // #assert
+    assert!(matches!(result, Err(KeyrunesError::HttpError(_))));
+    assert_eq!(client.degraded_decisions(), 0);
+}
+
+#[tokio::test]
+async fn test_get_user_groups_from_current_user() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let me_mock = server
+        .mock("GET", "/api/me")
+        .match_header("authorization", "Bearer test-token-789")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{"user_id":123,"username":"john","email":"john@example.com","groups":["users","admins"]}"#,
+        )
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::new(server.url()).unwrap();
+    client.set_token("test-token-789").await;
+
+    // #act
+    let result = client.get_user_groups(None::<&str>).await;
+
+    // #assert
+    assert!(result.is_ok());
+    let groups = result.unwrap();
+    assert_eq!(groups.len(), 2);
+    assert!(groups.contains(&"users".to_string()));
+    assert!(groups.contains(&"admins".to_string()));
+    me_mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_get_user_groups_from_user_id() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let user_mock = server
+        .mock("GET", "/api/users/123")
+        .match_header("authorization", "Bearer test-token-789")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{"user_id":123,"username":"john","email":"john@example.com","groups":["users"]}"#,
+        )
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::new(server.url()).unwrap();
+    client.set_token("test-token-789").await;
+
+    // #act
+    let result = client.get_user_groups(Some("123")).await;
+
+    // #assert
+    assert!(result.is_ok());
+    let groups = result.unwrap();
+    assert_eq!(groups.len(), 1);
+    assert!(groups.contains(&"users".to_string()));
+    user_mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_clear_token() {
+    // #setup
+    let client = KeyrunesClient::new("https://example.com").unwrap();
+    client.set_token("test-token-123").await;
+
+    // #act
+    client.clear_token().await;
+    let result = client.get_current_user().await;
+
+    // #assert
+    assert!(result.is_err());
+    assert!(matches!(result.unwrap_err(), KeyrunesError::InvalidToken));
+}
+
+#[tokio::test]
+async fn test_step_up_success() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("POST", "/api/step-up")
+        .match_header("authorization", "Bearer password-token")
+        .match_body(mockito::Matcher::PartialJsonString(
+            r#"{"method":"totp","code":"123456"}"#.to_string(),
+        ))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"token":"mfa-token"}"#)
+        .create_async()
+        .await;
+    let me_mock = server
+        .mock("GET", "/api/me")
+        .match_header("authorization", "Bearer mfa-token")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"user_id":123,"username":"john","email":"john@example.com"}"#)
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::new(server.url()).unwrap();
+    client.set_token("password-token").await;
+
+    // #act
+    let result = client
+        .step_up(keyrunes_rust_sdk::StepUpChallenge::new("totp", "123456"))
+        .await;
+    let user = client.get_current_user().await;
+
+    // #assert
+    assert_eq!(result.unwrap().token, "mfa-token");
+    assert!(user.is_ok());
+    mock.assert_async().await;
+    me_mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_step_up_no_token() {
+    // #setup
+    let client = KeyrunesClient::new("https://example.com").unwrap();
+
+    // #act
+    let result = client
+        .step_up(keyrunes_rust_sdk::StepUpChallenge::new("totp", "123456"))
+        .await;
+
+    // #assert
+    assert!(matches!(result.unwrap_err(), KeyrunesError::InvalidToken));
+}
+
+#[tokio::test]
+async fn test_report_security_event() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("POST", "/api/security/events")
+        .match_header("authorization", "Bearer test-token-789")
+        .match_body(mockito::Matcher::Json(serde_json::json!({
+            "kind": "impossible_travel",
+            "user_id": "123",
+            "ip": "203.0.113.7",
+            "metadata": {"previous_country": "BR"}
+        })))
+        .with_status(202)
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::new(server.url()).unwrap();
+    client.set_token("test-token-789").await;
+    let event = keyrunes_rust_sdk::SecurityEvent::new(
+        keyrunes_rust_sdk::SecurityEventKind::ImpossibleTravel,
+    )
+    .with_user_id("123")
+    .with_ip("203.0.113.7")
+    .with_metadata("previous_country", "BR");
+
+    // #act
+    let result = client.report_security_event(&event).await;
 
     // #assert
     assert!(result.is_ok());
-    let user = result.unwrap();
-    assert_eq!(user.id, "123");
-    assert_eq!(user.username, "john");
-    assert_eq!(user.email, "john@example.com");
-
     mock.assert_async().await;
 }
 
 #[tokio::test]
-async fn test_register_failure() {
+async fn test_login_with_context_authenticated() {
     // #setup
     let mut server = Server::new_async().await;
     let mock = server
-        .mock("POST", "/api/register")
-        .with_status(400)
+        .mock("POST", "/api/login")
+        .match_body(mockito::Matcher::PartialJsonString(
+            r#"{"identity":"john","context":{"ip":"203.0.113.7","user_agent":"curl/8.0"}}"#
+                .to_string(),
+        ))
+        .with_status(200)
         .with_header("content-type", "application/json")
-        .with_body(r#"{"message":"Email already exists"}"#)
+        .with_body(r#"{"token":"test-token-123"}"#)
         .create_async()
         .await;
 
-    // #act
     let client = KeyrunesClient::new(server.url()).unwrap();
+    let context = keyrunes_rust_sdk::ClientContext::new()
+        .with_ip("203.0.113.7")
+        .with_user_agent("curl/8.0");
+
+    // #act
     let result = client
-        .register("john", "john@example.com", "password123", None)
+        .login_with_context("john", "password", None, &context)
         .await;
 
     // #assert
-    assert!(result.is_err());
-
+    match result.unwrap() {
+        keyrunes_rust_sdk::LoginOutcome::Authenticated(token) => {
+            assert_eq!(token.token, "test-token-123")
+        }
+        _ => panic!("Expected Authenticated"),
+    }
     mock.assert_async().await;
 }
 
 #[tokio::test]
-async fn test_register_and_login_flow() {
+async fn test_login_challenge_required() {
     // #setup
     let mut server = Server::new_async().await;
-
-    let register_mock = server
-        .mock("POST", "/api/register")
-        .with_status(201)
+    let login_mock = server
+        .mock("POST", "/api/login")
+        .with_status(200)
         .with_header("content-type", "application/json")
-        .with_body(r#"{"user":{"user_id":123,"username":"john","email":"john@example.com","groups":[]},"token":"test-token","requires_password_change":false}"#)
+        .with_body(r#"{"challenge_id":"ch-1","methods":["totp"],"reason":"new_device"}"#)
+        .expect(2)
         .create_async()
         .await;
-
-    let login_mock = server
-        .mock("POST", "/api/login")
+    let challenge_mock = server
+        .mock("POST", "/api/login/challenge")
+        .match_body(mockito::Matcher::PartialJsonString(
+            r#"{"challenge_id":"ch-1","code":"123456"}"#.to_string(),
+        ))
         .with_status(200)
         .with_header("content-type", "application/json")
-        .with_body(r#"{"token":"test-token-456"}"#)
+        .with_body(r#"{"token":"verified-token"}"#)
         .create_async()
         .await;
 
-    // #act
     let client = KeyrunesClient::new(server.url()).unwrap();
-    let user = client
-        .register("john", "john@example.com", "password123", None)
+
+    // #act
+    let plain = client.login("john", "password", None).await;
+    let outcome = client
+        .login_with_context(
+            "john",
+            "password",
+            None,
+            &keyrunes_rust_sdk::ClientContext::new(),
+        )
         .await
         .unwrap();
+    let challenge = match outcome {
+        keyrunes_rust_sdk::LoginOutcome::ChallengeRequired(challenge) => challenge,
+        _ => panic!("Expected ChallengeRequired"),
+    };
     let token = client
-        .login("john@example.com", "password123", None)
-        .await
-        .unwrap();
+        .complete_login_challenge(
+            keyrunes_rust_sdk::StepUpChallenge::new("totp", "123456")
+                .with_challenge_id(challenge.challenge_id),
+        )
+        .await;
 
     // #assert
-    assert_eq!(user.username, "john");
-    assert_eq!(token.token, "test-token-456");
-
-    register_mock.assert_async().await;
+    assert!(matches!(
+        plain.unwrap_err(),
+        KeyrunesError::ChallengeRequired(id) if id == "ch-1"
+    ));
+    assert_eq!(challenge.methods, vec!["totp".to_string()]);
+    assert_eq!(token.unwrap().token, "verified-token");
     login_mock.assert_async().await;
+    challenge_mock.assert_async().await;
 }
 
-#[tokio::test]
-async fn test_set_token() {
+#[test]
+fn test_logout_url() {
     // #setup
-    let mut server = Server::new_async().await;
-    let mock = server
-        .mock("GET", "/api/me")
-        .match_header("authorization", "Bearer test-token-123")
-        .with_status(200)
-        .with_header("content-type", "application/json")
-        .with_body(r#"{"user_id":999,"username":"test","email":"test@example.com","groups":[]}"#)
-        .create_async()
-        .await;
-
-    let client = KeyrunesClient::new(server.url()).unwrap();
+    let client = KeyrunesClient::new("https://keyrunes.example.com/").unwrap();
 
     // #act
-    client.set_token("test-token-123").await;
-    let user = client.get_current_user().await;
+    let url = client.logout_url(
+        Some("https://app.example.com/bye?from=menu"),
+        Some("id.token"),
+    );
+    let bare = client.logout_url(None, None);
 
     // #assert
-    assert!(user.is_ok());
-    assert_eq!(user.unwrap().username, "test");
-    mock.assert_async().await;
+    assert_eq!(
+        url,
+        "https://keyrunes.example.com/oauth/logout?id_token_hint=id.token\
+         &post_logout_redirect_uri=https%3A%2F%2Fapp.example.com%2Fbye%3Ffrom%3Dmenu"
+    );
+    assert_eq!(bare, "https://keyrunes.example.com/oauth/logout");
 }
 
 #[tokio::test]
-async fn test_get_current_user_success() {
+async fn test_userinfo() {
     // #setup
     let mut server = Server::new_async().await;
     let mock = server
-        .mock("GET", "/api/me")
-        .match_header("authorization", "Bearer test-token-456")
+        .mock("GET", "/oauth/userinfo")
+        .match_header("authorization", "Bearer abc")
         .with_status(200)
         .with_header("content-type", "application/json")
         .with_body(
-            r#"{"user_id":123,"username":"john","email":"john@example.com","groups":["users"]}"#,
+            r#"{"sub":"123","name":"John Doe","preferred_username":"john",
+                "email":"john@example.com","email_verified":true,
+                "updated_at":1700000000,"groups":["admins"],"department":"sales"}"#,
         )
         .create_async()
         .await;
-
     let client = KeyrunesClient::new(server.url()).unwrap();
-    client.set_token("test-token-456").await;
+    client.set_token("abc").await;
 
     // #act
-    let result = client.get_current_user().await;
+    let info = client.userinfo().await.unwrap();
 
     // #assert
-    assert!(result.is_ok());
-    let user = result.unwrap();
-    assert_eq!(user.id, "123");
-    assert_eq!(user.username, "john");
-    assert_eq!(user.email, "john@example.com");
     mock.assert_async().await;
+    assert_eq!(info.user.id, "123");
+    assert_eq!(info.user.username, "john");
+    assert_eq!(info.user.email, "john@example.com");
+    assert_eq!(info.user.groups, vec!["admins"]);
+    assert_eq!(info.user.updated_at.unwrap().timestamp(), 1700000000);
+    assert_eq!(info.claims.name.as_deref(), Some("John Doe"));
+    assert_eq!(info.claims.email_verified, Some(true));
+    assert_eq!(info.claims.extra["department"], "sales");
+    assert_eq!(client.export_session().await.user.unwrap().id, "123");
 }
 
 #[tokio::test]
-async fn test_get_current_user_no_token() {
+async fn test_userinfo_requires_token() {
     // #setup
-    let client = KeyrunesClient::new("https://example.com").unwrap();
+    let server = Server::new_async().await;
+    let client = KeyrunesClient::new(server.url()).unwrap();
 
     // #act
-    let result = client.get_current_user().await;
+    let result = client.userinfo().await;
 
     // #assert
-    assert!(result.is_err());
-    assert!(matches!(result.unwrap_err(), KeyrunesError::InvalidToken));
+    assert!(matches!(result, Err(KeyrunesError::InvalidToken)));
 }
 
 #[tokio::test]
-async fn test_get_current_user_unauthorized() {
+async fn test_for_org_scopes_requests() {
     // #setup
     let mut server = Server::new_async().await;
-    let mock = server
-        .mock("GET", "/api/me")
-        .match_header("authorization", "Bearer invalid-token")
-        .with_status(401)
+    let scoped_mock = server
+        .mock("GET", "/api/admin/stats")
+        .match_header("authorization", "Bearer admin-token")
+        .match_header("x-keyrunes-org", "acme")
+        .with_status(403)
+        .with_body(r#"{"message":"Not an administrator of acme"}"#)
+        .expect(1)
+        .create_async()
+        .await;
+    let unscoped_mock = server
+        .mock("GET", "/api/users/123")
+        .match_header("x-keyrunes-org", Matcher::Missing)
+        .with_status(200)
         .with_header("content-type", "application/json")
-        .with_body(r#"{"message":"Invalid token"}"#)
+        .with_body(r#"{"id":"123","username":"john","email":"john@example.com"}"#)
+        .expect(1)
         .create_async()
         .await;
 
     let client = KeyrunesClient::new(server.url()).unwrap();
-    client.set_token("invalid-token").await;
+    let acme = client.for_org("acme");
+    client.set_token("admin-token").await;
 
     // #act
-    let result = client.get_current_user().await;
+    let stats = acme.admin().stats().await;
+    let user = client.get_user("123").await;
 
     // #assert
-    assert!(result.is_err());
+    assert_eq!(acme.org_id(), Some("acme"));
+    assert_eq!(client.org_id(), None);
     assert!(matches!(
-        result.unwrap_err(),
-        KeyrunesError::AuthenticationError(_)
+        stats,
+        Err(KeyrunesError::AuthorizationError { .. })
     ));
-    mock.assert_async().await;
+    assert!(user.is_ok());
+    scoped_mock.assert_async().await;
+    unscoped_mock.assert_async().await;
 }
 
 #[tokio::test]
-async fn test_register_admin_success() {
+async fn test_as_user_keeps_its_own_token() {
     // #setup
     let mut server = Server::new_async().await;
-    let mock = server
-        .mock("POST", "/api/register")
-        .with_status(201)
+    let alice_mock = server
+        .mock("GET", "/api/me")
+        .match_header("authorization", "Bearer alice-token")
+        .with_status(200)
         .with_header("content-type", "application/json")
-        .with_body(
-            r#"{"user":{"user_id":999,"username":"admin","email":"admin@example.com","groups":["admins"]},"token":"admin-token","requires_password_change":false}"#,
-        )
+        .with_body(r#"{"id":"1","username":"alice","email":"alice@example.com"}"#)
+        .expect(1)
+        .create_async()
+        .await;
+    let service_mock = server
+        .mock("GET", "/api/me")
+        .match_header("authorization", "Bearer service-token")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"id":"0","username":"service","email":"service@example.com"}"#)
+        .expect(1)
         .create_async()
         .await;
 
     let client = KeyrunesClient::new(server.url()).unwrap();
+    client.set_token("service-token").await;
 
     // #act
-    let result = client
-        .register_admin(
-            "admin",
-            "admin@example.com",
-            "password123",
-            "admin-key",
-            None,
-        )
-        .await;
+    let alice = client.as_user("alice-token");
+    let alice_user = alice.get_current_user().await.unwrap();
+    alice.clear_token().await;
+    let service_user = client.get_current_user().await.unwrap();
 
     // #assert
-    assert!(result.is_ok());
-    let admin = result.unwrap();
-    assert_eq!(admin.username, "admin");
-    assert_eq!(admin.email, "admin@example.com");
-    mock.assert_async().await;
+    assert_eq!(alice_user.username, "alice");
+    assert_eq!(service_user.username, "service");
+    assert!(alice.current_token().await.is_none());
+    assert_eq!(client.current_token().await.unwrap().token, "service-token");
+    alice_mock.assert_async().await;
+    service_mock.assert_async().await;
+}
+
+#[test]
+fn test_ip_preference_orders_addresses() {
+    // #setup
+    let v6: std::net::SocketAddr = "[2001:db8::1]:443".parse().unwrap();
+    let v4: std::net::SocketAddr = "192.0.2.1:443".parse().unwrap();
+    let v4_2: std::net::SocketAddr = "192.0.2.2:443".parse().unwrap();
+    let resolved = [v6, v4, v4_2];
+
+    // #act & #assert
+    assert_eq!(IpPreference::System.order(resolved), [v6, v4, v4_2]);
+    assert_eq!(IpPreference::PreferIpv4.order(resolved), [v4, v4_2, v6]);
+    assert_eq!(IpPreference::PreferIpv6.order(resolved), [v6, v4, v4_2]);
+    assert_eq!(IpPreference::Ipv4Only.order(resolved), [v4, v4_2]);
+    assert_eq!(IpPreference::Ipv6Only.order(resolved), [v6]);
 }
 
 #[tokio::test]
-async fn test_get_user_success() {
+async fn test_ip_preference_applies_to_connections() {
     // #setup
     let mut server = Server::new_async().await;
     let mock = server
         .mock("GET", "/api/users/123")
-        .match_header("authorization", "Bearer test-token-789")
         .with_status(200)
         .with_header("content-type", "application/json")
-        .with_body(
-            r#"{"user_id":123,"username":"john","email":"john@example.com","groups":["users"]}"#,
-        )
+        .with_body(r#"{"id":"123","username":"john","email":"john@example.com"}"#)
+        .expect(1)
         .create_async()
         .await;
+    let url = format!("http://localhost:{}", server.socket_address().port());
 
-    let client = KeyrunesClient::new(server.url()).unwrap();
-    client.set_token("test-token-789").await;
+    let ipv4 = KeyrunesClient::builder(&url)
+        .ip_preference(IpPreference::Ipv4Only)
+        .connect_timeout(std::time::Duration::from_secs(2))
+        .build()
+        .unwrap();
+    let ipv6 = KeyrunesClient::builder(&url)
+        .ip_preference(IpPreference::Ipv6Only)
+        .connect_timeout(std::time::Duration::from_secs(2))
+        .build()
+        .unwrap();
+    ipv4.set_token("test-token-123").await;
+    ipv6.set_token("test-token-123").await;
 
     // #act
-    let result = client.get_user("123").await;
+    let user = ipv4.get_user("123").await;
+    let unreachable = ipv6.get_user("123").await;
 
     // #assert
-    assert!(result.is_ok());
-    let user = result.unwrap();
-    assert_eq!(user.id, "123");
-    assert_eq!(user.username, "john");
+    assert_eq!(user.unwrap().username, "john");
+    assert!(unreachable.is_err());
     mock.assert_async().await;
 }
 
-#[tokio::test]
-async fn test_get_user_not_found() {
-    // #setup
-    let mut server = Server::new_async().await;
-    let mock = server
-        .mock("GET", "/api/users/999")
-        .match_header("authorization", "Bearer test-token-789")
-        .with_status(404)
-        .with_header("content-type", "application/json")
-        .with_body(r#"{"message":"User not found"}"#)
-        .create_async()
-        .await;
-
-    let client = KeyrunesClient::new(server.url()).unwrap();
-    client.set_token("test-token-789").await;
-
-    // #act
-    let result = client.get_user("999").await;
+/// Resolver serving fixed addresses for every hostname
+struct StaticResolver(Vec<std::net::SocketAddr>);
 
-    // #assert
-    assert!(result.is_err());
-    assert!(matches!(
-        result.unwrap_err(),
-        KeyrunesError::UserNotFoundError(_)
-    ));
-    mock.assert_async().await;
+impl reqwest::dns::Resolve for StaticResolver {
+    fn resolve(&self, _name: hyper::client::connect::dns::Name) -> reqwest::dns::Resolving {
+        let addrs = self.0.clone();
+        Box::pin(async move { Ok(Box::new(addrs.into_iter()) as reqwest::dns::Addrs) })
+    }
 }
 
 #[tokio::test]
-async fn test_has_group_true() {
+async fn test_ip_preference_falls_back_without_waiting_for_connect_timeout() {
     // #setup
     let mut server = Server::new_async().await;
     let mock = server
-        .mock("GET", "/api/users/123/groups/admins")
-        .match_header("authorization", "Bearer test-token-789")
+        .mock("GET", "/api/users/123")
         .with_status(200)
         .with_header("content-type", "application/json")
-        .with_body(r#"{"has_group":true}"#)
+        .with_body(r#"{"id":"123","username":"john","email":"john@example.com"}"#)
         .create_async()
         .await;
-
-    let client = KeyrunesClient::new(server.url()).unwrap();
-    client.set_token("test-token-789").await;
+    let port = server.socket_address().port();
+    // Discard-only prefix (RFC 6666): connection attempts never complete
+    let blackhole: std::net::SocketAddr = format!("[100::1]:{}", port).parse().unwrap();
+
+    let client = KeyrunesClient::builder(format!("http://keyrunes.test:{}", port))
+        .dns_resolver(StaticResolver(vec![
+            blackhole,
+            std::net::SocketAddr::from(([127, 0, 0, 1], port)),
+        ]))
+        .ip_preference(IpPreference::PreferIpv6)
+        .connect_timeout(std::time::Duration::from_secs(20))
+        .build()
+        .unwrap();
+    client.set_token("test-token-123").await;
+    let started = std::time::Instant::now();
 
     // #act
-    let result = client.has_group("123", "admins").await;
+    let user = client.get_user("123").await;
 
     // #assert
-    assert!(result.is_ok());
-    assert!(result.unwrap());
+    assert_eq!(user.unwrap().username, "john");
+    assert!(started.elapsed() < std::time::Duration::from_secs(5));
     mock.assert_async().await;
 }
 
 #[tokio::test]
-async fn test_has_group_false() {
+async fn test_custom_dns_resolver() {
     // #setup
     let mut server = Server::new_async().await;
     let mock = server
-        .mock("GET", "/api/users/123/groups/admins")
-        .match_header("authorization", "Bearer test-token-789")
+        .mock("GET", "/api/users/123")
+        .match_header("host", Matcher::Regex("^keyrunes.internal".into()))
         .with_status(200)
         .with_header("content-type", "application/json")
-        .with_body(r#"{"has_group":false}"#)
+        .with_body(r#"{"id":"123","username":"john","email":"john@example.com"}"#)
+        .expect(1)
         .create_async()
         .await;
-
-    let client = KeyrunesClient::new(server.url()).unwrap();
-    client.set_token("test-token-789").await;
+    let url = format!(
+        "http://keyrunes.internal:{}",
+        server.socket_address().port()
+    );
+
+    let client = KeyrunesClient::builder(url)
+        .dns_resolver(StaticResolver(vec![server.socket_address()]))
+        .build()
+        .unwrap();
+    client.set_token("test-token-123").await;
 
     // #act
-    let result = client.has_group("123", "admins").await;
+    let user = client.get_user("123").await.unwrap();
 
     // #assert
-    assert!(result.is_ok());
-    assert!(!result.unwrap());
+    assert_eq!(user.username, "john");
     mock.assert_async().await;
 }
 
+/// Answers every A query with 127.0.0.1, and other queries with no records
+#[cfg(feature = "hickory-dns")]
+async fn spawn_dns_server() -> std::net::SocketAddr {
+    let socket = tokio::net::UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let addr = socket.local_addr().unwrap();
+    tokio::spawn(async move {
+        let mut buf = [0u8; 512];
+        while let Ok((len, peer)) = socket.recv_from(&mut buf).await {
+            let query = &buf[..len];
+            // Question: labels up to the root, then type and class
+            let mut end = 12;
+            while query[end] != 0 {
+                end += query[end] as usize + 1;
+            }
+            end += 5;
+            let is_a = query[end - 4..end - 2] == [0, 1];
+
+            let mut reply = query[..2].to_vec();
+            reply.extend_from_slice(&[0x81, 0x80, 0, 1, 0, is_a as u8, 0, 0, 0, 0]);
+            reply.extend_from_slice(&query[12..end]);
+            if is_a {
+                reply.extend_from_slice(&[0xc0, 12, 0, 1, 0, 1, 0, 0, 0, 60, 0, 4, 127, 0, 0, 1]);
+            }
+            let _ = socket.send_to(&reply, peer).await;
+        }
+    });
+    addr
+}
+
+#[cfg(feature = "hickory-dns")]
 #[tokio::test]
-async fn test_get_user_groups_from_current_user() {
+async fn test_hickory_resolver_custom_nameserver() {
     // #setup
     let mut server = Server::new_async().await;
-    let me_mock = server
-        .mock("GET", "/api/me")
-        .match_header("authorization", "Bearer test-token-789")
+    let mock = server
+        .mock("GET", "/api/users/123")
+        .match_header("host", Matcher::Regex("^keyrunes.internal".into()))
         .with_status(200)
         .with_header("content-type", "application/json")
-        .with_body(
-            r#"{"user_id":123,"username":"john","email":"john@example.com","groups":["users","admins"]}"#,
-        )
+        .with_body(r#"{"id":"123","username":"john","email":"john@example.com"}"#)
+        .expect(1)
         .create_async()
         .await;
-
-    let client = KeyrunesClient::new(server.url()).unwrap();
-    client.set_token("test-token-789").await;
+    let nameserver = spawn_dns_server().await;
+    let url = format!(
+        "http://keyrunes.internal:{}",
+        server.socket_address().port()
+    );
+
+    let client = KeyrunesClient::builder(url)
+        .dns_resolver(keyrunes_rust_sdk::client::HickoryResolver::new([
+            nameserver,
+        ]))
+        .build()
+        .unwrap();
+    client.set_token("test-token-123").await;
 
     // #act
-    let result = client.get_user_groups(None::<&str>).await;
+    let user = client.get_user("123").await.unwrap();
 
     // #assert
-    assert!(result.is_ok());
-    let groups = result.unwrap();
-    assert_eq!(groups.len(), 2);
-    assert!(groups.contains(&"users".to_string()));
-    assert!(groups.contains(&"admins".to_string()));
-    me_mock.assert_async().await;
+    assert_eq!(user.username, "john");
+    mock.assert_async().await;
 }
 
 #[tokio::test]
-async fn test_get_user_groups_from_user_id() {
+async fn test_last_response_meta() {
     // #setup
     let mut server = Server::new_async().await;
-    let user_mock = server
+    let _user = server
         .mock("GET", "/api/users/123")
-        .match_header("authorization", "Bearer test-token-789")
         .with_status(200)
         .with_header("content-type", "application/json")
-        .with_body(
-            r#"{"user_id":123,"username":"john","email":"john@example.com","groups":["users"]}"#,
-        )
+        .with_header("x-request-id", "req-42")
+        .with_header("x-keyrunes-version", "2.4.1")
+        .with_header("x-ratelimit-limit", "100")
+        .with_header("x-ratelimit-remaining", "99")
+        .with_header("x-ratelimit-reset", "30")
+        .with_header("deprecation", "true")
+        .with_header("sunset", "Wed, 01 Jul 2026 00:00:00 GMT")
+        .with_body(r#"{"id":"123","username":"john","email":"john@example.com"}"#)
         .create_async()
         .await;
 
     let client = KeyrunesClient::new(server.url()).unwrap();
-    client.set_token("test-token-789").await;
-
-    // #act
-    let result = client.get_user_groups(Some("123")).await;
-
-    // #assert
-    assert!(result.is_ok());
-    let groups = result.unwrap();
-    assert_eq!(groups.len(), 1);
-    assert!(groups.contains(&"users".to_string()));
-    user_mock.assert_async().await;
-}
-
-#[tokio::test]
-async fn test_clear_token() {
-    // #setup
-    let client = KeyrunesClient::new("https://example.com").unwrap();
     client.set_token("test-token-123").await;
+    assert!(client.last_response_meta().is_none());
 
     // #act
-    client.clear_token().await;
-    let result = client.get_current_user().await;
+    client.get_user("123").await.unwrap();
+    let meta = client.last_response_meta().unwrap();
 
     // #assert
-    assert!(result.is_err());
-    assert!(matches!(result.unwrap_err(), KeyrunesError::InvalidToken));
+    assert_eq!(meta.status, 200);
+    assert_eq!(meta.request_id.as_deref(), Some("req-42"));
+    assert_eq!(meta.server_version.as_deref(), Some("2.4.1"));
+    assert_eq!(meta.rate_limit, Some(100));
+    assert_eq!(meta.rate_limit_remaining, Some(99));
+    assert_eq!(meta.rate_limit_reset, Some(30));
+    assert!(meta.is_deprecated());
+    assert_eq!(
+        meta.sunset.as_deref(),
+        Some("Wed, 01 Jul 2026 00:00:00 GMT")
+    );
 }
@@ -0,0 +1,204 @@
+#![cfg(feature = "sessions")]
+
+use keyrunes_rust_sdk::csrf::CsrfProtection;
+use keyrunes_rust_sdk::middleware::messages::RejectionKind;
+use keyrunes_rust_sdk::session::SessionManager;
+use keyrunes_rust_sdk::Token;
+
+fn token(value: &str) -> Token {
+    Token {
+        token: value.to_string(),
+        token_type: None,
+        expires_in: None,
+        refresh_token: None,
+        expires_at: None,
+        id_token: None,
+    }
+}
+
+#[test]
+fn test_safe_methods_are_not_checked() {
+    // #setup
+    let csrf = CsrfProtection::new();
+
+    // #act & #assert
+    for method in ["GET", "HEAD", "OPTIONS", "trace"] {
+        assert!(csrf.verify(method, None, None, None).is_ok());
+    }
+}
+
+#[test]
+fn test_header_must_match_cookie() {
+    // #setup
+    let csrf = CsrfProtection::new();
+
+    // #act
+    let matching = csrf.verify(
+        "POST",
+        Some("theme=dark; keyrunes_csrf=abc"),
+        Some("abc"),
+        None,
+    );
+    let mismatched = csrf.verify("DELETE", Some("keyrunes_csrf=abc"), Some("abd"), None);
+    let missing_cookie = csrf.verify("PUT", None, Some("abc"), None);
+    let empty = csrf.verify("POST", Some("keyrunes_csrf="), Some(""), None);
+
+    // #assert
+    assert!(matching.is_ok());
+    assert_eq!(
+        mismatched,
+        Err(RejectionKind::Forbidden(
+            "CSRF token missing or invalid".to_string()
+        ))
+    );
+    assert!(missing_cookie.is_err());
+    assert!(empty.is_err());
+}
+
+#[tokio::test]
+async fn test_header_must_match_session_token() {
+    // #setup
+    let csrf = CsrfProtection::new();
+    let sessions = SessionManager::in_memory();
+    let id = sessions.create(&token("abc")).await.unwrap();
+    let session = sessions.resolve(&id).await.unwrap().unwrap();
+    let own = format!("keyrunes_csrf={}", session.csrf_token);
+
+    // #act
+    let valid = csrf.verify(
+        "POST",
+        Some(&own),
+        Some(&session.csrf_token),
+        Some(&session),
+    );
+    let planted = csrf.verify(
+        "POST",
+        Some("keyrunes_csrf=evil"),
+        Some("evil"),
+        Some(&session),
+    );
+
+    // #assert
+    assert!(valid.is_ok());
+    assert!(planted.is_err());
+}
+
+#[test]
+fn test_csrf_cookie() {
+    // #setup
+    let csrf = CsrfProtection::new().with_cookie_name("xsrf");
+
+    // #act
+    let cookie = csrf.cookie("abc");
+    let generated = CsrfProtection::generate_token();
+
+    // #assert
+    assert_eq!(cookie, "xsrf=abc; Path=/; SameSite=Lax; Secure");
+    assert_eq!(generated.len(), 64);
+    assert_ne!(generated, CsrfProtection::generate_token());
+}
+
+#[cfg(feature = "axum")]
+mod axum_layer {
+    use super::token;
+    use axum::body::Body;
+    use axum::http::{header, Request, StatusCode};
+    use axum::routing::post;
+    use axum::Router;
+    use keyrunes_rust_sdk::csrf::CsrfProtection;
+    use keyrunes_rust_sdk::middleware::axum::{csrf_protect, session_auth};
+    use keyrunes_rust_sdk::session::SessionManager;
+    use tower::ServiceExt;
+
+    async fn status(sessions: SessionManager, id: &str, csrf_token: &str) -> StatusCode {
+        let app: Router = Router::new()
+            .route("/transfer", post(|| async { "ok" }))
+            .layer(axum::middleware::from_fn_with_state(
+                CsrfProtection::new(),
+                csrf_protect,
+            ))
+            .layer(axum::middleware::from_fn_with_state(sessions, session_auth));
+
+        app.oneshot(
+            Request::post("/transfer")
+                .header(
+                    header::COOKIE,
+                    format!("keyrunes_session={}; keyrunes_csrf={}", id, csrf_token),
+                )
+                .header("x-csrf-token", csrf_token)
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap()
+        .status()
+    }
+
+    #[tokio::test]
+    async fn test_session_csrf_token_is_accepted() {
+        // #setup
+        let sessions = SessionManager::in_memory();
+        let id = sessions.create(&token("abc")).await.unwrap();
+        let session = sessions.resolve(&id).await.unwrap().unwrap();
+
+        // #act
+        let status = status(sessions, &id, &session.csrf_token).await;
+
+        // #assert
+        assert_eq!(status, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_foreign_csrf_token_is_rejected() {
+        // #setup
+        let sessions = SessionManager::in_memory();
+        let id = sessions.create(&token("abc")).await.unwrap();
+
+        // #act
+        let status = status(sessions, &id, "planted").await;
+
+        // #assert
+        assert_eq!(status, StatusCode::FORBIDDEN);
+    }
+}
+
+#[cfg(feature = "actix")]
+mod actix_layer {
+    use actix_web::{test, web, App, HttpResponse};
+    use keyrunes_rust_sdk::csrf::CsrfProtection;
+    use keyrunes_rust_sdk::middleware::actix::{KeyrunesCsrf, KeyrunesState};
+    use keyrunes_rust_sdk::KeyrunesClient;
+
+    async fn status(cookie: &str, header: Option<&str>) -> u16 {
+        let state = KeyrunesState::new(KeyrunesClient::new("http://localhost").unwrap());
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(state))
+                .wrap(KeyrunesCsrf::new(CsrfProtection::new()))
+                .route("/transfer", web::post().to(HttpResponse::Ok)),
+        )
+        .await;
+
+        let mut request = test::TestRequest::post()
+            .uri("/transfer")
+            .insert_header(("cookie", cookie));
+        if let Some(header) = header {
+            request = request.insert_header(("x-csrf-token", header));
+        }
+        match test::try_call_service(&app, request.to_request()).await {
+            Ok(response) => response.status().as_u16(),
+            Err(err) => err.as_response_error().status_code().as_u16(),
+        }
+    }
+
+    #[actix_web::test]
+    async fn test_double_submit_token_is_checked() {
+        // #act
+        let valid = status("keyrunes_csrf=abc", Some("abc")).await;
+        let missing = status("keyrunes_csrf=abc", None).await;
+
+        // #assert
+        assert_eq!(valid, 200);
+        assert_eq!(missing, 403);
+    }
+}
@@ -0,0 +1,111 @@
+use keyrunes_rust_sdk::{KeyrunesClient, KeyrunesError, ResourceRef};
+use mockito::{Matcher, Server};
+
+const DELEGATION: &str = r#"{
+    "id": "dl-1",
+    "resource": {"type": "document", "id": "42"},
+    "from_user_id": "123",
+    "to_user_id": "456",
+    "permissions": ["read", "comment"],
+    "expires_at": "2030-01-01T00:00:00Z"
+}"#;
+
+#[tokio::test]
+async fn test_grant_access() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("POST", "/api/delegations")
+        .match_header("authorization", "Bearer test-token-789")
+        .match_body(Matcher::Json(serde_json::json!({
+            "resource": {"type": "document", "id": "42"},
+            "from_user_id": "123",
+            "to_user_id": "456",
+            "permissions": ["read", "comment"],
+            "expires_at": "2030-01-01T00:00:00Z"
+        })))
+        .with_status(201)
+        .with_header("content-type", "application/json")
+        .with_body(DELEGATION)
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::new(server.url()).unwrap();
+    client.set_token("test-token-789").await;
+    let expires_at = "2030-01-01T00:00:00Z".parse().unwrap();
+
+    // #act
+    let delegation = client
+        .grant_access(
+            &ResourceRef::new("document", "42"),
+            "123",
+            "456",
+            ["read", "comment"],
+            Some(expires_at),
+        )
+        .await
+        .unwrap();
+
+    // #assert
+    assert_eq!(delegation.id, "dl-1");
+    assert_eq!(delegation.resource.to_string(), "document:42");
+    assert_eq!(delegation.permissions, vec!["read", "comment"]);
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_list_and_revoke_access() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let list_mock = server
+        .mock("GET", "/api/delegations")
+        .match_query(Matcher::AllOf(vec![
+            Matcher::UrlEncoded("resource_type".into(), "document".into()),
+            Matcher::UrlEncoded("resource_id".into(), "42".into()),
+        ]))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(format!("[{}]", DELEGATION))
+        .create_async()
+        .await;
+    let revoke_mock = server
+        .mock("DELETE", "/api/delegations/dl-1")
+        .with_status(204)
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::new(server.url()).unwrap();
+    client.set_token("test-token-789").await;
+
+    // #act
+    let delegations = client
+        .list_delegations(&ResourceRef::new("document", "42"))
+        .await
+        .unwrap();
+    client.revoke_access(&delegations[0].id).await.unwrap();
+
+    // #assert
+    assert_eq!(delegations.len(), 1);
+    list_mock.assert_async().await;
+    revoke_mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_grant_access_without_token() {
+    // #setup
+    let client = KeyrunesClient::new("http://localhost:1").unwrap();
+
+    // #act
+    let result = client
+        .grant_access(
+            &ResourceRef::new("document", "42"),
+            "123",
+            "456",
+            ["read"],
+            None,
+        )
+        .await;
+
+    // #assert
+    assert!(matches!(result, Err(KeyrunesError::InvalidToken)));
+}
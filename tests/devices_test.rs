@@ -0,0 +1,63 @@
+use keyrunes_rust_sdk::KeyrunesClient;
+use mockito::Server;
+
+#[tokio::test]
+async fn test_list_devices() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("GET", "/api/users/123/devices")
+        .match_header("authorization", "Bearer test-token-789")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"[{"id":"d1","name":"Chrome on macOS","trusted":true},{"id":"d2","name":"iPhone"}]"#,
+        )
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::new(server.url()).unwrap();
+    client.set_token("test-token-789").await;
+
+    // #act
+    let devices = client.list_devices("123").await.unwrap();
+
+    // #assert
+    assert_eq!(devices.len(), 2);
+    assert!(devices[0].trusted);
+    assert!(!devices[1].trusted);
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_trust_and_revoke_device() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let trust_mock = server
+        .mock("POST", "/api/devices/d2/trust")
+        .match_header("authorization", "Bearer test-token-789")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"id":"d2","name":"iPhone","trusted":true}"#)
+        .create_async()
+        .await;
+    let revoke_mock = server
+        .mock("DELETE", "/api/devices/d2")
+        .match_header("authorization", "Bearer test-token-789")
+        .with_status(204)
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::new(server.url()).unwrap();
+    client.set_token("test-token-789").await;
+
+    // #act
+    let device = client.trust_device("d2").await.unwrap();
+    let revoked = client.revoke_device("d2").await;
+
+    // #assert
+    assert!(device.trusted);
+    assert!(revoked.is_ok());
+    trust_mock.assert_async().await;
+    revoke_mock.assert_async().await;
+}
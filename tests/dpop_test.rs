@@ -0,0 +1,195 @@
+#![cfg(feature = "dpop")]
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use jsonwebtoken::{Algorithm, DecodingKey, Validation};
+use keyrunes_rust_sdk::dpop::DpopKey;
+use keyrunes_rust_sdk::KeyrunesClient;
+use mockito::{Matcher, Server};
+use sha2::{Digest, Sha256};
+
+/// Verifies a proof with the key it embeds, returning its claims.
+fn verify(proof: &str) -> serde_json::Value {
+    let header = jsonwebtoken::decode_header(proof).unwrap();
+    assert_eq!(header.typ.as_deref(), Some("dpop+jwt"));
+    let key = DecodingKey::from_jwk(&header.jwk.unwrap()).unwrap();
+    let mut validation = Validation::new(Algorithm::ES256);
+    validation.validate_exp = false;
+    validation.required_spec_claims.clear();
+    jsonwebtoken::decode::<serde_json::Value>(proof, &key, &validation)
+        .unwrap()
+        .claims
+}
+
+/// Claims of the proof sent with a request, without verifying it
+fn proof_claims(request: &mockito::Request) -> serde_json::Value {
+    let proof = request.header("dpop")[0].to_str().unwrap().to_string();
+    let payload = proof.split('.').nth(1).unwrap();
+    serde_json::from_slice(&URL_SAFE_NO_PAD.decode(payload).unwrap()).unwrap()
+}
+
+#[test]
+fn test_proof_claims() {
+    // #setup
+    let key = DpopKey::generate().unwrap();
+
+    // #act
+    let proof = key
+        .proof(
+            "POST",
+            "https://keyrunes.example.com/api/me?expand=groups#top",
+            Some("abc"),
+            Some("n1"),
+        )
+        .unwrap();
+    let claims = verify(&proof);
+
+    // #assert
+    assert_eq!(claims["htm"], "POST");
+    assert_eq!(claims["htu"], "https://keyrunes.example.com/api/me");
+    assert_eq!(claims["nonce"], "n1");
+    assert_eq!(
+        claims["ath"],
+        URL_SAFE_NO_PAD.encode(Sha256::digest(b"abc"))
+    );
+    assert!(claims["jti"].as_str().is_some_and(|jti| !jti.is_empty()));
+    assert!(claims["iat"].is_i64());
+}
+
+#[test]
+fn test_key_round_trips_through_pkcs8() {
+    // #setup
+    let key = DpopKey::generate().unwrap();
+
+    // #act
+    let loaded = DpopKey::from_pkcs8(key.pkcs8()).unwrap();
+
+    // #assert
+    assert_eq!(loaded.thumbprint(), key.thumbprint());
+    assert_eq!(key.thumbprint().len(), 43);
+    assert_ne!(DpopKey::generate().unwrap().thumbprint(), key.thumbprint());
+}
+
+#[tokio::test]
+async fn test_client_sends_dpop_token_and_proof() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let url = format!("{}/api/me", server.url());
+    let mock = server
+        .mock("GET", "/api/me")
+        .match_header("authorization", "DPoP abc")
+        .match_request(move |request| {
+            let claims = proof_claims(request);
+            claims["htm"] == "GET" && claims["htu"] == url.as_str()
+        })
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"id":"123","username":"john","email":"john@example.com","groups":[]}"#)
+        .create_async()
+        .await;
+    let client = KeyrunesClient::builder(server.url())
+        .dpop(DpopKey::generate().unwrap())
+        .build()
+        .unwrap();
+    client.set_token("abc").await;
+
+    // #act
+    let user = client.get_current_user().await.unwrap();
+
+    // #assert
+    mock.assert_async().await;
+    assert_eq!(user.username, "john");
+}
+
+#[tokio::test]
+async fn test_logout_revokes_dpop_bound_token() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let url = format!("{}/api/logout", server.url());
+    let mock = server
+        .mock("POST", "/api/logout")
+        .match_header("authorization", "DPoP abc")
+        .match_request(move |request| {
+            let claims = proof_claims(request);
+            claims["htm"] == "POST"
+                && claims["htu"] == url.as_str()
+                && claims["ath"] == URL_SAFE_NO_PAD.encode(Sha256::digest(b"abc")).as_str()
+        })
+        .with_status(204)
+        .create_async()
+        .await;
+    let client = KeyrunesClient::builder(server.url())
+        .dpop(DpopKey::generate().unwrap())
+        .build()
+        .unwrap();
+    client.set_token("abc").await;
+
+    // #act
+    let acknowledged = client.logout().await.unwrap();
+
+    // #assert
+    mock.assert_async().await;
+    assert!(acknowledged);
+}
+
+#[tokio::test]
+async fn test_client_retries_nonce_challenge() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let challenge = server
+        .mock("POST", "/api/login")
+        .match_request(|request| proof_claims(request).get("nonce").is_none())
+        .with_status(400)
+        .with_header("DPoP-Nonce", "n1")
+        .with_body(r#"{"error":"use_dpop_nonce"}"#)
+        .expect(1)
+        .create_async()
+        .await;
+    let login = server
+        .mock("POST", "/api/login")
+        .match_request(|request| proof_claims(request)["nonce"] == "n1")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"token":"bound","token_type":"DPoP"}"#)
+        .expect(1)
+        .create_async()
+        .await;
+    let client = KeyrunesClient::builder(server.url())
+        .dpop(DpopKey::generate().unwrap())
+        .build()
+        .unwrap();
+
+    // #act
+    let token = client
+        .login("john@example.com", "secret", None)
+        .await
+        .unwrap();
+
+    // #assert
+    challenge.assert_async().await;
+    login.assert_async().await;
+    assert_eq!(token.token, "bound");
+}
+
+#[tokio::test]
+async fn test_client_without_dpop_sends_bearer_token() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("GET", "/api/me")
+        .match_header("authorization", "Bearer abc")
+        .match_header("dpop", Matcher::Missing)
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"id":"123","username":"john","email":"john@example.com","groups":[]}"#)
+        .create_async()
+        .await;
+    let client = KeyrunesClient::new(server.url()).unwrap();
+    client.set_token("abc").await;
+
+    // #act
+    client.get_current_user().await.unwrap();
+
+    // #assert
+    mock.assert_async().await;
+}
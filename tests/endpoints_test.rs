@@ -0,0 +1,118 @@
+use keyrunes_rust_sdk::endpoints::{self, EndpointResolver};
+use keyrunes_rust_sdk::KeyrunesClient;
+use mockito::Server;
+
+#[test]
+fn test_default_resolver_keeps_paths() {
+    // #setup
+    let resolver = EndpointResolver::new();
+
+    // #act
+    let login = resolver.resolve(endpoints::LOGIN);
+    let user = endpoints::user("123");
+
+    // #assert
+    assert_eq!(login, "/api/login");
+    assert_eq!(resolver.resolve(&user), "/api/users/123");
+}
+
+#[test]
+fn test_resolver_prefix_and_version() {
+    // #setup
+    let resolver = EndpointResolver::new().prefix("/auth/api/").version("v2");
+
+    // #act
+    let login = resolver.resolve(endpoints::LOGIN);
+    let query = resolver.resolve("/api?page=2");
+    let lookalike = resolver.resolve("/apis/login");
+    let jwks = resolver.resolve(endpoints::JWKS);
+    let scim = resolver.resolve(endpoints::SCIM);
+
+    // #assert
+    assert_eq!(login, "/auth/api/v2/login");
+    assert_eq!(query, "/auth/api/v2?page=2");
+    assert_eq!(lookalike, "/apis/login");
+    assert_eq!(jwks, "/.well-known/jwks.json");
+    assert_eq!(scim, "/scim/v2");
+}
+
+#[tokio::test]
+async fn test_client_uses_resolver() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let login_mock = server
+        .mock("POST", "/api/v2/login")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"token":"test-token-123"}"#)
+        .expect(1)
+        .create_async()
+        .await;
+    let user_mock = server
+        .mock("GET", "/api/v2/users/123")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"id":"123","username":"john","email":"john@example.com"}"#)
+        .expect(1)
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::builder(server.url())
+        .endpoints(EndpointResolver::new().version("v2"))
+        .build()
+        .unwrap();
+
+    // #act
+    client
+        .login("user@example.com", "password", None)
+        .await
+        .unwrap();
+    let user = client.get_user("123").await.unwrap();
+
+    // #assert
+    assert_eq!(user.username, "john");
+    login_mock.assert_async().await;
+    user_mock.assert_async().await;
+}
+
+#[test]
+fn test_openapi_spec_covers_endpoints() {
+    // #setup
+    let spec: serde_json::Value =
+        serde_json::from_str(include_str!("../openapi/keyrunes.json")).unwrap();
+    let paths = spec["paths"].as_object().unwrap();
+    let fixed = [
+        endpoints::LOGIN,
+        endpoints::LOGIN_CHALLENGE,
+        endpoints::REGISTER,
+        endpoints::ME,
+        endpoints::REFRESH,
+        endpoints::RELATIONS_CHECK,
+        endpoints::DELEGATIONS,
+        endpoints::ADMIN_GROUPS,
+        endpoints::ADMIN_SIGNING_KEYS_ROTATE,
+        endpoints::USERINFO,
+        endpoints::JWKS,
+    ];
+
+    // #act
+    let templated = [
+        endpoints::user("{user_id}"),
+        endpoints::user_group("{user_id}", "{group_id}"),
+        endpoints::device_trust("{device_id}"),
+        endpoints::admin_group_member("{group_id}", "{user_id}"),
+    ];
+
+    // #assert
+    for path in fixed
+        .iter()
+        .copied()
+        .chain(templated.iter().map(String::as_str))
+    {
+        assert!(
+            paths.contains_key(path),
+            "{} is missing from the spec",
+            path
+        );
+    }
+}
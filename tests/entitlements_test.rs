@@ -0,0 +1,88 @@
+use keyrunes_rust_sdk::KeyrunesClient;
+use mockito::Server;
+
+const ENTITLEMENTS: &str = r#"[
+    {"key":"advanced_reports","enabled":true},
+    {"key":"seats","value":25},
+    {"key":"beta_dashboard","enabled":false},
+    {"key":"trial_export","expires_at":"2000-01-01T00:00:00Z"}
+]"#;
+
+#[tokio::test]
+async fn test_get_entitlements_is_cached() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("GET", "/api/users/123/entitlements")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(ENTITLEMENTS)
+        .expect(1)
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::new(server.url()).unwrap();
+
+    // #act
+    let entitlements = client.get_entitlements("123").await.unwrap();
+    let again = client.get_entitlements("123").await.unwrap();
+
+    // #assert
+    assert_eq!(entitlements.len(), 4);
+    assert_eq!(entitlements, again);
+    assert_eq!(entitlements[1].value, Some(serde_json::json!(25)));
+    assert!(entitlements[1].enabled);
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_has_entitlement() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let _mock = server
+        .mock("GET", "/api/users/123/entitlements")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(ENTITLEMENTS)
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::new(server.url()).unwrap();
+
+    // #act & #assert
+    assert!(client
+        .has_entitlement("123", "advanced_reports")
+        .await
+        .unwrap());
+    assert!(client.has_entitlement("123", "seats").await.unwrap());
+    assert!(!client
+        .has_entitlement("123", "beta_dashboard")
+        .await
+        .unwrap());
+    assert!(!client.has_entitlement("123", "trial_export").await.unwrap());
+    assert!(!client.has_entitlement("123", "unknown").await.unwrap());
+}
+
+#[tokio::test]
+async fn test_invalidate_entitlements() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("GET", "/api/users/123/entitlements")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body("[]")
+        .expect(2)
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::new(server.url()).unwrap();
+
+    // #act
+    client.get_entitlements("123").await.unwrap();
+    client.invalidate_entitlements("123").await;
+    client.get_entitlements("123").await.unwrap();
+
+    // #assert
+    mock.assert_async().await;
+}
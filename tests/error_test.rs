@@ -3,7 +3,10 @@ use keyrunes_rust_sdk::KeyrunesError;
 #[test]
 fn test_authentication_error() {
     // #setup
-    let err = KeyrunesError::AuthenticationError("Invalid credentials".to_string());
+    let err = KeyrunesError::AuthenticationError {
+        message: "Invalid credentials".to_string(),
+        code: None,
+    };
 
     // #assert
     assert!(err.to_string().contains("Authentication error"));
@@ -13,7 +16,10 @@ fn test_authentication_error() {
 #[test]
 fn test_authorization_error() {
     // #setup
-    let err = KeyrunesError::AuthorizationError("Access denied".to_string());
+    let err = KeyrunesError::AuthorizationError {
+        message: "Access denied".to_string(),
+        code: None,
+    };
 
     // #assert
     assert!(err.to_string().contains("Authorization error"));
@@ -23,7 +29,10 @@ fn test_authorization_error() {
 #[test]
 fn test_user_not_found_error() {
     // #setup
-    let err = KeyrunesError::UserNotFoundError("User not found".to_string());
+    let err = KeyrunesError::UserNotFoundError {
+        message: "User not found".to_string(),
+        code: None,
+    };
 
     // #assert
     assert!(err.to_string().contains("User not found"));
@@ -32,7 +41,10 @@ fn test_user_not_found_error() {
 #[test]
 fn test_group_not_found_error() {
     // #setup
-    let err = KeyrunesError::GroupNotFoundError("Group not found".to_string());
+    let err = KeyrunesError::GroupNotFoundError {
+        message: "Group not found".to_string(),
+        code: None,
+    };
 
     // #assert
     assert!(err.to_string().contains("Group not found"));
@@ -69,3 +81,31 @@ fn test_from_url_parse_error() {
         _ => panic!("Expected InvalidUrl"),
     }
 }
+
+#[test]
+fn test_api_error_code() {
+    // #setup
+    let err = KeyrunesError::Api {
+        status: 409,
+        code: "username_taken".to_string(),
+        message: "Username already taken".to_string(),
+    };
+
+    // #assert
+    assert_eq!(err.code(), Some("username_taken"));
+    assert!(err.to_string().contains("409"));
+    assert_eq!(KeyrunesError::InvalidToken.code(), None);
+}
+
+#[test]
+fn test_invalid_audience() {
+    // #setup
+    let err = KeyrunesError::InvalidAudience {
+        expected: vec!["orders-api".to_string()],
+        found: vec!["reports-api".to_string()],
+    };
+
+    // #assert
+    assert!(err.to_string().contains("orders-api"));
+    assert!(err.to_string().contains("reports-api"));
+}
@@ -0,0 +1,129 @@
+use keyrunes_rust_sdk::{KeyrunesClient, KeyrunesError};
+use mockito::Server;
+use std::path::PathBuf;
+
+const HELLO_WORLD_SHA256: &str = "b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9";
+
+fn temp_path(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("keyrunes-{}-{}", std::process::id(), name));
+    let _ = std::fs::remove_file(&path);
+    let _ = std::fs::remove_file(path.with_extension("csv.part"));
+    path
+}
+
+#[tokio::test]
+async fn test_download_export() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("GET", "/api/exports/job-1/download")
+        .match_header("authorization", "Bearer test-token-789")
+        .with_status(200)
+        .with_header("x-checksum-sha256", HELLO_WORLD_SHA256)
+        .with_body("hello world")
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::new(server.url()).unwrap();
+    client.set_token("test-token-789").await;
+    let path = temp_path("full.csv");
+
+    // #act
+    let download = client.download_export("job-1", &path).await.unwrap();
+
+    // #assert
+    assert_eq!(download.bytes, 11);
+    assert_eq!(download.sha256, HELLO_WORLD_SHA256);
+    assert!(!download.resumed);
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "hello world");
+    mock.assert_async().await;
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[tokio::test]
+async fn test_download_export_resumes_partial_file() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("GET", "/api/exports/job-2/download")
+        .match_header("range", "bytes=6-")
+        .with_status(206)
+        .with_header("x-checksum-sha256", HELLO_WORLD_SHA256)
+        .with_body("world")
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::new(server.url()).unwrap();
+    client.set_token("test-token-789").await;
+    let path = temp_path("resume.csv");
+    std::fs::write(path.with_extension("csv.part"), "hello ").unwrap();
+
+    // #act
+    let download = client.download_export("job-2", &path).await.unwrap();
+
+    // #assert
+    assert!(download.resumed);
+    assert_eq!(download.bytes, 11);
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "hello world");
+    mock.assert_async().await;
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[tokio::test]
+async fn test_download_export_keeps_partial_file_on_error() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("GET", "/api/exports/job-4/download")
+        .match_header("range", "bytes=6-")
+        .with_status(500)
+        .with_body("Internal error")
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::new(server.url()).unwrap();
+    client.set_token("test-token-789").await;
+    let path = temp_path("transient.csv");
+    let partial = path.with_extension("csv.part");
+    std::fs::write(&partial, "hello ").unwrap();
+
+    // #act
+    let result = client.download_export("job-4", &path).await;
+
+    // #assert
+    assert!(matches!(result, Err(KeyrunesError::HttpError(_))));
+    assert_eq!(std::fs::read_to_string(&partial).unwrap(), "hello ");
+    assert!(!path.exists());
+    mock.assert_async().await;
+    std::fs::remove_file(&partial).unwrap();
+}
+
+#[tokio::test]
+async fn test_download_export_checksum_mismatch() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let _mock = server
+        .mock("GET", "/api/exports/job-3/download")
+        .with_status(200)
+        .with_header("x-checksum-sha256", HELLO_WORLD_SHA256)
+        .with_body("hello w0rld")
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::new(server.url()).unwrap();
+    client.set_token("test-token-789").await;
+    let path = temp_path("corrupt.csv");
+
+    // #act
+    let result = client.download_export("job-3", &path).await;
+
+    // #assert
+    match result {
+        Err(KeyrunesError::ChecksumMismatch { expected, .. }) => {
+            assert_eq!(expected, HELLO_WORLD_SHA256)
+        }
+        other => panic!("Expected ChecksumMismatch, got {:?}", other),
+    }
+    assert!(!path.exists());
+    assert!(!path.with_extension("csv.part").exists());
+}
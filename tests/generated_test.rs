@@ -0,0 +1,168 @@
+use chrono::{TimeZone, Utc};
+use keyrunes_rust_sdk::client::generated::DelegationRequest;
+use keyrunes_rust_sdk::{KeyrunesClient, KeyrunesError, PasswordResetRequest, ResourceRef};
+use mockito::{Matcher, Server};
+
+#[tokio::test]
+async fn test_generated_encodes_path_and_query_parameters() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("GET", "/api/users/user%201/login-history")
+        .match_query(Matcher::AllOf(vec![
+            Matcher::UrlEncoded("since".into(), "2024-01-01T00:00:00Z".into()),
+            Matcher::UrlEncoded("success".into(), "false".into()),
+            Matcher::UrlEncoded("cursor".into(), "a&b".into()),
+        ]))
+        .match_header("authorization", "Bearer test-token-789")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"items":[],"next_cursor":null}"#)
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::new(server.url()).unwrap();
+    client.set_token("test-token-789").await;
+    let since = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+
+    // #act
+    let page = client
+        .generated()
+        .get_login_history("user 1", Some(since), None, Some(false), None, Some("a&b"))
+        .await
+        .unwrap();
+
+    // #assert
+    assert!(page.items.is_empty());
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_generated_sends_generated_request_body() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("POST", "/api/delegations")
+        .match_header("authorization", "Bearer test-token-789")
+        .match_body(Matcher::Json(serde_json::json!({
+            "resource": {"type": "document", "id": "doc-1"},
+            "from_user_id": "u1",
+            "to_user_id": "u2",
+            "permissions": ["read"]
+        })))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{"id":"del-1","resource":{"type":"document","id":"doc-1"},"from_user_id":"u1","to_user_id":"u2","permissions":["read"]}"#,
+        )
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::new(server.url()).unwrap();
+    client.set_token("test-token-789").await;
+    let request = DelegationRequest {
+        resource: ResourceRef::new("document", "doc-1"),
+        from_user_id: "u1".to_string(),
+        to_user_id: "u2".to_string(),
+        permissions: vec!["read".to_string()],
+        expires_at: None,
+    };
+
+    // #act
+    let delegation = client
+        .generated()
+        .create_delegation(&request)
+        .await
+        .unwrap();
+
+    // #assert
+    assert_eq!(delegation.id, "del-1");
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_generated_converts_wire_types() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("GET", "/api/users/123")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{"user_id":123,"username":"alice","email":"alice@example.com","groups":["admins"]}"#,
+        )
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::new(server.url()).unwrap();
+    client.set_token("test-token-789").await;
+
+    // #act
+    let user = client.generated().get_user("123").await.unwrap();
+
+    // #assert
+    assert_eq!(user.id, "123");
+    assert_eq!(user.username, "alice");
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_generated_downloads_bytes_and_maps_errors() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let download_mock = server
+        .mock("GET", "/api/exports/job-1/download")
+        .with_status(200)
+        .with_header("content-type", "application/octet-stream")
+        .with_body("id,username\n1,alice\n")
+        .create_async()
+        .await;
+    let missing_mock = server
+        .mock("GET", "/api/exports/job-2/download")
+        .with_status(500)
+        .with_body("boom")
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::new(server.url()).unwrap();
+    client.set_token("test-token-789").await;
+
+    // #act
+    let data = client.generated().download_export("job-1").await.unwrap();
+    let result = client.generated().download_export("job-2").await;
+
+    // #assert
+    assert_eq!(data, b"id,username\n1,alice\n");
+    assert!(matches!(result, Err(KeyrunesError::HttpError(_))));
+    download_mock.assert_async().await;
+    missing_mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_generated_public_endpoint_sends_no_token() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("POST", "/api/password/reset")
+        .match_header("authorization", Matcher::Missing)
+        .match_body(Matcher::PartialJson(
+            serde_json::json!({"email": "alice@example.com"}),
+        ))
+        .with_status(204)
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::new(server.url()).unwrap();
+    client.set_token("test-token-789").await;
+    let request = PasswordResetRequest {
+        email: "alice@example.com".to_string(),
+        namespace: "public".to_string(),
+    };
+
+    // #act
+    let result = client.generated().request_password_reset(&request).await;
+
+    // #assert
+    assert!(result.is_ok());
+    mock.assert_async().await;
+}
@@ -0,0 +1,92 @@
+use keyrunes_rust_sdk::middleware::group_check::GroupCheckStrategy;
+use keyrunes_rust_sdk::{KeyrunesClient, User};
+use mockito::Server;
+
+fn user() -> User {
+    User {
+        id: "123".to_string(),
+        username: "john".to_string(),
+        email: "john@example.com".to_string(),
+        groups: vec!["editors".to_string()],
+        ..Default::default()
+    }
+}
+
+async fn client_with_group_check(
+    server: &mut Server,
+    group: &str,
+    calls: usize,
+) -> (KeyrunesClient, mockito::Mock) {
+    let mock = server
+        .mock("GET", format!("/api/users/123/groups/{}", group).as_str())
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"has_group":true}"#)
+        .expect(calls)
+        .create_async()
+        .await;
+    let client = KeyrunesClient::new(server.url()).unwrap();
+    client.set_token("token".to_string()).await;
+    (client, mock)
+}
+
+#[tokio::test]
+async fn test_trust_profile_groups_never_calls_keyrunes() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let (client, mock) = client_with_group_check(&mut server, "admins", 0).await;
+
+    // #act
+    let editors = GroupCheckStrategy::TrustProfileGroups
+        .check(&client, &user(), "editors")
+        .await
+        .unwrap();
+    let admins = GroupCheckStrategy::TrustProfileGroups
+        .check(&client, &user(), "admins")
+        .await
+        .unwrap();
+
+    // #assert
+    mock.assert_async().await;
+    assert!(editors);
+    assert!(!admins);
+}
+
+#[tokio::test]
+async fn test_always_verify_calls_keyrunes() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let (client, mock) = client_with_group_check(&mut server, "editors", 1).await;
+
+    // #act
+    let result = GroupCheckStrategy::AlwaysVerify
+        .check(&client, &user(), "editors")
+        .await
+        .unwrap();
+
+    // #assert
+    mock.assert_async().await;
+    assert!(result);
+}
+
+#[tokio::test]
+async fn test_verify_if_missing_only_calls_for_unlisted_groups() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let (client, mock) = client_with_group_check(&mut server, "admins", 1).await;
+
+    // #act
+    let editors = GroupCheckStrategy::VerifyIfMissing
+        .check(&client, &user(), "editors")
+        .await
+        .unwrap();
+    let admins = GroupCheckStrategy::VerifyIfMissing
+        .check(&client, &user(), "admins")
+        .await
+        .unwrap();
+
+    // #assert
+    mock.assert_async().await;
+    assert!(editors);
+    assert!(admins);
+}
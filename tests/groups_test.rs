@@ -0,0 +1,170 @@
+use keyrunes_rust_sdk::groups::{self, KnownGroups, KnownPermissions};
+use keyrunes_rust_sdk::provisioning::ProvisioningSpec;
+use keyrunes_rust_sdk::requirements::RouteRequirements;
+use keyrunes_rust_sdk::{keyrunes_groups, keyrunes_permissions, KeyrunesClient, KeyrunesError};
+use mockito::Server;
+
+keyrunes_groups! {
+    /// Groups of the test application
+    pub enum AppGroup {
+        Admins => "admins",
+        /// Content editors
+        Editors => "editors",
+    }
+}
+
+keyrunes_permissions! {
+    /// Permissions of the test application
+    pub enum AppPermission {
+        PostsRead => "posts:read": "Read posts",
+        PostsWrite => "posts:write": "Write and publish posts",
+    }
+}
+
+struct EditorsOnly;
+
+impl RouteRequirements for EditorsOnly {
+    const GROUPS: &'static [&'static str] = &[AppGroup::Editors.as_str()];
+    const PERMISSIONS: &'static [&'static str] = &[AppPermission::PostsWrite.as_str()];
+}
+
+#[test]
+fn test_groups_convert_to_names() {
+    assert_eq!(AppGroup::ALL, [AppGroup::Admins, AppGroup::Editors]);
+    assert_eq!(AppGroup::Admins.to_string(), "admins");
+    assert_eq!(String::from(AppGroup::Editors), "editors");
+    assert_eq!(EditorsOnly::GROUPS, ["editors"]);
+    assert_eq!(EditorsOnly::PERMISSIONS, ["posts:write"]);
+    assert_eq!(AppGroup::parse("admins"), Some(AppGroup::Admins));
+    assert_eq!("editors".parse::<AppGroup>().unwrap(), AppGroup::Editors);
+    assert!(matches!(
+        "admin".parse::<AppGroup>(),
+        Err(KeyrunesError::GroupNotFoundError { message, .. }) if message == "admin"
+    ));
+}
+
+#[test]
+fn test_permissions_convert_to_keys() {
+    assert_eq!(
+        AppPermission::ALL,
+        [AppPermission::PostsRead, AppPermission::PostsWrite]
+    );
+    assert_eq!(AppPermission::PostsRead.to_string(), "posts:read");
+    assert_eq!(
+        AppPermission::PostsWrite.description(),
+        "Write and publish posts"
+    );
+    assert_eq!(
+        AppPermission::parse("posts:write"),
+        Some(AppPermission::PostsWrite)
+    );
+    assert!(matches!(
+        "posts:delete".parse::<AppPermission>(),
+        Err(KeyrunesError::Other(_))
+    ));
+}
+
+#[test]
+fn test_shorthand_declares_keyrunes_group() {
+    keyrunes_groups! { Support => "support" }
+
+    assert_eq!(KeyrunesGroup::Support.as_str(), "support");
+}
+
+#[test]
+fn test_provisioning_declares_known_groups() {
+    // #setup
+    let spec = ProvisioningSpec::from_json(br#"{"groups": [{"name": "admins"}]}"#).unwrap();
+
+    // #act
+    let spec = spec.with_known_groups::<AppGroup>();
+
+    // #assert
+    let names: Vec<&str> = spec
+        .groups
+        .iter()
+        .map(|group| group.name.as_str())
+        .collect();
+    assert_eq!(names, ["admins", "editors"]);
+}
+
+#[test]
+fn test_provisioning_declares_known_permissions() {
+    // #setup
+    let spec = ProvisioningSpec::from_json(
+        br#"{"permissions": [{"key": "posts:read", "description": "Read all posts"}]}"#,
+    )
+    .unwrap();
+
+    // #act
+    let spec = spec.with_known_permissions::<AppPermission>();
+
+    // #assert
+    let permissions: Vec<(&str, &str)> = spec
+        .permissions
+        .iter()
+        .map(|permission| (permission.key.as_str(), permission.description.as_str()))
+        .collect();
+    assert_eq!(
+        permissions,
+        [
+            ("posts:read", "Read all posts"),
+            ("posts:write", "Write and publish posts")
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_missing_permissions() {
+    // #setup
+    let mut server = Server::new_async().await;
+    server
+        .mock("GET", "/api/admin/permissions")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"[{"key":"posts:read","description":"Read posts"}]"#)
+        .create_async()
+        .await;
+    let client = KeyrunesClient::new(server.url()).unwrap();
+    client.set_token("admin-token").await;
+
+    // #act
+    let missing = groups::missing_permissions::<AppPermission>(&client)
+        .await
+        .unwrap();
+
+    // #assert
+    assert_eq!(missing, [AppPermission::PostsWrite]);
+}
+
+#[tokio::test]
+async fn test_missing_groups_and_has_group() {
+    // #setup
+    let mut server = Server::new_async().await;
+    server
+        .mock("GET", "/api/admin/groups")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"[{"id":"g-1","name":"admins"}]"#)
+        .create_async()
+        .await;
+    let check = server
+        .mock("GET", "/api/users/123/groups/admins")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"has_group":true}"#)
+        .expect(1)
+        .create_async()
+        .await;
+    let client = KeyrunesClient::new(server.url()).unwrap();
+    client.set_token("admin-token").await;
+
+    // #act
+    let missing = groups::missing_groups::<AppGroup>(&client).await.unwrap();
+    let is_admin = client.has_group("123", AppGroup::Admins).await.unwrap();
+
+    // #assert
+    assert_eq!(missing, [AppGroup::Editors]);
+    assert!(is_admin);
+    check.assert_async().await;
+}
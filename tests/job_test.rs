@@ -0,0 +1,142 @@
+use keyrunes_rust_sdk::job::JobState;
+use keyrunes_rust_sdk::{ExportArtifact, KeyrunesClient, KeyrunesError};
+use mockito::Server;
+use std::time::Duration;
+
+#[tokio::test]
+async fn test_start_export_and_await_completion() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let start_mock = server
+        .mock("POST", "/api/exports")
+        .match_header("authorization", "Bearer test-token-789")
+        .match_body(mockito::Matcher::Json(
+            serde_json::json!({ "resource": "users" }),
+        ))
+        .with_status(202)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"job_id":"job-1"}"#)
+        .create_async()
+        .await;
+    let status_mock = server
+        .mock("GET", "/api/jobs/job-1")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"id":"job-1","state":"succeeded","progress":1.0,"result":{"size":42}}"#)
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::new(server.url()).unwrap();
+    client.set_token("test-token-789").await;
+
+    // #act
+    let job = client.start_export("users").await.unwrap();
+    let artifact = job
+        .await_completion(Duration::from_millis(10), Duration::from_secs(5))
+        .await
+        .unwrap();
+
+    // #assert
+    assert_eq!(job.id(), "job-1");
+    assert_eq!(artifact.size, Some(42));
+    start_mock.assert_async().await;
+    status_mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_job_failed() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let _mock = server
+        .mock("GET", "/api/jobs/job-2")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"id":"job-2","state":"failed","error":"disk full"}"#)
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::new(server.url()).unwrap();
+    client.set_token("test-token-789").await;
+    let job = client.job::<ExportArtifact>("job-2");
+
+    // #act
+    let result = job
+        .await_completion(Duration::from_millis(10), Duration::from_secs(5))
+        .await;
+
+    // #assert
+    match result {
+        Err(KeyrunesError::JobFailed { job_id, reason }) => {
+            assert_eq!(job_id, "job-2");
+            assert_eq!(reason, "disk full");
+        }
+        other => panic!("Expected JobFailed, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_job_timeout_and_cancel() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let _status_mock = server
+        .mock("GET", "/api/jobs/job-3")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"id":"job-3","state":"running","progress":0.5}"#)
+        .expect_at_least(1)
+        .create_async()
+        .await;
+    let cancel_mock = server
+        .mock("POST", "/api/jobs/job-3/cancel")
+        .with_status(204)
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::new(server.url()).unwrap();
+    client.set_token("test-token-789").await;
+    let job = client.job::<()>("job-3");
+
+    // #act
+    let status = job.status().await.unwrap();
+    let result = job
+        .await_completion(Duration::from_millis(10), Duration::from_millis(50))
+        .await;
+    job.cancel().await.unwrap();
+
+    // #assert
+    assert_eq!(status.state, JobState::Running);
+    assert!(!status.state.is_terminal());
+    assert!(matches!(result, Err(KeyrunesError::JobTimedOut(id)) if id == "job-3"));
+    cancel_mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_import_users_csv() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("POST", "/api/imports/users")
+        .match_header(
+            "content-type",
+            mockito::Matcher::Regex("multipart/form-data".to_string()),
+        )
+        .match_body(mockito::Matcher::Regex("alice@example.com".to_string()))
+        .with_status(202)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"id":"job-4"}"#)
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::new(server.url()).unwrap();
+    client.set_token("test-token-789").await;
+
+    // #act
+    let job = client
+        .import_users_csv("username,email\nalice,alice@example.com\n")
+        .await
+        .unwrap();
+
+    // #assert
+    assert_eq!(job.id(), "job-4");
+    mock.assert_async().await;
+}
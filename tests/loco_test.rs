@@ -0,0 +1,58 @@
+#![cfg(feature = "loco")]
+
+use keyrunes_rust_sdk::middleware::loco::{get_user_from_token, require_group, KeyrunesState};
+use keyrunes_rust_sdk::KeyrunesClient;
+use mockito::Server;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_concurrent_users_are_checked_with_their_own_token() {
+    // #setup
+    let mut server = Server::new_async().await;
+    for (token, id, has_group) in [("token-john", "1", true), ("token-jane", "2", false)] {
+        server
+            .mock("GET", "/api/me")
+            .match_header("authorization", format!("Bearer {}", token).as_str())
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(format!(
+                r#"{{"id":"{}","username":"{}","email":"user@example.com","groups":[]}}"#,
+                id, token
+            ))
+            .create_async()
+            .await;
+        server
+            .mock("GET", format!("/api/users/{}/groups/editors", id).as_str())
+            .match_header("authorization", format!("Bearer {}", token).as_str())
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(format!(r#"{{"has_group":{}}}"#, has_group))
+            .create_async()
+            .await;
+    }
+    let state = KeyrunesState::new(KeyrunesClient::new(server.url()).unwrap());
+
+    // #act
+    let tasks = (0..20).map(|i| {
+        let state = state.clone();
+        let token = if i % 2 == 0 {
+            "token-john"
+        } else {
+            "token-jane"
+        };
+        tokio::spawn(async move {
+            let user = get_user_from_token(&state.client, token).await.unwrap();
+            let allowed = require_group(&state.client, &user, "editors").await.is_ok();
+            (token, user, allowed)
+        })
+    });
+    let results = futures_util::future::join_all(tasks).await;
+
+    // #assert
+    for result in results {
+        let (token, user, allowed) = result.unwrap();
+        assert_eq!(user.user.username, token);
+        assert_eq!(user.token(), token);
+        assert_eq!(allowed, token == "token-john");
+    }
+    assert!(state.client.current_token().await.is_none());
+}
@@ -0,0 +1,211 @@
+use chrono::Utc;
+use keyrunes_rust_sdk::login_guard::{
+    InMemoryLoginAttemptStore, LoginAttemptStore, LoginGuard, LoginGuardConfig,
+};
+use keyrunes_rust_sdk::{KeyrunesClient, KeyrunesError};
+use mockito::Server;
+use std::time::Duration;
+
+fn config() -> LoginGuardConfig {
+    LoginGuardConfig {
+        max_attempts: 2,
+        base_lockout: Duration::from_secs(10),
+        max_lockout: Duration::from_secs(25),
+        window: Duration::from_secs(60),
+    }
+}
+
+#[tokio::test]
+async fn test_login_guard_allows_before_limit() {
+    // #setup
+    let guard = LoginGuard::in_memory().with_config(config());
+
+    // #act
+    let status = guard.record_failure("john", None).await.unwrap();
+
+    // #assert
+    assert_eq!(status.failures, 1);
+    assert!(status.locked_for.is_none());
+    assert!(guard.check("john", None).await.is_ok());
+}
+
+#[tokio::test]
+async fn test_login_guard_locks_with_exponential_backoff() {
+    // #setup
+    let guard = LoginGuard::in_memory().with_config(config());
+    guard.record_failure("john", None).await.unwrap();
+
+    // #act
+    let second = guard.record_failure("john", None).await.unwrap();
+    let third = guard.record_failure("john", None).await.unwrap();
+    let fourth = guard.record_failure("john", None).await.unwrap();
+
+    // #assert
+    assert_eq!(second.locked_for, Some(Duration::from_secs(10)));
+    assert_eq!(third.locked_for, Some(Duration::from_secs(20)));
+    assert_eq!(fourth.locked_for, Some(Duration::from_secs(25)));
+    assert!(matches!(
+        guard.check("john", None).await.unwrap_err(),
+        KeyrunesError::LoginLocked(_)
+    ));
+}
+
+#[tokio::test]
+async fn test_login_guard_locks_ip_across_identities() {
+    // #setup
+    let guard = LoginGuard::in_memory().with_config(config());
+    guard
+        .record_failure("alice", Some("10.0.0.1"))
+        .await
+        .unwrap();
+    guard.record_failure("bob", Some("10.0.0.1")).await.unwrap();
+
+    // #act
+    let result = guard.check("carol", Some("10.0.0.1")).await;
+
+    // #assert
+    assert!(matches!(result, Err(KeyrunesError::LoginLocked(_))));
+    assert!(guard.check("carol", Some("10.0.0.2")).await.is_ok());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_login_guard_counts_concurrent_failures() {
+    // #setup
+    let guard = LoginGuard::in_memory().with_config(LoginGuardConfig {
+        max_attempts: 100,
+        ..config()
+    });
+
+    // #act
+    let tasks: Vec<_> = (0..50)
+        .map(|_| {
+            let guard = guard.clone();
+            tokio::spawn(async move { guard.record_failure("john", Some("10.0.0.1")).await })
+        })
+        .collect();
+    for task in tasks {
+        task.await.unwrap().unwrap();
+    }
+    let status = guard
+        .record_failure("john", Some("10.0.0.1"))
+        .await
+        .unwrap();
+
+    // #assert
+    assert_eq!(status.failures, 51);
+}
+
+#[tokio::test]
+async fn test_in_memory_store_drops_expired_records() {
+    // #setup
+    let store = InMemoryLoginAttemptStore::new();
+    let config = config();
+    let now = Utc::now();
+    store.increment("identity:old", &config, now).await.unwrap();
+    store.increment("identity:old", &config, now).await.unwrap();
+
+    // #act
+    let later = now + chrono::Duration::seconds(60 + 10 + 1);
+    store
+        .increment("identity:new", &config, later)
+        .await
+        .unwrap();
+
+    // #assert
+    assert!(store.get("identity:old").await.unwrap().is_none());
+    assert_eq!(
+        store.get("identity:new").await.unwrap().unwrap().failures,
+        1
+    );
+}
+
+#[tokio::test]
+async fn test_login_guard_success_resets() {
+    // #setup
+    let guard = LoginGuard::in_memory().with_config(config());
+    guard.record_failure("john", None).await.unwrap();
+    guard.record_failure("john", None).await.unwrap();
+
+    // #act
+    guard.record_success("john").await.unwrap();
+
+    // #assert
+    assert!(guard.check("john", None).await.is_ok());
+}
+
+#[tokio::test]
+async fn test_login_guard_success_keeps_ip_counter() {
+    // #setup
+    let guard = LoginGuard::in_memory().with_config(config());
+    guard
+        .record_failure("victim", Some("10.0.0.1"))
+        .await
+        .unwrap();
+
+    // #act
+    guard.record_success("attacker").await.unwrap();
+    guard
+        .record_failure("victim", Some("10.0.0.1"))
+        .await
+        .unwrap();
+
+    // #assert
+    assert!(matches!(
+        guard.check("other", Some("10.0.0.1")).await,
+        Err(KeyrunesError::LoginLocked(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_login_guard_escalation_survives_the_window() {
+    // #setup
+    let guard = LoginGuard::in_memory().with_config(LoginGuardConfig {
+        max_attempts: 2,
+        base_lockout: Duration::from_millis(200),
+        max_lockout: Duration::from_secs(1),
+        window: Duration::from_millis(300),
+    });
+    guard.record_failure("john", None).await.unwrap();
+    guard.record_failure("john", None).await.unwrap();
+    // Past the window since the first failure, within it since the lockout ended
+    tokio::time::sleep(Duration::from_millis(350)).await;
+
+    // #act
+    let status = guard.record_failure("john", None).await.unwrap();
+
+    // #assert
+    assert_eq!(status.failures, 3);
+    assert_eq!(status.locked_for, Some(Duration::from_millis(400)));
+}
+
+#[tokio::test]
+async fn test_login_guard_reports_lockout() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("POST", "/api/security/events")
+        .match_body(mockito::Matcher::PartialJsonString(
+            r#"{"kind":"brute_force","ip":"10.0.0.1","metadata":{"identity":"john"}}"#.to_string(),
+        ))
+        .with_status(202)
+        .expect(1)
+        .create_async()
+        .await;
+    let client = KeyrunesClient::new(server.url()).unwrap();
+    let guard = LoginGuard::in_memory()
+        .with_config(config())
+        .with_reporting(client);
+
+    // #act
+    guard
+        .record_failure("john", Some("10.0.0.1"))
+        .await
+        .unwrap();
+    guard
+        .record_failure("john", Some("10.0.0.1"))
+        .await
+        .unwrap();
+
+    // #assert
+    mock.assert_async().await;
+}
@@ -0,0 +1,63 @@
+use keyrunes_rust_sdk::claims::AuthLevel;
+use keyrunes_rust_sdk::middleware::messages::{default_message, Locale, RejectionKind};
+use keyrunes_rust_sdk::KeyrunesError;
+
+#[test]
+fn test_locale_from_accept_language() {
+    // #assert
+    assert_eq!(
+        Locale::from_accept_language(Some("en;q=0.5, pt-BR, fr;q=0.8")).as_str(),
+        "pt-BR"
+    );
+    assert_eq!(
+        Locale::from_accept_language(Some("*, de;q=0.3")).as_str(),
+        "de"
+    );
+    assert_eq!(Locale::from_accept_language(Some("*")), Locale::default());
+    assert_eq!(Locale::from_accept_language(None).as_str(), "en");
+    assert_eq!(Locale::new("pt_BR").language(), "pt");
+}
+
+#[test]
+fn test_rejection_kind_from_error() {
+    // #setup
+    let coded = KeyrunesError::Api {
+        status: 403,
+        code: "forbidden".to_string(),
+        message: "Acesso negado".to_string(),
+    };
+
+    // #assert
+    assert_eq!(
+        RejectionKind::from(&KeyrunesError::StepUpRequired(AuthLevel::Mfa)),
+        RejectionKind::StepUpRequired(AuthLevel::Mfa)
+    );
+    assert_eq!(
+        RejectionKind::from(&coded),
+        RejectionKind::Forbidden("Acesso negado".to_string())
+    );
+    assert_eq!(
+        RejectionKind::from(&KeyrunesError::NetworkError("down".to_string())).status(),
+        500
+    );
+}
+
+#[test]
+fn test_default_message() {
+    // #setup
+    let locale = Locale::default();
+
+    // #assert
+    assert_eq!(
+        default_message(RejectionKind::MissingToken, &locale),
+        "Authentication token missing"
+    );
+    assert_eq!(
+        default_message(RejectionKind::TooManyRequests(30), &locale),
+        "Rate limit exceeded: retry after 30 seconds"
+    );
+    assert_eq!(
+        default_message(RejectionKind::Forbidden("No access".to_string()), &locale),
+        "No access"
+    );
+}
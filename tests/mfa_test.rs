@@ -0,0 +1,174 @@
+use keyrunes_rust_sdk::{KeyrunesClient, PushChallengeStatus};
+use mockito::Server;
+use std::time::Duration;
+
+#[tokio::test]
+async fn test_start_push_challenge() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("POST", "/api/mfa/push")
+        .match_body(mockito::Matcher::Json(
+            serde_json::json!({"user_id": "123"}),
+        ))
+        .with_status(201)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"challenge_id":"push-1"}"#)
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::new(server.url()).unwrap();
+
+    // #act
+    let challenge = client.start_push_challenge("123").await.unwrap();
+
+    // #assert
+    assert_eq!(challenge.challenge_id, "push-1");
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_await_push_approval_approved() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let poll_mock = server
+        .mock("GET", "/api/mfa/push/push-1")
+        .match_query(mockito::Matcher::Any)
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{"challenge_id":"push-1","status":"approved","token":{"token":"push-token"}}"#,
+        )
+        .create_async()
+        .await;
+    let me_mock = server
+        .mock("GET", "/api/me")
+        .match_header("authorization", "Bearer push-token")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"user_id":123,"username":"john","email":"john@example.com"}"#)
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::new(server.url()).unwrap();
+
+    // #act
+    let state = client
+        .await_push_approval("push-1", Duration::from_secs(5))
+        .await
+        .unwrap();
+    let user = client.get_current_user().await;
+
+    // #assert
+    assert_eq!(state.status, PushChallengeStatus::Approved);
+    assert!(user.is_ok());
+    poll_mock.assert_async().await;
+    me_mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_await_push_approval_timeout() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("GET", "/api/mfa/push/push-1")
+        .match_query(mockito::Matcher::Any)
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"challenge_id":"push-1","status":"pending"}"#)
+        .expect_at_least(1)
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::new(server.url()).unwrap();
+
+    // #act
+    let state = client
+        .await_push_approval("push-1", Duration::from_millis(200))
+        .await
+        .unwrap();
+
+    // #assert
+    assert_eq!(state.status, PushChallengeStatus::Pending);
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_generate_backup_codes() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("POST", "/api/mfa/backup-codes")
+        .match_header("authorization", "Bearer test-token-789")
+        .with_status(201)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"codes":["AAAA-1111","BBBB-2222"]}"#)
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::new(server.url()).unwrap();
+    client.set_token("test-token-789").await;
+
+    // #act
+    let backup = client.generate_backup_codes().await.unwrap();
+
+    // #assert
+    assert_eq!(backup.codes, vec!["AAAA-1111", "BBBB-2222"]);
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_list_backup_codes_status() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("GET", "/api/mfa/backup-codes")
+        .match_header("authorization", "Bearer test-token-789")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"total":10,"remaining":7}"#)
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::new(server.url()).unwrap();
+    client.set_token("test-token-789").await;
+
+    // #act
+    let status = client.list_backup_codes_status().await.unwrap();
+
+    // #assert
+    assert_eq!(status.total, 10);
+    assert_eq!(status.remaining, 7);
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_login_with_backup_code() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("POST", "/api/login/backup-code")
+        .match_body(mockito::Matcher::Json(serde_json::json!({
+            "identity": "john",
+            "password": "password123",
+            "namespace": "public",
+            "backup_code": "AAAA-1111"
+        })))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"token":"recovery-token"}"#)
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::new(server.url()).unwrap();
+
+    // #act
+    let token = client
+        .login_with_backup_code("john", "password123", "AAAA-1111")
+        .await
+        .unwrap();
+
+    // #assert
+    assert_eq!(token.token, "recovery-token");
+    mock.assert_async().await;
+}
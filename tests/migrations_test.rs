@@ -0,0 +1,162 @@
+use keyrunes_rust_sdk::migrations::{ImportOptions, Migration, MigrationSource, PasswordHash};
+use keyrunes_rust_sdk::{KeyrunesClient, KeyrunesError};
+use mockito::{Matcher, Server};
+use serde_json::json;
+use std::time::Duration;
+
+const AUTH0_EXPORT: &str = r#"{"user_id":"auth0|1","email":"john@example.com","email_verified":true,"given_name":"John","app_metadata":{"plan":"pro"}}
+not json
+{"_id":{"$oid":"60a1"},"email":"jane@example.com","username":"jane","passwordHash":"$2b$10$abcdefghijklmnopqrstuv"}
+{"user_id":"auth0|3"}
+"#;
+
+fn keycloak_realm() -> serde_json::Value {
+    json!({
+        "realm": "acme",
+        "groups": [
+            {"name": "engineering", "path": "/engineering", "subGroups": [
+                {"name": "backend", "path": "/engineering/backend", "subGroups": []}
+            ]}
+        ],
+        "users": [
+            {
+                "id": "kc-1",
+                "username": "john",
+                "email": "john@example.com",
+                "emailVerified": true,
+                "firstName": "John",
+                "lastName": "Doe",
+                "enabled": false,
+                "groups": ["/engineering/backend", "/sales"],
+                "credentials": [{
+                    "type": "password",
+                    "secretData": "{\"value\":\"aGFzaA==\",\"salt\":\"c2FsdA==\"}",
+                    "credentialData": "{\"hashIterations\":27500,\"algorithm\":\"pbkdf2-sha256\"}"
+                }]
+            },
+            {"id": "kc-2", "enabled": true}
+        ]
+    })
+}
+
+#[test]
+fn test_auth0_export() {
+    // #act
+    let migration = Migration::from_auth0_export(AUTH0_EXPORT.as_bytes());
+
+    // #assert
+    assert_eq!(migration.source(), MigrationSource::Auth0);
+    let users = migration.users();
+    assert_eq!(users.len(), 2);
+    assert_eq!(users[0].external_id, "auth0|1");
+    assert_eq!(users[0].username, "john@example.com");
+    assert!(users[0].email_verified);
+    assert_eq!(users[0].metadata["app_metadata"]["plan"], "pro");
+    assert_eq!(users[1].external_id, "60a1");
+    assert_eq!(users[1].username, "jane");
+    assert_eq!(users[1].password_hash.as_ref().unwrap().algorithm, "bcrypt");
+    let errors = migration.errors();
+    assert_eq!(errors.len(), 2);
+    assert_eq!(errors[0].record.as_deref(), Some("line 2"));
+    assert_eq!(errors[1].to_string(), "line 4: missing username and email");
+}
+
+#[test]
+fn test_keycloak_realm() {
+    // #act
+    let migration =
+        Migration::from_keycloak_realm(keycloak_realm().to_string().as_bytes()).unwrap();
+
+    // #assert
+    assert_eq!(
+        migration.groups(),
+        ["engineering", "engineering/backend", "sales"]
+    );
+    let user = &migration.users()[0];
+    assert_eq!(user.external_id, "kc-1");
+    assert_eq!(user.family_name.as_deref(), Some("Doe"));
+    assert!(!user.enabled);
+    assert_eq!(user.groups, ["engineering/backend", "sales"]);
+    assert_eq!(
+        user.password_hash,
+        Some(PasswordHash {
+            algorithm: "pbkdf2-sha256".to_string(),
+            hash: "aGFzaA==".to_string(),
+            salt: Some("c2FsdA==".to_string()),
+            iterations: Some(27500),
+        })
+    );
+    assert_eq!(migration.errors()[0].record.as_deref(), Some("kc-2"));
+}
+
+#[test]
+fn test_keycloak_realm_invalid() {
+    // #act
+    let result = Migration::from_keycloak_realm(b"[]");
+
+    // #assert
+    assert!(matches!(result, Err(KeyrunesError::SerializationError(_))));
+}
+
+#[tokio::test]
+async fn test_dry_run_import() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let import_mock = server
+        .mock("POST", "/api/imports/bulk")
+        .match_header("authorization", "Bearer admin-token")
+        .match_body(Matcher::PartialJson(json!({
+            "source": "keycloak",
+            "dry_run": true,
+            "groups": ["engineering", "engineering/backend", "sales"],
+            "users": [{
+                "external_id": "kc-1",
+                "username": "john",
+                "password_hash": {"algorithm": "pbkdf2-sha256", "iterations": 27500},
+            }],
+        })))
+        .with_status(202)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"id":"job-1"}"#)
+        .expect(1)
+        .create_async()
+        .await;
+    let job_mock = server
+        .mock("GET", "/api/jobs/job-1")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            json!({
+                "id": "job-1",
+                "state": "succeeded",
+                "result": {"created": 1, "updated": 0, "failed": 0, "errors": ["group sales: name is reserved"]},
+            })
+            .to_string(),
+        )
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::new(server.url()).unwrap();
+    client.set_token("admin-token").await;
+    let migration =
+        Migration::from_keycloak_realm(keycloak_realm().to_string().as_bytes()).unwrap();
+    let options = ImportOptions::new()
+        .dry_run()
+        .poll_interval(Duration::from_millis(10));
+
+    // #act
+    let report = migration.import(&client, &options).await.unwrap();
+
+    // #assert
+    assert!(report.dry_run);
+    assert_eq!(report.created, 1);
+    assert_eq!(report.with_password, 1);
+    assert_eq!(report.errors.len(), 2);
+    assert_eq!(report.errors[0].record.as_deref(), Some("kc-2"));
+    assert_eq!(
+        report.errors[1].to_string(),
+        "group sales: name is reserved"
+    );
+    import_mock.assert_async().await;
+    job_mock.assert_async().await;
+}
@@ -8,8 +8,7 @@ fn test_user_serialization() {
         username: "john".to_string(),
         email: "john@example.com".to_string(),
         groups: vec!["users".to_string(), "admins".to_string()],
-        created_at: None,
-        updated_at: None,
+        ..Default::default()
     };
 
     // #act
@@ -41,6 +40,83 @@ fn test_user_deserialization() {
     assert_eq!(user.groups.len(), 2);
 }
 
+#[test]
+fn test_user_numeric_id() {
+    // #setup
+    let json = r#"{"user_id": 42, "username": "john", "email": "john@example.com"}"#;
+
+    // #act
+    let user: User = serde_json::from_str(json).unwrap();
+    let roundtrip: User = serde_json::from_str(&serde_json::to_string(&user).unwrap()).unwrap();
+
+    // #assert
+    assert_eq!(user.id, "42");
+    assert_eq!(user.numeric_id, Some(42));
+    assert_eq!(roundtrip.numeric_id, Some(42));
+}
+
+#[test]
+fn test_user_id_as_string() {
+    // #setup
+    let json = r#"{"user_id": "123", "username": "john", "email": "john@example.com"}"#;
+
+    // #act
+    let user: User = serde_json::from_str(json).unwrap();
+
+    // #assert
+    assert_eq!(user.id, "123");
+    assert_eq!(user.numeric_id, Some(123));
+}
+
+#[test]
+fn test_token_expires_in_as_string() {
+    // #setup
+    let current = r#"{"token": "abc", "expires_in": "3600"}"#;
+    let legacy = r#"{"access_token": "abc", "expires_in": 3600}"#;
+    let null = r#"{"token": "abc", "expires_in": null}"#;
+
+    // #act
+    let current: Token = serde_json::from_str(current).unwrap();
+    let legacy: Token = serde_json::from_str(legacy).unwrap();
+    let null: Token = serde_json::from_str(null).unwrap();
+
+    // #assert
+    assert_eq!(current.expires_in, Some(3600));
+    assert_eq!(legacy.expires_in, Some(3600));
+    assert_eq!(null.expires_in, None);
+}
+
+#[test]
+fn test_token_expires_in_not_a_number() {
+    // #act
+    let result = serde_json::from_str::<Token>(r#"{"token": "abc", "expires_in": "soon"}"#);
+
+    // #assert
+    assert!(result.is_err());
+}
+
+#[cfg(feature = "uuid")]
+#[test]
+fn test_user_external_id() {
+    // #setup
+    let json = r#"{
+        "external_id": "6f1c2a9e-3b7d-4c1e-9f0a-2d5b8e7c4a10",
+        "username": "john",
+        "email": "john@example.com"
+    }"#;
+
+    // #act
+    let user: User = serde_json::from_str(json).unwrap();
+
+    // #assert
+    assert_eq!(user.id, "6f1c2a9e-3b7d-4c1e-9f0a-2d5b8e7c4a10");
+    assert_eq!(
+        user.external_id,
+        Some(uuid::Uuid::parse_str("6f1c2a9e-3b7d-4c1e-9f0a-2d5b8e7c4a10").unwrap())
+    );
+    assert_eq!(user.numeric_id, None);
+}
+
 #[test]
 fn test_token_serialization() {
     // #setup
@@ -50,6 +126,7 @@ fn test_token_serialization() {
         expires_in: None,
         refresh_token: None,
         expires_at: None,
+        id_token: None,
     };
 
     // #act
@@ -145,3 +222,20 @@ fn test_group_check() {
     // #assert
     assert!(json.contains("true"));
 }
+
+#[test]
+fn test_security_event_kind_serialization() {
+    // #setup
+    let standard = SecurityEventKind::CredentialStuffing;
+    let custom = SecurityEventKind::Other("geo_velocity".to_string());
+
+    // #act
+    let standard_json = serde_json::to_string(&standard).unwrap();
+    let custom_json = serde_json::to_string(&custom).unwrap();
+    let parsed: SecurityEventKind = serde_json::from_str(r#""geo_velocity""#).unwrap();
+
+    // #assert
+    assert_eq!(standard_json, r#""credential_stuffing""#);
+    assert_eq!(custom_json, r#""geo_velocity""#);
+    assert_eq!(parsed, custom);
+}
@@ -0,0 +1,176 @@
+#![cfg(feature = "mtls")]
+
+use jsonwebtoken::{EncodingKey, Header};
+use keyrunes_rust_sdk::mtls::{certificate_thumbprint, CertificateBinding, CertificateFormat};
+use keyrunes_rust_sdk::{AuthService, KeyrunesClient, KeyrunesError};
+use mockito::Server;
+use serde_json::json;
+
+/// Stand-in for a DER certificate: thumbprints hash the bytes as they are
+const CERTIFICATE: &[u8] = b"client certificate";
+
+/// `CERTIFICATE` in PEM, URL-encoded as nginx forwards it
+const ESCAPED_PEM: &str =
+    "-----BEGIN%20CERTIFICATE-----%0AY2xpZW50IGNlcnRpZmljYXRl%0A-----END%20CERTIFICATE-----%0A";
+
+fn token(claims: serde_json::Value) -> String {
+    jsonwebtoken::encode(
+        &Header::default(),
+        &claims,
+        &EncodingKey::from_secret(b"secret"),
+    )
+    .unwrap()
+}
+
+fn bound_token() -> String {
+    token(json!({"sub": "123", "cnf": {"x5t#S256": certificate_thumbprint(CERTIFICATE)}}))
+}
+
+#[test]
+fn test_thumbprint_formats() {
+    // #setup
+    let pem = CertificateBinding::new("X-Client-Cert", CertificateFormat::Pem);
+    let fingerprint =
+        CertificateBinding::new("X-Client-Fingerprint", CertificateFormat::Thumbprint);
+    let expected = certificate_thumbprint(CERTIFICATE);
+    let hex: String = {
+        use sha2::{Digest, Sha256};
+        Sha256::digest(CERTIFICATE)
+            .iter()
+            .map(|byte| format!("{:02X}", byte))
+            .collect::<Vec<_>>()
+            .join(":")
+    };
+
+    // #act
+    let from_pem = pem.thumbprint(ESCAPED_PEM);
+    let from_hex = fingerprint.thumbprint(&hex);
+    let from_base64 = fingerprint.thumbprint(&expected);
+
+    // #assert
+    assert_eq!(from_pem.as_deref(), Some(expected.as_str()));
+    assert_eq!(from_hex.as_deref(), Some(expected.as_str()));
+    assert_eq!(from_base64.as_deref(), Some(expected.as_str()));
+    assert_eq!(fingerprint.thumbprint("not-a-fingerprint"), None);
+}
+
+#[test]
+fn test_verify_bound_token() {
+    // #setup
+    let binding = CertificateBinding::new("X-Client-Cert", CertificateFormat::Pem);
+    let other = "-----BEGIN%20CERTIFICATE-----%0Ab3RoZXI%3D%0A-----END%20CERTIFICATE-----%0A";
+
+    // #act
+    let matching = binding.verify(&bound_token(), Some(ESCAPED_PEM));
+    let mismatched = binding.verify(&bound_token(), Some(other));
+    let missing = binding.verify(&bound_token(), None);
+
+    // #assert
+    assert!(matching.is_ok());
+    assert!(matches!(
+        mismatched,
+        Err(KeyrunesError::AuthenticationError { .. })
+    ));
+    assert!(matches!(
+        missing,
+        Err(KeyrunesError::AuthenticationError { .. })
+    ));
+}
+
+#[test]
+fn test_verify_unbound_token() {
+    // #setup
+    let unbound = token(json!({"sub": "123"}));
+    let binding = CertificateBinding::new("X-Client-Cert", CertificateFormat::Pem);
+
+    // #act
+    let accepted = binding.verify(&unbound, None);
+    let required = binding
+        .require_bound_tokens()
+        .verify(&unbound, Some(ESCAPED_PEM));
+
+    // #assert
+    assert!(accepted.is_ok());
+    assert!(matches!(
+        required,
+        Err(KeyrunesError::AuthenticationError { .. })
+    ));
+}
+
+#[tokio::test]
+async fn test_authenticate_request_checks_certificate() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let me = server
+        .mock("GET", "/api/me")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"id":"123","username":"john","email":"john@example.com","groups":[]}"#)
+        .expect(1)
+        .create_async()
+        .await;
+    let auth =
+        AuthService::new(KeyrunesClient::new(server.url()).unwrap()).with_certificate_binding(
+            CertificateBinding::new("X-Client-Cert", CertificateFormat::Pem),
+        );
+    let authorization = format!("Bearer {}", bound_token());
+
+    // #act
+    let rejected = auth.authenticate_header(Some(&authorization)).await;
+    let user = auth
+        .authenticate_request(Some(&authorization), Some(ESCAPED_PEM))
+        .await
+        .unwrap();
+
+    // #assert
+    me.assert_async().await;
+    assert_eq!(rejected.unwrap_err().status(), 401);
+    assert_eq!(user.id, "123");
+    assert_eq!(auth.certificate_header(), Some("X-Client-Cert"));
+}
+
+#[cfg(feature = "axum")]
+#[tokio::test]
+async fn test_axum_middleware_reads_certificate_header() {
+    use axum::{body::Body, http::Request, http::StatusCode, middleware, routing::get, Router};
+    use keyrunes_rust_sdk::middleware::axum::{require_auth, KeyrunesState};
+    use tower::ServiceExt;
+
+    // #setup
+    let mut server = Server::new_async().await;
+    let _me = server
+        .mock("GET", "/api/me")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"id":"123","username":"john","email":"john@example.com","groups":[]}"#)
+        .create_async()
+        .await;
+    let state =
+        KeyrunesState::new(KeyrunesClient::new(server.url()).unwrap()).with_certificate_binding(
+            CertificateBinding::new("X-Client-Cert", CertificateFormat::Pem),
+        );
+    let app = Router::new()
+        .route("/orders", get(|| async { "ok" }))
+        .layer(middleware::from_fn_with_state(state.clone(), require_auth))
+        .with_state(state);
+    let request = |certificate: Option<&str>| {
+        let mut request =
+            Request::get("/orders").header("authorization", format!("Bearer {}", bound_token()));
+        if let Some(certificate) = certificate {
+            request = request.header("x-client-cert", certificate);
+        }
+        request.body(Body::empty()).unwrap()
+    };
+
+    // #act
+    let accepted = app
+        .clone()
+        .oneshot(request(Some(ESCAPED_PEM)))
+        .await
+        .unwrap();
+    let rejected = app.oneshot(request(None)).await.unwrap();
+
+    // #assert
+    assert_eq!(accepted.status(), StatusCode::OK);
+    assert_eq!(rejected.status(), StatusCode::UNAUTHORIZED);
+}
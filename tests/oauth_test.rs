@@ -0,0 +1,102 @@
+#![cfg(feature = "oauth")]
+
+use keyrunes_rust_sdk::oauth::{
+    AuthorizationState, CookieStateStore, InMemoryStateStore, StateStore,
+};
+use keyrunes_rust_sdk::pkce;
+use std::time::Duration;
+
+const SECRET: &[u8] = b"0123456789abcdef0123456789abcdef";
+
+/// `Cookie` header sending back the cookie of a `Set-Cookie` value
+fn cookie_header(set_cookie: &str) -> String {
+    set_cookie.split(';').next().unwrap().to_string()
+}
+
+#[test]
+fn test_authorization_state_values() {
+    // #act
+    let request = AuthorizationState::new("https://app.example.com/callback");
+
+    // #assert
+    assert_eq!(request.redirect_uri, "https://app.example.com/callback");
+    assert!(request.nonce.is_some());
+    assert_ne!(Some(&request.state), request.nonce.as_ref());
+    assert!(pkce::verify_challenge(
+        &request.code_verifier,
+        &request.code_challenge()
+    ));
+}
+
+#[tokio::test]
+async fn test_in_memory_store_takes_requests_once() {
+    // #setup
+    let store = InMemoryStateStore::new();
+    let request = AuthorizationState::new("https://app.example.com/callback");
+    store.save(&request, Duration::from_secs(60)).await.unwrap();
+
+    // #act
+    let first = store.take(&request.state).await.unwrap();
+    let second = store.take(&request.state).await.unwrap();
+    let unknown = store.take("forged").await.unwrap();
+
+    // #assert
+    assert_eq!(first, Some(request));
+    assert_eq!(second, None);
+    assert_eq!(unknown, None);
+}
+
+#[tokio::test]
+async fn test_in_memory_store_expires_requests() {
+    // #setup
+    let store = InMemoryStateStore::new();
+    let request = AuthorizationState::new("https://app.example.com/callback");
+    store
+        .save(&request, Duration::from_millis(10))
+        .await
+        .unwrap();
+
+    // #act
+    tokio::time::sleep(Duration::from_millis(30)).await;
+    let taken = store.take(&request.state).await.unwrap();
+
+    // #assert
+    assert_eq!(taken, None);
+}
+
+#[test]
+fn test_cookie_store_round_trip() {
+    // #setup
+    let store = CookieStateStore::new(SECRET).unwrap();
+    let request = AuthorizationState::new("https://app.example.com/callback");
+
+    // #act
+    let set_cookie = store.cookie(&request, Duration::from_secs(600)).unwrap();
+    let header = cookie_header(&set_cookie);
+    let taken = store.take(Some(&header), &request.state);
+
+    // #assert
+    assert!(set_cookie.starts_with("keyrunes_oauth_state="));
+    assert!(set_cookie.contains("HttpOnly"));
+    assert!(set_cookie.contains("Max-Age=600"));
+    assert_eq!(taken, Some(request));
+    assert!(store.clear_cookie().contains("Max-Age=0"));
+}
+
+#[test]
+fn test_cookie_store_rejects_forged_or_mismatched_cookies() {
+    // #setup
+    let store = CookieStateStore::new(SECRET).unwrap();
+    let other = CookieStateStore::new(b"another secret of at least 32 bytes").unwrap();
+    let request = AuthorizationState::new("https://app.example.com/callback");
+    let header = cookie_header(&store.cookie(&request, Duration::from_secs(600)).unwrap());
+    let forged = cookie_header(&other.cookie(&request, Duration::from_secs(600)).unwrap());
+    let expired = cookie_header(&store.cookie(&request, Duration::ZERO).unwrap());
+
+    // #act & #assert
+    assert_eq!(store.take(Some(&header), "another-state"), None);
+    assert_eq!(store.take(Some(&forged), &request.state), None);
+    assert_eq!(store.take(Some(&expired), &request.state), None);
+    assert_eq!(store.take(None, &request.state), None);
+    assert!(CookieStateStore::new(b"short").is_err());
+}
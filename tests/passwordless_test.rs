@@ -0,0 +1,246 @@
+use keyrunes_rust_sdk::{KeyrunesClient, KeyrunesError, OtpChannel};
+use mockito::Server;
+
+#[tokio::test]
+async fn test_request_magic_link() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("POST", "/api/passwordless/magic-link")
+        .match_body(mockito::Matcher::Json(serde_json::json!({
+            "email": "john@example.com",
+            "redirect_uri": "https://app.example.com/callback",
+            "namespace": "public"
+        })))
+        .with_status(202)
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::new(server.url()).unwrap();
+
+    // #act
+    let result = client
+        .request_magic_link("john@example.com", "https://app.example.com/callback")
+        .await;
+
+    // #assert
+    assert!(result.is_ok());
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_complete_magic_link() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("POST", "/api/passwordless/magic-link/verify")
+        .match_body(mockito::Matcher::Json(
+            serde_json::json!({"token": "link-token"}),
+        ))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"token":"session-token"}"#)
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::new(server.url()).unwrap();
+
+    // #act
+    let token = client.complete_magic_link("link-token").await.unwrap();
+
+    // #assert
+    assert_eq!(token.token, "session-token");
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_complete_magic_link_expired() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("POST", "/api/passwordless/magic-link/verify")
+        .with_status(401)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"message":"Link expired"}"#)
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::new(server.url()).unwrap();
+
+    // #act
+    let result = client.complete_magic_link("link-token").await;
+
+    // #assert
+    assert!(matches!(
+        result.unwrap_err(),
+        KeyrunesError::AuthenticationError { .. }
+    ));
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_request_otp() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("POST", "/api/passwordless/otp")
+        .match_body(mockito::Matcher::PartialJsonString(
+            r#"{"identity":"john@example.com","channel":"email"}"#.to_string(),
+        ))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"channel":"email","expires_in":300,"resend_after":60}"#)
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::new(server.url()).unwrap();
+
+    // #act
+    let delivery = client
+        .request_otp("john@example.com", OtpChannel::Email)
+        .await
+        .unwrap();
+
+    // #assert
+    assert_eq!(delivery.channel, OtpChannel::Email);
+    assert_eq!(delivery.resend_after, Some(60));
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_request_otp_throttled() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("POST", "/api/passwordless/otp")
+        .with_status(429)
+        .with_header("retry-after", "42")
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::new(server.url()).unwrap();
+
+    // #act
+    let result = client.request_otp("+5511999999999", OtpChannel::Sms).await;
+
+    // #assert
+    assert!(matches!(result, Err(KeyrunesError::OtpThrottled(42))));
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_verify_otp_success() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("POST", "/api/passwordless/otp/verify")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"token":"otp-token"}"#)
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::new(server.url()).unwrap();
+
+    // #act
+    let token = client.verify_otp("john@example.com", "123456").await;
+
+    // #assert
+    assert_eq!(token.unwrap().token, "otp-token");
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_verify_otp_errors() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let expired_mock = server
+        .mock("POST", "/api/passwordless/otp/verify")
+        .match_body(mockito::Matcher::PartialJsonString(
+            r#"{"code":"111111"}"#.to_string(),
+        ))
+        .with_status(400)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"code":"otp_expired","message":"Code expired"}"#)
+        .create_async()
+        .await;
+    let invalid_mock = server
+        .mock("POST", "/api/passwordless/otp/verify")
+        .match_body(mockito::Matcher::PartialJsonString(
+            r#"{"code":"222222"}"#.to_string(),
+        ))
+        .with_status(400)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"code":"otp_invalid","attempts_remaining":2}"#)
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::new(server.url()).unwrap();
+
+    // #act
+    let expired = client.verify_otp("john@example.com", "111111").await;
+    let invalid = client.verify_otp("john@example.com", "222222").await;
+
+    // #assert
+    assert!(matches!(expired, Err(KeyrunesError::OtpExpired)));
+    assert!(matches!(
+        invalid,
+        Err(KeyrunesError::InvalidOtp {
+            attempts_remaining: Some(2)
+        })
+    ));
+    expired_mock.assert_async().await;
+    invalid_mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_passwordless_uses_session_namespace() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let login_mock = server
+        .mock("POST", "/api/login")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"token":"test-token-789"}"#)
+        .create_async()
+        .await;
+    let otp_mock = server
+        .mock("POST", "/api/passwordless/otp")
+        .match_body(mockito::Matcher::PartialJson(
+            serde_json::json!({"namespace": "tenant-a"}),
+        ))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"channel":"email","expires_in":300}"#)
+        .create_async()
+        .await;
+    let link_mock = server
+        .mock("POST", "/api/passwordless/magic-link")
+        .match_body(mockito::Matcher::PartialJson(
+            serde_json::json!({"namespace": "tenant-a"}),
+        ))
+        .with_status(202)
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::new(server.url()).unwrap();
+    client
+        .login("john@example.com", "password123", Some("tenant-a"))
+        .await
+        .unwrap();
+
+    // #act
+    let delivery = client
+        .request_otp("john@example.com", OtpChannel::Email)
+        .await;
+    let link = client
+        .request_magic_link("john@example.com", "https://app.example.com/callback")
+        .await;
+
+    // #assert
+    assert!(delivery.is_ok());
+    assert!(link.is_ok());
+    login_mock.assert_async().await;
+    otp_mock.assert_async().await;
+    link_mock.assert_async().await;
+}
@@ -0,0 +1,67 @@
+use keyrunes_rust_sdk::permissions::PermissionSet;
+use keyrunes_rust_sdk::KeyrunesClient;
+use mockito::Server;
+
+#[test]
+fn test_contains_is_exact() {
+    // #setup
+    let permissions = PermissionSet::new(["documents:*", "billing:read"]);
+
+    // #assert
+    assert!(permissions.contains("billing:read"));
+    assert!(permissions.contains("documents:*"));
+    assert!(!permissions.contains("documents:read"));
+}
+
+#[test]
+fn test_implies_wildcards() {
+    // #setup
+    let permissions = PermissionSet::new(["documents:*", "projects:*:read", "billing:read"]);
+
+    // #assert
+    assert!(permissions.implies("documents:read"));
+    assert!(permissions.implies("documents:read:own"));
+    assert!(permissions.implies("projects:42:read"));
+    assert!(!permissions.implies("projects:42:write"));
+    assert!(!permissions.implies("projects:42"));
+    assert!(!permissions.implies("billing:read:all"));
+    assert!(!permissions.implies("documents"));
+    assert!(PermissionSet::new(["*"]).implies("anything:at:all"));
+}
+
+#[test]
+fn test_implies_all_and_any() {
+    // #setup
+    let permissions = PermissionSet::new(["documents:read", "documents:write"]);
+
+    // #assert
+    assert!(permissions.implies_all(["documents:read", "documents:write"]));
+    assert!(!permissions.implies_all(["documents:read", "documents:delete"]));
+    assert!(permissions.implies_any(["documents:delete", "documents:write"]));
+    assert!(!PermissionSet::default().implies_any(["documents:read"]));
+}
+
+#[tokio::test]
+async fn test_compile_policy() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("GET", "/api/users/123/permissions")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"permissions":["documents:*","billing:read"]}"#)
+        .expect(1)
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::new(server.url()).unwrap();
+
+    // #act
+    let permissions = client.compile_policy("123").await.unwrap();
+
+    // #assert
+    assert_eq!(permissions.len(), 2);
+    assert!(permissions.implies("documents:share"));
+    assert!(!permissions.implies("billing:write"));
+    mock.assert_async().await;
+}
@@ -0,0 +1,60 @@
+#![cfg(feature = "oauth")]
+
+use keyrunes_rust_sdk::pkce::{self, Pkce};
+
+#[test]
+fn test_challenge_matches_rfc_7636_example() {
+    // #setup
+    let verifier = "dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk";
+
+    // #act
+    let pkce = Pkce::from_verifier(verifier).unwrap();
+
+    // #assert
+    assert_eq!(
+        pkce.challenge,
+        "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM"
+    );
+    assert_eq!(pkce.method(), "S256");
+    assert!(pkce::verify_challenge(verifier, &pkce.challenge));
+}
+
+#[test]
+fn test_generate_produces_distinct_valid_verifiers() {
+    // #act
+    let first = Pkce::generate();
+    let second = Pkce::generate();
+
+    // #assert
+    assert_eq!(first.verifier.len(), 43);
+    assert_ne!(first.verifier, second.verifier);
+    assert!(first.verify(&pkce::challenge(&first.verifier)));
+    assert!(!first.verify(&second.challenge));
+}
+
+#[test]
+fn test_rejects_invalid_verifiers() {
+    // #act & #assert
+    assert!(Pkce::from_verifier("too-short").is_err());
+    assert!(Pkce::from_verifier("a".repeat(129)).is_err());
+    assert!(Pkce::from_verifier(format!("{}!", "a".repeat(43))).is_err());
+    assert!(!pkce::verify_challenge(
+        "too-short",
+        &pkce::challenge("too-short")
+    ));
+}
+
+#[test]
+fn test_state_and_nonce() {
+    // #setup
+    let state = pkce::generate_state();
+    let nonce = pkce::generate_nonce();
+
+    // #act & #assert
+    assert_ne!(state, nonce);
+    assert!(pkce::verify_state(&state, &state));
+    assert!(!pkce::verify_state(&state, &nonce));
+    assert!(!pkce::verify_state("", ""));
+    assert!(pkce::verify_nonce(&nonce, &nonce));
+    assert!(!pkce::verify_nonce(&nonce, &state[..10]));
+}
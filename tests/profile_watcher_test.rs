@@ -0,0 +1,143 @@
+use keyrunes_rust_sdk::profile_watcher::ProfileWatcher;
+use keyrunes_rust_sdk::KeyrunesClient;
+use mockito::{Matcher, Server};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+#[tokio::test]
+async fn test_poll_reports_group_changes() {
+    // #setup
+    let mut server = Server::new_async().await;
+    server
+        .mock("GET", "/api/me")
+        .match_header("if-none-match", Matcher::Missing)
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_header("etag", "\"v1\"")
+        .with_body(
+            r#"{"user_id":123,"username":"john","email":"john@example.com","groups":["users","support"]}"#,
+        )
+        .create_async()
+        .await;
+    server
+        .mock("GET", "/api/me")
+        .match_header("if-none-match", "\"v1\"")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_header("etag", "\"v2\"")
+        .with_body(
+            r#"{"user_id":123,"username":"john","email":"john@example.com","groups":["users","admins"]}"#,
+        )
+        .create_async()
+        .await;
+    server
+        .mock("GET", "/api/me")
+        .match_header("if-none-match", "\"v2\"")
+        .with_status(304)
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::new(server.url()).unwrap();
+    client.set_token("test-token").await;
+    let group_changes = Arc::new(AtomicUsize::new(0));
+    let counter = group_changes.clone();
+    let watcher = ProfileWatcher::new(client).on_groups_change(move |_| {
+        counter.fetch_add(1, Ordering::SeqCst);
+    });
+
+    // #act
+    let baseline = watcher.poll().await.unwrap();
+    let change = watcher.poll().await.unwrap();
+    let unchanged = watcher.poll().await.unwrap();
+
+    // #assert
+    assert!(baseline.is_none());
+    let change = change.unwrap();
+    assert_eq!(change.groups_added, ["admins"]);
+    assert_eq!(change.groups_removed, ["support"]);
+    assert!(unchanged.is_none());
+    assert_eq!(group_changes.load(Ordering::SeqCst), 1);
+    assert_eq!(watcher.current().unwrap().groups, ["users", "admins"]);
+}
+
+#[tokio::test]
+async fn test_first_poll_sets_baseline_when_profile_already_fetched() {
+    // #setup
+    let mut server = Server::new_async().await;
+    server
+        .mock("GET", "/api/me")
+        .match_header("if-none-match", Matcher::Missing)
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_header("etag", "\"v1\"")
+        .with_body(
+            r#"{"user_id":123,"username":"john","email":"john@example.com","groups":["users"]}"#,
+        )
+        .expect(2)
+        .create_async()
+        .await;
+    server
+        .mock("GET", "/api/me")
+        .match_header("if-none-match", "\"v1\"")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_header("etag", "\"v2\"")
+        .with_body(
+            r#"{"user_id":123,"username":"john","email":"john@example.com","groups":["users","admins"]}"#,
+        )
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::new(server.url()).unwrap();
+    client.set_token("test-token").await;
+    client.get_current_user().await.unwrap();
+    let group_changes = Arc::new(AtomicUsize::new(0));
+    let counter = group_changes.clone();
+    let watcher = ProfileWatcher::new(client).on_groups_change(move |_| {
+        counter.fetch_add(1, Ordering::SeqCst);
+    });
+
+    // #act
+    let baseline = watcher.poll().await.unwrap();
+    let change = watcher.poll().await.unwrap();
+
+    // #assert
+    assert!(baseline.is_none());
+    assert_eq!(watcher.current().unwrap().groups, ["users", "admins"]);
+    assert_eq!(change.unwrap().groups_added, ["admins"]);
+    assert_eq!(group_changes.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn test_observe_ignores_identical_profiles() {
+    // #setup
+    let client = KeyrunesClient::new("http://localhost").unwrap();
+    let changes = Arc::new(AtomicUsize::new(0));
+    let counter = changes.clone();
+    let watcher = ProfileWatcher::new(client).on_change(move |_| {
+        counter.fetch_add(1, Ordering::SeqCst);
+    });
+    let user = keyrunes_rust_sdk::User {
+        id: "123".to_string(),
+        username: "john".to_string(),
+        email: "john@example.com".to_string(),
+        ..Default::default()
+    };
+    let renamed = keyrunes_rust_sdk::User {
+        email: "johnny@example.com".to_string(),
+        ..user.clone()
+    };
+
+    // #act
+    let baseline = watcher.observe(user.clone());
+    let same = watcher.observe(user);
+    let change = watcher.observe(renamed);
+
+    // #assert
+    assert!(baseline.is_none());
+    assert!(same.is_none());
+    let change = change.unwrap();
+    assert!(!change.groups_changed());
+    assert_eq!(change.previous.email, "john@example.com");
+    assert_eq!(changes.load(Ordering::SeqCst), 1);
+}
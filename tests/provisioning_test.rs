@@ -0,0 +1,252 @@
+use keyrunes_rust_sdk::provisioning::{Change, ProvisioningSpec};
+use keyrunes_rust_sdk::{KeyrunesClient, KeyrunesError};
+use mockito::{Matcher, Server, ServerGuard};
+use serde_json::json;
+
+const SPEC: &[u8] = br#"{
+    "groups": [
+        {"name": "engineering", "description": "Engineering team"},
+        {"name": "support"}
+    ],
+    "permissions": [
+        {"key": "deploys:create", "description": "Start deploys"},
+        {"key": "tickets:read", "description": "Read tickets"}
+    ],
+    "role_bindings": [
+        {"group": "engineering", "permission": "deploys:create"},
+        {"group": "support", "permission": "tickets:read"}
+    ],
+    "service_accounts": [
+        {"name": "ci", "groups": ["engineering"]}
+    ]
+}"#;
+
+/// Mocks the listings of a tenant where support and tickets:read exist
+async fn mock_tenant(server: &mut ServerGuard, bindings: &str, accounts: &str) {
+    server
+        .mock("GET", "/api/admin/groups")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"[{"id":"g-2","name":"support"}]"#)
+        .create_async()
+        .await;
+    server
+        .mock("GET", "/api/admin/permissions")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"[{"key":"tickets:read","description":"Read tickets","deprecated":true}]"#)
+        .create_async()
+        .await;
+    server
+        .mock("GET", "/api/admin/role-bindings")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(bindings)
+        .create_async()
+        .await;
+    server
+        .mock("GET", "/api/admin/service-accounts")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(accounts)
+        .create_async()
+        .await;
+}
+
+#[tokio::test]
+async fn test_plan_lists_changes() {
+    // #setup
+    let mut server = Server::new_async().await;
+    mock_tenant(
+        &mut server,
+        r#"[{"id":"b-1","group":"support","permission":"tickets:read"}]"#,
+        r#"[{"id":"sa-1","name":"ci","groups":[]}]"#,
+    )
+    .await;
+    let client = KeyrunesClient::new(server.url()).unwrap();
+    client.set_token("admin-token").await;
+    let spec = ProvisioningSpec::from_json(SPEC).unwrap();
+
+    // #act
+    let plan = spec.plan(&client).await.unwrap();
+
+    // #assert
+    let lines: Vec<String> = plan.changes.iter().map(ToString::to_string).collect();
+    assert_eq!(
+        lines,
+        [
+            "+ group engineering",
+            "+ permission deploys:create",
+            "~ permission tickets:read",
+            "+ role binding engineering -> deploys:create",
+            "~ service account ci",
+        ]
+    );
+    assert!(plan
+        .to_string()
+        .ends_with("Plan: 3 to create, 2 to update."));
+}
+
+#[tokio::test]
+async fn test_apply_creates_resources() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let spec = ProvisioningSpec::from_json(
+        br#"{
+            "groups": [{"name": "engineering"}],
+            "service_accounts": [{"name": "ci", "groups": ["engineering"]}]
+        }"#,
+    )
+    .unwrap();
+    mock_tenant(&mut server, "[]", "[]").await;
+    let group_mock = server
+        .mock("POST", "/api/admin/groups")
+        .match_body(Matcher::Json(
+            json!({"name": "engineering", "description": null}),
+        ))
+        .with_status(201)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"id":"g-1","name":"engineering"}"#)
+        .expect(1)
+        .create_async()
+        .await;
+    let account_mock = server
+        .mock("POST", "/api/admin/service-accounts")
+        .match_body(Matcher::Json(
+            json!({"name": "ci", "description": null, "groups": ["engineering"]}),
+        ))
+        .with_status(201)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"id":"sa-1","name":"ci","groups":["engineering"],"client_id":"ci","client_secret":"s3cret"}"#)
+        .expect(1)
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::new(server.url()).unwrap();
+    client.set_token("admin-token").await;
+
+    // #act
+    let report = spec.apply(&client).await.unwrap();
+
+    // #assert
+    assert_eq!(report.applied, 2);
+    assert_eq!(
+        report.service_accounts[0].client_secret.as_deref(),
+        Some("s3cret")
+    );
+    group_mock.assert_async().await;
+    account_mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_plan_is_empty_when_tenant_matches() {
+    // #setup
+    let mut server = Server::new_async().await;
+    mock_tenant(
+        &mut server,
+        r#"[{"id":"b-1","group":"support","permission":"tickets:read"}]"#,
+        "[]",
+    )
+    .await;
+    let client = KeyrunesClient::new(server.url()).unwrap();
+    client.set_token("admin-token").await;
+    let spec = ProvisioningSpec::from_json(
+        br#"{
+            "groups": [{"name": "support"}],
+            "role_bindings": [{"group": "support", "permission": "tickets:read"}]
+        }"#,
+    )
+    .unwrap();
+
+    // #act
+    let plan = spec.plan(&client).await.unwrap();
+
+    // #assert
+    assert!(plan.is_empty());
+    assert_eq!(plan.to_string(), "No changes.");
+}
+
+#[tokio::test]
+async fn test_apply_rolls_back_on_failure() {
+    // #setup
+    let mut server = Server::new_async().await;
+    mock_tenant(&mut server, "[]", "[]").await;
+    let _group = server
+        .mock("POST", "/api/admin/groups")
+        .with_status(201)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"id":"g-1","name":"engineering"}"#)
+        .create_async()
+        .await;
+    let _binding = server
+        .mock("POST", "/api/admin/role-bindings")
+        .with_status(500)
+        .with_body(r#"{"message":"Internal error"}"#)
+        .create_async()
+        .await;
+    let delete_mock = server
+        .mock("DELETE", "/api/admin/groups/g-1")
+        .with_status(204)
+        .expect(1)
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::new(server.url()).unwrap();
+    client.set_token("admin-token").await;
+    let spec = ProvisioningSpec::from_json(
+        br#"{
+            "groups": [{"name": "engineering"}],
+            "role_bindings": [{"group": "engineering", "permission": "tickets:read"}]
+        }"#,
+    )
+    .unwrap();
+    let plan = spec.plan(&client).await.unwrap();
+    assert!(matches!(plan.changes[0], Change::CreateGroup(_)));
+
+    // #act
+    let result = plan.apply(&client).await;
+
+    // #assert
+    match result {
+        Err(KeyrunesError::ProvisioningFailed {
+            change,
+            source,
+            rolled_back,
+        }) => {
+            assert_eq!(change, "+ role binding engineering -> tickets:read");
+            assert!(matches!(*source, KeyrunesError::HttpError(_)));
+            assert!(rolled_back);
+        }
+        other => panic!("Expected ProvisioningFailed, got {:?}", other),
+    }
+    delete_mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_invalid_specs_are_rejected() {
+    // #setup
+    let mut server = Server::new_async().await;
+    mock_tenant(&mut server, "[]", "[]").await;
+    let client = KeyrunesClient::new(server.url()).unwrap();
+    client.set_token("admin-token").await;
+    let unknown_group = ProvisioningSpec::from_json(
+        br#"{"role_bindings": [{"group": "sales", "permission": "tickets:read"}]}"#,
+    )
+    .unwrap();
+
+    // #act
+    let duplicate = ProvisioningSpec::from_json(br#"{"groups": [{"name": "a"}, {"name": "a"}]}"#);
+    let typo = ProvisioningSpec::from_json(br#"{"group": [{"name": "a"}]}"#);
+    let unknown = unknown_group.plan(&client).await;
+
+    // #assert
+    assert!(matches!(
+        duplicate,
+        Err(KeyrunesError::InvalidProvisioningSpec(_))
+    ));
+    assert!(matches!(typo, Err(KeyrunesError::SerializationError(_))));
+    assert!(matches!(
+        unknown,
+        Err(KeyrunesError::InvalidProvisioningSpec(message)) if message.contains("sales")
+    ));
+}
@@ -0,0 +1,159 @@
+use keyrunes_rust_sdk::quota::QuotaBatcher;
+use keyrunes_rust_sdk::{KeyrunesClient, KeyrunesError};
+use mockito::{Matcher, Server};
+
+#[tokio::test]
+async fn test_get_and_consume_quota() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let get_mock = server
+        .mock("GET", "/api/users/123/quotas/api_calls")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"meter":"api_calls","used":10,"limit":100}"#)
+        .create_async()
+        .await;
+    let consume_mock = server
+        .mock("POST", "/api/users/123/quotas/api_calls/consume")
+        .match_body(Matcher::Json(serde_json::json!({ "amount": 5 })))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"meter":"api_calls","used":15,"limit":100}"#)
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::new(server.url()).unwrap();
+
+    // #act
+    let quota = client.get_quota("123", "api_calls").await.unwrap();
+    let consumed = client.consume_quota("123", "api_calls", 5).await.unwrap();
+
+    // #assert
+    assert_eq!(quota.remaining(), Some(90));
+    assert_eq!(consumed.used, 15);
+    get_mock.assert_async().await;
+    consume_mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_consume_quota_exceeded() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let _mock = server
+        .mock("POST", "/api/users/123/quotas/api_calls/consume")
+        .with_status(429)
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::new(server.url()).unwrap();
+
+    // #act
+    let result = client.consume_quota("123", "api_calls", 1).await;
+
+    // #assert
+    assert!(matches!(result, Err(KeyrunesError::QuotaExceeded(meter)) if meter == "api_calls"));
+}
+
+#[tokio::test]
+async fn test_quota_batcher_batches_reports() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let get_mock = server
+        .mock("GET", "/api/users/123/quotas/api_calls")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"meter":"api_calls","used":0,"limit":5}"#)
+        .expect(1)
+        .create_async()
+        .await;
+    let consume_mock = server
+        .mock("POST", "/api/users/123/quotas/api_calls/consume")
+        .match_body(Matcher::Json(serde_json::json!({ "amount": 3 })))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"meter":"api_calls","used":3,"limit":5}"#)
+        .expect(1)
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::new(server.url()).unwrap();
+    let batcher = QuotaBatcher::new(client).with_max_pending(3);
+
+    // #act
+    for _ in 0..3 {
+        assert!(batcher.consume("123", "api_calls", 1).await.unwrap());
+    }
+    let remaining = batcher.remaining("123", "api_calls").await;
+    let over = batcher.consume("123", "api_calls", 3).await.unwrap();
+
+    // #assert
+    assert_eq!(remaining, Some(2));
+    assert!(!over);
+    get_mock.assert_async().await;
+    consume_mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_quota_batcher_flush() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let _get_mock = server
+        .mock("GET", "/api/users/123/quotas/api_calls")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"meter":"api_calls","used":0}"#)
+        .create_async()
+        .await;
+    let consume_mock = server
+        .mock("POST", "/api/users/123/quotas/api_calls/consume")
+        .match_body(Matcher::Json(serde_json::json!({ "amount": 2 })))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"meter":"api_calls","used":2}"#)
+        .expect(1)
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::new(server.url()).unwrap();
+    let batcher = QuotaBatcher::new(client);
+
+    // #act
+    batcher.consume("123", "api_calls", 2).await.unwrap();
+    batcher.flush().await.unwrap();
+    batcher.flush().await.unwrap();
+
+    // #assert
+    consume_mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_shutdown_reports_pending_units() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let _get_mock = server
+        .mock("GET", "/api/users/123/quotas/api_calls")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"meter":"api_calls","used":0}"#)
+        .create_async()
+        .await;
+    let consume_mock = server
+        .mock("POST", "/api/users/123/quotas/api_calls/consume")
+        .match_body(Matcher::Json(serde_json::json!({ "amount": 3 })))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"meter":"api_calls","used":3}"#)
+        .expect(1)
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::new(server.url()).unwrap();
+    let batcher = QuotaBatcher::new(client.clone());
+    batcher.consume("123", "api_calls", 3).await.unwrap();
+
+    // #act
+    client.shutdown().await.unwrap();
+
+    // #assert
+    consume_mock.assert_async().await;
+}
@@ -0,0 +1,79 @@
+use keyrunes_rust_sdk::rate_limit::{InMemoryRateLimitStore, RateLimitQuota, RateLimiter};
+use std::sync::Arc;
+use std::time::Duration;
+
+#[tokio::test]
+async fn test_rate_limit_allows_within_quota() {
+    // #setup
+    let limiter = RateLimiter::in_memory(RateLimitQuota::per_minute(3));
+
+    // #act
+    let first = limiter.check("user-1").await.unwrap();
+    let second = limiter.check("user-1").await.unwrap();
+
+    // #assert
+    assert!(first.allowed);
+    assert_eq!(first.remaining, 2);
+    assert!(second.allowed);
+    assert_eq!(second.remaining, 1);
+}
+
+#[tokio::test]
+async fn test_rate_limit_rejects_over_quota() {
+    // #setup
+    let limiter = RateLimiter::in_memory(RateLimitQuota::per_minute(1));
+    limiter.check("user-1").await.unwrap();
+
+    // #act
+    let decision = limiter.check("user-1").await.unwrap();
+
+    // #assert
+    assert!(!decision.allowed);
+    assert!(decision.retry_after.unwrap() > Duration::from_secs(50));
+}
+
+#[tokio::test]
+async fn test_rate_limit_is_per_user() {
+    // #setup
+    let limiter = RateLimiter::in_memory(RateLimitQuota::per_minute(1));
+    limiter.check("user-1").await.unwrap();
+
+    // #act
+    let decision = limiter.check("user-2").await.unwrap();
+
+    // #assert
+    assert!(decision.allowed);
+}
+
+#[tokio::test]
+async fn test_rate_limit_refills() {
+    // #setup
+    let limiter = RateLimiter::in_memory(RateLimitQuota::new(1, Duration::from_millis(50)));
+    limiter.check("user-1").await.unwrap();
+
+    // #act
+    tokio::time::sleep(Duration::from_millis(60)).await;
+    let decision = limiter.check("user-1").await.unwrap();
+
+    // #assert
+    assert!(decision.allowed);
+}
+
+#[tokio::test]
+async fn test_rate_limit_drops_refilled_buckets() {
+    // #setup
+    let store = Arc::new(InMemoryRateLimitStore::new());
+    let limiter = RateLimiter::new(
+        store.clone(),
+        RateLimitQuota::new(1, Duration::from_millis(50)),
+    );
+    limiter.check("user-1").await.unwrap();
+    limiter.check("user-2").await.unwrap();
+
+    // #act
+    tokio::time::sleep(Duration::from_millis(60)).await;
+    limiter.check("user-3").await.unwrap();
+
+    // #assert
+    assert_eq!(store.len().await, 1);
+}
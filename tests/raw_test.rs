@@ -0,0 +1,203 @@
+use keyrunes_rust_sdk::{KeyrunesClient, KeyrunesError};
+use mockito::{Matcher, Server};
+use serde_json::json;
+
+#[tokio::test]
+async fn test_raw_request() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("POST", "/api/beta/insights")
+        .match_header("authorization", "Bearer test-token-789")
+        .match_header("x-feature", "insights")
+        .match_query(Matcher::UrlEncoded("period".into(), "7 days".into()))
+        .match_body(Matcher::Json(json!({"metric": "logins"})))
+        .with_status(201)
+        .with_header("content-type", "application/json")
+        .with_header("x-request-id", "req-1")
+        .with_body(r#"{"logins":42}"#)
+        .expect(1)
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::new(server.url()).unwrap();
+    client.set_token("test-token-789").await;
+
+    // #act
+    let response = client
+        .raw()
+        .post("/api/beta/insights")
+        .query("period", "7 days")
+        .header("x-feature", "insights")
+        .json(&json!({"metric": "logins"}))
+        .send()
+        .await
+        .unwrap();
+
+    // #assert
+    assert_eq!(response.status(), 201);
+    assert_eq!(response.header("x-request-id"), Some("req-1"));
+    let body: serde_json::Value = response.json().unwrap();
+    assert_eq!(body["logins"], 42);
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_raw_request_anonymous() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("GET", "/api/beta/status")
+        .match_header("authorization", Matcher::Missing)
+        .with_status(200)
+        .with_body("ok")
+        .expect(1)
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::new(server.url()).unwrap();
+    client.set_token("test-token-789").await;
+
+    // #act
+    let response = client
+        .raw()
+        .get("/api/beta/status")
+        .anonymous()
+        .send()
+        .await
+        .unwrap();
+
+    // #assert
+    assert_eq!(response.text(), "ok");
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_raw_request_maps_errors() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let _forbidden = server
+        .mock("DELETE", "/api/beta/insights")
+        .with_status(403)
+        .with_body(r#"{"message":"Beta not enabled"}"#)
+        .create_async()
+        .await;
+    let _coded = server
+        .mock("PUT", "/api/beta/insights")
+        .with_status(422)
+        .with_body(r#"{"code":"invalid_period","message":"Unknown period"}"#)
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::new(server.url()).unwrap();
+
+    // #act
+    let forbidden = client.raw().delete("/api/beta/insights").send().await;
+    let coded = client.raw().put("/api/beta/insights").send().await;
+
+    // #assert
+    assert!(matches!(
+        forbidden,
+        Err(KeyrunesError::AuthorizationError { .. })
+    ));
+    assert_eq!(coded.unwrap_err().code(), Some("invalid_period"));
+}
+
+#[derive(serde::Serialize)]
+struct InsightsQuery {
+    metric: &'static str,
+}
+
+#[derive(serde::Deserialize)]
+struct Insights {
+    logins: u64,
+}
+
+#[tokio::test]
+async fn test_call_with_typed_body_and_namespace() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let _login = server
+        .mock("POST", "/api/login")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"token":"test-token-123"}"#)
+        .create_async()
+        .await;
+    let mock = server
+        .mock("POST", "/api/beta/insights")
+        .match_header("authorization", "Bearer test-token-123")
+        .match_header("x-keyrunes-namespace", "acme")
+        .match_body(Matcher::Json(json!({"metric": "logins"})))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"logins":42}"#)
+        .expect(1)
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::new(server.url()).unwrap();
+    client
+        .login("user@example.com", "password", Some("acme"))
+        .await
+        .unwrap();
+
+    // #act
+    let insights: Insights = client
+        .call(
+            reqwest::Method::POST,
+            "/api/beta/insights",
+            Some(&InsightsQuery { metric: "logins" }),
+        )
+        .await
+        .unwrap();
+
+    // #assert
+    assert_eq!(insights.logins, 42);
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_call_retries_idempotent_requests() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let get_mock = server
+        .mock("GET", "/api/beta/insights")
+        .with_status(503)
+        .expect(3)
+        .create_async()
+        .await;
+    let post_mock = server
+        .mock("POST", "/api/beta/insights")
+        .with_status(503)
+        .expect(1)
+        .create_async()
+        .await;
+    let delete_mock = server
+        .mock("DELETE", "/api/beta/insights/1")
+        .with_status(204)
+        .expect(1)
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::new(server.url()).unwrap();
+
+    // #act
+    let get = client
+        .call::<(), Insights>(reqwest::Method::GET, "/api/beta/insights", None)
+        .await;
+    let post = client
+        .call::<(), Insights>(reqwest::Method::POST, "/api/beta/insights", None)
+        .await;
+    let delete = client
+        .call::<(), ()>(reqwest::Method::DELETE, "/api/beta/insights/1", None)
+        .await;
+
+    // #assert
+    assert!(matches!(get, Err(KeyrunesError::HttpError(_))));
+    assert!(matches!(post, Err(KeyrunesError::HttpError(_))));
+    assert!(delete.is_ok());
+    get_mock.assert_async().await;
+    post_mock.assert_async().await;
+    delete_mock.assert_async().await;
+}
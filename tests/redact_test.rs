@@ -0,0 +1,109 @@
+use keyrunes_rust_sdk::permissions::PermissionSet;
+use keyrunes_rust_sdk::redact::{AuthContext, FieldRule, Redact, DEFAULT_MASK};
+use serde::Serialize;
+use serde_json::json;
+
+#[derive(Serialize)]
+struct Employee {
+    name: String,
+    salary: u64,
+    notes: String,
+}
+
+impl Redact for Employee {
+    const FIELD_RULES: &'static [FieldRule] = &[
+        FieldRule {
+            key: "salary",
+            groups: &["hr"],
+            permissions: &[],
+            mask: Some(DEFAULT_MASK),
+        },
+        FieldRule {
+            key: "notes",
+            groups: &["admins"],
+            permissions: &["employees:audit"],
+            mask: None,
+        },
+    ];
+}
+
+fn employee() -> Employee {
+    Employee {
+        name: "Ada".to_string(),
+        salary: 100,
+        notes: "internal".to_string(),
+    }
+}
+
+#[test]
+fn test_redacted_hides_restricted_fields() {
+    // #act
+    let value = employee().redacted(&AuthContext::default()).unwrap();
+
+    // #assert
+    assert_eq!(value, json!({"name": "Ada", "salary": "***"}));
+}
+
+#[test]
+fn test_redacted_shows_fields_to_allowed_callers() {
+    // #setup
+    let ctx = AuthContext::new(["hr"], PermissionSet::new(["employees:*"]));
+
+    // #act
+    let value = employee().redacted(&ctx).unwrap();
+
+    // #assert
+    assert_eq!(
+        value,
+        json!({"name": "Ada", "salary": 100, "notes": "internal"})
+    );
+}
+
+#[cfg(feature = "macros")]
+mod derive {
+    use super::*;
+    use keyrunes_rust_sdk::KeyrunesRedact;
+
+    #[derive(Serialize, KeyrunesRedact)]
+    struct Account {
+        id: String,
+        #[keyrunes(group = "admins", mask = "[hidden]")]
+        #[serde(rename = "internalNotes")]
+        notes: String,
+        #[keyrunes(group = "admins", permission = "billing:read")]
+        balance: i64,
+    }
+
+    fn account() -> Account {
+        Account {
+            id: "1".to_string(),
+            notes: "vip".to_string(),
+            balance: 42,
+        }
+    }
+
+    #[test]
+    fn test_derive_declares_field_rules() {
+        // #assert
+        assert_eq!(Account::FIELD_RULES.len(), 2);
+        assert_eq!(Account::FIELD_RULES[0].key, "internalNotes");
+        assert_eq!(Account::FIELD_RULES[1].permissions, ["billing:read"]);
+    }
+
+    #[test]
+    fn test_derive_redacts_by_group_and_permission() {
+        // #setup
+        let billing = AuthContext::new(["support"], PermissionSet::new(["billing:read"]));
+
+        // #act
+        let anonymous = account().redacted(&AuthContext::default()).unwrap();
+        let billing = account().redacted(&billing).unwrap();
+
+        // #assert
+        assert_eq!(anonymous, json!({"id": "1", "internalNotes": "[hidden]"}));
+        assert_eq!(
+            billing,
+            json!({"id": "1", "internalNotes": "[hidden]", "balance": 42})
+        );
+    }
+}
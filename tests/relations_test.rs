@@ -0,0 +1,229 @@
+use keyrunes_rust_sdk::{KeyrunesClient, KeyrunesError, Relation, ResourcePath, ResourceRef};
+use mockito::{Matcher, Server};
+
+fn editor_relation() -> Relation {
+    Relation::new(
+        ResourceRef::new("user", "456"),
+        "editor",
+        ResourceRef::new("document", "42"),
+    )
+}
+
+fn editor_relation_json() -> serde_json::Value {
+    serde_json::json!({
+        "subject": {"type": "user", "id": "456"},
+        "relation": "editor",
+        "object": {"type": "document", "id": "42"}
+    })
+}
+
+#[tokio::test]
+async fn test_register_resource() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("POST", "/api/resources")
+        .match_header("authorization", "Bearer test-token-789")
+        .match_body(Matcher::Json(serde_json::json!({
+            "type": "document",
+            "id": "42",
+            "owner_id": "123",
+            "parent": {"type": "folder", "id": "7"}
+        })))
+        .with_status(201)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{"type":"document","id":"42","owner_id":"123","parent":{"type":"folder","id":"7"}}"#,
+        )
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::new(server.url()).unwrap();
+    client.set_token("test-token-789").await;
+
+    // #act
+    let resource = client
+        .register_resource(
+            "document",
+            "42",
+            Some("123"),
+            Some(&ResourceRef::new("folder", "7")),
+        )
+        .await
+        .unwrap();
+
+    // #assert
+    assert_eq!(resource.to_ref(), ResourceRef::new("document", "42"));
+    assert_eq!(resource.parent, Some(ResourceRef::new("folder", "7")));
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_add_check_and_remove_relation() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let add_mock = server
+        .mock("POST", "/api/relations")
+        .match_body(Matcher::Json(editor_relation_json()))
+        .with_status(201)
+        .create_async()
+        .await;
+    let check_mock = server
+        .mock("POST", "/api/relations/check")
+        .match_body(Matcher::Json(editor_relation_json()))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"allowed":true}"#)
+        .create_async()
+        .await;
+    let remove_mock = server
+        .mock("DELETE", "/api/relations")
+        .match_body(Matcher::Json(editor_relation_json()))
+        .with_status(204)
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::new(server.url()).unwrap();
+    client.set_token("test-token-789").await;
+    let relation = editor_relation();
+
+    // #act
+    client.add_relation(&relation).await.unwrap();
+    let allowed = client.check_relation(&relation).await.unwrap();
+    client.remove_relation(&relation).await.unwrap();
+
+    // #assert
+    assert!(allowed);
+    assert_eq!(relation.to_string(), "document:42#editor@user:456");
+    add_mock.assert_async().await;
+    check_mock.assert_async().await;
+    remove_mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_list_relations() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("GET", "/api/relations")
+        .match_query(Matcher::AllOf(vec![
+            Matcher::UrlEncoded("object_type".into(), "document".into()),
+            Matcher::UrlEncoded("object_id".into(), "42".into()),
+        ]))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(format!("[{}]", editor_relation_json()))
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::new(server.url()).unwrap();
+    client.set_token("test-token-789").await;
+
+    // #act
+    let relations = client
+        .list_relations(&ResourceRef::new("document", "42"))
+        .await
+        .unwrap();
+
+    // #assert
+    assert_eq!(relations, vec![editor_relation()]);
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_list_accessible_follows_pages() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let first_mock = server
+        .mock("GET", "/api/users/123/accessible")
+        .match_query(Matcher::Exact(
+            "resource_type=document&permission=read".to_string(),
+        ))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"items":["1","2"],"next_cursor":"c2"}"#)
+        .create_async()
+        .await;
+    let second_mock = server
+        .mock("GET", "/api/users/123/accessible")
+        .match_query(Matcher::UrlEncoded("cursor".into(), "c2".into()))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"items":["3"]}"#)
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::new(server.url()).unwrap();
+
+    // #act
+    let ids = client
+        .list_accessible("123", "document", "read")
+        .await
+        .unwrap();
+
+    // #assert
+    assert_eq!(ids, vec!["1", "2", "3"]);
+    first_mock.assert_async().await;
+    second_mock.assert_async().await;
+}
+
+#[test]
+fn test_resource_path_parsing() {
+    // #act
+    let path: ResourcePath = "/org/123/project/456/doc/789".parse().unwrap();
+
+    // #assert
+    assert_eq!(path.resource(), &ResourceRef::new("doc", "789"));
+    assert_eq!(
+        path.ancestors().cloned().collect::<Vec<_>>(),
+        vec![
+            ResourceRef::new("project", "456"),
+            ResourceRef::new("org", "123")
+        ]
+    );
+    assert_eq!(path.parent().unwrap().to_string(), "org/123/project/456");
+    assert_eq!(path.to_string(), "org/123/project/456/doc/789");
+    assert!(matches!(
+        "org/123/project".parse::<ResourcePath>(),
+        Err(KeyrunesError::InvalidResourcePath(_))
+    ));
+    assert!("org//project/1".parse::<ResourcePath>().is_err());
+    assert!("".parse::<ResourcePath>().is_err());
+}
+
+#[tokio::test]
+async fn test_check_path_permission_sends_expanded_path() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("POST", "/api/authorize/hierarchy")
+        .match_body(Matcher::Json(serde_json::json!({
+            "user_id": "42",
+            "permission": "read",
+            "resources": [
+                {"type": "doc", "id": "789"},
+                {"type": "project", "id": "456"},
+                {"type": "org", "id": "123"}
+            ]
+        })))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"allowed":true,"granted_by":{"type":"org","id":"123"}}"#)
+        .expect(1)
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::new(server.url()).unwrap();
+    let path: ResourcePath = "org/123/project/456/doc/789".parse().unwrap();
+
+    // #act
+    let decision = client
+        .check_path_permission("42", &path, "read")
+        .await
+        .unwrap();
+
+    // #assert
+    assert!(decision.allowed);
+    assert_eq!(decision.granted_by, Some(ResourceRef::new("org", "123")));
+    mock.assert_async().await;
+}
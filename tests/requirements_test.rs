@@ -0,0 +1,181 @@
+use keyrunes_rust_sdk::requirements::{check, Require, RouteRequirements};
+use keyrunes_rust_sdk::{KeyrunesClient, KeyrunesError, User};
+use mockito::Server;
+
+enum EditPosts {}
+
+impl RouteRequirements for EditPosts {
+    const GROUPS: &'static [&'static str] = &["editors"];
+    const PERMISSIONS: &'static [&'static str] = &["posts:write"];
+}
+
+fn user() -> User {
+    User {
+        id: "123".to_string(),
+        username: "john".to_string(),
+        email: "john@example.com".to_string(),
+        groups: vec![],
+        ..Default::default()
+    }
+}
+
+async fn authenticated_client(server: &Server) -> KeyrunesClient {
+    let client = KeyrunesClient::new(server.url()).unwrap();
+    client.set_token("token".to_string()).await;
+    client
+}
+
+#[tokio::test]
+async fn test_check_passes_with_group_and_permission() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let group_mock = server
+        .mock("GET", "/api/users/123/groups/editors")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"has_group":true}"#)
+        .create_async()
+        .await;
+    let permissions_mock = server
+        .mock("GET", "/api/users/123/permissions")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"["posts:*"]"#)
+        .create_async()
+        .await;
+
+    let client = authenticated_client(&server).await;
+
+    // #act
+    let result = Require::<EditPosts>::check(&client, user()).await;
+
+    // #assert
+    group_mock.assert_async().await;
+    permissions_mock.assert_async().await;
+    assert_eq!(result.unwrap().user.id, "123");
+}
+
+#[tokio::test]
+async fn test_check_fails_without_group() {
+    // #setup
+    let mut server = Server::new_async().await;
+    server
+        .mock("GET", "/api/users/123/groups/editors")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"has_group":false}"#)
+        .create_async()
+        .await;
+    let permissions_mock = server
+        .mock("GET", "/api/users/123/permissions")
+        .expect(0)
+        .create_async()
+        .await;
+
+    let client = authenticated_client(&server).await;
+
+    // #act
+    let result = check::<EditPosts>(&client, &user()).await;
+
+    // #assert
+    permissions_mock.assert_async().await;
+    match result {
+        Err(KeyrunesError::AuthorizationError { message: msg, .. }) => {
+            assert!(msg.contains("editors"))
+        }
+        other => panic!("Expected AuthorizationError, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_check_fails_without_permission() {
+    // #setup
+    let mut server = Server::new_async().await;
+    server
+        .mock("GET", "/api/users/123/groups/editors")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"has_group":true}"#)
+        .create_async()
+        .await;
+    server
+        .mock("GET", "/api/users/123/permissions")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"["posts:read"]"#)
+        .create_async()
+        .await;
+
+    let client = authenticated_client(&server).await;
+
+    // #act
+    let result = check::<EditPosts>(&client, &user()).await;
+
+    // #assert
+    match result {
+        Err(KeyrunesError::AuthorizationError { message: msg, .. }) => {
+            assert!(msg.contains("posts:write"))
+        }
+        other => panic!("Expected AuthorizationError, got {:?}", other),
+    }
+}
+
+#[cfg(feature = "macros")]
+mod macros {
+    use super::{authenticated_client, user};
+    use keyrunes_rust_sdk::requirements::{Require, RouteRequirements};
+    use mockito::{Matcher, Server};
+
+    #[keyrunes_rust_sdk::require(
+        group = "editors",
+        group = "reviewers",
+        permission = "posts:write"
+    )]
+    async fn publish_post() -> &'static str {
+        "published"
+    }
+
+    #[test]
+    fn test_require_declares_requirements() {
+        // #assert
+        assert_eq!(
+            __KeyrunesRequire_publish_post::GROUPS,
+            ["editors", "reviewers"]
+        );
+        assert_eq!(__KeyrunesRequire_publish_post::PERMISSIONS, ["posts:write"]);
+    }
+
+    #[tokio::test]
+    async fn test_require_adds_extractor_argument() {
+        // #setup
+        let mut server = Server::new_async().await;
+        server
+            .mock(
+                "GET",
+                Matcher::Regex(r"^/api/users/123/groups/".to_string()),
+            )
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"has_group":true}"#)
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/api/users/123/permissions")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"["posts:write"]"#)
+            .create_async()
+            .await;
+
+        let client = authenticated_client(&server).await;
+        let guard = Require::<__KeyrunesRequire_publish_post>::check(&client, user())
+            .await
+            .unwrap();
+
+        // #act
+        let result = publish_post(guard).await;
+
+        // #assert
+        assert_eq!(result, "published");
+    }
+}
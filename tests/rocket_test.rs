@@ -0,0 +1,73 @@
+#![cfg(feature = "rocket")]
+
+use keyrunes_rust_sdk::middleware::rocket::{fairing, AuthenticatedUser, KeyrunesState};
+use keyrunes_rust_sdk::KeyrunesClient;
+use rocket::error::ErrorKind;
+use rocket::figment::Figment;
+use rocket::local::asynchronous::Client;
+use rocket::{get, routes, Build, Rocket};
+
+#[get("/me")]
+fn me(user: AuthenticatedUser) -> String {
+    user.user.username
+}
+
+fn rocket_with(figment: Figment) -> Rocket<Build> {
+    rocket::custom(figment).mount("/", routes![me])
+}
+
+fn base_figment() -> Figment {
+    rocket::Config::figment().merge(("log_level", "off"))
+}
+
+#[tokio::test]
+async fn test_sentinel_aborts_launch_without_state() {
+    // #act
+    let result = rocket_with(base_figment()).ignite().await;
+
+    // #assert
+    match result {
+        Err(err) => assert!(matches!(err.kind(), ErrorKind::SentinelAborts(_))),
+        Ok(_) => panic!("Expected launch to abort"),
+    }
+}
+
+#[tokio::test]
+async fn test_managed_state_satisfies_sentinel() {
+    // #setup
+    let state = KeyrunesState::new(KeyrunesClient::new("https://example.com").unwrap());
+
+    // #act
+    let result = Client::tracked(rocket_with(base_figment()).manage(state)).await;
+
+    // #assert
+    assert!(result.is_ok());
+}
+
+#[tokio::test]
+async fn test_fairing_builds_state_from_config() {
+    // #setup
+    let figment = base_figment()
+        .merge(("keyrunes.url", "https://keyrunes.example.com"))
+        .merge(("keyrunes.admin_group", "staff"));
+
+    // #act
+    let client = Client::tracked(rocket_with(figment).attach(fairing()))
+        .await
+        .unwrap();
+
+    // #assert
+    assert!(client.rocket().state::<KeyrunesState>().is_some());
+}
+
+#[tokio::test]
+async fn test_fairing_fails_without_config() {
+    // #act
+    let result = rocket_with(base_figment()).attach(fairing()).ignite().await;
+
+    // #assert
+    match result {
+        Err(err) => assert!(matches!(err.kind(), ErrorKind::FailedFairings(_))),
+        Ok(_) => panic!("Expected launch to abort"),
+    }
+}
@@ -0,0 +1,191 @@
+#![cfg(feature = "axum")]
+
+use axum::{body::Body, http::Request, http::StatusCode, Router};
+use keyrunes_rust_sdk::middleware::scim_router::{scim_router, ScimServer};
+use keyrunes_rust_sdk::KeyrunesClient;
+use mockito::{Matcher, Server, ServerGuard};
+use serde_json::{json, Value};
+use tower::ServiceExt;
+
+async fn app(server: &ServerGuard) -> Router {
+    let client = KeyrunesClient::new(server.url()).unwrap();
+    client.set_token("keyrunes-token").await;
+    Router::new().nest(
+        "/scim/v2",
+        scim_router(ScimServer::new(client).with_bearer_token("idp-token")),
+    )
+}
+
+fn request(method: &str, uri: &str, body: Option<Value>) -> Request<Body> {
+    let request = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header("authorization", "Bearer idp-token");
+    match body {
+        Some(body) => request
+            .header("content-type", "application/scim+json")
+            .body(Body::from(body.to_string()))
+            .unwrap(),
+        None => request.body(Body::empty()).unwrap(),
+    }
+}
+
+async fn json_body(response: axum::response::Response) -> Value {
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    serde_json::from_slice(&bytes).unwrap()
+}
+
+#[tokio::test]
+async fn test_rejects_unknown_bearer_token() {
+    // #setup
+    let server = Server::new_async().await;
+    let app = app(&server).await;
+    let request = Request::get("/scim/v2/Users")
+        .header("authorization", "Bearer keyrunes-token")
+        .body(Body::empty())
+        .unwrap();
+
+    // #act
+    let response = app.oneshot(request).await.unwrap();
+
+    // #assert
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    assert_eq!(response.headers()["content-type"], "application/scim+json");
+    let body = json_body(response).await;
+    assert_eq!(body["status"], "401");
+}
+
+#[tokio::test]
+async fn test_create_user_is_forwarded() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("POST", "/scim/v2/Users")
+        .match_header("authorization", "Bearer keyrunes-token")
+        .match_body(Matcher::PartialJson(json!({"userName": "john"})))
+        .with_status(201)
+        .with_header("content-type", "application/scim+json")
+        .with_body(r#"{"schemas":[],"id":"123","userName":"john"}"#)
+        .expect(1)
+        .create_async()
+        .await;
+    let user = json!({
+        "schemas": ["urn:ietf:params:scim:schemas:core:2.0:User"],
+        "userName": "john",
+        "externalId": "00u1",
+    });
+
+    // #act
+    let response = app(&server)
+        .await
+        .oneshot(request("POST", "/scim/v2/Users", Some(user)))
+        .await
+        .unwrap();
+
+    // #assert
+    assert_eq!(response.status(), StatusCode::CREATED);
+    assert_eq!(json_body(response).await["id"], "123");
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_list_users_passes_filter_and_caps_page_size() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("GET", "/scim/v2/Users")
+        .match_query(Matcher::AllOf(vec![
+            Matcher::UrlEncoded("filter".into(), r#"userName eq "john""#.into()),
+            Matcher::UrlEncoded("count".into(), "200".into()),
+        ]))
+        .with_status(200)
+        .with_header("content-type", "application/scim+json")
+        .with_body(r#"{"totalResults":1,"startIndex":1,"itemsPerPage":1,"Resources":[{"schemas":[],"id":"123","userName":"john"}]}"#)
+        .expect(1)
+        .create_async()
+        .await;
+
+    // #act
+    let response = app(&server)
+        .await
+        .oneshot(request(
+            "GET",
+            "/scim/v2/Users?filter=userName%20eq%20%22john%22&count=1000",
+            None,
+        ))
+        .await
+        .unwrap();
+
+    // #assert
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = json_body(response).await;
+    assert_eq!(
+        body["schemas"][0],
+        "urn:ietf:params:scim:api:messages:2.0:ListResponse"
+    );
+    assert_eq!(body["totalResults"], 1);
+    assert_eq!(body["Resources"][0]["userName"], "john");
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_errors_are_scim_errors() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let _conflict = server
+        .mock("PATCH", "/scim/v2/Groups/g1")
+        .with_status(409)
+        .with_body(r#"{"scimType":"mutability","detail":"members is read-only"}"#)
+        .create_async()
+        .await;
+    let _missing = server
+        .mock("DELETE", "/scim/v2/Users/404")
+        .with_status(404)
+        .with_body(r#"{"detail":"User not found"}"#)
+        .create_async()
+        .await;
+    let app = app(&server).await;
+    let patch = json!({
+        "schemas": ["urn:ietf:params:scim:api:messages:2.0:PatchOp"],
+        "Operations": [{"op": "replace", "path": "members", "value": []}],
+    });
+
+    // #act
+    let conflict = app
+        .clone()
+        .oneshot(request("PATCH", "/scim/v2/Groups/g1", Some(patch)))
+        .await
+        .unwrap();
+    let missing = app
+        .oneshot(request("DELETE", "/scim/v2/Users/404", None))
+        .await
+        .unwrap();
+
+    // #assert
+    assert_eq!(conflict.status(), StatusCode::CONFLICT);
+    let body = json_body(conflict).await;
+    assert_eq!(body["scimType"], "mutability");
+    assert_eq!(body["detail"], "members is read-only");
+    assert_eq!(missing.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_service_provider_config() {
+    // #setup
+    let server = Server::new_async().await;
+
+    // #act
+    let response = app(&server)
+        .await
+        .oneshot(request("GET", "/scim/v2/ServiceProviderConfig", None))
+        .await
+        .unwrap();
+
+    // #assert
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = json_body(response).await;
+    assert_eq!(body["patch"]["supported"], true);
+    assert_eq!(body["bulk"]["supported"], false);
+}
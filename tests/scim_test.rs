@@ -0,0 +1,246 @@
+use keyrunes_rust_sdk::scim::{
+    ScimEmail, ScimFilter, ScimGroup, ScimMember, ScimPatch, ScimQuery, ScimUser, PATCH_SCHEMA,
+};
+use keyrunes_rust_sdk::{KeyrunesClient, KeyrunesError};
+use mockito::{Matcher, Server};
+use serde_json::json;
+
+#[tokio::test]
+async fn test_create_user() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("POST", "/scim/v2/Users")
+        .match_header("authorization", "Bearer scim-token")
+        .match_body(Matcher::Json(json!({
+            "schemas": ["urn:ietf:params:scim:schemas:core:2.0:User"],
+            "externalId": "E-1042",
+            "userName": "john",
+            "emails": [{"value": "john@example.com", "type": "work", "primary": true}],
+            "active": true,
+        })))
+        .with_status(201)
+        .with_header("content-type", "application/scim+json")
+        .with_body(
+            json!({
+                "schemas": ["urn:ietf:params:scim:schemas:core:2.0:User"],
+                "id": "123",
+                "externalId": "E-1042",
+                "userName": "john",
+                "active": true,
+                "groups": [{"value": "g1", "display": "engineering"}],
+                "meta": {"resourceType": "User", "version": "W/\"1\""},
+                "urn:ietf:params:scim:schemas:extension:enterprise:2.0:User": {"department": "R&D"},
+            })
+            .to_string(),
+        )
+        .expect(1)
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::new(server.url()).unwrap();
+    client.set_token("scim-token").await;
+    let mut user = ScimUser::new("john");
+    user.external_id = Some("E-1042".to_string());
+    user.emails.push(ScimEmail::primary("john@example.com"));
+
+    // #act
+    let created = client.scim().create_user(&user).await.unwrap();
+
+    // #assert
+    assert_eq!(created.id.as_deref(), Some("123"));
+    assert_eq!(created.groups[0].display.as_deref(), Some("engineering"));
+    assert_eq!(created.meta.unwrap().resource_type, "User");
+    assert!(created
+        .extra
+        .contains_key("urn:ietf:params:scim:schemas:extension:enterprise:2.0:User"));
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_list_users_with_filter_and_pagination() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("GET", "/scim/v2/Users")
+        .match_query(Matcher::AllOf(vec![
+            Matcher::UrlEncoded("filter".into(), r#"userName sw "jo\"hn""#.into()),
+            Matcher::UrlEncoded("startIndex".into(), "3".into()),
+            Matcher::UrlEncoded("count".into(), "2".into()),
+        ]))
+        .with_status(200)
+        .with_header("content-type", "application/scim+json")
+        .with_body(
+            json!({
+                "schemas": ["urn:ietf:params:scim:api:messages:2.0:ListResponse"],
+                "totalResults": 5,
+                "startIndex": 3,
+                "itemsPerPage": 2,
+                "Resources": [
+                    {"schemas": [], "id": "3", "userName": "john3"},
+                    {"schemas": [], "id": "4", "userName": "john4", "active": false},
+                ],
+            })
+            .to_string(),
+        )
+        .expect(1)
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::new(server.url()).unwrap();
+    client.set_token("scim-token").await;
+    let query = ScimQuery::new()
+        .filter(ScimFilter::starts_with("userName", "jo\"hn"))
+        .start_index(3)
+        .count(2);
+
+    // #act
+    let page = client.scim().list_users(&query).await.unwrap();
+
+    // #assert
+    assert_eq!(page.total_results, 5);
+    assert_eq!(page.resources.len(), 2);
+    assert!(page.resources[0].active);
+    assert!(!page.resources[1].active);
+    assert_eq!(page.next_start_index(), Some(5));
+    mock.assert_async().await;
+}
+
+#[test]
+fn test_filter_composition() {
+    // #act
+    let filter = ScimFilter::eq("userName", "john")
+        .or(ScimFilter::present("externalId").and(ScimFilter::raw("active eq true")));
+
+    // #assert
+    assert_eq!(
+        filter.to_string(),
+        r#"(userName eq "john") or ((externalId pr) and (active eq true))"#
+    );
+}
+
+#[tokio::test]
+async fn test_patch_group_members() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("PATCH", "/scim/v2/Groups/g1")
+        .match_body(Matcher::Json(json!({
+            "schemas": [PATCH_SCHEMA],
+            "Operations": [
+                {"op": "add", "path": "members", "value": [{"value": "123"}, {"value": "456"}]},
+                {"op": "remove", "path": "members[value eq \"789\"]"},
+            ],
+        })))
+        .with_status(200)
+        .with_header("content-type", "application/scim+json")
+        .with_body(
+            json!({
+                "schemas": ["urn:ietf:params:scim:schemas:core:2.0:Group"],
+                "id": "g1",
+                "displayName": "engineering",
+                "members": [{"value": "123"}, {"value": "456"}],
+            })
+            .to_string(),
+        )
+        .expect(1)
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::new(server.url()).unwrap();
+    client.set_token("scim-token").await;
+    let patch = ScimPatch::new()
+        .add_members(["123", "456"])
+        .remove_member("789");
+
+    // #act
+    let group = client.scim().patch_group("g1", &patch).await.unwrap();
+
+    // #assert
+    assert_eq!(
+        group.members,
+        vec![ScimMember::new("123"), ScimMember::new("456")]
+    );
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_replace_and_delete_group() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let replace_mock = server
+        .mock("PUT", "/scim/v2/Groups/g1")
+        .match_body(Matcher::PartialJson(json!({
+            "displayName": "platform",
+            "members": [{"value": "123"}],
+        })))
+        .with_status(200)
+        .with_header("content-type", "application/scim+json")
+        .with_body(
+            r#"{"schemas":[],"id":"g1","displayName":"platform","members":[{"value":"123"}]}"#,
+        )
+        .expect(1)
+        .create_async()
+        .await;
+    let delete_mock = server
+        .mock("DELETE", "/scim/v2/Groups/g1")
+        .with_status(204)
+        .expect(1)
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::new(server.url()).unwrap();
+    client.set_token("scim-token").await;
+    let mut group = ScimGroup::new("platform");
+    group.members.push(ScimMember::new("123"));
+
+    // #act
+    let replaced = client.scim().replace_group("g1", &group).await.unwrap();
+    client.scim().delete_group("g1").await.unwrap();
+
+    // #assert
+    assert_eq!(replaced.display_name, "platform");
+    replace_mock.assert_async().await;
+    delete_mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_scim_error() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let _mock = server
+        .mock("POST", "/scim/v2/Users")
+        .with_status(409)
+        .with_header("content-type", "application/scim+json")
+        .with_body(
+            json!({
+                "schemas": ["urn:ietf:params:scim:api:messages:2.0:Error"],
+                "status": "409",
+                "scimType": "uniqueness",
+                "detail": "userName is already taken",
+            })
+            .to_string(),
+        )
+        .create_async()
+        .await;
+
+    let client = KeyrunesClient::new(server.url()).unwrap();
+    client.set_token("scim-token").await;
+
+    // #act
+    let result = client.scim().create_user(&ScimUser::new("john")).await;
+
+    // #assert
+    match result {
+        Err(KeyrunesError::Api {
+            status,
+            code,
+            message,
+        }) => {
+            assert_eq!(status, 409);
+            assert_eq!(code, "uniqueness");
+            assert_eq!(message, "userName is already taken");
+        }
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
@@ -0,0 +1,219 @@
+#![cfg(feature = "sessions")]
+
+use keyrunes_rust_sdk::session::{SessionManager, SessionTimeouts};
+use keyrunes_rust_sdk::Token;
+use std::time::Duration;
+
+fn token(value: &str, refresh_token: Option<&str>) -> Token {
+    Token {
+        token: value.to_string(),
+        token_type: None,
+        expires_in: None,
+        refresh_token: refresh_token.map(str::to_string),
+        expires_at: None,
+        id_token: None,
+    }
+}
+
+#[tokio::test]
+async fn test_create_and_resolve_session() {
+    // #setup
+    let sessions = SessionManager::in_memory();
+
+    // #act
+    let id = sessions.create(&token("abc", Some("r1"))).await.unwrap();
+    let session = sessions.resolve(&id).await.unwrap().unwrap();
+
+    // #assert
+    assert_eq!(id.len(), 64);
+    assert_eq!(session.token, "abc");
+    assert_eq!(session.refresh_token.as_deref(), Some("r1"));
+}
+
+#[tokio::test]
+async fn test_session_ids_are_unique() {
+    // #setup
+    let sessions = SessionManager::in_memory();
+
+    // #act
+    let first = sessions.create(&token("abc", None)).await.unwrap();
+    let second = sessions.create(&token("abc", None)).await.unwrap();
+
+    // #assert
+    assert_ne!(first, second);
+}
+
+#[tokio::test]
+async fn test_unknown_and_destroyed_sessions_do_not_resolve() {
+    // #setup
+    let sessions = SessionManager::in_memory();
+    let id = sessions.create(&token("abc", None)).await.unwrap();
+
+    // #act
+    sessions.destroy(&id).await.unwrap();
+
+    // #assert
+    assert!(sessions.resolve(&id).await.unwrap().is_none());
+    assert!(sessions.resolve("unknown").await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn test_idle_timeout_is_extended_by_activity() {
+    // #setup
+    let sessions = SessionManager::in_memory().with_timeouts(SessionTimeouts {
+        idle: Duration::from_millis(300),
+        absolute: Duration::from_secs(60),
+    });
+    let id = sessions.create(&token("abc", None)).await.unwrap();
+
+    // #act
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    let active = sessions.resolve(&id).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    let still_active = sessions.resolve(&id).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(400)).await;
+    let idle = sessions.resolve(&id).await.unwrap();
+
+    // #assert
+    assert!(active.is_some());
+    assert!(still_active.is_some());
+    assert!(idle.is_none());
+}
+
+#[tokio::test]
+async fn test_absolute_timeout_ends_active_sessions() {
+    // #setup
+    let sessions = SessionManager::in_memory().with_timeouts(SessionTimeouts {
+        idle: Duration::from_secs(60),
+        absolute: Duration::from_millis(300),
+    });
+    let id = sessions.create(&token("abc", None)).await.unwrap();
+
+    // #act
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    let active = sessions.resolve(&id).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    let expired = sessions.resolve(&id).await.unwrap();
+
+    // #assert
+    assert!(active.is_some());
+    assert!(expired.is_none());
+}
+
+#[tokio::test]
+async fn test_update_token_keeps_refresh_token() {
+    // #setup
+    let sessions = SessionManager::in_memory();
+    let id = sessions.create(&token("abc", Some("r1"))).await.unwrap();
+
+    // #act
+    let updated = sessions
+        .update_token(&id, &token("def", None))
+        .await
+        .unwrap();
+    let session = sessions.resolve(&id).await.unwrap().unwrap();
+
+    // #assert
+    assert!(updated);
+    assert_eq!(session.token, "def");
+    assert_eq!(session.refresh_token.as_deref(), Some("r1"));
+}
+
+#[test]
+fn test_session_cookie() {
+    // #setup
+    let sessions = SessionManager::in_memory().with_secure(false);
+
+    // #act
+    let cookie = sessions.cookie("abc");
+    let id = sessions.session_id(Some("theme=dark; keyrunes_session=abc"));
+
+    // #assert
+    assert_eq!(
+        cookie,
+        "keyrunes_session=abc; Path=/; HttpOnly; SameSite=Lax; Max-Age=43200"
+    );
+    assert_eq!(id, Some("abc"));
+    assert_eq!(sessions.session_id(Some("keyrunes_session=")), None);
+}
+
+#[cfg(feature = "axum")]
+mod axum_layer {
+    use super::token;
+    use axum::body::Body;
+    use axum::http::{header, Request, StatusCode};
+    use axum::routing::get;
+    use axum::Router;
+    use keyrunes_rust_sdk::middleware::axum::{session_auth, AuthenticatedUser, KeyrunesState};
+    use keyrunes_rust_sdk::session::SessionManager;
+    use keyrunes_rust_sdk::KeyrunesClient;
+    use mockito::Server;
+    use tower::ServiceExt;
+
+    fn app(server: &Server, sessions: SessionManager) -> Router {
+        Router::new()
+            .route(
+                "/me",
+                get(|AuthenticatedUser { user }: AuthenticatedUser| async move { user.username }),
+            )
+            .layer(axum::middleware::from_fn_with_state(sessions, session_auth))
+            .with_state(KeyrunesState::new(
+                KeyrunesClient::new(server.url()).unwrap(),
+            ))
+    }
+
+    #[tokio::test]
+    async fn test_session_cookie_authenticates_request() {
+        // #setup
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("GET", "/api/me")
+            .match_header("authorization", "Bearer abc")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"id":"123","username":"john","email":"john@example.com","groups":[]}"#)
+            .create_async()
+            .await;
+        let sessions = SessionManager::in_memory();
+        let id = sessions.create(&token("abc", None)).await.unwrap();
+
+        // #act
+        let response = app(&server, sessions)
+            .oneshot(
+                Request::get("/me")
+                    .header(header::COOKIE, format!("keyrunes_session={}", id))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        // #assert
+        mock.assert_async().await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_unknown_session_clears_cookie() {
+        // #setup
+        let server = Server::new_async().await;
+
+        // #act
+        let response = app(&server, SessionManager::in_memory())
+            .oneshot(
+                Request::get("/me")
+                    .header(header::COOKIE, "keyrunes_session=unknown")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        // #assert
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(
+            response.headers()[header::SET_COOKIE],
+            "keyrunes_session=; Path=/; HttpOnly; SameSite=Lax; Max-Age=0; Secure"
+        );
+    }
+}
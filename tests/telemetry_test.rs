@@ -0,0 +1,84 @@
+#![cfg(feature = "tracing")]
+
+use keyrunes_rust_sdk::middleware::messages::RejectionKind;
+use keyrunes_rust_sdk::telemetry::request_span;
+use keyrunes_rust_sdk::{AuthService, KeyrunesClient, User};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tracing::field::{Field, Visit};
+use tracing::span::{Id, Record};
+use tracing::Subscriber;
+use tracing_subscriber::layer::{Context, SubscriberExt};
+use tracing_subscriber::Layer;
+
+/// Collects the values recorded on spans after their creation
+#[derive(Clone, Default)]
+struct Recorded(Arc<Mutex<HashMap<String, String>>>);
+
+impl Visit for Recorded {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0
+            .lock()
+            .unwrap()
+            .insert(field.name().to_string(), value.to_string());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        self.record_str(field, &format!("{:?}", value));
+    }
+}
+
+impl<S: Subscriber> Layer<S> for Recorded {
+    fn on_record(&self, _: &Id, values: &Record<'_>, _: Context<'_, S>) {
+        values.record(&mut self.clone());
+    }
+}
+
+fn user() -> User {
+    User {
+        id: "123".to_string(),
+        username: "john".to_string(),
+        email: "john@example.com".to_string(),
+        groups: vec!["admins".to_string(), "editors".to_string()],
+        ..Default::default()
+    }
+}
+
+fn record(result: Result<(), RejectionKind>, user: Option<&User>) -> HashMap<String, String> {
+    let recorded = Recorded::default();
+    let subscriber = tracing_subscriber::registry().with(recorded.clone());
+    let auth = AuthService::new(KeyrunesClient::new("http://localhost").unwrap());
+
+    tracing::subscriber::with_default(subscriber, || {
+        request_span("GET", "/reports/1").in_scope(|| {
+            auth.audit()
+                .finish("GET", "/reports/:id", "authenticated", user, &result);
+        });
+    });
+
+    let fields = recorded.0.lock().unwrap().clone();
+    fields
+}
+
+#[test]
+fn test_allowed_request_records_identity() {
+    // #act
+    let fields = record(Ok(()), Some(&user()));
+
+    // #assert
+    assert_eq!(fields["enduser.id"], "123");
+    assert_eq!(fields["enduser.role"], "admins,editors");
+    assert_eq!(fields["http.route"], "/reports/:id");
+    assert_eq!(fields["keyrunes.decision"], "allow");
+}
+
+#[test]
+fn test_denied_request_records_decision() {
+    // #act
+    let fields = record(Err(RejectionKind::MissingToken), None);
+
+    // #assert
+    assert!(!fields.contains_key("enduser.id"));
+    assert_eq!(fields["http.route"], "/reports/:id");
+    assert_eq!(fields["keyrunes.decision"], "deny");
+}
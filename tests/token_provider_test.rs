@@ -0,0 +1,448 @@
+use futures_util::future::join_all;
+use keyrunes_rust_sdk::client::{MemoryTokenStore, StoredTokens, TokenStore};
+use keyrunes_rust_sdk::{ClientSession, KeyrunesClient, KeyrunesError};
+use mockito::{Matcher, Server};
+use std::sync::Arc;
+
+fn jwt(expires_in: i64) -> String {
+    jsonwebtoken::encode(
+        &jsonwebtoken::Header::default(),
+        &serde_json::json!({"sub": "123", "exp": chrono::Utc::now().timestamp() + expires_in}),
+        &jsonwebtoken::EncodingKey::from_secret(b"secret"),
+    )
+    .unwrap()
+}
+
+async fn signed_in(url: String, token: String) -> KeyrunesClient {
+    let client = KeyrunesClient::new(url).unwrap();
+    client
+        .restore_session(ClientSession {
+            version: ClientSession::VERSION,
+            token: Some(token),
+            refresh_token: Some("r1".to_string()),
+            namespace: None,
+            user: None,
+        })
+        .await
+        .unwrap();
+    client
+}
+
+#[tokio::test]
+async fn test_concurrent_refreshes_send_one_request() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("POST", "/api/refresh")
+        .match_body(Matcher::Json(serde_json::json!({"refresh_token": "r1"})))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"token":"fresh","refresh_token":"r2"}"#)
+        .expect(1)
+        .create_async()
+        .await;
+    let client = signed_in(server.url(), "stale".to_string()).await;
+    let provider = client.token_provider();
+
+    // #act
+    let results = join_all((0..10).map(|_| provider.refresh())).await;
+
+    // #assert
+    mock.assert_async().await;
+    for result in results {
+        assert_eq!(result.unwrap().token, "fresh");
+    }
+    let session = client.export_session().await;
+    assert_eq!(session.token.as_deref(), Some("fresh"));
+    assert_eq!(session.refresh_token.as_deref(), Some("r2"));
+}
+
+#[tokio::test]
+async fn test_concurrent_waiters_share_the_failure() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("POST", "/api/refresh")
+        .with_status(401)
+        .with_body(r#"{"message":"refresh token expired"}"#)
+        .expect(1)
+        .create_async()
+        .await;
+    let client = signed_in(server.url(), "stale".to_string()).await;
+    let provider = client.token_provider();
+
+    // #act
+    let results = join_all((0..5).map(|_| provider.refresh())).await;
+
+    // #assert
+    mock.assert_async().await;
+    for result in results {
+        assert!(matches!(
+            result,
+            Err(KeyrunesError::AuthenticationError { .. })
+        ));
+    }
+}
+
+#[tokio::test]
+async fn test_token_refreshes_when_expiring() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("POST", "/api/refresh")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"token":"fresh"}"#)
+        .expect(1)
+        .create_async()
+        .await;
+    let client = signed_in(server.url(), jwt(10)).await;
+
+    // #act
+    let token = client.token_provider().token().await.unwrap();
+
+    // #assert
+    mock.assert_async().await;
+    assert_eq!(token, "fresh");
+    // Without rotation, the refresh token stays valid.
+    let session = client.export_session().await;
+    assert_eq!(session.refresh_token.as_deref(), Some("r1"));
+}
+
+#[tokio::test]
+async fn test_token_returns_valid_token_as_is() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("POST", "/api/refresh")
+        .expect(0)
+        .create_async()
+        .await;
+    let valid = jwt(600);
+    let client = signed_in(server.url(), valid.clone()).await;
+
+    // #act
+    let token = client.token_provider().token().await.unwrap();
+
+    // #assert
+    mock.assert_async().await;
+    assert_eq!(token, valid);
+}
+
+#[tokio::test]
+async fn test_refresh_without_refresh_token() {
+    // #setup
+    let client = KeyrunesClient::new("http://localhost").unwrap();
+    client.set_token("abc").await;
+
+    // #act
+    let result = client.token_provider().refresh().await;
+
+    // #assert
+    assert!(matches!(result, Err(KeyrunesError::InvalidToken)));
+}
+
+#[tokio::test]
+async fn test_refresh_token_replaces_tokens() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("POST", "/api/refresh")
+        .match_body(Matcher::Json(serde_json::json!({"refresh_token": "r1"})))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"token":"fresh","refresh_token":"r2"}"#)
+        .expect(1)
+        .create_async()
+        .await;
+    let client = signed_in(server.url(), "stale".to_string()).await;
+
+    // #act
+    let token = client.refresh_token().await.unwrap();
+
+    // #assert
+    assert_eq!(token.token, "fresh");
+    let session = client.export_session().await;
+    assert_eq!(session.token.as_deref(), Some("fresh"));
+    assert_eq!(session.refresh_token.as_deref(), Some("r2"));
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_refresh_with_keeps_refresh_token_when_not_rotated() {
+    // #setup
+    let mut server = Server::new_async().await;
+    server
+        .mock("POST", "/api/refresh")
+        .match_body(Matcher::Json(
+            serde_json::json!({"refresh_token": "cookie-r1"}),
+        ))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"token":"fresh"}"#)
+        .create_async()
+        .await;
+    let client = KeyrunesClient::new(server.url()).unwrap();
+
+    // #act
+    let token = client.refresh_with("cookie-r1").await.unwrap();
+
+    // #assert
+    assert_eq!(token.refresh_token.as_deref(), Some("cookie-r1"));
+    let session = client.export_session().await;
+    assert_eq!(session.token.as_deref(), Some("fresh"));
+    assert_eq!(session.refresh_token.as_deref(), Some("cookie-r1"));
+}
+
+#[tokio::test]
+async fn test_refresh_saves_rotated_tokens_to_store() {
+    // #setup
+    let mut server = Server::new_async().await;
+    server
+        .mock("POST", "/api/refresh")
+        .match_body(Matcher::Json(serde_json::json!({"refresh_token": "r1"})))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"token":"fresh","refresh_token":"r2"}"#)
+        .create_async()
+        .await;
+    let store = Arc::new(MemoryTokenStore::new());
+    store
+        .save(&StoredTokens {
+            token: "stale".to_string(),
+            refresh_token: Some("r1".to_string()),
+        })
+        .await
+        .unwrap();
+    let client = KeyrunesClient::builder(server.url())
+        .token_store(store.clone())
+        .build()
+        .unwrap();
+
+    // #act
+    let loaded = client.load_tokens().await.unwrap();
+    client.token_provider().refresh().await.unwrap();
+
+    // #assert
+    assert!(loaded);
+    assert_eq!(
+        store.load().await.unwrap(),
+        Some(StoredTokens {
+            token: "fresh".to_string(),
+            refresh_token: Some("r2".to_string()),
+        })
+    );
+}
+
+#[tokio::test]
+async fn test_refresh_token_reuse_clears_tokens() {
+    // #setup
+    let mut server = Server::new_async().await;
+    server
+        .mock("POST", "/api/refresh")
+        .with_status(401)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"code":"refresh_token_reused","message":"refresh token reused"}"#)
+        .create_async()
+        .await;
+    let client = signed_in(server.url(), "stale".to_string()).await;
+
+    // #act
+    let result = client.token_provider().refresh().await;
+
+    // #assert
+    assert!(matches!(result, Err(KeyrunesError::RefreshTokenReused)));
+    assert!(client.current_token().await.is_none());
+    assert!(client.export_session().await.refresh_token.is_none());
+}
+
+#[tokio::test]
+async fn test_requests_refresh_expiring_tokens() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let refresh_mock = server
+        .mock("POST", "/api/refresh")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"token":"fresh","refresh_token":"r2"}"#)
+        .expect(1)
+        .create_async()
+        .await;
+    let user_mock = server
+        .mock("GET", "/api/users/123")
+        .match_header("authorization", "Bearer fresh")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"id":"123","username":"john","email":"john@example.com"}"#)
+        .expect(1)
+        .create_async()
+        .await;
+    let client = signed_in(server.url(), jwt(10)).await;
+
+    // #act
+    let user = client.get_user("123").await.unwrap();
+
+    // #assert
+    assert_eq!(user.username, "john");
+    refresh_mock.assert_async().await;
+    user_mock.assert_async().await;
+    let session = client.export_session().await;
+    assert_eq!(session.refresh_token.as_deref(), Some("r2"));
+}
+
+#[tokio::test]
+async fn test_unauthorized_requests_are_retried_once_after_refresh() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let refresh_mock = server
+        .mock("POST", "/api/refresh")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"token":"fresh"}"#)
+        .expect(1)
+        .create_async()
+        .await;
+    let rejected_mock = server
+        .mock("GET", "/api/users/123")
+        .match_header("authorization", "Bearer revoked")
+        .with_status(401)
+        .with_body(r#"{"message":"Token revoked"}"#)
+        .expect(1)
+        .create_async()
+        .await;
+    let retried_mock = server
+        .mock("GET", "/api/users/123")
+        .match_header("authorization", "Bearer fresh")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"id":"123","username":"john","email":"john@example.com"}"#)
+        .expect(1)
+        .create_async()
+        .await;
+    let client = signed_in(server.url(), "revoked".to_string()).await;
+
+    // #act
+    let user = client.get_user("123").await.unwrap();
+
+    // #assert
+    assert_eq!(user.username, "john");
+    refresh_mock.assert_async().await;
+    rejected_mock.assert_async().await;
+    retried_mock.assert_async().await;
+    assert_eq!(client.current_token().await.unwrap().token, "fresh");
+}
+
+#[tokio::test]
+async fn test_auto_refresh_can_be_disabled() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let refresh_mock = server
+        .mock("POST", "/api/refresh")
+        .expect(0)
+        .create_async()
+        .await;
+    let _rejected = server
+        .mock("GET", "/api/users/123")
+        .with_status(401)
+        .with_body(r#"{"message":"Token revoked"}"#)
+        .create_async()
+        .await;
+    let client = KeyrunesClient::builder(server.url())
+        .auto_refresh(false)
+        .build()
+        .unwrap();
+    client
+        .restore_session(ClientSession {
+            version: ClientSession::VERSION,
+            token: Some(jwt(10)),
+            refresh_token: Some("r1".to_string()),
+            namespace: None,
+            user: None,
+        })
+        .await
+        .unwrap();
+
+    // #act
+    let result = client.get_user("123").await;
+
+    // #assert
+    assert!(matches!(
+        result,
+        Err(KeyrunesError::AuthenticationError { .. })
+    ));
+    refresh_mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_manually_set_tokens_are_not_refreshed() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let refresh_mock = server
+        .mock("POST", "/api/refresh")
+        .expect(0)
+        .create_async()
+        .await;
+    let rejected_mock = server
+        .mock("GET", "/api/me")
+        .match_header("authorization", "Bearer forged")
+        .with_status(401)
+        .with_body(r#"{"message":"Invalid token"}"#)
+        .expect(1)
+        .create_async()
+        .await;
+    let client = signed_in(server.url(), "service-token".to_string()).await;
+    client.set_token("forged").await;
+
+    // #act
+    let result = client.get_current_user().await;
+
+    // #assert
+    assert!(matches!(
+        result,
+        Err(KeyrunesError::AuthenticationError { .. })
+    ));
+    refresh_mock.assert_async().await;
+    rejected_mock.assert_async().await;
+    assert_eq!(client.export_session().await.refresh_token, None);
+}
+
+#[tokio::test]
+async fn test_rejected_token_is_not_retried_with_another_callers_token() {
+    // #setup
+    let mut server = Server::new_async().await;
+    let _forged = server
+        .mock("GET", "/api/me")
+        .match_header("authorization", "Bearer forged")
+        .with_status(401)
+        .with_body(r#"{"message":"Invalid token"}"#)
+        .create_async()
+        .await;
+    let other_mock = server
+        .mock("GET", "/api/me")
+        .match_header("authorization", "Bearer other")
+        .expect(0)
+        .create_async()
+        .await;
+    let client = KeyrunesClient::new(server.url()).unwrap();
+    client.set_token("forged").await;
+    let request = client.get_current_user();
+    tokio::pin!(request);
+
+    // #act
+    // The token is replaced while the request is in flight.
+    let result = tokio::select! {
+        biased;
+        result = &mut request => result,
+        _ = async {
+            client.set_token("other").await;
+            std::future::pending::<()>().await
+        } => unreachable!(),
+    };
+
+    // #assert
+    assert!(matches!(
+        result,
+        Err(KeyrunesError::AuthenticationError { .. })
+    ));
+    other_mock.assert_async().await;
+}